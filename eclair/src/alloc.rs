@@ -48,6 +48,24 @@ impl Constant for bool {
     }
 }
 
+/// Implements [`Constant`] in the native compiler for the given `$type`.
+macro_rules! impl_native_constant {
+    ($($type:tt),* $(,)?) => {
+        $(
+            impl Constant for $type {
+                type Type = $type;
+
+                #[inline]
+                fn new_constant(this: &Self::Type, _: &mut ()) -> Self {
+                    *this
+                }
+            }
+        )*
+    };
+}
+
+impl_native_constant!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+
 impl<COM> Constant<COM> for ()
 where
     COM: ?Sized,
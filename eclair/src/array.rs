@@ -0,0 +1,150 @@
+//! Array Gadgets
+//!
+//! Circuit code over arrays and iterators often needs to thread the compiler through every step
+//! of a computation. Closures passed to iterator adapters like [`Iterator::map`] and
+//! [`Iterator::fold`] cannot both capture `&mut COM`, so the helpers in this module take the
+//! compiler as an explicit argument and lend it to each step in turn.
+
+use crate::alloc::{Constant, Variable};
+use core::array;
+
+#[cfg(feature = "alloc")]
+use {openzl_util::into_array_unchecked, rust_alloc::vec::Vec};
+
+/// Maps `f` over `array`, lending `compiler` to each call.
+#[inline]
+pub fn map_with_compiler<T, U, F, COM, const N: usize>(
+    array: [T; N],
+    compiler: &mut COM,
+    mut f: F,
+) -> [U; N]
+where
+    F: FnMut(T, &mut COM) -> U,
+    COM: ?Sized,
+{
+    array.map(|value| f(value, compiler))
+}
+
+/// Maps `f` over `array` by reference, lending `compiler` to each call.
+#[inline]
+pub fn map_ref_with_compiler<T, U, F, COM, const N: usize>(
+    array: &[T; N],
+    compiler: &mut COM,
+    mut f: F,
+) -> [U; N]
+where
+    F: FnMut(&T, &mut COM) -> U,
+    COM: ?Sized,
+{
+    array.each_ref().map(|value| f(value, compiler))
+}
+
+/// Combines `lhs` and `rhs` pointwise with `f`, lending `compiler` to each call.
+#[inline]
+pub fn zip_with_compiler<T, U, V, F, COM, const N: usize>(
+    lhs: [T; N],
+    rhs: [U; N],
+    compiler: &mut COM,
+    mut f: F,
+) -> [V; N]
+where
+    F: FnMut(T, U, &mut COM) -> V,
+    COM: ?Sized,
+{
+    let mut rhs = rhs.into_iter();
+    lhs.map(|lhs| {
+        f(
+            lhs,
+            rhs.next().expect("Arrays of equal length always zip."),
+            compiler,
+        )
+    })
+}
+
+/// Folds every element of `iter` into `init` with `f`, lending `compiler` to each call.
+#[inline]
+pub fn fold_with_compiler<I, B, F, COM>(iter: I, init: B, compiler: &mut COM, mut f: F) -> B
+where
+    I: IntoIterator,
+    F: FnMut(B, I::Item, &mut COM) -> B,
+    COM: ?Sized,
+{
+    iter.into_iter()
+        .fold(init, |accumulator, item| f(accumulator, item, compiler))
+}
+
+/// Reduces the elements of `iter` to a single one with `f`, lending `compiler` to each call.
+/// Returns `None` if `iter` is empty.
+#[inline]
+pub fn reduce_with_compiler<I, F, COM>(iter: I, compiler: &mut COM, f: F) -> Option<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item, I::Item, &mut COM) -> I::Item,
+    COM: ?Sized,
+{
+    let mut iter = iter.into_iter();
+    let first = iter.next()?;
+    Some(fold_with_compiler(iter, first, compiler, f))
+}
+
+/// Maps `f` over `iter` and collects the results into an array, lending `compiler` to each call.
+///
+/// # Panics
+///
+/// This function panics if `iter` does not yield exactly `N` elements.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn collect_with_compiler<I, U, F, COM, const N: usize>(
+    iter: I,
+    compiler: &mut COM,
+    mut f: F,
+) -> [U; N]
+where
+    I: IntoIterator,
+    F: FnMut(I::Item, &mut COM) -> U,
+    COM: ?Sized,
+{
+    into_array_unchecked(
+        iter.into_iter()
+            .map(|item| f(item, compiler))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Allocates every element of `values` as a constant in `compiler`.
+#[inline]
+pub fn allocate_constants<T, COM, const N: usize>(
+    values: &[T::Type; N],
+    compiler: &mut COM,
+) -> [T; N]
+where
+    T: Constant<COM>,
+    COM: ?Sized,
+{
+    map_ref_with_compiler(values, compiler, T::new_constant)
+}
+
+/// Allocates every element of `values` as a known variable with allocation mode `M` in
+/// `compiler`.
+#[inline]
+pub fn allocate_known<M, T, COM, const N: usize>(
+    values: &[T::Type; N],
+    compiler: &mut COM,
+) -> [T; N]
+where
+    T: Variable<M, COM>,
+    COM: ?Sized,
+{
+    map_ref_with_compiler(values, compiler, T::new_known)
+}
+
+/// Allocates `N` unknown variables with allocation mode `M` in `compiler`.
+#[inline]
+pub fn allocate_unknown<M, T, COM, const N: usize>(compiler: &mut COM) -> [T; N]
+where
+    T: Variable<M, COM>,
+    COM: ?Sized,
+{
+    array::from_fn(|_| T::new_unknown(compiler))
+}
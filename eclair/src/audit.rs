@@ -0,0 +1,219 @@
+//! Allocation Auditing
+//!
+//! Provers and verifiers must allocate the public inputs of a circuit in exactly the same order,
+//! otherwise proofs fail to verify without any hint of what went wrong. Compilers which implement
+//! [`Audit`] can record every allocation they perform, with the name of the allocated type and
+//! its allocation mode, so that the sequences recorded for the prover and the verifier can be
+//! compared with [`diff`], which reports the first [`Divergence`] between them.
+
+use crate::alloc::mode;
+use core::{any, fmt};
+
+#[cfg(feature = "alloc")]
+use rust_alloc::vec::Vec;
+
+/// Allocation Mode Kind
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Kind {
+    /// Constant Allocation
+    Constant,
+
+    /// Public Allocation
+    Public,
+
+    /// Secret Allocation
+    Secret,
+}
+
+impl fmt::Display for Kind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Constant => write!(f, "constant"),
+            Self::Public => write!(f, "public"),
+            Self::Secret => write!(f, "secret"),
+        }
+    }
+}
+
+/// Auditable Allocation Mode
+pub trait AuditMode {
+    /// Allocation Mode Kind
+    const KIND: Kind;
+}
+
+impl AuditMode for mode::Constant {
+    const KIND: Kind = Kind::Constant;
+}
+
+impl AuditMode for mode::Public {
+    const KIND: Kind = Kind::Public;
+}
+
+impl AuditMode for mode::Secret {
+    const KIND: Kind = Kind::Secret;
+}
+
+/// Allocation Record
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Allocation {
+    /// Name of the Allocated Type
+    pub type_name: &'static str,
+
+    /// Allocation Mode Kind
+    pub kind: Kind,
+}
+
+impl Allocation {
+    /// Builds a new [`Allocation`] record for the allocation of `T` with mode `M`.
+    #[inline]
+    pub fn new<T, M>() -> Self
+    where
+        T: ?Sized,
+        M: AuditMode,
+    {
+        Self {
+            type_name: any::type_name::<T>(),
+            kind: M::KIND,
+        }
+    }
+}
+
+impl fmt::Display for Allocation {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} `{}`", self.kind, self.type_name)
+    }
+}
+
+/// Allocation Audit
+pub trait Audit {
+    /// Records `allocation` if auditing is enabled for `self`.
+    fn record(&mut self, allocation: Allocation);
+
+    /// Records the allocation of `T` with mode `M` if auditing is enabled for `self`.
+    #[inline]
+    fn record_allocation<T, M>(&mut self)
+    where
+        T: ?Sized,
+        M: AuditMode,
+    {
+        self.record(Allocation::new::<T, M>())
+    }
+}
+
+impl Audit for () {
+    #[inline]
+    fn record(&mut self, allocation: Allocation) {
+        let _ = allocation;
+    }
+}
+
+/// Audit Log
+///
+/// This log stores the sequence of allocations recorded by a compiler while auditing is enabled.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AuditLog(Vec<Allocation>);
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl AuditLog {
+    /// Builds a new empty [`AuditLog`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded allocations in the order in which they were performed.
+    #[inline]
+    pub fn allocations(&self) -> &[Allocation] {
+        &self.0
+    }
+
+    /// Returns the number of recorded allocations.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no allocations were recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the allocations in `self` of the given `kind`.
+    #[inline]
+    pub fn of_kind(&self, kind: Kind) -> impl Iterator<Item = &Allocation> {
+        self.0
+            .iter()
+            .filter(move |allocation| allocation.kind == kind)
+    }
+
+    /// Compares `self` against `other`, returning their first divergence. See [`diff`] for more.
+    #[inline]
+    pub fn diff(&self, other: &Self) -> Option<Divergence> {
+        diff(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl Audit for AuditLog {
+    #[inline]
+    fn record(&mut self, allocation: Allocation) {
+        self.0.push(allocation);
+    }
+}
+
+/// Allocation Divergence
+///
+/// The first position at which two allocation sequences differ. Either side is `None` if its
+/// sequence ended before the other one.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Divergence {
+    /// Index of the First Differing Allocation
+    pub index: usize,
+
+    /// Allocation in the Prover Sequence
+    pub prover: Option<Allocation>,
+
+    /// Allocation in the Verifier Sequence
+    pub verifier: Option<Allocation>,
+}
+
+impl fmt::Display for Divergence {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "allocation #{} diverged: ", self.index)?;
+        match self.prover {
+            Some(allocation) => write!(f, "prover allocated {allocation}")?,
+            _ => write!(f, "prover allocated nothing")?,
+        }
+        match self.verifier {
+            Some(allocation) => write!(f, ", verifier allocated {allocation}"),
+            _ => write!(f, ", verifier allocated nothing"),
+        }
+    }
+}
+
+/// Compares the `prover` and `verifier` allocation sequences, returning the first position at
+/// which they differ or `None` if they are identical.
+#[inline]
+pub fn diff(prover: &[Allocation], verifier: &[Allocation]) -> Option<Divergence> {
+    let index = prover
+        .iter()
+        .zip(verifier)
+        .position(|(lhs, rhs)| lhs != rhs)
+        .unwrap_or_else(|| prover.len().min(verifier.len()));
+    if index == prover.len() && index == verifier.len() {
+        return None;
+    }
+    Some(Divergence {
+        index,
+        prover: prover.get(index).copied(),
+        verifier: verifier.get(index).copied(),
+    })
+}
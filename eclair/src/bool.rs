@@ -7,9 +7,13 @@
 use crate::{cmp::PartialEq, Has, Type};
 
 #[cfg(feature = "alloc")]
-use openzl_util::{
-    iter::IteratorExt,
-    vec::{Vec, VecExt},
+use {
+    core::fmt,
+    openzl_util::{
+        iter::IteratorExt,
+        vec::{Vec, VecExt},
+    },
+    rust_alloc::string::{String, ToString},
 };
 
 /// Boolean Type Inside of the Compiler
@@ -39,6 +43,133 @@ impl Assert for () {
     }
 }
 
+/// Labeled Assertion
+///
+/// Extends [`Assert`] with a label describing the property being asserted, so that failures can be
+/// traced back to a specific property instead of a bare boolean.
+pub trait AssertLabeled: Assert {
+    /// Asserts that `bit` reduces to `true`, attaching `label` to the assertion.
+    ///
+    /// By default, the label is ignored and this method is equivalent to [`Assert::assert`].
+    #[inline]
+    fn assert_labeled(&mut self, bit: &Bool<Self>, label: &str) {
+        let _ = label;
+        self.assert(bit);
+    }
+}
+
+impl AssertLabeled for () {
+    #[inline]
+    fn assert_labeled(&mut self, bit: &bool, label: &str) {
+        assert!(bit, "Assertion failed: {label}.")
+    }
+}
+
+/// Assertion Report
+///
+/// This is a native compiler for assertions which records the labels of the failed assertions
+/// instead of panicking on the first one, so that tests can report every property that was
+/// violated. Unlabeled assertions are recorded with an empty label.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Report {
+    /// Number of Assertions
+    assertions: usize,
+
+    /// Failed Assertion Labels
+    failures: Vec<String>,
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl Report {
+    /// Builds a new empty [`Report`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of assertions recorded in `self`.
+    #[inline]
+    pub fn assertions(&self) -> usize {
+        self.assertions
+    }
+
+    /// Returns the labels of the failed assertions in the order they were recorded.
+    #[inline]
+    pub fn failures(&self) -> &[String] {
+        &self.failures
+    }
+
+    /// Returns `true` if no assertion recorded in `self` has failed.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns `Ok` if no assertion recorded in `self` has failed, and returns `self` otherwise.
+    #[inline]
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_success() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Panics with the list of failed assertions if any assertion recorded in `self` has failed.
+    #[inline]
+    pub fn assert_success(&self) {
+        assert!(self.is_success(), "{self}");
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl fmt::Display for Report {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} assertions failed",
+            self.failures.len(),
+            self.assertions
+        )?;
+        for label in &self.failures {
+            write!(f, "\n  - {label}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl Has<bool> for Report {
+    type Type = bool;
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl Assert for Report {
+    #[inline]
+    fn assert(&mut self, bit: &bool) {
+        self.assert_labeled(bit, "")
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl AssertLabeled for Report {
+    #[inline]
+    fn assert_labeled(&mut self, bit: &bool, label: &str) {
+        self.assertions += 1;
+        if !bit {
+            self.failures.push(label.to_string());
+        }
+    }
+}
+
 /// Equality Assertion
 pub trait AssertEq: Assert {
     /// Asserts that `lhs` and `rhs` are equal.
@@ -203,7 +334,62 @@ macro_rules! impl_conditional_select {
     }
 }
 
-impl_conditional_select!(bool, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl_conditional_select!(bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
+
+impl<COM> ConditionalSelect<COM> for ()
+where
+    COM: Has<bool> + ?Sized,
+{
+    #[inline]
+    fn select(bit: &Bool<COM>, true_value: &Self, false_value: &Self, compiler: &mut COM) -> Self {
+        let _ = (bit, true_value, false_value, compiler);
+    }
+}
+
+impl<T, COM, const N: usize> ConditionalSelect<COM> for [T; N]
+where
+    T: ConditionalSelect<COM>,
+    COM: Has<bool> + ?Sized,
+{
+    #[inline]
+    fn select(bit: &Bool<COM>, true_value: &Self, false_value: &Self, compiler: &mut COM) -> Self {
+        core::array::from_fn(|i| T::select(bit, &true_value[i], &false_value[i], compiler))
+    }
+}
+
+/// Implements [`ConditionalSelect`] for tuples of types which implement [`ConditionalSelect`].
+macro_rules! impl_conditional_select_tuple {
+    ($(($($type:ident: $index:tt),+)),* $(,)?) => {
+        $(
+            impl<$($type,)+ COM> ConditionalSelect<COM> for ($($type,)+)
+            where
+                $($type: ConditionalSelect<COM>,)+
+                COM: Has<bool> + ?Sized,
+            {
+                #[inline]
+                fn select(
+                    bit: &Bool<COM>,
+                    true_value: &Self,
+                    false_value: &Self,
+                    compiler: &mut COM,
+                ) -> Self {
+                    ($($type::select(bit, &true_value.$index, &false_value.$index, compiler),)+)
+                }
+            }
+        )*
+    }
+}
+
+impl_conditional_select_tuple!(
+    (A: 0),
+    (A: 0, B: 1),
+    (A: 0, B: 1, C: 2),
+    (A: 0, B: 1, C: 2, D: 3),
+    (A: 0, B: 1, C: 2, D: 3, E: 4),
+    (A: 0, B: 1, C: 2, D: 3, E: 4, F: 5),
+    (A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6),
+    (A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7),
+);
 
 /// Conditional Swap
 pub trait ConditionalSwap<COM = ()>: Sized
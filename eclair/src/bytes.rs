@@ -0,0 +1,428 @@
+//! Byte Strings
+//!
+//! Circuits have a fixed shape, so byte strings whose length is only known at execution time, like
+//! URLs or the fields of a JSON document, have to be represented with a fixed capacity. A
+//! [`Bytes`] value stores up to `MAX_LEN` bytes padded with zeroes together with its length. Its
+//! operations only compare bytes and lengths for equality, so they cost the same whether the length
+//! is a constant, a public input, or a secret witness.
+//!
+//! # Length Hiding
+//!
+//! The [`Variable`] implementation for [`Bytes`] uses the [`Derived`] mode with one mode for the
+//! bytes and one for the length. Allocating the length in the [`Secret`] mode hides it, so that
+//! only the capacity `MAX_LEN` is revealed by the shape of the circuit, while allocating it in the
+//! [`Public`] mode reveals the length but can still keep the bytes secret.
+//!
+//! [`Secret`]: crate::alloc::mode::Secret
+//! [`Public`]: crate::alloc::mode::Public
+
+use crate::{
+    alloc::{mode::Derived, Allocate, Allocator, Constant, Variable},
+    bool::{Assert, Bool, ConditionalSelect},
+    cmp::PartialEq,
+    num::{AssertWithinBitRange, One, Zero},
+    ops::{Add, BitAnd, BitOr, Not},
+    Has, Type,
+};
+use core::{array, fmt, hash, iter};
+
+/// Byte Type Inside of the Compiler
+pub type Byte<COM = ()> = Type<COM, u8>;
+
+/// Length Type Inside of the Compiler
+pub type Length<COM = ()> = Type<COM, usize>;
+
+/// Padded Byte String
+///
+/// The first `length`-many entries of `bytes` hold the string and the remaining entries are zero.
+/// Values built with [`new`](Self::new) or allocated as variables are asserted to have this shape,
+/// and the operations on [`Bytes`] assume it.
+pub struct Bytes<COM, const MAX_LEN: usize>
+where
+    COM: Has<u8> + Has<usize>,
+{
+    /// Padded Bytes
+    bytes: [Byte<COM>; MAX_LEN],
+
+    /// Length
+    length: Length<COM>,
+}
+
+impl<COM, const MAX_LEN: usize> Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+{
+    /// Builds a new [`Bytes`] from the padded `bytes` and their `length` without checking that
+    /// `length` is at most `MAX_LEN` and that the bytes after `length` are zero. See
+    /// [`new`](Self::new) for a checked constructor.
+    #[inline]
+    pub fn new_unchecked(bytes: [Byte<COM>; MAX_LEN], length: Length<COM>) -> Self {
+        Self { bytes, length }
+    }
+
+    /// Returns the padded bytes of `self`.
+    #[inline]
+    pub fn bytes(&self) -> &[Byte<COM>; MAX_LEN] {
+        &self.bytes
+    }
+
+    /// Returns the length of `self`.
+    #[inline]
+    pub fn length(&self) -> &Length<COM> {
+        &self.length
+    }
+
+    /// Returns the padded bytes and the length of `self`.
+    #[inline]
+    pub fn into_inner(self) -> ([Byte<COM>; MAX_LEN], Length<COM>) {
+        (self.bytes, self.length)
+    }
+}
+
+impl<COM, const MAX_LEN: usize> Bytes<COM, MAX_LEN>
+where
+    COM: Has<bool> + Has<u8> + Has<usize>,
+    Bool<COM>: Clone
+        + Constant<COM, Type = bool>
+        + BitAnd<Bool<COM>, COM, Output = Bool<COM>>
+        + BitOr<Bool<COM>, COM, Output = Bool<COM>>
+        + Not<COM, Output = Bool<COM>>,
+    Byte<COM>: PartialEq<Byte<COM>, COM> + Zero<COM, Verification = Bool<COM>>,
+    Length<COM>: Add<Length<COM>, COM, Output = Length<COM>>
+        + Clone
+        + One<COM>
+        + PartialEq<Length<COM>, COM>
+        + Zero<COM>,
+{
+    /// Builds a new [`Bytes`] from the padded `bytes` and their `length`, asserting that `length`
+    /// is at most `MAX_LEN` and that the bytes after `length` are zero.
+    #[inline]
+    pub fn new(bytes: [Byte<COM>; MAX_LEN], length: Length<COM>, compiler: &mut COM) -> Self
+    where
+        COM: Assert,
+    {
+        let value = Self::new_unchecked(bytes, length);
+        value.assert_well_formed(compiler);
+        value
+    }
+
+    /// Returns the bits which are `true` if `length` is equal to the index of the bit, for the
+    /// indices smaller than `MAX_LEN`, together with the bit which is `true` if `length` is equal
+    /// to `MAX_LEN`.
+    #[inline]
+    fn indicators(length: &Length<COM>, compiler: &mut COM) -> ([Bool<COM>; MAX_LEN], Bool<COM>) {
+        let one = Length::<COM>::one(compiler);
+        let mut index = Length::<COM>::zero(compiler);
+        let indicators = array::from_fn(|_| {
+            let is_index = length.eq(&index, compiler);
+            index = index.clone().add(one.clone(), compiler);
+            is_index
+        });
+        let is_max_len = length.eq(&index, compiler);
+        (indicators, is_max_len)
+    }
+
+    /// Returns the bits which are `true` at the positions of `self` smaller than its length,
+    /// together with the bit which is `true` if its length is at most `MAX_LEN`.
+    #[inline]
+    fn scan(&self, compiler: &mut COM) -> ([Bool<COM>; MAX_LEN], Bool<COM>) {
+        let (is_end, mut is_bounded) = Self::indicators(&self.length, compiler);
+        let mut is_active = true.as_constant::<Bool<COM>>(compiler);
+        let mask = array::from_fn(|index| {
+            let is_not_end = is_end[index].clone().not(compiler);
+            is_active = is_active.clone().bitand(is_not_end, compiler);
+            is_bounded = is_bounded.clone().bitor(is_end[index].clone(), compiler);
+            is_active.clone()
+        });
+        (mask, is_bounded)
+    }
+
+    /// Returns the bits which are `true` at the positions of `self` smaller than its length.
+    #[inline]
+    pub fn mask(&self, compiler: &mut COM) -> [Bool<COM>; MAX_LEN] {
+        self.scan(compiler).0
+    }
+
+    /// Returns `true` if the length of `self` is at most `MAX_LEN` and the bytes after its length
+    /// are zero.
+    #[inline]
+    pub fn is_well_formed(&self, compiler: &mut COM) -> Bool<COM> {
+        let (mask, is_bounded) = self.scan(compiler);
+        mask.into_iter()
+            .zip(&self.bytes)
+            .fold(is_bounded, |is_well_formed, (is_active, byte)| {
+                let is_padding = byte.is_zero(compiler);
+                let is_valid = is_active.bitor(is_padding, compiler);
+                is_well_formed.bitand(is_valid, compiler)
+            })
+    }
+
+    /// Asserts that the length of `self` is at most `MAX_LEN` and that the bytes after its length
+    /// are zero.
+    #[inline]
+    pub fn assert_well_formed(&self, compiler: &mut COM)
+    where
+        COM: Assert,
+    {
+        let is_well_formed = self.is_well_formed(compiler);
+        compiler.assert(&is_well_formed);
+    }
+
+    /// Returns `true` if `needle` is equal to the substring of `self` which starts at `offset`
+    /// and has the length of `needle`.
+    ///
+    /// The result is `false` whenever that substring does not end within the length of `self`,
+    /// so trailing zero bytes of `needle` cannot be matched against the padding of `self`.
+    #[inline]
+    pub fn substring_eq<const N: usize>(
+        &self,
+        offset: &Length<COM>,
+        needle: &Bytes<COM, N>,
+        compiler: &mut COM,
+    ) -> Bool<COM> {
+        let (needle_mask, _) = needle.scan(compiler);
+        let (starts, starts_at_max_len) = Self::indicators(offset, compiler);
+        let mut is_match = false.as_constant::<Bool<COM>>(compiler);
+        for (start, is_start) in starts
+            .into_iter()
+            .chain(iter::once(starts_at_max_len))
+            .enumerate()
+        {
+            let mut is_window_match = is_start;
+            for (index, (is_active, byte)) in needle_mask.iter().zip(&needle.bytes).enumerate() {
+                let is_byte_match = match self.bytes.get(start + index) {
+                    Some(haystack_byte) => haystack_byte.eq(byte, compiler),
+                    _ => false.as_constant(compiler),
+                };
+                let is_skipped = is_active.clone().not(compiler);
+                let is_valid = is_skipped.bitor(is_byte_match, compiler);
+                is_window_match = is_window_match.bitand(is_valid, compiler);
+            }
+            is_match = is_match.bitor(is_window_match, compiler);
+        }
+        let end = offset.clone().add(needle.length.clone(), compiler);
+        let (ends, ends_at_max_len) = Self::indicators(&end, compiler);
+        let (mask, _) = self.scan(compiler);
+        let mut is_in_range = false.as_constant::<Bool<COM>>(compiler);
+        for (is_end, is_within_length) in ends
+            .into_iter()
+            .chain(iter::once(ends_at_max_len))
+            .zip(iter::once(true.as_constant(compiler)).chain(mask))
+        {
+            let is_valid_end = is_end.bitand(is_within_length, compiler);
+            is_in_range = is_in_range.bitor(is_valid_end, compiler);
+        }
+        is_match.bitand(is_in_range, compiler)
+    }
+
+    /// Returns `true` if `self` starts with `prefix`.
+    #[inline]
+    pub fn starts_with<const N: usize>(
+        &self,
+        prefix: &Bytes<COM, N>,
+        compiler: &mut COM,
+    ) -> Bool<COM> {
+        let offset = Length::<COM>::zero(compiler);
+        self.substring_eq(&offset, prefix, compiler)
+    }
+}
+
+impl<const MAX_LEN: usize> Bytes<(), MAX_LEN> {
+    /// Builds a new [`Bytes`] holding `bytes`, returning `None` if `bytes` is longer than
+    /// `MAX_LEN`.
+    #[inline]
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() > MAX_LEN {
+            return None;
+        }
+        let mut padded = [0; MAX_LEN];
+        padded[..bytes.len()].copy_from_slice(bytes);
+        Some(Self::new_unchecked(padded, bytes.len()))
+    }
+
+    /// Returns the bytes of `self` without the padding.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.length.min(MAX_LEN)]
+    }
+}
+
+impl<COM, const MAX_LEN: usize> Clone for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: Clone,
+    Length<COM>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new_unchecked(self.bytes.clone(), self.length.clone())
+    }
+}
+
+impl<COM, const MAX_LEN: usize> Copy for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: Copy,
+    Length<COM>: Copy,
+{
+}
+
+impl<COM, const MAX_LEN: usize> fmt::Debug for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: fmt::Debug,
+    Length<COM>: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Bytes")
+            .field("bytes", &self.bytes)
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl<COM, const MAX_LEN: usize> core::cmp::PartialEq for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: core::cmp::PartialEq,
+    Length<COM>: core::cmp::PartialEq,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self) -> bool {
+        self.bytes == rhs.bytes && self.length == rhs.length
+    }
+}
+
+impl<COM, const MAX_LEN: usize> core::cmp::Eq for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: core::cmp::Eq,
+    Length<COM>: core::cmp::Eq,
+{
+}
+
+impl<COM, const MAX_LEN: usize> hash::Hash for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: hash::Hash,
+    Length<COM>: hash::Hash,
+{
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        self.bytes.hash(state);
+        self.length.hash(state);
+    }
+}
+
+impl<COM, const MAX_LEN: usize> PartialEq<Self, COM> for Bytes<COM, MAX_LEN>
+where
+    COM: Has<bool> + Has<u8> + Has<usize>,
+    Bool<COM>: Constant<COM, Type = bool> + BitAnd<Bool<COM>, COM, Output = Bool<COM>>,
+    Byte<COM>: PartialEq<Byte<COM>, COM>,
+    Length<COM>: PartialEq<Length<COM>, COM>,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self, compiler: &mut COM) -> Bool<COM> {
+        let are_equal = self.length.eq(&rhs.length, compiler);
+        self.bytes
+            .iter()
+            .zip(&rhs.bytes)
+            .fold(are_equal, |are_equal, (lhs, rhs)| {
+                let is_equal = lhs.eq(rhs, compiler);
+                are_equal.bitand(is_equal, compiler)
+            })
+    }
+
+    #[inline]
+    fn assert_equal(&self, rhs: &Self, compiler: &mut COM)
+    where
+        COM: Assert,
+    {
+        self.length.assert_equal(&rhs.length, compiler);
+        for (lhs, rhs) in self.bytes.iter().zip(&rhs.bytes) {
+            lhs.assert_equal(rhs, compiler);
+        }
+    }
+}
+
+impl<COM, const MAX_LEN: usize> ConditionalSelect<COM> for Bytes<COM, MAX_LEN>
+where
+    COM: Has<bool> + Has<u8> + Has<usize>,
+    Byte<COM>: ConditionalSelect<COM>,
+    Length<COM>: ConditionalSelect<COM>,
+{
+    #[inline]
+    fn select(bit: &Bool<COM>, true_value: &Self, false_value: &Self, compiler: &mut COM) -> Self {
+        Self::new_unchecked(
+            ConditionalSelect::select(bit, &true_value.bytes, &false_value.bytes, compiler),
+            Length::<COM>::select(bit, &true_value.length, &false_value.length, compiler),
+        )
+    }
+}
+
+impl<COM, const MAX_LEN: usize> Constant<COM> for Bytes<COM, MAX_LEN>
+where
+    COM: Has<u8> + Has<usize>,
+    Byte<COM>: Constant<COM>,
+    <Byte<COM> as Constant<COM>>::Type: From<u8>,
+    Length<COM>: Constant<COM>,
+    <Length<COM> as Constant<COM>>::Type: From<usize>,
+{
+    type Type = Bytes<(), MAX_LEN>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new_unchecked(
+            this.bytes
+                .map(|byte| compiler.allocate_constant::<Byte<COM>>(&byte.into())),
+            compiler.allocate_constant::<Length<COM>>(&this.length.into()),
+        )
+    }
+}
+
+impl<B, L, COM, const MAX_LEN: usize> Variable<Derived<(B, L)>, COM> for Bytes<COM, MAX_LEN>
+where
+    COM: Assert + AssertWithinBitRange<Byte<COM>, 8> + Has<u8> + Has<usize>,
+    Bool<COM>: Clone
+        + Constant<COM, Type = bool>
+        + BitAnd<Bool<COM>, COM, Output = Bool<COM>>
+        + BitOr<Bool<COM>, COM, Output = Bool<COM>>
+        + Not<COM, Output = Bool<COM>>,
+    Byte<COM>: PartialEq<Byte<COM>, COM> + Variable<B, COM> + Zero<COM, Verification = Bool<COM>>,
+    <Byte<COM> as Variable<B, COM>>::Type: From<u8>,
+    Length<COM>: Add<Length<COM>, COM, Output = Length<COM>>
+        + Clone
+        + One<COM>
+        + PartialEq<Length<COM>, COM>
+        + Variable<L, COM>
+        + Zero<COM>,
+    <Length<COM> as Variable<L, COM>>::Type: From<usize>,
+{
+    type Type = Bytes<(), MAX_LEN>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        let bytes = array::from_fn(|_| {
+            let byte = compiler.allocate_unknown::<B, Byte<COM>>();
+            compiler.assert_within_range(&byte);
+            byte
+        });
+        let length = compiler.allocate_unknown::<L, Length<COM>>();
+        Self::new(bytes, length, compiler)
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        let bytes = this.bytes.map(|byte| {
+            let byte = compiler.allocate_known::<B, Byte<COM>>(&byte.into());
+            compiler.assert_within_range(&byte);
+            byte
+        });
+        let length = compiler.allocate_known::<L, Length<COM>>(&this.length.into());
+        Self::new(bytes, length, compiler)
+    }
+}
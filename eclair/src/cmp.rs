@@ -112,7 +112,7 @@ macro_rules! impl_partial_eq {
     };
 }
 
-impl_partial_eq!(bool, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl_partial_eq!(bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
 
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
@@ -242,6 +242,75 @@ where
     }
 }
 
+/// Partial Orders
+///
+/// The comparisons in this trait must be consistent with a total order on the values for which
+/// they are defined, so that [`le`](Self::le) and [`ge`](Self::ge) can be computed as the
+/// negations of [`gt`](Self::gt) and [`lt`](Self::lt).
+pub trait PartialOrd<Rhs, COM = ()>: PartialEq<Rhs, COM>
+where
+    Rhs: ?Sized,
+    COM: Has<bool> + ?Sized,
+{
+    /// Returns `true` if `self` is strictly less than `rhs`.
+    fn lt(&self, rhs: &Rhs, compiler: &mut COM) -> Bool<COM>;
+
+    /// Returns `true` if `self` is strictly greater than `rhs`.
+    fn gt(&self, rhs: &Rhs, compiler: &mut COM) -> Bool<COM>;
+
+    /// Returns `true` if `self` is less than or equal to `rhs`.
+    #[inline]
+    fn le(&self, rhs: &Rhs, compiler: &mut COM) -> Bool<COM>
+    where
+        Bool<COM>: Not<COM, Output = Bool<COM>>,
+    {
+        self.gt(rhs, compiler).not(compiler)
+    }
+
+    /// Returns `true` if `self` is greater than or equal to `rhs`.
+    #[inline]
+    fn ge(&self, rhs: &Rhs, compiler: &mut COM) -> Bool<COM>
+    where
+        Bool<COM>: Not<COM, Output = Bool<COM>>,
+    {
+        self.lt(rhs, compiler).not(compiler)
+    }
+}
+
+/// Implements [`PartialOrd`] for the given `$type`.
+macro_rules! impl_partial_ord {
+    ($($type:tt),* $(,)?) => {
+        $(
+            impl<Rhs> PartialOrd<Rhs> for $type
+            where
+                $type: cmp::PartialOrd<Rhs> + PartialEq<Rhs>,
+            {
+                #[inline]
+                fn lt(&self, rhs: &Rhs, _: &mut ()) -> bool {
+                    cmp::PartialOrd::lt(self, rhs)
+                }
+
+                #[inline]
+                fn gt(&self, rhs: &Rhs, _: &mut ()) -> bool {
+                    cmp::PartialOrd::gt(self, rhs)
+                }
+
+                #[inline]
+                fn le(&self, rhs: &Rhs, _: &mut ()) -> bool {
+                    cmp::PartialOrd::le(self, rhs)
+                }
+
+                #[inline]
+                fn ge(&self, rhs: &Rhs, _: &mut ()) -> bool {
+                    cmp::PartialOrd::ge(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_ord!(bool, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
 /// Equality
 pub trait Eq<COM = ()>: PartialEq<Self, COM>
 where
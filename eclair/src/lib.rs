@@ -9,12 +9,20 @@
 extern crate alloc as rust_alloc;
 
 pub mod alloc;
+pub mod array;
+pub mod audit;
 pub mod bool;
+pub mod bytes;
 pub mod cmp;
 pub mod execution;
+pub mod linalg;
 pub mod num;
 pub mod ops;
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod pattern;
+
 #[cfg(feature = "derive")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "derive")))]
 pub use eclair_derive::*;
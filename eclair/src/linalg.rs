@@ -0,0 +1,350 @@
+//! Linear Algebra Gadgets
+//!
+//! Permutations like Poseidon and Rescue, and many other gadgets, multiply small dense matrices
+//! with vectors of field elements. This module defines the const-generic [`Vector`] and [`Matrix`]
+//! types whose operations thread the compiler through every step, together with
+//! [`linear_combination_with`] which the matrix products are built from. Gadgets whose dimensions
+//! are only known at runtime, or which multiply constants with variables, can use
+//! [`linear_combination_with`] directly with their own multiplication and addition.
+
+use crate::{
+    array::{fold_with_compiler, map_ref_with_compiler, zip_with_compiler},
+    num::{One, Zero},
+    ops::{Add, Mul},
+};
+use core::{
+    array,
+    ops::{Index, IndexMut},
+};
+
+/// Computes the sum of the pointwise products of `lhs` and `rhs` with `mul` and `add`, lending
+/// `compiler` to each call. Returns `None` if either of `lhs` or `rhs` is empty.
+///
+/// The products are summed from left to right and the iteration stops at the end of the shorter
+/// of `lhs` and `rhs`.
+#[inline]
+pub fn linear_combination_with<I, J, T, M, S, COM>(
+    lhs: I,
+    rhs: J,
+    compiler: &mut COM,
+    mut mul: M,
+    mut add: S,
+) -> Option<T>
+where
+    I: IntoIterator,
+    J: IntoIterator,
+    M: FnMut(I::Item, J::Item, &mut COM) -> T,
+    S: FnMut(T, T, &mut COM) -> T,
+    COM: ?Sized,
+{
+    let mut terms = lhs.into_iter().zip(rhs);
+    let (lhs, rhs) = terms.next()?;
+    let first = mul(lhs, rhs, compiler);
+    Some(fold_with_compiler(
+        terms,
+        first,
+        compiler,
+        |accumulator, (lhs, rhs), compiler| {
+            let term = mul(lhs, rhs, compiler);
+            add(accumulator, term, compiler)
+        },
+    ))
+}
+
+/// Computes the inner product of `lhs` and `rhs` in `compiler`, returning zero if they are empty.
+#[inline]
+pub fn inner_product<T, COM>(lhs: &[T], rhs: &[T], compiler: &mut COM) -> T
+where
+    T: Add<T, COM, Output = T> + Clone + Mul<T, COM, Output = T> + Zero<COM>,
+{
+    linear_combination_with(
+        lhs,
+        rhs,
+        compiler,
+        |lhs, rhs, compiler| lhs.clone().mul(rhs.clone(), compiler),
+        |lhs, rhs, compiler| lhs.add(rhs, compiler),
+    )
+    .unwrap_or_else(|| T::zero(compiler))
+}
+
+/// Vector
+///
+/// A column vector with `N` entries.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Vector<T, const N: usize>(
+    /// Entries
+    pub [T; N],
+);
+
+impl<T, const N: usize> Vector<T, N> {
+    /// Builds a new [`Vector`] from `entries`.
+    #[inline]
+    pub fn new(entries: [T; N]) -> Self {
+        Self(entries)
+    }
+
+    /// Returns the entries of `self`.
+    #[inline]
+    pub fn into_inner(self) -> [T; N] {
+        self.0
+    }
+
+    /// Returns an iterator over the entries of `self`.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<T> {
+        self.0.iter()
+    }
+
+    /// Returns the zero vector in `compiler`.
+    #[inline]
+    pub fn zero<COM>(compiler: &mut COM) -> Self
+    where
+        T: Zero<COM>,
+    {
+        Self(array::from_fn(|_| T::zero(compiler)))
+    }
+
+    /// Maps `f` over the entries of `self`, lending `compiler` to each call.
+    #[inline]
+    pub fn map_with<U, F, COM>(&self, compiler: &mut COM, f: F) -> Vector<U, N>
+    where
+        F: FnMut(&T, &mut COM) -> U,
+    {
+        Vector(map_ref_with_compiler(&self.0, compiler, f))
+    }
+
+    /// Adds `self` and `rhs` entrywise in `compiler`.
+    #[inline]
+    pub fn add<COM>(&self, rhs: &Self, compiler: &mut COM) -> Self
+    where
+        T: Add<T, COM, Output = T> + Clone,
+    {
+        Self(zip_with_compiler(
+            self.0.clone(),
+            rhs.0.clone(),
+            compiler,
+            T::add,
+        ))
+    }
+
+    /// Multiplies every entry of `self` by `scalar` in `compiler`.
+    #[inline]
+    pub fn scale<COM>(&self, scalar: &T, compiler: &mut COM) -> Self
+    where
+        T: Clone + Mul<T, COM, Output = T>,
+    {
+        self.map_with(compiler, |entry, compiler| {
+            scalar.clone().mul(entry.clone(), compiler)
+        })
+    }
+
+    /// Computes the inner product of `self` and `rhs` in `compiler`.
+    #[inline]
+    pub fn dot<COM>(&self, rhs: &Self, compiler: &mut COM) -> T
+    where
+        T: Add<T, COM, Output = T> + Clone + Mul<T, COM, Output = T> + Zero<COM>,
+    {
+        inner_product(&self.0, &rhs.0, compiler)
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Vector<T, N> {
+    #[inline]
+    fn from(entries: [T; N]) -> Self {
+        Self(entries)
+    }
+}
+
+impl<T, const N: usize> From<Vector<T, N>> for [T; N] {
+    #[inline]
+    fn from(vector: Vector<T, N>) -> Self {
+        vector.0
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Vector<T, N> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+/// Matrix
+///
+/// A dense matrix with `R` rows and `C` columns stored in row-major order.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Matrix<T, const R: usize, const C: usize>(
+    /// Rows
+    pub [[T; C]; R],
+);
+
+impl<T, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Builds a new [`Matrix`] from its `rows`.
+    #[inline]
+    pub fn new(rows: [[T; C]; R]) -> Self {
+        Self(rows)
+    }
+
+    /// Returns the rows of `self`.
+    #[inline]
+    pub fn into_inner(self) -> [[T; C]; R] {
+        self.0
+    }
+
+    /// Returns an iterator over the rows of `self`.
+    #[inline]
+    pub fn rows(&self) -> core::slice::Iter<[T; C]> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over the entries of the `index`-th column of `self`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is not smaller than `C`.
+    #[inline]
+    pub fn column(&self, index: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            index < C,
+            "The column index must be smaller than the number of columns."
+        );
+        self.0.iter().map(move |row| &row[index])
+    }
+
+    /// Returns the zero matrix in `compiler`.
+    #[inline]
+    pub fn zero<COM>(compiler: &mut COM) -> Self
+    where
+        T: Zero<COM>,
+    {
+        Self(array::from_fn(|_| array::from_fn(|_| T::zero(compiler))))
+    }
+
+    /// Returns the transpose of `self`.
+    #[inline]
+    pub fn transpose(&self) -> Matrix<T, C, R>
+    where
+        T: Clone,
+    {
+        Matrix(array::from_fn(|i| array::from_fn(|j| self.0[j][i].clone())))
+    }
+
+    /// Adds `self` and `rhs` entrywise in `compiler`.
+    #[inline]
+    pub fn add<COM>(&self, rhs: &Self, compiler: &mut COM) -> Self
+    where
+        T: Add<T, COM, Output = T> + Clone,
+    {
+        Self(zip_with_compiler(
+            self.0.clone(),
+            rhs.0.clone(),
+            compiler,
+            |lhs, rhs, compiler| zip_with_compiler(lhs, rhs, compiler, T::add),
+        ))
+    }
+
+    /// Multiplies `self` with the column vector `vector` using `mul` and `add`, lending `compiler`
+    /// to each call. This is the entry point for products whose entries have different types,
+    /// like constant matrices multiplied with vectors of variables.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `C` is zero.
+    #[inline]
+    pub fn mul_vector_with<U, V, M, S, COM>(
+        &self,
+        vector: &Vector<U, C>,
+        compiler: &mut COM,
+        mut mul: M,
+        mut add: S,
+    ) -> Vector<V, R>
+    where
+        M: FnMut(&T, &U, &mut COM) -> V,
+        S: FnMut(V, V, &mut COM) -> V,
+    {
+        Vector(map_ref_with_compiler(&self.0, compiler, |row, compiler| {
+            linear_combination_with(row, &vector.0, compiler, &mut mul, &mut add)
+                .expect("Matrices must have at least one column.")
+        }))
+    }
+
+    /// Multiplies `self` with the column vector `vector` in `compiler`.
+    #[inline]
+    pub fn mul_vector<COM>(&self, vector: &Vector<T, C>, compiler: &mut COM) -> Vector<T, R>
+    where
+        T: Add<T, COM, Output = T> + Clone + Mul<T, COM, Output = T> + Zero<COM>,
+    {
+        Vector(map_ref_with_compiler(&self.0, compiler, |row, compiler| {
+            inner_product(row, &vector.0, compiler)
+        }))
+    }
+
+    /// Multiplies `self` with `rhs` in `compiler`.
+    #[inline]
+    pub fn mul<COM, const K: usize>(
+        &self,
+        rhs: &Matrix<T, C, K>,
+        compiler: &mut COM,
+    ) -> Matrix<T, R, K>
+    where
+        T: Add<T, COM, Output = T> + Clone + Mul<T, COM, Output = T> + Zero<COM>,
+    {
+        let columns = rhs.transpose();
+        Matrix(map_ref_with_compiler(&self.0, compiler, |row, compiler| {
+            map_ref_with_compiler(&columns.0, compiler, |column, compiler| {
+                inner_product(row, column, compiler)
+            })
+        }))
+    }
+}
+
+impl<T, const N: usize> Matrix<T, N, N> {
+    /// Returns the identity matrix in `compiler`.
+    #[inline]
+    pub fn identity<COM>(compiler: &mut COM) -> Self
+    where
+        T: One<COM> + Zero<COM>,
+    {
+        Self(array::from_fn(|i| {
+            array::from_fn(|j| {
+                if i == j {
+                    T::one(compiler)
+                } else {
+                    T::zero(compiler)
+                }
+            })
+        }))
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    #[inline]
+    fn from(rows: [[T; C]; R]) -> Self {
+        Self(rows)
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<usize> for Matrix<T, R, C> {
+    type Output = [T; C];
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<usize> for Matrix<T, R, C> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
@@ -96,7 +96,7 @@ macro_rules! define_zero_one {
     }
 }
 
-define_zero_one!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+define_zero_one!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
 
 /// Within-Bit-Range Assertion
 ///
@@ -110,6 +110,122 @@ pub trait AssertWithinBitRange<T, const BITS: usize> {
     fn assert_within_range(&mut self, value: &T);
 }
 
+/// Byte Decomposition
+///
+/// The checked methods of this `trait` return the canonical byte representation of `self`. The
+/// unchecked methods skip the checks which make the representation canonical, so that for types
+/// like field elements any representation of the same value can be returned.
+pub trait ToBytes<const BYTES: usize, COM = ()> {
+    /// Byte Type
+    type Byte;
+
+    /// Returns the canonical little-endian byte representation of `self`, with trailing zeroes.
+    fn to_bytes_le(&self, compiler: &mut COM) -> [Self::Byte; BYTES];
+
+    /// Returns the canonical big-endian byte representation of `self`, with leading zeroes.
+    #[inline]
+    fn to_bytes_be(&self, compiler: &mut COM) -> [Self::Byte; BYTES] {
+        let mut bytes = self.to_bytes_le(compiler);
+        bytes.reverse();
+        bytes
+    }
+
+    /// Returns a little-endian byte representation of `self`, with trailing zeroes, without
+    /// checking that it is canonical.
+    ///
+    /// # Crypto Safety
+    ///
+    /// The returned bytes can be any representation of the value of `self`. For example, the
+    /// bytes of a field element may encode its value plus the modulus. Only use this method when
+    /// the bytes are checked against their canonical form elsewhere or when every representation
+    /// is acceptable.
+    fn to_bytes_le_unchecked(&self, compiler: &mut COM) -> [Self::Byte; BYTES];
+
+    /// Returns a big-endian byte representation of `self`, with leading zeroes, without checking
+    /// that it is canonical. See [`to_bytes_le_unchecked`](Self::to_bytes_le_unchecked) for more.
+    #[inline]
+    fn to_bytes_be_unchecked(&self, compiler: &mut COM) -> [Self::Byte; BYTES] {
+        let mut bytes = self.to_bytes_le_unchecked(compiler);
+        bytes.reverse();
+        bytes
+    }
+}
+
+/// Bit Recomposition
+///
+/// This `trait` is the inverse of [`BitDecomposition`]. The checked methods assert that the bits
+/// are the canonical representation of the result, while the unchecked methods accept any bits
+/// and reduce them into the result.
+pub trait FromBits<const BITS: usize, COM = ()>: Sized
+where
+    COM: Has<bool> + ?Sized,
+{
+    /// Builds a value from its canonical little-endian `bits`, asserting that they are canonical.
+    fn from_bits_le(bits: [Bool<COM>; BITS], compiler: &mut COM) -> Self;
+
+    /// Builds a value from its canonical big-endian `bits`, asserting that they are canonical.
+    #[inline]
+    fn from_bits_be(mut bits: [Bool<COM>; BITS], compiler: &mut COM) -> Self {
+        bits.reverse();
+        Self::from_bits_le(bits, compiler)
+    }
+
+    /// Builds a value from the little-endian `bits` without asserting that they are canonical.
+    ///
+    /// # Crypto Safety
+    ///
+    /// Different bit strings can result in the same value. For example, the bits of a field
+    /// element and the bits of that element plus the modulus both recompose into the element.
+    /// Only use this method when the bits are known to be in range, for example because `BITS` is
+    /// smaller than the number of bits of the modulus.
+    fn from_bits_le_unchecked(bits: [Bool<COM>; BITS], compiler: &mut COM) -> Self;
+
+    /// Builds a value from the big-endian `bits` without asserting that they are canonical. See
+    /// [`from_bits_le_unchecked`](Self::from_bits_le_unchecked) for more.
+    #[inline]
+    fn from_bits_be_unchecked(mut bits: [Bool<COM>; BITS], compiler: &mut COM) -> Self {
+        bits.reverse();
+        Self::from_bits_le_unchecked(bits, compiler)
+    }
+}
+
+/// Implements [`ToBytes`] and [`FromBits`] for the given `$type`.
+macro_rules! impl_bytes_and_bits {
+    ($($type:tt),* $(,)?) => {
+        $(
+            impl ToBytes<{ $type::BITS as usize / 8 }> for $type {
+                type Byte = u8;
+
+                #[inline]
+                fn to_bytes_le(&self, _: &mut ()) -> [u8; $type::BITS as usize / 8] {
+                    (*self).to_le_bytes()
+                }
+
+                #[inline]
+                fn to_bytes_le_unchecked(&self, compiler: &mut ()) -> [u8; $type::BITS as usize / 8] {
+                    self.to_bytes_le(compiler)
+                }
+            }
+
+            impl FromBits<{ $type::BITS as usize }> for $type {
+                #[inline]
+                fn from_bits_le(bits: [bool; $type::BITS as usize], _: &mut ()) -> Self {
+                    bits.iter()
+                        .rev()
+                        .fold(0, |value, bit| (value << 1) | (*bit as $type))
+                }
+
+                #[inline]
+                fn from_bits_le_unchecked(bits: [bool; $type::BITS as usize], compiler: &mut ()) -> Self {
+                    Self::from_bits_le(bits, compiler)
+                }
+            }
+        )*
+    }
+}
+
+impl_bytes_and_bits!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
 /// Unsigned Integer
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct UnsignedInteger<T, const BITS: usize>(T);
@@ -1,5 +1,9 @@
 //! Overloadable Operations
 
+use crate::{
+    bool::{Bool, ConditionalSelect},
+    Has,
+};
 use core::ops;
 
 /// Defines a unary operation for compilers based on those defined in [`core::ops`].
@@ -228,3 +232,56 @@ binary_op_assign!(RemAssign, HasRemAssign, rem_assign, "Remainder", "%=");
 binary_op_assign!(ShlAssign, HasShlAssign, shl_assign, "Left Shift", "<<=");
 binary_op_assign!(ShrAssign, HasShrAssign, shr_assign, "Right Shift", ">>=");
 binary_op_assign!(SubAssign, HasSubAssign, sub_assign, "Subtraction", "-=");
+
+/// Multiplicative Inverse
+///
+/// # Zero Semantics
+///
+/// Values without an inverse, like zero in a field, have no well-defined result for
+/// [`inverse`](Self::inverse): compilers must reject them, leaving the constraint system
+/// unsatisfiable, and the native compiler panics. Use [`checked_inverse`](Self::checked_inverse)
+/// when the input may be zero.
+pub trait Inverse<COM = ()>
+where
+    COM: ?Sized,
+{
+    /// Verification Type
+    type Verification;
+
+    /// Returns the multiplicative inverse of `self`, asserting that it exists.
+    fn inverse(&self, compiler: &mut COM) -> Self;
+
+    /// Returns the multiplicative inverse of `self` together with a truthy value if `self` is
+    /// invertible. When `self` is not invertible, the returned value is the additive identity and
+    /// the verification is falsy.
+    fn checked_inverse(&self, compiler: &mut COM) -> (Self, Self::Verification)
+    where
+        Self: Sized;
+}
+
+/// Conditionally executes a sub-circuit, returning the output of `true_branch` when `bit == true`
+/// and the output of `false_branch` when `bit == false`.
+///
+/// # Execution
+///
+/// Inside of a circuit both branches are always executed, and their outputs are merged with
+/// [`ConditionalSelect`]. Any bundle of variables can be returned from the branches as long as it
+/// implements [`ConditionalSelect`], like tuples, arrays, or `struct`s deriving
+/// `ConditionalSelect`. Assertions made inside of either branch are not conditional on `bit`.
+#[inline]
+pub fn select_many<T, F, G, COM>(
+    bit: &Bool<COM>,
+    true_branch: F,
+    false_branch: G,
+    compiler: &mut COM,
+) -> T
+where
+    T: ConditionalSelect<COM>,
+    F: FnOnce(&mut COM) -> T,
+    G: FnOnce(&mut COM) -> T,
+    COM: Has<bool> + ?Sized,
+{
+    let true_value = true_branch(compiler);
+    let false_value = false_branch(compiler);
+    T::select(bit, &true_value, &false_value, compiler)
+}
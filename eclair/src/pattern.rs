@@ -0,0 +1,487 @@
+//! Pattern Matching
+//!
+//! Statements about documents often only need to show that a byte string contains some field, like
+//! `"amount":`, or that it has some fixed shape. This module compiles a small pattern language into
+//! a deterministic finite automaton, a [`Dfa`], while the circuit is being built, and runs the
+//! automaton over a padded [`Bytes`] string in any compiler with [`Dfa::matches`].
+//!
+//! # Syntax
+//!
+//! A pattern is a sequence of atoms, each of which can be followed by a quantifier:
+//!
+//! - a byte matches itself, unless it is one of the reserved bytes `\.[]*+?(){}|^$`,
+//! - `\` followed by any byte matches that byte,
+//! - `.` matches any byte,
+//! - `[...]` matches the bytes and ranges like `a-z` listed in the class, or every other byte if
+//!   the class starts with `^`, and
+//! - `*`, `+`, and `?` match the preceding atom any number of times, at least once, or at most
+//!   once.
+//!
+//! Groups and alternation are not supported.
+//!
+//! # Cost
+//!
+//! The automaton only distinguishes between classes of bytes with the same transitions. For every
+//! byte of the input, [`Dfa::matches`] compares the byte with every member of every class except
+//! for the largest one, and then updates one bit per state of the automaton. Patterns made of
+//! literals and `.` are therefore much cheaper than patterns with large classes like `[^"]`.
+
+use crate::{
+    alloc::{Allocate, Allocator, Constant},
+    bool::Bool,
+    bytes::{Byte, Bytes, Length},
+    cmp::PartialEq,
+    num::{One, Zero},
+    ops::{Add, BitAnd, BitOr, Not},
+    Has,
+};
+use rust_alloc::{collections::VecDeque, vec::Vec};
+
+/// Maximum Number of Atoms
+///
+/// Patterns are compiled through automata whose states are subsets of the atoms of the pattern,
+/// which are stored as bit sets. A `+` quantifier counts as two atoms.
+pub const MAX_ATOMS: usize = 127;
+
+/// Pattern Error
+///
+/// This `enum` is the error state of [`Dfa::compile`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PatternError {
+    /// Unexpected End
+    ///
+    /// The pattern ends inside of an escape sequence or a class.
+    UnexpectedEnd,
+
+    /// Unexpected Byte
+    ///
+    /// The byte at `position` is a quantifier without an atom, a reserved byte which is not
+    /// supported, the end of an empty class, or the end of a decreasing range.
+    UnexpectedByte {
+        /// Position of the Byte in the Pattern
+        position: usize,
+    },
+
+    /// Too Many Atoms
+    ///
+    /// The pattern has more than [`MAX_ATOMS`] atoms.
+    TooManyAtoms,
+}
+
+/// Matching Mode
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Mode {
+    /// Full Match
+    ///
+    /// The whole input has to match the pattern.
+    Full,
+
+    /// Substring Match
+    ///
+    /// Some substring of the input has to match the pattern.
+    Contains,
+}
+
+/// Byte Set
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+struct ByteSet([u128; 2]);
+
+impl ByteSet {
+    /// Returns the set of all bytes.
+    #[inline]
+    fn full() -> Self {
+        Self([u128::MAX; 2])
+    }
+
+    /// Returns `true` if `byte` is in `self`.
+    #[inline]
+    fn contains(&self, byte: u8) -> bool {
+        self.0[usize::from(byte >> 7)] & (1 << (byte & 127)) != 0
+    }
+
+    /// Inserts the bytes from `start` to `end` into `self`.
+    #[inline]
+    fn insert_range(&mut self, start: u8, end: u8) {
+        for byte in start..=end {
+            self.0[usize::from(byte >> 7)] |= 1 << (byte & 127);
+        }
+    }
+
+    /// Returns the set of bytes which are not in `self`.
+    #[inline]
+    fn complement(self) -> Self {
+        Self([!self.0[0], !self.0[1]])
+    }
+}
+
+/// Atom Quantifier
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Quantifier {
+    /// Exactly Once
+    One,
+
+    /// At Most Once
+    Optional,
+
+    /// Any Number of Times
+    Star,
+}
+
+/// Returns `true` if `byte` has to be escaped to match itself outside of a class.
+#[inline]
+fn is_reserved(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'\\'
+            | b'.'
+            | b'['
+            | b']'
+            | b'*'
+            | b'+'
+            | b'?'
+            | b'('
+            | b')'
+            | b'{'
+            | b'}'
+            | b'|'
+            | b'^'
+            | b'$'
+    )
+}
+
+/// Parses `pattern` into its sequence of atoms, expanding `+` quantifiers into an atom followed by
+/// a starred copy of itself.
+#[inline]
+fn parse(pattern: &[u8]) -> Result<Vec<(ByteSet, Quantifier)>, PatternError> {
+    let mut atoms = Vec::new();
+    let mut position = 0;
+    while let Some(&byte) = pattern.get(position) {
+        let set = match byte {
+            b'\\' => {
+                position += 1;
+                let escaped = *pattern.get(position).ok_or(PatternError::UnexpectedEnd)?;
+                let mut set = ByteSet::default();
+                set.insert_range(escaped, escaped);
+                set
+            }
+            b'.' => ByteSet::full(),
+            b'[' => {
+                position += 1;
+                let negated = pattern.get(position) == Some(&b'^');
+                if negated {
+                    position += 1;
+                }
+                let mut set = ByteSet::default();
+                let mut is_empty = true;
+                loop {
+                    let start = match *pattern.get(position).ok_or(PatternError::UnexpectedEnd)? {
+                        b']' if !is_empty => break,
+                        b']' => return Err(PatternError::UnexpectedByte { position }),
+                        b'\\' => {
+                            position += 1;
+                            *pattern.get(position).ok_or(PatternError::UnexpectedEnd)?
+                        }
+                        byte => byte,
+                    };
+                    let mut end = start;
+                    if pattern.get(position + 1) == Some(&b'-')
+                        && !matches!(pattern.get(position + 2), Some(b']') | None)
+                    {
+                        position += 2;
+                        end = match pattern[position] {
+                            b'\\' => {
+                                position += 1;
+                                *pattern.get(position).ok_or(PatternError::UnexpectedEnd)?
+                            }
+                            byte => byte,
+                        };
+                        if end < start {
+                            return Err(PatternError::UnexpectedByte { position });
+                        }
+                    }
+                    set.insert_range(start, end);
+                    is_empty = false;
+                    position += 1;
+                }
+                if negated {
+                    set.complement()
+                } else {
+                    set
+                }
+            }
+            byte if is_reserved(byte) => return Err(PatternError::UnexpectedByte { position }),
+            byte => {
+                let mut set = ByteSet::default();
+                set.insert_range(byte, byte);
+                set
+            }
+        };
+        position += 1;
+        match pattern.get(position) {
+            Some(b'*') => {
+                atoms.push((set, Quantifier::Star));
+                position += 1;
+            }
+            Some(b'+') => {
+                atoms.push((set, Quantifier::One));
+                atoms.push((set, Quantifier::Star));
+                position += 1;
+            }
+            Some(b'?') => {
+                atoms.push((set, Quantifier::Optional));
+                position += 1;
+            }
+            _ => atoms.push((set, Quantifier::One)),
+        }
+        if atoms.len() > MAX_ATOMS {
+            return Err(PatternError::TooManyAtoms);
+        }
+    }
+    Ok(atoms)
+}
+
+/// Nondeterministic Automaton
+///
+/// The state `i` of the automaton means that the first `i` atoms have been matched, and sets of
+/// states are stored as bit sets.
+struct Nfa {
+    /// Atoms
+    atoms: Vec<(ByteSet, Quantifier)>,
+
+    /// Matching Mode
+    mode: Mode,
+}
+
+impl Nfa {
+    /// Returns the accepting state of `self`.
+    #[inline]
+    fn accepting(&self) -> u128 {
+        1 << self.atoms.len()
+    }
+
+    /// Adds the states to `states` which can be reached from them without consuming a byte.
+    #[inline]
+    fn closure(&self, mut states: u128) -> u128 {
+        for (i, (_, quantifier)) in self.atoms.iter().enumerate() {
+            if states & (1 << i) != 0 && *quantifier != Quantifier::One {
+                states |= 1 << (i + 1);
+            }
+        }
+        states
+    }
+
+    /// Returns the states which can be reached from `states` by consuming `byte`.
+    #[inline]
+    fn step(&self, states: u128, byte: u8) -> u128 {
+        let mut next = 0;
+        for (i, (set, quantifier)) in self.atoms.iter().enumerate() {
+            if states & (1 << i) != 0 && set.contains(byte) {
+                next |= match quantifier {
+                    Quantifier::Star => 1 << i,
+                    _ => 1 << (i + 1),
+                };
+            }
+        }
+        if self.mode == Mode::Contains {
+            next |= 1 | (states & self.accepting());
+        }
+        self.closure(next)
+    }
+}
+
+/// Deterministic Finite Automaton
+///
+/// The automaton starts in the state `0` and its bytes are partitioned into classes of bytes with
+/// the same transitions. See the [module-level documentation](self) for more.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Dfa {
+    /// Class of every Byte
+    class_of: Vec<usize>,
+
+    /// Members of every Class
+    classes: Vec<Vec<u8>>,
+
+    /// Largest Class
+    largest_class: usize,
+
+    /// Transitions from every State for every Class
+    transitions: Vec<Vec<usize>>,
+
+    /// Accepting States
+    accepting: Vec<bool>,
+}
+
+impl Dfa {
+    /// Compiles `pattern` into a [`Dfa`] which matches inputs with respect to `mode`.
+    #[inline]
+    pub fn compile(pattern: &[u8], mode: Mode) -> Result<Self, PatternError> {
+        let nfa = Nfa {
+            atoms: parse(pattern)?,
+            mode,
+        };
+        let mut states = Vec::from([nfa.closure(1)]);
+        let mut queue = VecDeque::from([0]);
+        let mut byte_transitions = Vec::<[usize; 256]>::new();
+        while let Some(index) = queue.pop_front() {
+            let mut row = [0; 256];
+            for (byte, target) in (0..=u8::MAX).zip(row.iter_mut()) {
+                let next = nfa.step(states[index], byte);
+                *target = match states.iter().position(|state| *state == next) {
+                    Some(target) => target,
+                    _ => {
+                        states.push(next);
+                        queue.push_back(states.len() - 1);
+                        states.len() - 1
+                    }
+                };
+            }
+            byte_transitions.push(row);
+        }
+        let mut class_of = Vec::with_capacity(256);
+        let mut classes = Vec::<Vec<u8>>::new();
+        let mut columns = Vec::<Vec<usize>>::new();
+        for byte in 0..=u8::MAX {
+            let column = byte_transitions
+                .iter()
+                .map(|row| row[usize::from(byte)])
+                .collect::<Vec<_>>();
+            let class = match columns.iter().position(|c| *c == column) {
+                Some(class) => class,
+                _ => {
+                    columns.push(column);
+                    classes.push(Vec::new());
+                    classes.len() - 1
+                }
+            };
+            classes[class].push(byte);
+            class_of.push(class);
+        }
+        let largest_class = (0..classes.len())
+            .max_by_key(|class| classes[*class].len())
+            .expect("Every byte belongs to a class.");
+        let transitions = byte_transitions
+            .iter()
+            .map(|row| {
+                classes
+                    .iter()
+                    .map(|members| row[usize::from(members[0])])
+                    .collect()
+            })
+            .collect();
+        let accepting = states
+            .iter()
+            .map(|state| state & nfa.accepting() != 0)
+            .collect();
+        Ok(Self {
+            class_of,
+            classes,
+            largest_class,
+            transitions,
+            accepting,
+        })
+    }
+
+    /// Returns the number of states of `self`.
+    #[inline]
+    pub fn states(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Returns the number of classes of bytes distinguished by `self`.
+    #[inline]
+    pub fn classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Returns `true` if `self` accepts `input`.
+    #[inline]
+    pub fn is_match(&self, input: &[u8]) -> bool {
+        let state = input.iter().fold(0, |state, byte| {
+            self.transitions[state][self.class_of[usize::from(*byte)]]
+        });
+        self.accepting[state]
+    }
+
+    /// Returns `true` if `self` accepts the bytes of `input` up to its length.
+    ///
+    /// The input must be well-formed, which holds for [`Bytes`] built with [`Bytes::new`] or
+    /// allocated as variables.
+    #[inline]
+    pub fn matches<COM, const MAX_LEN: usize>(
+        &self,
+        input: &Bytes<COM, MAX_LEN>,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        COM: Has<bool> + Has<u8> + Has<usize>,
+        Bool<COM>: Clone
+            + Constant<COM, Type = bool>
+            + BitAnd<Bool<COM>, COM, Output = Bool<COM>>
+            + BitOr<Bool<COM>, COM, Output = Bool<COM>>
+            + Not<COM, Output = Bool<COM>>,
+        Byte<COM>: Constant<COM> + PartialEq<Byte<COM>, COM> + Zero<COM, Verification = Bool<COM>>,
+        <Byte<COM> as Constant<COM>>::Type: From<u8>,
+        Length<COM>: Add<Length<COM>, COM, Output = Length<COM>>
+            + Clone
+            + One<COM>
+            + PartialEq<Length<COM>, COM>
+            + Zero<COM>,
+    {
+        let members = self
+            .classes
+            .iter()
+            .enumerate()
+            .map(|(class, members)| {
+                if class == self.largest_class {
+                    Vec::new()
+                } else {
+                    members
+                        .iter()
+                        .map(|byte| compiler.allocate_constant::<Byte<COM>>(&(*byte).into()))
+                        .collect()
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut states = (0..self.states())
+            .map(|state| (state == 0).as_constant(compiler))
+            .collect::<Vec<Bool<COM>>>();
+        for (byte, is_active) in input.bytes().iter().zip(input.mask(compiler)) {
+            let mut is_class = Vec::with_capacity(members.len());
+            let mut is_other = false.as_constant::<Bool<COM>>(compiler);
+            for members in &members {
+                let mut is_member = false.as_constant::<Bool<COM>>(compiler);
+                for member in members {
+                    let is_equal = byte.eq(member, compiler);
+                    is_member = is_member.bitor(is_equal, compiler);
+                }
+                is_other = is_other.bitor(is_member.clone(), compiler);
+                is_class.push(is_member);
+            }
+            is_class[self.largest_class] = is_other.not(compiler);
+            let is_inactive = is_active.clone().not(compiler);
+            let mut next = Vec::with_capacity(states.len());
+            for (target, state) in states.iter().enumerate() {
+                let mut is_reached = false.as_constant::<Bool<COM>>(compiler);
+                for (source, row) in self.transitions.iter().enumerate() {
+                    for (class, _) in row.iter().enumerate().filter(|(_, t)| **t == target) {
+                        let is_taken = states[source]
+                            .clone()
+                            .bitand(is_class[class].clone(), compiler);
+                        is_reached = is_reached.bitor(is_taken, compiler);
+                    }
+                }
+                let is_advanced = is_active.clone().bitand(is_reached, compiler);
+                let is_kept = is_inactive.clone().bitand(state.clone(), compiler);
+                next.push(is_advanced.bitor(is_kept, compiler));
+            }
+            states = next;
+        }
+        states
+            .into_iter()
+            .zip(&self.accepting)
+            .filter(|(_, is_accepting)| **is_accepting)
+            .fold(false.as_constant(compiler), |is_match, (state, _)| {
+                is_match.bitor(state, compiler)
+            })
+    }
+}
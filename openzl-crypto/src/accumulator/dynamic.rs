@@ -0,0 +1,131 @@
+//! Dynamic Accumulators
+//!
+//! Dynamic accumulators extend the insert-only [`Accumulator`] interface with removals. Since
+//! removing an item changes the accumulated output, every outstanding [`MembershipProof`] for the
+//! remaining items becomes stale. The [`DynamicAccumulator`] `trait` publishes an
+//! [`Update`](DynamicAccumulator::Update) for every change so that holders of membership proofs
+//! can update them without access to the accumulator itself. This is the interface needed for
+//! revocation-list style applications, like credential revocation.
+
+use crate::accumulator::{Accumulator, MembershipProof, Types};
+
+/// Dynamic Accumulator
+pub trait DynamicAccumulator: Accumulator {
+    /// Witness Update Type
+    ///
+    /// The update is published by the accumulator whenever its state changes and allows the
+    /// holders of membership proofs for other items to bring them up to date with
+    /// [`update_witness`](Self::update_witness).
+    type Update;
+
+    /// Inserts `item` into `self` like [`insert`](Accumulator::insert), returning the [`Update`]
+    /// for the outstanding membership proofs. This method returns `None` if `item` could not be
+    /// inserted.
+    ///
+    /// [`Update`]: Self::Update
+    fn insert_with_update(&mut self, item: &Self::Item) -> Option<Self::Update>;
+
+    /// Removes `item` from `self`, returning the [`Update`] for the outstanding membership proofs.
+    /// This method returns `None` if `item` is not stored in `self`.
+    ///
+    /// After a successful removal, `self` must not return a membership proof for `item` with a
+    /// call to [`prove`](Accumulator::prove) until it is inserted again.
+    ///
+    /// [`Update`]: Self::Update
+    fn remove(&mut self, item: &Self::Item) -> Option<Self::Update>;
+
+    /// Updates the membership `witness` and accumulated `output` for `item` with `update`,
+    /// returning `false` if the membership proof cannot be updated, for example, if `update`
+    /// records the removal of `item` itself.
+    fn update_witness(
+        model: &Self::Model,
+        item: &Self::Item,
+        witness: &mut Self::Witness,
+        output: &mut Self::Output,
+        update: &Self::Update,
+    ) -> bool;
+}
+
+impl<M> MembershipProof<M>
+where
+    M: Types + ?Sized,
+{
+    /// Updates `self` with the `update` published by the dynamic accumulator `A`, returning
+    /// `false` if `self` cannot be updated. See [`DynamicAccumulator::update_witness`] for more.
+    #[inline]
+    pub fn update<A>(&mut self, model: &M, item: &M::Item, update: &A::Update) -> bool
+    where
+        A: DynamicAccumulator<Model = M, Item = M::Item, Witness = M::Witness, Output = M::Output>,
+    {
+        A::update_witness(model, item, &mut self.witness, &mut self.output, update)
+    }
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+    use crate::accumulator::Model;
+    use alloc::vec::Vec;
+    use core::fmt::Debug;
+
+    /// Inserts every item of `items` into `accumulator`, removes `removed` and asserts that the
+    /// following laws hold:
+    ///
+    /// 1. The removed item is no longer contained in the accumulator.
+    /// 2. The membership proofs of the remaining items, collected before the removal and updated
+    ///    with the published updates, verify and match the ones returned by the accumulator.
+    /// 3. The membership proof of the removed item cannot be updated past its removal.
+    #[inline]
+    pub fn assert_removal_laws<A>(accumulator: &mut A, items: &[A::Item], removed: &A::Item)
+    where
+        A: DynamicAccumulator,
+        A::Model: Model<Verification = bool>,
+        A::Item: Debug + PartialEq,
+        A::Witness: Debug + PartialEq,
+        A::Output: Debug + PartialEq,
+    {
+        for item in items {
+            assert!(
+                accumulator.insert_with_update(item).is_some(),
+                "Unable to insert {item:?}."
+            );
+        }
+        let mut proofs = items
+            .iter()
+            .map(|item| {
+                accumulator
+                    .prove(item)
+                    .unwrap_or_else(|| panic!("Missing proof for inserted item {item:?}."))
+            })
+            .collect::<Vec<_>>();
+        let update = accumulator
+            .remove(removed)
+            .unwrap_or_else(|| panic!("Unable to remove {removed:?}."));
+        assert!(
+            !accumulator.contains(removed),
+            "The removed item {removed:?} must not be contained in the accumulator."
+        );
+        for (item, proof) in items.iter().zip(&mut proofs) {
+            let updated = proof.update::<A>(accumulator.model(), item, &update);
+            if item == removed {
+                assert!(
+                    !updated,
+                    "The proof of the removed item {item:?} must not be updatable."
+                );
+                continue;
+            }
+            assert!(updated, "Unable to update the proof for {item:?}.");
+            assert!(
+                proof.verify(accumulator.model(), item, &mut ()),
+                "Invalid updated proof for {item:?}."
+            );
+            assert_eq!(
+                Some(&*proof),
+                accumulator.prove(item).as_ref(),
+                "The updated proof for {item:?} must match the one returned by the accumulator."
+            );
+        }
+    }
+}
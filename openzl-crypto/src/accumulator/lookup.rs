@@ -0,0 +1,330 @@
+//! Private Lookups
+//!
+//! Wallets which sync against a remote server need the encrypted payloads of their leaves together
+//! with membership proofs for them, but asking the server for a single index reveals which leaf
+//! belongs to the wallet. An [`EncryptedAccumulator`] stores [`EncryptedMessage`]s next to an
+//! [`Accumulator`] over their items, and answers a [`Query`] for a batch of indices with a
+//! [`ProofBundle`] holding the message and membership proof of every index in the batch. Clients
+//! hide the index they are interested in among uniformly sampled decoys with [`Query::sample`] and
+//! check the response for the real index with [`ProofBundle::open`].
+//!
+//! # Privacy
+//!
+//! The server sees every index of a query, so the real index is only hidden among the decoys of
+//! that query. Querying the same index again with fresh decoys reveals it as the intersection of
+//! the queries, so clients should keep the responses they have already received, or repeat the
+//! same query, instead of sampling a new one.
+
+use crate::{
+    accumulator::{Accumulator, ItemHashFunction, MembershipProof, Model, Types},
+    encryption::{CiphertextType, EncryptedMessage, HeaderType},
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::{derivative, rand::RngCore};
+
+/// Encrypted Accumulator
+///
+/// Stores [`EncryptedMessage`]s in insertion order and inserts the item of every message under
+/// the item hash `H` into the accumulator `A`. See the [module-level documentation](self) for
+/// more.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "A: Clone, H: Clone, E::Header: Clone, E::Ciphertext: Clone"),
+    Debug(bound = "A: Debug, H: Debug, E::Header: Debug, E::Ciphertext: Debug")
+)]
+pub struct EncryptedAccumulator<A, H, E>
+where
+    A: Accumulator,
+    E: CiphertextType + HeaderType,
+{
+    /// Accumulator
+    accumulator: A,
+
+    /// Item Hash Function
+    item_hash: H,
+
+    /// Stored Messages
+    messages: Vec<EncryptedMessage<E>>,
+}
+
+impl<A, H, E> EncryptedAccumulator<A, H, E>
+where
+    A: Accumulator,
+    E: CiphertextType + HeaderType,
+{
+    /// Builds a new [`EncryptedAccumulator`] over `accumulator` and `item_hash`.
+    ///
+    /// The indices of the messages are only meaningful if `accumulator` starts out empty.
+    #[inline]
+    pub fn new(accumulator: A, item_hash: H) -> Self {
+        Self {
+            accumulator,
+            item_hash,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying accumulator of `self`.
+    #[inline]
+    pub fn accumulator(&self) -> &A {
+        &self.accumulator
+    }
+
+    /// Returns the item hash function of `self`.
+    #[inline]
+    pub fn item_hash(&self) -> &H {
+        &self.item_hash
+    }
+
+    /// Returns the messages stored in `self` in insertion order.
+    #[inline]
+    pub fn messages(&self) -> &[EncryptedMessage<E>] {
+        &self.messages
+    }
+
+    /// Returns the number of messages stored in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if `self` does not store any messages.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the message stored at `index`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&EncryptedMessage<E>> {
+        self.messages.get(index)
+    }
+
+    /// Inserts `message` into `self`, returning its index, or returning `message` back if its item
+    /// could not be inserted into the accumulator.
+    #[inline]
+    pub fn insert(&mut self, message: EncryptedMessage<E>) -> Result<usize, EncryptedMessage<E>>
+    where
+        H: ItemHashFunction<EncryptedMessage<E>, Item = A::Item>,
+    {
+        if !self
+            .accumulator
+            .insert(&self.item_hash.item_hash(&message, &mut ()))
+        {
+            return Err(message);
+        }
+        self.messages.push(message);
+        Ok(self.messages.len() - 1)
+    }
+
+    /// Answers `query` with the message and membership proof of every index in the query,
+    /// returning `None` if some index is not stored in `self` or if the accumulator cannot prove
+    /// the membership of its item.
+    #[inline]
+    pub fn serve(&self, query: &Query) -> Option<ProofBundle<A::Model, E>>
+    where
+        H: ItemHashFunction<EncryptedMessage<E>, Item = A::Item>,
+        E::Header: Clone,
+        E::Ciphertext: Clone,
+    {
+        query
+            .indices
+            .iter()
+            .map(|index| {
+                let message = self.get(*index)?;
+                Some(Response {
+                    index: *index,
+                    proof: self
+                        .accumulator
+                        .prove(&self.item_hash.item_hash(message, &mut ()))?,
+                    message: message.clone(),
+                })
+            })
+            .collect::<Option<_>>()
+            .map(ProofBundle::new)
+    }
+}
+
+/// Lookup Query
+///
+/// A batch of indices into an [`EncryptedAccumulator`], of which the client is only interested in
+/// one. The position of that index in the batch is kept by the client.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Query {
+    /// Queried Indices
+    indices: Vec<usize>,
+}
+
+impl Query {
+    /// Builds a new [`Query`] for `indices`.
+    #[inline]
+    pub fn new(indices: Vec<usize>) -> Self {
+        Self { indices }
+    }
+
+    /// Samples a [`Query`] for `index` among `decoys`-many distinct decoy indices drawn uniformly
+    /// from the `len`-many indices of an [`EncryptedAccumulator`], returning the query and the
+    /// position of `index` in it. This method returns `None` if `index` is not smaller than `len`.
+    ///
+    /// If `decoys` is not smaller than `len`, every index is queried.
+    #[inline]
+    pub fn sample<R>(index: usize, decoys: usize, len: usize, rng: &mut R) -> Option<(Self, usize)>
+    where
+        R: RngCore + ?Sized,
+    {
+        if index >= len {
+            return None;
+        }
+        let decoys = decoys.min(len - 1);
+        let mut indices = Vec::with_capacity(decoys + 1);
+        indices.push(index);
+        while indices.len() <= decoys {
+            let decoy = sample_below(len, rng);
+            if !indices.contains(&decoy) {
+                indices.push(decoy);
+            }
+        }
+        let mut position = 0;
+        for i in (1..indices.len()).rev() {
+            let j = sample_below(i + 1, rng);
+            indices.swap(i, j);
+            if position == i {
+                position = j;
+            } else if position == j {
+                position = i;
+            }
+        }
+        Some((Self::new(indices), position))
+    }
+
+    /// Returns the indices of `self`.
+    #[inline]
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// Samples an index smaller than `bound` uniformly from `rng`.
+#[inline]
+fn sample_below<R>(bound: usize, rng: &mut R) -> usize
+where
+    R: RngCore + ?Sized,
+{
+    let bound = bound as u64;
+    let limit = u64::MAX - u64::MAX % bound;
+    loop {
+        let sample = rng.next_u64();
+        if sample < limit {
+            return (sample % bound) as usize;
+        }
+    }
+}
+
+/// Lookup Response
+///
+/// The message stored at `index` and the membership proof of its item.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "E::Header: Clone, E::Ciphertext: Clone, M::Witness: Clone, M::Output: Clone"),
+    Debug(bound = "E::Header: Debug, E::Ciphertext: Debug, M::Witness: Debug, M::Output: Debug"),
+    Eq(bound = "E::Header: Eq, E::Ciphertext: Eq, M::Witness: Eq, M::Output: Eq"),
+    Hash(bound = "E::Header: Hash, E::Ciphertext: Hash, M::Witness: Hash, M::Output: Hash"),
+    PartialEq(
+        bound = "E::Header: PartialEq, E::Ciphertext: PartialEq, M::Witness: PartialEq, M::Output: PartialEq"
+    )
+)]
+pub struct Response<M, E>
+where
+    M: Types + ?Sized,
+    E: CiphertextType + HeaderType + ?Sized,
+{
+    /// Index of the Message
+    pub index: usize,
+
+    /// Encrypted Message
+    pub message: EncryptedMessage<E>,
+
+    /// Membership Proof of the Item of the Message
+    pub proof: MembershipProof<M>,
+}
+
+/// Proof Bundle
+///
+/// The [`Response`]s to every index of a [`Query`], in the order of the query.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "E::Header: Clone, E::Ciphertext: Clone, M::Witness: Clone, M::Output: Clone"),
+    Debug(bound = "E::Header: Debug, E::Ciphertext: Debug, M::Witness: Debug, M::Output: Debug"),
+    Eq(bound = "E::Header: Eq, E::Ciphertext: Eq, M::Witness: Eq, M::Output: Eq"),
+    Hash(bound = "E::Header: Hash, E::Ciphertext: Hash, M::Witness: Hash, M::Output: Hash"),
+    PartialEq(
+        bound = "E::Header: PartialEq, E::Ciphertext: PartialEq, M::Witness: PartialEq, M::Output: PartialEq"
+    )
+)]
+pub struct ProofBundle<M, E>
+where
+    M: Types + ?Sized,
+    E: CiphertextType + HeaderType + ?Sized,
+{
+    /// Responses
+    responses: Vec<Response<M, E>>,
+}
+
+impl<M, E> ProofBundle<M, E>
+where
+    M: Types + ?Sized,
+    E: CiphertextType + HeaderType + ?Sized,
+{
+    /// Builds a new [`ProofBundle`] from `responses`.
+    #[inline]
+    pub fn new(responses: Vec<Response<M, E>>) -> Self {
+        Self { responses }
+    }
+
+    /// Returns the responses of `self`.
+    #[inline]
+    pub fn responses(&self) -> &[Response<M, E>] {
+        &self.responses
+    }
+
+    /// Returns the responses of `self`, dropping the bundle.
+    #[inline]
+    pub fn into_inner(self) -> Vec<Response<M, E>> {
+        self.responses
+    }
+
+    /// Returns the message at `position` in `query` if `self` answers every index of `query` and
+    /// the membership proof of that message verifies against the accumulated `output` under
+    /// `model` and `item_hash`. The message can then be decrypted with
+    /// [`EncryptedMessage::decrypt`].
+    #[inline]
+    pub fn open<H, COM>(
+        &self,
+        query: &Query,
+        position: usize,
+        model: &M,
+        output: &M::Output,
+        item_hash: &H,
+        compiler: &mut COM,
+    ) -> Option<&EncryptedMessage<E>>
+    where
+        M: Model<COM, Verification = bool>,
+        M::Output: PartialEq,
+        H: ItemHashFunction<EncryptedMessage<E>, COM, Item = M::Item>,
+    {
+        if self.responses.len() != query.indices.len()
+            || self
+                .responses
+                .iter()
+                .zip(&query.indices)
+                .any(|(response, index)| response.index != *index)
+        {
+            return None;
+        }
+        let response = self.responses.get(position)?;
+        let item = item_hash.item_hash(&response.message, compiler);
+        (response.proof.output() == output && response.proof.verify(model, &item, compiler))
+            .then_some(&response.message)
+    }
+}
@@ -0,0 +1,861 @@
+//! Dynamic Cryptographic Accumulators
+
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use eclair::alloc::{mode::Derived, Allocate, Allocator, Constant, Variable};
+use openzl_util::{
+    codec::{Decode, DecodeError, Encode, Read, Write},
+    derivative,
+    rand::{RngCore, Sample},
+};
+
+#[cfg(feature = "prototyping")]
+use {
+    crate::Prototype,
+    eclair::{bool::Bool, Has},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+pub mod dynamic;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod lookup;
+
+/// Accumulator Membership Model Types
+pub trait Types {
+    /// Item Type
+    type Item;
+
+    /// Secret Witness Type
+    type Witness;
+
+    /// Output Type
+    type Output;
+}
+
+impl<T> Types for &T
+where
+    T: Types + ?Sized,
+{
+    type Item = T::Item;
+    type Witness = T::Witness;
+    type Output = T::Output;
+}
+
+impl<T> Types for &mut T
+where
+    T: Types + ?Sized,
+{
+    type Item = T::Item;
+    type Witness = T::Witness;
+    type Output = T::Output;
+}
+
+/// Accumulator Item Type
+pub type Item<T> = <T as Types>::Item;
+
+/// Accumulator Witness Type
+pub type Witness<T> = <T as Types>::Witness;
+
+/// Accumulator Output Type
+pub type Output<T> = <T as Types>::Output;
+
+/// Accumulator Membership Model
+pub trait Model<COM = ()>: Types {
+    /// Verification Type
+    ///
+    /// Typically this is either [`bool`], a [`Result`] type, or a circuit boolean variable.
+    type Verification;
+
+    /// Verifies that `item` is stored in a known accumulator with accumulated `output` and
+    /// membership `witness`.
+    fn verify(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        compiler: &mut COM,
+    ) -> Self::Verification;
+}
+
+/// Accumulator Item Hash Function
+pub trait ItemHashFunction<T, COM = ()> {
+    /// Item Type
+    type Item;
+
+    /// Converts `value` into an [`Item`](Self::Item) that is compatible with the relevant
+    /// accumulator.
+    fn item_hash(&self, value: &T, compiler: &mut COM) -> Self::Item;
+}
+
+/// Domain Item
+///
+/// Wraps an accumulator item of type `T` with the item domain `D`, which is only used as a
+/// type-level tag. Accumulators whose [`Item`](Types::Item) is a [`DomainItem`] can only insert or
+/// prove items which were explicitly built for their domain, so that items from other domains
+/// with the same underlying type are rejected at compile time. Generic code can require the items
+/// of an accumulator to belong to a domain with the [`InDomain`] bound, for example
+/// `A::Item: InDomain<D>`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "T: Deserialize<'de>", serialize = "T: Serialize"),
+        crate = "openzl_util::serde",
+        transparent
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "T: Clone"),
+    Copy(bound = "T: Copy"),
+    Debug(bound = "T: Debug"),
+    Default(bound = "T: Default"),
+    Eq(bound = "T: Eq"),
+    Hash(bound = "T: Hash"),
+    Ord(bound = "T: Ord"),
+    PartialEq(bound = "T: PartialEq"),
+    PartialOrd(bound = "T: PartialOrd")
+)]
+pub struct DomainItem<D, T> {
+    /// Item Value
+    value: T,
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<D>,
+}
+
+impl<D, T> DomainItem<D, T> {
+    /// Builds a new [`DomainItem`] in the domain `D` from `value`.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            __: PhantomData,
+        }
+    }
+
+    /// Returns a shared reference to the underlying value of `self`.
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the underlying value of `self`.
+    #[inline]
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<D, T> AsRef<T> for DomainItem<D, T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<D, T, COM> Constant<COM> for DomainItem<D, T>
+where
+    T: Constant<COM>,
+{
+    type Type = DomainItem<D, T::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.value.as_constant(compiler))
+    }
+}
+
+impl<D, T, M, COM> Variable<M, COM> for DomainItem<D, T>
+where
+    T: Variable<M, COM>,
+{
+    type Type = DomainItem<D, T::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.value.as_known(compiler))
+    }
+}
+
+impl<D, T> Decode for DomainItem<D, T>
+where
+    T: Decode,
+{
+    type Error = T::Error;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(T::decode(reader)?))
+    }
+}
+
+impl<D, T> Encode for DomainItem<D, T>
+where
+    T: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.value.encode(writer)
+    }
+}
+
+impl<D, T, S> Sample<S> for DomainItem<D, T>
+where
+    T: Sample<S>,
+{
+    #[inline]
+    fn sample<R>(distribution: S, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(T::sample(distribution, rng))
+    }
+}
+
+/// Item Domain Membership
+///
+/// This `trait` is implemented for every [`DomainItem`] in the domain `D`, and can be used to
+/// require that an accumulator only stores items of the domain `D`.
+pub trait InDomain<D> {
+    /// Value Type
+    type Value;
+
+    /// Returns a shared reference to the underlying value of `self`.
+    fn value(&self) -> &Self::Value;
+}
+
+impl<D, T> InDomain<D> for DomainItem<D, T> {
+    type Value = T;
+
+    #[inline]
+    fn value(&self) -> &Self::Value {
+        &self.value
+    }
+}
+
+/// Domain Item Hash Function
+///
+/// Wraps an [`ItemHashFunction`] so that its items are tagged with the domain `D`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "H: Deserialize<'de>", serialize = "H: Serialize"),
+        crate = "openzl_util::serde",
+        transparent
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "H: Clone"),
+    Copy(bound = "H: Copy"),
+    Debug(bound = "H: Debug"),
+    Default(bound = "H: Default"),
+    Eq(bound = "H: Eq"),
+    Hash(bound = "H: Hash"),
+    PartialEq(bound = "H: PartialEq")
+)]
+pub struct DomainItemHash<H, D> {
+    /// Base Item Hash Function
+    hasher: H,
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<D>,
+}
+
+impl<H, D> DomainItemHash<H, D> {
+    /// Builds a new [`DomainItemHash`] over `hasher`.
+    #[inline]
+    pub fn new(hasher: H) -> Self {
+        Self {
+            hasher,
+            __: PhantomData,
+        }
+    }
+
+    /// Returns the underlying hash function of `self`.
+    #[inline]
+    pub fn into_inner(self) -> H {
+        self.hasher
+    }
+}
+
+impl<H, D, T, COM> ItemHashFunction<T, COM> for DomainItemHash<H, D>
+where
+    H: ItemHashFunction<T, COM>,
+{
+    type Item = DomainItem<D, H::Item>;
+
+    #[inline]
+    fn item_hash(&self, value: &T, compiler: &mut COM) -> Self::Item {
+        DomainItem::new(self.hasher.item_hash(value, compiler))
+    }
+}
+
+/// Accumulator Membership Model Validity Assertion
+///
+/// For situations where we just want to assert validity of the membership proof, we can use this
+/// trait as an optimization path for [`Model::verify`]. See [`assert_valid`](Self::assert_valid)
+/// for more details.
+pub trait AssertValidVerification<COM = ()>: Model<COM> {
+    /// Asserts that the verification of the storage of `item` in the known accumulator is valid.
+    ///
+    /// # Optimization
+    ///
+    /// In compilers where assertions for more complex statements other than booleans being `true`,
+    /// this function can provide an optimization path to reduce the cost of assertion.
+    fn assert_valid(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        compiler: &mut COM,
+    );
+}
+
+/// Unsafe Assume-Valid Membership Model
+///
+/// # Crypto Safety
+///
+/// This model accepts every membership proof without checking it, both natively and in-circuit.
+/// It keeps the item, witness, and output types of `M` so that it can stand in for `M` while
+/// prototyping the rest of a protocol, but it must never be used in production. See
+/// [`Prototype`] for more.
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UnsafeAssumeValid<M>(
+    /// Underlying Membership Model
+    pub M,
+);
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<M> Prototype for UnsafeAssumeValid<M> {
+    const WARNING: &'static str =
+        "accumulator membership proofs are assumed to be valid without being verified";
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<M, COM> Constant<COM> for UnsafeAssumeValid<M>
+where
+    M: Constant<COM>,
+{
+    type Type = UnsafeAssumeValid<M::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<M> Types for UnsafeAssumeValid<M>
+where
+    M: Types,
+{
+    type Item = M::Item;
+    type Witness = M::Witness;
+    type Output = M::Output;
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<M, COM> Model<COM> for UnsafeAssumeValid<M>
+where
+    M: Types,
+    COM: Has<bool>,
+    Bool<COM>: Constant<COM, Type = bool>,
+{
+    type Verification = Bool<COM>;
+
+    #[inline]
+    fn verify(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        compiler: &mut COM,
+    ) -> Self::Verification {
+        let _ = (item, witness, output);
+        Bool::<COM>::new_constant(&true, compiler)
+    }
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<M, COM> AssertValidVerification<COM> for UnsafeAssumeValid<M>
+where
+    M: Types,
+    COM: Has<bool>,
+    Bool<COM>: Constant<COM, Type = bool>,
+{
+    #[inline]
+    fn assert_valid(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        compiler: &mut COM,
+    ) {
+        let _ = (item, witness, output, compiler);
+    }
+}
+
+/// Wraps `model` into an [`UnsafeAssumeValid`] model which accepts every membership proof.
+///
+/// # Crypto Safety
+///
+/// See [`UnsafeAssumeValid`] for more.
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+#[inline]
+pub fn unsafe_assume_valid<M>(model: M) -> UnsafeAssumeValid<M>
+where
+    M: Types,
+{
+    UnsafeAssumeValid(model)
+}
+
+/// Accumulator
+pub trait Accumulator: Types {
+    /// Model Type
+    type Model: Model<Item = Self::Item, Witness = Self::Witness, Output = Self::Output> + ?Sized;
+
+    /// Returns the model associated with `self`.
+    fn model(&self) -> &Self::Model;
+
+    /// Inserts `item` into `self` with the guarantee that `self` can later return a valid
+    /// membership proof for `item` with a call to [`prove`](Self::prove). This method returns
+    /// `false` if the maximum capacity of the accumulator would be exceeded by inserting `item`.
+    fn insert(&mut self, item: &Self::Item) -> bool;
+
+    /// Returns a membership proof for `item` if it is contained in `self`.
+    fn prove(&self, item: &Self::Item) -> Option<MembershipProof<Self::Model>>;
+
+    /// Returns `true` if `item` is stored in `self`.
+    ///
+    /// # Implementation Note
+    ///
+    /// This method must at least return `true` for `item` whenever a valid proof of membership
+    /// exists. It may return `true` in other cases when `self` knows that it has `item` stored but
+    /// cannot return a proof for it, like in the case of [`OptimizedAccumulator`] implementations.
+    /// In other words, this method is allowed to return false negatives, but not false positives.
+    #[inline]
+    fn contains(&self, item: &Self::Item) -> bool {
+        self.prove(item).is_some()
+    }
+}
+
+impl<A> Accumulator for &mut A
+where
+    A: Accumulator + ?Sized,
+{
+    type Model = A::Model;
+
+    #[inline]
+    fn model(&self) -> &Self::Model {
+        (**self).model()
+    }
+
+    #[inline]
+    fn insert(&mut self, item: &Self::Item) -> bool {
+        (**self).insert(item)
+    }
+
+    #[inline]
+    fn prove(&self, item: &Self::Item) -> Option<MembershipProof<Self::Model>> {
+        (**self).prove(item)
+    }
+
+    #[inline]
+    fn contains(&self, item: &Self::Item) -> bool {
+        (**self).contains(item)
+    }
+}
+
+/// Constant Capacity Accumulator
+pub trait ConstantCapacityAccumulator: Accumulator {
+    /// Returns the total number of items that can be stored in `self`.
+    fn capacity() -> usize;
+}
+
+/// Exact Size Accumulator
+pub trait ExactSizeAccumulator: Accumulator {
+    /// Returns the number of items stored in `self`.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the length of `self` is zero.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Optimized Accumulator
+pub trait OptimizedAccumulator: Accumulator {
+    /// Inserts `item` into `self` without the guarantee that `self` with be able to return a proof
+    /// of membership for `item`. This method returns `false` if the maximum capacity of the
+    /// accumulator would be exceeded by inserting `item`.
+    ///
+    /// # Implementation Note
+    ///
+    /// By default, this method uses [`insert`] to store `item` in `self`. Since this method may
+    /// insert items which will never have membership proofs, the [`contains`] method is allowed to
+    /// return `false` for those items if necessary. In other words, the [`contains`] method is
+    /// allowed to return false negatives, but not false positives.
+    ///
+    /// [`insert`]: Accumulator::insert
+    /// [`contains`]: Accumulator::contains
+    #[inline]
+    fn insert_nonprovable(&mut self, item: &Self::Item) -> bool {
+        self.insert(item)
+    }
+
+    /// Removes the witnesses to the membership of `item` in `self`. The resulting state of the
+    /// accumulator after removing a proof should be the same as if the item had been inserted into
+    /// the accumulator with [`insert_nonprovable`](Self::insert_nonprovable). This method returns
+    /// `true` if the item was successfully demoted to non-provable.
+    ///
+    /// # Implementation Note
+    ///
+    /// By default, this method does nothing and returns `false`. Implementations of this method may
+    /// fail arbitrarily, and should only successfully remove a proof if the implementation is
+    /// efficient enough. Space and time tradeoffs should be studied to determine the usefulness of
+    /// this method.
+    #[inline]
+    fn remove_proof(&mut self, item: &Self::Item) -> bool {
+        let _ = item;
+        false
+    }
+}
+
+/// Accumulator Membership Proof
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "M::Witness: Clone, M::Output: Clone"),
+    Copy(bound = "M::Witness: Copy, M::Output: Copy"),
+    Debug(bound = "M::Witness: Debug, M::Output: Debug"),
+    Default(bound = "M::Witness: Default, M::Output: Default"),
+    Eq(bound = "M::Witness: Eq, M::Output: Eq"),
+    Hash(bound = "M::Witness: Hash, M::Output: Hash"),
+    PartialEq(bound = "M::Witness: PartialEq, M::Output: PartialEq")
+)]
+pub struct MembershipProof<M>
+where
+    M: Types + ?Sized,
+{
+    /// Secret Membership Witness
+    witness: M::Witness,
+
+    /// Accumulator Output
+    output: M::Output,
+}
+
+impl<M> MembershipProof<M>
+where
+    M: Types + ?Sized,
+{
+    /// Builds a new [`MembershipProof`] from `witness` and `output`.
+    #[inline]
+    pub fn new(witness: M::Witness, output: M::Output) -> Self {
+        Self { witness, output }
+    }
+
+    /// Returns the accumulated output part of `self`, dropping the [`M::Witness`](Types::Witness).
+    #[inline]
+    pub fn into_output(self) -> M::Output {
+        self.output
+    }
+
+    /// Returns a reference to the accumulated output part of `self`.
+    #[inline]
+    pub fn output(&self) -> &M::Output {
+        &self.output
+    }
+
+    /// Splits `self` into its [`M::Witness`](Types::Witness) and [`M::Output`](Types::Output).
+    #[inline]
+    pub fn into_parts(self) -> (M::Witness, M::Output) {
+        (self.witness, self.output)
+    }
+
+    /// Verifies that `item` is stored in a known accumulator using `model`.
+    #[inline]
+    pub fn verify<COM>(&self, model: &M, item: &M::Item, compiler: &mut COM) -> M::Verification
+    where
+        M: Model<COM>,
+    {
+        model.verify(item, &self.witness, &self.output, compiler)
+    }
+
+    /// Asserts that the verification of the storage of `item` in the known accumulator is valid.
+    #[inline]
+    pub fn assert_valid<COM>(&self, model: &M, item: &M::Item, compiler: &mut COM)
+    where
+        M: AssertValidVerification<COM>,
+    {
+        model.assert_valid(item, &self.witness, &self.output, compiler)
+    }
+
+    /// Converts `self` from the `M` accumulator model to the `N` accumulator model.
+    ///
+    /// # Validity
+    ///
+    /// This function cannot guarantee that the point-wise conversion of the witness and output
+    /// preserves the membership proof validity.
+    #[inline]
+    pub fn into<N>(self) -> MembershipProof<N>
+    where
+        N: Types + ?Sized,
+        M::Witness: Into<N::Witness>,
+        M::Output: Into<N::Output>,
+    {
+        MembershipProof::new(self.witness.into(), self.output.into())
+    }
+}
+
+impl<M, W, O, COM> Variable<Derived<(W, O)>, COM> for MembershipProof<M>
+where
+    M: Constant<COM> + Model<COM>,
+    M::Type: Model,
+    M::Witness: Variable<W, COM, Type = <M::Type as Types>::Witness>,
+    M::Output: Variable<O, COM, Type = <M::Type as Types>::Output>,
+{
+    type Type = MembershipProof<M::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.witness.as_known(compiler),
+            this.output.as_known(compiler),
+        )
+    }
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+    use alloc::{format, vec::Vec};
+    use core::fmt::Debug;
+    use eclair::bool::{AssertLabeled, Report};
+    use openzl_util::rand::{Rand, RngCore, Sample};
+
+    /// Accumulator Operation
+    ///
+    /// Operations are sampled by [`Operations`] to build random operation sequences for
+    /// [`assert_operation_laws`].
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum Operation<T> {
+        /// Insert an item into the accumulator
+        Insert(T),
+
+        /// Request a membership proof for an item
+        Prove(T),
+    }
+
+    impl<T, D> Sample<D> for Operation<T>
+    where
+        T: Sample<D>,
+    {
+        #[inline]
+        fn sample<R>(distribution: D, rng: &mut R) -> Self
+        where
+            R: RngCore + ?Sized,
+        {
+            if rng.gen() {
+                Self::Insert(rng.sample(distribution))
+            } else {
+                Self::Prove(rng.sample(distribution))
+            }
+        }
+    }
+
+    /// Operation Sequence Distribution
+    ///
+    /// Samples a sequence of `length`-many [`Operation`]s with items drawn from `distribution`.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct Operations<D = ()> {
+        /// Number of Operations
+        pub length: usize,
+
+        /// Item Distribution
+        pub distribution: D,
+    }
+
+    impl<D> Operations<D> {
+        /// Builds a new [`Operations`] distribution for `length`-many operations over items
+        /// sampled from `distribution`.
+        #[inline]
+        pub fn new(length: usize, distribution: D) -> Self {
+            Self {
+                length,
+                distribution,
+            }
+        }
+    }
+
+    impl<T, D> Sample<Operations<D>> for Vec<Operation<T>>
+    where
+        T: Sample<D>,
+        D: Clone,
+    {
+        #[inline]
+        fn sample<R>(distribution: Operations<D>, rng: &mut R) -> Self
+        where
+            R: RngCore + ?Sized,
+        {
+            (0..distribution.length)
+                .map(|_| rng.sample(distribution.distribution.clone()))
+                .collect()
+        }
+    }
+
+    /// Runs the `operations` against `accumulator` and asserts that the following laws hold after
+    /// every step:
+    ///
+    /// 1. Every item that was successfully inserted is contained in the accumulator and has a
+    ///    membership proof which verifies.
+    /// 2. Every membership proof returned by the accumulator verifies.
+    /// 3. The accumulator never returns a membership proof for an item that was never inserted.
+    #[inline]
+    pub fn assert_operation_laws<A, I>(accumulator: &mut A, operations: I)
+    where
+        A: Accumulator,
+        A::Item: Clone + Debug + PartialEq,
+        A::Model: Model<Verification = bool>,
+        I: IntoIterator<Item = Operation<A::Item>>,
+    {
+        let mut report = Report::new();
+        let mut inserted = Vec::new();
+        for (step, operation) in operations.into_iter().enumerate() {
+            match operation {
+                Operation::Insert(item) => {
+                    if accumulator.insert(&item) {
+                        inserted.push(item);
+                    }
+                }
+                Operation::Prove(item) => match accumulator.prove(&item) {
+                    Some(proof) => {
+                        report.assert_labeled(
+                            &inserted.contains(&item),
+                            &format!("no proof for uninserted {item:?} at step {step:?}"),
+                        );
+                        report.assert_labeled(
+                            &proof.verify(accumulator.model(), &item, &mut ()),
+                            &format!("valid proof for {item:?} at step {step:?}"),
+                        );
+                    }
+                    _ => report.assert_labeled(
+                        &!inserted.contains(&item),
+                        &format!("proof for inserted {item:?} at step {step:?}"),
+                    ),
+                },
+            }
+            for item in &inserted {
+                report.assert_labeled(
+                    &accumulator
+                        .prove(item)
+                        .map(|proof| proof.verify(accumulator.model(), item, &mut ()))
+                        .unwrap_or(false),
+                    &format!("valid proof for inserted {item:?} at step {step:?}"),
+                );
+            }
+        }
+        report.assert_success();
+    }
+
+    /// Samples a random sequence of operations from `distribution` and runs
+    /// [`assert_operation_laws`] against `accumulator`.
+    #[inline]
+    pub fn assert_random_operation_laws<A, D, R>(
+        accumulator: &mut A,
+        distribution: Operations<D>,
+        rng: &mut R,
+    ) where
+        A: Accumulator,
+        A::Item: Clone + Debug + PartialEq + Sample<D>,
+        A::Model: Model<Verification = bool>,
+        D: Clone,
+        R: RngCore + ?Sized,
+    {
+        assert_operation_laws(
+            accumulator,
+            rng.sample::<_, Vec<Operation<A::Item>>>(distribution),
+        )
+    }
+
+    /// Asserts that `accumulator` can prove the membership of `item` after it is inserted.
+    #[inline]
+    pub fn assert_provable_membership<A>(accumulator: &mut A, item: &A::Item) -> Output<A>
+    where
+        A: Accumulator,
+        A::Model: Model<Verification = bool>,
+    {
+        assert!(
+            accumulator.insert(item),
+            "Item could not be inserted into the accumulator."
+        );
+        assert!(
+            accumulator.contains(item),
+            "Item was supposed to be contained in the accumulator after insertion."
+        );
+        if let Some(proof) = accumulator.prove(item) {
+            assert!(
+                proof.verify(accumulator.model(), item, &mut ()),
+                "Invalid proof returned for inserted item."
+            );
+            proof.into_output()
+        } else {
+            panic!("Item was supposed to be contained in the accumulator after insertion.")
+        }
+    }
+
+    /// Asserts that the `accumulator` yields unique accumulated values after every insertion of
+    /// items from `iter`.
+    #[inline]
+    pub fn assert_unique_outputs<'i, A, I>(accumulator: &mut A, iter: I)
+    where
+        A: Accumulator,
+        A::Item: 'i,
+        A::Model: Model<Verification = bool>,
+        Output<A>: Debug + PartialEq,
+        I: IntoIterator<Item = &'i A::Item>,
+    {
+        let outputs = iter
+            .into_iter()
+            .map(move |item| assert_provable_membership(accumulator, item))
+            .collect::<Vec<_>>();
+        for (i, x) in outputs.iter().enumerate() {
+            for (j, y) in outputs.iter().enumerate().skip(i + 1) {
+                assert_ne!(x, y, "Found matching outputs at {i:?} and {j:?}.")
+            }
+        }
+    }
+}
@@ -229,6 +229,13 @@ where
     }
 }
 
+impl<S, G, GEN> key::agreement::security::KeyPrivacy for StandardDiffieHellman<S, G, GEN>
+where
+    G: security::DecisionalDiffieHellmanHardness,
+    GEN: security::DecisionalDiffieHellmanHardness,
+{
+}
+
 impl<S, G, GEN> key::agreement::SecretKeyType for KnownScalarDiffieHellman<S, G, GEN> {
     type SecretKey = S;
 }
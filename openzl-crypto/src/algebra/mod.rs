@@ -6,14 +6,41 @@ use core::borrow::Borrow;
 use {
     eclair::{
         bool::{Bool, ConditionalSelect},
+        cmp::PartialEq,
         num::Zero,
+        ops::Neg,
         Has,
     },
     openzl_util::{into_array_unchecked, vec::Vec},
 };
 
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
 pub mod diffie_hellman;
 
+/// Algebra Error
+///
+/// This `enum` is the error state of the fallible constructors in this module, like
+/// [`Window::try_new`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Invalid Window Size
+    ///
+    /// Window sizes must be at least `1` and strictly less than the number of bits in a `usize`.
+    InvalidWindowSize(usize),
+
+    /// Invalid Window Table Length
+    ///
+    /// Window tables must have length `2^n` for some window size `n` greater than `0`.
+    InvalidTableLength(usize),
+}
+
 /// Group
 pub trait Group<COM = ()>: Sized {
     /// Adds `rhs` to `self` in the group.
@@ -75,6 +102,43 @@ pub trait ScalarMulGroup<S, COM = ()>: Group<COM> + ScalarMul<S, COM> {}
 
 impl<G, S, COM> ScalarMulGroup<S, COM> for G where G: Group<COM> + ScalarMul<S, COM> {}
 
+/// Multi-Base Scalar Multiplication
+///
+/// Linear combinations of several bases, like `a * G + b * H` in Schnorr or Pedersen verification,
+/// can share work across their terms. Interleaved windowed multiplication with a [`MultiWindow`]
+/// shares all doublings, so that a combination of `k` terms costs one set of doublings instead of
+/// `k`. The default implementation computes every term on its own.
+pub trait MultiScalarMul<S, COM = ()>: ScalarMulGroup<S, COM, Output = Self> {
+    /// Returns the sum of `base * scalar` over all `terms`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `terms` is empty.
+    #[inline]
+    fn multi_scalar_mul(terms: &[(&Self, &S)], compiler: &mut COM) -> Self {
+        let ((base, scalar), rest) = terms
+            .split_first()
+            .expect("Multi-base scalar multiplication needs at least one term.");
+        let mut result = base.scalar_mul(scalar, compiler);
+        for (base, scalar) in rest {
+            let product = base.scalar_mul(scalar, compiler);
+            result.add_assign(&product, compiler);
+        }
+        result
+    }
+}
+
+/// Group Endomorphism
+///
+/// An efficiently computable endomorphism `phi` of the group which acts as the scalar
+/// multiplication by a fixed eigenvalue `lambda`. Curves with such an endomorphism support GLV
+/// scalar multiplication, which splits a scalar `k` into `k_1 + lambda * k_2` with half-width
+/// `k_1` and `k_2` and computes `k_1 * P + k_2 * phi(P)` with half as many doublings.
+pub trait Endomorphism<COM = ()>: Group<COM> {
+    /// Applies the endomorphism to `self`.
+    fn endomorphism(&self, compiler: &mut COM) -> Self;
+}
+
 /// Fixed Base Scalar Multiplication using Precomputed Base Points
 pub trait FixedBaseScalarMul<S, COM = ()>: Group<COM> {
     /// Fixed Base Point
@@ -138,12 +202,28 @@ impl<G> Window<G> {
         Self { table }
     }
 
+    /// Creates a new [`Window`] from `table`, returning an error if the length of `table` is not
+    /// a power of two greater than `1`.
+    ///
+    /// # Crypto Safety
+    ///
+    /// This method only checks the shape of `table` and not that the entries are the correct
+    /// multiples of some base point.
+    #[inline]
+    pub fn try_from_table(table: Vec<G>) -> Result<Self, Error> {
+        if table.len() < 2 || !table.len().is_power_of_two() {
+            return Err(Error::InvalidTableLength(table.len()));
+        }
+        Ok(Self::new_unchecked(table))
+    }
+
     /// Creates a new [`Window`] table by repeatedly adding `point` to itself to
     /// support a table with windowed multiplication with `window_size`.
     ///
     /// # Panics
     ///
-    /// This method panics if `window_size` is less than `1`.
+    /// This method panics if `window_size` is less than `1`. See [`try_new`](Self::try_new) for a
+    /// fallible version of this method.
     ///
     /// # Implementation Note
     ///
@@ -157,7 +237,23 @@ impl<G> Window<G> {
     where
         G: Clone + Group<COM> + Zero<COM>,
     {
-        assert!(window_size > 0, "Window size must be at least 1.");
+        match Self::try_new(window_size, point, compiler) {
+            Ok(window) => window,
+            Err(err) => panic!("Unable to build window table: {err:?}"),
+        }
+    }
+
+    /// Creates a new [`Window`] table by repeatedly adding `point` to itself to
+    /// support a table with windowed multiplication with `window_size`, returning an error if
+    /// `window_size` is out of range. See [`new`](Self::new) for more.
+    #[inline]
+    pub fn try_new<COM>(window_size: usize, point: G, compiler: &mut COM) -> Result<Self, Error>
+    where
+        G: Clone + Group<COM> + Zero<COM>,
+    {
+        if window_size == 0 || window_size >= usize::BITS as usize {
+            return Err(Error::InvalidWindowSize(window_size));
+        }
         let table_length = 2usize.pow(window_size as u32);
         let mut table = Vec::with_capacity(table_length);
         table.push(G::zero(compiler));
@@ -165,7 +261,7 @@ impl<G> Window<G> {
         for _ in 2..table_length {
             table.push(table.last().unwrap().add(&point, compiler));
         }
-        Self::new_unchecked(table)
+        Ok(Self::new_unchecked(table))
     }
 
     /// Returns the window size.
@@ -234,12 +330,412 @@ impl<G> Window<G> {
     }
 }
 
+/// Windowed Scalar Multiplication
+///
+/// This `trait` abstracts over the precomputed tables for windowed scalar multiplication, like
+/// [`Window`] and [`Wnaf`], so that circuits can be written against the [`ScalarMulStrategy`] of
+/// their compiler instead of a fixed table.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub trait WindowedScalarMul<G, COM = ()>: Sized
+where
+    COM: Has<bool>,
+{
+    /// Builds a new table for multiplying `point` with windows of `window_size` bits, returning an
+    /// error if `window_size` is out of range.
+    fn try_new(window_size: usize, point: G, compiler: &mut COM) -> Result<Self, Error>;
+
+    /// Multiplies the base point of `self` by the scalar with big-endian `bits`.
+    fn scalar_mul<'b, B>(&self, bits: B, compiler: &mut COM) -> G
+    where
+        Bool<COM>: 'b,
+        B: IntoIterator<Item = &'b Bool<COM>>;
+}
+
+#[cfg(feature = "alloc")]
+impl<G, COM> WindowedScalarMul<G, COM> for Window<G>
+where
+    COM: Has<bool>,
+    G: Clone + ConditionalSelect<COM> + Group<COM> + Zero<COM>,
+{
+    #[inline]
+    fn try_new(window_size: usize, point: G, compiler: &mut COM) -> Result<Self, Error> {
+        Self::try_new(window_size, point, compiler)
+    }
+
+    #[inline]
+    fn scalar_mul<'b, B>(&self, bits: B, compiler: &mut COM) -> G
+    where
+        Bool<COM>: 'b,
+        B: IntoIterator<Item = &'b Bool<COM>>,
+    {
+        self.scalar_mul(bits, compiler)
+    }
+}
+
+/// Interleaved Multi-Base Window Tables
+///
+/// This `struct` holds one [`Window`] table per base point, all of the same window size, and
+/// multiplies every base by its own scalar in one interleaved pass (Strauss' method). Each round
+/// doubles the accumulator once per bit of the window and then adds one table entry per base, so
+/// the doublings are shared across all bases.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MultiWindow<G> {
+    /// Window Tables
+    windows: Vec<Window<G>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<G> MultiWindow<G> {
+    /// Creates a new [`MultiWindow`] with one [`Window`] table of `window_size` for each of the
+    /// `points`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `window_size` is less than `1`. See [`try_new`](Self::try_new) for a
+    /// fallible version of this method.
+    ///
+    /// # Implementation Note
+    ///
+    /// For `k` bases and scalars of `B` bits, creating the tables costs `k * (2^n - 2)` additions
+    /// and the multiplication costs `B` doublings, `k * B/n` table look-ups, and `k * B/n`
+    /// additions, where `n` is the window size. Multiplying every base on its own would cost
+    /// `k * B` doublings instead.
+    #[inline]
+    pub fn new<I, COM>(window_size: usize, points: I, compiler: &mut COM) -> Self
+    where
+        G: Clone + Group<COM> + Zero<COM>,
+        I: IntoIterator<Item = G>,
+    {
+        match Self::try_new(window_size, points, compiler) {
+            Ok(windows) => windows,
+            Err(err) => panic!("Unable to build window tables: {err:?}"),
+        }
+    }
+
+    /// Creates a new [`MultiWindow`] with one [`Window`] table of `window_size` for each of the
+    /// `points`, returning an error if `window_size` is out of range. See [`new`](Self::new) for
+    /// more.
+    #[inline]
+    pub fn try_new<I, COM>(window_size: usize, points: I, compiler: &mut COM) -> Result<Self, Error>
+    where
+        G: Clone + Group<COM> + Zero<COM>,
+        I: IntoIterator<Item = G>,
+    {
+        Ok(Self {
+            windows: points
+                .into_iter()
+                .map(|point| Window::try_new(window_size, point, compiler))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Returns the window size, or `None` if `self` has no tables.
+    #[inline]
+    pub fn window_size(&self) -> Option<usize> {
+        self.windows.first().map(Window::window_size)
+    }
+
+    /// Returns a shared reference to the window tables.
+    #[inline]
+    pub fn windows(&self) -> &[Window<G>] {
+        &self.windows
+    }
+
+    /// Returns the window tables, dropping `self`.
+    #[inline]
+    pub fn into_inner(self) -> Vec<Window<G>> {
+        self.windows
+    }
+
+    /// Doubles `result`, `window_size`-many times and then adds the element of every table
+    /// corresponding to its chunk in `chunks`.
+    #[inline]
+    fn scalar_mul_round<COM>(
+        &self,
+        window_size: usize,
+        chunks: &[&[&Bool<COM>]],
+        result: &mut G,
+        compiler: &mut COM,
+    ) where
+        G: Clone + ConditionalSelect<COM> + Group<COM>,
+        COM: Has<bool>,
+    {
+        result.repeated_double_assign(window_size, compiler);
+        for (window, chunk) in self.windows.iter().zip(chunks) {
+            let subtable = &window.table[0..1 << window_size];
+            let selected_element =
+                G::select_from_table(chunk.iter().copied().rev(), subtable, compiler);
+            result.add_assign(&selected_element, compiler);
+        }
+    }
+
+    /// Returns the sum of the base points multiplied by their `scalars`, in the same order as the
+    /// tables of `self`.
+    ///
+    /// # Implementation Note
+    ///
+    /// Like for [`Window::scalar_mul`], the bits of every scalar must be in the big-endian
+    /// representation.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of `scalars` is not the number of tables or if the scalars
+    /// have different numbers of bits.
+    #[inline]
+    pub fn scalar_mul<'b, I, B, COM>(&self, scalars: I, compiler: &mut COM) -> G
+    where
+        Bool<COM>: 'b,
+        I: IntoIterator<Item = B>,
+        B: IntoIterator<Item = &'b Bool<COM>>,
+        COM: Has<bool>,
+        G: Clone + ConditionalSelect<COM> + Group<COM> + Zero<COM>,
+    {
+        let bit_vectors = scalars
+            .into_iter()
+            .map(|bits| bits.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bit_vectors.len(),
+            self.windows.len(),
+            "There must be exactly one scalar for every base."
+        );
+        let mut result = G::zero(compiler);
+        let (window_size, bit_count) = match (self.window_size(), bit_vectors.first()) {
+            (Some(window_size), Some(bits)) => (window_size, bits.len()),
+            _ => return result,
+        };
+        assert!(
+            bit_vectors.iter().all(|bits| bits.len() == bit_count),
+            "All scalars must have the same number of bits."
+        );
+        let mut offset = 0;
+        while offset < bit_count {
+            let round_size = window_size.min(bit_count - offset);
+            let chunks = bit_vectors
+                .iter()
+                .map(|bits| &bits[offset..offset + round_size])
+                .collect::<Vec<_>>();
+            self.scalar_mul_round(round_size, &chunks, &mut result, compiler);
+            offset += round_size;
+        }
+        result
+    }
+}
+
+/// Odd Multiple Table for Signed-Digit Windowed Point Multiplication
+///
+/// The table holds the odd multiples `P, 3P, ..., (2^n - 1)P` of a point `P` for a window size
+/// `n` and multiplies with signed odd digits in `{-(2^n - 1), ..., -1, 1, ..., 2^n - 1}`. This is
+/// the regular form of the width-`n` non-adjacent form (wNAF): in-circuit every window costs the
+/// same, so instead of skipping zero digits the recoding trades half of the table for a negation
+/// per window.
+///
+/// # Recoding
+///
+/// For the big-endian bits `b_{m-1}, ..., b_0` of a scalar `k`, the odd scalar `k | 1` is equal to
+/// the sum of `(2 * c_i - 1) * 2^i` for `0 <= i < m` where `c_i = b_{i + 1}` and `c_{m-1} = 1`.
+/// Every window of these signed bits is a signed odd digit whose sign is its top bit and whose
+/// table index is the remaining bits, complemented for negative digits. The product with `k` is
+/// recovered by subtracting `P` when `b_0` is zero, so the recoding needs no arithmetic on the
+/// scalar.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Wnaf<G> {
+    /// Odd Multiple Table
+    table: Vec<G>,
+}
+
+#[cfg(feature = "alloc")]
+impl<G> Wnaf<G> {
+    /// Creates a new [`Wnaf`] from `table` without checking its correctness.
+    #[inline]
+    pub fn new_unchecked(table: Vec<G>) -> Self {
+        Self { table }
+    }
+
+    /// Creates a new [`Wnaf`] table of the odd multiples of `point` for signed-digit
+    /// multiplication with `window_size`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `window_size` is less than `1`. See [`try_new`](Self::try_new) for a
+    /// fallible version of this method.
+    ///
+    /// # Implementation Note
+    ///
+    /// Creating the table costs `2^(n - 1)` additions in the group, and each round involves `1`
+    /// table look-up in a table of half the size of the one in [`Window`], `n - 1` bit equality
+    /// checks, `1` negation, `n` doublings and `1` addition. One more addition corrects for even
+    /// scalars at the end.
+    #[inline]
+    pub fn new<COM>(window_size: usize, point: G, compiler: &mut COM) -> Self
+    where
+        G: Group<COM>,
+    {
+        match Self::try_new(window_size, point, compiler) {
+            Ok(wnaf) => wnaf,
+            Err(err) => panic!("Unable to build wNAF table: {err:?}"),
+        }
+    }
+
+    /// Creates a new [`Wnaf`] table of the odd multiples of `point` for signed-digit
+    /// multiplication with `window_size`, returning an error if `window_size` is out of range. See
+    /// [`new`](Self::new) for more.
+    #[inline]
+    pub fn try_new<COM>(window_size: usize, point: G, compiler: &mut COM) -> Result<Self, Error>
+    where
+        G: Group<COM>,
+    {
+        if window_size == 0 || window_size >= usize::BITS as usize {
+            return Err(Error::InvalidWindowSize(window_size));
+        }
+        let table_length = 1usize << (window_size - 1);
+        let double = point.add(&point, compiler);
+        let mut table = Vec::with_capacity(table_length);
+        table.push(point);
+        for _ in 1..table_length {
+            table.push(table.last().unwrap().add(&double, compiler));
+        }
+        Ok(Self::new_unchecked(table))
+    }
+
+    /// Returns the window size.
+    #[inline]
+    pub fn window_size(&self) -> usize {
+        self.table.len().trailing_zeros() as usize + 1
+    }
+
+    /// Returns a shared reference to the odd multiple table.
+    #[inline]
+    pub fn table(&self) -> &[G] {
+        &self.table
+    }
+
+    /// Returns the odd multiple table, dropping `self`.
+    #[inline]
+    pub fn into_inner(self) -> Vec<G> {
+        self.table
+    }
+
+    /// Doubles `result`, `index.len() + 1`-many times and then adds the odd multiple from `self`
+    /// corresponding to `index`, negated whenever `sign` is `false`. A missing `sign` stands for
+    /// the implicit top bit `1` of the most significant window.
+    #[inline]
+    fn scalar_mul_round<COM>(
+        &self,
+        sign: Option<&Bool<COM>>,
+        index: &[&Bool<COM>],
+        result: &mut G,
+        compiler: &mut COM,
+    ) where
+        COM: Has<bool>,
+        Bool<COM>: PartialEq<Bool<COM>, COM>,
+        G: Clone + ConditionalSelect<COM> + Group<COM> + Neg<COM, Output = G>,
+    {
+        let subtable = &self.table[0..1 << index.len()];
+        result.repeated_double_assign(index.len() + 1, compiler);
+        let selected_element = match sign {
+            Some(sign) => {
+                let index = index
+                    .iter()
+                    .map(|bit| bit.eq(sign, compiler))
+                    .collect::<Vec<_>>();
+                let selected_element = G::select_from_table(&index, subtable, compiler);
+                let negated_element = selected_element.clone().neg(compiler);
+                G::select(sign, &selected_element, &negated_element, compiler)
+            }
+            _ => G::select_from_table(index.iter().copied(), subtable, compiler),
+        };
+        result.add_assign(&selected_element, compiler);
+    }
+
+    /// Multiplies a point in G by `scalar` using `self` as the odd multiple table.
+    ///
+    /// # Implementation Note
+    ///
+    /// Like for [`Window::scalar_mul`], `bits` must be in the big-endian representation.
+    #[inline]
+    pub fn scalar_mul<'b, B, COM>(&self, bits: B, compiler: &mut COM) -> G
+    where
+        Bool<COM>: 'b + PartialEq<Bool<COM>, COM>,
+        B: IntoIterator<Item = &'b Bool<COM>>,
+        COM: Has<bool>,
+        G: Clone + ConditionalSelect<COM> + Group<COM> + Neg<COM, Output = G> + Zero<COM>,
+    {
+        let mut result = G::zero(compiler);
+        let bit_vector = bits.into_iter().collect::<Vec<_>>();
+        let (least_significant_bit, signed_bits) = match bit_vector.split_last() {
+            Some(split) => split,
+            _ => return result,
+        };
+        let window_size = self.window_size();
+        let (first_index, chunks) = signed_bits.split_at(signed_bits.len() % window_size);
+        self.scalar_mul_round(None, first_index, &mut result, compiler);
+        for chunk in chunks.chunks_exact(window_size) {
+            let (sign, index) = chunk.split_first().expect("Chunks are never empty.");
+            self.scalar_mul_round(Some(sign), index, &mut result, compiler);
+        }
+        let corrected_result = result.add(&self.table[0].clone().neg(compiler), compiler);
+        G::select(least_significant_bit, &result, &corrected_result, compiler)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G, COM> WindowedScalarMul<G, COM> for Wnaf<G>
+where
+    COM: Has<bool>,
+    Bool<COM>: PartialEq<Bool<COM>, COM>,
+    G: Clone + ConditionalSelect<COM> + Group<COM> + Neg<COM, Output = G> + Zero<COM>,
+{
+    #[inline]
+    fn try_new(window_size: usize, point: G, compiler: &mut COM) -> Result<Self, Error> {
+        Self::try_new(window_size, point, compiler)
+    }
+
+    #[inline]
+    fn scalar_mul<'b, B>(&self, bits: B, compiler: &mut COM) -> G
+    where
+        Bool<COM>: 'b,
+        B: IntoIterator<Item = &'b Bool<COM>>,
+    {
+        self.scalar_mul(bits, compiler)
+    }
+}
+
+/// Scalar Multiplication Strategy
+///
+/// Compilers choose the [`WindowedScalarMul`] table which best fits their cost model. Lookups into
+/// precomputed tables dominate the cost of windowed multiplication whenever additions are cheap,
+/// so those compilers prefer the half-size tables of [`Wnaf`], while native computation prefers
+/// the plain [`Window`] which needs no negations or bit equality checks.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub trait ScalarMulStrategy<G>: Has<bool> + Sized {
+    /// Windowed Scalar Multiplication Table
+    type Strategy: WindowedScalarMul<G, Self>;
+}
+
+#[cfg(feature = "alloc")]
+impl<G> ScalarMulStrategy<G> for ()
+where
+    G: Clone + ConditionalSelect + Group + Zero,
+{
+    type Strategy = Window<G>;
+}
+
 /// Security Assumptions
 ///
 /// The following outlines some standard security assumptions for cryptographic protocols built on
 /// types that implement [`ScalarMul`] for some set of scalars. These security properties can be
 /// attached to instances of [`ScalarMul`] which we assume to have these hardness properties.
 pub mod security {
+    #[doc(inline)]
+    pub use crate::security::SecurityLevel;
+
     /// Discrete Logarithm Hardness Assumption
     ///
     /// For a type `G`, it should be infeasible to find a procedure `f` that makes this function
@@ -330,4 +826,64 @@ pub mod test {
             .scalar_mul(&Vec::from_iter(bit_conversion(scalar, compiler)), compiler);
         product.assert_equal(&windowed_product, compiler);
     }
+
+    /// Tests if interleaved windowed multiplication of the bit decompositions of `scalars` with
+    /// `points` returns the sum of the products `scalars[i]` * `points[i]`, and that
+    /// [`MultiScalarMul`] agrees with it.
+    #[inline]
+    pub fn multi_window_correctness<S, G, F, B, COM>(
+        window_size: usize,
+        scalars: &[S],
+        points: &[G],
+        mut bit_conversion: F,
+        compiler: &mut COM,
+    ) where
+        G: Clone + ConditionalSelect<COM> + MultiScalarMul<S, COM> + PartialEq<G, COM> + Zero<COM>,
+        F: FnMut(&S, &mut COM) -> B,
+        B: IntoIterator<Item = Bool<COM>>,
+        COM: Assert,
+    {
+        let mut sum = G::zero(compiler);
+        for (point, scalar) in points.iter().zip(scalars) {
+            let product = point.scalar_mul(scalar, compiler);
+            sum.add_assign(&product, compiler);
+        }
+        let bit_vectors = scalars
+            .iter()
+            .map(|scalar| Vec::from_iter(bit_conversion(scalar, compiler)))
+            .collect::<Vec<_>>();
+        let interleaved_sum = MultiWindow::new(window_size, points.iter().cloned(), compiler)
+            .scalar_mul(&bit_vectors, compiler);
+        sum.assert_equal(&interleaved_sum, compiler);
+        let terms = points.iter().zip(scalars).collect::<Vec<_>>();
+        let multi_sum = G::multi_scalar_mul(&terms, compiler);
+        sum.assert_equal(&multi_sum, compiler);
+    }
+
+    /// Tests if signed-digit windowed scalar multiplication of the bit decomposition of `scalar`
+    /// with `point` returns the product `scalar` * `point`
+    #[inline]
+    pub fn wnaf_correctness<S, G, F, B, COM>(
+        window_size: usize,
+        scalar: &S,
+        point: G,
+        bit_conversion: F,
+        compiler: &mut COM,
+    ) where
+        G: Clone
+            + ConditionalSelect<COM>
+            + Neg<COM, Output = G>
+            + PartialEq<G, COM>
+            + ScalarMulGroup<S, COM, Output = G>
+            + Zero<COM>,
+        F: FnOnce(&S, &mut COM) -> B,
+        B: IntoIterator<Item = Bool<COM>>,
+        Bool<COM>: PartialEq<Bool<COM>, COM>,
+        COM: Assert,
+    {
+        let product = point.scalar_mul(scalar, compiler);
+        let wnaf_product = Wnaf::new(window_size, point, compiler)
+            .scalar_mul(&Vec::from_iter(bit_conversion(scalar, compiler)), compiler);
+        product.assert_equal(&wnaf_product, compiler);
+    }
 }
@@ -0,0 +1,3 @@
+//! Commitment Schemes
+
+pub mod timed;
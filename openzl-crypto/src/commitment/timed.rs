@@ -0,0 +1,313 @@
+//! Timed Commitments
+//!
+//! Commit-reveal protocols, like sealed-bid auctions, have every participant publish a commitment
+//! to their value first and reveal the value only once all commitments are in. The commitment to
+//! an [`Opening`] is `H(value, trapdoor, epoch)`, which binds the value to the `epoch` at which it
+//! may be revealed. Revealing proves inside of a circuit that the [`Reveal`] statement, made of
+//! the commitment, the value, and the epoch, is consistent with a secret trapdoor, and
+//! [`Parameters::assert_timed_reveal`] additionally checks the epoch against the current epoch,
+//! which the verifier supplies as a public input.
+//!
+//! Commitments which should be revealable at any time use the zero epoch, see
+//! [`Parameters::commit_untimed`].
+
+use crate::{
+    constraint::{HasInput, Input},
+    hash::ArrayHashFunction,
+};
+use eclair::{
+    alloc::{mode::Public, Allocate, Allocator, Constant, Variable},
+    bool::{Assert, AssertEq, Bool},
+    cmp::PartialOrd,
+    num::Zero,
+    ops::Not,
+};
+use openzl_util::{
+    codec::{Encode, Write},
+    rand::{Rand, RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Commitment Opening
+///
+/// The `trapdoor` is sampled uniformly at random for every commitment so that the commitment
+/// hides the `value`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Opening<F> {
+    /// Committed Value
+    pub value: F,
+
+    /// Commitment Trapdoor
+    pub trapdoor: F,
+}
+
+impl<F> Opening<F> {
+    /// Builds a new [`Opening`] from `value` and `trapdoor`.
+    #[inline]
+    pub fn new(value: F, trapdoor: F) -> Self {
+        Self { value, trapdoor }
+    }
+}
+
+impl<F, D> Sample<D> for Opening<F>
+where
+    F: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution.clone()), rng.sample(distribution))
+    }
+}
+
+impl<F, M, COM> Variable<M, COM> for Opening<F>
+where
+    F: Variable<M, COM>,
+{
+    type Type = Opening<F::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.value.as_known(compiler),
+            this.trapdoor.as_known(compiler),
+        )
+    }
+}
+
+/// Reveal Statement
+///
+/// The public part of revealing a commitment: the commitment itself, the revealed value, and the
+/// epoch which the commitment is bound to.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Reveal<F> {
+    /// Commitment
+    pub commitment: F,
+
+    /// Revealed Value
+    pub value: F,
+
+    /// Epoch
+    pub epoch: F,
+}
+
+impl<F> Reveal<F> {
+    /// Builds a new [`Reveal`] statement from `commitment`, `value`, and `epoch`.
+    #[inline]
+    pub fn new(commitment: F, value: F, epoch: F) -> Self {
+        Self {
+            commitment,
+            value,
+            epoch,
+        }
+    }
+}
+
+impl<F, COM> Variable<Public, COM> for Reveal<F>
+where
+    F: Variable<Public, COM>,
+{
+    type Type = Reveal<F::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown::<Public, _>(),
+            compiler.allocate_unknown::<Public, _>(),
+            compiler.allocate_unknown::<Public, _>(),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.commitment.as_known::<Public, _>(compiler),
+            this.value.as_known::<Public, _>(compiler),
+            this.epoch.as_known::<Public, _>(compiler),
+        )
+    }
+}
+
+impl<F> Encode for Reveal<F>
+where
+    F: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.commitment.encode(&mut writer)?;
+        self.value.encode(&mut writer)?;
+        self.epoch.encode(&mut writer)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.commitment.encoded_len() + self.value.encoded_len() + self.epoch.encoded_len()
+    }
+}
+
+impl<F, P> Input<P> for Reveal<F>
+where
+    P: HasInput<F> + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut P::Input) {
+        P::extend(input, &self.commitment);
+        P::extend(input, &self.value);
+        P::extend(input, &self.epoch);
+    }
+}
+
+/// Timed Commitment Parameters
+///
+/// The commitment to an opening at `epoch` is `H(value, trapdoor, epoch)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Parameters<H> {
+    /// Commitment Hash Function
+    pub hasher: H,
+}
+
+impl<H> Parameters<H> {
+    /// Builds a new [`Parameters`] from `hasher`.
+    #[inline]
+    pub fn new(hasher: H) -> Self {
+        Self { hasher }
+    }
+
+    /// Computes the commitment to `opening` bound to `epoch`.
+    #[inline]
+    pub fn commit<F, COM>(&self, opening: &Opening<F>, epoch: &F, compiler: &mut COM) -> F
+    where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+    {
+        self.hasher
+            .hash([&opening.value, &opening.trapdoor, epoch], compiler)
+    }
+
+    /// Computes the commitment to `opening` bound to the zero epoch, which can be revealed at any
+    /// time.
+    #[inline]
+    pub fn commit_untimed<F, COM>(&self, opening: &Opening<F>, compiler: &mut COM) -> F
+    where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+        F: Zero<COM>,
+    {
+        let epoch = F::zero(compiler);
+        self.commit(opening, &epoch, compiler)
+    }
+
+    /// Builds the [`Reveal`] statement for `opening` bound to `epoch`.
+    #[inline]
+    pub fn reveal<F, COM>(&self, opening: &Opening<F>, epoch: F, compiler: &mut COM) -> Reveal<F>
+    where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+        F: Clone,
+    {
+        Reveal::new(
+            self.commit(opening, &epoch, compiler),
+            opening.value.clone(),
+            epoch,
+        )
+    }
+
+    /// Returns `true` if `trapdoor` opens the commitment of `reveal` to its value and epoch.
+    #[inline]
+    pub fn verify<F>(&self, reveal: &Reveal<F>, trapdoor: &F) -> bool
+    where
+        H: ArrayHashFunction<3, Input = F, Output = F>,
+        F: Clone + PartialEq,
+    {
+        let opening = Opening::new(reveal.value.clone(), trapdoor.clone());
+        self.commit(&opening, &reveal.epoch, &mut ()) == reveal.commitment
+    }
+
+    /// Asserts that `trapdoor` opens the commitment of `reveal` to its value and epoch.
+    #[inline]
+    pub fn assert_reveal<F, COM>(&self, reveal: &Reveal<F>, trapdoor: &F, compiler: &mut COM)
+    where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+        F: eclair::cmp::PartialEq<F, COM>,
+        COM: Assert,
+    {
+        let commitment = self
+            .hasher
+            .hash([&reveal.value, trapdoor, &reveal.epoch], compiler);
+        compiler.assert_eq(&commitment, &reveal.commitment);
+    }
+
+    /// Asserts that `trapdoor` opens the commitment of `reveal` and that `current_epoch` is not
+    /// before the epoch of `reveal`.
+    ///
+    /// The ordering of `F` in the compiler may restrict the range of epochs. For example, epochs
+    /// over prime fields are usually required to be at most half of the modulus.
+    #[inline]
+    pub fn assert_timed_reveal<F, COM>(
+        &self,
+        reveal: &Reveal<F>,
+        trapdoor: &F,
+        current_epoch: &F,
+        compiler: &mut COM,
+    ) where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+        F: PartialOrd<F, COM>,
+        Bool<COM>: Not<COM, Output = Bool<COM>>,
+        COM: Assert,
+    {
+        self.assert_reveal(reveal, trapdoor, compiler);
+        let is_revealable = current_epoch.ge(&reveal.epoch, compiler);
+        compiler.assert(&is_revealable);
+    }
+}
+
+impl<H, COM> Constant<COM> for Parameters<H>
+where
+    H: Constant<COM>,
+{
+    type Type = Parameters<H::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.hasher.as_constant(compiler))
+    }
+}
+
+impl<H, D> Sample<D> for Parameters<H>
+where
+    H: Sample<D>,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution))
+    }
+}
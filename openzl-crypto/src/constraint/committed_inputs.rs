@@ -0,0 +1,62 @@
+//! Committed Public Inputs
+//!
+//! Verifying a proof costs time, or gas on-chain, for every element of its public input. Circuits
+//! with many public inputs can instead allocate them as secret variables and expose only their
+//! digest as the public input. The digest chains an arity-two hash function, like Poseidon, over
+//! the inputs starting from zero:
+//!
+//! ```text
+//! digest(x_1, ..., x_n) = H(...H(H(0, x_1), x_2)..., x_n)
+//! ```
+//!
+//! Since [`digest`] is generic over the compiler, verifiers compute the same digest natively with
+//! [`extend_input`] that circuits constrain with [`assert_committed`]. The number of inputs is
+//! fixed by the circuit, so the digest does not commit to it, and layouts whose inputs vary in
+//! length must include the length as one of the inputs.
+
+use crate::{constraint::HasInput, hash::ArrayHashFunction};
+use eclair::{bool::AssertEq, cmp::PartialEq, num::Zero};
+
+/// Computes the digest of `inputs` with `hasher` using `compiler`.
+///
+/// The digest of an empty sequence of inputs is zero.
+#[inline]
+pub fn digest<'i, F, H, I, COM>(hasher: &H, inputs: I, compiler: &mut COM) -> F
+where
+    F: 'i + Zero<COM>,
+    H: ArrayHashFunction<2, COM, Input = F, Output = F>,
+    I: IntoIterator<Item = &'i F>,
+{
+    inputs
+        .into_iter()
+        .fold(Zero::zero(compiler), |accumulator, input| {
+            hasher.hash([&accumulator, input], compiler)
+        })
+}
+
+/// Asserts that the digest of `inputs` under `hasher` is equal to `committed`, which should be
+/// allocated as the public input of the circuit.
+#[inline]
+pub fn assert_committed<'i, F, H, I, COM>(hasher: &H, inputs: I, committed: &F, compiler: &mut COM)
+where
+    COM: AssertEq,
+    F: 'i + PartialEq<F, COM> + Zero<COM>,
+    H: ArrayHashFunction<2, COM, Input = F, Output = F>,
+    I: IntoIterator<Item = &'i F>,
+{
+    let digest = digest(hasher, inputs, compiler);
+    compiler.assert_eq(&digest, committed);
+}
+
+/// Extends the `input` of the proof system `P` with the digest of the native `inputs` under
+/// `hasher`, which replaces the inputs themselves.
+#[inline]
+pub fn extend_input<'i, P, F, H, I>(input: &mut P::Input, hasher: &H, inputs: I)
+where
+    P: HasInput<F> + ?Sized,
+    F: 'i + Zero,
+    H: ArrayHashFunction<2, Input = F, Output = F>,
+    I: IntoIterator<Item = &'i F>,
+{
+    P::extend(input, &digest(hasher, inputs, &mut ()));
+}
@@ -0,0 +1,240 @@
+//! Golden Constraint Counts
+//!
+//! Gadget implementations can silently become more expensive when their dependencies change. The
+//! [`Golden`] measurements in this module record the expected [`Size`] of named circuits and fail
+//! whenever a fresh measurement drifts from them by more than a [`Tolerance`], so downstream CI
+//! can pin the cost of the circuits it relies on with [`assert_golden`].
+
+use crate::constraint::measure::{Measure, Size};
+use core::fmt;
+
+/// Drift Tolerance
+///
+/// Measurements are compared field by field and each of them may drift by at most `absolute`
+/// plus `percent` percent of the expected value, in either direction.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Tolerance {
+    /// Absolute Tolerance
+    pub absolute: usize,
+
+    /// Relative Tolerance in Percent
+    pub percent: usize,
+}
+
+impl Tolerance {
+    /// Exact Tolerance
+    pub const EXACT: Self = Self::new(0, 0);
+
+    /// Builds a new [`Tolerance`] from `absolute` and `percent`.
+    #[inline]
+    pub const fn new(absolute: usize, percent: usize) -> Self {
+        Self { absolute, percent }
+    }
+
+    /// Builds a new relative [`Tolerance`] of `percent` percent.
+    #[inline]
+    pub const fn percent(percent: usize) -> Self {
+        Self::new(0, percent)
+    }
+
+    /// Returns the largest allowed distance from `expected`.
+    #[inline]
+    pub const fn slack(&self, expected: usize) -> usize {
+        self.absolute + expected * self.percent / 100
+    }
+
+    /// Returns `true` if `actual` is within `self` of `expected`.
+    #[inline]
+    pub const fn accepts(&self, expected: usize, actual: usize) -> bool {
+        expected.abs_diff(actual) <= self.slack(expected)
+    }
+}
+
+/// Measured Quantity
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Quantity {
+    /// Number of Constraints
+    Constraints,
+
+    /// Number of Constants
+    Constants,
+
+    /// Number of Public Variables
+    PublicVariables,
+
+    /// Number of Secret Variables
+    SecretVariables,
+}
+
+/// Drift from a [`Golden`] Measurement
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Drift {
+    /// Circuit Name
+    pub name: &'static str,
+
+    /// Drifting Quantity
+    pub quantity: Quantity,
+
+    /// Expected Value
+    pub expected: usize,
+
+    /// Actual Value
+    ///
+    /// This is `None` whenever the compiler does not count the [`quantity`](Self::quantity) even
+    /// though the [`Golden`] measurement records it.
+    pub actual: Option<usize>,
+}
+
+impl fmt::Display for Drift {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(
+                f,
+                "{}: expected {} {:?} but measured {}",
+                self.name, self.expected, self.quantity, actual
+            ),
+            _ => write!(
+                f,
+                "{}: expected {} {:?} but the compiler does not count them",
+                self.name, self.expected, self.quantity
+            ),
+        }
+    }
+}
+
+/// Golden Measurement
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Golden {
+    /// Circuit Name
+    pub name: &'static str,
+
+    /// Expected Size
+    ///
+    /// Variable counts which are `None` are not checked.
+    pub size: Size,
+
+    /// Drift Tolerance
+    pub tolerance: Tolerance,
+}
+
+impl Golden {
+    /// Builds a new [`Golden`] measurement for the circuit `name` with the expected
+    /// `constraint_count` and no variable counts, which only accepts exact matches.
+    #[inline]
+    pub const fn new(name: &'static str, constraint_count: usize) -> Self {
+        Self {
+            name,
+            size: Size {
+                constraint_count,
+                constant_count: None,
+                public_variable_count: None,
+                secret_variable_count: None,
+            },
+            tolerance: Tolerance::EXACT,
+        }
+    }
+
+    /// Sets the expected number of public variables to `count`.
+    #[inline]
+    pub const fn with_public_variables(mut self, count: usize) -> Self {
+        self.size.public_variable_count = Some(count);
+        self
+    }
+
+    /// Sets the expected number of secret variables to `count`.
+    #[inline]
+    pub const fn with_secret_variables(mut self, count: usize) -> Self {
+        self.size.secret_variable_count = Some(count);
+        self
+    }
+
+    /// Sets the drift tolerance to `tolerance`.
+    #[inline]
+    pub const fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Checks `expected` against `actual` for `quantity`.
+    #[inline]
+    fn check_quantity(
+        &self,
+        quantity: Quantity,
+        expected: Option<usize>,
+        actual: Option<usize>,
+    ) -> Result<(), Drift> {
+        match (expected, actual) {
+            (Some(expected), Some(actual)) if self.tolerance.accepts(expected, actual) => Ok(()),
+            (Some(expected), actual) => Err(Drift {
+                name: self.name,
+                quantity,
+                expected,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `actual` is within the tolerance of `self`, returning the first [`Drift`]
+    /// otherwise.
+    #[inline]
+    pub fn check(&self, actual: Size) -> Result<(), Drift> {
+        self.check_quantity(
+            Quantity::Constraints,
+            Some(self.size.constraint_count),
+            Some(actual.constraint_count),
+        )?;
+        self.check_quantity(
+            Quantity::Constants,
+            self.size.constant_count,
+            actual.constant_count,
+        )?;
+        self.check_quantity(
+            Quantity::PublicVariables,
+            self.size.public_variable_count,
+            actual.public_variable_count,
+        )?;
+        self.check_quantity(
+            Quantity::SecretVariables,
+            self.size.secret_variable_count,
+            actual.secret_variable_count,
+        )
+    }
+
+    /// Measures the constraints and variables that `f` adds to `compiler` and checks them against
+    /// `self`, returning the output of `f` and the result of the check.
+    #[inline]
+    pub fn measure<T, F, COM>(&self, f: F, compiler: &mut COM) -> (T, Result<(), Drift>)
+    where
+        F: FnOnce(&mut COM) -> T,
+        COM: Measure,
+    {
+        let before = compiler.measure();
+        let value = f(compiler);
+        let size = compiler
+            .measure()
+            .checked_sub(before)
+            .expect("Measurements should increase when adding more constraints.");
+        (value, self.check(size))
+    }
+}
+
+/// Runs `f` in `compiler` and asserts that it adds as many constraints and variables as `golden`
+/// records, returning the output of `f`.
+///
+/// # Panics
+///
+/// This function panics if the measurement of `f` drifts beyond the tolerance of `golden`.
+#[inline]
+pub fn assert_golden<T, F, COM>(golden: &Golden, f: F, compiler: &mut COM) -> T
+where
+    F: FnOnce(&mut COM) -> T,
+    COM: Measure,
+{
+    let (value, result) = golden.measure(f, compiler);
+    if let Err(drift) = result {
+        panic!("Golden measurement drifted beyond its tolerance. {drift}");
+    }
+    value
+}
@@ -0,0 +1,859 @@
+//! Constraint Systems and Proof Systems
+
+// FIXME: Leverage the type system to constrain allocation to only unknown modes for verifier
+//        generation and only known modes for proof generation, instead of relying on the `for_*`
+//        methods to "do the right thing".
+// TODO:  Find ways to enforce public input structure, since it's very easy to extend the input
+//        vector by the wrong amount or in the wrong order.
+
+use openzl_util::rand::{CryptoRng, RngCore};
+
+pub mod committed_inputs;
+
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod golden;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod packing;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod registry;
+
+/// Constraint System Satisfaction
+pub trait Satisfied {
+    /// Returns `true` if all the constraints in `self` are satisfied.
+    fn is_satisfied(&self) -> bool;
+}
+
+impl Satisfied for () {
+    /// Returns `true` because the native compiler is always satisfied at runtime.
+    ///
+    /// Assertions in the native compiler are runtime `panic!`s so there is no state that can be
+    /// accessed at runtime where the native compiler does not have all its constraints satisfied.
+    /// It is the fact that the runtime has not already `panic!`-ed that indicates that no
+    /// constraint violations have occured yet.
+    #[inline]
+    fn is_satisfied(&self) -> bool {
+        true
+    }
+}
+
+/// Proof System
+pub trait ProofSystem {
+    /// Context Compiler
+    type Compiler;
+
+    /// Public Parameters Type
+    type PublicParameters;
+
+    /// Proving Context Type
+    type ProvingContext;
+
+    /// Verifying Context Type
+    type VerifyingContext;
+
+    /// Verification Input Type
+    type Input: Default;
+
+    /// Proof Type
+    type Proof;
+
+    /// Error Type
+    type Error;
+
+    /// Returns a compiler which is setup to build proving and verifying contexts.
+    #[must_use]
+    fn context_compiler() -> Self::Compiler;
+
+    /// Returns a compiler which is setup to build a proof.
+    #[must_use]
+    fn proof_compiler() -> Self::Compiler;
+
+    /// Returns proving and verifying contexts for the constraints contained in `compiler` using
+    /// `public_parameters`.
+    fn compile<R>(
+        public_parameters: &Self::PublicParameters,
+        compiler: Self::Compiler,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingContext, Self::VerifyingContext), Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized;
+
+    /// Returns a proof that the constraint system encoded in `compiler` is consistent with the
+    /// proving `context`.
+    fn prove<R>(
+        context: &Self::ProvingContext,
+        compiler: Self::Compiler,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized;
+
+    /// Verifies that a proof generated from this proof system is valid.
+    fn verify(
+        context: &Self::VerifyingContext,
+        input: &Self::Input,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error>;
+}
+
+/// Proof System Input
+pub trait Input<P>
+where
+    P: ProofSystem + ?Sized,
+{
+    /// Extends the `input` buffer with data from `self`.
+    fn extend(&self, input: &mut P::Input);
+}
+
+/// Proof System Input Introspection
+///
+/// This `trait` is automatically implemented for all [`T: Input<Self>`](Input) and cannot be
+/// implemented manually.
+pub trait HasInput<T>: ProofSystem
+where
+    T: ?Sized,
+{
+    /// Extends the `input` buffer with data from `value`.
+    fn extend(input: &mut Self::Input, value: &T);
+}
+
+impl<P, T> HasInput<T> for P
+where
+    P: ProofSystem + ?Sized,
+    T: Input<P> + ?Sized,
+{
+    #[inline]
+    fn extend(input: &mut Self::Input, value: &T) {
+        value.extend(input)
+    }
+}
+
+impl<P> Input<P> for ()
+where
+    P: ProofSystem + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut P::Input) {
+        let _ = input;
+    }
+}
+
+/// Proof Rerandomization
+///
+/// Proof systems which implement this `trait` can turn a valid proof into a fresh proof of the
+/// same statement without knowing the witness. The rerandomized proof must be distributed like a
+/// proof that was generated from scratch.
+pub trait Rerandomize: ProofSystem {
+    /// Rerandomizes `proof` for the statement verified by `context`.
+    fn rerandomize<R>(
+        context: &Self::VerifyingContext,
+        proof: &Self::Proof,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized;
+}
+
+/// Constraint System Measurement
+pub mod measure {
+    use core::{
+        fmt::Debug,
+        hash::Hash,
+        ops::{Add, AddAssign},
+    };
+    use eclair::alloc::mode::{Constant, Public, Secret};
+
+    #[cfg(feature = "alloc")]
+    use {
+        alloc::{fmt::Display, format, string::String, vec::Vec},
+        core::ops::{Deref, DerefMut},
+    };
+
+    #[cfg(feature = "serde")]
+    use openzl_util::serde::{Deserialize, Serialize};
+
+    /// Variable Counting
+    pub trait Count<M> {
+        /// Returns the number of variables of the given mode `M` stored in `self`.
+        #[inline]
+        fn count(&self) -> Option<usize> {
+            None
+        }
+    }
+
+    impl<M> Count<M> for () {}
+
+    /// Constraint System Measurement
+    pub trait Measure: Count<Constant> + Count<Public> + Count<Secret> {
+        /// Returns the number of constraints stored in `self`.
+        fn constraint_count(&self) -> usize;
+
+        /// Returns a [`Size`] with the number of constraints and variables of each kind.
+        #[inline]
+        fn measure(&self) -> Size {
+            Size {
+                constraint_count: self.constraint_count(),
+                constant_count: Count::<Constant>::count(self),
+                public_variable_count: Count::<Public>::count(self),
+                secret_variable_count: Count::<Secret>::count(self),
+            }
+        }
+
+        /// Performs a measurement after running `f` on `self`, adding the result to `measurement`.
+        #[inline]
+        fn after<T, F>(&mut self, measurement: &mut Size, f: F) -> T
+        where
+            F: FnOnce(&mut Self) -> T,
+        {
+            let value = f(self);
+            *measurement += self.measure();
+            value
+        }
+
+        /// Performs a measurement after running `f` on `self`, ignoring the resulting value,
+        /// returning the measurement only.
+        #[inline]
+        fn after_ignore<T, F>(&mut self, f: F) -> Size
+        where
+            F: FnOnce(&mut Self) -> T,
+        {
+            let mut measurement = Default::default();
+            self.after(&mut measurement, f);
+            measurement
+        }
+    }
+
+    impl Measure for () {
+        #[inline]
+        fn constraint_count(&self) -> usize {
+            0
+        }
+    }
+
+    /// Constraint System Size Measurement
+    #[cfg_attr(
+        feature = "serde",
+        derive(Deserialize, Serialize),
+        serde(crate = "openzl_util::serde", deny_unknown_fields)
+    )]
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    #[must_use]
+    pub struct Size {
+        /// Number of Constraints
+        pub constraint_count: usize,
+
+        /// Number of Constants
+        pub constant_count: Option<usize>,
+
+        /// Number of Public Variables
+        pub public_variable_count: Option<usize>,
+
+        /// Number of Secret Variables
+        pub secret_variable_count: Option<usize>,
+    }
+
+    impl Size {
+        /// Computes the difference between `self` and `rhs`. If any of the measurements in `rhs`
+        /// are greater than those in `self`, this method returns `None`.
+        #[inline]
+        pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+            Some(Self {
+                constraint_count: self.constraint_count.checked_sub(rhs.constraint_count)?,
+                constant_count: match (self.constant_count, rhs.constant_count) {
+                    (Some(lhs), Some(rhs)) => Some(lhs.checked_sub(rhs)?),
+                    (Some(lhs), None) => Some(lhs),
+                    _ => None,
+                },
+                public_variable_count: match (self.public_variable_count, rhs.public_variable_count)
+                {
+                    (Some(lhs), Some(rhs)) => Some(lhs.checked_sub(rhs)?),
+                    (Some(lhs), None) => Some(lhs),
+                    _ => None,
+                },
+                secret_variable_count: match (self.secret_variable_count, rhs.secret_variable_count)
+                {
+                    (Some(lhs), Some(rhs)) => Some(lhs.checked_sub(rhs)?),
+                    (Some(lhs), None) => Some(lhs),
+                    _ => None,
+                },
+            })
+        }
+    }
+
+    impl Add for Size {
+        type Output = Self;
+
+        #[inline]
+        fn add(mut self, rhs: Self) -> Self::Output {
+            self += rhs;
+            self
+        }
+    }
+
+    impl AddAssign for Size {
+        #[inline]
+        fn add_assign(&mut self, rhs: Self) {
+            self.constraint_count += rhs.constraint_count;
+            match (self.constant_count.as_mut(), rhs.constant_count) {
+                (Some(lhs), Some(rhs)) => *lhs += rhs,
+                (Some(_), None) => {}
+                (None, rhs) => self.constant_count = rhs,
+            }
+            match (
+                self.public_variable_count.as_mut(),
+                rhs.public_variable_count,
+            ) {
+                (Some(lhs), Some(rhs)) => *lhs += rhs,
+                (Some(_), None) => {}
+                (None, rhs) => self.public_variable_count = rhs,
+            }
+            match (
+                self.secret_variable_count.as_mut(),
+                rhs.secret_variable_count,
+            ) {
+                (Some(lhs), Some(rhs)) => *lhs += rhs,
+                (Some(_), None) => {}
+                (None, rhs) => self.secret_variable_count = rhs,
+            }
+        }
+    }
+
+    /// Prints the measurement of the call to `f` with the given `label`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn print_measurement<D, F, T, COM>(label: D, f: F, compiler: &mut COM) -> T
+    where
+        D: Display,
+        F: FnOnce(&mut COM) -> T,
+        COM: Measure,
+    {
+        let before = compiler.measure();
+        let value = f(compiler);
+        println!(
+            "{}: {:?}",
+            label,
+            compiler
+                .measure()
+                .checked_sub(before)
+                .expect("Measurements should increase when adding more constraints.")
+        );
+        value
+    }
+
+    /// Measurement Instrument
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    pub struct Instrument<'c, COM>
+    where
+        COM: Measure,
+    {
+        /// Base Compiler
+        pub base: &'c mut COM,
+
+        /// Measurements
+        pub measurements: Vec<(String, Size)>,
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'c, COM> Instrument<'c, COM>
+    where
+        COM: Measure,
+    {
+        /// Builds a new [`Instrument`] for `base`.
+        #[inline]
+        pub fn new(base: &'c mut COM) -> Self {
+            Self {
+                base,
+                measurements: Default::default(),
+            }
+        }
+
+        /// Measures the size of `f` in the base compiler, attaching `label` to the measurement.
+        #[inline]
+        pub fn measure<D, T, F>(&mut self, label: D, f: F) -> T
+        where
+            D: Display,
+            F: FnOnce(&mut COM) -> T,
+        {
+            let before = self.base.measure();
+            let value = f(self.base);
+            self.measurements.push((
+                format!("{label}"),
+                self.base
+                    .measure()
+                    .checked_sub(before)
+                    .expect("Measurements should increase when adding more constraints."),
+            ));
+            value
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'c, COM> Deref for Instrument<'c, COM>
+    where
+        COM: Measure,
+    {
+        type Target = COM;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            self.base
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'c, COM> DerefMut for Instrument<'c, COM>
+    where
+        COM: Measure,
+    {
+        #[inline]
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            self.base
+        }
+    }
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+    use core::fmt::Debug;
+
+    /// Checks that attempting to verify `proof` against fuzzed inputs fails.
+    #[inline]
+    pub fn verify_fuzz_public_input<P, F>(
+        context: &P::VerifyingContext,
+        input: &P::Input,
+        proof: &P::Proof,
+        mut fuzzer: F,
+    ) where
+        P: ProofSystem,
+        P::Error: Debug,
+        F: FnMut(&P::Input) -> P::Input,
+    {
+        assert!(
+            !P::verify(context, &fuzzer(input), proof).expect("Unable to verify proof."),
+            "Proof remained valid after fuzzing."
+        );
+    }
+
+    /// Differential Testing
+    ///
+    /// The functions in this module run the same computation in the native compiler `()` and in
+    /// some other compiler `COM`, asserting in `COM` that the circuit output is equal to the
+    /// allocation of the native output. If the circuit and native code paths diverge, the
+    /// constraint system in `COM` will not be [`Satisfied`].
+    pub mod differential {
+        use super::*;
+        use crate::{
+            accumulator::Model,
+            hash::ArrayHashFunction,
+            signature::Verify,
+        };
+        use eclair::{
+            alloc::{mode::Secret, Allocate, Constant, Variable},
+            bool::{Assert, Bool},
+            cmp::PartialEq,
+            Has,
+        };
+        use openzl_util::rand::{Rand, Sample};
+
+        /// Runs `native` over `input` in the native compiler and `circuit` over `input` in
+        /// `compiler`, asserting that both computations agree. The `circuit` function is
+        /// responsible for allocating `input` into `compiler`. Returns the native output.
+        ///
+        /// # Panics
+        ///
+        /// This function panics if `compiler` is not satisfied after asserting that the circuit
+        /// output is equal to the native output.
+        #[inline]
+        pub fn assert_agreement<T, O, W, F, G, COM>(
+            input: &T,
+            native: F,
+            circuit: G,
+            compiler: &mut COM,
+        ) -> O
+        where
+            O: Debug,
+            W: PartialEq<W, COM> + Variable<Secret, COM, Type = O>,
+            F: FnOnce(&T, &mut ()) -> O,
+            G: FnOnce(&T, &mut COM) -> W,
+            COM: Assert + Has<bool> + Satisfied,
+        {
+            let expected = native(input, &mut ());
+            let output = circuit(input, compiler);
+            let expected_output = expected.as_known::<Secret, W>(compiler);
+            output.assert_equal(&expected_output, compiler);
+            assert!(
+                compiler.is_satisfied(),
+                "Native and circuit computations diverged for native output {expected:?}."
+            );
+            expected
+        }
+
+        /// Samples `rounds`-many inputs from `distribution` and runs [`assert_agreement`] on each
+        /// of them, building a fresh compiler for every round with `new_compiler`.
+        #[inline]
+        pub fn assert_random_agreement<T, D, O, W, F, G, C, COM, R>(
+            distribution: D,
+            rounds: usize,
+            mut native: F,
+            mut circuit: G,
+            mut new_compiler: C,
+            rng: &mut R,
+        ) where
+            T: Sample<D>,
+            D: Clone,
+            O: Debug,
+            W: PartialEq<W, COM> + Variable<Secret, COM, Type = O>,
+            F: FnMut(&T, &mut ()) -> O,
+            G: FnMut(&T, &mut COM) -> W,
+            C: FnMut() -> COM,
+            COM: Assert + Has<bool> + Satisfied,
+            R: RngCore + ?Sized,
+        {
+            for _ in 0..rounds {
+                assert_agreement(
+                    &rng.sample::<_, T>(distribution.clone()),
+                    &mut native,
+                    &mut circuit,
+                    &mut new_compiler(),
+                );
+            }
+        }
+
+        /// Asserts that the native and in-circuit evaluations of `hasher` agree on `input`.
+        #[inline]
+        pub fn hash_agreement<H, V, const ARITY: usize, COM>(
+            hasher: &H,
+            input: &[H::Input; ARITY],
+            compiler: &mut COM,
+        ) -> H::Output
+        where
+            H: ArrayHashFunction<ARITY>,
+            H::Input: Sized,
+            H::Output: Debug,
+            V: ArrayHashFunction<ARITY, COM> + Constant<COM, Type = H>,
+            V::Input: Sized + Variable<Secret, COM, Type = H::Input>,
+            V::Output: PartialEq<V::Output, COM> + Variable<Secret, COM, Type = H::Output>,
+            COM: Assert + Has<bool> + Satisfied,
+        {
+            assert_agreement(
+                input,
+                |input, compiler| hasher.hash(input.each_ref(), compiler),
+                |input, compiler| {
+                    let hasher = hasher.as_constant::<V>(compiler);
+                    let input = input
+                        .each_ref()
+                        .map(|value| value.as_known::<Secret, V::Input>(compiler));
+                    hasher.hash(input.each_ref(), compiler)
+                },
+                compiler,
+            )
+        }
+
+        /// Asserts that the native and in-circuit verifications of the membership of `item` in
+        /// the accumulator with the given `witness` and `output` agree under `model`.
+        ///
+        /// This can be used to check merkle path verification since merkle tree
+        /// [`Parameters`](crate::merkle_tree::Parameters) implement [`Model`].
+        #[inline]
+        pub fn membership_agreement<M, V, COM>(
+            model: &M,
+            item: &M::Item,
+            witness: &M::Witness,
+            output: &M::Output,
+            compiler: &mut COM,
+        ) -> bool
+        where
+            M: Model<Verification = bool>,
+            V: Constant<COM, Type = M> + Model<COM, Verification = Bool<COM>>,
+            V::Item: Variable<Secret, COM, Type = M::Item>,
+            V::Witness: Variable<Secret, COM, Type = M::Witness>,
+            V::Output: Variable<Secret, COM, Type = M::Output>,
+            Bool<COM>: PartialEq<Bool<COM>, COM> + Variable<Secret, COM, Type = bool>,
+            COM: Assert + Has<bool> + Satisfied,
+        {
+            assert_agreement(
+                &(),
+                |_, compiler| model.verify(item, witness, output, compiler),
+                |_, compiler| {
+                    let model = model.as_constant::<V>(compiler);
+                    let item = item.as_known::<Secret, V::Item>(compiler);
+                    let witness = witness.as_known::<Secret, V::Witness>(compiler);
+                    let output = output.as_known::<Secret, V::Output>(compiler);
+                    model.verify(&item, &witness, &output, compiler)
+                },
+                compiler,
+            )
+        }
+
+        /// Asserts that the native and in-circuit verifications of `signature` over `message`
+        /// with `verifying_key` agree under `scheme`.
+        #[inline]
+        pub fn signature_agreement<S, V, COM>(
+            scheme: &S,
+            verifying_key: &S::VerifyingKey,
+            message: &S::Message,
+            signature: &S::Signature,
+            compiler: &mut COM,
+        ) -> bool
+        where
+            S: Verify<Verification = bool>,
+            V: Constant<COM, Type = S> + Verify<COM, Verification = Bool<COM>>,
+            V::VerifyingKey: Variable<Secret, COM, Type = S::VerifyingKey>,
+            V::Message: Variable<Secret, COM, Type = S::Message>,
+            V::Signature: Variable<Secret, COM, Type = S::Signature>,
+            Bool<COM>: PartialEq<Bool<COM>, COM> + Variable<Secret, COM, Type = bool>,
+            COM: Assert + Has<bool> + Satisfied,
+        {
+            assert_agreement(
+                &(),
+                |_, compiler| scheme.verify(verifying_key, message, signature, compiler),
+                |_, compiler| {
+                    let scheme = scheme.as_constant::<V>(compiler);
+                    let verifying_key = verifying_key.as_known::<Secret, V::VerifyingKey>(compiler);
+                    let message = message.as_known::<Secret, V::Message>(compiler);
+                    let signature = signature.as_known::<Secret, V::Signature>(compiler);
+                    scheme.verify(&verifying_key, &message, &signature, compiler)
+                },
+                compiler,
+            )
+        }
+    }
+
+    /// Zero-Knowledge Smoke Tests
+    ///
+    /// The functions in this module prove the same statement under two different witnesses with
+    /// identical public inputs and check that the encoded proofs do not reveal which witness was
+    /// used. The checks are statistical and can only catch gross leaks, like proofs which are not
+    /// randomized or byte positions which are fixed by the witness, so they are a baseline sanity
+    /// check for custom statements and not a proof of zero-knowledge.
+    pub mod zk {
+        use super::*;
+        use alloc::vec::Vec;
+        use openzl_util::codec::Encode;
+
+        /// Proves the statement built by `circuit` over `witness` `samples`-many times, asserting
+        /// that every proof is valid and that every public input is equal to `input`, if known.
+        /// Returns the public input and the encoded proofs.
+        #[inline]
+        fn encoded_proofs<P, W, F, R>(
+            proving_context: &P::ProvingContext,
+            verifying_context: &P::VerifyingContext,
+            witness: &W,
+            samples: usize,
+            circuit: &mut F,
+            rng: &mut R,
+        ) -> (P::Input, Vec<Vec<u8>>)
+        where
+            P: ProofSystem,
+            P::Error: Debug,
+            P::Input: Debug + PartialEq,
+            P::Proof: Encode,
+            F: FnMut(&W, &mut P::Compiler) -> P::Input,
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            assert!(samples > 0, "At least one proof must be sampled.");
+            let mut input = None;
+            let mut proofs = Vec::with_capacity(samples);
+            for _ in 0..samples {
+                let mut compiler = P::proof_compiler();
+                let next_input = circuit(witness, &mut compiler);
+                let proof =
+                    P::prove(proving_context, compiler, rng).expect("Unable to generate proof.");
+                assert!(
+                    P::verify(verifying_context, &next_input, &proof)
+                        .expect("Unable to verify proof."),
+                    "Proofs under every witness must be valid."
+                );
+                match &input {
+                    Some(input) => assert_eq!(
+                        input, &next_input,
+                        "The public input must not depend on the proof randomness."
+                    ),
+                    _ => input = Some(next_input),
+                }
+                proofs.push(proof.to_vec());
+            }
+            (input.expect("At least one proof was sampled."), proofs)
+        }
+
+        /// Returns the byte of every position which is the same in all of the `proofs`, or `None`
+        /// for positions which vary between proofs.
+        #[inline]
+        fn fixed_bytes(proofs: &[Vec<u8>]) -> Vec<Option<u8>> {
+            let mut fixed = proofs[0].iter().copied().map(Some).collect::<Vec<_>>();
+            for proof in &proofs[1..] {
+                for (fixed, byte) in fixed.iter_mut().zip(proof) {
+                    if *fixed != Some(*byte) {
+                        *fixed = None;
+                    }
+                }
+            }
+            fixed
+        }
+
+        /// Returns the total number of set bits in `proofs`.
+        #[inline]
+        fn hamming_weight(proofs: &[Vec<u8>]) -> u128 {
+            proofs
+                .iter()
+                .flatten()
+                .map(|byte| u128::from(byte.count_ones()))
+                .sum()
+        }
+
+        /// Asserts that the encoded proofs in `lhs` and `rhs`, which were generated under two
+        /// different witnesses, cannot be told apart by their lengths, by the byte positions which
+        /// are fixed in either of them, or by their total Hamming weights.
+        ///
+        /// # Panics
+        ///
+        /// This function panics if `lhs` and `rhs` do not contain the same number of proofs, or if
+        /// they contain fewer than two proofs each.
+        #[inline]
+        pub fn assert_indistinguishable_bytes(lhs: &[Vec<u8>], rhs: &[Vec<u8>]) {
+            assert_eq!(
+                lhs.len(),
+                rhs.len(),
+                "Both witnesses must be sampled the same number of times."
+            );
+            assert!(
+                lhs.len() > 1,
+                "At least two proofs must be sampled for every witness."
+            );
+            let len = lhs[0].len();
+            assert!(
+                lhs.iter().chain(rhs).all(|proof| proof.len() == len),
+                "Proofs must have the same length under every witness."
+            );
+            for proofs in [lhs, rhs] {
+                for (i, proof) in proofs.iter().enumerate() {
+                    assert!(
+                        !proofs[..i].contains(proof),
+                        "Proofs of the same witness must be randomized."
+                    );
+                }
+            }
+            for (position, (lhs, rhs)) in fixed_bytes(lhs)
+                .into_iter()
+                .zip(fixed_bytes(rhs))
+                .enumerate()
+            {
+                assert_eq!(
+                    lhs, rhs,
+                    "Byte {position} of the proofs must not be fixed by the witness."
+                );
+            }
+            // NOTE: The weights of independent fair bits differ with variance `bits / 2`, so this
+            //       allows for six standard deviations.
+            let bits = 8 * (len as u128) * (lhs.len() as u128);
+            let (lhs, rhs) = (hamming_weight(lhs), hamming_weight(rhs));
+            let difference = lhs.abs_diff(rhs);
+            assert!(
+                2 * difference * difference <= 36 * bits,
+                "The Hamming weights of the proofs must not depend on the witness: {lhs} != {rhs}."
+            );
+        }
+
+        /// Proves the statement built by `circuit` `samples`-many times under each of the
+        /// `witnesses` and asserts that the proofs are valid, that both witnesses give the same
+        /// public input, and that the encoded proofs pass [`assert_indistinguishable_bytes`].
+        /// Returns the public input and the encoded proofs under each witness.
+        ///
+        /// The `circuit` function allocates the statement with the given witness into a compiler
+        /// from [`ProofSystem::proof_compiler`] and returns its public input.
+        #[inline]
+        pub fn assert_witness_indistinguishable<P, W, F, R>(
+            proving_context: &P::ProvingContext,
+            verifying_context: &P::VerifyingContext,
+            witnesses: [&W; 2],
+            samples: usize,
+            mut circuit: F,
+            rng: &mut R,
+        ) -> (P::Input, [Vec<Vec<u8>>; 2])
+        where
+            P: ProofSystem,
+            P::Error: Debug,
+            P::Input: Debug + PartialEq,
+            P::Proof: Encode,
+            F: FnMut(&W, &mut P::Compiler) -> P::Input,
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let (input, lhs) = encoded_proofs::<P, _, _, _>(
+                proving_context,
+                verifying_context,
+                witnesses[0],
+                samples,
+                &mut circuit,
+                rng,
+            );
+            let (rhs_input, rhs) = encoded_proofs::<P, _, _, _>(
+                proving_context,
+                verifying_context,
+                witnesses[1],
+                samples,
+                &mut circuit,
+                rng,
+            );
+            assert_eq!(
+                input, rhs_input,
+                "Both witnesses must give the same public input."
+            );
+            assert_indistinguishable_bytes(&lhs, &rhs);
+            (input, [lhs, rhs])
+        }
+
+        /// Rerandomizes `samples`-many times the proof under each of the `witnesses` and asserts
+        /// that the rerandomized proofs are valid and pass [`assert_indistinguishable_bytes`].
+        #[inline]
+        pub fn assert_rerandomized_indistinguishable<P, W, F, R>(
+            proving_context: &P::ProvingContext,
+            verifying_context: &P::VerifyingContext,
+            witnesses: [&W; 2],
+            samples: usize,
+            mut circuit: F,
+            rng: &mut R,
+        ) where
+            P: Rerandomize,
+            P::Error: Debug,
+            P::Input: Debug + PartialEq,
+            P::Proof: Encode,
+            F: FnMut(&W, &mut P::Compiler) -> P::Input,
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let [lhs, rhs] = witnesses.map(|witness| {
+                let mut compiler = P::proof_compiler();
+                let input = circuit(witness, &mut compiler);
+                let proof =
+                    P::prove(proving_context, compiler, rng).expect("Unable to generate proof.");
+                let encoded = proof.to_vec();
+                let mut proofs = Vec::with_capacity(samples);
+                for _ in 0..samples {
+                    let rerandomized = P::rerandomize(verifying_context, &proof, rng)
+                        .expect("Unable to rerandomize proof.");
+                    assert!(
+                        P::verify(verifying_context, &input, &rerandomized)
+                            .expect("Unable to verify proof."),
+                        "Rerandomized proofs must be valid."
+                    );
+                    let rerandomized = rerandomized.to_vec();
+                    assert_ne!(
+                        encoded, rerandomized,
+                        "Rerandomized proofs must differ from the original proof."
+                    );
+                    proofs.push(rerandomized);
+                }
+                proofs
+            });
+            assert_indistinguishable_bytes(&lhs, &rhs);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Byte Packing
+//!
+//! Public input layouts often contain byte payloads, like headers or memos, which have to be
+//! represented as field elements. This module packs byte strings canonically: the bytes are split
+//! into chunks of [`BYTES`](PackBytes::BYTES) bytes and every chunk is read as a little-endian
+//! integer. Only the last chunk can be shorter than [`BYTES`](PackBytes::BYTES), so a byte string
+//! of length `n` is always packed into [`packed_len`] field elements. The length of the byte string
+//! is not part of the packing, so layouts with variable-length payloads must include the length
+//! separately.
+
+use crate::constraint::HasInput;
+use alloc::vec::Vec;
+
+/// Byte Packing
+pub trait PackBytes: Sized {
+    /// Number of Bytes Packed into each Element
+    ///
+    /// Every integer of this many bytes must be representable by `Self` without reduction.
+    const BYTES: usize;
+
+    /// Builds an element from the little-endian `bytes` whose length is at most
+    /// [`BYTES`](Self::BYTES).
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+
+    /// Returns the little-endian byte representation of `self`.
+    ///
+    /// The representation may contain more than [`BYTES`](Self::BYTES) bytes, for example trailing
+    /// zeros or the bytes of an element which was not built by packing.
+    fn to_le_bytes(&self) -> Vec<u8>;
+}
+
+/// Byte Unpacking Gadget
+pub trait UnpackBytes<COM = ()> {
+    /// Byte Type
+    type Byte;
+
+    /// Number of Bytes Packed into each Element
+    ///
+    /// This must be equal to [`PackBytes::BYTES`] of the underlying element type.
+    const BYTES: usize;
+
+    /// Returns the first `len` little-endian bytes of `self`, asserting that the remaining bytes of
+    /// `self` are zero. The length `len` must be at most [`BYTES`](Self::BYTES).
+    fn unpack_bytes(&self, len: usize, compiler: &mut COM) -> Vec<Self::Byte>;
+}
+
+/// Returns the number of elements holding `bytes_per_element` bytes each that are required to pack
+/// `len` bytes.
+#[inline]
+pub const fn packed_len(len: usize, bytes_per_element: usize) -> usize {
+    (len + bytes_per_element - 1) / bytes_per_element
+}
+
+/// Packs `bytes` into the minimum number of elements of type `F`.
+#[inline]
+pub fn pack<F>(bytes: &[u8]) -> Vec<F>
+where
+    F: PackBytes,
+{
+    bytes.chunks(F::BYTES).map(F::from_le_bytes).collect()
+}
+
+/// Unpacks `len` bytes from `elements`, returning `None` if `elements` is not the packing of a byte
+/// string of length `len`.
+#[inline]
+pub fn unpack<F>(elements: &[F], len: usize) -> Option<Vec<u8>>
+where
+    F: PackBytes,
+{
+    if elements.len() != packed_len(len, F::BYTES) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(len);
+    for element in elements {
+        let chunk_len = F::BYTES.min(len - bytes.len());
+        let mut chunk = element.to_le_bytes();
+        if chunk.iter().skip(chunk_len).any(|byte| *byte != 0) {
+            return None;
+        }
+        chunk.resize(chunk_len, 0);
+        bytes.extend(chunk);
+    }
+    Some(bytes)
+}
+
+/// Unpacks `len` bytes from `elements` using `compiler`, asserting that `elements` is the packing of
+/// a byte string of length `len`.
+///
+/// # Panics
+///
+/// This function panics if the number of `elements` does not match the packing of `len` bytes.
+#[inline]
+pub fn unpack_with<F, COM>(elements: &[F], len: usize, compiler: &mut COM) -> Vec<F::Byte>
+where
+    F: UnpackBytes<COM>,
+{
+    assert_eq!(
+        elements.len(),
+        packed_len(len, F::BYTES),
+        "The number of elements must match the packing of {len} bytes."
+    );
+    let mut bytes = Vec::with_capacity(len);
+    for element in elements {
+        let chunk_len = F::BYTES.min(len - bytes.len());
+        bytes.extend(element.unpack_bytes(chunk_len, compiler));
+    }
+    bytes
+}
+
+/// Extends the `input` of the proof system `P` with the packing of `bytes` into elements of type
+/// `F`.
+#[inline]
+pub fn extend_input<P, F>(input: &mut P::Input, bytes: &[u8])
+where
+    P: HasInput<F> + ?Sized,
+    F: PackBytes,
+{
+    for element in pack::<F>(bytes) {
+        P::extend(input, &element);
+    }
+}
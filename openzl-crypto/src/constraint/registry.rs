@@ -0,0 +1,606 @@
+//! Circuit Key Registry
+//!
+//! Applications which deploy several circuits have to keep track of which proving and verifying
+//! keys belong to which version of which circuit, and of the parameters those keys were generated
+//! for. A [`KeyRegistry`] maps every [`CircuitId`], made of a circuit name, a version, and a
+//! fingerprint of the circuit parameters, to the [`KeyDigests`] of its keys. Entries are kept
+//! sorted by identifier, so two registries with the same entries always have the same encoding.
+
+use alloc::{string::String, vec::Vec};
+use core::{cmp::Ordering, fmt};
+use openzl_util::codec::{Decode, DecodeError, Encode, Read, Write};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Circuit Identifier
+///
+/// Identifiers are ordered by name, then by version, and then by parameter fingerprint.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CircuitId<P> {
+    /// Circuit Name
+    pub name: String,
+
+    /// Circuit Version
+    pub version: u32,
+
+    /// Parameter Fingerprint
+    pub fingerprint: P,
+}
+
+impl<P> CircuitId<P> {
+    /// Builds a new [`CircuitId`] from `name`, `version`, and `fingerprint`.
+    #[inline]
+    pub fn new(name: String, version: u32, fingerprint: P) -> Self {
+        Self {
+            name,
+            version,
+            fingerprint,
+        }
+    }
+
+    /// Compares `self` against the identifier made of `name`, `version`, and `fingerprint`.
+    #[inline]
+    fn cmp_parts(&self, name: &str, version: u32, fingerprint: &P) -> Ordering
+    where
+        P: Ord,
+    {
+        (self.name.as_str(), self.version, &self.fingerprint).cmp(&(name, version, fingerprint))
+    }
+}
+
+impl<P> fmt::Display for CircuitId<P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` version {}", self.name, self.version)
+    }
+}
+
+/// Key Digests
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct KeyDigests<D> {
+    /// Proving Key Digest
+    pub proving_key: D,
+
+    /// Verifying Key Digest
+    pub verifying_key: D,
+}
+
+impl<D> KeyDigests<D> {
+    /// Builds a new [`KeyDigests`] from `proving_key` and `verifying_key` digests.
+    #[inline]
+    pub fn new(proving_key: D, verifying_key: D) -> Self {
+        Self {
+            proving_key,
+            verifying_key,
+        }
+    }
+}
+
+/// Registry Entry
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Entry<P, D> {
+    /// Circuit Identifier
+    pub id: CircuitId<P>,
+
+    /// Key Digests
+    pub keys: KeyDigests<D>,
+}
+
+impl<P, D> Entry<P, D> {
+    /// Builds a new [`Entry`] from `id` and `keys`.
+    #[inline]
+    pub fn new(id: CircuitId<P>, keys: KeyDigests<D>) -> Self {
+        Self { id, keys }
+    }
+}
+
+/// Registration Conflict
+///
+/// This error is returned whenever a circuit identifier is registered again with different key
+/// digests.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Conflict<P, D> {
+    /// Circuit Identifier
+    pub id: CircuitId<P>,
+
+    /// Registered Key Digests
+    pub registered: KeyDigests<D>,
+
+    /// Rejected Key Digests
+    pub rejected: KeyDigests<D>,
+}
+
+impl<P, D> fmt::Display for Conflict<P, D> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "circuit {} is already registered with different key digests",
+            self.id
+        )
+    }
+}
+
+/// Circuit Key Registry
+///
+/// See the [module-level documentation](self) for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "P: Clone + Deserialize<'de> + Ord, D: Clone + Deserialize<'de> + PartialEq",
+            serialize = "P: Clone + Serialize, D: Clone + Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields,
+        into = "Vec<Entry<P, D>>",
+        try_from = "Vec<Entry<P, D>>"
+    )
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct KeyRegistry<P, D> {
+    /// Registry Entries
+    ///
+    /// Entries are sorted by their circuit identifiers, which are unique.
+    entries: Vec<Entry<P, D>>,
+}
+
+impl<P, D> KeyRegistry<P, D> {
+    /// Builds a new empty [`KeyRegistry`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the number of entries in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if `self` has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries of `self` sorted by their circuit identifiers.
+    #[inline]
+    pub fn entries(&self) -> &[Entry<P, D>] {
+        &self.entries
+    }
+
+    /// Returns an iterator over all the versions of the circuit `name` in increasing order.
+    #[inline]
+    pub fn versions<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s Entry<P, D>> + 's {
+        let start = self
+            .entries
+            .partition_point(|entry| entry.id.name.as_str() < name);
+        self.entries[start..]
+            .iter()
+            .take_while(move |entry| entry.id.name == name)
+    }
+
+    /// Returns the entry with the highest version of the circuit `name`.
+    #[inline]
+    pub fn latest(&self, name: &str) -> Option<&Entry<P, D>> {
+        self.versions(name).last()
+    }
+
+    /// Returns the entry with the highest version of the circuit `name` whose keys were generated
+    /// for the parameters with the given `fingerprint`.
+    #[inline]
+    pub fn latest_for(&self, name: &str, fingerprint: &P) -> Option<&Entry<P, D>>
+    where
+        P: PartialEq,
+    {
+        self.versions(name)
+            .filter(|entry| &entry.id.fingerprint == fingerprint)
+            .last()
+    }
+
+    /// Returns the entries whose verifying key digest is `verifying_key`.
+    ///
+    /// This lets verifiers find out which circuits a verifying key they received belongs to.
+    #[inline]
+    pub fn find_verifying_key<'s>(
+        &'s self,
+        verifying_key: &'s D,
+    ) -> impl Iterator<Item = &'s Entry<P, D>> + 's
+    where
+        D: PartialEq,
+    {
+        self.entries
+            .iter()
+            .filter(move |entry| &entry.keys.verifying_key == verifying_key)
+    }
+}
+
+impl<P, D> KeyRegistry<P, D>
+where
+    P: Ord,
+{
+    /// Searches for the entry with the given identifier parts, returning its position or the
+    /// position where it should be inserted.
+    #[inline]
+    fn search(&self, name: &str, version: u32, fingerprint: &P) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by(|entry| entry.id.cmp_parts(name, version, fingerprint))
+    }
+
+    /// Builds a new [`KeyRegistry`] by registering every entry of `entries` in order.
+    ///
+    /// See [`register`](Self::register) for more.
+    #[inline]
+    pub fn from_entries<I>(entries: I) -> Result<Self, Conflict<P, D>>
+    where
+        I: IntoIterator<Item = Entry<P, D>>,
+        P: Clone,
+        D: Clone + PartialEq,
+    {
+        let mut registry = Self::new();
+        for entry in entries {
+            registry.register(entry.id, entry.keys)?;
+        }
+        Ok(registry)
+    }
+
+    /// Returns the key digests registered for `name` at `version` with the parameter
+    /// `fingerprint`.
+    #[inline]
+    pub fn get(&self, name: &str, version: u32, fingerprint: &P) -> Option<&KeyDigests<D>> {
+        self.search(name, version, fingerprint)
+            .ok()
+            .map(|index| &self.entries[index].keys)
+    }
+
+    /// Returns the key digests registered for `id`.
+    #[inline]
+    pub fn get_id(&self, id: &CircuitId<P>) -> Option<&KeyDigests<D>> {
+        self.get(&id.name, id.version, &id.fingerprint)
+    }
+
+    /// Returns `true` if `verifying_key` is the verifying key digest registered for `name` at
+    /// `version` with the parameter `fingerprint`.
+    #[inline]
+    pub fn is_compatible(
+        &self,
+        name: &str,
+        version: u32,
+        fingerprint: &P,
+        verifying_key: &D,
+    ) -> bool
+    where
+        D: PartialEq,
+    {
+        self.get(name, version, fingerprint)
+            .map(|keys| &keys.verifying_key == verifying_key)
+            .unwrap_or(false)
+    }
+
+    /// Registers `keys` for the circuit `id`.
+    ///
+    /// Registering the same keys twice is allowed and leaves `self` unchanged, but registering
+    /// different keys for an identifier which is already registered returns a [`Conflict`].
+    #[inline]
+    pub fn register(&mut self, id: CircuitId<P>, keys: KeyDigests<D>) -> Result<(), Conflict<P, D>>
+    where
+        D: Clone + PartialEq,
+    {
+        match self.search(&id.name, id.version, &id.fingerprint) {
+            Ok(index) if self.entries[index].keys == keys => Ok(()),
+            Ok(index) => Err(Conflict {
+                id,
+                registered: self.entries[index].keys.clone(),
+                rejected: keys,
+            }),
+            Err(index) => {
+                self.entries.insert(index, Entry::new(id, keys));
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the entry registered for `name` at `version` with the parameter `fingerprint`,
+    /// returning its key digests if it was registered.
+    #[inline]
+    pub fn remove(&mut self, name: &str, version: u32, fingerprint: &P) -> Option<KeyDigests<D>> {
+        self.search(name, version, fingerprint)
+            .ok()
+            .map(|index| self.entries.remove(index).keys)
+    }
+}
+
+impl<P, D> Default for KeyRegistry<P, D> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, D> From<KeyRegistry<P, D>> for Vec<Entry<P, D>> {
+    #[inline]
+    fn from(registry: KeyRegistry<P, D>) -> Self {
+        registry.entries
+    }
+}
+
+impl<P, D> TryFrom<Vec<Entry<P, D>>> for KeyRegistry<P, D>
+where
+    P: Clone + Ord,
+    D: Clone + PartialEq,
+{
+    type Error = Conflict<P, D>;
+
+    #[inline]
+    fn try_from(entries: Vec<Entry<P, D>>) -> Result<Self, Self::Error> {
+        Self::from_entries(entries)
+    }
+}
+
+/// Registry Decode Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegistryDecodeError<P, D> {
+    /// Missing Length Prefix
+    MissingLength,
+
+    /// Invalid Circuit Name
+    ///
+    /// The circuit name was truncated or is not valid UTF-8.
+    InvalidName,
+
+    /// Missing Circuit Version
+    MissingVersion,
+
+    /// Parameter Fingerprint Decoding Error
+    Fingerprint(P),
+
+    /// Key Digest Decoding Error
+    KeyDigest(D),
+
+    /// Non-Canonical Entry Order
+    ///
+    /// Entries must be encoded in strictly increasing order of their circuit identifiers.
+    NonCanonical,
+}
+
+impl<P, D> Encode for KeyRegistry<P, D>
+where
+    P: Encode,
+    D: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        (self.entries.len() as u64).encode(&mut writer)?;
+        for entry in &self.entries {
+            entry.id.name.as_bytes().encode(&mut writer)?;
+            entry.id.version.encode(&mut writer)?;
+            entry.id.fingerprint.encode(&mut writer)?;
+            entry.keys.proving_key.encode(&mut writer)?;
+            entry.keys.verifying_key.encode(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0u64.encoded_len()
+            + self
+                .entries
+                .iter()
+                .map(|entry| {
+                    entry.id.name.as_bytes().encoded_len()
+                        + entry.id.version.encoded_len()
+                        + entry.id.fingerprint.encoded_len()
+                        + entry.keys.proving_key.encoded_len()
+                        + entry.keys.verifying_key.encoded_len()
+                })
+                .sum::<usize>()
+    }
+}
+
+impl<P, D> Decode for KeyRegistry<P, D>
+where
+    P: Decode + Ord,
+    D: Decode,
+{
+    type Error = RegistryDecodeError<P::Error, D::Error>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let len = u64::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| RegistryDecodeError::MissingLength))?;
+        let mut entries = Vec::<Entry<P, D>>::new();
+        for _ in 0..len {
+            let name = Vec::<u8>::decode(&mut reader)
+                .map_err(|err| err.map_decode(|_| RegistryDecodeError::InvalidName))?;
+            let name = String::from_utf8(name)
+                .map_err(|_| DecodeError::Decode(RegistryDecodeError::InvalidName))?;
+            let version = u32::decode(&mut reader)
+                .map_err(|err| err.map_decode(|_| RegistryDecodeError::MissingVersion))?;
+            let fingerprint = P::decode(&mut reader)
+                .map_err(|err| err.map_decode(RegistryDecodeError::Fingerprint))?;
+            let proving_key = D::decode(&mut reader)
+                .map_err(|err| err.map_decode(RegistryDecodeError::KeyDigest))?;
+            let verifying_key = D::decode(&mut reader)
+                .map_err(|err| err.map_decode(RegistryDecodeError::KeyDigest))?;
+            let id = CircuitId::new(name, version, fingerprint);
+            if let Some(last) = entries.last() {
+                if last.id >= id {
+                    return Err(DecodeError::Decode(RegistryDecodeError::NonCanonical));
+                }
+            }
+            entries.push(Entry::new(id, KeyDigests::new(proving_key, verifying_key)));
+        }
+        Ok(Self { entries })
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Builds the identifier of the circuit `name` at `version` with the parameter `fingerprint`.
+    #[inline]
+    fn id(name: &str, version: u32, fingerprint: u64) -> CircuitId<u64> {
+        CircuitId::new(name.into(), version, fingerprint)
+    }
+
+    /// Tests that the registry looks up keys by identifier, version, and verifying key, and
+    /// rejects conflicting registrations.
+    #[test]
+    fn registry_lookups_and_conflicts() {
+        let mut registry = KeyRegistry::<u64, [u8; 4]>::new();
+        registry
+            .register(id("transfer", 2, 7), KeyDigests::new([2; 4], [3; 4]))
+            .expect("Registering a new circuit should succeed.");
+        registry
+            .register(id("transfer", 1, 7), KeyDigests::new([0; 4], [1; 4]))
+            .expect("Registering a new circuit should succeed.");
+        registry
+            .register(id("transfer", 3, 8), KeyDigests::new([4; 4], [5; 4]))
+            .expect("Registering a new circuit should succeed.");
+        registry
+            .register(id("mint", 1, 7), KeyDigests::new([6; 4], [7; 4]))
+            .expect("Registering a new circuit should succeed.");
+        assert_eq!(
+            registry.get("transfer", 1, &7),
+            Some(&KeyDigests::new([0; 4], [1; 4])),
+            "The registered keys should be found."
+        );
+        assert_eq!(
+            registry.get("transfer", 1, &8),
+            None,
+            "Keys for different parameters should not be found."
+        );
+        assert_eq!(
+            registry
+                .versions("transfer")
+                .map(|entry| entry.id.version)
+                .collect::<Vec<_>>(),
+            [1, 2, 3],
+            "The versions should be listed in increasing order."
+        );
+        assert_eq!(
+            registry.latest("transfer").map(|entry| entry.id.version),
+            Some(3),
+            "The latest version should be the highest one."
+        );
+        assert_eq!(
+            registry
+                .latest_for("transfer", &7)
+                .map(|entry| entry.id.version),
+            Some(2),
+            "The latest version should only consider the given parameters."
+        );
+        assert_eq!(
+            registry
+                .find_verifying_key(&[7; 4])
+                .map(|entry| &entry.id)
+                .collect::<Vec<_>>(),
+            [&id("mint", 1, 7)],
+            "The verifying key should be traced back to its circuit."
+        );
+        assert!(
+            registry.is_compatible("transfer", 2, &7, &[3; 4]),
+            "The registered verifying key should be compatible."
+        );
+        assert!(
+            !registry.is_compatible("transfer", 3, &8, &[3; 4]),
+            "A different verifying key should not be compatible."
+        );
+        assert_eq!(
+            registry.register(id("transfer", 2, 7), KeyDigests::new([2; 4], [3; 4])),
+            Ok(()),
+            "Registering the same keys again should succeed."
+        );
+        assert_eq!(
+            registry
+                .register(id("transfer", 2, 7), KeyDigests::new([9; 4], [3; 4]))
+                .map_err(|conflict| conflict.registered),
+            Err(KeyDigests::new([2; 4], [3; 4])),
+            "Registering different keys should be rejected."
+        );
+        assert_eq!(
+            registry.len(),
+            4,
+            "Conflicts should not change the registry."
+        );
+        assert_eq!(
+            registry.remove("transfer", 3, &8),
+            Some(KeyDigests::new([4; 4], [5; 4])),
+            "Removing a registered circuit should return its keys."
+        );
+        assert_eq!(
+            registry.latest("transfer").map(|entry| entry.id.version),
+            Some(2),
+            "The latest version should change after removing the highest one."
+        );
+    }
+
+    /// Tests that the encoding of a registry does not depend on the registration order and
+    /// round-trips, and that non-canonical encodings are rejected.
+    #[test]
+    fn registry_encoding_is_canonical() {
+        let entries = [
+            Entry::new(id("transfer", 1, 7), KeyDigests::new(0u64, 1)),
+            Entry::new(id("mint", 1, 7), KeyDigests::new(2, 3)),
+            Entry::new(id("transfer", 2, 7), KeyDigests::new(4, 5)),
+        ];
+        let registry =
+            KeyRegistry::from_entries(entries.clone()).expect("The entries should not conflict.");
+        let reversed = KeyRegistry::from_entries(entries.iter().rev().cloned())
+            .expect("The entries should not conflict.");
+        let encoding = registry.to_vec();
+        assert_eq!(
+            encoding,
+            reversed.to_vec(),
+            "The encoding should not depend on the registration order."
+        );
+        assert_eq!(
+            encoding.len(),
+            registry.encoded_len(),
+            "The encoded length should match the encoding."
+        );
+        assert_eq!(
+            KeyRegistry::<u64, u64>::from_vec(encoding).ok(),
+            Some(registry),
+            "The registry should round-trip through its encoding."
+        );
+        let unordered = KeyRegistry {
+            entries: entries.to_vec(),
+        }
+        .to_vec();
+        assert_eq!(
+            KeyRegistry::<u64, u64>::from_vec(unordered),
+            Err(RegistryDecodeError::NonCanonical),
+            "Entries out of order should be rejected."
+        );
+    }
+}
@@ -1,8 +1,8 @@
 //! Encryption and Decryption Key Conversion Primitives and Adapters
 
 use crate::encryption::{
-    CiphertextType, Decrypt, DecryptedPlaintextType, DecryptionKeyType, Derive, Encrypt,
-    EncryptionKeyType, HeaderType, PlaintextType, RandomnessType,
+    security::KeyPrivacy, CiphertextType, Decrypt, DecryptedPlaintextType, DecryptionKeyType,
+    Derive, Encrypt, EncryptionKeyType, HeaderType, PlaintextType, RandomnessType,
 };
 use core::marker::PhantomData;
 use eclair::alloc::Constant;
@@ -192,6 +192,8 @@ where
     }
 }
 
+impl<E, C> KeyPrivacy for Converter<E, C> where E: KeyPrivacy {}
+
 impl<E, C, COM> Constant<COM> for Converter<E, C>
 where
     E: Constant<COM>,
@@ -3,6 +3,12 @@
 //! For encrypting against the same [`EncryptionKey`] and [`DecryptionKey`] we may want to use a
 //! key-exchange protocol in order to generate these keys as unique shared secrets. The [`Hybrid`]
 //! encryption scheme inlines this complexity into the encryption interfaces.
+//!
+//! The [`Ciphertext`] of a [`Hybrid`] encryption scheme only consists of the ephemeral public key
+//! and the ciphertext of the base encryption scheme, and the header is passed to the base scheme
+//! unchanged. Whenever the key agreement scheme and the base encryption scheme are key-private, so
+//! is the [`Hybrid`] scheme, which means that its ciphertexts do not reveal which encryption key
+//! they target. See [`Hybrid::key_private`] for more.
 
 use crate::{
     constraint::{HasInput, Input},
@@ -14,6 +20,7 @@ use crate::{
         self, EphemeralPublicKey, EphemeralPublicKeyType, EphemeralSecretKey,
         EphemeralSecretKeyType, PublicKeyType, SecretKeyType,
     },
+    security::{self, SecurityLevel},
 };
 use core::{fmt::Debug, hash::Hash};
 use eclair::{
@@ -345,6 +352,33 @@ impl<K, E> Hybrid<K, E> {
             encryption_scheme,
         }
     }
+
+    /// Builds a new key-private [`Hybrid`] encryption scheme from `key_agreement_scheme` and a
+    /// base `encryption_scheme`.
+    ///
+    /// This constructor is the same as [`new`](Self::new) but only accepts components which are
+    /// key-private, so that the resulting scheme is key-private as well. See
+    /// [`KeyPrivacy`](encryption::security::KeyPrivacy) for more.
+    ///
+    /// # Crypto Safety
+    ///
+    /// Key privacy only extends to the [`Ciphertext`]. The header is not encrypted, so it must not
+    /// contain any information about the encryption key, like a commitment to it.
+    #[inline]
+    pub fn key_private(key_agreement_scheme: K, encryption_scheme: E) -> Self
+    where
+        K: agreement::security::KeyPrivacy,
+        E: encryption::security::KeyPrivacy,
+    {
+        Self::new(key_agreement_scheme, encryption_scheme)
+    }
+}
+
+impl<K, E> encryption::security::KeyPrivacy for Hybrid<K, E>
+where
+    K: agreement::security::KeyPrivacy,
+    E: encryption::security::KeyPrivacy,
+{
 }
 
 impl<K, E> EncryptedMessage<Hybrid<K, E>>
@@ -359,6 +393,14 @@ where
     }
 }
 
+impl<K, E> SecurityLevel for Hybrid<K, E>
+where
+    K: SecurityLevel,
+    E: SecurityLevel,
+{
+    const SECURITY_LEVEL: u32 = security::min(K::SECURITY_LEVEL, E::SECURITY_LEVEL);
+}
+
 impl<K, E> HeaderType for Hybrid<K, E>
 where
     E: HeaderType,
@@ -30,6 +30,11 @@ use openzl_util::serde::{Deserialize, Serialize};
 
 pub mod convert;
 pub mod hybrid;
+pub mod verifiable;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod rotate;
 
 /// Encryption Header
 ///
@@ -742,6 +747,11 @@ where
         self.ciphertext.encode(&mut writer)?;
         Ok(())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.header.encoded_len() + self.ciphertext.encoded_len()
+    }
 }
 
 impl<E, P> Input<P> for EncryptedMessage<E>
@@ -756,11 +766,44 @@ where
     }
 }
 
+/// Security Assumptions
+///
+/// The following outlines some security properties of encryption schemes beyond the secrecy of
+/// the [`Plaintext`](PlaintextType::Plaintext), which can be attached to schemes which are known
+/// to provide them.
+pub mod security {
+    /// Key Privacy
+    ///
+    /// For an encryption scheme `E`, a fixed header `h` and plaintext `p`, it should be infeasible
+    /// to distinguish the probability distributions over the following two functions when the
+    /// decryption keys `k_0` and `k_1` and the randomness `r` are sampled uniformly from their
+    /// domains, even when given the encryption keys derived from `k_0` and `k_1`:
+    ///
+    /// ```text
+    /// fn encrypt_to_first<E>(k_0: E::DecryptionKey, k_1: E::DecryptionKey, r: E::Randomness) {
+    ///     E::encrypt(E::derive(k_0), r, h, p)
+    /// }
+    ///
+    /// fn encrypt_to_second<E>(k_0: E::DecryptionKey, k_1: E::DecryptionKey, r: E::Randomness) {
+    ///     E::encrypt(E::derive(k_1), r, h, p)
+    /// }
+    /// ```
+    ///
+    /// In other words, ciphertexts do not reveal which encryption key they target. Since the
+    /// [`Header`](super::HeaderType::Header) is not encrypted, protocols which rely on key privacy
+    /// must not store any information about the encryption key, like a commitment to it, in the
+    /// header.
+    pub trait KeyPrivacy {}
+}
+
 /// Testing Framework
 #[cfg(feature = "test")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test {
     use super::*;
+    use alloc::format;
+    use core::fmt::Debug;
+    use eclair::bool::{AssertLabeled, Report};
 
     /// Tests if encryption of `plaintext` using `encryption_key` and `randomness` returns the
     /// original `plaintext` on decryption using `decryption_key`. The `assert_same` function is
@@ -813,4 +856,162 @@ pub mod test {
             assert_same,
         )
     }
+
+    /// Encrypts `plaintext`, runs `tamper` over the resulting ciphertext, and asserts that the
+    /// decryption of the tampered ciphertext is rejected. The `accepts` function should return
+    /// `true` whenever the decrypted plaintext is accepted as a valid decryption of `plaintext`.
+    ///
+    /// # Contract
+    ///
+    /// The `tamper` function must modify the ciphertext, otherwise this test always fails.
+    #[inline]
+    pub fn tampered_ciphertext_rejected<E, T, F>(
+        cipher: &E,
+        encryption_key: &E::EncryptionKey,
+        decryption_key: &E::DecryptionKey,
+        randomness: &E::Randomness,
+        header: &E::Header,
+        plaintext: &E::Plaintext,
+        tamper: T,
+        accepts: F,
+    ) where
+        E: Decrypt + Encrypt,
+        T: FnOnce(&mut E::Ciphertext),
+        F: FnOnce(&E::Plaintext, &E::DecryptedPlaintext) -> bool,
+    {
+        let mut ciphertext = cipher.encrypt(encryption_key, randomness, header, plaintext, &mut ());
+        tamper(&mut ciphertext);
+        assert!(
+            !accepts(
+                plaintext,
+                &cipher.decrypt(decryption_key, header, &ciphertext, &mut ())
+            ),
+            "Tampered ciphertext should never decrypt to the original plaintext."
+        );
+    }
+
+    /// Samples `rounds`-many random encryption instances with `sample` and asserts for each one
+    /// that the untampered ciphertext decrypts to the original plaintext and that the ciphertext
+    /// tampered by `tamper` never decrypts. The `sample` function returns the decryption key,
+    /// randomness, header, and plaintext of every round. See [`correctness_with_derive`] and
+    /// [`tampered_ciphertext_rejected`] for more.
+    #[inline]
+    pub fn assert_encryption_laws<E, S, T, F, R>(
+        cipher: &E,
+        rounds: usize,
+        mut sample: S,
+        mut tamper: T,
+        mut accepts: F,
+        rng: &mut R,
+    ) where
+        E: Decrypt + Derive + Encrypt,
+        E::Plaintext: Debug,
+        S: FnMut(&mut R) -> (E::DecryptionKey, E::Randomness, E::Header, E::Plaintext),
+        T: FnMut(&mut E::Ciphertext, &mut R),
+        F: FnMut(&E::Plaintext, &E::DecryptedPlaintext) -> bool,
+        R: RngCore + ?Sized,
+    {
+        let mut report = Report::new();
+        for round in 0..rounds {
+            let (decryption_key, randomness, header, plaintext) = sample(rng);
+            let encryption_key = cipher.derive(&decryption_key, &mut ());
+            let ciphertext =
+                cipher.encrypt(&encryption_key, &randomness, &header, &plaintext, &mut ());
+            report.assert_labeled(
+                &accepts(
+                    &plaintext,
+                    &cipher.decrypt(&decryption_key, &header, &ciphertext, &mut ()),
+                ),
+                &format!("decryption of {plaintext:?} at round {round:?}"),
+            );
+            let mut tampered = ciphertext;
+            tamper(&mut tampered, rng);
+            report.assert_labeled(
+                &!accepts(
+                    &plaintext,
+                    &cipher.decrypt(&decryption_key, &header, &tampered, &mut ()),
+                ),
+                &format!("rejection of tampered {plaintext:?} at round {round:?}"),
+            );
+        }
+        report.assert_success();
+    }
+
+    /// Plays `rounds`-many rounds of the key privacy game against `adversary`, returning the
+    /// number of rounds in which the adversary identified the receiver of the challenge
+    /// ciphertext.
+    ///
+    /// In every round, two receivers are sampled from `rng` and one of them is chosen uniformly at
+    /// random. The `sample` function returns the randomness, header, and plaintext of the round
+    /// given the encryption key of the chosen receiver, and the plaintext is encrypted to that
+    /// receiver. The adversary is given both encryption keys, the header, the plaintext, and the
+    /// ciphertext, and returns `true` if it guesses the second receiver. See
+    /// [`KeyPrivacy`](security::KeyPrivacy) for more.
+    #[inline]
+    pub fn key_privacy_game<E, S, A, R>(
+        cipher: &E,
+        rounds: usize,
+        mut sample: S,
+        mut adversary: A,
+        rng: &mut R,
+    ) -> usize
+    where
+        E: Derive + Encrypt,
+        E::DecryptionKey: Sample,
+        S: FnMut(&E::EncryptionKey, &mut R) -> (E::Randomness, E::Header, E::Plaintext),
+        A: FnMut(
+            &E::EncryptionKey,
+            &E::EncryptionKey,
+            &E::Header,
+            &E::Plaintext,
+            &E::Ciphertext,
+        ) -> bool,
+        R: RngCore + ?Sized,
+    {
+        let mut wins = 0;
+        for _ in 0..rounds {
+            let first = cipher.derive(&rng.gen(), &mut ());
+            let second = cipher.derive(&rng.gen(), &mut ());
+            let is_second: bool = rng.gen();
+            let receiver = if is_second { &second } else { &first };
+            let (randomness, header, plaintext) = sample(receiver, rng);
+            let ciphertext = cipher.encrypt(receiver, &randomness, &header, &plaintext, &mut ());
+            if adversary(&first, &second, &header, &plaintext, &ciphertext) == is_second {
+                wins += 1;
+            }
+        }
+        wins
+    }
+
+    /// Asserts that `adversary` identifies the receiver of the challenge ciphertext of the key
+    /// privacy game in at most `rounds / 2 + tolerance` of the `rounds`-many rounds, i.e. that it
+    /// does not do noticeably better than guessing. See [`key_privacy_game`] for more.
+    #[inline]
+    pub fn assert_key_privacy<E, S, A, R>(
+        cipher: &E,
+        rounds: usize,
+        tolerance: usize,
+        sample: S,
+        adversary: A,
+        rng: &mut R,
+    ) where
+        E: Derive + Encrypt,
+        E::DecryptionKey: Sample,
+        S: FnMut(&E::EncryptionKey, &mut R) -> (E::Randomness, E::Header, E::Plaintext),
+        A: FnMut(
+            &E::EncryptionKey,
+            &E::EncryptionKey,
+            &E::Header,
+            &E::Plaintext,
+            &E::Ciphertext,
+        ) -> bool,
+        R: RngCore + ?Sized,
+    {
+        let wins = key_privacy_game(cipher, rounds, sample, adversary, rng);
+        assert!(
+            wins <= rounds / 2 + tolerance,
+            "The adversary should not identify the receiver noticeably more often than by guessing, \
+             but it did in {wins} out of {rounds} rounds."
+        );
+    }
 }
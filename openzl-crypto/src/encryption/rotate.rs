@@ -0,0 +1,357 @@
+//! Key Rotation
+//!
+//! When a decryption key of a long-lived store is compromised, every [`EncryptedMessage`] in the
+//! store has to be re-encrypted under a new key. The [`Rotation`] adapter decrypts each message
+//! with the old key, re-encrypts its plaintext under the new key with fresh randomness, and keeps
+//! the header of the original message. Every re-encryption is recorded as an [`Entry`] which maps
+//! the digest of the old ciphertext to the digest of the new one, so that the rotation can be
+//! audited after the fact without access to either key.
+//!
+//! Messages are rotated lazily by the [`Rotate`] iterator, so stores can stream their messages
+//! through the adapter in constant memory. The [`rotate_all`](Rotation::rotate_all) method
+//! collects the rotated messages and their [`Log`] in bulk.
+
+use crate::{
+    encryption::{Decrypt, Encrypt, EncryptedMessage},
+    hash::HashFunction,
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::{
+    derivative,
+    rand::{Rand, RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Rotation Log Entry
+///
+/// The entry for the message at `index` maps the digest of its `old` ciphertext to the digest of
+/// its `new` ciphertext. If the message could not be decrypted with the old key, it is not
+/// re-encrypted and `new` is `None`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Entry<D> {
+    /// Message Index
+    pub index: usize,
+
+    /// Old Ciphertext Digest
+    pub old: D,
+
+    /// New Ciphertext Digest
+    pub new: Option<D>,
+}
+
+impl<D> Entry<D> {
+    /// Builds a new [`Entry`] for the message at `index` from the `old` and `new` digests.
+    #[inline]
+    pub fn new(index: usize, old: D, new: Option<D>) -> Self {
+        Self { index, old, new }
+    }
+
+    /// Returns `true` if the message of `self` was re-encrypted under the new key.
+    #[inline]
+    pub fn is_rotated(&self) -> bool {
+        self.new.is_some()
+    }
+}
+
+/// Rotation Log
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "D: Clone"),
+    Debug(bound = "D: Debug"),
+    Default(bound = ""),
+    Eq(bound = "D: Eq"),
+    Hash(bound = "D: Hash"),
+    PartialEq(bound = "D: PartialEq")
+)]
+pub struct Log<D> {
+    /// Entries
+    entries: Vec<Entry<D>>,
+}
+
+impl<D> Log<D> {
+    /// Builds a new empty [`Log`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the entries of `self` in the order of their messages.
+    #[inline]
+    pub fn entries(&self) -> &[Entry<D>] {
+        &self.entries
+    }
+
+    /// Returns the number of entries in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if `self` has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends `entry` to `self`.
+    #[inline]
+    pub fn push(&mut self, entry: Entry<D>) {
+        self.entries.push(entry)
+    }
+
+    /// Returns `true` if every message recorded in `self` was re-encrypted under the new key.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.entries.iter().all(Entry::is_rotated)
+    }
+
+    /// Returns an iterator over the entries of the messages which could not be decrypted with the
+    /// old key.
+    #[inline]
+    pub fn failures(&self) -> impl Iterator<Item = &Entry<D>> {
+        self.entries.iter().filter(|entry| !entry.is_rotated())
+    }
+
+    /// Returns the digest of the new ciphertext which replaced the ciphertext with the `old`
+    /// digest, if it was recorded in `self` and re-encrypted.
+    #[inline]
+    pub fn lookup(&self, old: &D) -> Option<&D>
+    where
+        D: PartialEq,
+    {
+        self.entries
+            .iter()
+            .find(|entry| &entry.old == old)
+            .and_then(|entry| entry.new.as_ref())
+    }
+
+    /// Returns the inner entries of `self`.
+    #[inline]
+    pub fn into_inner(self) -> Vec<Entry<D>> {
+        self.entries
+    }
+}
+
+impl<D> Extend<Entry<D>> for Log<D> {
+    #[inline]
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Entry<D>>,
+    {
+        self.entries.extend(iter)
+    }
+}
+
+impl<D> FromIterator<Entry<D>> for Log<D> {
+    #[inline]
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Entry<D>>,
+    {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Key Rotation Adapter
+///
+/// See the [module-level documentation](self) for more.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = "E: Debug, E::DecryptionKey: Debug, E::EncryptionKey: Debug, H: Debug")
+)]
+pub struct Rotation<'k, E, H>
+where
+    E: Decrypt + Encrypt,
+{
+    /// Cipher
+    cipher: &'k E,
+
+    /// Old Decryption Key
+    old_key: &'k E::DecryptionKey,
+
+    /// New Encryption Key
+    new_key: &'k E::EncryptionKey,
+
+    /// Ciphertext Hasher
+    hasher: &'k H,
+}
+
+impl<'k, E, H> Rotation<'k, E, H>
+where
+    E: Decrypt + Encrypt,
+    H: HashFunction<Input = EncryptedMessage<E>>,
+{
+    /// Builds a new [`Rotation`] which re-encrypts messages of `cipher` from `old_key` to
+    /// `new_key`, recording their digests under `hasher`.
+    #[inline]
+    pub fn new(
+        cipher: &'k E,
+        old_key: &'k E::DecryptionKey,
+        new_key: &'k E::EncryptionKey,
+        hasher: &'k H,
+    ) -> Self {
+        Self {
+            cipher,
+            old_key,
+            new_key,
+            hasher,
+        }
+    }
+
+    /// Returns the digest of `message` which is recorded in the rotation log.
+    #[inline]
+    pub fn digest(&self, message: &EncryptedMessage<E>) -> H::Output {
+        self.hasher.hash(message, &mut ())
+    }
+
+    /// Re-encrypts the `message` at `index` under the new key with `randomness`, using `open` to
+    /// extract the plaintext from the result of decryption with the old key. If `open` returns
+    /// `None`, the original `message` is returned with an [`Entry`] which records the failure.
+    ///
+    /// The `open` function is responsible for checking any authentication which comes with the
+    /// decrypted plaintext, like the tag verification of a duplex cipher, since the shape of
+    /// [`DecryptedPlaintext`](super::DecryptedPlaintextType::DecryptedPlaintext) is specific to
+    /// each cipher.
+    #[inline]
+    pub fn rotate<F>(
+        &self,
+        index: usize,
+        message: EncryptedMessage<E>,
+        randomness: &E::Randomness,
+        open: F,
+    ) -> (EncryptedMessage<E>, Entry<H::Output>)
+    where
+        E::Header: Clone,
+        F: FnOnce(E::DecryptedPlaintext) -> Option<E::Plaintext>,
+    {
+        let old = self.digest(&message);
+        match open(message.decrypt(self.cipher, self.old_key, &mut ())) {
+            Some(plaintext) => {
+                let rotated = self.cipher.encrypt_into(
+                    self.new_key,
+                    randomness,
+                    message.header.clone(),
+                    &plaintext,
+                    &mut (),
+                );
+                let new = self.digest(&rotated);
+                (rotated, Entry::new(index, old, Some(new)))
+            }
+            _ => (message, Entry::new(index, old, None)),
+        }
+    }
+
+    /// Returns an iterator which lazily re-encrypts `messages` under the new key, sampling fresh
+    /// randomness from `rng` for every message. See [`rotate`](Self::rotate) for more on `open`.
+    #[inline]
+    pub fn stream<'r, I, F, R>(
+        self,
+        messages: I,
+        open: F,
+        rng: &'r mut R,
+    ) -> Rotate<'k, 'r, E, H, I::IntoIter, F, R>
+    where
+        I: IntoIterator<Item = EncryptedMessage<E>>,
+        F: FnMut(E::DecryptedPlaintext) -> Option<E::Plaintext>,
+        R: RngCore + ?Sized,
+    {
+        Rotate {
+            rotation: self,
+            messages: messages.into_iter(),
+            open,
+            rng,
+            index: 0,
+        }
+    }
+
+    /// Re-encrypts all of `messages` under the new key, returning the resulting messages in their
+    /// original order together with the rotation [`Log`]. See [`rotate`](Self::rotate) for more
+    /// on `open`.
+    #[inline]
+    pub fn rotate_all<I, F, R>(
+        self,
+        messages: I,
+        open: F,
+        rng: &mut R,
+    ) -> (Vec<EncryptedMessage<E>>, Log<H::Output>)
+    where
+        E::Header: Clone,
+        E::Randomness: Sample,
+        I: IntoIterator<Item = EncryptedMessage<E>>,
+        F: FnMut(E::DecryptedPlaintext) -> Option<E::Plaintext>,
+        R: RngCore + ?Sized,
+    {
+        self.stream(messages, open, rng).unzip()
+    }
+}
+
+/// Key Rotation Iterator
+///
+/// This `struct` is created by the [`stream`](Rotation::stream) method on [`Rotation`]. See its
+/// documentation for more.
+pub struct Rotate<'k, 'r, E, H, I, F, R>
+where
+    E: Decrypt + Encrypt,
+    R: ?Sized,
+{
+    /// Rotation Adapter
+    rotation: Rotation<'k, E, H>,
+
+    /// Message Iterator
+    messages: I,
+
+    /// Plaintext Opening Function
+    open: F,
+
+    /// Random Number Generator
+    rng: &'r mut R,
+
+    /// Index of the Next Message
+    index: usize,
+}
+
+impl<'k, 'r, E, H, I, F, R> Iterator for Rotate<'k, 'r, E, H, I, F, R>
+where
+    E: Decrypt + Encrypt,
+    E::Header: Clone,
+    E::Randomness: Sample,
+    H: HashFunction<Input = EncryptedMessage<E>>,
+    I: Iterator<Item = EncryptedMessage<E>>,
+    F: FnMut(E::DecryptedPlaintext) -> Option<E::Plaintext>,
+    R: RngCore + ?Sized,
+{
+    type Item = (EncryptedMessage<E>, Entry<H::Output>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let message = self.messages.next()?;
+        let randomness = self.rng.gen();
+        let item = self
+            .rotation
+            .rotate(self.index, message, &randomness, &mut self.open);
+        self.index += 1;
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.messages.size_hint()
+    }
+}
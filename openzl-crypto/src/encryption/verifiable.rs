@@ -0,0 +1,311 @@
+//! Verifiable Encryption
+//!
+//! A verifiable encryption circuit proves the statement "this [`Ciphertext`] is the encryption of
+//! some [`Plaintext`] under this [`EncryptionKey`] and that [`Plaintext`] satisfies the predicate
+//! `P`" without revealing the [`Plaintext`] or the encryption [`Randomness`]. The [`Verifiable`]
+//! builder composes any [`Encrypt`] implementation with a predicate over the [`Plaintext`] variable
+//! and takes care of allocating the [`Statement`] and [`Witness`] in the right modes.
+//!
+//! [`Ciphertext`]: CiphertextType::Ciphertext
+//! [`Plaintext`]: PlaintextType::Plaintext
+//! [`EncryptionKey`]: EncryptionKeyType::EncryptionKey
+//! [`Randomness`]: RandomnessType::Randomness
+
+use crate::{
+    encryption::{
+        self, CiphertextType, Encrypt, EncryptionKeyType, Header, HeaderType, PlaintextType,
+        RandomnessType,
+    },
+    ProofInput,
+};
+use core::{fmt::Debug, hash::Hash};
+use eclair::{
+    alloc::{
+        mode::{Public, Secret},
+        Allocate, Allocator, Constant, Variable,
+    },
+    bool::{Assert, Bool},
+};
+use openzl_util::derivative;
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Verifiable Encryption Statement
+///
+/// The statement is the public part of a verifiable encryption circuit and is allocated as
+/// [`Public`] input. The [`Input`] implementation extends the proof system input in the same order
+/// as the allocation.
+///
+/// [`Input`]: crate::constraint::Input
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative, ProofInput)]
+#[derivative(
+    Clone(bound = "E::EncryptionKey: Clone, E::Ciphertext: Clone"),
+    Copy(bound = "E::EncryptionKey: Copy, E::Ciphertext: Copy"),
+    Debug(bound = "E::EncryptionKey: Debug, E::Ciphertext: Debug"),
+    Default(bound = "E::EncryptionKey: Default, E::Ciphertext: Default"),
+    Eq(bound = "E::EncryptionKey: Eq, E::Ciphertext: Eq"),
+    Hash(bound = "E::EncryptionKey: Hash, E::Ciphertext: Hash"),
+    PartialEq(bound = "E::EncryptionKey: PartialEq, E::Ciphertext: PartialEq")
+)]
+#[proof_input(crate = "crate")]
+pub struct Statement<E>
+where
+    E: CiphertextType + EncryptionKeyType,
+{
+    /// Encryption Key
+    pub encryption_key: E::EncryptionKey,
+
+    /// Ciphertext
+    pub ciphertext: E::Ciphertext,
+}
+
+impl<E> Statement<E>
+where
+    E: CiphertextType + EncryptionKeyType,
+{
+    /// Builds a new [`Statement`] from `encryption_key` and `ciphertext`.
+    #[inline]
+    pub fn new(encryption_key: E::EncryptionKey, ciphertext: E::Ciphertext) -> Self {
+        Self {
+            encryption_key,
+            ciphertext,
+        }
+    }
+}
+
+impl<E, COM> Variable<Public, COM> for Statement<E>
+where
+    E: CiphertextType + EncryptionKeyType + Constant<COM>,
+    E::EncryptionKey: Variable<Public, COM, Type = encryption::EncryptionKey<E::Type>>,
+    E::Ciphertext: Variable<Public, COM, Type = encryption::Ciphertext<E::Type>>,
+    E::Type: CiphertextType + EncryptionKeyType,
+{
+    type Type = Statement<E::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.encryption_key.as_known(compiler),
+            this.ciphertext.as_known(compiler),
+        )
+    }
+}
+
+/// Verifiable Encryption Witness
+///
+/// The witness is the private part of a verifiable encryption circuit and is allocated as
+/// [`Secret`] input.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "E::Randomness: Clone, E::Plaintext: Clone"),
+    Copy(bound = "E::Randomness: Copy, E::Plaintext: Copy"),
+    Debug(bound = "E::Randomness: Debug, E::Plaintext: Debug"),
+    Default(bound = "E::Randomness: Default, E::Plaintext: Default"),
+    Eq(bound = "E::Randomness: Eq, E::Plaintext: Eq"),
+    Hash(bound = "E::Randomness: Hash, E::Plaintext: Hash"),
+    PartialEq(bound = "E::Randomness: PartialEq, E::Plaintext: PartialEq")
+)]
+pub struct Witness<E>
+where
+    E: PlaintextType + RandomnessType,
+{
+    /// Encryption Randomness
+    pub randomness: E::Randomness,
+
+    /// Plaintext
+    pub plaintext: E::Plaintext,
+}
+
+impl<E> Witness<E>
+where
+    E: PlaintextType + RandomnessType,
+{
+    /// Builds a new [`Witness`] from `randomness` and `plaintext`.
+    #[inline]
+    pub fn new(randomness: E::Randomness, plaintext: E::Plaintext) -> Self {
+        Self {
+            randomness,
+            plaintext,
+        }
+    }
+}
+
+impl<E, COM> Variable<Secret, COM> for Witness<E>
+where
+    E: PlaintextType + RandomnessType + Constant<COM>,
+    E::Randomness: Variable<Secret, COM, Type = encryption::Randomness<E::Type>>,
+    E::Plaintext: Variable<Secret, COM, Type = encryption::Plaintext<E::Type>>,
+    E::Type: PlaintextType + RandomnessType,
+{
+    type Type = Witness<E::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.randomness.as_known(compiler),
+            this.plaintext.as_known(compiler),
+        )
+    }
+}
+
+/// Enforces that `statement.ciphertext` is the encryption of `witness.plaintext` under
+/// `statement.encryption_key`, `witness.randomness`, and `header`, and that `predicate` holds
+/// over `witness.plaintext`.
+#[inline]
+pub fn enforce<E, F, COM>(
+    cipher: &E,
+    header: &E::Header,
+    statement: &Statement<E>,
+    witness: &Witness<E>,
+    predicate: F,
+    compiler: &mut COM,
+) where
+    E: Encrypt<COM>,
+    E::Ciphertext: eclair::cmp::PartialEq<E::Ciphertext, COM>,
+    F: FnOnce(&E::Plaintext, &mut COM) -> Bool<COM>,
+    COM: Assert,
+{
+    let ciphertext = cipher.encrypt(
+        &statement.encryption_key,
+        &witness.randomness,
+        header,
+        &witness.plaintext,
+        compiler,
+    );
+    eclair::cmp::PartialEq::assert_equal(&ciphertext, &statement.ciphertext, compiler);
+    let is_valid = predicate(&witness.plaintext, compiler);
+    compiler.assert(&is_valid);
+}
+
+/// Verifiable Encryption Circuit Builder
+///
+/// Composes the native encryption scheme `E` and its [`Header`] with a `predicate` over the
+/// plaintext variable. The scheme and the header are compiled into the circuit as constants, the
+/// [`Statement`] is allocated as [`Public`] input and the [`Witness`] as [`Secret`] input.
+///
+/// [`Header`]: HeaderType::Header
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "E: Clone, E::Header: Clone, F: Clone"),
+    Copy(bound = "E: Copy, E::Header: Copy, F: Copy"),
+    Debug(bound = "E: Debug, E::Header: Debug, F: Debug")
+)]
+pub struct Verifiable<E, F>
+where
+    E: HeaderType,
+{
+    /// Encryption Scheme
+    pub cipher: E,
+
+    /// Encryption Header
+    pub header: E::Header,
+
+    /// Plaintext Predicate
+    pub predicate: F,
+}
+
+impl<E, F> Verifiable<E, F>
+where
+    E: HeaderType,
+{
+    /// Builds a new [`Verifiable`] circuit builder from `cipher`, `header`, and `predicate`.
+    #[inline]
+    pub fn new(cipher: E, header: E::Header, predicate: F) -> Self {
+        Self {
+            cipher,
+            header,
+            predicate,
+        }
+    }
+
+    /// Allocates an unknown [`Statement`] and [`Witness`] into `compiler` and enforces the
+    /// verifiable encryption constraints over them. This is used to build the circuit during
+    /// proving and verifying context generation.
+    #[inline]
+    pub fn build_unknown<V, COM>(&self, compiler: &mut COM) -> Statement<V>
+    where
+        E: CiphertextType + EncryptionKeyType + PlaintextType + RandomnessType,
+        V: Encrypt<COM> + Constant<COM, Type = E>,
+        V::Header: Constant<COM, Type = Header<E>>,
+        V::Ciphertext: eclair::cmp::PartialEq<V::Ciphertext, COM>,
+        Statement<V>: Variable<Public, COM, Type = Statement<E>>,
+        Witness<V>: Variable<Secret, COM, Type = Witness<E>>,
+        F: Fn(&V::Plaintext, &mut COM) -> Bool<COM>,
+        COM: Assert,
+    {
+        let statement: Statement<V> = compiler.allocate_unknown::<Public, _>();
+        let witness: Witness<V> = compiler.allocate_unknown::<Secret, _>();
+        self.enforce(&statement, &witness, compiler);
+        statement
+    }
+
+    /// Allocates the known `statement` and `witness` into `compiler` and enforces the verifiable
+    /// encryption constraints over them. This is used to build the circuit during proof
+    /// generation.
+    #[inline]
+    pub fn build_known<V, COM>(
+        &self,
+        statement: &Statement<E>,
+        witness: &Witness<E>,
+        compiler: &mut COM,
+    ) -> Statement<V>
+    where
+        E: CiphertextType + EncryptionKeyType + PlaintextType + RandomnessType,
+        V: Encrypt<COM> + Constant<COM, Type = E>,
+        V::Header: Constant<COM, Type = Header<E>>,
+        V::Ciphertext: eclair::cmp::PartialEq<V::Ciphertext, COM>,
+        Statement<V>: Variable<Public, COM, Type = Statement<E>>,
+        Witness<V>: Variable<Secret, COM, Type = Witness<E>>,
+        F: Fn(&V::Plaintext, &mut COM) -> Bool<COM>,
+        COM: Assert,
+    {
+        let statement: Statement<V> = statement.as_known::<Public, _>(compiler);
+        let witness: Witness<V> = witness.as_known::<Secret, _>(compiler);
+        self.enforce(&statement, &witness, compiler);
+        statement
+    }
+
+    /// Compiles the scheme and the header into `compiler` and enforces the verifiable encryption
+    /// constraints over the allocated `statement` and `witness`.
+    #[inline]
+    fn enforce<V, COM>(&self, statement: &Statement<V>, witness: &Witness<V>, compiler: &mut COM)
+    where
+        V: Encrypt<COM> + Constant<COM, Type = E>,
+        V::Header: Constant<COM, Type = Header<E>>,
+        V::Ciphertext: eclair::cmp::PartialEq<V::Ciphertext, COM>,
+        F: Fn(&V::Plaintext, &mut COM) -> Bool<COM>,
+        COM: Assert,
+    {
+        let cipher = self.cipher.as_constant::<V>(compiler);
+        let header = self.header.as_constant::<V::Header>(compiler);
+        enforce(
+            &cipher,
+            &header,
+            statement,
+            witness,
+            &self.predicate,
+            compiler,
+        );
+    }
+}
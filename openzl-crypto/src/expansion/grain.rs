@@ -0,0 +1,182 @@
+//! GRAIN Linear Feedback Shift Register
+
+use crate::expansion::Expander;
+use core::iter::FusedIterator;
+
+/// An 80-bit linear feedback shift register, described in [GKRRS19] Appendix A.
+///
+/// [GKRRS19]: https://eprint.iacr.org/2019/458.pdf
+///
+/// # Note
+///
+/// This `struct` does not implement `Copy` because it also implements `Iterator` which would lead
+/// to confusion when using this type in looping contexts.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GrainLFSR {
+    /// LFSR Internal State
+    state: [bool; Self::SIZE],
+
+    /// Head Pointer into [`self.state`](Self::state)
+    head: usize,
+}
+
+impl GrainLFSR {
+    /// LFSR State Size
+    pub const SIZE: usize = 80;
+
+    /// Generates a [`GrainLFSR`] from a sequence of `(n, bits)` pairs, each of which contributes
+    /// the `n` least significant bits of `bits` to the initial state, most significant bit first.
+    #[inline]
+    pub fn from_seed<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, u128)>,
+    {
+        let mut lfsr = Self {
+            state: [false; Self::SIZE],
+            head: 0,
+        };
+        for (n, bits) in iter {
+            lfsr.append_seed_bits(n, bits);
+        }
+        lfsr.skip_updates(Self::SIZE * 2);
+        lfsr
+    }
+
+    /// Generates a [`GrainLFSR`] by absorbing the length of `seed` as a 64-bit integer followed by
+    /// the bytes of `seed`, most significant bit first.
+    ///
+    /// Unlike [`from_seed`](Self::from_seed), which overwrites the state and so only supports
+    /// seeds of up to [`SIZE`](Self::SIZE) bits, every absorbed bit is added to the feedback of one
+    /// update, so that seeds of any length affect the whole state. The state starts out with all
+    /// bits set so that short seeds do not leave it close to the all-zero state.
+    #[inline]
+    pub fn from_seed_bytes(seed: &[u8]) -> Self {
+        let mut lfsr = Self {
+            state: [true; Self::SIZE],
+            head: 0,
+        };
+        for byte in (seed.len() as u64).to_be_bytes().iter().chain(seed) {
+            for i in (0..8).rev() {
+                lfsr.absorb((byte >> i) & 1 != 0);
+            }
+        }
+        lfsr.skip_updates(Self::SIZE * 2);
+        lfsr
+    }
+
+    /// Appends `n` seed bits into the LFSR state.
+    #[inline]
+    fn append_seed_bits(&mut self, n: usize, bits: u128) {
+        for i in (0..n).rev() {
+            self.set_next((bits >> i) & 1 != 0);
+        }
+    }
+
+    /// Performs `n` updates, ignoring their results.
+    #[inline]
+    fn skip_updates(&mut self, n: usize) {
+        for _ in 0..n {
+            self.update();
+        }
+    }
+
+    /// Sets the bit at the current bit pointed to by the head pointer to `next`, moving the head
+    /// pointer forward one step.
+    #[inline]
+    fn set_next(&mut self, next: bool) -> bool {
+        self.state[self.head] = next;
+        self.head += 1;
+        self.head %= Self::SIZE;
+        next
+    }
+
+    /// Returns the bit value of `self.state` at the position `index + self.head`.
+    #[inline]
+    fn bit(&self, index: usize) -> bool {
+        self.state[(index + self.head) % Self::SIZE]
+    }
+
+    /// Updates 1 bit at `self.state[self.head]` with `bit` added to the feedback and increases
+    /// `self.head` by 1.
+    #[inline]
+    fn absorb(&mut self, bit: bool) -> bool {
+        let feedback = self.feedback();
+        self.set_next(feedback ^ bit)
+    }
+
+    /// Returns the feedback bit of the next update.
+    #[inline]
+    fn feedback(&self) -> bool {
+        self.bit(62) ^ self.bit(51) ^ self.bit(38) ^ self.bit(23) ^ self.bit(13) ^ self.bit(0)
+    }
+
+    /// Updates 1 bit at `self.state[self.head]` and increases `self.head` by 1.
+    fn update(&mut self) -> bool {
+        self.set_next(self.feedback())
+    }
+}
+
+impl Iterator for GrainLFSR {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bit = self.update();
+        while !bit {
+            self.update();
+            bit = self.update();
+        }
+        Some(self.update())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for GrainLFSR {}
+
+impl Expander for GrainLFSR {
+    #[inline]
+    fn new(seed: &str) -> Self {
+        Self::from_seed_bytes(seed.as_bytes())
+    }
+
+    #[inline]
+    fn next_bit(&mut self) -> bool {
+        self.next()
+            .expect("The GRAIN LFSR is an infinite iterator and is not allowed to end.")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks that the string-seeded [`GrainLFSR`] matches the known test vectors.
+    #[test]
+    fn grain_expansion_matches_known_values() {
+        let test_vectors = [
+            (
+                "openzl/expansion/test",
+                [
+                    0x2f, 0xc7, 0x52, 0xb8, 0x5c, 0x80, 0xb6, 0x8e, 0x8f, 0x0d, 0x6a, 0x34, 0x5d,
+                    0xc6, 0xeb, 0x84,
+                ],
+            ),
+            (
+                "",
+                [
+                    0x11, 0x23, 0x6b, 0x1d, 0x3a, 0xed, 0x8f, 0xa9, 0x17, 0xd2, 0x70, 0x2e, 0x60,
+                    0x32, 0xbc, 0x20,
+                ],
+            ),
+        ];
+        for (seed, expected) in test_vectors {
+            let mut bytes = [0; 16];
+            GrainLFSR::new(seed).fill_bytes(&mut bytes);
+            assert_eq!(bytes, expected, "Mismatched expansion for seed {:?}.", seed);
+        }
+    }
+}
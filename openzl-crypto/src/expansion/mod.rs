@@ -0,0 +1,137 @@
+//! Deterministic Parameter Expansion
+//!
+//! Public parameters like round constants or generators should be reproducible by anyone from a
+//! short human-readable seed. An [`Expander`] turns such a seed string into an endless
+//! deterministic stream of bits, so that every primitive derives its constants in the same
+//! auditable way. The [`GrainLFSR`](grain::GrainLFSR) expander matches the reference Poseidon
+//! parameter generation and the [`Sha256Expander`](sha256::Sha256Expander) expander runs SHA-256
+//! in counter mode.
+//!
+//! # Note
+//!
+//! Expanders are only meant for deriving public parameters. Their outputs are predictable from the
+//! seed, so they are not allowed to be used for secrets.
+
+use core::iter::FusedIterator;
+use openzl_util::rand::{Error, RngCore};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub mod grain;
+
+#[cfg(feature = "sha256")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "sha256")))]
+pub mod sha256;
+
+/// Deterministic Expander
+pub trait Expander {
+    /// Builds a new expander from the human-readable `seed`.
+    fn new(seed: &str) -> Self
+    where
+        Self: Sized;
+
+    /// Returns the next bit of the stream.
+    fn next_bit(&mut self) -> bool;
+
+    /// Fills `bytes` with the next bits of the stream, most significant bit first.
+    #[inline]
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        for byte in bytes {
+            *byte = (0..8).fold(0, |acc, _| (acc << 1) | self.next_bit() as u8);
+        }
+    }
+
+    /// Returns the next `n` bytes of the stream. See [`fill_bytes`](Self::fill_bytes) for more.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn next_bytes(&mut self, n: usize) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; n];
+        self.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Returns an iterator over the bits of the stream.
+    #[inline]
+    fn bits(&mut self) -> Bits<Self> {
+        Bits(self)
+    }
+
+    /// Converts `self` into a random number generator over the stream.
+    #[inline]
+    fn into_rng(self) -> ExpanderRng<Self>
+    where
+        Self: Sized,
+    {
+        ExpanderRng(self)
+    }
+}
+
+/// Expander Bit Iterator
+///
+/// This `struct` is created by the [`bits`](Expander::bits) method on [`Expander`].
+#[derive(Debug)]
+pub struct Bits<'e, E>(&'e mut E)
+where
+    E: Expander + ?Sized;
+
+impl<'e, E> Iterator for Bits<'e, E>
+where
+    E: Expander + ?Sized,
+{
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.0.next_bit())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<'e, E> FusedIterator for Bits<'e, E> where E: Expander + ?Sized {}
+
+/// Expander Random Number Generator
+///
+/// This `struct` adapts an [`Expander`] into an [`RngCore`] so that parameters can be derived
+/// through the usual sampling APIs. It intentionally does not implement `CryptoRng`, see the
+/// [module-level documentation](self) for more.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ExpanderRng<E>(
+    /// Expander
+    pub E,
+);
+
+impl<E> RngCore for ExpanderRng<E>
+where
+    E: Expander,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        self.0.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        self.0.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.fill_bytes(dest);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! SHA-256 Counter Mode Expansion
+
+use crate::expansion::Expander;
+use core::fmt;
+use sha2::{Digest, Sha256};
+
+/// SHA-256 Counter Mode Expander
+///
+/// The stream of this expander is the concatenation of the blocks `SHA-256(seed || i)` for
+/// `i = 0, 1, 2, ...` where `i` is encoded as a 64-bit big-endian integer, read most significant
+/// bit first.
+#[derive(Clone)]
+pub struct Sha256Expander {
+    /// Hasher with the Seed Absorbed
+    prefix: Sha256,
+
+    /// Counter of the Next Block
+    counter: u64,
+
+    /// Current Block
+    block: [u8; Self::BLOCK_SIZE],
+
+    /// Position of the Next Bit in the Current Block
+    position: usize,
+}
+
+impl Sha256Expander {
+    /// Block Size in Bytes
+    pub const BLOCK_SIZE: usize = 32;
+
+    /// Builds a new [`Sha256Expander`] from the raw bytes of `seed`.
+    #[inline]
+    pub fn from_seed_bytes(seed: &[u8]) -> Self {
+        Self {
+            prefix: Sha256::new().chain_update(seed),
+            counter: 0,
+            block: [0; Self::BLOCK_SIZE],
+            position: 8 * Self::BLOCK_SIZE,
+        }
+    }
+
+    /// Computes the next block if the current one has been consumed.
+    #[inline]
+    fn refill(&mut self) {
+        if self.position == 8 * Self::BLOCK_SIZE {
+            self.block = self
+                .prefix
+                .clone()
+                .chain_update(self.counter.to_be_bytes())
+                .finalize()
+                .into();
+            self.counter = self
+                .counter
+                .checked_add(1)
+                .expect("The SHA-256 block counter is not allowed to overflow.");
+            self.position = 0;
+        }
+    }
+}
+
+impl fmt::Debug for Sha256Expander {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sha256Expander")
+            .field("counter", &self.counter)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Expander for Sha256Expander {
+    #[inline]
+    fn new(seed: &str) -> Self {
+        Self::from_seed_bytes(seed.as_bytes())
+    }
+
+    #[inline]
+    fn next_bit(&mut self) -> bool {
+        self.refill();
+        let bit = (self.block[self.position / 8] >> (7 - self.position % 8)) & 1 == 1;
+        self.position += 1;
+        bit
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        if self.position % 8 != 0 {
+            for byte in bytes {
+                *byte = (0..8).fold(0, |acc, _| (acc << 1) | self.next_bit() as u8);
+            }
+            return;
+        }
+        let mut filled = 0;
+        while filled < bytes.len() {
+            self.refill();
+            let start = self.position / 8;
+            let count = (Self::BLOCK_SIZE - start).min(bytes.len() - filled);
+            bytes[filled..filled + count].copy_from_slice(&self.block[start..start + count]);
+            self.position += 8 * count;
+            filled += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks that the [`Sha256Expander`] matches the known test vector, which spans two blocks.
+    #[test]
+    fn sha256_expansion_matches_known_values() {
+        let expected = [
+            0x45, 0x03, 0x07, 0x33, 0xa0, 0x05, 0x6e, 0xcf, 0xe8, 0x92, 0x80, 0xb7, 0x21, 0xa9,
+            0xbd, 0x61, 0x72, 0x4b, 0x85, 0x2e, 0x09, 0x93, 0x27, 0x50, 0x0d, 0x0f, 0xb7, 0x8f,
+            0xf7, 0x28, 0x48, 0x40, 0xe5, 0x0c, 0x46, 0xc7, 0x46, 0xce, 0x0d, 0x11,
+        ];
+        let mut bytes = [0; 40];
+        Sha256Expander::new("openzl/expansion/test").fill_bytes(&mut bytes);
+        assert_eq!(bytes, expected);
+    }
+
+    /// Checks that filling bytes after reading unaligned bits agrees with reading bit by bit.
+    #[test]
+    fn unaligned_fill_matches_bits() {
+        let mut expander = Sha256Expander::new("openzl/expansion/test");
+        let mut reference = expander.clone();
+        assert_eq!(expander.next_bit(), reference.next_bit());
+        let mut bytes = [0; 40];
+        expander.fill_bytes(&mut bytes);
+        for byte in bytes {
+            let expected = (0..8).fold(0, |acc, _| (acc << 1) | reference.next_bit() as u8);
+            assert_eq!(byte, expected);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Field Element Conversions
+//!
+//! Every backend wraps its field elements in its own type, so sharing parameters between backends
+//! needs a common representation. The canonical encoding of a prime field element is the
+//! little-endian encoding of its integer representative in `[0, p)`, padded with zeros to
+//! [`BYTES`](CanonicalBytes::BYTES) bytes. [`LeBytes`] carries this encoding between backends with
+//! its trailing zeros removed, so that an element of one field converts into an element of another
+//! field whenever its integer representative is smaller than the modulus of the other field.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Conversion Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ConversionError {
+    /// Invalid Length
+    ///
+    /// The encoding has more bytes than the canonical encoding of the target field.
+    InvalidLength {
+        /// Maximum Length
+        expected: usize,
+
+        /// Given Length
+        found: usize,
+    },
+
+    /// Non-Canonical Encoding
+    ///
+    /// The encoded integer is not smaller than the modulus of the target field.
+    NonCanonical,
+}
+
+impl fmt::Display for ConversionError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidLength { expected, found } => write!(
+                f,
+                "expected at most {expected} bytes but found {found} bytes"
+            ),
+            Self::NonCanonical => write!(f, "the encoded integer is not smaller than the modulus"),
+        }
+    }
+}
+
+/// Canonical Byte Encoding
+pub trait CanonicalBytes: Sized {
+    /// Number of Bytes of the Canonical Encoding
+    const BYTES: usize;
+
+    /// Returns the canonical little-endian encoding of `self` which has exactly
+    /// [`BYTES`](Self::BYTES) bytes.
+    fn to_canonical_bytes(&self) -> Vec<u8>;
+
+    /// Decodes the little-endian `bytes` into an element, returning an error if there are more
+    /// than [`BYTES`](Self::BYTES) bytes or if the encoded integer is not smaller than the modulus.
+    /// Encodings shorter than [`BYTES`](Self::BYTES) bytes are padded with zeros.
+    fn from_canonical_bytes(bytes: &[u8]) -> Result<Self, ConversionError>;
+}
+
+/// Little-Endian Byte Encoding
+///
+/// Backend-independent encoding of a field element which stores its canonical encoding without
+/// trailing zeros. Plugins implement [`TryFrom<LeBytes>`] and [`From`] conversions into
+/// [`LeBytes`] for their field element wrappers.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LeBytes(Vec<u8>);
+
+impl LeBytes {
+    /// Builds a new [`LeBytes`] from the little-endian `bytes`, removing trailing zeros.
+    #[inline]
+    pub fn new(mut bytes: Vec<u8>) -> Self {
+        let len = bytes
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |i| i + 1);
+        bytes.truncate(len);
+        Self(bytes)
+    }
+
+    /// Builds a new [`LeBytes`] from the canonical encoding of `element`.
+    #[inline]
+    pub fn from_element<F>(element: &F) -> Self
+    where
+        F: CanonicalBytes,
+    {
+        Self::new(element.to_canonical_bytes())
+    }
+
+    /// Decodes `self` into an element of `F`.
+    #[inline]
+    pub fn to_element<F>(&self) -> Result<F, ConversionError>
+    where
+        F: CanonicalBytes,
+    {
+        F::from_canonical_bytes(&self.0)
+    }
+
+    /// Returns the encoding without trailing zeros.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the underlying byte vector.
+    #[inline]
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for LeBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Converts `element` into the element of `B` with the same integer representative, returning an
+/// error if that integer is not smaller than the modulus of `B`.
+#[inline]
+pub fn convert<A, B>(element: &A) -> Result<B, ConversionError>
+where
+    A: CanonicalBytes,
+    B: CanonicalBytes,
+{
+    LeBytes::from_element(element).to_element()
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+    use core::fmt::Debug;
+
+    /// Asserts that the canonical encoding of `element` has [`BYTES`](CanonicalBytes::BYTES) bytes
+    /// and decodes back to `element`, both directly and through [`LeBytes`].
+    #[inline]
+    pub fn assert_round_trip<F>(element: &F)
+    where
+        F: CanonicalBytes + Debug + PartialEq,
+    {
+        let bytes = element.to_canonical_bytes();
+        assert_eq!(
+            bytes.len(),
+            F::BYTES,
+            "Canonical encodings must have a fixed length."
+        );
+        assert_eq!(
+            &F::from_canonical_bytes(&bytes).expect("Canonical encodings are not allowed to fail."),
+            element,
+            "Canonical encodings must decode to the encoded element."
+        );
+        assert_eq!(
+            &LeBytes::from_element(element)
+                .to_element::<F>()
+                .expect("Canonical encodings are not allowed to fail."),
+            element,
+            "Little-endian encodings must decode to the encoded element."
+        );
+    }
+
+    /// Asserts that converting `element` from `A` into `B` succeeds and converts back to
+    /// `element`.
+    #[inline]
+    pub fn assert_conversion_round_trip<A, B>(element: &A)
+    where
+        A: CanonicalBytes + Debug + PartialEq,
+        B: CanonicalBytes,
+    {
+        let converted = convert::<A, B>(element).expect("The element must fit into the target.");
+        assert_eq!(
+            &convert::<B, A>(&converted).expect("Converting back is not allowed to fail."),
+            element,
+            "Converting back must return the original element."
+        );
+    }
+
+    /// Asserts that decoding `F::BYTES + 1` bytes and decoding the all-ones encoding of
+    /// [`BYTES`](CanonicalBytes::BYTES) bytes both fail.
+    #[inline]
+    pub fn assert_rejects_invalid<F>()
+    where
+        F: CanonicalBytes + Debug,
+    {
+        let mut bytes = alloc::vec![0xFF; F::BYTES];
+        assert_eq!(
+            F::from_canonical_bytes(&bytes).unwrap_err(),
+            ConversionError::NonCanonical,
+        );
+        bytes.push(0);
+        assert_eq!(
+            F::from_canonical_bytes(&bytes).unwrap_err(),
+            ConversionError::InvalidLength {
+                expected: F::BYTES,
+                found: F::BYTES + 1
+            },
+        );
+    }
+}
@@ -0,0 +1,3 @@
+//! Field Elements
+
+pub mod convert;
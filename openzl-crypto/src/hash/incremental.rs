@@ -0,0 +1,248 @@
+//! Incremental Hashing
+//!
+//! The [`ArrayHashFunction`] and [`HashFunction`] traits compute a digest in one call, so the whole
+//! input has to be in memory at once. An [`IncrementalHash`] splits the computation into
+//! [`init`](IncrementalHash::init), [`update`](IncrementalHash::update), and
+//! [`finalize`](IncrementalHash::finalize) steps over a running state, so that large payloads,
+//! like the contents of a file that is being committed to, can be digested as they are read. This
+//! module provides two constructions:
+//!
+//! - [`MerkleDamgard`] chains an [`ArrayHashFunction`] of arity two over the input blocks and
+//!   finalizes with the number of blocks, and
+//! - [`SpongeHash`] absorbs the input blocks into the state of a [`PseudorandomPermutation`] and
+//!   squeezes the digest out of it.
+//!
+//! Both constructions work natively and in-circuit. The number of compressions or permutations
+//! they perform grows with the number of blocks, so a circuit has to fix the length of the input in
+//! advance.
+//!
+//! [`HashFunction`]: super::HashFunction
+
+use crate::{
+    hash::ArrayHashFunction,
+    permutation::{
+        sponge::{Read, Sponge, Write},
+        PseudorandomPermutation,
+    },
+};
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use openzl_util::derivative;
+
+/// Incremental Hash Function
+pub trait IncrementalHash<COM = ()> {
+    /// Input Block Type
+    type Input: ?Sized;
+
+    /// Running State Type
+    type State;
+
+    /// Output Type
+    type Output;
+
+    /// Returns the state of a computation which has not absorbed any input yet.
+    fn init(&self, compiler: &mut COM) -> Self::State;
+
+    /// Updates `state` by absorbing the next `input` block.
+    fn update(&self, state: &mut Self::State, input: &Self::Input, compiler: &mut COM);
+
+    /// Computes the digest of all the blocks absorbed into `state`.
+    fn finalize(&self, state: Self::State, compiler: &mut COM) -> Self::Output;
+
+    /// Computes the digest of all the blocks in `input` in one call.
+    #[inline]
+    fn hash_all<'i, I>(&self, input: I, compiler: &mut COM) -> Self::Output
+    where
+        Self::Input: 'i,
+        I: IntoIterator<Item = &'i Self::Input>,
+    {
+        let mut state = self.init(compiler);
+        for block in input {
+            self.update(&mut state, block, compiler);
+        }
+        self.finalize(state, compiler)
+    }
+}
+
+impl<H, COM> IncrementalHash<COM> for &H
+where
+    H: IncrementalHash<COM>,
+{
+    type Input = H::Input;
+    type State = H::State;
+    type Output = H::Output;
+
+    #[inline]
+    fn init(&self, compiler: &mut COM) -> Self::State {
+        (*self).init(compiler)
+    }
+
+    #[inline]
+    fn update(&self, state: &mut Self::State, input: &Self::Input, compiler: &mut COM) {
+        (*self).update(state, input, compiler)
+    }
+
+    #[inline]
+    fn finalize(&self, state: Self::State, compiler: &mut COM) -> Self::Output {
+        (*self).finalize(state, compiler)
+    }
+}
+
+/// Length Encoding
+///
+/// Chaining values of a [`MerkleDamgard`] hash implement this trait to embed the number of absorbed
+/// blocks into the last compression, so that inputs of different lengths never end in the same
+/// chain.
+pub trait EncodeLength<COM = ()> {
+    /// Encodes `length` as a constant in `compiler`.
+    fn encode_length(length: u64, compiler: &mut COM) -> Self;
+}
+
+/// Merkle-Damgård Hash
+///
+/// Starting from the `initial_value`, every input block is compressed with the current chaining
+/// value by the `hash_function`, and the digest is the compression of the last chaining value with
+/// the number of blocks. The hash function has to accept its own outputs as inputs, like the
+/// Poseidon hashers over a single field.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "H: Clone, T: Clone"),
+    Copy(bound = "H: Copy, T: Copy"),
+    Debug(bound = "H: Debug, T: Debug"),
+    Default(bound = "H: Default, T: Default"),
+    Eq(bound = "H: Eq, T: Eq"),
+    Hash(bound = "H: Hash, T: Hash"),
+    PartialEq(bound = "H: PartialEq, T: PartialEq")
+)]
+pub struct MerkleDamgard<H, T> {
+    /// Compression Function
+    pub hash_function: H,
+
+    /// Initial Chaining Value
+    pub initial_value: T,
+}
+
+impl<H, T> MerkleDamgard<H, T> {
+    /// Builds a new [`MerkleDamgard`] hash over `hash_function` starting from `initial_value`.
+    #[inline]
+    pub fn new(hash_function: H, initial_value: T) -> Self {
+        Self {
+            hash_function,
+            initial_value,
+        }
+    }
+}
+
+/// Merkle-Damgård State
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MerkleDamgardState<T> {
+    /// Chaining Value
+    pub chaining_value: T,
+
+    /// Number of Absorbed Blocks
+    pub length: u64,
+}
+
+impl<H, T, COM> IncrementalHash<COM> for MerkleDamgard<H, T>
+where
+    H: ArrayHashFunction<2, COM, Input = T, Output = T>,
+    T: Clone + EncodeLength<COM>,
+{
+    type Input = T;
+    type State = MerkleDamgardState<T>;
+    type Output = T;
+
+    #[inline]
+    fn init(&self, compiler: &mut COM) -> Self::State {
+        let _ = compiler;
+        MerkleDamgardState {
+            chaining_value: self.initial_value.clone(),
+            length: 0,
+        }
+    }
+
+    #[inline]
+    fn update(&self, state: &mut Self::State, input: &Self::Input, compiler: &mut COM) {
+        state.chaining_value = self
+            .hash_function
+            .hash([&state.chaining_value, input], compiler);
+        state.length += 1;
+    }
+
+    #[inline]
+    fn finalize(&self, state: Self::State, compiler: &mut COM) -> Self::Output {
+        let length = T::encode_length(state.length, compiler);
+        self.hash_function
+            .hash([&state.chaining_value, &length], compiler)
+    }
+}
+
+/// Sponge Hash
+///
+/// Every input block of type `W` is absorbed into a copy of the `initial_state` with
+/// [`Sponge::absorb`], and the digest of type `R` is squeezed out with [`Sponge::squeeze`]. Since
+/// every block is followed by a permutation, inputs of different lengths do not need to be padded.
+/// The `initial_state` should separate the domain of the hash from other uses of the permutation.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "P: Clone, P::Domain: Clone"),
+    Debug(bound = "P: Debug, P::Domain: Debug"),
+    Eq(bound = "P: Eq, P::Domain: Eq"),
+    Hash(bound = "P: Hash, P::Domain: Hash"),
+    PartialEq(bound = "P: PartialEq, P::Domain: PartialEq")
+)]
+pub struct SpongeHash<P, W, R, COM = ()>
+where
+    P: PseudorandomPermutation<COM>,
+{
+    /// Permutation
+    pub permutation: P,
+
+    /// Initial Sponge State
+    pub initial_state: P::Domain,
+
+    /// Type Parameter Marker
+    __: PhantomData<(fn(&W), fn() -> R)>,
+}
+
+impl<P, W, R, COM> SpongeHash<P, W, R, COM>
+where
+    P: PseudorandomPermutation<COM>,
+{
+    /// Builds a new [`SpongeHash`] over `permutation` starting from `initial_state`.
+    #[inline]
+    pub fn new(permutation: P, initial_state: P::Domain) -> Self {
+        Self {
+            permutation,
+            initial_state,
+            __: PhantomData,
+        }
+    }
+}
+
+impl<P, W, R, COM> IncrementalHash<COM> for SpongeHash<P, W, R, COM>
+where
+    P: PseudorandomPermutation<COM>,
+    P::Domain: Clone,
+    W: Write<P, COM>,
+    R: Read<P, COM>,
+{
+    type Input = W;
+    type State = P::Domain;
+    type Output = R;
+
+    #[inline]
+    fn init(&self, compiler: &mut COM) -> Self::State {
+        let _ = compiler;
+        self.initial_state.clone()
+    }
+
+    #[inline]
+    fn update(&self, state: &mut Self::State, input: &Self::Input, compiler: &mut COM) {
+        Sponge::new(&self.permutation, state).absorb(input, compiler);
+    }
+
+    #[inline]
+    fn finalize(&self, mut state: Self::State, compiler: &mut COM) -> Self::Output {
+        Sponge::new(&self.permutation, &mut state).squeeze(compiler)
+    }
+}
@@ -1,5 +1,8 @@
 //! Hash Functions
 
+pub mod incremental;
+pub mod prf;
+
 /// Hash Function
 pub trait HashFunction<COM = ()> {
     /// Input Type
@@ -43,6 +46,9 @@ pub trait ArrayHashFunction<const ARITY: usize, COM = ()> {
 /// properties can be attached to general types that don't exactly conform to the hash function
 /// `trait`s to describe the same cryptographic assumptions or guarantees given by the type.
 pub mod security {
+    #[doc(inline)]
+    pub use crate::security::SecurityLevel;
+
     /// Preimage Resistance
     ///
     /// For a hash function `H` and an output `y`, it should be infeasible to find a preimage `x`
@@ -0,0 +1,299 @@
+//! Pseudorandom Functions
+//!
+//! A pseudorandom function is a keyed function whose outputs cannot be distinguished from random
+//! by anyone without the key. Protocols use them to derive nullifiers from secret keys and, with a
+//! commitment to the key, to authenticate data with the [`KeyCommittingMac`].
+
+use crate::{
+    hash::ArrayHashFunction,
+    security::{self as level, SecurityLevel},
+    Decode, Encode,
+};
+use core::{fmt::Debug, hash::Hash};
+use eclair::{
+    alloc::{mode::Public, Allocate, Allocator, Constant, Variable},
+    bool::{Assert, AssertEq, Bool},
+    ops::BitAnd,
+    Has,
+};
+use openzl_util::derivative;
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Pseudorandom Function
+pub trait PseudorandomFunction<COM = ()> {
+    /// Key Type
+    type Key: ?Sized;
+
+    /// Input Type
+    type Input: ?Sized;
+
+    /// Output Type
+    type Output;
+
+    /// Evaluates the pseudorandom function on `input` under `key`.
+    fn evaluate(&self, key: &Self::Key, input: &Self::Input, compiler: &mut COM) -> Self::Output;
+}
+
+impl<P, COM> PseudorandomFunction<COM> for &P
+where
+    P: PseudorandomFunction<COM>,
+{
+    type Key = P::Key;
+    type Input = P::Input;
+    type Output = P::Output;
+
+    #[inline]
+    fn evaluate(&self, key: &Self::Key, input: &Self::Input, compiler: &mut COM) -> Self::Output {
+        (*self).evaluate(key, input, compiler)
+    }
+}
+
+/// Hash-Based Pseudorandom Function
+///
+/// This pseudorandom function evaluates `input` under `key` by hashing them together as
+/// `H(key, input)`. It is a pseudorandom function whenever `H` can be modeled as a random oracle,
+/// which is the standard assumption for Poseidon, and its outputs commit to the key whenever `H`
+/// is collision resistant.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Decode, Default, Encode, Eq, Hash, PartialEq)]
+pub struct HashPrf<H>(
+    /// Hash Function
+    pub H,
+);
+
+impl<H> HashPrf<H> {
+    /// Builds a new [`HashPrf`] over `hasher`.
+    #[inline]
+    pub fn new(hasher: H) -> Self {
+        Self(hasher)
+    }
+}
+
+impl<H, COM> PseudorandomFunction<COM> for HashPrf<H>
+where
+    H: ArrayHashFunction<2, COM>,
+{
+    type Key = H::Input;
+    type Input = H::Input;
+    type Output = H::Output;
+
+    #[inline]
+    fn evaluate(&self, key: &Self::Key, input: &Self::Input, compiler: &mut COM) -> Self::Output {
+        self.0.hash([key, input], compiler)
+    }
+}
+
+impl<H, COM> Constant<COM> for HashPrf<H>
+where
+    H: Constant<COM>,
+{
+    type Type = HashPrf<H::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+impl<H> SecurityLevel for HashPrf<H>
+where
+    H: SecurityLevel,
+{
+    const SECURITY_LEVEL: u32 = H::SECURITY_LEVEL;
+}
+
+impl<H> security::KeyCommitment for HashPrf<H> where H: crate::hash::security::CollisionResistance {}
+
+/// Key-Committing MAC Tag
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Decode, Default, Encode, Eq, Hash, PartialEq)]
+pub struct Tag<T> {
+    /// Key Commitment
+    pub key_commitment: T,
+
+    /// Authenticator
+    pub value: T,
+}
+
+impl<T> Tag<T> {
+    /// Builds a new [`Tag`] from `key_commitment` and `value`.
+    #[inline]
+    pub fn new(key_commitment: T, value: T) -> Self {
+        Self {
+            key_commitment,
+            value,
+        }
+    }
+}
+
+impl<T, COM> eclair::cmp::PartialEq<Self, COM> for Tag<T>
+where
+    COM: Has<bool>,
+    Bool<COM>: BitAnd<Bool<COM>, COM, Output = Bool<COM>>,
+    T: eclair::cmp::PartialEq<T, COM>,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self, compiler: &mut COM) -> Bool<COM> {
+        self.key_commitment
+            .eq(&rhs.key_commitment, compiler)
+            .bitand(self.value.eq(&rhs.value, compiler), compiler)
+    }
+
+    #[inline]
+    fn assert_equal(&self, rhs: &Self, compiler: &mut COM)
+    where
+        COM: Assert,
+    {
+        compiler.assert_eq(&self.key_commitment, &rhs.key_commitment);
+        compiler.assert_eq(&self.value, &rhs.value);
+    }
+}
+
+impl<T, COM> Variable<Public, COM> for Tag<T>
+where
+    T: Variable<Public, COM>,
+{
+    type Type = Tag<T::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.key_commitment.as_known(compiler),
+            this.value.as_known(compiler),
+        )
+    }
+}
+
+/// Key-Committing Message Authentication Code
+///
+/// The tag of a message is made of a hash of the key and of the pseudorandom function of the key
+/// on the message. The authenticator alone is unforgeable when `P` is a pseudorandom function, and
+/// the key commitment makes it infeasible to find two keys under which the same tag verifies when
+/// `H` is collision resistant, which rules out partitioning attacks on protocols that try several
+/// keys.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "P: Clone, H: Clone"),
+    Copy(bound = "P: Copy, H: Copy"),
+    Debug(bound = "P: Debug, H: Debug"),
+    Default(bound = "P: Default, H: Default"),
+    Eq(bound = "P: Eq, H: Eq"),
+    Hash(bound = "P: Hash, H: Hash"),
+    PartialEq(bound = "P: PartialEq, H: PartialEq")
+)]
+pub struct KeyCommittingMac<P, H> {
+    /// Pseudorandom Function
+    pub prf: P,
+
+    /// Key Hash Function
+    pub key_hash: H,
+}
+
+impl<P, H> KeyCommittingMac<P, H> {
+    /// Builds a new [`KeyCommittingMac`] from `prf` and `key_hash`.
+    #[inline]
+    pub fn new(prf: P, key_hash: H) -> Self {
+        Self { prf, key_hash }
+    }
+
+    /// Computes the tag of `message` under `key`.
+    #[inline]
+    pub fn tag<COM>(&self, key: &P::Key, message: &P::Input, compiler: &mut COM) -> Tag<P::Output>
+    where
+        P: PseudorandomFunction<COM>,
+        H: ArrayHashFunction<1, COM, Input = P::Key, Output = P::Output>,
+    {
+        Tag::new(
+            self.key_hash.hash([key], compiler),
+            self.prf.evaluate(key, message, compiler),
+        )
+    }
+
+    /// Verifies that `tag` is the tag of `message` under `key`.
+    #[inline]
+    pub fn verify<COM>(
+        &self,
+        key: &P::Key,
+        message: &P::Input,
+        tag: &Tag<P::Output>,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        COM: Has<bool>,
+        P: PseudorandomFunction<COM>,
+        H: ArrayHashFunction<1, COM, Input = P::Key, Output = P::Output>,
+        Tag<P::Output>: eclair::cmp::PartialEq<Tag<P::Output>, COM>,
+    {
+        eclair::cmp::PartialEq::eq(&self.tag(key, message, compiler), tag, compiler)
+    }
+}
+
+impl<P, H, COM> Constant<COM> for KeyCommittingMac<P, H>
+where
+    P: Constant<COM>,
+    H: Constant<COM>,
+{
+    type Type = KeyCommittingMac<P::Type, H::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.prf.as_constant(compiler),
+            this.key_hash.as_constant(compiler),
+        )
+    }
+}
+
+impl<P, H> SecurityLevel for KeyCommittingMac<P, H>
+where
+    P: SecurityLevel,
+    H: SecurityLevel,
+{
+    const SECURITY_LEVEL: u32 = level::min(P::SECURITY_LEVEL, H::SECURITY_LEVEL);
+}
+
+/// Security Assumptions
+///
+/// The following outlines the standard security assumptions for pseudorandom functions. As with
+/// the [hash function assumptions](crate::hash::security), these properties can be attached to
+/// types to describe the cryptographic guarantees given by the type.
+pub mod security {
+    /// Pseudorandomness
+    ///
+    /// For a pseudorandom function `F` and a key `k` sampled uniformly at random, it should be
+    /// infeasible to distinguish the function `x -> F(k, x)` from a uniformly random function given
+    /// oracle access to it.
+    pub trait Pseudorandomness {}
+
+    /// Key Commitment
+    ///
+    /// For a pseudorandom function `F` it should be infeasible to find two keys `k_1` and `k_2`
+    /// and an input `x` such that the following function returns `true`:
+    ///
+    /// ```text
+    /// fn is_key_collision(k_1: F::Key, k_2: F::Key, x: F::Input) -> bool {
+    ///     (k_1 != k_2) && (F(k_1, x) == F(k_2, x))
+    /// }
+    /// ```
+    pub trait KeyCommitment {}
+}
@@ -148,6 +148,25 @@ where
     }
 }
 
+/// Security Assumptions
+///
+/// The following outlines some security properties of key agreement schemes which can be attached
+/// to schemes which are known to provide them.
+pub mod security {
+    /// Key Privacy
+    ///
+    /// For a key agreement scheme `K`, the [`EphemeralPublicKey`] should only depend on the
+    /// [`EphemeralSecretKey`], and it should be infeasible to distinguish the [`SharedSecret`]
+    /// generated for a [`PublicKey`] from the one generated for an unrelated public key, even when
+    /// given both public keys and the [`EphemeralPublicKey`].
+    ///
+    /// [`EphemeralPublicKey`]: super::EphemeralPublicKeyType::EphemeralPublicKey
+    /// [`EphemeralSecretKey`]: super::EphemeralSecretKeyType::EphemeralSecretKey
+    /// [`SharedSecret`]: super::SharedSecretType::SharedSecret
+    /// [`PublicKey`]: super::PublicKeyType::PublicKey
+    pub trait KeyPrivacy {}
+}
+
 /// Testing Framework
 #[cfg(feature = "test")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
@@ -0,0 +1,422 @@
+//! Key Blinding Schemes
+//!
+//! Key blinding lets a sender derive an unlinkable one-time public key for a recipient from the
+//! recipient's long-term public key, while only the recipient can compute the matching one-time
+//! secret key. This is the basic building block of stealth addresses and diversified payment
+//! addresses.
+//!
+//! For a recipient with secret key `x` and public key `X = x * G`, the sender samples an ephemeral
+//! secret key `r` and publishes `R = r * G` alongside the one-time public key
+//!
+//! ```text
+//! P = X + H(r * X, i) * G
+//! ```
+//!
+//! where `H` is the [`BlindingHash`] and `i` is an index which diversifies the keys derived from
+//! the same ephemeral key. Since `r * X = x * R`, the recipient can recompute the blinding factor
+//! and the one-time secret key `p = x + H(x * R, i)` for which `P = p * G`.
+
+use crate::algebra::{Group, HasGenerator, Ring, ScalarMul, ScalarMulGroup};
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use eclair::{bool::Bool, cmp::PartialEq, Has};
+use openzl_util::{
+    derivative,
+    rand::{Rand, RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+#[cfg(feature = "alloc")]
+use {
+    crate::{
+        hash::ArrayHashFunction,
+        poseidon::{
+            self,
+            hash::{DomainTag, Hasher},
+        },
+    },
+    eclair::alloc::{Allocate, Const, Constant},
+};
+
+/// Blinding Hash Function
+pub trait BlindingHash<COM = ()> {
+    /// Shared Secret Type
+    type SharedSecret;
+
+    /// Diversifier Index Type
+    type Index;
+
+    /// Blinding Factor Type
+    type BlindingFactor;
+
+    /// Derives the blinding factor from the `shared_secret` between sender and recipient and the
+    /// diversifier `index`.
+    fn blinding_factor(
+        &self,
+        shared_secret: &Self::SharedSecret,
+        index: &Self::Index,
+        compiler: &mut COM,
+    ) -> Self::BlindingFactor;
+}
+
+impl<H, COM> BlindingHash<COM> for &H
+where
+    H: BlindingHash<COM>,
+{
+    type SharedSecret = H::SharedSecret;
+    type Index = H::Index;
+    type BlindingFactor = H::BlindingFactor;
+
+    #[inline]
+    fn blinding_factor(
+        &self,
+        shared_secret: &Self::SharedSecret,
+        index: &Self::Index,
+        compiler: &mut COM,
+    ) -> Self::BlindingFactor {
+        (*self).blinding_factor(shared_secret, index, compiler)
+    }
+}
+
+/// Blinded Key
+///
+/// This is the output of the sender side of the [`KeyBlinding`] scheme which is published to the
+/// recipient.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Copy(bound = "G: Copy"),
+    Debug(bound = "G: Debug"),
+    Default(bound = "G: Default"),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: core::cmp::PartialEq")
+)]
+pub struct BlindedKey<G> {
+    /// Ephemeral Public Key
+    pub ephemeral_public_key: G,
+
+    /// One-Time Public Key
+    pub one_time_public_key: G,
+}
+
+/// Key Blinding Scheme
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "G: Clone, H: Clone"),
+    Copy(bound = "G: Copy, H: Copy"),
+    Debug(bound = "G: Debug, H: Debug"),
+    Default(bound = "G: Default, H: Default"),
+    Eq(bound = "G: Eq, H: Eq"),
+    Hash(bound = "G: Hash, H: Hash"),
+    PartialEq(bound = "G: core::cmp::PartialEq, H: core::cmp::PartialEq")
+)]
+pub struct KeyBlinding<S, G, H, COM = ()> {
+    /// Group Generator
+    pub generator: G,
+
+    /// Blinding Hash Function
+    pub hash: H,
+
+    /// Type Parameter Marker
+    __: PhantomData<(S, COM)>,
+}
+
+impl<S, G, H, COM> KeyBlinding<S, G, H, COM> {
+    /// Builds a new [`KeyBlinding`] scheme over `generator` and `hash`.
+    #[inline]
+    pub fn new(generator: G, hash: H) -> Self {
+        Self {
+            generator,
+            hash,
+            __: PhantomData,
+        }
+    }
+}
+
+impl<S, G, H, COM> KeyBlinding<S, G, H, COM>
+where
+    S: Ring<COM>,
+    G: ScalarMulGroup<S, COM, Output = G>,
+    H: BlindingHash<COM, SharedSecret = G, BlindingFactor = S>,
+{
+    /// Derives the public key for `secret_key`.
+    #[inline]
+    pub fn derive(&self, secret_key: &S, compiler: &mut COM) -> G {
+        self.generator.scalar_mul(secret_key, compiler)
+    }
+
+    /// Blinds `public_key` with `ephemeral_secret_key` and the diversifier `index`, returning the
+    /// [`BlindedKey`] for the recipient.
+    #[inline]
+    pub fn blind(
+        &self,
+        public_key: &G,
+        ephemeral_secret_key: &S,
+        index: &H::Index,
+        compiler: &mut COM,
+    ) -> BlindedKey<G> {
+        let ephemeral_public_key = self.derive(ephemeral_secret_key, compiler);
+        let shared_secret = public_key.scalar_mul(ephemeral_secret_key, compiler);
+        let blinding_factor = self
+            .hash
+            .blinding_factor(&shared_secret, index, compiler);
+        BlindedKey {
+            ephemeral_public_key,
+            one_time_public_key: public_key.add(&self.derive(&blinding_factor, compiler), compiler),
+        }
+    }
+
+    /// Computes the blinding factor on the recipient side from their `secret_key`, the
+    /// `ephemeral_public_key` of the sender, and the diversifier `index`.
+    #[inline]
+    pub fn recover_blinding_factor(
+        &self,
+        secret_key: &S,
+        ephemeral_public_key: &G,
+        index: &H::Index,
+        compiler: &mut COM,
+    ) -> S {
+        let shared_secret = ephemeral_public_key.scalar_mul(secret_key, compiler);
+        self.hash.blinding_factor(&shared_secret, index, compiler)
+    }
+
+    /// Computes the one-time secret key on the recipient side which corresponds to the one-time
+    /// public key derived from `ephemeral_public_key` and the diversifier `index`.
+    #[inline]
+    pub fn one_time_secret_key(
+        &self,
+        secret_key: &S,
+        ephemeral_public_key: &G,
+        index: &H::Index,
+        compiler: &mut COM,
+    ) -> S {
+        secret_key.add(
+            &self.recover_blinding_factor(secret_key, ephemeral_public_key, index, compiler),
+            compiler,
+        )
+    }
+
+    /// Returns `true` if `blinded_key` was derived for the recipient with `secret_key` and the
+    /// diversifier `index`.
+    #[inline]
+    pub fn is_recipient(
+        &self,
+        secret_key: &S,
+        blinded_key: &BlindedKey<G>,
+        index: &H::Index,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        COM: Has<bool>,
+        G: PartialEq<G, COM>,
+    {
+        let one_time_secret_key = self.one_time_secret_key(
+            secret_key,
+            &blinded_key.ephemeral_public_key,
+            index,
+            compiler,
+        );
+        self.derive(&one_time_secret_key, compiler)
+            .eq(&blinded_key.one_time_public_key, compiler)
+    }
+}
+
+impl<S, G, H, COM> HasGenerator<G, COM> for KeyBlinding<S, G, H, COM>
+where
+    G: Group<COM>,
+{
+    type Generator = G;
+
+    #[inline]
+    fn generator(&self) -> &Self::Generator {
+        &self.generator
+    }
+}
+
+impl<S, G, H, DG, DH> Sample<(DG, DH)> for KeyBlinding<S, G, H>
+where
+    G: Sample<DG>,
+    H: Sample<DH>,
+{
+    #[inline]
+    fn sample<R>(distribution: (DG, DH), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution.0), rng.sample(distribution.1))
+    }
+}
+
+/// Poseidon Conversion
+///
+/// Converts between the group and scalar types of a [`KeyBlinding`] scheme and the field used by a
+/// Poseidon hasher.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub trait PoseidonConversion<F, COM = ()> {
+    /// Shared Secret Type
+    type SharedSecret;
+
+    /// Blinding Factor Type
+    type BlindingFactor;
+
+    /// Converts `shared_secret` into a field element to be hashed.
+    fn to_field(shared_secret: &Self::SharedSecret, compiler: &mut COM) -> F;
+
+    /// Converts the hash `digest` into a blinding factor.
+    fn from_field(digest: F, compiler: &mut COM) -> Self::BlindingFactor;
+}
+
+/// Poseidon Blinding Hash
+///
+/// This [`BlindingHash`] hashes the converted shared secret together with the diversifier index
+/// using an arity-two Poseidon [`Hasher`].
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Hasher<S, T, 2, COM>: Clone"),
+    Debug(bound = "Hasher<S, T, 2, COM>: Debug"),
+    Eq(bound = "Hasher<S, T, 2, COM>: Eq"),
+    Hash(bound = "Hasher<S, T, 2, COM>: Hash"),
+    PartialEq(bound = "Hasher<S, T, 2, COM>: core::cmp::PartialEq")
+)]
+pub struct PoseidonBlindingHash<S, T, C, COM = ()>
+where
+    S: poseidon::Specification<COM>,
+    T: DomainTag<S>,
+{
+    /// Poseidon Hasher
+    pub hasher: Hasher<S, T, 2, COM>,
+
+    /// Type Parameter Marker
+    __: PhantomData<C>,
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<S, T, C, COM> PoseidonBlindingHash<S, T, C, COM>
+where
+    S: poseidon::Specification<COM>,
+    T: DomainTag<S>,
+{
+    /// Builds a new [`PoseidonBlindingHash`] over `hasher`.
+    #[inline]
+    pub fn new(hasher: Hasher<S, T, 2, COM>) -> Self {
+        Self {
+            hasher,
+            __: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<S, T, C, COM> BlindingHash<COM> for PoseidonBlindingHash<S, T, C, COM>
+where
+    S: poseidon::Specification<COM>,
+    T: DomainTag<S>,
+    C: PoseidonConversion<S::Field, COM>,
+{
+    type SharedSecret = C::SharedSecret;
+    type Index = S::Field;
+    type BlindingFactor = C::BlindingFactor;
+
+    #[inline]
+    fn blinding_factor(
+        &self,
+        shared_secret: &Self::SharedSecret,
+        index: &Self::Index,
+        compiler: &mut COM,
+    ) -> Self::BlindingFactor {
+        let shared_secret = C::to_field(shared_secret, compiler);
+        C::from_field(
+            self.hasher.hash([&shared_secret, index], compiler),
+            compiler,
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<S, T, C, COM> Constant<COM> for PoseidonBlindingHash<S, T, C, COM>
+where
+    S: poseidon::Specification<COM> + Constant<COM>,
+    S::Type: poseidon::Specification<ParameterField = Const<S::ParameterField, COM>>,
+    S::ParameterField: Constant<COM>,
+    T: DomainTag<S> + Constant<COM>,
+    T::Type: DomainTag<S::Type>,
+{
+    type Type = PoseidonBlindingHash<S::Type, T::Type, C>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.hasher.as_constant(compiler))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<S, T, C, D> Sample<D> for PoseidonBlindingHash<S, T, C>
+where
+    S: poseidon::Specification,
+    T: DomainTag<S>,
+    Hasher<S, T, 2>: Sample<D>,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution))
+    }
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+    use eclair::bool::{Assert, AssertEq};
+
+    /// Tests that the one-time secret key recovered by the recipient with `secret_key` derives the
+    /// one-time public key built by the sender with `ephemeral_secret_key` and `index`.
+    #[inline]
+    pub fn correctness<S, G, H, COM>(
+        scheme: &KeyBlinding<S, G, H, COM>,
+        secret_key: &S,
+        ephemeral_secret_key: &S,
+        index: &H::Index,
+        compiler: &mut COM,
+    ) where
+        COM: Assert,
+        S: Ring<COM>,
+        G: ScalarMulGroup<S, COM, Output = G> + PartialEq<G, COM>,
+        H: BlindingHash<COM, SharedSecret = G, BlindingFactor = S>,
+    {
+        let public_key = scheme.derive(secret_key, compiler);
+        let blinded_key = scheme.blind(&public_key, ephemeral_secret_key, index, compiler);
+        let one_time_secret_key = scheme.one_time_secret_key(
+            secret_key,
+            &blinded_key.ephemeral_public_key,
+            index,
+            compiler,
+        );
+        let one_time_public_key = scheme.derive(&one_time_secret_key, compiler);
+        compiler.assert_eq(&one_time_public_key, &blinded_key.one_time_public_key);
+        let is_recipient = scheme.is_recipient(secret_key, &blinded_key, index, compiler);
+        compiler.assert(&is_recipient);
+    }
+}
@@ -1,3 +1,8 @@
 //! Cryptographic Key Primitives
 
 pub mod agreement;
+pub mod blinding;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod ratchet;
@@ -0,0 +1,362 @@
+//! Symmetric Key Ratchets
+//!
+//! A symmetric ratchet evolves a chain key with a one-way function and derives a fresh message key
+//! at every step. Since the previous chain key is dropped as soon as the next one is computed, an
+//! attacker who compromises the current state cannot recover the message keys of earlier steps,
+//! which gives forward secrecy to streaming encryption protocols. Message keys can be requested
+//! out of order, in which case the keys of the skipped steps are kept until they are used.
+
+use crate::{component, Decode, Encode};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::derivative;
+
+#[cfg(feature = "hkdf")]
+use {hkdf::Hkdf, sha2::Sha256};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Chain Key
+#[component]
+pub type ChainKey;
+
+/// Message Key
+#[component]
+pub type MessageKey;
+
+/// Ratchet Step Function
+pub trait Ratchet: ChainKeyType + MessageKeyType {
+    /// Advances `chain_key`, returning the next chain key and the message key of the current step.
+    ///
+    /// # Crypto Safety
+    ///
+    /// It must be infeasible to recover `chain_key` or the message keys of earlier steps from the
+    /// outputs of this function.
+    fn step(&self, chain_key: &Self::ChainKey) -> (Self::ChainKey, Self::MessageKey);
+}
+
+impl<R> Ratchet for &R
+where
+    R: Ratchet,
+{
+    #[inline]
+    fn step(&self, chain_key: &Self::ChainKey) -> (Self::ChainKey, Self::MessageKey) {
+        (*self).step(chain_key)
+    }
+}
+
+/// Ratchet Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Message key at the given index was already used or evicted from the skipped keys
+    Unavailable {
+        /// Message Index
+        index: u64,
+    },
+
+    /// Message index is too far ahead of the chain
+    TooFarAhead {
+        /// Message Index
+        index: u64,
+
+        /// Maximum Number of Skipped Keys
+        max_skip: u64,
+    },
+}
+
+/// Skipped Message Key
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "R::MessageKey: Deserialize<'de>",
+            serialize = "R::MessageKey: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "R::MessageKey: Clone"),
+    Copy(bound = "R::MessageKey: Copy"),
+    Debug(bound = "R::MessageKey: Debug"),
+    Eq(bound = "R::MessageKey: Eq"),
+    Hash(bound = "R::MessageKey: Hash"),
+    PartialEq(bound = "R::MessageKey: PartialEq")
+)]
+pub struct SkippedKey<R>
+where
+    R: MessageKeyType + ?Sized,
+{
+    /// Message Index
+    pub index: u64,
+
+    /// Message Key
+    pub message_key: R::MessageKey,
+}
+
+/// Symmetric Ratchet State
+///
+/// This is the state of one direction of a stream: the current chain key, the index of the next
+/// message, and the message keys of the steps which were skipped by out-of-order messages. The
+/// state can be persisted with its `serde` or codec implementations, which only contain secrets
+/// that can decrypt future or skipped messages.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "R::ChainKey: Deserialize<'de>, R::MessageKey: Deserialize<'de>",
+            serialize = "R::ChainKey: Serialize, R::MessageKey: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "R::ChainKey: Clone, R::MessageKey: Clone"),
+    Debug(bound = "R::ChainKey: Debug, R::MessageKey: Debug"),
+    Eq(bound = "R::ChainKey: Eq, R::MessageKey: Eq"),
+    Hash(bound = "R::ChainKey: Hash, R::MessageKey: Hash"),
+    PartialEq(bound = "R::ChainKey: PartialEq, R::MessageKey: PartialEq")
+)]
+pub struct SymmetricRatchet<R>
+where
+    R: Ratchet + ?Sized,
+{
+    /// Chain Key
+    chain_key: R::ChainKey,
+
+    /// Index of the Next Message
+    index: u64,
+
+    /// Maximum Number of Skipped Keys
+    max_skip: u64,
+
+    /// Skipped Message Keys
+    ///
+    /// Skipped keys are stored in increasing order of their index.
+    skipped: Vec<SkippedKey<R>>,
+}
+
+impl<R> SymmetricRatchet<R>
+where
+    R: Ratchet + ?Sized,
+{
+    /// Builds a new [`SymmetricRatchet`] starting from `chain_key` which keeps at most `max_skip`
+    /// message keys for out-of-order messages.
+    #[inline]
+    pub fn new(chain_key: R::ChainKey, max_skip: u64) -> Self {
+        Self {
+            chain_key,
+            index: 0,
+            max_skip,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the next message of the chain.
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Returns the maximum number of skipped message keys kept by `self`.
+    #[inline]
+    pub fn max_skip(&self) -> u64 {
+        self.max_skip
+    }
+
+    /// Returns the message keys which were skipped and not used yet.
+    #[inline]
+    pub fn skipped(&self) -> &[SkippedKey<R>] {
+        &self.skipped
+    }
+
+    /// Advances the chain by one step, returning the index and the key of the next message.
+    #[inline]
+    pub fn next_message_key(&mut self, ratchet: &R) -> (u64, R::MessageKey) {
+        let (chain_key, message_key) = ratchet.step(&self.chain_key);
+        self.chain_key = chain_key;
+        let index = self.index;
+        self.index += 1;
+        (index, message_key)
+    }
+
+    /// Returns the message key at the given `index`, consuming it.
+    ///
+    /// If `index` is ahead of the chain, the chain is advanced up to `index` and the keys of the
+    /// skipped steps are kept for later use. At most [`max_skip`](Self::max_skip) keys are kept,
+    /// evicting the oldest ones first.
+    #[inline]
+    pub fn message_key(&mut self, ratchet: &R, index: u64) -> Result<R::MessageKey, Error> {
+        if index < self.index {
+            return match self.skipped.binary_search_by_key(&index, |key| key.index) {
+                Ok(position) => Ok(self.skipped.remove(position).message_key),
+                _ => Err(Error::Unavailable { index }),
+            };
+        }
+        if index - self.index > self.max_skip {
+            return Err(Error::TooFarAhead {
+                index,
+                max_skip: self.max_skip,
+            });
+        }
+        while self.index < index {
+            let (skipped_index, message_key) = self.next_message_key(ratchet);
+            self.skipped.push(SkippedKey {
+                index: skipped_index,
+                message_key,
+            });
+        }
+        let excess = self
+            .skipped
+            .len()
+            .saturating_sub(self.max_skip.try_into().unwrap_or(usize::MAX));
+        self.skipped.drain(..excess);
+        Ok(self.next_message_key(ratchet).1)
+    }
+}
+
+/// HKDF Info used to Expand the Next Chain Key
+#[cfg(feature = "hkdf")]
+const CHAIN_KEY_INFO: &[u8] = b"openzl/ratchet/chain";
+
+/// HKDF Info used to Expand the Message Key
+#[cfg(feature = "hkdf")]
+const MESSAGE_KEY_INFO: &[u8] = b"openzl/ratchet/message";
+
+/// HKDF-SHA256 Ratchet
+///
+/// This ratchet uses the chain key as an HKDF pseudorandom key and expands it with two distinct
+/// labels into the next chain key and the message key.
+#[cfg(feature = "hkdf")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hkdf")))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HkdfRatchet;
+
+#[cfg(feature = "hkdf")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hkdf")))]
+impl ChainKeyType for HkdfRatchet {
+    type ChainKey = [u8; 32];
+}
+
+#[cfg(feature = "hkdf")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hkdf")))]
+impl MessageKeyType for HkdfRatchet {
+    type MessageKey = [u8; 32];
+}
+
+#[cfg(feature = "hkdf")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hkdf")))]
+impl Ratchet for HkdfRatchet {
+    #[inline]
+    fn step(&self, chain_key: &Self::ChainKey) -> (Self::ChainKey, Self::MessageKey) {
+        let hkdf = Hkdf::<Sha256>::from_prk(chain_key)
+            .expect("The chain key has the length of the SHA-256 output.");
+        let mut next_chain_key = [0; 32];
+        let mut message_key = [0; 32];
+        hkdf.expand(CHAIN_KEY_INFO, &mut next_chain_key)
+            .expect("The output is shorter than the HKDF maximum output length.");
+        hkdf.expand(MESSAGE_KEY_INFO, &mut message_key)
+            .expect("The output is shorter than the HKDF maximum output length.");
+        (next_chain_key, message_key)
+    }
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "hkdf"))]
+mod test {
+    use super::*;
+    use openzl_util::codec::{Decode as _, Encode as _};
+
+    /// Returns the first `n` message keys of the chain starting at `chain_key`.
+    #[inline]
+    fn in_order_keys(chain_key: [u8; 32], n: u64) -> Vec<[u8; 32]> {
+        let mut ratchet = SymmetricRatchet::<HkdfRatchet>::new(chain_key, 0);
+        (0..n)
+            .map(|_| ratchet.next_message_key(&HkdfRatchet).1)
+            .collect()
+    }
+
+    /// Tests that message keys requested out of order match the keys of the in-order chain and
+    /// can only be used once.
+    #[test]
+    fn out_of_order_message_keys_match() {
+        let chain_key = [7; 32];
+        let keys = in_order_keys(chain_key, 8);
+        let mut ratchet = SymmetricRatchet::<HkdfRatchet>::new(chain_key, 8);
+        for index in [3, 0, 7, 1, 2, 6, 4, 5] {
+            assert_eq!(
+                ratchet.message_key(&HkdfRatchet, index),
+                Ok(keys[index as usize]),
+                "Message key {index} does not match the in-order chain."
+            );
+        }
+        assert!(ratchet.skipped().is_empty());
+        for index in 0..8 {
+            assert_eq!(
+                ratchet.message_key(&HkdfRatchet, index),
+                Err(Error::Unavailable { index }),
+                "Message keys must only be usable once."
+            );
+        }
+    }
+
+    /// Tests that the ratchet refuses to skip more than its maximum number of keys and evicts the
+    /// oldest skipped keys first.
+    #[test]
+    fn skipped_keys_are_bounded() {
+        let chain_key = [11; 32];
+        let keys = in_order_keys(chain_key, 8);
+        let mut ratchet = SymmetricRatchet::<HkdfRatchet>::new(chain_key, 2);
+        assert_eq!(
+            ratchet.message_key(&HkdfRatchet, 3),
+            Err(Error::TooFarAhead {
+                index: 3,
+                max_skip: 2
+            })
+        );
+        assert_eq!(ratchet.message_key(&HkdfRatchet, 2), Ok(keys[2]));
+        assert_eq!(ratchet.message_key(&HkdfRatchet, 5), Ok(keys[5]));
+        assert_eq!(
+            ratchet.message_key(&HkdfRatchet, 0),
+            Err(Error::Unavailable { index: 0 })
+        );
+        assert_eq!(ratchet.message_key(&HkdfRatchet, 4), Ok(keys[4]));
+        assert_eq!(ratchet.message_key(&HkdfRatchet, 3), Ok(keys[3]));
+    }
+
+    /// Tests that the ratchet state round-trips through the codec.
+    #[test]
+    fn state_codec_round_trip() {
+        let mut ratchet = SymmetricRatchet::<HkdfRatchet>::new([3; 32], 4);
+        ratchet
+            .message_key(&HkdfRatchet, 2)
+            .expect("Skipping two keys is allowed.");
+        let bytes = ratchet.to_vec_exact();
+        assert_eq!(
+            bytes.len(),
+            ratchet.encoded_len(),
+            "The derived encoded length should match the encoding."
+        );
+        assert_eq!(
+            SymmetricRatchet::<HkdfRatchet>::from_vec(bytes).ok(),
+            Some(ratchet)
+        );
+    }
+}
@@ -10,13 +10,25 @@ extern crate alloc;
 
 pub mod accumulator;
 pub mod algebra;
+pub mod commitment;
 pub mod constraint;
 pub mod encryption;
+pub mod expansion;
 pub mod hash;
 pub mod key;
+pub mod nullifier;
 pub mod password;
 pub mod permutation;
+pub mod protocol;
+pub mod security;
 pub mod signature;
+pub mod statements;
+pub mod transcript;
+pub mod vdf;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod field;
 
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
@@ -26,6 +38,22 @@ pub mod merkle_tree;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
 pub mod poseidon;
 
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub mod prover;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod rangeproof;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod secret_sharing;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod sigma;
+
 #[doc(inline)]
 pub use openzl_derive::*;
 
@@ -33,3 +61,19 @@ pub use openzl_derive::*;
 ///
 /// See [`eclair::NonNative`] for why we need this construction.
 pub trait NonNative {}
+
+/// Prototyping Component Marker Trait
+///
+/// Components which skip checks that a protocol relies on, like
+/// [`accumulator::UnsafeAssumeValid`] and [`signature::UnsafeAssumeValid`], implement this trait
+/// and only exist when the `prototyping` feature is enabled. Production builds leave this feature
+/// off so that these components cannot reach them, and auditors can look for implementations of
+/// this trait to find every component which still has to be replaced.
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+pub trait Prototype {
+    /// Warning Message
+    ///
+    /// Describes the check which is skipped by this component.
+    const WARNING: &'static str;
+}
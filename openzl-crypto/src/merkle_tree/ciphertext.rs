@@ -0,0 +1,299 @@
+//! Ciphertext Merkle Trees
+//!
+//! Encrypted mempools and private mailboxes store every [`EncryptedMessage`] they receive and
+//! publish a merkle root over them, so that recipients can prove that a ciphertext was delivered
+//! without revealing which one they can decrypt. A [`CiphertextTree`] keeps the messages next to a
+//! [`FullMerkleTree`] whose leaves are the digests of the messages under the leaf hash of the
+//! configuration, for example the Poseidon
+//! [`MessageHash`](crate::poseidon::encryption::MessageHash).
+
+use crate::{
+    encryption::{CiphertextType, EncryptedMessage, HeaderType},
+    merkle_tree::{
+        capacity,
+        full::FullMerkleTree,
+        inner_tree::{BTreeMap, InnerMap},
+        Configuration, InnerDigest, LeafDigest, LeafHash, Node, Parameters, Path, PathError, Root,
+    },
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::{
+    codec::{Decode, DecodeError, Encode, Read, Write},
+    derivative,
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Ciphertext Merkle Tree
+///
+/// See the [module-level documentation](self) for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                EncryptedMessage<E>: Deserialize<'de>,
+                FullMerkleTree<C, M>: Deserialize<'de>
+            ",
+            serialize = "EncryptedMessage<E>: Serialize, FullMerkleTree<C, M>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "EncryptedMessage<E>: Clone, FullMerkleTree<C, M>: Clone"),
+    Debug(bound = "EncryptedMessage<E>: Debug, FullMerkleTree<C, M>: Debug")
+)]
+pub struct CiphertextTree<E, C, M = BTreeMap<C>>
+where
+    E: CiphertextType + HeaderType,
+    C: Configuration + ?Sized,
+    C::LeafHash: LeafHash<Leaf = EncryptedMessage<E>>,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    /// Encrypted Messages
+    messages: Vec<EncryptedMessage<E>>,
+
+    /// Merkle Tree over the Message Digests
+    tree: FullMerkleTree<C, M>,
+}
+
+impl<E, C, M> CiphertextTree<E, C, M>
+where
+    E: CiphertextType + HeaderType,
+    C: Configuration + ?Sized,
+    C::LeafHash: LeafHash<Leaf = EncryptedMessage<E>>,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    /// Builds a new empty [`CiphertextTree`] with `parameters`.
+    #[inline]
+    pub fn new(parameters: Parameters<C>) -> Self {
+        Self {
+            messages: Vec::new(),
+            tree: FullMerkleTree::new(parameters),
+        }
+    }
+
+    /// Builds a new [`CiphertextTree`] with `parameters` holding `messages`, returning `None` if
+    /// there are more `messages` than the capacity of the tree.
+    #[inline]
+    pub fn from_messages(
+        parameters: Parameters<C>,
+        messages: Vec<EncryptedMessage<E>>,
+    ) -> Option<Self> {
+        Some(Self {
+            tree: FullMerkleTree::from_iter(parameters, &messages)?,
+            messages,
+        })
+    }
+
+    /// Returns a shared reference to the parameters of the underlying merkle tree.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        self.tree.parameters()
+    }
+
+    /// Returns a shared reference to the underlying merkle tree.
+    #[inline]
+    pub fn tree(&self) -> &FullMerkleTree<C, M> {
+        &self.tree
+    }
+
+    /// Returns the number of messages that can fit in `self`.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        capacity::<C, _>()
+    }
+
+    /// Returns the number of messages in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if `self` has no messages.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the root of the merkle tree over the messages in `self`.
+    #[inline]
+    pub fn root(&self) -> &Root<C> {
+        self.tree.root()
+    }
+
+    /// Returns the messages in `self` in the order of their insertion.
+    #[inline]
+    pub fn messages(&self) -> &[EncryptedMessage<E>] {
+        &self.messages
+    }
+
+    /// Returns the message stored at `index` if it exists.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&EncryptedMessage<E>> {
+        self.messages.get(index)
+    }
+
+    /// Returns the digest of `message` as it would be stored in the leaves of `self`.
+    #[inline]
+    pub fn digest(&self, message: &EncryptedMessage<E>) -> LeafDigest<C> {
+        self.parameters().digest(message)
+    }
+
+    /// Returns the index of `message` if it is stored in `self`.
+    #[inline]
+    pub fn position(&self, message: &EncryptedMessage<E>) -> Option<usize> {
+        self.tree.position(&self.digest(message))
+    }
+
+    /// Inserts `message` into `self`, returning its index, or returning `message` back if `self`
+    /// is already full.
+    #[inline]
+    pub fn insert(&mut self, message: EncryptedMessage<E>) -> Result<usize, EncryptedMessage<E>> {
+        if !self.tree.push(&message) {
+            return Err(message);
+        }
+        self.messages.push(message);
+        Ok(self.messages.len() - 1)
+    }
+
+    /// Returns the inclusion proof for the message stored at `index`.
+    #[inline]
+    pub fn path(&self, index: usize) -> Result<Path<C>, PathError> {
+        self.tree.path(index)
+    }
+
+    /// Returns the index of `message` and its inclusion proof if it is stored in `self`.
+    #[inline]
+    pub fn prove(&self, message: &EncryptedMessage<E>) -> Option<(usize, Path<C>)> {
+        let index = self.position(message)?;
+        Some((index, self.path(index).ok()?))
+    }
+
+    /// Verifies that `path` witnesses the inclusion of `message` in a tree with the current root
+    /// of `self`.
+    #[inline]
+    pub fn verify(&self, message: &EncryptedMessage<E>, path: &Path<C>) -> bool {
+        self.parameters().verify_path(path, self.root(), message)
+    }
+
+    /// Returns the messages and the underlying merkle tree of `self`.
+    #[inline]
+    pub fn into_inner(self) -> (Vec<EncryptedMessage<E>>, FullMerkleTree<C, M>) {
+        (self.messages, self.tree)
+    }
+}
+
+/// Ciphertext Tree Decode Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CiphertextTreeDecodeError<P, H, C> {
+    /// Parameters Decoding Error
+    Parameters(P),
+
+    /// Header Decoding Error
+    Header(H),
+
+    /// Ciphertext Decoding Error
+    Ciphertext(C),
+
+    /// Length Error
+    ///
+    /// The encoded number of messages is larger than the capacity of the tree.
+    Length,
+}
+
+impl<E, C, M> Decode for CiphertextTree<E, C, M>
+where
+    E: CiphertextType + HeaderType,
+    E::Ciphertext: Decode,
+    E::Header: Decode,
+    C: Configuration + ?Sized,
+    C::LeafHash: LeafHash<Leaf = EncryptedMessage<E>>,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+    Parameters<C>: Decode,
+{
+    #[allow(clippy::type_complexity)] // NOTE: This is an implementation type so it doesn't matter.
+    type Error = CiphertextTreeDecodeError<
+        <Parameters<C> as Decode>::Error,
+        <E::Header as Decode>::Error,
+        <E::Ciphertext as Decode>::Error,
+    >;
+
+    /// Decodes the parameters and the messages of a [`CiphertextTree`] and rebuilds the merkle
+    /// tree from the messages, so that the decoded root always matches the decoded messages.
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let parameters = Parameters::<C>::decode(&mut reader)
+            .map_err(|err| err.map_decode(CiphertextTreeDecodeError::Parameters))?;
+        let len = Node::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| CiphertextTreeDecodeError::Length))?
+            .0;
+        if len > capacity::<C, _>() {
+            return Err(DecodeError::Decode(CiphertextTreeDecodeError::Length));
+        }
+        let mut tree = Self::new(parameters);
+        for _ in 0..len {
+            let header = E::Header::decode(&mut reader)
+                .map_err(|err| err.map_decode(CiphertextTreeDecodeError::Header))?;
+            let ciphertext = E::Ciphertext::decode(&mut reader)
+                .map_err(|err| err.map_decode(CiphertextTreeDecodeError::Ciphertext))?;
+            if tree
+                .insert(EncryptedMessage::new(header, ciphertext))
+                .is_err()
+            {
+                return Err(DecodeError::Decode(CiphertextTreeDecodeError::Length));
+            }
+        }
+        Ok(tree)
+    }
+}
+
+impl<E, C, M> Encode for CiphertextTree<E, C, M>
+where
+    E: CiphertextType + HeaderType,
+    E::Ciphertext: Encode,
+    E::Header: Encode,
+    C: Configuration + ?Sized,
+    C::LeafHash: LeafHash<Leaf = EncryptedMessage<E>>,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+    Parameters<C>: Encode,
+{
+    /// Encodes the parameters and the messages of `self`. The merkle tree is not encoded since it
+    /// is determined by the parameters and the messages.
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.parameters().encode(&mut writer)?;
+        Node(self.len()).encode(&mut writer)?;
+        for message in &self.messages {
+            message.encode(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.parameters().encoded_len()
+            + Node(self.len()).encoded_len()
+            + self.messages.iter().map(Encode::encoded_len).sum::<usize>()
+    }
+}
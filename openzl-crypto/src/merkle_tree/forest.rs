@@ -13,14 +13,21 @@ use crate::{
     },
     merkle_tree::{
         fork::ForkedTree,
+        full::Full,
         inner_tree::InnerMap,
-        path::Path,
+        path::{constraint::PathVar, Path},
         tree::{self, Leaf, Parameters, Root, Tree},
         InnerDigest, LeafDigest, WithProofs,
     },
+    NonNative,
 };
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
 use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use eclair::{
+    alloc::{mode::Secret, Allocate, Allocator, Constant, Variable},
+    bool::{Bool, ConditionalSwap},
+    Has,
+};
 use openzl_util::{derivative, persistence::Rollback, BoxArray};
 
 #[cfg(feature = "serde")]
@@ -630,3 +637,1191 @@ where
         }
     }
 }
+
+/// Leaf Digest Prefix
+///
+/// Sharded forests route every leaf to a shard using the leading bits of its leaf digest. Since the
+/// digest is the output of a hash function, these bits are uniformly distributed and the shards
+/// fill evenly.
+pub trait DigestPrefix {
+    /// Returns the first `bits` bits of `self` as an integer less than `2^bits`, reading `self` in
+    /// big-endian order and padding with zeros if `self` has fewer than `bits` bits.
+    fn prefix(&self, bits: u32) -> usize;
+}
+
+/// Returns the first `bits` bits of `bytes` as an integer, padding with zeros if `bytes` is too
+/// short.
+#[inline]
+fn byte_prefix(bytes: &[u8], bits: u32) -> usize {
+    let mut prefix = 0;
+    let mut remaining = bits;
+    let mut bytes = bytes.iter();
+    while remaining > 0 {
+        let byte = bytes.next().copied().unwrap_or(0);
+        let taken = remaining.min(8);
+        prefix = (prefix << taken) | usize::from(byte >> (8 - taken));
+        remaining -= taken;
+    }
+    prefix
+}
+
+macro_rules! impl_digest_prefix_for_integer {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl DigestPrefix for $type {
+                #[inline]
+                fn prefix(&self, bits: u32) -> usize {
+                    byte_prefix(&self.to_be_bytes(), bits)
+                }
+            }
+        )*
+    };
+}
+
+impl_digest_prefix_for_integer!(u8, u16, u32, u64, u128);
+
+impl DigestPrefix for [u8] {
+    #[inline]
+    fn prefix(&self, bits: u32) -> usize {
+        byte_prefix(self, bits)
+    }
+}
+
+impl<const N: usize> DigestPrefix for [u8; N] {
+    #[inline]
+    fn prefix(&self, bits: u32) -> usize {
+        byte_prefix(self, bits)
+    }
+}
+
+impl DigestPrefix for Vec<u8> {
+    #[inline]
+    fn prefix(&self, bits: u32) -> usize {
+        byte_prefix(self, bits)
+    }
+}
+
+impl DigestPrefix for String {
+    #[inline]
+    fn prefix(&self, bits: u32) -> usize {
+        byte_prefix(self.as_bytes(), bits)
+    }
+}
+
+/// Sharded Merkle Forest Parameters
+///
+/// Membership model of a [`ShardedForest`]. On top of checking the merkle path, verification
+/// checks that the shard index of the witness matches the prefix of the leaf digest.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Parameters<C>: Deserialize<'de>",
+            serialize = "Parameters<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Parameters<C>: Clone"),
+    Copy(bound = "Parameters<C>: Copy"),
+    Debug(bound = "Parameters<C>: Debug"),
+    Eq(bound = "Parameters<C>: Eq"),
+    Hash(bound = "Parameters<C>: Hash"),
+    PartialEq(bound = "Parameters<C>: PartialEq")
+)]
+pub struct ShardedParameters<C>
+where
+    C: tree::Configuration + ?Sized,
+{
+    /// Merkle Tree Parameters
+    pub parameters: Parameters<C>,
+
+    /// Number of Leaf Digest Prefix Bits used to Select a Shard
+    pub prefix_bits: u32,
+}
+
+impl<C> ShardedParameters<C>
+where
+    C: tree::Configuration + ?Sized,
+    LeafDigest<C>: DigestPrefix,
+{
+    /// Returns the index of the shard that stores `leaf_digest`.
+    #[inline]
+    pub fn shard_index(&self, leaf_digest: &LeafDigest<C>) -> usize {
+        leaf_digest.prefix(self.prefix_bits)
+    }
+}
+
+impl<C> accumulator::Types for ShardedParameters<C>
+where
+    C: tree::Configuration + ?Sized,
+{
+    type Item = Leaf<C>;
+    type Witness = ShardedPath<C>;
+    type Output = Root<C>;
+}
+
+impl<C> accumulator::Model for ShardedParameters<C>
+where
+    C: tree::Configuration + ?Sized,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: PartialEq,
+{
+    type Verification = bool;
+
+    #[inline]
+    fn verify(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        _: &mut (),
+    ) -> Self::Verification {
+        let leaf_digest = self.parameters.digest(item);
+        witness.shard == self.shard_index(&leaf_digest)
+            && witness
+                .path
+                .verify_digest(&self.parameters, output, &leaf_digest)
+    }
+}
+
+/// Sharded Merkle Path
+///
+/// Membership witness of a [`ShardedForest`], tagging the merkle path with the index of the shard
+/// whose root it opens to.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Path<C>: Deserialize<'de>",
+            serialize = "Path<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Path<C>: Clone"),
+    Debug(bound = "Path<C>: Debug"),
+    Default(bound = "Path<C>: Default"),
+    Eq(bound = "Path<C>: Eq"),
+    Hash(bound = "Path<C>: Hash"),
+    PartialEq(bound = "Path<C>: PartialEq")
+)]
+pub struct ShardedPath<C>
+where
+    C: tree::Configuration + ?Sized,
+{
+    /// Shard Index
+    pub shard: usize,
+
+    /// Merkle Path in the Shard
+    pub path: Path<C>,
+}
+
+impl<C> ShardedPath<C>
+where
+    C: tree::Configuration + ?Sized,
+{
+    /// Builds a new [`ShardedPath`] from `shard` and `path`.
+    #[inline]
+    pub fn new(shard: usize, path: Path<C>) -> Self {
+        Self { shard, path }
+    }
+}
+
+/// Sharded Merkle Forest
+///
+/// Unlike [`MerkleForest`], which asks the [`Configuration`] for the index of the tree of every
+/// leaf, a sharded forest routes each leaf to one of `2^prefix_bits` shards using the
+/// [`DigestPrefix`] of its leaf digest, so any [`tree::Configuration`] can be sharded. Membership
+/// proofs are [`ShardedPath`]s tagged with the shard index and verify against the root of that
+/// shard.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Parameters<C>: Deserialize<'de>, T: Deserialize<'de>",
+            serialize = "Parameters<C>: Serialize, T: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Parameters<C>: Clone, T: Clone"),
+    Debug(bound = "Parameters<C>: Debug, T: Debug"),
+    Eq(bound = "Parameters<C>: Eq, T: Eq"),
+    Hash(bound = "Parameters<C>: Hash, T: Hash"),
+    PartialEq(bound = "Parameters<C>: PartialEq, T: PartialEq")
+)]
+pub struct ShardedForest<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+{
+    /// Sharded Merkle Forest Parameters
+    parameters: ShardedParameters<C>,
+
+    /// Shards
+    shards: Vec<T>,
+}
+
+impl<C, T> ShardedForest<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+    LeafDigest<C>: DigestPrefix,
+{
+    /// Builds a new [`ShardedForest`] with `2^prefix_bits` empty shards.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `prefix_bits` is not smaller than the number of bits of a [`usize`].
+    #[inline]
+    pub fn new(parameters: Parameters<C>, prefix_bits: u32) -> Self {
+        assert!(
+            prefix_bits < usize::BITS,
+            "The number of prefix bits must be smaller than the number of bits of a `usize`."
+        );
+        let shards = (0..1usize << prefix_bits)
+            .map(|_| T::new(&parameters))
+            .collect();
+        Self {
+            parameters: ShardedParameters {
+                parameters,
+                prefix_bits,
+            },
+            shards,
+        }
+    }
+
+    /// Returns a shared reference to the parameters used by this sharded forest.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        &self.parameters.parameters
+    }
+
+    /// Builds a new [`ShardedForest`] from `parameters` and its `shards`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the number of `shards` is not `2^prefix_bits`.
+    #[inline]
+    pub fn from_parts(parameters: ShardedParameters<C>, shards: Vec<T>) -> Self {
+        assert_eq!(
+            shards.len(),
+            1usize << parameters.prefix_bits,
+            "The number of shards must be equal to two to the number of prefix bits."
+        );
+        Self { parameters, shards }
+    }
+
+    /// Returns the parameters and the shards of this sharded forest.
+    #[inline]
+    pub fn into_parts(self) -> (ShardedParameters<C>, Vec<T>) {
+        (self.parameters, self.shards)
+    }
+
+    /// Returns the number of leaf digest prefix bits used to select a shard.
+    #[inline]
+    pub fn prefix_bits(&self) -> u32 {
+        self.parameters.prefix_bits
+    }
+
+    /// Returns the shards of this sharded forest.
+    #[inline]
+    pub fn shards(&self) -> &[T] {
+        &self.shards
+    }
+
+    /// Returns a shared reference to the shard at the given `index` if it exists.
+    #[inline]
+    pub fn shard(&self, index: usize) -> Option<&T> {
+        self.shards.get(index)
+    }
+
+    /// Returns the index of the shard that stores `leaf`.
+    #[inline]
+    pub fn shard_index(&self, leaf: &Leaf<C>) -> usize {
+        self.parameters
+            .shard_index(&self.parameters.parameters.digest(leaf))
+    }
+
+    /// Returns the number of leaves that can fit in this sharded forest.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shards.len() * tree::capacity::<C, _>()
+    }
+
+    /// Returns the number of leaves in this sharded forest.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.len()).sum()
+    }
+
+    /// Returns `true` if this sharded forest is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.is_empty())
+    }
+
+    /// Inserts `leaf` at the next available leaf node of its shard, returning `false` if the leaf
+    /// could not be inserted because its shard has exhausted its capacity.
+    #[inline]
+    pub fn push(&mut self, leaf: &Leaf<C>) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(leaf);
+        let shard = self.parameters.shard_index(&leaf_digest);
+        self.shards[shard].push_digest(&self.parameters.parameters, move || leaf_digest)
+    }
+}
+
+impl<C, T> accumulator::Types for ShardedForest<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+{
+    type Item = Leaf<C>;
+    type Witness = ShardedPath<C>;
+    type Output = Root<C>;
+}
+
+impl<C, T> Accumulator for ShardedForest<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    type Model = ShardedParameters<C>;
+
+    #[inline]
+    fn model(&self) -> &Self::Model {
+        &self.parameters
+    }
+
+    #[inline]
+    fn insert(&mut self, item: &Self::Item) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let shard = self.parameters.shard_index(&leaf_digest);
+        self.shards[shard].push_provable_digest(&self.parameters.parameters, move || leaf_digest)
+    }
+
+    #[inline]
+    fn prove(&self, item: &Self::Item) -> Option<MembershipProof<Self::Model>> {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let shard = self.parameters.shard_index(&leaf_digest);
+        let tree = &self.shards[shard];
+        Some(MembershipProof::new(
+            ShardedPath::new(
+                shard,
+                tree.path(&self.parameters.parameters, tree.position(&leaf_digest)?)
+                    .ok()?,
+            ),
+            tree.root().clone(),
+        ))
+    }
+
+    #[inline]
+    fn contains(&self, item: &Self::Item) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        self.shards[self.parameters.shard_index(&leaf_digest)].contains(&leaf_digest)
+    }
+}
+
+impl<C, T> ExactSizeAccumulator for ShardedForest<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<C, T> OptimizedAccumulator for ShardedForest<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    #[inline]
+    fn insert_nonprovable(&mut self, item: &Self::Item) -> bool {
+        self.push(item)
+    }
+
+    #[inline]
+    fn remove_proof(&mut self, item: &Self::Item) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let tree = &mut self.shards[self.parameters.shard_index(&leaf_digest)];
+        tree.position(&leaf_digest)
+            .map(move |i| tree.remove_path(i))
+            .unwrap_or(false)
+    }
+}
+
+/// Two-Level Merkle Forest Parameters
+///
+/// Membership model of a [`RootAccumulatedForest`]. An item is first opened to the root of its
+/// tree using the `tree` parameters, and that root is then opened as a leaf of the top-level tree
+/// of roots using the `roots` parameters. Verifiers only need the root of the top-level tree.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Parameters<C, COM>: Deserialize<'de>, Parameters<R, COM>: Deserialize<'de>",
+            serialize = "Parameters<C, COM>: Serialize, Parameters<R, COM>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Parameters<C, COM>: Clone, Parameters<R, COM>: Clone"),
+    Copy(bound = "Parameters<C, COM>: Copy, Parameters<R, COM>: Copy"),
+    Debug(bound = "Parameters<C, COM>: Debug, Parameters<R, COM>: Debug"),
+    Default(bound = "Parameters<C, COM>: Default, Parameters<R, COM>: Default"),
+    Eq(bound = "Parameters<C, COM>: Eq, Parameters<R, COM>: Eq"),
+    Hash(bound = "Parameters<C, COM>: Hash, Parameters<R, COM>: Hash"),
+    PartialEq(bound = "Parameters<C, COM>: PartialEq, Parameters<R, COM>: PartialEq")
+)]
+pub struct TwoLevelParameters<C, R, COM = ()>
+where
+    C: tree::HashConfiguration<COM> + ?Sized,
+    R: tree::HashConfiguration<COM> + ?Sized,
+{
+    /// Merkle Tree Parameters
+    pub tree: Parameters<C, COM>,
+
+    /// Merkle Tree of Roots Parameters
+    pub roots: Parameters<R, COM>,
+}
+
+impl<C, R, COM> TwoLevelParameters<C, R, COM>
+where
+    C: tree::HashConfiguration<COM> + ?Sized,
+    R: tree::HashConfiguration<COM> + ?Sized,
+{
+    /// Builds a new [`TwoLevelParameters`] from `tree` and `roots` parameters.
+    #[inline]
+    pub fn new(tree: Parameters<C, COM>, roots: Parameters<R, COM>) -> Self {
+        Self { tree, roots }
+    }
+
+    /// Computes the root of the top-level tree relative to `leaf` using the two-level `path`.
+    #[inline]
+    pub fn root_with(
+        &self,
+        path: &TwoLevelPathVar<C, R, COM>,
+        leaf: &Leaf<C, COM>,
+        compiler: &mut COM,
+    ) -> Root<R, COM>
+    where
+        C: tree::Configuration<COM>,
+        R: tree::Configuration<COM>,
+        <R as tree::HashConfiguration<COM>>::LeafHash: tree::LeafHash<COM, Leaf = Root<C, COM>>,
+        COM: Has<bool>,
+        InnerDigest<C, COM>: ConditionalSwap<COM>,
+        LeafDigest<C, COM>: ConditionalSwap<COM>,
+        InnerDigest<R, COM>: ConditionalSwap<COM>,
+        LeafDigest<R, COM>: ConditionalSwap<COM>,
+    {
+        let tree_root =
+            path.path
+                .root(&self.tree, &self.tree.digest_with(leaf, compiler), compiler);
+        let root_digest = self.roots.digest_with(&tree_root, compiler);
+        path.root_path.root(&self.roots, &root_digest, compiler)
+    }
+
+    /// Verify that `path` witnesses the fact that `leaf` is a member of a tree whose root is
+    /// stored in the top-level tree with the given `root`.
+    #[inline]
+    pub fn verify_path_with(
+        &self,
+        path: &TwoLevelPathVar<C, R, COM>,
+        root: &Root<R, COM>,
+        leaf: &Leaf<C, COM>,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        C: tree::Configuration<COM>,
+        R: tree::Configuration<COM>,
+        <R as tree::HashConfiguration<COM>>::LeafHash: tree::LeafHash<COM, Leaf = Root<C, COM>>,
+        COM: Has<bool>,
+        InnerDigest<C, COM>: ConditionalSwap<COM>,
+        LeafDigest<C, COM>: ConditionalSwap<COM>,
+        InnerDigest<R, COM>:
+            ConditionalSwap<COM> + eclair::cmp::PartialEq<InnerDigest<R, COM>, COM>,
+        LeafDigest<R, COM>: ConditionalSwap<COM>,
+    {
+        let tree_root =
+            path.path
+                .root(&self.tree, &self.tree.digest_with(leaf, compiler), compiler);
+        let root_digest = self.roots.digest_with(&tree_root, compiler);
+        path.root_path
+            .verify_digest(&self.roots, root, &root_digest, compiler)
+    }
+}
+
+impl<C, R> TwoLevelParameters<C, R>
+where
+    C: tree::HashConfiguration + ?Sized,
+    R: tree::HashConfiguration + ?Sized,
+{
+    /// Computes the root of the top-level tree relative to `leaf` using the two-level `path`.
+    #[inline]
+    pub fn root(&self, path: &TwoLevelPath<C, R>, leaf: &Leaf<C>) -> Root<R>
+    where
+        C: tree::Configuration,
+        R: tree::Configuration,
+        R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    {
+        let tree_root = path.path.root(&self.tree, &self.tree.digest(leaf));
+        path.root_path
+            .root(&self.roots, &self.roots.digest(&tree_root))
+    }
+
+    /// Verify that `path` witnesses the fact that `leaf` is a member of a tree whose root is
+    /// stored in the top-level tree with the given `root`.
+    #[inline]
+    pub fn verify_path(&self, path: &TwoLevelPath<C, R>, root: &Root<R>, leaf: &Leaf<C>) -> bool
+    where
+        C: tree::Configuration,
+        R: tree::Configuration,
+        R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+        InnerDigest<R>: PartialEq,
+    {
+        &self.root(path, leaf) == root
+    }
+}
+
+impl<C, R, COM> Constant<COM> for TwoLevelParameters<C, R, COM>
+where
+    C: tree::HashConfiguration<COM> + Constant<COM> + ?Sized,
+    C::Type: tree::HashConfiguration,
+    R: tree::HashConfiguration<COM> + Constant<COM> + ?Sized,
+    R::Type: tree::HashConfiguration,
+    Parameters<C, COM>: Constant<COM, Type = Parameters<C::Type>>,
+    Parameters<R, COM>: Constant<COM, Type = Parameters<R::Type>>,
+{
+    type Type = TwoLevelParameters<C::Type, R::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.tree.as_constant(compiler),
+            this.roots.as_constant(compiler),
+        )
+    }
+}
+
+impl<C, R> accumulator::Types for TwoLevelParameters<C, R>
+where
+    C: tree::Configuration + ?Sized,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    InnerDigest<R>: PartialEq,
+{
+    type Item = Leaf<C>;
+    type Witness = TwoLevelPath<C, R>;
+    type Output = Root<R>;
+}
+
+impl<C, R> accumulator::Model for TwoLevelParameters<C, R>
+where
+    C: tree::Configuration + ?Sized,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    InnerDigest<R>: PartialEq,
+{
+    type Verification = bool;
+
+    #[inline]
+    fn verify(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        _: &mut (),
+    ) -> Self::Verification {
+        self.verify_path(witness, output, item)
+    }
+}
+
+impl<C, R, COM> accumulator::Types for TwoLevelParameters<C, R, COM>
+where
+    C: tree::Configuration<COM> + ?Sized,
+    R: tree::Configuration<COM> + ?Sized,
+    <R as tree::HashConfiguration<COM>>::LeafHash: tree::LeafHash<COM, Leaf = Root<C, COM>>,
+    COM: Has<bool> + NonNative,
+    InnerDigest<C, COM>: ConditionalSwap<COM>,
+    LeafDigest<C, COM>: ConditionalSwap<COM>,
+    InnerDigest<R, COM>: ConditionalSwap<COM> + eclair::cmp::PartialEq<InnerDigest<R, COM>, COM>,
+    LeafDigest<R, COM>: ConditionalSwap<COM>,
+{
+    type Item = Leaf<C, COM>;
+    type Witness = TwoLevelPathVar<C, R, COM>;
+    type Output = Root<R, COM>;
+}
+
+impl<C, R, COM> accumulator::Model<COM> for TwoLevelParameters<C, R, COM>
+where
+    C: tree::Configuration<COM> + ?Sized,
+    R: tree::Configuration<COM> + ?Sized,
+    <R as tree::HashConfiguration<COM>>::LeafHash: tree::LeafHash<COM, Leaf = Root<C, COM>>,
+    COM: Has<bool> + NonNative,
+    InnerDigest<C, COM>: ConditionalSwap<COM>,
+    LeafDigest<C, COM>: ConditionalSwap<COM>,
+    InnerDigest<R, COM>: ConditionalSwap<COM> + eclair::cmp::PartialEq<InnerDigest<R, COM>, COM>,
+    LeafDigest<R, COM>: ConditionalSwap<COM>,
+{
+    type Verification = Bool<COM>;
+
+    #[inline]
+    fn verify(
+        &self,
+        item: &Self::Item,
+        witness: &Self::Witness,
+        output: &Self::Output,
+        compiler: &mut COM,
+    ) -> Self::Verification {
+        self.verify_path_with(witness, output, item, compiler)
+    }
+}
+
+/// Two-Level Merkle Path
+///
+/// Membership witness of a [`RootAccumulatedForest`], made of the merkle path of an item in its
+/// tree and the merkle path of the root of that tree in the top-level tree of roots.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Path<C>: Deserialize<'de>, Path<R>: Deserialize<'de>",
+            serialize = "Path<C>: Serialize, Path<R>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Path<C>: Clone, Path<R>: Clone"),
+    Debug(bound = "Path<C>: Debug, Path<R>: Debug"),
+    Default(bound = "Path<C>: Default, Path<R>: Default"),
+    Eq(bound = "Path<C>: Eq, Path<R>: Eq"),
+    Hash(bound = "Path<C>: Hash, Path<R>: Hash"),
+    PartialEq(bound = "Path<C>: PartialEq, Path<R>: PartialEq")
+)]
+pub struct TwoLevelPath<C, R>
+where
+    C: tree::Configuration + ?Sized,
+    R: tree::Configuration + ?Sized,
+{
+    /// Merkle Path in the Tree
+    pub path: Path<C>,
+
+    /// Merkle Path of the Tree Root in the Tree of Roots
+    pub root_path: Path<R>,
+}
+
+impl<C, R> TwoLevelPath<C, R>
+where
+    C: tree::Configuration + ?Sized,
+    R: tree::Configuration + ?Sized,
+{
+    /// Builds a new [`TwoLevelPath`] from `path` and `root_path`.
+    #[inline]
+    pub fn new(path: Path<C>, root_path: Path<R>) -> Self {
+        Self { path, root_path }
+    }
+
+    /// Returns the index of the tree which `self` opens into.
+    ///
+    /// Since this index is part of the witness, it is not revealed by in-circuit verification.
+    #[inline]
+    pub fn tree_index(&self) -> usize {
+        self.root_path.leaf_index().0
+    }
+}
+
+/// Two-Level Merkle Path Variable
+pub struct TwoLevelPathVar<C, R, COM>
+where
+    C: tree::Configuration<COM> + ?Sized,
+    R: tree::Configuration<COM> + ?Sized,
+    COM: Has<bool>,
+{
+    /// Merkle Path in the Tree
+    pub path: PathVar<C, COM>,
+
+    /// Merkle Path of the Tree Root in the Tree of Roots
+    pub root_path: PathVar<R, COM>,
+}
+
+impl<C, R, COM> Variable<Secret, COM> for TwoLevelPathVar<C, R, COM>
+where
+    C: tree::Configuration<COM> + Constant<COM> + ?Sized,
+    C::Type: tree::Configuration,
+    R: tree::Configuration<COM> + Constant<COM> + ?Sized,
+    R::Type: tree::Configuration,
+    COM: Has<bool>,
+    PathVar<C, COM>: Variable<Secret, COM, Type = Path<C::Type>>,
+    PathVar<R, COM>: Variable<Secret, COM, Type = Path<R::Type>>,
+{
+    type Type = TwoLevelPath<C::Type, R::Type>;
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self {
+            path: this.path.as_known(compiler),
+            root_path: this.root_path.as_known(compiler),
+        }
+    }
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self {
+            path: compiler.allocate_unknown(),
+            root_path: compiler.allocate_unknown(),
+        }
+    }
+}
+
+/// Root-Accumulated Merkle Forest
+///
+/// Unlike [`MerkleForest`], whose membership proofs open to the root of the tree storing the item,
+/// this forest accumulates the roots of its `N` trees into a top-level merkle tree over `R`, at the
+/// position given by their tree index. Membership proofs are [`TwoLevelPath`]s which verify
+/// against the root of the top-level tree, so verifiers only track a single root and the tree of
+/// an item stays hidden when the path is a secret witness.
+///
+/// The top-level tree is rebuilt from the roots of the trees whenever it is needed, see
+/// [`root_tree`](Self::root_tree).
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "TwoLevelParameters<C, R>: Deserialize<'de>, T: Deserialize<'de>",
+            serialize = "TwoLevelParameters<C, R>: Serialize, T: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "TwoLevelParameters<C, R>: Clone, T: Clone"),
+    Debug(bound = "TwoLevelParameters<C, R>: Debug, T: Debug"),
+    Eq(bound = "TwoLevelParameters<C, R>: Eq, T: Eq"),
+    Hash(bound = "TwoLevelParameters<C, R>: Hash, T: Hash"),
+    PartialEq(bound = "TwoLevelParameters<C, R>: PartialEq, T: PartialEq")
+)]
+pub struct RootAccumulatedForest<C, R, T, const N: usize>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    T: Tree<C>,
+{
+    /// Two-Level Merkle Forest Parameters
+    parameters: TwoLevelParameters<C, R>,
+
+    /// Underlying Forest Structure
+    forest: TreeArray<C, T, N>,
+}
+
+impl<C, R, T, const N: usize> RootAccumulatedForest<C, R, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    T: Tree<C>,
+{
+    /// Builds a new [`RootAccumulatedForest`] from `parameters`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the top-level tree cannot store the roots of all `N` trees.
+    #[inline]
+    pub fn new(parameters: TwoLevelParameters<C, R>) -> Self {
+        Self::from_forest(Forest::new(&parameters.tree), parameters)
+    }
+
+    /// Builds a new [`RootAccumulatedForest`] from a pre-constructed `forest` and `parameters`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the top-level tree cannot store the roots of all `N` trees.
+    #[inline]
+    pub fn from_forest(forest: TreeArray<C, T, N>, parameters: TwoLevelParameters<C, R>) -> Self {
+        assert!(
+            N <= tree::capacity::<R, _>(),
+            "The tree of roots must be able to store the roots of all the trees in the forest."
+        );
+        Self { parameters, forest }
+    }
+
+    /// Returns a shared reference to the parameters used by this merkle forest.
+    #[inline]
+    pub fn parameters(&self) -> &TwoLevelParameters<C, R> {
+        &self.parameters
+    }
+
+    /// Returns a shared reference to the underlying forest structure.
+    #[inline]
+    pub fn forest(&self) -> &TreeArray<C, T, N> {
+        &self.forest
+    }
+
+    /// Returns the number of leaves that can fit in this merkle forest.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.forest.capacity()
+    }
+
+    /// Returns the number of leaves in this merkle forest.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.forest.len()
+    }
+
+    /// Returns `true` if this merkle forest is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.forest.is_empty()
+    }
+
+    /// Inserts `leaf` at the next available leaf node of the tree corresponding with `leaf`,
+    /// returning `false` if the leaf could not be inserted because its tree has exhausted its
+    /// capacity.
+    #[inline]
+    pub fn push(&mut self, leaf: &Leaf<C>) -> bool {
+        self.forest
+            .get_tree_mut(leaf)
+            .push(&self.parameters.tree, leaf)
+    }
+
+    /// Builds the top-level tree whose leaves are the roots of the trees in this forest.
+    ///
+    /// This computes the leaf digests of all `N` roots, so callers which need several values from
+    /// the top-level tree should build it once and reuse it.
+    #[inline]
+    pub fn root_tree(&self) -> Full<R>
+    where
+        R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+        LeafDigest<R>: Clone + Default,
+        InnerDigest<R>: Clone + Default + PartialEq,
+    {
+        Full::from_iter(
+            &self.parameters.roots,
+            self.forest.as_ref().iter().map(|tree| tree.root()),
+        )
+        .expect("The tree of roots is large enough to store all the roots of the forest.")
+    }
+
+    /// Returns the root of the top-level tree, which is the accumulated value of this forest.
+    #[inline]
+    pub fn root(&self) -> Root<R>
+    where
+        R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+        LeafDigest<R>: Clone + Default,
+        InnerDigest<R>: Clone + Default + PartialEq,
+    {
+        self.root_tree().root().clone()
+    }
+}
+
+impl<C, R, T, const N: usize> accumulator::Types for RootAccumulatedForest<C, R, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    T: Tree<C>,
+    InnerDigest<R>: PartialEq,
+{
+    type Item = Leaf<C>;
+    type Witness = TwoLevelPath<C, R>;
+    type Output = Root<R>;
+}
+
+impl<C, R, T, const N: usize> Accumulator for RootAccumulatedForest<C, R, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    T: Tree<C> + WithProofs<C>,
+    InnerDigest<C>: Clone + PartialEq,
+    LeafDigest<R>: Clone + Default + PartialEq,
+    InnerDigest<R>: Clone + Default + PartialEq,
+{
+    type Model = TwoLevelParameters<C, R>;
+
+    #[inline]
+    fn model(&self) -> &Self::Model {
+        self.parameters()
+    }
+
+    #[inline]
+    fn insert(&mut self, item: &Self::Item) -> bool {
+        self.forest
+            .get_tree_mut(item)
+            .push_provable(&self.parameters.tree, item)
+    }
+
+    #[inline]
+    fn prove(&self, item: &Self::Item) -> Option<MembershipProof<Self::Model>> {
+        let index: usize = C::tree_index(item).into();
+        let tree = &self.forest.as_ref()[index];
+        let path = tree
+            .path(
+                &self.parameters.tree,
+                tree.position(&self.parameters.tree.digest(item))?,
+            )
+            .ok()?;
+        let root_tree = self.root_tree();
+        let root_path = root_tree.path(&self.parameters.roots, index).ok()?;
+        Some(MembershipProof::new(
+            TwoLevelPath::new(path, root_path),
+            root_tree.root().clone(),
+        ))
+    }
+
+    #[inline]
+    fn contains(&self, item: &Self::Item) -> bool {
+        self.forest
+            .get_tree(item)
+            .contains(&self.parameters.tree.digest(item))
+    }
+}
+
+impl<C, R, T, const N: usize> ConstantCapacityAccumulator for RootAccumulatedForest<C, R, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    T: Tree<C> + WithProofs<C>,
+    InnerDigest<C>: Clone + PartialEq,
+    LeafDigest<R>: Clone + Default + PartialEq,
+    InnerDigest<R>: Clone + Default + PartialEq,
+{
+    #[inline]
+    fn capacity() -> usize {
+        capacity::<C, TreeArray<C, T, N>>()
+    }
+}
+
+impl<C, R, T, const N: usize> ExactSizeAccumulator for RootAccumulatedForest<C, R, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    T: Tree<C> + WithProofs<C>,
+    InnerDigest<C>: Clone + PartialEq,
+    LeafDigest<R>: Clone + Default + PartialEq,
+    InnerDigest<R>: Clone + Default + PartialEq,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<C, R, T, const N: usize> OptimizedAccumulator for RootAccumulatedForest<C, R, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    R: tree::Configuration + ?Sized,
+    R::LeafHash: tree::LeafHash<Leaf = Root<C>>,
+    T: Tree<C> + WithProofs<C>,
+    InnerDigest<C>: Clone + PartialEq,
+    LeafDigest<R>: Clone + Default + PartialEq,
+    InnerDigest<R>: Clone + Default + PartialEq,
+{
+    #[inline]
+    fn insert_nonprovable(&mut self, item: &Self::Item) -> bool {
+        self.push(item)
+    }
+
+    #[inline]
+    fn remove_proof(&mut self, item: &Self::Item) -> bool {
+        let tree = self.forest.get_tree_mut(item);
+        tree.position(&self.parameters.tree.digest(item))
+            .map(move |i| tree.remove_path(i))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::{accumulator::Model, merkle_tree::test::Test};
+    use openzl_util::rand::{RngCore, TestRng};
+
+    /// Test Merkle Tree Configuration with four Leaves per Shard
+    type Config = Test<u64, 3>;
+
+    /// Sharded Forest with Full Shards
+    type Forest = ShardedForest<Config, Full<Config>>;
+
+    /// Number of Leaf Digest Prefix Bits
+    const PREFIX_BITS: u32 = 2;
+
+    /// Samples a leaf which is routed to `shard`.
+    #[inline]
+    fn leaf_in_shard(shard: usize, rng: &mut TestRng) -> u64 {
+        ((shard as u64) << (u64::BITS - PREFIX_BITS)) | (rng.next_u64() >> PREFIX_BITS)
+    }
+
+    /// Builds a sharded forest holding three leaves in every shard, returning the forest and the
+    /// leaves of each shard.
+    #[inline]
+    fn sample_forest(rng: &mut TestRng) -> (Forest, Vec<Vec<u64>>) {
+        let mut forest = Forest::new(Default::default(), PREFIX_BITS);
+        let leaves = (0..1 << PREFIX_BITS)
+            .map(|shard| {
+                (0..3)
+                    .map(|_| leaf_in_shard(shard, rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for leaf in leaves.iter().flatten() {
+            assert!(forest.insert(leaf), "The leaf should have been inserted.");
+        }
+        (forest, leaves)
+    }
+
+    /// Tests that leaves are routed to the shard selected by the prefix of their digest and that
+    /// a full shard rejects new leaves without affecting the other shards.
+    #[test]
+    fn sharded_forest_routes_leaves_by_prefix() {
+        let mut rng = TestRng::new();
+        let (mut forest, leaves) = sample_forest(&mut rng);
+        assert_eq!(
+            forest.shards().len(),
+            4,
+            "There should be one shard per prefix."
+        );
+        assert_eq!(
+            forest.capacity(),
+            16,
+            "Every shard should hold four leaves."
+        );
+        assert_eq!(forest.len(), 12, "Every inserted leaf should be counted.");
+        for (shard, shard_leaves) in leaves.iter().enumerate() {
+            assert_eq!(
+                forest.shard(shard).map(|tree| tree.len()),
+                Some(3),
+                "Every shard should hold the leaves with its prefix."
+            );
+            for leaf in shard_leaves {
+                assert_eq!(
+                    forest.shard_index(leaf),
+                    shard,
+                    "The shard index should be the prefix of the leaf digest."
+                );
+                assert!(
+                    forest.contains(leaf),
+                    "The forest should contain every inserted leaf."
+                );
+            }
+        }
+        assert!(
+            forest.shard(4).is_none(),
+            "There should be no shard beyond the prefix range."
+        );
+        assert!(
+            forest.push(&leaf_in_shard(1, &mut rng)),
+            "The last slot of a shard should accept a leaf."
+        );
+        assert!(
+            !forest.push(&leaf_in_shard(1, &mut rng)),
+            "A full shard should reject new leaves."
+        );
+        assert!(
+            forest.insert(&leaf_in_shard(2, &mut rng)),
+            "The other shards should still accept leaves."
+        );
+        assert_eq!(forest.len(), 14, "Rejected leaves should not be counted.");
+    }
+
+    /// Tests that membership proofs are tagged with the shard of the leaf and verify against the
+    /// root of that shard, but not against the root or tag of any other shard.
+    #[test]
+    fn sharded_proofs_verify_only_against_their_shard() {
+        let mut rng = TestRng::new();
+        let (forest, leaves) = sample_forest(&mut rng);
+        let model = forest.model();
+        for (shard, shard_leaves) in leaves.iter().enumerate() {
+            for leaf in shard_leaves {
+                let proof = forest
+                    .prove(leaf)
+                    .expect("The inserted leaf should have a membership proof.");
+                assert!(
+                    proof.verify(model, leaf, &mut ()),
+                    "The proof should verify against its own shard."
+                );
+                let (witness, output) = proof.into_parts();
+                assert_eq!(
+                    witness.shard, shard,
+                    "The proof should be tagged with the shard of the leaf."
+                );
+                assert_eq!(
+                    &output,
+                    forest.shard(shard).expect("The shard exists.").root(),
+                    "The proof should open to the root of the shard of the leaf."
+                );
+                for other in (0..forest.shards().len()).filter(|other| *other != shard) {
+                    let other_root = forest.shard(other).expect("The shard exists.").root();
+                    assert!(
+                        !model.verify(leaf, &witness, other_root, &mut ()),
+                        "The proof should not verify against the root of another shard."
+                    );
+                    let retagged = ShardedPath::new(other, witness.path.clone());
+                    assert!(
+                        !model.verify(leaf, &retagged, &output, &mut ()),
+                        "The proof should not verify when tagged with another shard."
+                    );
+                    assert!(
+                        !model.verify(leaf, &retagged, other_root, &mut ()),
+                        "The proof should not verify when moved to another shard."
+                    );
+                }
+            }
+        }
+        assert!(
+            forest.prove(&leaf_in_shard(3, &mut rng)).is_none(),
+            "Leaves which were not inserted should not have a membership proof."
+        );
+    }
+}
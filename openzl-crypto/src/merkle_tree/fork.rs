@@ -120,6 +120,79 @@ where
         }
     }
 
+    /// Returns the number of forks which are currently attached to `self`.
+    #[inline]
+    pub fn fork_count(&self) -> usize {
+        P::weak_count(self.borrow_base())
+    }
+
+    /// Detaches `fork` from `self`, returning `false` if `fork` was not attached to `self`.
+    ///
+    /// The detached fork keeps its leaves and can be attached again with [`attach`](Self::attach)
+    /// or [`Fork::attach`].
+    #[inline]
+    pub fn detach<M>(&self, fork: &mut Fork<C, T, P, M>) -> bool
+    where
+        M: Default + InnerMap<C>,
+    {
+        if !fork.is_attached_to(self) {
+            return false;
+        }
+        fork.detach();
+        true
+    }
+
+    /// Moves every fork in `forks` which is not attached to `self` out of `forks` and returns
+    /// them, releasing the pointers they still hold to previous base trees. Both the forks which
+    /// remain in `forks` and the returned forks keep their relative order.
+    ///
+    /// After a [`merge`](Self::merge), every fork of the old base tree is detached but still keeps
+    /// the allocation of the old base tree alive through its weak pointer. Long-running services
+    /// which keep forks around should call this method to find those forks and drop or re-attach
+    /// them.
+    #[inline]
+    pub fn split_off_detached<M>(&self, forks: &mut Vec<Fork<C, T, P, M>>) -> Vec<Fork<C, T, P, M>>
+    where
+        M: Default + InnerMap<C>,
+    {
+        let (attached, mut detached): (Vec<_>, Vec<_>) = mem::take(forks)
+            .into_iter()
+            .partition(|fork| fork.is_attached_to(self));
+        *forks = attached;
+        for fork in &mut detached {
+            fork.detach();
+        }
+        detached
+    }
+
+    /// Removes the paths of the leaves at the given `indices` from the base tree, returning the
+    /// number of paths which were removed.
+    ///
+    /// # Crypto Safety
+    ///
+    /// Like [`merge`](Self::merge), this method rebuilds the base tree so it automatically detaches
+    /// all of the forks associated to this trunk. Use [`Fork::attach`] or [`attach`](Self::attach)
+    /// to attach them again.
+    ///
+    /// # Implementation Note
+    ///
+    /// Whether any memory is released depends on the [`WithProofs::remove_path`] implementation
+    /// of the base tree, which is only an optimization path.
+    #[inline]
+    pub fn compact<I>(&mut self, indices: I) -> usize
+    where
+        T: WithProofs<C>,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut base = P::claim(mem::take(&mut self.base).unwrap());
+        let removed = indices
+            .into_iter()
+            .filter(|index| base.remove_path(*index))
+            .count();
+        self.base = Some(P::new(base));
+        removed
+    }
+
     /// Performs a merge of the `branch` onto `fork_base`, setting `self` equal to the resulting
     /// merged tree.
     #[inline]
@@ -282,7 +355,7 @@ where
         LeafDigest<C>: Clone + Default,
         InnerDigest<C>: Default,
     {
-        if leaf_digests.len() + base.len() >= capacity::<C, _>() {
+        if leaf_digests.len() + base.len() > capacity::<C, _>() {
             return None;
         }
         Some(Self::new_unchecked(parameters, base, leaf_digests))
@@ -382,7 +455,9 @@ where
         LeafDigest<C>: Clone + Default,
         InnerDigest<C>: Default,
     {
-        if self.data.len() + base.len() - (self.base_contribution as usize) >= capacity::<C, _>() {
+        if self.data.leaf_digests().len() - (self.base_contribution as usize) + base.len()
+            > capacity::<C, _>()
+        {
             return false;
         }
         let new_branch = Self::new_unchecked(
@@ -617,6 +692,18 @@ where
         true
     }
 
+    /// Detaches `self` from its [`Trunk`], returning `true` if `self` was attached.
+    ///
+    /// Detaching releases the pointer to the base tree, so a dropped or merged base tree is no
+    /// longer kept alive by `self`. The leaves of `self` are kept and it can be attached again with
+    /// [`attach`](Self::attach).
+    #[inline]
+    pub fn detach(&mut self) -> bool {
+        let was_attached = self.is_attached();
+        self.base = Default::default();
+        was_attached
+    }
+
     /// Returns `true` if this fork is attached to some [`Trunk`].
     #[inline]
     pub fn is_attached(&self) -> bool {
@@ -987,3 +1074,125 @@ where
         self.path(parameters, index)
     }
 }
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::{
+        full::Full,
+        test::{assert_model_agreement, random_operations, Test},
+    };
+    use alloc::string::{String, ToString};
+    use openzl_util::rand::{RngCore, TestRng};
+
+    #[cfg(feature = "std")]
+    use crate::merkle_tree::inner_tree::HashMap;
+
+    /// Test Merkle Tree Configuration
+    type Config = Test<String, 5>;
+
+    /// Tests that forked trees, trunks, and their forks agree with the naive model on random
+    /// sequences of operations.
+    #[test]
+    fn forks_agree_with_naive_model() {
+        let mut rng = TestRng::new();
+        let parameters = Parameters::<Config>::default();
+        for _ in 0..16 {
+            let operations = random_operations(128, &mut rng, |rng| rng.next_u64().to_string());
+            assert_model_agreement::<Config, Full<Config>>(&parameters, &operations);
+            #[cfg(feature = "std")]
+            assert_model_agreement::<Config, Full<Config, HashMap<Config>>>(
+                &parameters,
+                &operations,
+            );
+        }
+    }
+
+    /// Builds a fork of `trunk` holding the single leaf `leaf`.
+    #[inline]
+    fn fork_with(
+        parameters: &Parameters<Config>,
+        trunk: &Trunk<Config, Full<Config>>,
+        leaf: &str,
+    ) -> Fork<Config, Full<Config>> {
+        let mut fork = trunk.fork(parameters);
+        assert_eq!(fork.push(parameters, &leaf.to_string()), Some(true));
+        fork
+    }
+
+    /// Returns the leaves of `forks` in order.
+    #[inline]
+    fn leaves(forks: &[Fork<Config, Full<Config>>]) -> Vec<&str> {
+        forks
+            .iter()
+            .map(|fork| {
+                fork.current_leaf()
+                    .expect("Every fork holds a leaf.")
+                    .as_str()
+            })
+            .collect()
+    }
+
+    /// Checks that detaching, merging, and compacting detach forks and update the fork count of
+    /// their trunk, and that detached forks are split off in order.
+    #[test]
+    fn detached_forks_release_their_trunk() {
+        let parameters = Parameters::<Config>::default();
+        let mut trunk = Trunk::<Config, Full<Config>>::new(Full::new(&parameters));
+        let mut forks =
+            Vec::from(["a", "b", "c", "d"].map(|leaf| fork_with(&parameters, &trunk, leaf)));
+        assert_eq!(trunk.fork_count(), 4, "Every fork must be counted.");
+        assert!(trunk.detach(&mut forks[1]), "Attached forks must detach.");
+        assert!(
+            !trunk.detach(&mut forks[1]),
+            "Detached forks must not detach again."
+        );
+        assert!(!forks[1].is_attached());
+        assert_eq!(forks[1].len(), 1, "Detached forks must keep their leaves.");
+        assert_eq!(trunk.fork_count(), 3, "Detaching must release the fork.");
+        let detached = trunk.split_off_detached(&mut forks);
+        assert_eq!(
+            leaves(&forks),
+            ["a", "c", "d"],
+            "Attached forks must keep their order."
+        );
+        assert_eq!(leaves(&detached), ["b"]);
+        assert_eq!(trunk.fork_count(), 3);
+        let merged = forks.remove(1);
+        assert!(
+            trunk.merge(&parameters, merged).is_ok(),
+            "Attached forks must merge."
+        );
+        assert_eq!(trunk.len(), 1, "Merging must add the leaves of the fork.");
+        assert_eq!(trunk.fork_count(), 0, "Merging must detach every fork.");
+        assert!(forks.iter().all(|fork| !fork.is_attached()));
+        let detached = trunk.split_off_detached(&mut forks);
+        assert!(forks.is_empty());
+        assert_eq!(
+            leaves(&detached),
+            ["a", "d"],
+            "Detached forks must keep their order."
+        );
+        let mut forks = detached;
+        for fork in &mut forks {
+            assert!(
+                trunk.attach(&parameters, fork),
+                "Detached forks must re-attach."
+            );
+        }
+        assert_eq!(trunk.fork_count(), 2);
+        assert_eq!(
+            forks[0].len(),
+            2,
+            "Re-attached forks must catch up with their trunk."
+        );
+        assert_eq!(trunk.compact([0]), 0, "Full trees must keep every path.");
+        assert_eq!(
+            trunk.len(),
+            1,
+            "Compacting must keep the leaves of the trunk."
+        );
+        assert_eq!(trunk.fork_count(), 0, "Compacting must detach every fork.");
+        assert!(forks.iter().all(|fork| !fork.is_attached()));
+    }
+}
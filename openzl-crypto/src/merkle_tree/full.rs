@@ -4,7 +4,8 @@
 
 use crate::merkle_tree::{
     capacity,
-    inner_tree::{BTreeMap, InnerMap, InnerTree},
+    inner_tree::{BTreeMap, InnerMap, InnerTree, InnerTreePathIter},
+    path::LazyPath,
     Configuration, CurrentPath, InnerDigest, LeafDigest, MerkleTree, Node, Parameters, Path,
     PathError, Root, Tree, WithProofs,
 };
@@ -77,6 +78,12 @@ where
         &self.leaf_digests
     }
 
+    /// Returns the inner digests currently stored in the merkle tree.
+    #[inline]
+    pub fn inner_digests(&self) -> &InnerTree<C, M> {
+        &self.inner_digests
+    }
+
     /// Returns the leaf digests stored in the tree, dropping the rest of the tree data.
     #[inline]
     pub fn into_leaves(self) -> Vec<LeafDigest<C>> {
@@ -116,6 +123,41 @@ where
         self.get_leaf_sibling(index).cloned().unwrap_or_default()
     }
 
+    /// Returns the path for the leaf stored at the given `index` if it exists, borrowing its inner
+    /// digests from `self`.
+    ///
+    /// This is the allocation-free variant of [`WithProofs::path`].
+    #[inline]
+    pub fn path_iter(&self, index: usize) -> Result<LazyPath<C, InnerTreePathIter<C, M>>, PathError>
+    where
+        LeafDigest<C>: Clone + Default,
+    {
+        let length = self.len();
+        if index > 0 && index >= length {
+            return Err(PathError::IndexTooLarge { length });
+        }
+        let leaf_index = Node(index);
+        Ok(LazyPath::new(
+            self.get_owned_leaf_sibling(leaf_index),
+            leaf_index,
+            self.inner_digests.path_iter_for_leaf(leaf_index),
+        ))
+    }
+
+    /// Returns the path for the current (right-most) leaf of the tree, borrowing its inner digests
+    /// from `self`, or `None` if the tree is empty.
+    ///
+    /// This is the allocation-free variant of [`Tree::current_path`].
+    #[inline]
+    pub fn current_path_iter(&self) -> Option<LazyPath<C, InnerTreePathIter<C, M>>>
+    where
+        LeafDigest<C>: Clone + Default,
+    {
+        self.len()
+            .checked_sub(1)
+            .and_then(|index| self.path_iter(index).ok())
+    }
+
     /// Appends a `leaf_digest` with index given by `leaf_index` into the tree.
     #[inline]
     fn push_leaf_digest(
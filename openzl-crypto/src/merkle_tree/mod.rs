@@ -10,6 +10,7 @@
 mod node;
 mod tree;
 
+pub mod ciphertext;
 pub mod forest;
 pub mod fork;
 pub mod full;
@@ -17,6 +18,12 @@ pub mod inner_tree;
 pub mod partial;
 pub mod path;
 pub mod single_path;
+pub mod sparse;
+pub mod stream;
+
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub mod sync;
 
 #[cfg(feature = "test")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
@@ -3,8 +3,9 @@
 use crate::merkle_tree::{HashConfiguration, InnerDigest, InnerHash, LeafDigest, Parameters};
 use core::{
     iter::FusedIterator,
-    ops::{Add, Sub},
+    ops::{Add, Range, Sub},
 };
+use openzl_util::codec::{Decode, DecodeError, Encode, Read, Write};
 
 #[cfg(feature = "serde")]
 use openzl_util::serde::{Deserialize, Serialize};
@@ -260,6 +261,76 @@ impl Node {
         NodeParents { index: *self }
     }
 
+    /// Returns the ancestor of `self` which is `generations` levels above it.
+    ///
+    /// For `generations = 1` this is the same as [`parent`](Self::parent), and for
+    /// `generations = 0` this is `self`.
+    #[inline]
+    #[must_use]
+    pub const fn ancestor(&self, generations: usize) -> Self {
+        if generations >= usize::BITS as usize {
+            Self(0)
+        } else {
+            Self(self.0 >> generations)
+        }
+    }
+
+    /// Returns `true` if `self` is a descendant of `ancestor` which sits `generations` levels
+    /// above `self`. Every node is its own descendant at `generations = 0`.
+    #[inline]
+    pub const fn is_descendant_of(&self, ancestor: &Self, generations: usize) -> bool {
+        self.ancestor(generations).0 == ancestor.0
+    }
+
+    /// Returns the leftmost descendant of `self` which is `height` levels below it.
+    ///
+    /// When `height` is the distance from `self` to the leaves, this is the leftmost leaf of the
+    /// subtree rooted at `self`.
+    #[inline]
+    #[must_use]
+    pub const fn leftmost_leaf(&self, height: usize) -> Self {
+        Self(self.0 << height)
+    }
+
+    /// Returns the rightmost descendant of `self` which is `height` levels below it.
+    ///
+    /// When `height` is the distance from `self` to the leaves, this is the rightmost leaf of the
+    /// subtree rooted at `self`.
+    #[inline]
+    #[must_use]
+    pub const fn rightmost_leaf(&self, height: usize) -> Self {
+        Self(((self.0 + 1) << height) - 1)
+    }
+
+    /// Returns the range of indices of the descendants of `self` which are `height` levels below
+    /// it.
+    #[inline]
+    pub const fn leaf_range(&self, height: usize) -> Range<usize> {
+        self.leftmost_leaf(height).0..(self.rightmost_leaf(height).0 + 1)
+    }
+
+    /// Returns the lowest common ancestor of `lhs` and `rhs`, which must be on the same level,
+    /// together with the number of levels between it and `lhs`.
+    ///
+    /// The subtree under the returned node is the smallest subtree that covers every node between
+    /// `lhs` and `rhs`.
+    #[inline]
+    pub const fn common_ancestor(lhs: &Self, rhs: &Self) -> (Self, usize) {
+        let generations = (usize::BITS - (lhs.0 ^ rhs.0).leading_zeros()) as usize;
+        (lhs.ancestor(generations), generations)
+    }
+
+    /// Returns an iterator over the smallest set of disjoint subtrees which cover exactly the
+    /// nodes in `range`, on the level of `self`.
+    ///
+    /// The subtrees are returned from left to right as pairs of their root and their height above
+    /// the level of `range`. Proving or pruning every leaf in `range` only needs to visit these
+    /// roots instead of every leaf. See [`SubtreeCover`] for more.
+    #[inline]
+    pub const fn cover(range: Range<usize>) -> SubtreeCover {
+        SubtreeCover { range }
+    }
+
     /// Combines two inner digests into a new inner digest using `parameters`, swapping the order
     /// of `lhs` and `rhs` depending on the location of `self`.
     #[inline]
@@ -291,6 +362,35 @@ impl Node {
     }
 }
 
+impl Decode for Node {
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        usize::try_from(u64::decode(reader)?)
+            .map(Self)
+            .map_err(|_| DecodeError::Decode(()))
+    }
+}
+
+impl Encode for Node {
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        (self.0 as u64).encode(writer)
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0u64.encoded_len()
+    }
+}
+
 impl<Idx> Add<Idx> for Node<Idx>
 where
     Idx: Add<Output = Idx>,
@@ -417,3 +517,138 @@ impl Iterator for NodeParents {
 }
 
 impl FusedIterator for NodeParents {}
+
+/// Subtree Cover Iterator
+///
+/// An iterator over the maximal subtrees which cover a contiguous range of nodes on a single level
+/// of a binary tree. Each item is the root [`Node`] of a subtree together with its height above
+/// the level of the range, so the subtree covers the range given by
+/// [`leaf_range`](Node::leaf_range) with that height.
+///
+/// This `struct` is created by the [`cover`](Node::cover) method on [`Node`].
+/// See its documentation for more.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SubtreeCover {
+    /// Remaining Range
+    range: Range<usize>,
+}
+
+impl Iterator for SubtreeCover {
+    type Item = (Node, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let start = self.range.start;
+        let remaining = self.range.end - start;
+        let height = start
+            .trailing_zeros()
+            .min(usize::BITS - 1 - remaining.leading_zeros()) as usize;
+        self.range.start += 1 << height;
+        Some((Node(start >> height), height))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.range.is_empty() {
+            (0, Some(0))
+        } else {
+            (1, Some(2 * usize::BITS as usize))
+        }
+    }
+}
+
+impl FusedIterator for SubtreeCover {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    /// Tests that the leaf range of a node matches the descendants found by walking its children.
+    #[test]
+    fn leaf_range_matches_children() {
+        for index in 0..32 {
+            let node = Node(index);
+            let mut level = vec![node];
+            for height in 0..6 {
+                assert_eq!(level.first(), Some(&node.leftmost_leaf(height)));
+                assert_eq!(level.last(), Some(&node.rightmost_leaf(height)));
+                assert_eq!(
+                    level.iter().map(|node| node.0).collect::<Vec<_>>(),
+                    node.leaf_range(height).collect::<Vec<_>>()
+                );
+                for descendant in &level {
+                    assert_eq!(descendant.ancestor(height), node);
+                    assert!(descendant.is_descendant_of(&node, height));
+                }
+                level = level
+                    .into_iter()
+                    .flat_map(|node| {
+                        let (left, right) = node.children();
+                        [left, right]
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    /// Tests that the ancestors of a node agree with its iterator of parents.
+    #[test]
+    fn ancestor_matches_parents() {
+        let node = Node(0b1011_0110);
+        assert_eq!(node.ancestor(0), node);
+        for (generations, parent) in node.parents().take(10).enumerate() {
+            assert_eq!(node.ancestor(generations + 1), parent);
+        }
+        assert_eq!(node.ancestor(usize::BITS as usize), Node(0));
+        assert!(!Node(5).is_descendant_of(&Node(1), 1));
+    }
+
+    /// Tests that the common ancestor of two nodes is the smallest subtree that covers both.
+    #[test]
+    fn common_ancestor_is_minimal() {
+        for lhs in 0..64 {
+            for rhs in lhs..64 {
+                let (ancestor, generations) = Node::common_ancestor(&Node(lhs), &Node(rhs));
+                let range = ancestor.leaf_range(generations);
+                assert!(range.contains(&lhs) && range.contains(&rhs));
+                if generations > 0 {
+                    let (left, right) = ancestor.children();
+                    assert!(!left.leaf_range(generations - 1).contains(&rhs));
+                    assert!(!right.leaf_range(generations - 1).contains(&lhs));
+                }
+            }
+        }
+    }
+
+    /// Tests that the subtree cover of a range is exact, ordered, and uses maximal subtrees.
+    #[test]
+    fn cover_is_exact_and_maximal() {
+        for start in 0..40 {
+            for end in start..40 {
+                let cover = Node::cover(start..end).collect::<Vec<_>>();
+                let leaves = cover
+                    .iter()
+                    .flat_map(|(node, height)| node.leaf_range(*height))
+                    .collect::<Vec<_>>();
+                assert_eq!(leaves, (start..end).collect::<Vec<_>>());
+                for (node, height) in &cover {
+                    let (parent, parent_height) = (node.parent(), height + 1);
+                    let parent_range = parent.leaf_range(parent_height);
+                    assert!(
+                        parent_range.start < start || parent_range.end > end,
+                        "The subtree should be maximal."
+                    );
+                }
+                assert!(cover.len() <= 2 * (usize::BITS as usize));
+            }
+        }
+        assert_eq!(
+            Node::cover(3..12).collect::<Vec<_>>(),
+            vec![(Node(3), 0), (Node(1), 2), (Node(2), 2)]
+        );
+    }
+}
@@ -3,9 +3,13 @@
 // TODO: Move some methods to a `raw` module for paths.
 // TODO: Move to a uniform interface for native and circuit paths.
 
-use crate::merkle_tree::{
-    inner_tree::{InnerNode, InnerNodeIter},
-    path_length, Configuration, InnerDigest, Leaf, LeafDigest, Node, Parameters, Parity, Root,
+use crate::{
+    merkle_tree::{
+        inner_tree::{InnerNode, InnerNodeIter},
+        path_length, try_path_length, Configuration, Error, InnerDigest, Leaf, LeafDigest, Node,
+        Parameters, Parity, Root,
+    },
+    Decode, Encode,
 };
 use alloc::vec::{self, Vec};
 use core::{
@@ -15,7 +19,7 @@ use core::{
     iter::FusedIterator,
     mem,
     ops::{Index, IndexMut},
-    slice::SliceIndex,
+    slice::{self, SliceIndex},
 };
 use openzl_util::derivative;
 
@@ -40,7 +44,7 @@ pub(super) mod prelude {
         deny_unknown_fields
     )
 )]
-#[derive(derivative::Derivative)]
+#[derive(derivative::Derivative, Decode, Encode)]
 #[derivative(
     Clone(bound = "InnerDigest<C>: Clone"),
     Debug(bound = "InnerDigest<C>: Debug"),
@@ -76,6 +80,20 @@ where
         Self { leaf_index, path }
     }
 
+    /// Builds a new [`InnerPath`] from `leaf_index` and `path`, returning an error if `path` does
+    /// not have length given by [`path_length`].
+    #[inline]
+    pub fn try_new(leaf_index: Node, path: Vec<InnerDigest<C>>) -> Result<Self, Error> {
+        let expected = try_path_length::<C, _>()?;
+        if path.len() != expected {
+            return Err(Error::InvalidPathLength {
+                expected,
+                found: path.len(),
+            });
+        }
+        Ok(Self::new(leaf_index, path))
+    }
+
     /// Checks if `self` could represent the [`CurrentInnerPath`] of some tree.
     #[inline]
     pub fn is_current(&self) -> bool
@@ -395,17 +413,19 @@ where
         })
     }
 
-    /// Updates `self` to the next current path with `next_leaf_digest`, updating `leaf_digest`
-    /// and `sibling_digest` as needed.
+    /// Advances `self` to the next current path with `next_leaf_digest`, updating `leaf_digest`
+    /// and `sibling_digest` as needed, without computing the new root.
+    ///
+    /// Only the subtrees completed by the previous leaf are hashed, so advancing over `n` leaves
+    /// takes `O(n)` hashes in total.
     #[inline]
-    fn update(
+    fn advance(
         &mut self,
         parameters: &Parameters<C>,
         leaf_digest: &mut LeafDigest<C>,
         sibling_digest: &mut LeafDigest<C>,
         next_leaf_digest: LeafDigest<C>,
-    ) -> Root<C>
-    where
+    ) where
         LeafDigest<C>: Default,
         InnerDigest<C>: Default,
     {
@@ -418,10 +438,8 @@ where
                     &mem::take(sibling_digest),
                     &mem::replace(leaf_digest, next_leaf_digest),
                 );
-                let mut accumulator = parameters.join_leaves(leaf_digest, sibling_digest);
                 let default_inner_digest = Default::default();
                 let mut i = 0;
-                let mut depth = 0;
                 while !Node::are_siblings(&last_index.into_parent(), &index.into_parent()) {
                     last_accumulator = Self::fold_fn(
                         parameters,
@@ -434,27 +452,33 @@ where
                             next
                         },
                     );
-                    accumulator = parameters.join(&accumulator, &default_inner_digest);
-                    depth += 1;
                 }
                 mem::drop(self.path.drain(0..i));
                 self.path.insert(0, last_accumulator);
-                accumulator = parameters.join(&self.path[0], &accumulator);
-                Self::fold(
-                    parameters,
-                    depth + 1,
-                    index,
-                    accumulator,
-                    &default_inner_digest,
-                    &self.path[1..],
-                )
             }
             Parity::Right => {
                 *sibling_digest = mem::replace(leaf_digest, next_leaf_digest);
-                self.root(parameters, leaf_digest, sibling_digest)
             }
         }
     }
+
+    /// Updates `self` to the next current path with `next_leaf_digest`, updating `leaf_digest`
+    /// and `sibling_digest` as needed.
+    #[inline]
+    fn update(
+        &mut self,
+        parameters: &Parameters<C>,
+        leaf_digest: &mut LeafDigest<C>,
+        sibling_digest: &mut LeafDigest<C>,
+        next_leaf_digest: LeafDigest<C>,
+    ) -> Root<C>
+    where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        self.advance(parameters, leaf_digest, sibling_digest, next_leaf_digest);
+        self.root(parameters, leaf_digest, sibling_digest)
+    }
 }
 
 impl<C> From<CurrentPath<C>> for CurrentInnerPath<C>
@@ -616,7 +640,7 @@ impl<C> FusedIterator for CurrentInnerPathNodeIter<C> where C: Configuration + ?
         deny_unknown_fields
     )
 )]
-#[derive(derivative::Derivative)]
+#[derive(derivative::Derivative, Decode, Encode)]
 #[derivative(
     Clone(bound = "LeafDigest<C>: Clone, InnerDigest<C>: Clone"),
     Debug(bound = "LeafDigest<C>: Debug, InnerDigest<C>: Debug"),
@@ -650,6 +674,20 @@ where
         Self::from_inner(sibling_digest, InnerPath::new(leaf_index, path))
     }
 
+    /// Builds a new [`Path`] from `sibling_digest`, `leaf_index`, and `path`, returning an error
+    /// if `path` does not have length given by [`path_length`].
+    #[inline]
+    pub fn try_new(
+        sibling_digest: LeafDigest<C>,
+        leaf_index: Node,
+        path: Vec<InnerDigest<C>>,
+    ) -> Result<Self, Error> {
+        Ok(Self::from_inner(
+            sibling_digest,
+            InnerPath::try_new(leaf_index, path)?,
+        ))
+    }
+
     /// Builds a new [`Path`] from `sibling_digest` and `inner_path`.
     #[inline]
     pub fn from_inner(sibling_digest: LeafDigest<C>, inner_path: InnerPath<C>) -> Self {
@@ -716,6 +754,69 @@ where
     {
         self.verify_digest(parameters, root, &parameters.digest(leaf))
     }
+
+    /// Returns `true` if the path given by `sibling_digest`, `leaf_index`, and the inner digests
+    /// yielded by `inner_path` is a witness to the fact that `leaf_digest` is stored in a merkle
+    /// tree with the given `root`.
+    ///
+    /// This is the same as [`verify_digest`](Self::verify_digest) except that the inner path is
+    /// consumed lazily, so it does not need to be collected into a [`Path`] first. Since the
+    /// iterator is not checked ahead of time, this method returns `false` whenever `inner_path`
+    /// does not yield exactly [`path_length`] digests, like [`Path::try_new`] would reject it.
+    #[inline]
+    pub fn verify_digest_iter<'d, I>(
+        parameters: &'d Parameters<C>,
+        root: &Root<C>,
+        leaf_digest: &LeafDigest<C>,
+        sibling_digest: &LeafDigest<C>,
+        leaf_index: Node,
+        inner_path: I,
+    ) -> bool
+    where
+        InnerDigest<C>: 'd + PartialEq,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        let expected = path_length::<C, _>();
+        let mut length = 0;
+        let computed_root = InnerPath::fold(
+            parameters,
+            leaf_index,
+            leaf_index.join_leaves(parameters, leaf_digest, sibling_digest),
+            inner_path
+                .into_iter()
+                .take(expected + 1)
+                .inspect(|_| length += 1),
+        );
+        length == expected && root == &computed_root
+    }
+
+    /// Returns `true` if the path given by `sibling_digest`, `leaf_index`, and the inner digests
+    /// yielded by `inner_path` is a witness to the fact that `leaf` is stored in a merkle tree with
+    /// the given `root`.
+    ///
+    /// See [`verify_digest_iter`](Self::verify_digest_iter) for more.
+    #[inline]
+    pub fn verify_iter<'d, I>(
+        parameters: &'d Parameters<C>,
+        root: &Root<C>,
+        leaf: &Leaf<C>,
+        sibling_digest: &LeafDigest<C>,
+        leaf_index: Node,
+        inner_path: I,
+    ) -> bool
+    where
+        InnerDigest<C>: 'd + PartialEq,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        Self::verify_digest_iter(
+            parameters,
+            root,
+            &parameters.digest(leaf),
+            sibling_digest,
+            leaf_index,
+            inner_path,
+        )
+    }
 }
 
 impl<C> From<CurrentPath<C>> for Path<C>
@@ -753,6 +854,441 @@ where
     }
 }
 
+/// Merkle Tree Lazy Path
+///
+/// This is a [`Path`] whose inner digests are yielded by the `inner_path` iterator instead of
+/// being stored in a vector. Trees return lazy paths which borrow their inner digests from the
+/// underlying storage, so that paths can be verified without allocating.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "LeafDigest<C>: Clone, I: Clone"),
+    Debug(bound = "LeafDigest<C>: Debug, I: Debug")
+)]
+pub struct LazyPath<C, I>
+where
+    C: Configuration + ?Sized,
+{
+    /// Sibling Digest
+    pub sibling_digest: LeafDigest<C>,
+
+    /// Leaf Index
+    pub leaf_index: Node,
+
+    /// Inner Path Iterator
+    ///
+    /// Inner digests are yielded from leaf to root, not including the root.
+    pub inner_path: I,
+}
+
+impl<C, I> LazyPath<C, I>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`LazyPath`] from `sibling_digest`, `leaf_index`, and `inner_path`.
+    #[inline]
+    pub fn new(sibling_digest: LeafDigest<C>, leaf_index: Node, inner_path: I) -> Self {
+        Self {
+            sibling_digest,
+            leaf_index,
+            inner_path,
+        }
+    }
+
+    /// Computes the root of the merkle tree relative to `leaf_digest` using `parameters`.
+    #[inline]
+    pub fn root<'d>(self, parameters: &'d Parameters<C>, leaf_digest: &LeafDigest<C>) -> Root<C>
+    where
+        InnerDigest<C>: 'd,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        InnerPath::fold(
+            parameters,
+            self.leaf_index,
+            self.leaf_index
+                .join_leaves(parameters, leaf_digest, &self.sibling_digest),
+            self.inner_path,
+        )
+    }
+
+    /// Returns `true` if `self` is a witness to the fact that `leaf_digest` is stored in a
+    /// merkle tree with the given `root`.
+    ///
+    /// See [`Path::verify_digest_iter`] for the length check on the inner path iterator.
+    #[inline]
+    pub fn verify_digest<'d>(
+        self,
+        parameters: &'d Parameters<C>,
+        root: &Root<C>,
+        leaf_digest: &LeafDigest<C>,
+    ) -> bool
+    where
+        InnerDigest<C>: 'd + PartialEq,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        Path::verify_digest_iter(
+            parameters,
+            root,
+            leaf_digest,
+            &self.sibling_digest,
+            self.leaf_index,
+            self.inner_path,
+        )
+    }
+
+    /// Returns `true` if `self` is a witness to the fact that `leaf` is stored in a merkle tree
+    /// with the given `root`.
+    #[inline]
+    pub fn verify<'d>(self, parameters: &'d Parameters<C>, root: &Root<C>, leaf: &Leaf<C>) -> bool
+    where
+        InnerDigest<C>: 'd + PartialEq,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        self.verify_digest(parameters, root, &parameters.digest(leaf))
+    }
+
+    /// Collects the inner digests of `self` into an owned [`Path`].
+    #[inline]
+    pub fn into_path<'d>(self) -> Path<C>
+    where
+        InnerDigest<C>: 'd + Clone,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        Path::new(
+            self.sibling_digest,
+            self.leaf_index,
+            self.inner_path.into_iter().cloned().collect(),
+        )
+    }
+
+    /// Collects the inner digests of `self` into an owned [`Path`], returning an error if the
+    /// inner path iterator does not yield exactly [`path_length`] digests.
+    #[inline]
+    pub fn try_into_path<'d>(self) -> Result<Path<C>, Error>
+    where
+        InnerDigest<C>: 'd + Clone,
+        I: IntoIterator<Item = &'d InnerDigest<C>>,
+    {
+        Path::try_new(
+            self.sibling_digest,
+            self.leaf_index,
+            self.inner_path.into_iter().cloned().collect(),
+        )
+    }
+}
+
+/// Merkle Tree Multi-Path
+///
+/// A [`MultiPath`] witnesses the membership of several leaves at once. Sibling digests which can
+/// be computed from the opened leaves themselves are not stored, so the multi-path of `k` leaves
+/// is never larger than `k` independent [`Path`]s and is much smaller when the leaves are close to
+/// each other in the tree.
+///
+/// The stored digests are sorted by tree position: first the leaf siblings from left to right,
+/// then the inner digests level by level from the leaves to the root and from left to right on
+/// each level.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "LeafDigest<C>: Deserialize<'de>, InnerDigest<C>: Deserialize<'de>",
+            serialize = "LeafDigest<C>: Serialize, InnerDigest<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "LeafDigest<C>: Clone, InnerDigest<C>: Clone"),
+    Debug(bound = "LeafDigest<C>: Debug, InnerDigest<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "LeafDigest<C>: Eq, InnerDigest<C>: Eq"),
+    Hash(bound = "LeafDigest<C>: Hash, InnerDigest<C>: Hash"),
+    PartialEq(bound = "LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq")
+)]
+pub struct MultiPath<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Leaf Indices
+    ///
+    /// Leaf indices are stored in strictly increasing order.
+    pub leaf_indices: Vec<Node>,
+
+    /// Leaf Sibling Digests
+    ///
+    /// Only the siblings of leaves whose sibling is not itself opened are stored.
+    pub leaf_siblings: Vec<LeafDigest<C>>,
+
+    /// Inner Digests
+    ///
+    /// Inner digests are stored from leaf to root, not including the root, and from left to right
+    /// on each level. Only the digests which cannot be computed from the opened leaves are stored.
+    pub path: Vec<InnerDigest<C>>,
+}
+
+impl<C> MultiPath<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`MultiPath`] from `leaf_indices`, `leaf_siblings`, and `path`.
+    #[inline]
+    pub fn new(
+        leaf_indices: Vec<Node>,
+        leaf_siblings: Vec<LeafDigest<C>>,
+        path: Vec<InnerDigest<C>>,
+    ) -> Self {
+        Self {
+            leaf_indices,
+            leaf_siblings,
+            path,
+        }
+    }
+
+    /// Builds a new [`MultiPath`] by merging the shared digests of `paths`.
+    ///
+    /// # Crypto Safety
+    ///
+    /// All of the `paths` must open to the same root and have a path with length given by
+    /// [`path_length`]. Paths with repeated leaf indices are only included once.
+    #[inline]
+    pub fn from_paths(paths: &[Path<C>]) -> Self
+    where
+        LeafDigest<C>: Clone,
+        InnerDigest<C>: Clone,
+    {
+        let mut level = paths
+            .iter()
+            .map(|path| (path.leaf_index(), path))
+            .collect::<Vec<_>>();
+        level.sort_by_key(|(index, _)| index.0);
+        level.dedup_by_key(|(index, _)| *index);
+        let leaf_indices = level.iter().map(|(index, _)| *index).collect();
+        let mut leaf_siblings = Vec::new();
+        level = Self::merge_level(&level, |path| {
+            leaf_siblings.push(path.sibling_digest.clone())
+        });
+        let mut inner_path = Vec::new();
+        for depth in 0..path_length::<C, _>() {
+            level = Self::merge_level(&level, |path| {
+                inner_path.push(path.inner_path.path[depth].clone())
+            });
+        }
+        Self::new(leaf_indices, leaf_siblings, inner_path)
+    }
+
+    /// Computes the parents of the nodes in `level`, calling `missing` on the representative path
+    /// of every node whose sibling is not in `level`.
+    #[inline]
+    fn merge_level<'p, F>(level: &[(Node, &'p Path<C>)], mut missing: F) -> Vec<(Node, &'p Path<C>)>
+    where
+        F: FnMut(&'p Path<C>),
+    {
+        let mut parents = Vec::with_capacity(level.len());
+        let mut i = 0;
+        while i < level.len() {
+            let (index, path) = level[i];
+            if index.is_left()
+                && matches!(level.get(i + 1), Some((next, _)) if *next == index.sibling())
+            {
+                i += 2;
+            } else {
+                missing(path);
+                i += 1;
+            }
+            parents.push((index.parent(), path));
+        }
+        parents
+    }
+
+    /// Returns the number of opened leaves.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.leaf_indices.len()
+    }
+
+    /// Returns `true` if `self` opens no leaves.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leaf_indices.is_empty()
+    }
+
+    /// Computes the root of the merkle tree relative to `leaf_digests` using `parameters`,
+    /// returning `None` if the shape of `self` does not match its leaf indices or the number of
+    /// `leaf_digests`.
+    ///
+    /// The `leaf_digests` must be given in the order of the [`leaf_indices`](Self::leaf_indices).
+    #[inline]
+    pub fn root(
+        &self,
+        parameters: &Parameters<C>,
+        leaf_digests: &[LeafDigest<C>],
+    ) -> Option<Root<C>> {
+        fold_multi_path(
+            parameters,
+            &self.leaf_indices,
+            leaf_digests,
+            &self.leaf_siblings,
+            &self.path,
+            &mut (),
+        )
+    }
+
+    /// Returns `true` if `self` is a witness to the fact that `leaf_digests` are stored in a
+    /// merkle tree with the given `root`.
+    #[inline]
+    pub fn verify_digests(
+        &self,
+        parameters: &Parameters<C>,
+        root: &Root<C>,
+        leaf_digests: &[LeafDigest<C>],
+    ) -> bool
+    where
+        InnerDigest<C>: PartialEq,
+    {
+        self.root(parameters, leaf_digests)
+            .map(|computed_root| computed_root == *root)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if `self` is a witness to the fact that `leaves` are stored in a merkle tree
+    /// with the given `root`.
+    #[inline]
+    pub fn verify(&self, parameters: &Parameters<C>, root: &Root<C>, leaves: &[Leaf<C>]) -> bool
+    where
+        InnerDigest<C>: PartialEq,
+    {
+        self.verify_digests(
+            parameters,
+            root,
+            &leaves
+                .iter()
+                .map(|leaf| parameters.digest(leaf))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Returns the number of leaf sibling digests and inner digests stored in a [`MultiPath`] which
+/// opens the leaves at `leaf_indices`, or `None` if `leaf_indices` is not strictly increasing.
+#[inline]
+pub fn multi_path_shape<C, COM>(leaf_indices: &[Node]) -> Option<(usize, usize)>
+where
+    C: Configuration<COM> + ?Sized,
+{
+    if !is_strictly_increasing(leaf_indices) {
+        return None;
+    }
+    let mut level = leaf_indices.to_vec();
+    let mut leaf_sibling_count = 0;
+    let mut inner_count = 0;
+    for depth in 0..=path_length::<C, _>() {
+        let mut parents = Vec::with_capacity(level.len());
+        let mut i = 0;
+        while i < level.len() {
+            let index = level[i];
+            if index.is_left() && level.get(i + 1) == Some(&index.sibling()) {
+                i += 2;
+            } else {
+                if depth == 0 {
+                    leaf_sibling_count += 1;
+                } else {
+                    inner_count += 1;
+                }
+                i += 1;
+            }
+            parents.push(index.parent());
+        }
+        level = parents;
+    }
+    Some((leaf_sibling_count, inner_count))
+}
+
+/// Returns `true` if `indices` is strictly increasing.
+#[inline]
+fn is_strictly_increasing(indices: &[Node]) -> bool {
+    indices.windows(2).all(|pair| pair[0].0 < pair[1].0)
+}
+
+/// Folds one level of a multi-path, joining every node in `indices` with its sibling which is
+/// either the next node of `indices` or the next element of `siblings`.
+#[inline]
+fn fold_multi_path_level<X, D, COM, F>(
+    indices: &[Node],
+    digests: &[X],
+    siblings: &mut slice::Iter<X>,
+    compiler: &mut COM,
+    mut join: F,
+) -> Option<(Vec<Node>, Vec<D>)>
+where
+    F: FnMut(&X, &X, &mut COM) -> D,
+{
+    let mut parents = Vec::with_capacity(indices.len());
+    let mut parent_digests = Vec::with_capacity(indices.len());
+    let mut i = 0;
+    while i < indices.len() {
+        let index = indices[i];
+        if index.is_left() && indices.get(i + 1) == Some(&index.sibling()) {
+            parent_digests.push(join(&digests[i], &digests[i + 1], compiler));
+            i += 2;
+        } else {
+            let (lhs, rhs) = index.parity().order(&digests[i], siblings.next()?);
+            parent_digests.push(join(lhs, rhs, compiler));
+            i += 1;
+        }
+        parents.push(index.parent());
+    }
+    Some((parents, parent_digests))
+}
+
+/// Computes the root of the multi-path with the given `leaf_indices`, `leaf_siblings`, and inner
+/// `path` relative to `leaf_digests`, returning `None` if the number of digests does not match the
+/// shape of `leaf_indices` or some leaf index is out of bounds.
+#[inline]
+fn fold_multi_path<C, COM>(
+    parameters: &Parameters<C, COM>,
+    leaf_indices: &[Node],
+    leaf_digests: &[LeafDigest<C, COM>],
+    leaf_siblings: &[LeafDigest<C, COM>],
+    path: &[InnerDigest<C, COM>],
+    compiler: &mut COM,
+) -> Option<Root<C, COM>>
+where
+    C: Configuration<COM> + ?Sized,
+{
+    if leaf_indices.is_empty()
+        || leaf_indices.len() != leaf_digests.len()
+        || !is_strictly_increasing(leaf_indices)
+    {
+        return None;
+    }
+    let mut leaf_siblings = leaf_siblings.iter();
+    let mut path = path.iter();
+    let (mut indices, mut digests) = fold_multi_path_level(
+        leaf_indices,
+        leaf_digests,
+        &mut leaf_siblings,
+        compiler,
+        |lhs, rhs, compiler| parameters.join_leaves_with(lhs, rhs, compiler),
+    )?;
+    for _ in 0..path_length::<C, _>() {
+        let (parents, parent_digests) = fold_multi_path_level(
+            &indices,
+            &digests,
+            &mut path,
+            compiler,
+            |lhs, rhs, compiler| parameters.join_with(lhs, rhs, compiler),
+        )?;
+        indices = parents;
+        digests = parent_digests;
+    }
+    if indices != [Node(0)] || leaf_siblings.next().is_some() || path.next().is_some() {
+        return None;
+    }
+    digests.pop()
+}
+
 /// Merkle Tree Current Path
 #[cfg_attr(
     feature = "serde",
@@ -889,6 +1425,25 @@ where
         self.inner_path
             .update(parameters, current, &mut self.sibling_digest, next)
     }
+
+    /// Advances the path to the next current path with `next`, updating `current`, without
+    /// computing the new root.
+    ///
+    /// This is the same as [`update`](Self::update) except that the root is not recomputed, so
+    /// advancing over `n` leaves only takes `O(n)` hashes in total instead of `O(n * height)`.
+    #[inline]
+    pub fn advance(
+        &mut self,
+        parameters: &Parameters<C>,
+        current: &mut LeafDigest<C>,
+        next: LeafDigest<C>,
+    ) where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        self.inner_path
+            .advance(parameters, current, &mut self.sibling_digest, next)
+    }
 }
 
 impl<C> TryFrom<Path<C>> for CurrentPath<C>
@@ -1152,4 +1707,291 @@ pub mod constraint {
             }
         }
     }
+
+    /// Multi-Path Variable
+    ///
+    /// The leaf indices of a multi-path determine which digests are joined with each other, so they
+    /// fix the shape of the circuit and are kept as constants. Only the digests are allocated.
+    pub struct MultiPathVar<C, COM>
+    where
+        C: Configuration<COM> + ?Sized,
+    {
+        /// Leaf Indices
+        ///
+        /// Leaf indices are stored in strictly increasing order.
+        pub leaf_indices: Vec<Node>,
+
+        /// Leaf Sibling Digests
+        pub leaf_siblings: Vec<LeafDigest<C, COM>>,
+
+        /// Inner Digests
+        ///
+        /// Inner digests are stored in the same order as in [`MultiPath::path`].
+        pub path: Vec<InnerDigest<C, COM>>,
+    }
+
+    impl<C, COM> MultiPathVar<C, COM>
+    where
+        C: Configuration<COM> + ?Sized,
+    {
+        /// Allocates the digests of `multi_path` as known secret variables, using its leaf indices
+        /// as the shape of the circuit.
+        #[inline]
+        pub fn new_known(multi_path: &MultiPath<C::Type>, compiler: &mut COM) -> Self
+        where
+            C: Constant<COM>,
+            C::Type: Configuration,
+            InnerDigest<C, COM>: Variable<Secret, COM, Type = InnerDigest<C::Type>>,
+            LeafDigest<C, COM>: Variable<Secret, COM, Type = LeafDigest<C::Type>>,
+        {
+            Self {
+                leaf_indices: multi_path.leaf_indices.clone(),
+                leaf_siblings: multi_path
+                    .leaf_siblings
+                    .iter()
+                    .map(|d| d.as_known(compiler))
+                    .collect(),
+                path: multi_path
+                    .path
+                    .iter()
+                    .map(|d| d.as_known(compiler))
+                    .collect(),
+            }
+        }
+
+        /// Allocates unknown secret digests for the multi-path which opens the leaves at
+        /// `leaf_indices`.
+        ///
+        /// # Panics
+        ///
+        /// This method panics if `leaf_indices` is not strictly increasing.
+        #[inline]
+        pub fn new_unknown(leaf_indices: Vec<Node>, compiler: &mut COM) -> Self
+        where
+            InnerDigest<C, COM>: Variable<Secret, COM>,
+            LeafDigest<C, COM>: Variable<Secret, COM>,
+        {
+            let (leaf_sibling_count, inner_count) = multi_path_shape::<C, COM>(&leaf_indices)
+                .expect("Leaf indices must be strictly increasing.");
+            Self {
+                leaf_indices,
+                leaf_siblings: (0..leaf_sibling_count)
+                    .map(|_| compiler.allocate_unknown())
+                    .collect(),
+                path: (0..inner_count)
+                    .map(|_| compiler.allocate_unknown())
+                    .collect(),
+            }
+        }
+
+        /// Computes the root of the merkle tree relative to `leaf_digests` using `parameters`.
+        ///
+        /// The `leaf_digests` must be given in the order of the
+        /// [`leaf_indices`](Self::leaf_indices).
+        ///
+        /// # Panics
+        ///
+        /// This method panics if the shape of `self` does not match its leaf indices or the number
+        /// of `leaf_digests`.
+        #[inline]
+        pub fn root(
+            &self,
+            parameters: &Parameters<C, COM>,
+            leaf_digests: &[LeafDigest<C, COM>],
+            compiler: &mut COM,
+        ) -> Root<C, COM> {
+            fold_multi_path(
+                parameters,
+                &self.leaf_indices,
+                leaf_digests,
+                &self.leaf_siblings,
+                &self.path,
+                compiler,
+            )
+            .expect("The shape of the multi-path must match its leaf indices and leaf digests.")
+        }
+
+        /// Returns `true` if `self` is a witness to the fact that `leaf_digests` are stored in a
+        /// merkle tree with the given `root`.
+        #[inline]
+        pub fn verify_digests(
+            &self,
+            parameters: &Parameters<C, COM>,
+            root: &Root<C, COM>,
+            leaf_digests: &[LeafDigest<C, COM>],
+            compiler: &mut COM,
+        ) -> Bool<COM>
+        where
+            COM: Has<bool>,
+            Root<C, COM>: PartialEq<Root<C, COM>, COM>,
+        {
+            let computed_root = self.root(parameters, leaf_digests, compiler);
+            root.eq(&computed_root, compiler)
+        }
+
+        /// Returns `true` if `self` is a witness to the fact that `leaves` are stored in a merkle
+        /// tree with the given `root`.
+        #[inline]
+        pub fn verify(
+            &self,
+            parameters: &Parameters<C, COM>,
+            root: &Root<C, COM>,
+            leaves: &[Leaf<C, COM>],
+            compiler: &mut COM,
+        ) -> Bool<COM>
+        where
+            COM: Has<bool>,
+            Root<C, COM>: PartialEq<Root<C, COM>, COM>,
+        {
+            let leaf_digests = leaves
+                .iter()
+                .map(|leaf| parameters.digest_with(leaf, compiler))
+                .collect::<Vec<_>>();
+            self.verify_digests(parameters, root, &leaf_digests, compiler)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::{full::FullMerkleTree, test::Test};
+    use alloc::vec;
+
+    /// Test Configuration
+    ///
+    /// Inner digests of this configuration are joined with XOR, so appending the zero digest to a
+    /// path does not change the root it computes.
+    type Config = Test<u64, 4>;
+
+    /// Test Leaves
+    const LEAVES: [u64; 5] = [3, 1, 4, 1, 5];
+
+    /// Builds a full tree storing [`LEAVES`].
+    #[inline]
+    fn sample_tree() -> FullMerkleTree<Config> {
+        FullMerkleTree::from_iter(Parameters::new((), ()), &LEAVES)
+            .expect("The leaves should fit in the tree.")
+    }
+
+    /// Tests that paths are only built from inner paths of length [`path_length`].
+    #[test]
+    fn paths_of_wrong_length_are_rejected() {
+        let expected = path_length::<Config, _>();
+        for found in [0, expected - 1, expected + 1] {
+            assert_eq!(
+                Path::<Config>::try_new(0, Node(0), vec![0; found]),
+                Err(Error::InvalidPathLength { expected, found }),
+                "Paths of the wrong length must be rejected."
+            );
+        }
+        assert_eq!(
+            Path::<Config>::try_new(0, Node(0), vec![0; expected]),
+            Ok(Path::new(0, Node(0), vec![0; expected])),
+            "Paths of the right length should be accepted."
+        );
+    }
+
+    /// Tests that the lazy paths of a tree verify and agree with the paths collected by the tree.
+    #[test]
+    fn lazy_paths_agree_with_collected_paths() {
+        let tree = sample_tree();
+        for (index, leaf) in LEAVES.iter().enumerate() {
+            let path = tree.path(index).expect("The index should be in the tree.");
+            let lazy_path = || {
+                tree.tree
+                    .path_iter(index)
+                    .expect("The index should be in the tree.")
+            };
+            assert!(
+                lazy_path().verify(tree.parameters(), tree.root(), leaf),
+                "The lazy path should verify."
+            );
+            assert!(
+                Path::verify_iter(
+                    tree.parameters(),
+                    tree.root(),
+                    leaf,
+                    &path.sibling_digest,
+                    path.leaf_index(),
+                    path.inner_path.path.iter(),
+                ),
+                "Verifying the inner path by reference should succeed."
+            );
+            assert_eq!(
+                lazy_path().into_path(),
+                path,
+                "The collected lazy path should match the path of the tree."
+            );
+            assert_eq!(
+                lazy_path().try_into_path(),
+                Ok(path),
+                "The lazy path should have the expected length."
+            );
+        }
+        let current_path = tree
+            .tree
+            .current_path_iter()
+            .expect("The tree should not be empty.");
+        assert_eq!(
+            current_path.into_path(),
+            Path::from(tree.current_path()),
+            "The lazy current path should match the current path of the tree."
+        );
+    }
+
+    /// Tests that inner path iterators which are shorter or longer than [`path_length`] are
+    /// rejected, even when they fold to the root of the tree.
+    #[test]
+    fn lazy_paths_of_wrong_length_are_rejected() {
+        let tree = sample_tree();
+        let parameters = tree.parameters();
+        let path = tree.path(0).expect("The index should be in the tree.");
+        let leaf_digest = parameters.digest(&LEAVES[0]);
+        let truncated = LazyPath::<Config, _>::new(
+            path.sibling_digest,
+            path.leaf_index(),
+            &path.inner_path.path[..path_length::<Config, _>() - 1],
+        );
+        let truncated_root = truncated.clone().root(parameters, &leaf_digest);
+        assert!(
+            !truncated
+                .clone()
+                .verify_digest(parameters, &truncated_root, &leaf_digest),
+            "A truncated path must be rejected even against the root it folds to."
+        );
+        assert_eq!(
+            truncated.try_into_path(),
+            Err(Error::InvalidPathLength {
+                expected: path_length::<Config, _>(),
+                found: path_length::<Config, _>() - 1,
+            }),
+            "A truncated path must not be collected."
+        );
+        let zero = 0;
+        let extended = LazyPath::<Config, _>::new(
+            path.sibling_digest,
+            path.leaf_index(),
+            path.inner_path.path.iter().chain([&zero]),
+        );
+        assert_eq!(
+            &extended.clone().root(parameters, &leaf_digest),
+            tree.root(),
+            "Appending the zero digest should not change the root."
+        );
+        assert!(
+            !extended
+                .clone()
+                .verify_digest(parameters, tree.root(), &leaf_digest),
+            "An extended path must be rejected even though it folds to the root."
+        );
+        assert_eq!(
+            extended.try_into_path(),
+            Err(Error::InvalidPathLength {
+                expected: path_length::<Config, _>(),
+                found: path_length::<Config, _>() + 1,
+            }),
+            "An extended path must not be collected."
+        );
+    }
 }
@@ -1,15 +1,24 @@
 //! Single Path Merkle Tree Storage
+//!
+//! This module stores only the right-most path of a merkle tree, either with its root cached in
+//! [`SinglePath`] or with the root computed on demand in [`Frontier`].
 
-// TODO: Should we be storing the root? Can we have a version where we don't?
 // TODO: How should we design the free functions here? We need them for now for ledger state, but
 //       it would be nice to have a more elegant solution that doesn't require duplicate interfaces.
 
 use crate::merkle_tree::{
-    capacity, Configuration, CurrentPath, InnerDigest, LeafDigest, MerkleTree, Parameters, Root,
-    Tree,
+    capacity,
+    inner_tree::{InnerMap, PartialInnerTree},
+    partial::Partial,
+    path_length, Configuration, CurrentPath, InnerDigest, LeafDigest, MerkleTree, Node, Parameters,
+    Parity, Root, Tree,
 };
+use alloc::vec;
 use core::{fmt::Debug, hash::Hash};
-use openzl_util::derivative;
+use openzl_util::{
+    codec::{Decode, DecodeError, Encode, Read, Write},
+    derivative,
+};
 
 /// Tree Length State
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -162,6 +171,400 @@ where
     }
 }
 
+impl<C> SinglePath<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Converts `self` into a [`Frontier`], dropping the cached root.
+    #[inline]
+    pub fn into_frontier(self) -> Frontier<C> {
+        Frontier {
+            leaf_digest: self.leaf_digest,
+            current_path: self.current_path,
+        }
+    }
+}
+
+impl<C> From<SinglePath<C>> for Frontier<C>
+where
+    C: Configuration + ?Sized,
+{
+    #[inline]
+    fn from(tree: SinglePath<C>) -> Self {
+        tree.into_frontier()
+    }
+}
+
+/// Merkle Tree Frontier
+///
+/// The frontier of a merkle tree is its right-most leaf, the sibling of that leaf, and the roots
+/// of the completed subtrees to the left of the path from that leaf to the root. This is all that
+/// is needed to append new leaves and to compute the root, so the frontier only takes
+/// `O(height)` memory no matter how many leaves the tree has.
+///
+/// Unlike [`SinglePath`], the frontier does not cache its root. Appending with
+/// [`push_digest`](Self::push_digest) only hashes the subtrees that the new leaf completes, which
+/// is a constant number of hashes on average, and the root is computed on demand with
+/// [`root`](Self::root). When every intermediate root is needed, for example to publish the state
+/// after every block, use [`roots`](Self::roots) to stream them instead.
+///
+/// Once proofs of membership need to be tracked, the frontier can be converted into a
+/// [`Partial`] tree with [`into_partial`](Self::into_partial).
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "LeafDigest<C>: Clone, InnerDigest<C>: Clone"),
+    Debug(bound = "LeafDigest<C>: Debug, InnerDigest<C>: Debug"),
+    Default(bound = "LeafDigest<C>: Default, InnerDigest<C>: Default"),
+    Eq(bound = "LeafDigest<C>: Eq, InnerDigest<C>: Eq"),
+    Hash(bound = "LeafDigest<C>: Hash, InnerDigest<C>: Hash"),
+    PartialEq(bound = "LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq")
+)]
+pub struct Frontier<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Leaf Digest
+    leaf_digest: Option<LeafDigest<C>>,
+
+    /// Current Path
+    current_path: CurrentPath<C>,
+}
+
+impl<C> Frontier<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new empty [`Frontier`].
+    #[inline]
+    pub fn new() -> Self
+    where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        Default::default()
+    }
+
+    /// Returns the number of leaves in the merkle tree.
+    #[inline]
+    pub fn len(&self) -> usize {
+        if self.leaf_digest.is_none() {
+            0
+        } else {
+            self.current_path.leaf_index().0 + 1
+        }
+    }
+
+    /// Returns `true` if the merkle tree is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leaf_digest.is_none()
+    }
+
+    /// Returns the state of the length of this tree.
+    #[inline]
+    pub fn length_state(&self) -> Length {
+        raw::length_state(&self.leaf_digest, &self.current_path)
+    }
+
+    /// Returns the current merkle tree path for the current leaf.
+    #[inline]
+    pub fn current_path(&self) -> &CurrentPath<C> {
+        &self.current_path
+    }
+
+    /// Returns the currently stored leaf digest, returning `None` if the tree is empty.
+    #[inline]
+    pub fn leaf_digest(&self) -> Option<&LeafDigest<C>> {
+        self.leaf_digest.as_ref()
+    }
+
+    /// Computes the current merkle tree root, taking `O(height)` hashes.
+    ///
+    /// The root of the empty tree is the default inner digest, matching [`SinglePath`].
+    #[inline]
+    pub fn root(&self, parameters: &Parameters<C>) -> Root<C>
+    where
+        InnerDigest<C>: Default,
+    {
+        match &self.leaf_digest {
+            Some(leaf_digest) => self.current_path.root(parameters, leaf_digest),
+            _ => Default::default(),
+        }
+    }
+
+    /// Appends `leaf_digest` to the tree without computing the new root, returning `false` if the
+    /// tree is full.
+    #[inline]
+    pub fn push_digest(&mut self, parameters: &Parameters<C>, leaf_digest: LeafDigest<C>) -> bool
+    where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        match self.length_state() {
+            Length::Full => return false,
+            Length::Empty => self.leaf_digest = Some(leaf_digest),
+            Length::CanAccept => self.current_path.advance(
+                parameters,
+                self.leaf_digest.as_mut().unwrap(),
+                leaf_digest,
+            ),
+        }
+        true
+    }
+
+    /// Appends every digest in `leaf_digests` to the tree, returning `false` if the tree became
+    /// full before all of them were appended.
+    #[inline]
+    pub fn extend_digests<I>(&mut self, parameters: &Parameters<C>, leaf_digests: I) -> bool
+    where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+        I: IntoIterator<Item = LeafDigest<C>>,
+    {
+        leaf_digests
+            .into_iter()
+            .all(move |leaf_digest| self.push_digest(parameters, leaf_digest))
+    }
+
+    /// Returns an iterator which appends the digests in `leaf_digests` to the tree one at a time,
+    /// yielding the root of the tree after each of them.
+    ///
+    /// The iterator stops early if the tree becomes full. See [`Roots`] for more.
+    #[inline]
+    pub fn roots<'t, I>(
+        &'t mut self,
+        parameters: &'t Parameters<C>,
+        leaf_digests: I,
+    ) -> Roots<'t, C, I::IntoIter>
+    where
+        I: IntoIterator<Item = LeafDigest<C>>,
+    {
+        Roots {
+            frontier: self,
+            parameters,
+            leaf_digests: leaf_digests.into_iter(),
+        }
+    }
+
+    /// Converts `self` into a [`SinglePath`] tree, computing its root with `parameters`.
+    #[inline]
+    pub fn into_single_path(self, parameters: &Parameters<C>) -> SinglePath<C>
+    where
+        InnerDigest<C>: Default,
+    {
+        SinglePath {
+            root: self.root(parameters),
+            leaf_digest: self.leaf_digest,
+            current_path: self.current_path,
+        }
+    }
+
+    /// Converts `self` into a [`Partial`] tree which starts tracking paths from the current leaf
+    /// onwards.
+    ///
+    /// The returned tree stores the current leaf and its sibling, so paths are available for the
+    /// current leaf and for every leaf appended after the conversion.
+    #[inline]
+    pub fn into_partial<M>(self, parameters: &Parameters<C>) -> Partial<C, M>
+    where
+        M: InnerMap<C> + Default,
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        let leaf_digest = match self.leaf_digest {
+            Some(leaf_digest) => leaf_digest,
+            _ => return Default::default(),
+        };
+        let CurrentPath {
+            sibling_digest,
+            inner_path,
+        } = self.current_path;
+        let (base, leaf_digests) = match inner_path.leaf_index.parity() {
+            Parity::Left => (
+                parameters.join_leaves(&leaf_digest, &sibling_digest),
+                vec![leaf_digest],
+            ),
+            Parity::Right => (
+                parameters.join_leaves(&sibling_digest, &leaf_digest),
+                vec![sibling_digest, leaf_digest],
+            ),
+        };
+        Partial::new_unchecked(
+            leaf_digests,
+            PartialInnerTree::from_current(parameters, base, inner_path),
+        )
+    }
+}
+
+/// Frontier Decode Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FrontierDecodeError<L, I> {
+    /// Leaf Decoding Error
+    Leaf(L),
+
+    /// Inner Decoding Error
+    Inner(I),
+
+    /// Length Error
+    ///
+    /// The encoded number of leaves is larger than the capacity of the tree or the encoded path
+    /// is longer than the path length of the tree.
+    Length,
+}
+
+impl<C> Decode for Frontier<C>
+where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Decode + Default,
+    InnerDigest<C>: Decode,
+{
+    #[allow(clippy::type_complexity)] // NOTE: This is an implementation type so it doesn't matter.
+    type Error =
+        FrontierDecodeError<<LeafDigest<C> as Decode>::Error, <InnerDigest<C> as Decode>::Error>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let len = Node::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| FrontierDecodeError::Length))?
+            .0;
+        if len == 0 {
+            return Ok(Self {
+                leaf_digest: None,
+                current_path: Default::default(),
+            });
+        }
+        if len > capacity::<C, _>() {
+            return Err(DecodeError::Decode(FrontierDecodeError::Length));
+        }
+        let leaf_index = Node(len - 1);
+        let leaf_digest = LeafDigest::<C>::decode(&mut reader)
+            .map_err(|err| err.map_decode(FrontierDecodeError::Leaf))?;
+        let sibling_digest = if leaf_index.is_right() {
+            LeafDigest::<C>::decode(&mut reader)
+                .map_err(|err| err.map_decode(FrontierDecodeError::Leaf))?
+        } else {
+            Default::default()
+        };
+        let path_len = Node::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| FrontierDecodeError::Length))?
+            .0;
+        if path_len > path_length::<C, _>() {
+            return Err(DecodeError::Decode(FrontierDecodeError::Length));
+        }
+        let path = (0..path_len)
+            .map(|_| {
+                InnerDigest::<C>::decode(&mut reader)
+                    .map_err(|err| err.map_decode(FrontierDecodeError::Inner))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            leaf_digest: Some(leaf_digest),
+            current_path: CurrentPath::new(sibling_digest, leaf_index, path),
+        })
+    }
+}
+
+impl<C> Encode for Frontier<C>
+where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Encode,
+    InnerDigest<C>: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        // NOTE: The sibling of a left leaf is always the default digest so it is not encoded.
+        Node(self.len()).encode(&mut writer)?;
+        if let Some(leaf_digest) = &self.leaf_digest {
+            leaf_digest.encode(&mut writer)?;
+            if self.current_path.leaf_index().is_right() {
+                self.current_path.sibling_digest.encode(&mut writer)?;
+            }
+            Node(self.current_path.inner_path.path.len()).encode(&mut writer)?;
+            for digest in &self.current_path.inner_path.path {
+                digest.encode(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        let mut len = Node(self.len()).encoded_len();
+        if let Some(leaf_digest) = &self.leaf_digest {
+            len += leaf_digest.encoded_len();
+            if self.current_path.leaf_index().is_right() {
+                len += self.current_path.sibling_digest.encoded_len();
+            }
+            len += Node(self.current_path.inner_path.path.len()).encoded_len();
+            len += self
+                .current_path
+                .inner_path
+                .path
+                .iter()
+                .map(Encode::encoded_len)
+                .sum::<usize>();
+        }
+        len
+    }
+}
+
+/// Streaming Root Iterator
+///
+/// An iterator which appends leaf digests to a [`Frontier`] and yields the root after each of
+/// them. Every step takes `O(height)` hashes to compute the new root.
+///
+/// This `struct` is created by the [`roots`](Frontier::roots) method on [`Frontier`].
+/// See its documentation for more.
+pub struct Roots<'t, C, I>
+where
+    C: Configuration + ?Sized,
+{
+    /// Frontier
+    frontier: &'t mut Frontier<C>,
+
+    /// Merkle Tree Parameters
+    parameters: &'t Parameters<C>,
+
+    /// Leaf Digests
+    leaf_digests: I,
+}
+
+impl<'t, C, I> Iterator for Roots<'t, C, I>
+where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Default,
+    InnerDigest<C>: Default,
+    I: Iterator<Item = LeafDigest<C>>,
+{
+    type Item = Root<C>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frontier.length_state() == Length::Full {
+            return None;
+        }
+        raw::insert(
+            self.parameters,
+            &mut self.frontier.leaf_digest,
+            &mut self.frontier.current_path,
+            self.leaf_digests.next()?,
+        )
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, max) = self.leaf_digests.size_hint();
+        let remaining = capacity::<C, _>() - self.frontier.len();
+        (0, Some(max.map_or(remaining, |max| max.min(remaining))))
+    }
+}
+
 /// Raw Merkle Tree Interfaces
 pub mod raw {
     use super::*;
@@ -210,3 +613,111 @@ pub mod raw {
         }
     }
 }
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::{full::FullMerkleTree, inner_tree::BTreeMap, test::Test};
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    /// Test Merkle Tree Configuration
+    type Config = Test<String, 6>;
+
+    /// Returns the `n` leaves used by the tests.
+    #[inline]
+    fn leaves(n: usize) -> Vec<String> {
+        (0..n).map(|i| i.to_string()).collect()
+    }
+
+    /// Tests that the frontier, its streamed roots, and a full tree agree on every root.
+    #[test]
+    fn frontier_roots_match_full_tree() {
+        let parameters = Parameters::<Config>::default();
+        let leaves = leaves(capacity::<Config, _>());
+        let mut full = FullMerkleTree::<Config>::new(parameters.clone());
+        let mut frontier = Frontier::<Config>::new();
+        assert_eq!(&frontier.root(&parameters), full.root());
+        let mut streamed = Frontier::<Config>::new();
+        let roots = streamed
+            .roots(&parameters, leaves.iter().cloned())
+            .collect::<Vec<_>>();
+        for (leaf, root) in leaves.iter().zip(&roots) {
+            assert!(full.push(leaf));
+            assert!(frontier.push_digest(&parameters, leaf.clone()));
+            assert_eq!(&frontier.root(&parameters), full.root());
+            assert_eq!(root, full.root());
+            assert_eq!(frontier.current_path(), &full.current_path());
+        }
+        assert_eq!(roots.len(), leaves.len());
+        assert_eq!(frontier, streamed);
+        assert_eq!(frontier.length_state(), Length::Full);
+        assert!(!frontier.push_digest(&parameters, "full".into()));
+        assert_eq!(streamed.roots(&parameters, leaves(1)).next(), None);
+    }
+
+    /// Tests that the frontier converts into single path and partial trees with the same root,
+    /// and that the partial tree keeps producing valid paths as it grows.
+    #[test]
+    fn frontier_converts_into_trees() {
+        let parameters = Parameters::<Config>::default();
+        let leaves = leaves(capacity::<Config, _>());
+        for split in 0..leaves.len() {
+            let mut frontier = Frontier::<Config>::new();
+            assert!(frontier.extend_digests(&parameters, leaves[..split].iter().cloned()));
+            let single_path = frontier.clone().into_single_path(&parameters);
+            assert_eq!(single_path.root(), &frontier.root(&parameters));
+            assert_eq!(single_path.into_frontier(), frontier);
+            let mut partial = MerkleTree::from_tree(
+                frontier.into_partial::<BTreeMap<Config>>(&parameters),
+                parameters.clone(),
+            );
+            let full = FullMerkleTree::<Config>::from_slice(parameters.clone(), &leaves[..split])
+                .expect("The leaves should fit in the tree.");
+            assert_eq!(partial.root(), full.root());
+            for leaf in &leaves[split..] {
+                assert!(partial.push(leaf));
+            }
+            for index in split.saturating_sub(1)..leaves.len() {
+                assert!(
+                    partial
+                        .path(index)
+                        .expect("Paths after the frontier should be tracked.")
+                        .verify(&parameters, partial.root(), &leaves[index]),
+                    "Path should be valid."
+                );
+            }
+        }
+    }
+
+    /// Tests that the frontier round-trips through its encoding.
+    #[test]
+    fn frontier_encoding_round_trips() {
+        let parameters = Parameters::<Test<u64, 6>>::default();
+        let mut frontier = Frontier::<Test<u64, 6>>::new();
+        for leaf in 1..=capacity::<Test<u64, 6>, _>() as u64 {
+            let bytes = frontier.to_vec_exact();
+            assert_eq!(
+                bytes.len(),
+                frontier.encoded_len(),
+                "The encoded length should match the length of the encoding."
+            );
+            assert_eq!(
+                Frontier::decode(bytes.as_slice()).ok(),
+                Some(frontier.clone()),
+                "The decoded frontier should match the encoded one."
+            );
+            assert!(frontier.push_digest(&parameters, leaf));
+        }
+        let mut bytes = Vec::new();
+        Node(capacity::<Test<u64, 6>, _>() + 1)
+            .encode(&mut bytes)
+            .expect("Encoding into a vector is not allowed to fail.");
+        assert!(matches!(
+            Frontier::<Test<u64, 6>>::decode(bytes.as_slice()),
+            Err(DecodeError::Decode(FrontierDecodeError::Length))
+        ));
+    }
+}
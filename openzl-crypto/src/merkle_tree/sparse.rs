@@ -0,0 +1,535 @@
+//! Sparse Merkle Tree Storage
+//!
+//! In a sparse merkle tree, the position of every leaf is determined by the leaf itself through a
+//! [`LeafIndex`] function instead of by the insertion order. Only the occupied leaves and the
+//! inner digests on their paths are stored, so leaves can be removed again, which makes the
+//! [`SparseMerkleTree`] a [`DynamicAccumulator`].
+//!
+//! # Removals and Non-Membership
+//!
+//! Empty positions hold the default leaf digest and missing inner digests are represented by the
+//! digests of [`EmptySubtrees`]. A removed leaf is replaced by the default leaf digest and the
+//! inner digests on its path are recomputed, so the root of a tree after inserting and removing a
+//! leaf is the same as the root of a tree where the leaf was never inserted. This also means that
+//! every empty position has a valid path for the default leaf digest, which proves that no leaf
+//! is stored there. See [`SparseMerkleTree::prove_non_membership`] for more.
+
+use crate::{
+    accumulator::{
+        self, dynamic::DynamicAccumulator, Accumulator, ConstantCapacityAccumulator,
+        ExactSizeAccumulator, MembershipProof,
+    },
+    merkle_tree::{
+        capacity,
+        inner_tree::{BTreeMap, InnerMap, InnerNodeIter, InnerTree, SentinelSource},
+        path_length, Configuration, InnerDigest, Leaf, LeafDigest, Node, Parameters, Path, Root,
+    },
+};
+use alloc::{collections::btree_map, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::derivative;
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Sparse Merkle Tree Leaf Index
+///
+/// Computes the position of a leaf in a [`SparseMerkleTree`] from its leaf digest.
+pub trait LeafIndex<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Returns the index of the leaf with the given `leaf_digest`.
+    ///
+    /// # Contract
+    ///
+    /// The returned index is reduced modulo the [`capacity`] of the tree, so implementations do not
+    /// need to restrict its range.
+    fn leaf_index(&self, leaf_digest: &LeafDigest<C>) -> usize;
+}
+
+impl<C, I> LeafIndex<C> for &I
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C> + ?Sized,
+{
+    #[inline]
+    fn leaf_index(&self, leaf_digest: &LeafDigest<C>) -> usize {
+        (**self).leaf_index(leaf_digest)
+    }
+}
+
+/// Empty Subtree Digests
+///
+/// Sentinel source for the inner digests of a [`SparseMerkleTree`] which returns the root of an
+/// empty subtree of the right height for every missing inner digest.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "InnerDigest<C>: Deserialize<'de>",
+            serialize = "InnerDigest<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "InnerDigest<C>: Clone"),
+    Debug(bound = "InnerDigest<C>: Debug"),
+    Eq(bound = "InnerDigest<C>: Eq"),
+    Hash(bound = "InnerDigest<C>: Hash"),
+    PartialEq(bound = "InnerDigest<C>: PartialEq")
+)]
+pub struct EmptySubtrees<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Empty Subtree Roots
+    ///
+    /// Roots are stored from the root of the tree down to the parents of the leaves.
+    roots: Vec<InnerDigest<C>>,
+}
+
+impl<C> EmptySubtrees<C>
+where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Default,
+    InnerDigest<C>: Clone,
+{
+    /// Computes the roots of the empty subtrees of every height using `parameters`.
+    #[inline]
+    pub fn new(parameters: &Parameters<C>) -> Self {
+        let mut roots = Vec::with_capacity(path_length::<C, _>() + 1);
+        let mut root = parameters.join_leaves(&Default::default(), &Default::default());
+        for _ in 0..path_length::<C, _>() {
+            let parent = parameters.join(&root, &root);
+            roots.push(root);
+            root = parent;
+        }
+        roots.push(root);
+        roots.reverse();
+        Self { roots }
+    }
+}
+
+impl<C> SentinelSource<C> for EmptySubtrees<C>
+where
+    C: Configuration + ?Sized,
+{
+    #[inline]
+    fn get(&self, index: usize) -> &InnerDigest<C> {
+        &self.roots[(usize::BITS - 1 - (index + 1).leading_zeros()) as usize]
+    }
+}
+
+/// Sparse Merkle Tree Update
+///
+/// Records the change of a single leaf in a [`SparseMerkleTree`] so that the paths of the other
+/// leaves can be updated with [`apply`](Self::apply).
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "LeafDigest<C>: Deserialize<'de>, InnerDigest<C>: Deserialize<'de>",
+            serialize = "LeafDigest<C>: Serialize, InnerDigest<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "LeafDigest<C>: Clone, InnerDigest<C>: Clone"),
+    Debug(bound = "LeafDigest<C>: Debug, InnerDigest<C>: Debug"),
+    Eq(bound = "LeafDigest<C>: Eq, InnerDigest<C>: Eq"),
+    Hash(bound = "LeafDigest<C>: Hash, InnerDigest<C>: Hash"),
+    PartialEq(bound = "LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq")
+)]
+pub struct Update<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Leaf Index
+    pub leaf_index: Node,
+
+    /// New Leaf Digest
+    pub leaf_digest: LeafDigest<C>,
+
+    /// New Inner Digests
+    ///
+    /// Inner digests on the path of the changed leaf are stored from leaf to root, not including
+    /// the root.
+    pub inner_digests: Vec<InnerDigest<C>>,
+
+    /// New Root
+    pub root: Root<C>,
+}
+
+impl<C> Update<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Updates the `path` to the leaf at `path.leaf_index()` and the `root` with the change
+    /// recorded in `self`, returning `false` if `path` is the path of the changed leaf itself.
+    #[inline]
+    pub fn apply(&self, path: &mut Path<C>, root: &mut Root<C>) -> bool
+    where
+        LeafDigest<C>: Clone,
+        InnerDigest<C>: Clone,
+    {
+        let leaf_index = path.leaf_index();
+        if leaf_index == self.leaf_index {
+            return false;
+        }
+        if Node::are_siblings(&leaf_index, &self.leaf_index) {
+            path.sibling_digest = self.leaf_digest.clone();
+        } else {
+            let (mut lhs, mut rhs) = (leaf_index.parent(), self.leaf_index.parent());
+            for (digest, new_digest) in path.inner_path.path.iter_mut().zip(&self.inner_digests) {
+                if Node::are_siblings(&lhs, &rhs) {
+                    *digest = new_digest.clone();
+                    break;
+                }
+                lhs.into_parent();
+                rhs.into_parent();
+            }
+        }
+        *root = self.root.clone();
+        true
+    }
+}
+
+/// Sparse Merkle Tree
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                Parameters<C>: Deserialize<'de>,
+                I: Deserialize<'de>,
+                LeafDigest<C>: Deserialize<'de>,
+                InnerDigest<C>: Deserialize<'de>,
+                M: Deserialize<'de>
+            ",
+            serialize = r"
+                Parameters<C>: Serialize,
+                I: Serialize,
+                LeafDigest<C>: Serialize,
+                InnerDigest<C>: Serialize,
+                M: Serialize
+            "
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(
+        bound = "Parameters<C>: Clone, I: Clone, LeafDigest<C>: Clone, InnerDigest<C>: Clone, M: Clone"
+    ),
+    Debug(
+        bound = "Parameters<C>: Debug, I: Debug, LeafDigest<C>: Debug, InnerDigest<C>: Debug, M: Debug"
+    )
+)]
+pub struct SparseMerkleTree<C, I, M = BTreeMap<C>>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+{
+    /// Merkle Tree Parameters
+    parameters: Parameters<C>,
+
+    /// Leaf Index Function
+    leaf_index: I,
+
+    /// Leaf Digests
+    leaf_digests: btree_map::BTreeMap<usize, LeafDigest<C>>,
+
+    /// Inner Digests
+    inner_digests: InnerTree<C, M, EmptySubtrees<C>>,
+}
+
+impl<C, I, M> SparseMerkleTree<C, I, M>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    /// Builds a new empty [`SparseMerkleTree`] from `parameters` and the `leaf_index` function.
+    #[inline]
+    pub fn new(parameters: Parameters<C>, leaf_index: I) -> Self
+    where
+        M: Default,
+    {
+        Self {
+            leaf_index,
+            leaf_digests: Default::default(),
+            inner_digests: InnerTree::with_sentinel(EmptySubtrees::new(&parameters)),
+            parameters,
+        }
+    }
+
+    /// Returns a shared reference to the parameters used by this merkle tree.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        &self.parameters
+    }
+
+    /// Returns the number of leaves stored in this tree.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.leaf_digests.len()
+    }
+
+    /// Returns `true` if this tree is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leaf_digests.is_empty()
+    }
+
+    /// Returns a reference to the root inner digest.
+    #[inline]
+    pub fn root(&self) -> &Root<C> {
+        self.inner_digests.root()
+    }
+
+    /// Returns the position of `leaf_digest` in the tree.
+    #[inline]
+    pub fn position(&self, leaf_digest: &LeafDigest<C>) -> usize {
+        self.leaf_index.leaf_index(leaf_digest) % capacity::<C, _>()
+    }
+
+    /// Returns the leaf digest stored at `index`.
+    #[inline]
+    pub fn leaf_digest(&self, index: usize) -> Option<&LeafDigest<C>> {
+        self.leaf_digests.get(&index)
+    }
+
+    /// Returns `true` if `leaf_digest` is stored in the tree.
+    #[inline]
+    pub fn contains(&self, leaf_digest: &LeafDigest<C>) -> bool {
+        self.leaf_digest(self.position(leaf_digest)) == Some(leaf_digest)
+    }
+
+    /// Returns the path to the leaf stored at `index`, if it exists.
+    #[inline]
+    pub fn path(&self, index: usize) -> Option<Path<C>> {
+        self.leaf_digest(index)?;
+        Some(self.path_unchecked(Node(index)))
+    }
+
+    /// Returns a proof that `leaf_digest` is not stored in the tree, returning `None` if its
+    /// position is occupied.
+    ///
+    /// The proof is the path to the default leaf digest at the [`position`](Self::position) of
+    /// `leaf_digest` and is checked with [`verify_non_membership`](Self::verify_non_membership).
+    /// If the position is occupied by a different leaf, the [`path`](Self::path) of that leaf
+    /// proves non-membership instead.
+    #[inline]
+    pub fn prove_non_membership(&self, leaf_digest: &LeafDigest<C>) -> Option<Path<C>> {
+        let index = self.position(leaf_digest);
+        match self.leaf_digest(index) {
+            Some(_) => None,
+            _ => Some(self.path_unchecked(Node(index))),
+        }
+    }
+
+    /// Returns `true` if `path` is a witness to the fact that `leaf_digest` is not stored in a
+    /// tree with the given `root`.
+    #[inline]
+    pub fn verify_non_membership(
+        &self,
+        root: &Root<C>,
+        leaf_digest: &LeafDigest<C>,
+        path: &Path<C>,
+    ) -> bool {
+        path.leaf_index() == self.position(leaf_digest)
+            && path.verify_digest(&self.parameters, root, &Default::default())
+    }
+
+    /// Returns the path to `leaf_index` without checking that it is occupied.
+    #[inline]
+    fn path_unchecked(&self, leaf_index: Node) -> Path<C> {
+        Path::from_inner(
+            self.leaf_digest(leaf_index.sibling().0)
+                .cloned()
+                .unwrap_or_default(),
+            self.inner_digests.path(leaf_index),
+        )
+    }
+
+    /// Inserts `leaf` into the tree at its [`position`](Self::position), returning `None` if the
+    /// position is already occupied by a different leaf.
+    #[inline]
+    pub fn insert(&mut self, leaf: &Leaf<C>) -> Option<Update<C>> {
+        let leaf_digest = self.parameters.digest(leaf);
+        let index = self.position(&leaf_digest);
+        match self.leaf_digests.get(&index) {
+            Some(current) if current == &leaf_digest => {}
+            Some(_) => return None,
+            _ => {
+                self.leaf_digests.insert(index, leaf_digest.clone());
+            }
+        }
+        Some(self.set_leaf_digest(Node(index), leaf_digest))
+    }
+
+    /// Removes `leaf` from the tree, returning `None` if it is not stored in the tree.
+    #[inline]
+    pub fn remove(&mut self, leaf: &Leaf<C>) -> Option<Update<C>> {
+        let leaf_digest = self.parameters.digest(leaf);
+        let index = self.position(&leaf_digest);
+        if self.leaf_digests.get(&index) != Some(&leaf_digest) {
+            return None;
+        }
+        self.leaf_digests.remove(&index);
+        Some(self.set_leaf_digest(Node(index), Default::default()))
+    }
+
+    /// Sets the leaf at `leaf_index` to `leaf_digest`, recomputing the inner digests on its path
+    /// and returning the resulting [`Update`].
+    #[inline]
+    fn set_leaf_digest(&mut self, leaf_index: Node, leaf_digest: LeafDigest<C>) -> Update<C> {
+        let sibling_digest = self
+            .leaf_digest(leaf_index.sibling().0)
+            .cloned()
+            .unwrap_or_default();
+        self.inner_digests.insert(
+            &self.parameters,
+            leaf_index,
+            leaf_index.join_leaves(&self.parameters, &leaf_digest, &sibling_digest),
+        );
+        Update {
+            leaf_index,
+            leaf_digest,
+            inner_digests: InnerNodeIter::from_leaf::<C>(leaf_index)
+                .map(|node| self.inner_digests[node].clone())
+                .collect(),
+            root: self.root().clone(),
+        }
+    }
+}
+
+impl<C, I, M> accumulator::Types for SparseMerkleTree<C, I, M>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+{
+    type Item = Leaf<C>;
+    type Witness = Path<C>;
+    type Output = Root<C>;
+}
+
+impl<C, I, M> Accumulator for SparseMerkleTree<C, I, M>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    type Model = Parameters<C>;
+
+    #[inline]
+    fn model(&self) -> &Self::Model {
+        self.parameters()
+    }
+
+    #[inline]
+    fn insert(&mut self, item: &Self::Item) -> bool {
+        self.insert(item).is_some()
+    }
+
+    #[inline]
+    fn prove(&self, item: &Self::Item) -> Option<MembershipProof<Self::Model>> {
+        let leaf_digest = self.parameters.digest(item);
+        if !self.contains(&leaf_digest) {
+            return None;
+        }
+        Some(MembershipProof::new(
+            self.path(self.position(&leaf_digest))?,
+            self.root().clone(),
+        ))
+    }
+
+    #[inline]
+    fn contains(&self, item: &Self::Item) -> bool {
+        self.contains(&self.parameters.digest(item))
+    }
+}
+
+impl<C, I, M> ConstantCapacityAccumulator for SparseMerkleTree<C, I, M>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    #[inline]
+    fn capacity() -> usize {
+        capacity::<C, _>()
+    }
+}
+
+impl<C, I, M> ExactSizeAccumulator for SparseMerkleTree<C, I, M>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<C, I, M> DynamicAccumulator for SparseMerkleTree<C, I, M>
+where
+    C: Configuration + ?Sized,
+    I: LeafIndex<C>,
+    M: InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    type Update = Update<C>;
+
+    #[inline]
+    fn insert_with_update(&mut self, item: &Self::Item) -> Option<Self::Update> {
+        self.insert(item)
+    }
+
+    #[inline]
+    fn remove(&mut self, item: &Self::Item) -> Option<Self::Update> {
+        self.remove(item)
+    }
+
+    #[inline]
+    fn update_witness(
+        model: &Self::Model,
+        item: &Self::Item,
+        witness: &mut Self::Witness,
+        output: &mut Self::Output,
+        update: &Self::Update,
+    ) -> bool {
+        let _ = (model, item);
+        update.apply(witness, output)
+    }
+}
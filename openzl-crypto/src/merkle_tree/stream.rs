@@ -0,0 +1,438 @@
+//! Streaming Merkle Tree Encoding
+//!
+//! Encoding a large [`Full`] merkle tree with [`Encode`] writes everything in one call with no
+//! feedback along the way. The functions in this module write the tree level by level instead,
+//! starting from the leaves and ending at the root, and report their [`Progress`] to a callback
+//! every `interval` digests.
+//!
+//! # Format
+//!
+//! The stream starts with a [`Header`] which stores the number of leaves of the tree and, for
+//! every level, the byte offset of that level from the end of the header. The levels follow the
+//! header in order. Level `0` stores the leaf digests and level `k > 0` stores the inner digests
+//! which are `k` steps above the leaves, from left to right, so the last level only stores the
+//! root. Only the digests covering the leaves of the tree are written, which are the only ones
+//! stored by [`Full`].
+//!
+//! Since the offsets of every level are known after reading the header, readers can resume
+//! decoding from any level with [`skip_to_level`] and [`decode_level`] without decoding the
+//! levels before it.
+
+use crate::merkle_tree::{
+    capacity,
+    full::Full,
+    inner_tree::{InnerMap, InnerTree},
+    Configuration, InnerDigest, LeafDigest, Node,
+};
+use alloc::vec::Vec;
+use openzl_util::codec::{Decode, DecodeError, Encode, Read, ReadExactError, Write};
+
+/// Streaming Progress
+///
+/// This `struct` is passed to the progress callbacks of [`encode`] and [`decode`] after every
+/// `interval` digests of a level and after the last digest of a level.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Progress {
+    /// Current Level
+    pub level: usize,
+
+    /// Number of Levels
+    pub levels: usize,
+
+    /// Number of Digests of the Current Level Processed so far
+    pub processed: usize,
+
+    /// Number of Digests of the Current Level
+    pub total: usize,
+}
+
+impl Progress {
+    /// Returns `true` if the current level has been processed completely.
+    #[inline]
+    pub fn is_level_complete(&self) -> bool {
+        self.processed == self.total
+    }
+
+    /// Returns `true` if the whole tree has been processed.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.level + 1 == self.levels && self.is_level_complete()
+    }
+}
+
+/// Reports the progress on `level` to `progress` after `processed` digests out of `total` if
+/// `processed` is a multiple of `interval` or the level is complete.
+#[inline]
+fn report<F>(
+    progress: &mut F,
+    interval: usize,
+    level: usize,
+    levels: usize,
+    processed: usize,
+    total: usize,
+) where
+    F: FnMut(Progress),
+{
+    if processed == total || processed % interval.max(1) == 0 {
+        progress(Progress {
+            level,
+            levels,
+            processed,
+            total,
+        })
+    }
+}
+
+/// Stream Header
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Header {
+    /// Number of Leaves
+    pub len: usize,
+
+    /// Level Offsets
+    ///
+    /// The byte offset of every level from the end of the header.
+    pub offsets: Vec<u64>,
+}
+
+impl Header {
+    /// Builds the [`Header`] for the encoding of `tree`.
+    #[inline]
+    pub fn new<C, M>(tree: &Full<C, M>) -> Self
+    where
+        C: Configuration + ?Sized,
+        M: InnerMap<C>,
+        LeafDigest<C>: Encode,
+        InnerDigest<C>: Encode,
+    {
+        let mut header = Self {
+            len: tree.len(),
+            offsets: Vec::with_capacity(C::HEIGHT),
+        };
+        let mut offset = 0;
+        for level in 0..C::HEIGHT {
+            header.offsets.push(offset as u64);
+            offset += if level == 0 {
+                tree.leaf_digests()
+                    .iter()
+                    .map(Encode::encoded_len)
+                    .sum::<usize>()
+            } else {
+                (0..header.level_len(level))
+                    .map(|index| inner_digest(tree, level, index).encoded_len())
+                    .sum::<usize>()
+            };
+        }
+        header
+    }
+
+    /// Returns the number of levels in the stream.
+    #[inline]
+    pub fn levels(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns the number of digests stored at `level`.
+    #[inline]
+    pub fn level_len(&self, level: usize) -> usize {
+        match self.len.checked_sub(1) {
+            Some(last) if level == 0 => last + 1,
+            Some(last) => (last >> level) + 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns the byte offset of `level` from the end of the header, if `level` is in the stream.
+    #[inline]
+    pub fn offset(&self, level: usize) -> Option<u64> {
+        self.offsets.get(level).copied()
+    }
+}
+
+impl Encode for Header {
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        Node(self.len).encode(&mut writer)?;
+        Node(self.levels()).encode(&mut writer)?;
+        for offset in &self.offsets {
+            offset.encode(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        Node(self.len).encoded_len()
+            + Node(self.levels()).encoded_len()
+            + self.offsets.iter().map(Encode::encoded_len).sum::<usize>()
+    }
+}
+
+impl Decode for Header {
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let len = Node::decode(&mut reader)?.0;
+        let levels = Node::decode(&mut reader)?.0;
+        if levels > usize::BITS as usize + 1 {
+            return Err(DecodeError::Decode(()));
+        }
+        let offsets = (0..levels)
+            .map(|_| u64::decode(&mut reader))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { len, offsets })
+    }
+}
+
+/// Returns the inner digest of `tree` at `index` on `level`, which must be above the leaves.
+#[inline]
+fn inner_digest<C, M>(tree: &Full<C, M>, level: usize, index: usize) -> &InnerDigest<C>
+where
+    C: Configuration + ?Sized,
+    M: InnerMap<C>,
+{
+    tree.inner_digests()
+        .map_get_or_sentinel(inner_map_index::<C>(level, index))
+}
+
+/// Returns the [`InnerMap`] index of the inner digest at `index` on `level`, which must be above
+/// the leaves.
+#[inline]
+fn inner_map_index<C>(level: usize, index: usize) -> usize
+where
+    C: Configuration + ?Sized,
+{
+    (1 << (C::HEIGHT - 1 - level)) - 1 + index
+}
+
+/// Encodes `tree` level by level into `writer`, calling `progress` every `interval` digests and
+/// at the end of every level. See the [module-level documentation](self) for the format.
+#[inline]
+pub fn encode<C, M, W, F>(
+    tree: &Full<C, M>,
+    interval: usize,
+    mut writer: W,
+    mut progress: F,
+) -> Result<(), W::Error>
+where
+    C: Configuration + ?Sized,
+    M: InnerMap<C>,
+    LeafDigest<C>: Encode,
+    InnerDigest<C>: Encode,
+    W: Write,
+    F: FnMut(Progress),
+{
+    let header = Header::new(tree);
+    header.encode(&mut writer)?;
+    let levels = header.levels();
+    for level in 0..levels {
+        let total = header.level_len(level);
+        if total == 0 {
+            report(&mut progress, interval, level, levels, 0, 0);
+        }
+        for index in 0..total {
+            if level == 0 {
+                tree.leaf_digests()[index].encode(&mut writer)?;
+            } else {
+                inner_digest(tree, level, index).encode(&mut writer)?;
+            }
+            report(&mut progress, interval, level, levels, index + 1, total);
+        }
+    }
+    Ok(())
+}
+
+/// Skips `reader` forward to the start of `level`, assuming that `reader` is positioned right
+/// after the `header`.
+///
+/// # Panics
+///
+/// This function panics if `level` is not in the stream.
+#[inline]
+pub fn skip_to_level<R>(
+    header: &Header,
+    level: usize,
+    mut reader: R,
+) -> Result<(), ReadExactError<R::Error>>
+where
+    R: Read,
+{
+    let mut remaining = header
+        .offset(level)
+        .expect("The level must be in the stream.");
+    let mut buffer = [0; 256];
+    while remaining > 0 {
+        let chunk = remaining.min(buffer.len() as u64) as usize;
+        reader.read_exact(&mut buffer[..chunk])?;
+        remaining -= chunk as u64;
+    }
+    Ok(())
+}
+
+/// Decodes the digests of `level` from `reader`, assuming that `reader` is positioned at the start
+/// of `level`. Digests of level `0` are leaf digests and all others are inner digests.
+#[inline]
+pub fn decode_level<T, R>(
+    header: &Header,
+    level: usize,
+    mut reader: R,
+) -> Result<Vec<T>, DecodeError<R::Error, T::Error>>
+where
+    T: Decode,
+    R: Read,
+{
+    (0..header.level_len(level))
+        .map(|_| T::decode(&mut reader))
+        .collect()
+}
+
+/// Stream Decode Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StreamDecodeError<L, I> {
+    /// Header Decoding Error
+    Header,
+
+    /// Leaf Decoding Error
+    Leaf(L),
+
+    /// Inner Decoding Error
+    Inner(I),
+
+    /// Length Error
+    ///
+    /// The encoded number of leaves is larger than the capacity of the tree or the encoded number
+    /// of levels does not match the height of the tree.
+    Length,
+}
+
+/// Decodes a [`Full`] tree level by level from `reader`, calling `progress` every `interval`
+/// digests and at the end of every level. See the [module-level documentation](self) for the
+/// format.
+///
+/// # Crypto Safety
+///
+/// The decoded inner digests are not checked against the decoded leaf digests, so the root of the
+/// decoded tree should be compared against a trusted root before using it.
+#[allow(clippy::type_complexity)] // NOTE: This is an implementation type so it doesn't matter.
+#[inline]
+pub fn decode<C, M, R, F>(
+    mut reader: R,
+    interval: usize,
+    mut progress: F,
+) -> Result<
+    Full<C, M>,
+    DecodeError<
+        R::Error,
+        StreamDecodeError<<LeafDigest<C> as Decode>::Error, <InnerDigest<C> as Decode>::Error>,
+    >,
+>
+where
+    C: Configuration + ?Sized,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Decode,
+    InnerDigest<C>: Decode + Default,
+    R: Read,
+    F: FnMut(Progress),
+{
+    let header =
+        Header::decode(&mut reader).map_err(|err| err.map_decode(|_| StreamDecodeError::Header))?;
+    let levels = header.levels();
+    if header.len > capacity::<C, _>() || levels != C::HEIGHT {
+        return Err(DecodeError::Decode(StreamDecodeError::Length));
+    }
+    let total = header.level_len(0);
+    let mut leaf_digests = Vec::with_capacity(total);
+    if total == 0 {
+        report(&mut progress, interval, 0, levels, 0, 0);
+    }
+    for index in 0..total {
+        leaf_digests.push(
+            LeafDigest::<C>::decode(&mut reader)
+                .map_err(|err| err.map_decode(StreamDecodeError::Leaf))?,
+        );
+        report(&mut progress, interval, 0, levels, index + 1, total);
+    }
+    let mut map = M::default();
+    for level in 1..levels {
+        let total = header.level_len(level);
+        if total == 0 {
+            report(&mut progress, interval, level, levels, 0, 0);
+        }
+        for index in 0..total {
+            map.set(
+                inner_map_index::<C>(level, index),
+                InnerDigest::<C>::decode(&mut reader)
+                    .map_err(|err| err.map_decode(StreamDecodeError::Inner))?,
+            );
+            report(&mut progress, interval, level, levels, index + 1, total);
+        }
+    }
+    Ok(Full::new_unchecked(leaf_digests, InnerTree::with_map(map)))
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::{full::FullMerkleTree, test::Test, Parameters};
+
+    /// Test Merkle Tree Configuration
+    type Config = Test<u64, 6>;
+
+    /// Tests that trees round-trip through the streaming encoding, that progress is reported for
+    /// every level, and that every level can be decoded on its own after skipping to it.
+    #[test]
+    fn streaming_encoding_round_trips() {
+        let parameters = Parameters::<Config>::default();
+        for len in [0, 1, 5, capacity::<Config, _>()] {
+            let tree = FullMerkleTree::<Config>::from_slice(
+                parameters.clone(),
+                &(1..=len as u64).collect::<Vec<_>>(),
+            )
+            .expect("The leaves should fit in the tree.");
+            let mut bytes = Vec::new();
+            let mut reports = Vec::new();
+            encode(&tree.tree, 2, &mut bytes, |progress| reports.push(progress))
+                .expect("Encoding into a vector is not allowed to fail.");
+            let header = Header::new(&tree.tree);
+            assert_eq!(
+                reports.iter().filter(|p| p.is_level_complete()).count(),
+                header.levels(),
+                "Every level should report its completion."
+            );
+            assert!(
+                matches!(reports.last(), Some(progress) if progress.is_complete()),
+                "The last report should complete the tree."
+            );
+            let decoded = decode::<Config, _, _, _>(bytes.as_slice(), 2, |_| {})
+                .expect("Decoding an encoded tree should succeed.");
+            assert_eq!(
+                decoded, tree.tree,
+                "The decoded tree should match the encoded one."
+            );
+            for level in 0..header.levels() {
+                let mut reader = &bytes[header.encoded_len()..];
+                skip_to_level(&header, level, &mut reader)
+                    .expect("Skipping to a level in the stream should succeed.");
+                let digests = decode_level::<u64, _>(&header, level, &mut reader)
+                    .expect("Decoding a level should succeed.");
+                if level == 0 {
+                    assert_eq!(digests, tree.tree.leaf_digests(), "Leaves should match.");
+                } else if level + 1 == header.levels() && len > 0 {
+                    assert_eq!(
+                        &digests[0],
+                        tree.root(),
+                        "The last level should be the root."
+                    );
+                }
+            }
+        }
+    }
+}
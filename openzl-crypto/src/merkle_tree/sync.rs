@@ -0,0 +1,433 @@
+//! Concurrent Merkle Forests
+//!
+//! The [`Accumulator`] trait takes `&mut self` for insertions, which forces multi-threaded services
+//! to lock the whole accumulator even for read-only proving. The [`SyncAccumulator`] shards its
+//! leaves like a [`ShardedForest`] and puts every shard behind its own [`RwLock`], so membership
+//! proofs can be computed concurrently while insertions are serialized per shard.
+
+use crate::{
+    accumulator::{self, Accumulator, ExactSizeAccumulator, MembershipProof, OptimizedAccumulator},
+    merkle_tree::{
+        forest::{DigestPrefix, ShardedForest, ShardedParameters, ShardedPath},
+        tree::{self, Leaf, Parameters, Root, Tree},
+        InnerDigest, LeafDigest, WithProofs,
+    },
+};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use openzl_util::derivative;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Batch Insertion Error
+///
+/// This `struct` is the error state of [`SyncAccumulator::try_insert_batch`], which only inserts
+/// a batch if every leaf of the batch fits into its shard.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BatchCapacityError {
+    /// Index of the Shard without Enough Capacity
+    pub shard: usize,
+
+    /// Number of Leaves of the Batch Routed to the Shard
+    pub required: usize,
+
+    /// Remaining Capacity of the Shard
+    pub available: usize,
+}
+
+/// Thread-Safe Sharded Accumulator
+///
+/// Every shard is guarded by its own [`RwLock`]. Proving and membership checks take read locks and
+/// can run concurrently with each other, while insertions take the write lock of a single shard,
+/// so insertions into the same shard are serialized and insertions into different shards are not.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = "Parameters<C>: Debug, T: Debug"))]
+pub struct SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+{
+    /// Sharded Merkle Forest Parameters
+    parameters: ShardedParameters<C>,
+
+    /// Shards
+    shards: Vec<RwLock<T>>,
+}
+
+impl<C, T> SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+    LeafDigest<C>: DigestPrefix,
+{
+    /// Builds a new [`SyncAccumulator`] with `2^prefix_bits` empty shards.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `prefix_bits` is not smaller than the number of bits of a [`usize`].
+    #[inline]
+    pub fn new(parameters: Parameters<C>, prefix_bits: u32) -> Self {
+        ShardedForest::new(parameters, prefix_bits).into()
+    }
+
+    /// Returns a shared reference to the parameters used by this accumulator.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        &self.parameters.parameters
+    }
+
+    /// Returns the number of shards of this accumulator.
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the index of the shard that stores the leaf with the given `leaf_digest`.
+    #[inline]
+    fn shard_of(&self, leaf_digest: &LeafDigest<C>) -> usize {
+        self.parameters.shard_index(leaf_digest)
+    }
+
+    /// Acquires the read lock of the shard at `index`.
+    #[inline]
+    fn read(&self, index: usize) -> RwLockReadGuard<T> {
+        self.shards[index]
+            .read()
+            .expect("Shard locks are not allowed to be poisoned.")
+    }
+
+    /// Acquires the write lock of the shard at `index`.
+    #[inline]
+    fn write(&self, index: usize) -> RwLockWriteGuard<T> {
+        self.shards[index]
+            .write()
+            .expect("Shard locks are not allowed to be poisoned.")
+    }
+
+    /// Returns the number of leaves in this accumulator.
+    ///
+    /// The shards are locked one after the other, so concurrent insertions may or may not be
+    /// counted.
+    #[inline]
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|i| self.read(i).len()).sum()
+    }
+
+    /// Returns `true` if this accumulator is empty. See [`len`](Self::len) for more.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read(i).is_empty())
+    }
+
+    /// Inserts `item` into its shard through a shared reference, returning `false` if its shard
+    /// has exhausted its capacity. See [`Accumulator::insert`] for more.
+    #[inline]
+    pub fn insert_shared(&self, item: &Leaf<C>) -> bool
+    where
+        T: WithProofs<C>,
+    {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let shard = self.shard_of(&leaf_digest);
+        self.write(shard)
+            .push_provable_digest(&self.parameters.parameters, move || leaf_digest)
+    }
+
+    /// Inserts every item of `items` through a shared reference if all of them fit into their
+    /// shards, returning a [`BatchCapacityError`] without inserting anything otherwise.
+    ///
+    /// The write locks of all shards touched by the batch are held in order of their index for
+    /// the whole insertion, so concurrent readers never observe a partially inserted batch in any
+    /// one shard and concurrent batches cannot deadlock.
+    #[inline]
+    pub fn try_insert_batch(&self, items: &[Leaf<C>]) -> Result<(), BatchCapacityError>
+    where
+        T: WithProofs<C>,
+    {
+        let mut batches = (0..self.shards.len())
+            .map(|_| Vec::new())
+            .collect::<Vec<_>>();
+        for item in items {
+            let leaf_digest = self.parameters.parameters.digest(item);
+            batches[self.shard_of(&leaf_digest)].push(leaf_digest);
+        }
+        let mut guards = Vec::new();
+        for (shard, batch) in batches.iter().enumerate() {
+            if batch.is_empty() {
+                continue;
+            }
+            let guard = self.write(shard);
+            let available = tree::capacity::<C, _>() - guard.len();
+            if batch.len() > available {
+                return Err(BatchCapacityError {
+                    shard,
+                    required: batch.len(),
+                    available,
+                });
+            }
+            guards.push((guard, shard));
+        }
+        for (guard, shard) in guards.iter_mut() {
+            for leaf_digest in batches[*shard].drain(..) {
+                let inserted =
+                    guard.push_provable_digest(&self.parameters.parameters, move || leaf_digest);
+                assert!(
+                    inserted,
+                    "Inserting into a shard with enough capacity is not allowed to fail."
+                );
+            }
+        }
+        drop(guards);
+        Ok(())
+    }
+
+    /// Converts `self` back into a [`ShardedForest`].
+    #[inline]
+    pub fn into_forest(self) -> ShardedForest<C, T> {
+        ShardedForest::from_parts(
+            self.parameters,
+            self.shards
+                .into_iter()
+                .map(|shard| {
+                    shard
+                        .into_inner()
+                        .expect("Shard locks are not allowed to be poisoned.")
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<C, T> From<ShardedForest<C, T>> for SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+    LeafDigest<C>: DigestPrefix,
+{
+    #[inline]
+    fn from(forest: ShardedForest<C, T>) -> Self {
+        let (parameters, shards) = forest.into_parts();
+        Self {
+            parameters,
+            shards: shards.into_iter().map(RwLock::new).collect(),
+        }
+    }
+}
+
+impl<C, T> accumulator::Types for SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C>,
+{
+    type Item = Leaf<C>;
+    type Witness = ShardedPath<C>;
+    type Output = Root<C>;
+}
+
+impl<C, T> Accumulator for SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    type Model = ShardedParameters<C>;
+
+    #[inline]
+    fn model(&self) -> &Self::Model {
+        &self.parameters
+    }
+
+    #[inline]
+    fn insert(&mut self, item: &Self::Item) -> bool {
+        self.insert_shared(item)
+    }
+
+    #[inline]
+    fn prove(&self, item: &Self::Item) -> Option<MembershipProof<Self::Model>> {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let shard = self.shard_of(&leaf_digest);
+        let tree = self.read(shard);
+        Some(MembershipProof::new(
+            ShardedPath::new(
+                shard,
+                tree.path(&self.parameters.parameters, tree.position(&leaf_digest)?)
+                    .ok()?,
+            ),
+            tree.root().clone(),
+        ))
+    }
+
+    #[inline]
+    fn contains(&self, item: &Self::Item) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        self.read(self.shard_of(&leaf_digest))
+            .contains(&leaf_digest)
+    }
+}
+
+impl<C, T> ExactSizeAccumulator for SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<C, T> OptimizedAccumulator for SyncAccumulator<C, T>
+where
+    C: tree::Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: DigestPrefix,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    #[inline]
+    fn insert_nonprovable(&mut self, item: &Self::Item) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let shard = self.shard_of(&leaf_digest);
+        self.write(shard)
+            .push_digest(&self.parameters.parameters, move || leaf_digest)
+    }
+
+    #[inline]
+    fn remove_proof(&mut self, item: &Self::Item) -> bool {
+        let leaf_digest = self.parameters.parameters.digest(item);
+        let mut tree = self.write(self.shard_of(&leaf_digest));
+        tree.position(&leaf_digest)
+            .map(|i| tree.remove_path(i))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::{full::Full, test::Test};
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+
+    /// Test Merkle Tree Configuration
+    type Config = Test<u64, 6>;
+
+    /// Test Accumulator
+    type TestAccumulator = SyncAccumulator<Config, Full<Config>>;
+
+    /// Leaf Bit Routing a Leaf to the Second Shard
+    const HIGH: u64 = 1 << 63;
+
+    /// Number of Batches Inserted by Every Writer
+    const ROUNDS: u64 = 8;
+
+    /// Returns the lengths and roots of all the shards of `accumulator`, holding their read locks
+    /// in order of their index so that the result is a consistent snapshot.
+    #[inline]
+    fn snapshot(accumulator: &TestAccumulator) -> Vec<(usize, u64)> {
+        let guards = (0..accumulator.shard_count())
+            .map(|shard| accumulator.read(shard))
+            .collect::<Vec<_>>();
+        guards
+            .iter()
+            .map(|guard| (guard.len(), *guard.root()))
+            .collect()
+    }
+
+    /// Tests that readers running concurrently with batch writers always get valid proofs and
+    /// never observe a batch which is only inserted into some of its shards.
+    #[test]
+    fn concurrent_readers_and_writers_agree() {
+        let accumulator = TestAccumulator::new(Default::default(), 1);
+        let is_done = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let writers = [0, ROUNDS].map(|offset| {
+                let accumulator = &accumulator;
+                scope.spawn(move || {
+                    for n in 1..=ROUNDS {
+                        let leaf = offset + n;
+                        accumulator
+                            .try_insert_batch(&[leaf, HIGH | leaf])
+                            .expect("Every shard has enough capacity for the batch.");
+                    }
+                })
+            });
+            let observer = scope.spawn(|| {
+                while !is_done.load(Ordering::Acquire) {
+                    let snapshot = snapshot(&accumulator);
+                    assert!(
+                        snapshot.windows(2).all(|pair| pair[0].0 == pair[1].0),
+                        "Every batch must be visible in all of its shards or in none: {snapshot:?}."
+                    );
+                }
+            });
+            let prover = scope.spawn(|| {
+                for n in 1..=2 * ROUNDS {
+                    for leaf in [n, HIGH | n] {
+                        while !accumulator.contains(&leaf) {
+                            thread::yield_now();
+                        }
+                        let proof = accumulator
+                            .prove(&leaf)
+                            .expect("Inserted leaves must have a membership proof.");
+                        assert!(
+                            proof.verify(accumulator.model(), &leaf, &mut ()),
+                            "Membership proofs computed during insertions must be valid."
+                        );
+                    }
+                }
+            });
+            for writer in writers {
+                writer.join().expect("Writers should not panic.");
+            }
+            prover.join().expect("The prover should not panic.");
+            is_done.store(true, Ordering::Release);
+            observer.join().expect("The observer should not panic.");
+        });
+        assert_eq!(accumulator.len(), 4 * ROUNDS as usize);
+    }
+
+    /// Tests that a batch which overflows one of its shards is rejected without changing any
+    /// shard, including the ones which had enough capacity.
+    #[test]
+    fn overflowing_batch_leaves_every_shard_unchanged() {
+        let accumulator = TestAccumulator::new(Default::default(), 1);
+        let capacity = tree::capacity::<Config, _>() as u64;
+        assert!(
+            accumulator.insert_shared(&1),
+            "The first shard must accept a leaf."
+        );
+        for n in 1..capacity {
+            assert!(
+                accumulator.insert_shared(&(HIGH | n)),
+                "The second shard must accept leaves up to its capacity."
+            );
+        }
+        let before = snapshot(&accumulator);
+        let batch = [2, 3, HIGH | capacity, HIGH | (capacity + 1)];
+        assert_eq!(
+            accumulator.try_insert_batch(&batch),
+            Err(BatchCapacityError {
+                shard: 1,
+                required: 2,
+                available: 1,
+            })
+        );
+        assert_eq!(
+            snapshot(&accumulator),
+            before,
+            "Rejected batches must not change any shard."
+        );
+        for leaf in batch {
+            assert!(
+                !accumulator.contains(&leaf),
+                "Leaves of a rejected batch must not be inserted."
+            );
+        }
+    }
+}
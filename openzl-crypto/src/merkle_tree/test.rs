@@ -1,11 +1,24 @@
 //! Testing Framework
 
-use crate::merkle_tree::{
-    Configuration, HashConfiguration, IdentityLeafHash, InnerDigest, InnerHash,
-    InnerHashParameters, Leaf, LeafHashParameters, MerkleTree, Parameters, Path, Tree, WithProofs,
+use crate::{
+    accumulator::Model,
+    constraint::{test::differential::membership_agreement, Satisfied},
+    merkle_tree::{
+        capacity,
+        fork::{Fork, ForkedTree, Trunk},
+        path::MultiPath,
+        path_length, Configuration, HashConfiguration, IdentityLeafHash, InnerDigest, InnerHash,
+        InnerHashParameters, Leaf, LeafDigest, LeafHashParameters, MerkleTree, Node, Parameters,
+        Path, PathError, Root, Tree, WithProofs,
+    },
+};
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Debug, hash::Hash, marker::PhantomData, mem};
+use eclair::{
+    alloc::{mode::Secret, Constant, Variable},
+    bool::{Assert, Bool},
+    Has,
 };
-use alloc::string::String;
-use core::{fmt::Debug, hash::Hash, marker::PhantomData};
 use openzl_util::{
     derivative,
     rand::{RngCore, Sample},
@@ -150,6 +163,614 @@ where
     }
 }
 
+/// Tests multi-path construction by checking that the multi-path for the strictly increasing
+/// `indices` on `tree` is a valid [`MultiPath`] for `leaves` and that it stores at most as many
+/// digests as the independent paths for `indices`.
+#[inline]
+pub fn assert_valid_multi_path<C, T>(tree: &MerkleTree<C, T>, indices: &[usize], leaves: &[Leaf<C>])
+where
+    C: Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: Clone,
+    InnerDigest<C>: Clone + Debug + PartialEq,
+    MultiPath<C>: Debug,
+    Leaf<C>: Sized,
+{
+    let multi_path = tree
+        .multi_path(indices.iter().copied())
+        .expect("Only valid queries are accepted.");
+    assert!(
+        multi_path.verify(tree.parameters(), tree.root(), leaves),
+        "Multi-path returned from tree was not valid: {:?}.",
+        multi_path,
+    );
+    assert!(
+        multi_path.leaf_siblings.len() + multi_path.path.len()
+            <= indices.len() * (path_length::<C, _>() + 1),
+        "Multi-paths must not be larger than the independent paths they merge.",
+    );
+}
+
+/// Path Mutation
+///
+/// Each mutation turns a valid [`Path`] into a malformed witness which a sound verifier must
+/// reject. See [`mutated_paths`] for how they are applied.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PathMutation {
+    /// Replaces the sibling digest of the leaf
+    FlipSiblingDigest,
+
+    /// Replaces the inner digest at the given position in the inner path
+    FlipInnerDigest(usize),
+
+    /// Swaps the leaf with its sibling by flipping the parity of the leaf index
+    SwapSiblings,
+
+    /// Moves the leaf index to the next leaf
+    NextIndex,
+
+    /// Moves the leaf index to the previous leaf
+    PreviousIndex,
+
+    /// Drops the inner digest closest to the root
+    Truncate,
+}
+
+/// Returns every [`PathMutation`] of `path` which applies to it, together with the mutated path.
+///
+/// The `flip_leaf_digest` and `flip_inner_digest` functions must return digests which are
+/// different from their inputs. Index mutations which would leave the tree are skipped.
+#[inline]
+pub fn mutated_paths<C, L, I>(
+    path: &Path<C>,
+    mut flip_leaf_digest: L,
+    mut flip_inner_digest: I,
+) -> Vec<(PathMutation, Path<C>)>
+where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Clone,
+    InnerDigest<C>: Clone,
+    L: FnMut(&LeafDigest<C>) -> LeafDigest<C>,
+    I: FnMut(&InnerDigest<C>) -> InnerDigest<C>,
+{
+    let index = path.inner_path.leaf_index.0;
+    let with_index = |index| {
+        let mut mutated = path.clone();
+        mutated.inner_path.leaf_index = Node(index);
+        mutated
+    };
+    let mut mutations = Vec::new();
+    let mut mutated = path.clone();
+    mutated.sibling_digest = flip_leaf_digest(&path.sibling_digest);
+    mutations.push((PathMutation::FlipSiblingDigest, mutated));
+    for (i, digest) in path.inner_path.path.iter().enumerate() {
+        let mut mutated = path.clone();
+        mutated.inner_path.path[i] = flip_inner_digest(digest);
+        mutations.push((PathMutation::FlipInnerDigest(i), mutated));
+    }
+    mutations.push((PathMutation::SwapSiblings, with_index(index ^ 1)));
+    if index + 1 < capacity::<C, _>() {
+        mutations.push((PathMutation::NextIndex, with_index(index + 1)));
+    }
+    if index > 0 {
+        mutations.push((PathMutation::PreviousIndex, with_index(index - 1)));
+    }
+    if !path.inner_path.path.is_empty() {
+        let mut mutated = path.clone();
+        mutated.inner_path.path.pop();
+        mutations.push((PathMutation::Truncate, mutated));
+    }
+    mutations
+}
+
+/// Tests path verification soundness by checking that every mutation of the path at the given
+/// `index` on `tree` is rejected as a witness for `leaf`.
+///
+/// # Limitations
+///
+/// Index mutations are only rejected when `leaf` differs from its neighbours, and swapping
+/// siblings is only rejected when the inner hash is not commutative, which rules out the [`Test`]
+/// configuration over `u64`.
+#[inline]
+pub fn assert_rejects_mutated_paths<C, T, L, I>(
+    tree: &MerkleTree<C, T>,
+    index: usize,
+    leaf: &Leaf<C>,
+    flip_leaf_digest: L,
+    flip_inner_digest: I,
+) where
+    C: Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    LeafDigest<C>: Clone,
+    InnerDigest<C>: Clone + PartialEq,
+    L: FnMut(&LeafDigest<C>) -> LeafDigest<C>,
+    I: FnMut(&InnerDigest<C>) -> InnerDigest<C>,
+{
+    let path = tree.path(index).expect("Only valid queries are accepted.");
+    let root = tree.root();
+    for (mutation, path) in mutated_paths(&path, flip_leaf_digest, flip_inner_digest) {
+        assert!(
+            !path.verify(tree.parameters(), root, leaf),
+            "Path for index {index} remained valid after mutation {mutation:?}."
+        );
+    }
+}
+
+/// Tests in-circuit path verification soundness by checking that every mutation of `path` is
+/// rejected as a witness for `leaf` under `root`, both natively and in a fresh compiler built
+/// with `new_compiler`.
+///
+/// The in-circuit verification must compute the same rejection as the native one, so the
+/// compilers must remain satisfied. See [`assert_rejects_mutated_paths`] for the limitations of
+/// the mutations.
+#[inline]
+pub fn assert_rejects_mutated_paths_in_circuit<C, V, COM, L, I, F>(
+    parameters: &Parameters<C>,
+    root: &Root<C>,
+    leaf: &Leaf<C>,
+    path: &Path<C>,
+    flip_leaf_digest: L,
+    flip_inner_digest: I,
+    mut new_compiler: F,
+) where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Clone,
+    InnerDigest<C>: Clone + PartialEq,
+    Leaf<C>: Sized,
+    V: Constant<COM, Type = Parameters<C>> + Model<COM, Verification = Bool<COM>>,
+    V::Item: Variable<Secret, COM, Type = Leaf<C>>,
+    V::Witness: Variable<Secret, COM, Type = Path<C>>,
+    V::Output: Variable<Secret, COM, Type = Root<C>>,
+    Bool<COM>: eclair::cmp::PartialEq<Bool<COM>, COM> + Variable<Secret, COM, Type = bool>,
+    COM: Assert + Has<bool> + Satisfied,
+    L: FnMut(&LeafDigest<C>) -> LeafDigest<C>,
+    I: FnMut(&InnerDigest<C>) -> InnerDigest<C>,
+    F: FnMut() -> COM,
+{
+    for (mutation, path) in mutated_paths(path, flip_leaf_digest, flip_inner_digest) {
+        assert!(
+            !membership_agreement::<_, V, _>(parameters, leaf, &path, root, &mut new_compiler()),
+            "Path remained valid in-circuit after mutation {mutation:?}."
+        );
+    }
+}
+
+/// Naive Merkle Tree
+///
+/// This tree only stores its leaf digests and recomputes every inner digest from scratch, so that
+/// it can serve as the reference model for the production tree types in
+/// [`assert_model_agreement`].
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "LeafDigest<C>: Clone"),
+    Debug(bound = "LeafDigest<C>: Debug"),
+    Default(bound = "")
+)]
+pub struct NaiveMerkleTree<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Leaf Digests
+    leaf_digests: Vec<LeafDigest<C>>,
+}
+
+impl<C> NaiveMerkleTree<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new empty [`NaiveMerkleTree`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the leaf digests of `self`.
+    #[inline]
+    pub fn leaf_digests(&self) -> &[LeafDigest<C>] {
+        &self.leaf_digests
+    }
+
+    /// Returns the number of leaves in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.leaf_digests.len()
+    }
+
+    /// Returns `true` if `self` has no leaves.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leaf_digests.is_empty()
+    }
+
+    /// Appends `leaf_digest` to `self`, returning `false` if `self` is already full.
+    #[inline]
+    pub fn push_digest(&mut self, leaf_digest: LeafDigest<C>) -> bool {
+        if self.len() >= capacity::<C, _>() {
+            return false;
+        }
+        self.leaf_digests.push(leaf_digest);
+        true
+    }
+
+    /// Appends all of `leaf_digests` to `self`, returning `false` and leaving `self` unchanged if
+    /// they do not fit.
+    #[inline]
+    pub fn extend_digests(&mut self, leaf_digests: &[LeafDigest<C>]) -> bool
+    where
+        LeafDigest<C>: Clone,
+    {
+        if self.len() + leaf_digests.len() > capacity::<C, _>() {
+            return false;
+        }
+        self.leaf_digests.extend_from_slice(leaf_digests);
+        true
+    }
+
+    /// Computes the inner digests of every non-empty subtree, level by level from the parents of
+    /// the leaves up to the root. Empty subtrees are left out and stand for the default digest.
+    #[inline]
+    fn levels(&self, parameters: &Parameters<C>) -> Vec<Vec<InnerDigest<C>>>
+    where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        let default_leaf = Default::default();
+        let default_inner = Default::default();
+        let mut levels = Vec::with_capacity(path_length::<C, _>() + 1);
+        let mut level = self
+            .leaf_digests
+            .chunks(2)
+            .map(|pair| parameters.join_leaves(&pair[0], pair.get(1).unwrap_or(&default_leaf)))
+            .collect::<Vec<_>>();
+        for _ in 0..path_length::<C, _>() {
+            let parents = level
+                .chunks(2)
+                .map(|pair| parameters.join(&pair[0], pair.get(1).unwrap_or(&default_inner)))
+                .collect();
+            levels.push(mem::replace(&mut level, parents));
+        }
+        levels.push(level);
+        levels
+    }
+
+    /// Computes the root of `self`.
+    #[inline]
+    pub fn root(&self, parameters: &Parameters<C>) -> Root<C>
+    where
+        LeafDigest<C>: Default,
+        InnerDigest<C>: Default,
+    {
+        self.levels(parameters)
+            .pop()
+            .and_then(|mut root| root.pop())
+            .unwrap_or_default()
+    }
+
+    /// Computes the path of the leaf at `index`, returning `None` if `index` is out of bounds.
+    #[inline]
+    pub fn path(&self, parameters: &Parameters<C>, index: usize) -> Option<Path<C>>
+    where
+        LeafDigest<C>: Clone + Default,
+        InnerDigest<C>: Clone + Default,
+    {
+        if index >= self.len() {
+            return None;
+        }
+        let levels = self.levels(parameters);
+        Some(Path::new(
+            self.leaf_digests
+                .get(index ^ 1)
+                .cloned()
+                .unwrap_or_default(),
+            Node(index),
+            (0..path_length::<C, _>())
+                .map(|height| {
+                    levels[height]
+                        .get((index >> (height + 1)) ^ 1)
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Merkle Tree Operation
+///
+/// The operations performed by [`assert_model_agreement`]. Indices are reduced modulo the number
+/// of available leaves or forks, and operations on forks are skipped while there are none. See
+/// [`random_operations`] for how they are sampled.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Operation<L> {
+    /// Pushes a leaf onto the main tree.
+    Push(L),
+
+    /// Extends the main tree by several leaves at once.
+    Extend(Vec<L>),
+
+    /// Creates a new fork of the trunk.
+    Fork,
+
+    /// Pushes a leaf onto the fork at the given index.
+    PushFork(usize, L),
+
+    /// Merges the fork at the given index into the trunk, and merges the pending leaves of the
+    /// forked main tree into its base tree.
+    Merge(usize),
+
+    /// Attaches the fork at the given index to the trunk.
+    Attach(usize),
+
+    /// Checks the paths at the given index in every tree.
+    Path(usize),
+}
+
+/// Samples `count` random [`Operation`]s, drawing their leaves from `leaf`.
+#[inline]
+pub fn random_operations<L, F, R>(count: usize, rng: &mut R, mut leaf: F) -> Vec<Operation<L>>
+where
+    F: FnMut(&mut R) -> L,
+    R: RngCore + ?Sized,
+{
+    (0..count)
+        .map(|_| {
+            let index = rng.next_u32() as usize;
+            match rng.next_u32() % 8 {
+                0 | 1 => Operation::Push(leaf(rng)),
+                2 => {
+                    let count = rng.next_u32() % 4;
+                    Operation::Extend((0..count).map(|_| leaf(rng)).collect())
+                }
+                3 => Operation::Fork,
+                4 => Operation::PushFork(index, leaf(rng)),
+                5 => Operation::Merge(index),
+                6 => Operation::Attach(index),
+                _ => Operation::Path(index),
+            }
+        })
+        .collect()
+}
+
+/// Fork Model
+///
+/// The reference state of a [`Fork`] in [`assert_model_agreement`].
+struct ForkModel<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Leaves of the Fork including its Base
+    tree: NaiveMerkleTree<C>,
+
+    /// Number of Leaves Contributed by the Base
+    base_len: usize,
+
+    /// Attachment Flag
+    attached: bool,
+}
+
+/// Asserts that `path` is equal to the path at `index` in `model` and that it verifies against the
+/// root of `model`.
+#[inline]
+fn assert_model_path<C>(
+    parameters: &Parameters<C>,
+    model: &NaiveMerkleTree<C>,
+    index: usize,
+    path: Result<Path<C>, PathError>,
+    name: &str,
+    step: usize,
+) where
+    C: Configuration + ?Sized,
+    LeafDigest<C>: Clone + Debug + Default + PartialEq,
+    InnerDigest<C>: Clone + Debug + Default + PartialEq,
+{
+    let expected = model
+        .path(parameters, index)
+        .expect("The index must be in bounds of the model.");
+    assert!(
+        expected.verify_digest(
+            parameters,
+            &model.root(parameters),
+            &model.leaf_digests()[index]
+        ),
+        "The model path at index {index} should verify at step {step}."
+    );
+    assert_eq!(
+        path.as_ref(),
+        Ok(&expected),
+        "The {name} path at index {index} should match the model at step {step}."
+    );
+}
+
+/// Performs `operations` on production trees with the `T` backend and on [`NaiveMerkleTree`]
+/// models, asserting that their roots agree after every operation and that their paths agree
+/// whenever an [`Operation::Path`] is performed.
+///
+/// The main tree is checked as a [`MerkleTree`] and as a [`ForkedTree`] whose pending leaves are
+/// merged on every [`Operation::Merge`]. The forks are checked as [`Fork`]s of a separate
+/// [`Trunk`], which only grows by merging them, so that merges detach the other forks and attaching
+/// them again rebases their leaves onto the new trunk.
+#[inline]
+pub fn assert_model_agreement<C, T>(parameters: &Parameters<C>, operations: &[Operation<Leaf<C>>])
+where
+    C: Configuration + ?Sized,
+    T: Tree<C> + WithProofs<C>,
+    Leaf<C>: Sized,
+    LeafDigest<C>: Clone + Debug + Default + PartialEq,
+    InnerDigest<C>: Clone + Debug + Default + PartialEq,
+    Parameters<C>: Clone,
+{
+    let mut tree = MerkleTree::<C, T>::new(parameters.clone());
+    let mut forked = ForkedTree::<C, T>::new(T::new(parameters), parameters);
+    let mut model = NaiveMerkleTree::<C>::new();
+    let mut trunk = Trunk::<C, T>::new(T::new(parameters));
+    let mut trunk_model = NaiveMerkleTree::<C>::new();
+    let mut forks = Vec::<(Fork<C, T>, ForkModel<C>)>::new();
+    for (step, operation) in operations.iter().enumerate() {
+        match operation {
+            Operation::Push(leaf) => {
+                let pushed = model.push_digest(parameters.digest(leaf));
+                assert_eq!(
+                    tree.push(leaf),
+                    pushed,
+                    "Pushes should agree at step {step}."
+                );
+                assert_eq!(
+                    forked.push(parameters, leaf),
+                    pushed,
+                    "Forked pushes should agree at step {step}."
+                );
+            }
+            Operation::Extend(leaves) => {
+                let extended = model.extend_digests(
+                    &leaves
+                        .iter()
+                        .map(|leaf| parameters.digest(leaf))
+                        .collect::<Vec<_>>(),
+                );
+                assert_eq!(
+                    tree.extend_slice(leaves),
+                    extended,
+                    "Extensions should agree at step {step}."
+                );
+                assert_eq!(
+                    Tree::extend_slice(&mut forked, parameters, leaves),
+                    extended,
+                    "Forked extensions should agree at step {step}."
+                );
+            }
+            Operation::Fork => forks.push((
+                trunk.fork(parameters),
+                ForkModel {
+                    tree: trunk_model.clone(),
+                    base_len: trunk_model.len(),
+                    attached: true,
+                },
+            )),
+            Operation::PushFork(index, leaf) if !forks.is_empty() => {
+                let index = index % forks.len();
+                let (fork, fork_model) = &mut forks[index];
+                let pushed = fork_model
+                    .attached
+                    .then(|| fork_model.tree.push_digest(parameters.digest(leaf)));
+                assert_eq!(
+                    fork.push(parameters, leaf),
+                    pushed,
+                    "Pushes onto fork {index} should agree at step {step}."
+                );
+            }
+            Operation::Merge(index) => {
+                forked.merge_fork(parameters);
+                if !forks.is_empty() {
+                    let (fork, fork_model) = forks.remove(index % forks.len());
+                    match trunk.merge(parameters, fork) {
+                        Ok(()) => {
+                            assert!(
+                                fork_model.attached,
+                                "Only attached forks should merge at step {step}."
+                            );
+                            trunk_model = fork_model.tree;
+                            for (_, fork_model) in &mut forks {
+                                fork_model.attached = false;
+                            }
+                        }
+                        Err(fork) => {
+                            assert!(
+                                !fork_model.attached,
+                                "Attached forks should merge at step {step}."
+                            );
+                            forks.push((fork, fork_model));
+                        }
+                    }
+                }
+            }
+            Operation::Attach(index) if !forks.is_empty() => {
+                let index = index % forks.len();
+                let (fork, fork_model) = &mut forks[index];
+                let mut rebased = trunk_model.clone();
+                let attached =
+                    rebased.extend_digests(&fork_model.tree.leaf_digests()[fork_model.base_len..]);
+                if attached {
+                    *fork_model = ForkModel {
+                        tree: rebased,
+                        base_len: trunk_model.len(),
+                        attached,
+                    };
+                }
+                assert_eq!(
+                    trunk.attach(parameters, fork),
+                    attached,
+                    "Attaching fork {index} should agree at step {step}."
+                );
+            }
+            Operation::Path(index) => {
+                if !model.is_empty() {
+                    let index = index % model.len();
+                    assert_model_path(parameters, &model, index, tree.path(index), "tree", step);
+                    assert_model_path(
+                        parameters,
+                        &model,
+                        index,
+                        forked.path(parameters, index),
+                        "forked tree",
+                        step,
+                    );
+                }
+                if !trunk_model.is_empty() {
+                    let index = index % trunk_model.len();
+                    let base: &T = &trunk;
+                    assert_model_path(
+                        parameters,
+                        &trunk_model,
+                        index,
+                        base.path(parameters, index),
+                        "trunk",
+                        step,
+                    );
+                }
+                for (fork, fork_model) in &forks {
+                    if fork_model.attached && !fork_model.tree.is_empty() {
+                        let index = index % fork_model.tree.len();
+                        assert_model_path(
+                            parameters,
+                            &fork_model.tree,
+                            index,
+                            fork.path(parameters, index),
+                            "fork",
+                            step,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        let root = model.root(parameters);
+        assert_eq!(tree.root(), &root, "Roots should agree at step {step}.");
+        assert_eq!(
+            forked.root(),
+            &root,
+            "Forked roots should agree at step {step}."
+        );
+        let base: &T = &trunk;
+        assert_eq!(
+            base.root(),
+            &trunk_model.root(parameters),
+            "Trunk roots should agree at step {step}."
+        );
+        for (index, (fork, fork_model)) in forks.iter().enumerate() {
+            assert_eq!(
+                fork.is_attached_to(&trunk),
+                fork_model.attached,
+                "Attachment of fork {index} should agree at step {step}."
+            );
+            assert_eq!(
+                fork.root(),
+                &fork_model.tree.root(parameters),
+                "Roots of fork {index} should agree at step {step}."
+            );
+        }
+    }
+}
+
 /// Test Inner Hash
 ///
 /// # Warning
@@ -10,16 +10,17 @@
 
 use crate::{
     accumulator::{
-        self, Accumulator, ConstantCapacityAccumulator, ExactSizeAccumulator, MembershipProof,
-        OptimizedAccumulator,
+        self, Accumulator, ConstantCapacityAccumulator, DomainItem, ExactSizeAccumulator,
+        MembershipProof, OptimizedAccumulator,
     },
     merkle_tree::{
         fork::{ForkedTree, Trunk},
         inner_tree::InnerMap,
-        path::{constraint::PathVar, CurrentPath, Path},
+        path::{constraint::PathVar, CurrentPath, MultiPath, Path},
     },
     NonNative,
 };
+use alloc::vec::Vec;
 use core::{fmt::Debug, hash::Hash, marker::PhantomData};
 use eclair::{
     self,
@@ -89,6 +90,48 @@ where
     }
 }
 
+/// Domain Leaf Hash
+///
+/// Wraps the leaf hash `L` so that the leaves of the merkle tree are [`DomainItem`]s of the item
+/// domain `D`. Trees and forests over a configuration with this leaf hash can only insert or prove
+/// leaves which were explicitly built for the domain `D`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Ord(bound = ""),
+    PartialEq(bound = ""),
+    PartialOrd(bound = "")
+)]
+pub struct DomainLeafHash<L, D>(PhantomData<(L, D)>);
+
+impl<L, D, COM> LeafHash<COM> for DomainLeafHash<L, D>
+where
+    L: LeafHash<COM>,
+{
+    type Leaf = DomainItem<D, L::Leaf>;
+    type Parameters = L::Parameters;
+    type Output = L::Output;
+
+    #[inline]
+    fn digest(
+        parameters: &Self::Parameters,
+        leaf: &Self::Leaf,
+        compiler: &mut COM,
+    ) -> Self::Output {
+        L::digest(parameters, leaf.value(), compiler)
+    }
+}
+
 /// Merkle Tree Inner Hash
 pub trait InnerHash<COM = ()> {
     /// Leaf Digest Type
@@ -215,6 +258,59 @@ where
     C::HEIGHT - 2
 }
 
+/// Merkle Tree Error
+///
+/// This `enum` is the error state of the fallible constructors and size computations in the
+/// [`merkle_tree`](crate::merkle_tree) module, like [`try_path_length`] and [`Path::try_new`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Invalid Height
+    ///
+    /// Merkle trees must have height at least `2`.
+    InvalidHeight(usize),
+
+    /// Invalid Path Length
+    InvalidPathLength {
+        /// Expected Length
+        expected: usize,
+
+        /// Given Length
+        found: usize,
+    },
+}
+
+/// Returns the capacity of the merkle tree with the given [`C::HEIGHT`](Configuration::HEIGHT)
+/// parameter, returning an error if the height is smaller than `2` or if the capacity does not
+/// fit in a `usize`. See [`capacity`] for more.
+#[inline]
+pub fn try_capacity<C, COM>() -> Result<usize, Error>
+where
+    C: Configuration<COM> + ?Sized,
+{
+    if C::HEIGHT < 2 || C::HEIGHT > usize::BITS as usize {
+        return Err(Error::InvalidHeight(C::HEIGHT));
+    }
+    Ok(capacity::<C, COM>())
+}
+
+/// Returns the path length of the merkle tree with the given [`C::HEIGHT`](Configuration::HEIGHT)
+/// parameter, returning an error if the height is smaller than `2`. See [`path_length`] for more.
+#[inline]
+pub fn try_path_length<C, COM>() -> Result<usize, Error>
+where
+    C: Configuration<COM> + ?Sized,
+{
+    if C::HEIGHT < 2 {
+        return Err(Error::InvalidHeight(C::HEIGHT));
+    }
+    Ok(path_length::<C, COM>())
+}
+
 /// Merkle Tree Structure
 pub trait Tree<C>: Sized
 where
@@ -658,6 +754,11 @@ where
         self.inner.encode(&mut writer)?;
         Ok(())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.leaf.encoded_len() + self.inner.encoded_len()
+    }
 }
 
 /// Merkle Tree Root
@@ -993,6 +1094,25 @@ where
         self.tree.path(&self.parameters, index)
     }
 
+    /// Returns the multi-path for the leaves stored at the given `indices` if they all exist.
+    ///
+    /// See [`MultiPath::from_paths`] for more.
+    #[inline]
+    pub fn multi_path<I>(&self, indices: I) -> Result<MultiPath<C>, PathError>
+    where
+        T: WithProofs<C>,
+        I: IntoIterator<Item = usize>,
+        LeafDigest<C>: Clone,
+        InnerDigest<C>: Clone,
+    {
+        Ok(MultiPath::from_paths(
+            &indices
+                .into_iter()
+                .map(|index| self.path(index))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
     /// Converts `self` into a fork-able merkle tree.
     ///
     /// Use [`Trunk::into_tree`] to convert back.
@@ -1143,3 +1263,96 @@ where
         self.tree.merge_fork(&self.parameters);
     }
 }
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::{
+        accumulator::test::assert_provable_membership,
+        merkle_tree::{
+            full::FullMerkleTree,
+            test::{assert_rejects_mutated_paths, Test},
+            Node,
+        },
+    };
+    use alloc::{format, string::String};
+
+    /// Test Item Domain
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Notes;
+
+    /// Test Configuration with Leaves in the [`Notes`] Domain
+    struct NoteTree;
+
+    impl HashConfiguration for NoteTree {
+        type LeafHash = DomainLeafHash<IdentityLeafHash<u64>, Notes>;
+        type InnerHash = Test<u64, 4>;
+    }
+
+    impl Configuration for NoteTree {
+        const HEIGHT: usize = 4;
+    }
+
+    /// Tests that a tree over domain leaves accepts and proves items of its domain, and that
+    /// tagging the leaves does not change the root of the tree.
+    #[test]
+    fn domain_leaves_match_untagged_tree() {
+        let values = [3, 1, 4, 1, 5];
+        let mut tree = FullMerkleTree::<NoteTree>::new(Parameters::new((), ()));
+        assert!(tree.extend(&values.map(DomainItem::new)));
+        assert_provable_membership(&mut tree, &DomainItem::new(9));
+        let untagged = FullMerkleTree::<Test<u64, 4>>::from_iter(
+            Parameters::new((), ()),
+            values.iter().chain([9].iter()),
+        )
+        .expect("The values should fit in the tree.");
+        assert_eq!(
+            tree.root(),
+            untagged.root(),
+            "Domain tags should not change the root of the tree."
+        );
+    }
+
+    /// Tests that the fallible size computations reject heights which are too small or too large.
+    #[test]
+    fn invalid_heights_are_rejected() {
+        assert_eq!(
+            try_capacity::<Test<u64, 1>, ()>(),
+            Err(Error::InvalidHeight(1))
+        );
+        assert_eq!(
+            try_path_length::<Test<u64, 1>, ()>(),
+            Err(Error::InvalidHeight(1))
+        );
+        assert_eq!(
+            try_capacity::<Test<u64, 65>, ()>(),
+            Err(Error::InvalidHeight(65)),
+            "Capacities which do not fit in a `usize` must be rejected."
+        );
+        assert_eq!(
+            Path::<Test<u64, 1>>::try_new(0, Node(0), Vec::new()),
+            Err(Error::InvalidHeight(1)),
+            "Paths of trees with invalid heights must be rejected."
+        );
+        assert_eq!(try_capacity::<Test<u64, 4>, ()>(), Ok(8));
+        assert_eq!(try_path_length::<Test<u64, 4>, ()>(), Ok(2));
+    }
+
+    /// Tests that every mutation of every path of a full tree is rejected natively, using the
+    /// non-commutative [`Test`] configuration over strings with distinct leaves of equal length.
+    #[test]
+    fn mutated_paths_are_rejected() {
+        let leaves = (0..8).map(|i| format!("{i:02}")).collect::<Vec<_>>();
+        let tree = FullMerkleTree::<Test<String, 4>>::from_iter(Parameters::new((), ()), &leaves)
+            .expect("The leaves should fit in the tree.");
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert_rejects_mutated_paths(
+                &tree,
+                index,
+                leaf,
+                |digest| format!("{digest}!"),
+                |digest| format!("{digest}!"),
+            );
+        }
+    }
+}
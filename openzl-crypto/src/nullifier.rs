@@ -0,0 +1,403 @@
+//! Nullifiers
+//!
+//! A nullifier marks an accumulator item as spent without revealing which item was spent. It is
+//! derived with a pseudorandom function of the secret key of the owner and an identifier of the
+//! item, so only the owner can compute it and every item has exactly one nullifier. A
+//! [`Nullifier`] carries the type of the items it nullifies, so that nullifiers of different
+//! protocols cannot be mixed up, and a [`NullifierSet`] stores the revealed nullifiers of one item
+//! type and rejects duplicates.
+
+use crate::{
+    accumulator::{Accumulator, MembershipProof, Types},
+    constraint::{HasInput, Input},
+    hash::prf::PseudorandomFunction,
+};
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use eclair::{
+    alloc::{Allocate, Allocator, Constant, Variable},
+    bool::{Assert, AssertEq, Bool},
+    Has,
+};
+use openzl_util::{
+    codec::{Encode, Write},
+    derivative,
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Nullifier Type
+///
+/// This marker `trait` ties a nullifier type to the type of the accumulator items it nullifies.
+pub trait NullifierType {
+    /// Nullified Item Type
+    type Item: ?Sized;
+}
+
+/// Nullifier
+///
+/// This `struct` wraps the raw nullifier `value` of an item of type `I`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "T: Deserialize<'de>", serialize = "T: Serialize"),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "T: Clone"),
+    Copy(bound = "T: Copy"),
+    Debug(bound = "T: Debug"),
+    Default(bound = "T: Default"),
+    Eq(bound = "T: Eq"),
+    Hash(bound = "T: Hash"),
+    Ord(bound = "T: Ord"),
+    PartialEq(bound = "T: PartialEq"),
+    PartialOrd(bound = "T: PartialOrd")
+)]
+pub struct Nullifier<T, I>
+where
+    I: ?Sized,
+{
+    /// Nullifier Value
+    pub value: T,
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<I>,
+}
+
+impl<T, I> Nullifier<T, I>
+where
+    I: ?Sized,
+{
+    /// Builds a new [`Nullifier`] for an item of type `I` from its raw `value`.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            __: PhantomData,
+        }
+    }
+
+    /// Returns the raw value of `self`.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, I> NullifierType for Nullifier<T, I>
+where
+    I: ?Sized,
+{
+    type Item = I;
+}
+
+impl<T, I, COM> eclair::cmp::PartialEq<Self, COM> for Nullifier<T, I>
+where
+    T: eclair::cmp::PartialEq<T, COM>,
+    I: ?Sized,
+    COM: Has<bool>,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self, compiler: &mut COM) -> Bool<COM> {
+        self.value.eq(&rhs.value, compiler)
+    }
+
+    #[inline]
+    fn assert_equal(&self, rhs: &Self, compiler: &mut COM)
+    where
+        COM: Assert,
+    {
+        self.value.assert_equal(&rhs.value, compiler)
+    }
+}
+
+impl<T, I, M, COM> Variable<M, COM> for Nullifier<T, I>
+where
+    T: Variable<M, COM>,
+    I: ?Sized,
+{
+    type Type = Nullifier<T::Type, I>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.value.as_known(compiler))
+    }
+}
+
+impl<T, I> Encode for Nullifier<T, I>
+where
+    T: Encode,
+    I: ?Sized,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.value.encode(writer)
+    }
+}
+
+impl<T, I, P> Input<P> for Nullifier<T, I>
+where
+    I: ?Sized,
+    P: HasInput<T> + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut P::Input) {
+        P::extend(input, &self.value)
+    }
+}
+
+/// Nullifier Derivation
+pub trait Derivation<COM = ()> {
+    /// Secret Key Type
+    type SecretKey: ?Sized;
+
+    /// Item Identifier Type
+    type Item: ?Sized;
+
+    /// Nullifier Type
+    type Nullifier: NullifierType<Item = Self::Item>;
+
+    /// Derives the nullifier of `item` owned by `secret_key`.
+    fn derive(
+        &self,
+        secret_key: &Self::SecretKey,
+        item: &Self::Item,
+        compiler: &mut COM,
+    ) -> Self::Nullifier;
+
+    /// Asserts that `nullifier` is the nullifier of `item` owned by `secret_key`.
+    #[inline]
+    fn assert_derived(
+        &self,
+        secret_key: &Self::SecretKey,
+        item: &Self::Item,
+        nullifier: &Self::Nullifier,
+        compiler: &mut COM,
+    ) where
+        Self::Nullifier: eclair::cmp::PartialEq<Self::Nullifier, COM>,
+        COM: Assert,
+    {
+        let expected = self.derive(secret_key, item, compiler);
+        compiler.assert_eq(&expected, nullifier);
+    }
+}
+
+/// Pseudorandom Function Nullifier Derivation
+///
+/// Derives the nullifier of an item as `F(secret_key, item)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PrfDerivation<F>(
+    /// Pseudorandom Function
+    pub F,
+);
+
+impl<F, COM> Constant<COM> for PrfDerivation<F>
+where
+    F: Constant<COM>,
+{
+    type Type = PrfDerivation<F::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+impl<F, COM> Derivation<COM> for PrfDerivation<F>
+where
+    F: PseudorandomFunction<COM>,
+{
+    type SecretKey = F::Key;
+    type Item = F::Input;
+    type Nullifier = Nullifier<F::Output, F::Input>;
+
+    #[inline]
+    fn derive(
+        &self,
+        secret_key: &Self::SecretKey,
+        item: &Self::Item,
+        compiler: &mut COM,
+    ) -> Self::Nullifier {
+        Nullifier::new(self.0.evaluate(secret_key, item, compiler))
+    }
+}
+
+/// Nullifier Set Error
+///
+/// This `enum` is the error state of [`NullifierSet::insert`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NullifierSetError {
+    /// Duplicate Nullifier
+    ///
+    /// The nullifier was already revealed, so its item was already spent.
+    Duplicate,
+
+    /// Capacity Exhausted
+    ///
+    /// The underlying accumulator has no room for another nullifier.
+    CapacityExhausted,
+}
+
+/// Nullifier Set
+///
+/// A nullifier set stores the raw nullifier values of items of type `I` in the accumulator `A`
+/// and rejects every nullifier it already contains.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "A: Deserialize<'de>", serialize = "A: Serialize"),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "A: Clone"),
+    Debug(bound = "A: Debug"),
+    Default(bound = "A: Default"),
+    Eq(bound = "A: Eq"),
+    Hash(bound = "A: Hash"),
+    PartialEq(bound = "A: PartialEq")
+)]
+pub struct NullifierSet<A, I>
+where
+    I: ?Sized,
+{
+    /// Nullifier Accumulator
+    accumulator: A,
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<I>,
+}
+
+impl<A, I> NullifierSet<A, I>
+where
+    A: Accumulator,
+    I: ?Sized,
+{
+    /// Builds a new [`NullifierSet`] over `accumulator`.
+    #[inline]
+    pub fn new(accumulator: A) -> Self {
+        Self {
+            accumulator,
+            __: PhantomData,
+        }
+    }
+
+    /// Returns a shared reference to the underlying accumulator.
+    #[inline]
+    pub fn accumulator(&self) -> &A {
+        &self.accumulator
+    }
+
+    /// Extracts the underlying accumulator from `self`.
+    #[inline]
+    pub fn into_inner(self) -> A {
+        self.accumulator
+    }
+
+    /// Returns `true` if `nullifier` was already inserted into `self`.
+    #[inline]
+    pub fn contains(&self, nullifier: &Nullifier<A::Item, I>) -> bool {
+        self.accumulator.contains(&nullifier.value)
+    }
+
+    /// Inserts `nullifier` into `self`, returning an error if it was already inserted or if the
+    /// underlying accumulator is full.
+    #[inline]
+    pub fn insert(&mut self, nullifier: &Nullifier<A::Item, I>) -> Result<(), NullifierSetError> {
+        if self.contains(nullifier) {
+            return Err(NullifierSetError::Duplicate);
+        }
+        if !self.accumulator.insert(&nullifier.value) {
+            return Err(NullifierSetError::CapacityExhausted);
+        }
+        Ok(())
+    }
+
+    /// Returns a membership proof for `nullifier` if it is contained in `self`.
+    #[inline]
+    pub fn prove(&self, nullifier: &Nullifier<A::Item, I>) -> Option<MembershipProof<A::Model>> {
+        self.accumulator.prove(&nullifier.value)
+    }
+}
+
+impl<A, I> Types for NullifierSet<A, I>
+where
+    A: Types,
+    I: ?Sized,
+{
+    type Item = A::Item;
+    type Witness = A::Witness;
+    type Output = A::Output;
+}
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::{full::FullMerkleTree, test::Test};
+
+    /// Test Pseudorandom Function
+    ///
+    /// # Warning
+    ///
+    /// This is only meant for testing purposes, and is not a pseudorandom function.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct TestPrf;
+
+    impl PseudorandomFunction for TestPrf {
+        type Key = u64;
+        type Input = u64;
+        type Output = u64;
+
+        #[inline]
+        fn evaluate(&self, key: &u64, input: &u64, _: &mut ()) -> u64 {
+            key.rotate_left(17) ^ input
+        }
+    }
+
+    /// Tests that a nullifier set accepts every derived nullifier exactly once.
+    #[test]
+    fn nullifier_set_rejects_duplicates() {
+        let derivation = PrfDerivation(TestPrf);
+        let mut set =
+            NullifierSet::<_, u64>::new(FullMerkleTree::<Test<u64, 3>>::new(Default::default()));
+        for item in 0..4 {
+            let nullifier = derivation.derive(&7, &item, &mut ());
+            assert_eq!(set.insert(&nullifier), Ok(()));
+            assert_eq!(set.insert(&nullifier), Err(NullifierSetError::Duplicate));
+            assert!(set.prove(&nullifier).is_some());
+        }
+        let nullifier = derivation.derive(&7, &4, &mut ());
+        assert_eq!(
+            set.insert(&nullifier),
+            Err(NullifierSetError::CapacityExhausted)
+        );
+    }
+}
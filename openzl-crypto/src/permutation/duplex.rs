@@ -6,16 +6,17 @@
 use crate::{
     constraint::{HasInput, Input},
     encryption::{
-        CiphertextType, Decrypt, DecryptedPlaintextType, DecryptionKeyType, Encrypt,
+        self, CiphertextType, Decrypt, DecryptedPlaintextType, DecryptionKeyType, Encrypt,
         EncryptionKeyType, HeaderType, PlaintextType, RandomnessType,
     },
     permutation::{
         sponge::{Read, Sponge, Write},
         PseudorandomPermutation,
     },
+    security::{self, SecurityLevel},
 };
 use alloc::vec::Vec;
-use core::marker::PhantomData;
+use core::{fmt::Debug, hash::Hash, marker::PhantomData, mem};
 use eclair::{
     self,
     alloc::{mode::Public, Allocate, Allocator, Constant, Variable},
@@ -105,6 +106,25 @@ where
     ) -> Self::Verification;
 }
 
+/// Duplex Sponge Forking
+pub trait Fork<P, COM = ()>: Types<P, COM>
+where
+    P: PseudorandomPermutation<COM>,
+{
+    /// Fork Label Type
+    type Label;
+
+    /// Separates the `state` of a new fork with `label` from its parent and from every fork with
+    /// a different label.
+    ///
+    /// # Crypto Safety
+    ///
+    /// The separation must not be reachable by writing any setup, plaintext, or ciphertext block
+    /// into the state, for example by writing `label` into the capacity of the sponge which the
+    /// blocks never touch. The [`Session`] applies the permutation after this method returns.
+    fn separate(&self, state: &mut P::Domain, label: &Self::Label, compiler: &mut COM);
+}
+
 /// Ciphertext Payload
 #[cfg_attr(
     feature = "serde",
@@ -170,6 +190,35 @@ where
     }
 }
 
+/// Ciphertext Decode Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CiphertextDecodeError<T, C> {
+    /// Tag Decoding Error
+    Tag(T),
+
+    /// Message Decoding Error
+    Message(C),
+}
+
+impl<T, C> Decode for Ciphertext<T, C>
+where
+    T: Decode,
+    C: Decode,
+{
+    type Error = CiphertextDecodeError<T::Error, C::Error>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        Ok(Self::new(
+            T::decode(&mut reader).map_err(|err| err.map_decode(CiphertextDecodeError::Tag))?,
+            C::decode(&mut reader).map_err(|err| err.map_decode(CiphertextDecodeError::Message))?,
+        ))
+    }
+}
+
 impl<T, C> Encode for Ciphertext<T, C>
 where
     T: Encode,
@@ -287,6 +336,193 @@ where
     }
 }
 
+impl<P, C, COM> Duplexer<P, C, COM>
+where
+    P: PseudorandomPermutation<COM>,
+    C: Setup<P, COM>,
+{
+    /// Starts a new [`Session`] by absorbing the `key` and `header`.
+    ///
+    /// Duplexing the plaintext blocks of a message through the session and reading its
+    /// [`tag`](Session::tag) yields the same ciphertext and tag as [`Encrypt::encrypt`].
+    #[inline]
+    pub fn session(
+        &self,
+        key: &C::Key,
+        header: &C::Header,
+        compiler: &mut COM,
+    ) -> Session<P, C, COM> {
+        Session {
+            duplexer: self,
+            state: self.setup(key, header, compiler),
+        }
+    }
+}
+
+/// Duplex Sponge Session
+///
+/// A session keeps the running state of a [`Duplexer`] so that blocks can be absorbed and values
+/// squeezed one at a time, like a transcript for a multi-round protocol. Sessions can be saved
+/// with [`checkpoint`](Self::checkpoint) and rolled back with [`restore`](Self::restore), or
+/// split with [`fork`](Self::fork) into independent sessions which derive unrelated outputs.
+///
+/// This `struct` is created by the [`session`](Duplexer::session) method on [`Duplexer`].
+/// See its documentation for more.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "P::Domain: Clone"),
+    Debug(bound = "P: Debug, C: Debug, COM: Debug, P::Domain: Debug")
+)]
+pub struct Session<'d, P, C, COM = ()>
+where
+    P: PseudorandomPermutation<COM>,
+    C: Types<P, COM>,
+{
+    /// Duplexer
+    duplexer: &'d Duplexer<P, C, COM>,
+
+    /// Sponge State
+    state: P::Domain,
+}
+
+impl<'d, P, C, COM> Session<'d, P, C, COM>
+where
+    P: PseudorandomPermutation<COM>,
+    C: Types<P, COM>,
+{
+    /// Returns the duplexer that `self` runs on.
+    #[inline]
+    pub fn duplexer(&self) -> &'d Duplexer<P, C, COM> {
+        self.duplexer
+    }
+
+    /// Returns the current sponge state of `self`.
+    #[inline]
+    pub fn state(&self) -> &P::Domain {
+        &self.state
+    }
+
+    /// Absorbs `input` into the state, returning the output of the write.
+    ///
+    /// For a plaintext block this returns its ciphertext block and for a ciphertext block this
+    /// returns its plaintext block.
+    #[inline]
+    pub fn absorb<W>(&mut self, input: &W, compiler: &mut COM) -> W::Output
+    where
+        W: Write<P, COM>,
+    {
+        Sponge::new(&self.duplexer.permutation, &mut self.state).absorb(input, compiler)
+    }
+
+    /// Squeezes a value of type `R` out of the state.
+    #[inline]
+    pub fn squeeze<R>(&mut self, compiler: &mut COM) -> R
+    where
+        R: Read<P, COM>,
+    {
+        Sponge::new(&self.duplexer.permutation, &mut self.state).squeeze(compiler)
+    }
+
+    /// Reads the authentication tag of the blocks absorbed so far without changing the state.
+    #[inline]
+    pub fn tag(&self, compiler: &mut COM) -> C::Tag {
+        C::Tag::read(&self.state, compiler)
+    }
+
+    /// Saves the current state of `self` into a [`Checkpoint`].
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint<P, COM>
+    where
+        P::Domain: Clone,
+    {
+        Checkpoint {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Rolls `self` back to the state saved in `checkpoint`, returning the state that was
+    /// replaced as a new [`Checkpoint`].
+    ///
+    /// The `checkpoint` should come from a session on the same duplexer, otherwise the restored
+    /// session continues from a state that its duplexer never produced.
+    #[inline]
+    pub fn restore(&mut self, checkpoint: Checkpoint<P, COM>) -> Checkpoint<P, COM> {
+        Checkpoint {
+            state: mem::replace(&mut self.state, checkpoint.state),
+        }
+    }
+
+    /// Forks a new session off of `self` which is separated from `self` and from every fork with
+    /// a different `label`, leaving `self` unchanged.
+    ///
+    /// Forking twice with the same `label` from the same state yields the same session, so labels
+    /// should be unique for every fork taken at a given point of the protocol.
+    #[inline]
+    pub fn fork(&self, label: &C::Label, compiler: &mut COM) -> Self
+    where
+        C: Fork<P, COM>,
+        P::Domain: Clone,
+    {
+        let mut state = self.state.clone();
+        self.duplexer
+            .configuration
+            .separate(&mut state, label, compiler);
+        self.duplexer.permutation.permute(&mut state, compiler);
+        Self {
+            duplexer: self.duplexer,
+            state,
+        }
+    }
+}
+
+/// Duplex Sponge Checkpoint
+///
+/// A saved sponge state of a [`Session`] which can be restored with [`Session::restore`].
+///
+/// This `struct` is created by the [`checkpoint`](Session::checkpoint) method on [`Session`].
+/// See its documentation for more.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "P::Domain: Clone"),
+    Debug(bound = "P::Domain: Debug"),
+    Eq(bound = "P::Domain: Eq"),
+    Hash(bound = "P::Domain: Hash"),
+    PartialEq(bound = "P::Domain: PartialEq")
+)]
+pub struct Checkpoint<P, COM = ()>
+where
+    P: PseudorandomPermutation<COM>,
+{
+    /// Sponge State
+    state: P::Domain,
+}
+
+impl<P, COM> Checkpoint<P, COM>
+where
+    P: PseudorandomPermutation<COM>,
+{
+    /// Returns the saved sponge state.
+    #[inline]
+    pub fn state(&self) -> &P::Domain {
+        &self.state
+    }
+}
+
+impl<P, C, COM> SecurityLevel for Duplexer<P, C, COM>
+where
+    P: PseudorandomPermutation<COM> + SecurityLevel,
+    C: Types<P, COM> + SecurityLevel,
+{
+    const SECURITY_LEVEL: u32 = security::min(P::SECURITY_LEVEL, C::SECURITY_LEVEL);
+}
+
+impl<P, C, COM> encryption::security::KeyPrivacy for Duplexer<P, C, COM>
+where
+    P: PseudorandomPermutation<COM>,
+    C: Types<P, COM>,
+{
+}
+
 impl<P, C, COM> Constant<COM> for Duplexer<P, C, COM>
 where
     P: PseudorandomPermutation<COM> + Constant<COM>,
@@ -0,0 +1,324 @@
+//! Batched Poseidon Permutation
+//!
+//! Building large merkle trees spends most of its time hashing independent leaves. Instead of
+//! permuting one [`State`] at a time, [`BatchState`] stores many states in a structure-of-arrays
+//! layout where the `i`-th column holds the `i`-th element of every state. Every step of a round
+//! then runs as a tight loop over one contiguous column with the same constant, which lets the
+//! compiler autovectorize the field arithmetic whenever the [`Specification`] allows it.
+
+use crate::poseidon::{additive_keys, Permutation, Specification, State};
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::{
+    accelerator::{Accelerator, Execute, Job},
+    derivative,
+};
+
+/// Batched Poseidon State
+///
+/// This `struct` stores [`S::WIDTH`](crate::poseidon::Constants::WIDTH)-many columns of equal
+/// length, one for each element of the permutation state, with one lane for each state in the
+/// batch.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "S::Field: Clone"),
+    Debug(bound = "S::Field: Debug"),
+    Eq(bound = "S::Field: Eq"),
+    Hash(bound = "S::Field: Hash"),
+    PartialEq(bound = "S::Field: PartialEq")
+)]
+pub struct BatchState<S, COM = ()>
+where
+    S: Specification<COM>,
+{
+    /// State Columns
+    columns: Box<[Vec<S::Field>]>,
+}
+
+impl<S, COM> BatchState<S, COM>
+where
+    S: Specification<COM>,
+{
+    /// Builds a new [`BatchState`] from `columns`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there are not exactly [`S::WIDTH`](crate::poseidon::Constants::WIDTH)
+    /// columns or if the columns do not all have the same length.
+    #[inline]
+    pub fn from_columns(columns: Box<[Vec<S::Field>]>) -> Self {
+        assert_eq!(
+            columns.len(),
+            S::WIDTH,
+            "The number of columns must be equal to the width of the permutation."
+        );
+        assert!(
+            columns
+                .iter()
+                .all(|column| column.len() == columns[0].len()),
+            "All columns must have the same length."
+        );
+        Self { columns }
+    }
+
+    /// Builds a new [`BatchState`] by transposing `states` into columns.
+    #[inline]
+    pub fn from_states<I>(states: I) -> Self
+    where
+        I: IntoIterator<Item = State<S, COM>>,
+    {
+        let states = states.into_iter();
+        let mut columns = (0..S::WIDTH)
+            .map(|_| Vec::with_capacity(states.size_hint().0))
+            .collect::<Box<[_]>>();
+        for state in states {
+            for (column, elem) in columns.iter_mut().zip(state.0.into_vec()) {
+                column.push(elem);
+            }
+        }
+        Self { columns }
+    }
+
+    /// Returns the number of states in the batch.
+    #[inline]
+    pub fn lanes(&self) -> usize {
+        self.columns[0].len()
+    }
+
+    /// Returns `true` if the batch contains no states.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lanes() == 0
+    }
+
+    /// Returns the column of the `index`-th state element of every state in the batch.
+    #[inline]
+    pub fn column(&self, index: usize) -> &[S::Field] {
+        &self.columns[index]
+    }
+
+    /// Returns the columns of `self`.
+    #[inline]
+    pub fn into_columns(self) -> Box<[Vec<S::Field>]> {
+        self.columns
+    }
+
+    /// Transposes `self` back into a vector of states.
+    #[inline]
+    pub fn into_states(self) -> Vec<State<S, COM>> {
+        let lanes = self.lanes();
+        let mut columns = self
+            .columns
+            .into_vec()
+            .into_iter()
+            .map(Vec::into_iter)
+            .collect::<Vec<_>>();
+        (0..lanes)
+            .map(|_| {
+                State(
+                    columns
+                        .iter_mut()
+                        .map(|column| column.next().expect("All columns have the same length."))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Adds the round constants in `additive_keys_current_round` to every state in the batch.
+    #[inline]
+    fn add_round_keys(
+        &mut self,
+        additive_keys_current_round: &[S::ParameterField],
+        compiler: &mut COM,
+    ) {
+        for (column, key) in self.columns.iter_mut().zip(additive_keys_current_round) {
+            for elem in column {
+                S::add_const_assign(elem, key, compiler);
+            }
+        }
+    }
+
+    /// Applies the S-BOX to every element of `column`.
+    #[inline]
+    fn apply_sbox(column: &mut [S::Field], compiler: &mut COM) {
+        for elem in column {
+            S::apply_sbox(elem, compiler);
+        }
+    }
+
+    /// Computes the MDS matrix multiplication against every state in the batch, accumulating one
+    /// output column at a time.
+    #[inline]
+    fn mds_matrix_multiply(&mut self, mds_matrix: &[S::ParameterField], compiler: &mut COM) {
+        let next = mds_matrix
+            .chunks(S::WIDTH)
+            .map(|row| {
+                let mut columns = self.columns.iter().zip(row);
+                let (column, coefficient) = columns.next().expect("The state is never empty.");
+                let mut acc = column
+                    .iter()
+                    .map(|elem| S::mul_const(elem, coefficient, compiler))
+                    .collect::<Vec<_>>();
+                for (column, coefficient) in columns {
+                    for (acc, elem) in acc.iter_mut().zip(column) {
+                        let term = S::mul_const(elem, coefficient, compiler);
+                        S::add_assign(acc, &term, compiler);
+                    }
+                }
+                acc
+            })
+            .collect();
+        self.columns = next;
+    }
+
+    /// Computes a full round on every state in the batch.
+    #[inline]
+    fn full_round(
+        &mut self,
+        additive_keys_current_round: &[S::ParameterField],
+        mds_matrix: &[S::ParameterField],
+        compiler: &mut COM,
+    ) {
+        self.add_round_keys(additive_keys_current_round, compiler);
+        for column in self.columns.iter_mut() {
+            Self::apply_sbox(column, compiler);
+        }
+        self.mds_matrix_multiply(mds_matrix, compiler);
+    }
+
+    /// Computes a partial round on every state in the batch.
+    #[inline]
+    fn partial_round(
+        &mut self,
+        additive_keys_current_round: &[S::ParameterField],
+        mds_matrix: &[S::ParameterField],
+        compiler: &mut COM,
+    ) {
+        self.add_round_keys(additive_keys_current_round, compiler);
+        Self::apply_sbox(&mut self.columns[0], compiler);
+        self.mds_matrix_multiply(mds_matrix, compiler);
+    }
+}
+
+impl<S, COM> Permutation<S, COM>
+where
+    S: Specification<COM>,
+{
+    /// Permutes every state in `batch`, computing the same result as running
+    /// [`permute`](crate::permutation::PseudorandomPermutation::permute) on each of them.
+    #[inline]
+    pub fn permute_batch(&self, batch: &mut BatchState<S, COM>, compiler: &mut COM) {
+        let keys = |round| additive_keys::<S, S, COM>(&self.additive_round_keys, round);
+        for round in 0..S::HALF_FULL_ROUNDS {
+            batch.full_round(keys(round), &self.mds_matrix, compiler);
+        }
+        for round in S::HALF_FULL_ROUNDS..(S::HALF_FULL_ROUNDS + S::PARTIAL_ROUNDS) {
+            batch.partial_round(keys(round), &self.mds_matrix, compiler);
+        }
+        for round in (S::HALF_FULL_ROUNDS + S::PARTIAL_ROUNDS)..S::ROUNDS {
+            batch.full_round(keys(round), &self.mds_matrix, compiler);
+        }
+    }
+}
+
+impl<S> Permutation<S>
+where
+    S: Specification,
+{
+    /// Permutes every state in `batch` on `accelerator`. See [`permute_batch`](Self::permute_batch)
+    /// for more.
+    #[inline]
+    pub fn permute_batch_with<'p, A>(
+        &'p self,
+        batch: BatchState<S>,
+        accelerator: &mut A,
+    ) -> Result<BatchState<S>, A::Error>
+    where
+        A: Accelerator<PermuteBatch<'p, S>>,
+    {
+        accelerator.run(PermuteBatch::new(self, batch))
+    }
+}
+
+/// Batched Permutation Job
+///
+/// This [`Job`] permutes every state of its batch with its permutation, so that batched Poseidon
+/// hashing can be offloaded to an [`Accelerator`].
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "S::Field: Clone"),
+    Debug(bound = "S::ParameterField: Debug, S::Field: Debug")
+)]
+pub struct PermuteBatch<'p, S>
+where
+    S: Specification,
+{
+    /// Permutation
+    pub permutation: &'p Permutation<S>,
+
+    /// Batch of States
+    pub batch: BatchState<S>,
+}
+
+impl<'p, S> PermuteBatch<'p, S>
+where
+    S: Specification,
+{
+    /// Builds a new [`PermuteBatch`] job from `permutation` and `batch`.
+    #[inline]
+    pub fn new(permutation: &'p Permutation<S>, batch: BatchState<S>) -> Self {
+        Self { permutation, batch }
+    }
+}
+
+impl<'p, S> Job for PermuteBatch<'p, S>
+where
+    S: Specification,
+{
+    type Output = BatchState<S>;
+}
+
+impl<'p, S> Execute for PermuteBatch<'p, S>
+where
+    S: Specification,
+{
+    #[inline]
+    fn execute(mut self) -> Self::Output {
+        self.permutation.permute_batch(&mut self.batch, &mut ());
+        self.batch
+    }
+}
+
+/// Builds the [`BatchState`] whose first column is filled with `domain_tag` and whose remaining
+/// columns are the transposition of `inputs`.
+#[inline]
+pub(super) fn tagged_batch<S, COM, const ARITY: usize>(
+    domain_tag: &S::Field,
+    inputs: &[[S::Field; ARITY]],
+) -> BatchState<S, COM>
+where
+    S: Specification<COM>,
+    S::Field: Clone,
+{
+    let mut columns = Vec::with_capacity(ARITY + 1);
+    columns.push(alloc::vec![domain_tag.clone(); inputs.len()]);
+    for i in 0..ARITY {
+        columns.push(inputs.iter().map(|input| input[i].clone()).collect());
+    }
+    BatchState::from_columns(columns.into_boxed_slice())
+}
+
+/// Returns the first field element of every state of `batch`.
+#[inline]
+pub(super) fn first_column<S, COM>(batch: BatchState<S, COM>) -> Vec<S::Field>
+where
+    S: Specification<COM>,
+{
+    batch
+        .into_columns()
+        .into_vec()
+        .into_iter()
+        .next()
+        .expect("The state is never empty.")
+}
@@ -3,7 +3,7 @@
 use crate::poseidon::Specification;
 
 #[cfg(feature = "std")]
-use {openzl_util::num::Ceil, security::SecurityCondition};
+use {crate::poseidon::Error, openzl_util::num::Ceil, security::SecurityCondition};
 
 /// Poseidon Security Parameters
 ///
@@ -141,15 +141,28 @@ impl Constants {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     #[inline]
     pub fn from_specification<S>() -> Self
+    where
+        S: Specification,
+    {
+        Self::try_from_specification::<S>().expect("Poseidon constants need to be secure.")
+    }
+
+    /// Converts a [`Specification`] into [`Constants`], returning an error if the constants are
+    /// not secure. See [`from_specification`] for the panicking version of this method.
+    ///
+    /// [`from_specification`]: Self::from_specification
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn try_from_specification<S>() -> Result<Self, Error>
     where
         S: Specification,
     {
         let constants = Self::from_specification_unchecked::<S>();
-        assert!(
-            constants.are_secure(),
-            "Poseidon constants need to be secure."
-        );
-        constants
+        if !constants.are_secure() {
+            return Err(Error::InsecureConstants);
+        }
+        Ok(constants)
     }
 
     /// Returns `true` if `self` are secure constants under the conditions set out in the Poseidon
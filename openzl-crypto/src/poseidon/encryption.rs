@@ -1,15 +1,20 @@
 //! Poseidon Encryption Implementation
 
 use crate::{
-    constraint::{HasInput, Input},
+    constraint::{committed_inputs, HasInput, Input},
+    encryption::EncryptedMessage,
+    merkle_tree::LeafHash,
     permutation::{
-        duplex::{self, Setup, Types, Verify},
+        duplex::{self, Fork, Setup, Types, Verify},
         sponge,
     },
-    poseidon::{Permutation, Specification, State},
+    poseidon::{
+        hash::{DomainTag, Hasher},
+        Permutation, Specification, State,
+    },
 };
 use alloc::{boxed::Box, vec::Vec};
-use core::{fmt::Debug, hash::Hash, iter, ops::Deref, slice};
+use core::{fmt::Debug, hash::Hash, iter, marker::PhantomData, ops::Deref, slice};
 use eclair::{
     self,
     alloc::{
@@ -308,6 +313,22 @@ where
     }
 }
 
+impl<S> Decode for CiphertextBlock<S>
+where
+    S: Specification,
+    S::Field: Decode,
+{
+    type Error = Option<<S::Field as Decode>::Error>;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        Ok(Self(Decode::decode(reader)?))
+    }
+}
+
 impl<S> Encode for CiphertextBlock<S>
 where
     S: Specification,
@@ -385,6 +406,21 @@ impl<'b, B, const N: usize> IntoIterator for &'b BlockArray<B, N> {
     }
 }
 
+impl<B, const N: usize> Decode for BlockArray<B, N>
+where
+    B: Decode,
+{
+    type Error = Option<B::Error>;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        Ok(Self(<[B; N]>::decode(reader)?.into()))
+    }
+}
+
 impl<B, const N: usize> Encode for BlockArray<B, N>
 where
     B: Encode,
@@ -521,6 +557,22 @@ where
     }
 }
 
+impl<S> Decode for Tag<S>
+where
+    S: Specification,
+    S::Field: Decode,
+{
+    type Error = <S::Field as Decode>::Error;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        Ok(Self(Decode::decode(reader)?))
+    }
+}
+
 impl<S> Encode for Tag<S>
 where
     S: Specification,
@@ -683,20 +735,184 @@ where
     }
 }
 
-impl<const N: usize, S> Verify<Permutation<S>> for FixedEncryption<N, S>
+impl<const N: usize, S, COM> Fork<Permutation<S, COM>, COM> for FixedEncryption<N, S, COM>
 where
-    S: Specification,
-    S::Field: Clone + PartialEq + BlockElement,
+    S: Specification<COM>,
+    S::Field: BlockElement<COM>,
+{
+    type Label = S::Field;
+
+    /// Adds `label` to the capacity element of `state`, which setup, plaintext, and ciphertext
+    /// blocks never write to.
+    #[inline]
+    fn separate(&self, state: &mut State<S, COM>, label: &Self::Label, compiler: &mut COM) {
+        let capacity = state
+            .iter_mut()
+            .next()
+            .expect("The Poseidon state is never empty.");
+        *capacity = capacity.add(label, compiler);
+    }
+}
+
+impl<const N: usize, S, COM> Verify<Permutation<S, COM>, COM> for FixedEncryption<N, S, COM>
+where
+    COM: Has<bool>,
+    S: Specification<COM>,
+    S::Field: Clone + BlockElement<COM> + eclair::cmp::PartialEq<S::Field, COM>,
 {
-    type Verification = bool;
+    type Verification = Bool<COM>;
 
+    /// Compares the `encryption_tag` against the `decryption_tag`, which is the last squeeze of
+    /// the sponge after absorbing the ciphertext, so that circuits can branch on the result.
     #[inline]
     fn verify(
         &self,
         encryption_tag: &Self::Tag,
         decryption_tag: &Self::Tag,
-        _: &mut (),
+        compiler: &mut COM,
     ) -> Self::Verification {
-        encryption_tag == decryption_tag
+        encryption_tag.eq(decryption_tag, compiler)
+    }
+}
+
+/// Authenticated Decryption Result
+///
+/// The plaintext returned by [`decrypt_authenticated`](duplex::Duplexer::decrypt_authenticated)
+/// together with the result of checking the authentication tag. The plaintext is only meaningful
+/// when `is_valid` holds.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Bool<COM>: Clone, S::Field: Clone"),
+    Debug(bound = "Bool<COM>: Debug, S::Field: Debug"),
+    Eq(bound = "Bool<COM>: Eq, S::Field: Eq"),
+    Hash(bound = "Bool<COM>: Hash, S::Field: Hash"),
+    PartialEq(bound = "Bool<COM>: PartialEq, S::Field: PartialEq")
+)]
+pub struct DecryptedPlaintext<const N: usize, S, COM = ()>
+where
+    COM: Has<bool>,
+    S: Specification<COM>,
+{
+    /// Tag Validity
+    pub is_valid: Bool<COM>,
+
+    /// Decrypted Plaintext
+    pub plaintext: FixedPlaintext<N, S, COM>,
+}
+
+impl<const N: usize, S, COM> DecryptedPlaintext<N, S, COM>
+where
+    COM: Has<bool>,
+    S: Specification<COM>,
+{
+    /// Builds a new [`DecryptedPlaintext`] from `is_valid` and `plaintext`.
+    #[inline]
+    pub fn new(is_valid: Bool<COM>, plaintext: FixedPlaintext<N, S, COM>) -> Self {
+        Self {
+            is_valid,
+            plaintext,
+        }
+    }
+
+    /// Asserts that the authentication tag of the decryption was valid, returning the plaintext.
+    #[inline]
+    pub fn assert_valid(self, compiler: &mut COM) -> FixedPlaintext<N, S, COM>
+    where
+        COM: Assert,
+    {
+        compiler.assert(&self.is_valid);
+        self.plaintext
+    }
+}
+
+impl<const N: usize, S> DecryptedPlaintext<N, S>
+where
+    S: Specification,
+{
+    /// Returns the plaintext if the authentication tag of the decryption was valid.
+    #[inline]
+    pub fn into_option(self) -> Option<FixedPlaintext<N, S>> {
+        self.is_valid.then_some(self.plaintext)
+    }
+}
+
+impl<const N: usize, S, COM> From<(Bool<COM>, FixedPlaintext<N, S, COM>)>
+    for DecryptedPlaintext<N, S, COM>
+where
+    COM: Has<bool>,
+    S: Specification<COM>,
+{
+    #[inline]
+    fn from((is_valid, plaintext): (Bool<COM>, FixedPlaintext<N, S, COM>)) -> Self {
+        Self::new(is_valid, plaintext)
+    }
+}
+
+impl<const N: usize, S, COM> FixedDuplexer<N, S, COM>
+where
+    COM: Has<bool>,
+    S: Specification<COM>,
+    S::Field: Clone + BlockElement<COM> + Zero<COM> + eclair::cmp::PartialEq<S::Field, COM>,
+{
+    /// Decrypts `ciphertext` with `key` and `header`, recomputing its authentication tag and
+    /// returning the plaintext together with the validity of the tag.
+    #[allow(clippy::ptr_arg)] // NOTE: These are the key and header types of the scheme.
+    #[inline]
+    pub fn decrypt_authenticated(
+        &self,
+        key: &Vec<S::Field>,
+        header: &Vec<S::Field>,
+        ciphertext: &duplex::Ciphertext<Tag<S, COM>, FixedCiphertext<N, S, COM>>,
+        compiler: &mut COM,
+    ) -> DecryptedPlaintext<N, S, COM> {
+        crate::encryption::Decrypt::decrypt(self, key, header, ciphertext, compiler).into()
+    }
+}
+
+/// Encrypted Message Leaf Hash
+///
+/// Hashes an [`EncryptedMessage`] of the [`FixedDuplexer`] over the specification `S` into a
+/// single field element with the Poseidon [`Hasher`] of arity two over the specification `H`, so
+/// that encrypted messages can be stored as the leaves of a merkle tree. The header is hashed
+/// first, and its digest is then chained with the tag and the ciphertext blocks using
+/// [`committed_inputs::digest`].
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Ord(bound = ""),
+    PartialEq(bound = ""),
+    PartialOrd(bound = "")
+)]
+pub struct MessageHash<const N: usize, S, H, T, COM = ()>(PhantomData<(S, H, T, COM)>);
+
+impl<const N: usize, S, H, T, COM> LeafHash<COM> for MessageHash<N, S, H, T, COM>
+where
+    S: Specification<COM>,
+    S::Field: Clone + BlockElement<COM> + Zero<COM>,
+    H: Specification<COM, Field = S::Field>,
+    T: DomainTag<H>,
+{
+    type Leaf = EncryptedMessage<FixedDuplexer<N, S, COM>>;
+    type Parameters = Hasher<H, T, 2, COM>;
+    type Output = S::Field;
+
+    #[inline]
+    fn digest(parameters: &Self::Parameters, leaf: &Self::Leaf, compiler: &mut COM) -> S::Field {
+        let header = committed_inputs::digest(parameters, &leaf.header, compiler);
+        committed_inputs::digest(
+            parameters,
+            [&header, &leaf.ciphertext.tag.0].into_iter().chain(
+                leaf.ciphertext
+                    .message
+                    .iter()
+                    .flat_map(|block| block.0.iter()),
+            ),
+            compiler,
+        )
     }
 }
@@ -1,12 +1,17 @@
 //! Poseidon Hash Implementation
 
 use crate::{
-    hash::ArrayHashFunction,
-    poseidon::{FieldGeneration, NativeField, ParameterFieldType, Permutation, Specification},
+    hash::{prf::HashPrf, ArrayHashFunction},
+    poseidon::{
+        batch::{self, PermuteBatch},
+        Error, FieldGeneration, NativeField, ParameterFieldType, Permutation, Specification,
+    },
+    security::SecurityLevel,
 };
 use core::{fmt::Debug, hash::Hash, marker::PhantomData};
 use eclair::alloc::{Allocate, Const, Constant};
 use openzl_util::{
+    accelerator::Accelerator,
     codec::{Decode, DecodeError, Encode, Read, Write},
     derivative,
     rand::{Rand, RngCore, Sample},
@@ -25,6 +30,13 @@ where
     fn domain_tag() -> T::ParameterField;
 }
 
+/// Poseidon Pseudorandom Function
+///
+/// This is the [`HashPrf`] over the Poseidon [`Hasher`] of arity two, which evaluates an input
+/// under a key by hashing them together. It works natively and in-circuit with the same compiler
+/// as the underlying [`Hasher`].
+pub type Prf<S, T, COM = ()> = HashPrf<Hasher<S, T, 2, COM>>;
+
 /// Poseidon Hasher
 #[cfg_attr(
     feature = "serde",
@@ -78,10 +90,30 @@ where
     }
 
     /// Builds a new [`Hasher`] over `permutation` and `domain_tag`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `ARITY + 1 != S::WIDTH`. See [`try_new`](Self::try_new) for a
+    /// fallible version of this method.
     #[inline]
     pub fn new(permutation: Permutation<S, COM>, domain_tag: S::Field) -> Self {
-        assert_eq!(ARITY + 1, S::WIDTH);
-        Self::new_unchecked(permutation, domain_tag)
+        match Self::try_new(permutation, domain_tag) {
+            Ok(hasher) => hasher,
+            Err(err) => panic!("Unable to build Poseidon hasher: {err:?}"),
+        }
+    }
+
+    /// Builds a new [`Hasher`] over `permutation` and `domain_tag`, returning an error if
+    /// `ARITY + 1 != S::WIDTH`.
+    #[inline]
+    pub fn try_new(permutation: Permutation<S, COM>, domain_tag: S::Field) -> Result<Self, Error> {
+        if ARITY + 1 != S::WIDTH {
+            return Err(Error::InvalidArity {
+                arity: ARITY,
+                width: S::WIDTH,
+            });
+        }
+        Ok(Self::new_unchecked(permutation, domain_tag))
     }
 
     /// Builds a new [`Hasher`] over `permutation` using `T` to generate the domain tag.
@@ -102,6 +134,43 @@ where
             .permute_without_first_round(&mut state, compiler);
         state.0.into_vec()
     }
+
+    /// Computes the hash over every input in `inputs` in the given `compiler`, permuting all of
+    /// them at once in a [`BatchState`](batch::BatchState). This returns the same outputs as
+    /// calling [`hash`](ArrayHashFunction::hash) on each input.
+    #[inline]
+    pub fn hash_batch(&self, inputs: &[[S::Field; ARITY]], compiler: &mut COM) -> Vec<S::Field>
+    where
+        S::Field: Clone,
+    {
+        let mut batch = batch::tagged_batch(&self.domain_tag, inputs);
+        self.permutation.permute_batch(&mut batch, compiler);
+        batch::first_column(batch)
+    }
+}
+
+impl<S, T, const ARITY: usize> Hasher<S, T, ARITY>
+where
+    S: Specification,
+    T: DomainTag<S>,
+{
+    /// Computes the hash over every input in `inputs` on `accelerator`. See
+    /// [`hash_batch`](Self::hash_batch) for more.
+    #[inline]
+    pub fn hash_batch_with<'h, A>(
+        &'h self,
+        inputs: &[[S::Field; ARITY]],
+        accelerator: &mut A,
+    ) -> Result<Vec<S::Field>, A::Error>
+    where
+        S::Field: Clone,
+        A: Accelerator<PermuteBatch<'h, S>>,
+    {
+        let batch = batch::tagged_batch(&self.domain_tag, inputs);
+        Ok(batch::first_column(
+            self.permutation.permute_batch_with(batch, accelerator)?,
+        ))
+    }
 }
 
 impl<S, T, const ARITY: usize, COM> Constant<COM> for Hasher<S, T, ARITY, COM>
@@ -134,6 +203,14 @@ where
     }
 }
 
+impl<S, T, const ARITY: usize, COM> SecurityLevel for Hasher<S, T, ARITY, COM>
+where
+    S: Specification<COM> + SecurityLevel,
+    T: DomainTag<S>,
+{
+    const SECURITY_LEVEL: u32 = S::SECURITY_LEVEL;
+}
+
 impl<S, T, const ARITY: usize, COM> Decode for Hasher<S, T, ARITY, COM>
 where
     S: Specification<COM>,
@@ -0,0 +1,377 @@
+//! Parameter Import
+//!
+//! Projects which must agree with other Poseidon implementations, like circomlib and neptune, have
+//! to use the exact parameters those implementations publish. These parameters are usually
+//! distributed as JSON documents holding lists of round constants and rows of the MDS matrix,
+//! written as decimal or hexadecimal strings or numbers. This module parses such documents into
+//! [`Value`]s, which can be navigated to the lists a given implementation uses, and builds a
+//! [`Permutation`] from them with [`permutation`], checking their sizes against the [`Constants`] of
+//! the [`Specification`].
+//!
+//! [`Constants`]: super::Constants
+
+use crate::poseidon::{Error, FieldGeneration, Permutation, Specification};
+use alloc::vec::Vec;
+use openzl_util::num::{big::Uint, CheckedAdd};
+
+/// Import Error
+///
+/// This `enum` is the error state of the parsers in this module.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImportError {
+    /// Syntax Error
+    ///
+    /// The document is not valid JSON, or uses a feature of JSON which parameter sets do not need,
+    /// like escape sequences, negative or fractional numbers, or the `true`, `false`, and `null`
+    /// literals.
+    Syntax {
+        /// Byte Offset of the Error in the Document
+        position: usize,
+    },
+
+    /// Unexpected Value
+    ///
+    /// The value at `index` was expected to be a field element, but it is an object, or a row of the
+    /// MDS matrix was expected to be an array, but it is not.
+    UnexpectedValue {
+        /// Index of the Value in its List
+        index: usize,
+    },
+
+    /// Invalid Field Element
+    ///
+    /// The field element at `index` is not a decimal or hexadecimal integer smaller than the
+    /// modulus of the field.
+    InvalidFieldElement {
+        /// Index of the Field Element in its List
+        index: usize,
+    },
+
+    /// Invalid Permutation Parameters
+    Permutation(Error),
+}
+
+impl From<Error> for ImportError {
+    #[inline]
+    fn from(err: Error) -> Self {
+        Self::Permutation(err)
+    }
+}
+
+/// JSON Value
+///
+/// Numbers and strings borrow their contents from the parsed document and are only interpreted
+/// when they are converted to field elements, so that integers larger than any primitive type are
+/// kept exactly.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Value<'s> {
+    /// Number Literal
+    Number(&'s str),
+
+    /// String Literal
+    String(&'s str),
+
+    /// Array
+    Array(Vec<Value<'s>>),
+
+    /// Object
+    Object(Vec<(&'s str, Value<'s>)>),
+}
+
+impl<'s> Value<'s> {
+    /// Parses `source` into a [`Value`], returning an error if `source` is not a single JSON value.
+    #[inline]
+    pub fn parse(source: &'s str) -> Result<Self, ImportError> {
+        let mut parser = Parser {
+            source,
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position != source.len() {
+            return Err(parser.error());
+        }
+        Ok(value)
+    }
+
+    /// Returns the value at `key` if `self` is an object which contains `key`.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(entries) => entries
+                .iter()
+                .find_map(|(k, value)| (*k == key).then_some(value)),
+            _ => None,
+        }
+    }
+
+    /// Returns the value at `index` if `self` is an array which is longer than `index`.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<&Self> {
+        self.as_array()?.get(index)
+    }
+
+    /// Returns the elements of `self` if it is an array.
+    #[inline]
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the contents of `self` if it is a number or a string.
+    #[inline]
+    pub fn as_literal(&self) -> Option<&'s str> {
+        match self {
+            Self::Number(literal) | Self::String(literal) => Some(literal),
+            _ => None,
+        }
+    }
+
+    /// Appends the literals of `self` to `literals` in order, descending into nested arrays.
+    #[inline]
+    fn flatten_into(&self, literals: &mut Vec<&'s str>) -> Result<(), ImportError> {
+        match self {
+            Self::Array(values) => {
+                for value in values {
+                    value.flatten_into(literals)?;
+                }
+                Ok(())
+            }
+            Self::Object(_) => Err(ImportError::UnexpectedValue {
+                index: literals.len(),
+            }),
+            Self::Number(literal) | Self::String(literal) => {
+                literals.push(literal);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// JSON Parser
+struct Parser<'s> {
+    /// Source Document
+    source: &'s str,
+
+    /// Current Byte Offset
+    position: usize,
+}
+
+impl<'s> Parser<'s> {
+    /// Returns a syntax error at the current position.
+    #[inline]
+    fn error(&self) -> ImportError {
+        ImportError::Syntax {
+            position: self.position,
+        }
+    }
+
+    /// Returns the next byte without consuming it.
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    /// Skips any whitespace at the current position.
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes `byte` after any whitespace, returning an error if the next byte is different.
+    #[inline]
+    fn expect(&mut self, byte: u8) -> Result<(), ImportError> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error());
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Parses the value at the current position.
+    #[inline]
+    fn value(&mut self) -> Result<Value<'s>, ImportError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'[') => {
+                self.position += 1;
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            return Ok(Value::Array(values));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                    return Ok(Value::Object(entries));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    entries.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {
+                            self.position += 1;
+                            return Ok(Value::Object(entries));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'"') => Ok(Value::String(self.string()?)),
+            Some(b'0'..=b'9') => {
+                let start = self.position;
+                while matches!(self.peek(), Some(b'0'..=b'9')) {
+                    self.position += 1;
+                }
+                Ok(Value::Number(&self.source[start..self.position]))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Parses the string at the current position, returning its contents.
+    #[inline]
+    fn string(&mut self) -> Result<&'s str, ImportError> {
+        if self.peek() != Some(b'"') {
+            return Err(self.error());
+        }
+        self.position += 1;
+        let start = self.position;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(&self.source[start..self.position - 1]);
+                }
+                Some(b'\\') | None => return Err(self.error()),
+                _ => self.position += 1,
+            }
+        }
+    }
+}
+
+/// Field Element Literal
+///
+/// Literals are parsed into integers of 1024 bits, which hold the elements of every field that
+/// Poseidon is instantiated over in practice.
+type Literal = Uint<16>;
+
+/// Parses `literal` into a field element, returning `None` if it is not a decimal integer, or a
+/// hexadecimal integer prefixed by `0x`, which is smaller than the modulus of `F`.
+#[inline]
+pub fn parse_field_element<F>(literal: &str) -> Option<F>
+where
+    F: FieldGeneration,
+{
+    let (digits, radix) = match literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        Some(digits) => (digits, 16),
+        _ => (literal, 10),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let mut value = Literal::ZERO;
+    for digit in digits.chars() {
+        let digit = Literal::from_u64(u64::from(digit.to_digit(radix)?));
+        value = value
+            .checked_mul(&Literal::from_u64(u64::from(radix)))?
+            .checked_add(digit)?;
+    }
+    if value.bits() > F::MODULUS_BITS {
+        return None;
+    }
+    F::try_from_bits_be(
+        &(0..F::MODULUS_BITS)
+            .rev()
+            .map(|index| value.bit(index))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Parses the field elements in `value`, which can be a single literal or arrays of literals nested
+/// to any depth, in the order in which they appear in the document.
+#[inline]
+pub fn field_elements<F>(value: &Value) -> Result<Vec<F>, ImportError>
+where
+    F: FieldGeneration,
+{
+    let mut literals = Vec::new();
+    value.flatten_into(&mut literals)?;
+    literals
+        .into_iter()
+        .enumerate()
+        .map(|(index, literal)| {
+            parse_field_element(literal).ok_or(ImportError::InvalidFieldElement { index })
+        })
+        .collect()
+}
+
+/// Builds a [`Permutation`] from the `round_constants` and `mds_matrix` of a published parameter
+/// set.
+///
+/// The `round_constants` are read in round order and can be given as one flat list, or as one list
+/// per round. The `mds_matrix` must be given as a list of its rows. The number of round constants
+/// and the shape of the matrix are checked against the [`Constants`] of `S`. Use
+/// [`Permutation::check_mds_matrix`] to check that an imported matrix is also secure.
+///
+/// [`Constants`]: super::Constants
+#[inline]
+pub fn permutation<S, COM>(
+    round_constants: &Value,
+    mds_matrix: &Value,
+) -> Result<Permutation<S, COM>, ImportError>
+where
+    S: Specification<COM>,
+    S::ParameterField: FieldGeneration,
+{
+    let rows = mds_matrix
+        .as_array()
+        .ok_or(ImportError::UnexpectedValue { index: 0 })?;
+    let mut entries = 0;
+    for (index, row) in rows.iter().enumerate() {
+        let row = row
+            .as_array()
+            .ok_or(ImportError::UnexpectedValue { index })?;
+        if index >= S::WIDTH || row.len() != S::WIDTH {
+            return Err(Error::InvalidMdsMatrixLength {
+                expected: S::MDS_MATRIX_SIZE,
+                found: entries + row.len(),
+            }
+            .into());
+        }
+        entries += row.len();
+    }
+    Ok(Permutation::try_new(
+        field_elements(round_constants)?.into_boxed_slice(),
+        field_elements(mds_matrix)?.into_boxed_slice(),
+    )?)
+}
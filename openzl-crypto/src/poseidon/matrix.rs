@@ -6,6 +6,7 @@ use core::{
     ops::{Deref, Index, IndexMut},
     slice,
 };
+use eclair::linalg::linear_combination_with;
 use openzl_util::vec::{Vec, VecExt};
 
 /// Allocates a matrix of shape `(num_rows, num_columns)` where `allocate_row` generates default
@@ -173,15 +174,7 @@ where
         if self.num_rows() != v.len() {
             return None;
         }
-        Some(
-            self.rows()
-                .map(|row| {
-                    row.iter()
-                        .zip(v)
-                        .fold(F::zero(), |acc, (r, v)| F::add(&acc, &F::mul(r, v)))
-                })
-                .collect(),
-        )
+        Some(self.rows().map(|row| inner_product(row, v)).collect())
     }
 
     /// Multiplies matrix `self` with row vector `vec` on the left-hand side.
@@ -192,12 +185,7 @@ where
         }
         Some(
             (0..v.len())
-                .map(|j| {
-                    self.0
-                        .iter()
-                        .zip(v)
-                        .fold(F::zero(), |acc, (row, v)| F::add(&acc, &F::mul(v, &row[j])))
-                })
+                .map(|j| linear_combination(v, self.column(j)))
                 .collect(),
         )
     }
@@ -611,15 +599,31 @@ where
     }
 }
 
+/// Computes the sum of the pointwise products of `a` and `b`, returning zero if either is empty.
+#[inline]
+fn linear_combination<'f, F, A, B>(a: A, b: B) -> F
+where
+    F: 'f + NativeField,
+    A: IntoIterator<Item = &'f F>,
+    B: IntoIterator<Item = &'f F>,
+{
+    linear_combination_with(
+        a,
+        b,
+        &mut (),
+        |a, b, _| F::mul(a, b),
+        |a, b, _| F::add(&a, &b),
+    )
+    .unwrap_or_else(F::zero)
+}
+
 /// Computes the inner product of vector `a` and `b`.
 #[inline]
 pub fn inner_product<F>(a: &[F], b: &[F]) -> F
 where
     F: NativeField,
 {
-    a.iter()
-        .zip(b)
-        .fold(F::zero(), |acc, (v1, v2)| F::add(&acc, &F::mul(v1, v2)))
+    linear_combination(a, b)
 }
 
 /// Adds two vectors elementwise (i.e., `out[i] = a[i] + b[i]`).
@@ -1,13 +1,219 @@
 //! MDS Data Generation
+//!
+//! Poseidon mixes its state with a maximum distance separable (MDS) matrix, which is a matrix whose
+//! square submatrices are all invertible. Parameter sets differ in how they construct this matrix,
+//! so this module offers the [`MdsConstruction`]s used by common implementations, selected with
+//! [`Constants::MDS_CONSTRUCTION`](super::Constants::MDS_CONSTRUCTION), together with the checks
+//! in [`MdsMatrices::validate`] and [`check_mds`] for matrices which come from elsewhere.
 
 use crate::poseidon::{
     matrix::{Matrix, MatrixOperations, SparseMatrix, SquareMatrix},
-    FieldGeneration, NativeField,
+    Error, FieldGeneration, NativeField,
 };
 use alloc::vec;
 use core::fmt::Debug;
 use openzl_util::vec::{Vec, VecExt};
 
+/// MDS Matrix Construction
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum MdsConstruction {
+    /// Cauchy Matrix
+    ///
+    /// The matrix with entries `1 / (x_i + y_j)` for `x_i = i` and `y_j = t + j` where `t` is the
+    /// width, which is the construction of the Poseidon reference implementation. See
+    /// [`MdsMatrices::generate_mds`] for more.
+    #[default]
+    Cauchy,
+
+    /// Shifted Cauchy Matrix
+    ///
+    /// The matrix with entries `1 / (x_i + y_j)` for `x_i = x_offset + i` and
+    /// `y_j = y_offset + j`. See [`cauchy_matrix`] for more.
+    ShiftedCauchy {
+        /// Offset of the Row Sequence
+        x_offset: u64,
+
+        /// Offset of the Column Sequence
+        y_offset: u64,
+    },
+
+    /// Circulant Matrix
+    ///
+    /// The matrix whose first row is given and whose other rows are its cyclic shifts to the
+    /// right. Only some first rows give MDS matrices, so these should be checked with
+    /// [`check_mds`]. See [`circulant_matrix`] for more.
+    Circulant(&'static [u64]),
+}
+
+impl MdsConstruction {
+    /// Generates the matrix of width `t` for this construction, returning an error if `t` is zero,
+    /// if the construction has the wrong number of entries for `t`, or if some Cauchy entry would
+    /// have a zero denominator.
+    #[inline]
+    pub fn generate<F>(&self, t: usize) -> Result<SquareMatrix<F>, Error>
+    where
+        F: FieldGeneration + NativeField,
+    {
+        match self {
+            Self::Cauchy => Self::ShiftedCauchy {
+                x_offset: 0,
+                y_offset: t as u64,
+            }
+            .generate(t),
+            Self::ShiftedCauchy { x_offset, y_offset } => {
+                let xs = (0..t as u64)
+                    .map(|i| F::from_u64(x_offset + i))
+                    .collect::<Vec<_>>();
+                let ys = (0..t as u64)
+                    .map(|j| F::from_u64(y_offset + j))
+                    .collect::<Vec<_>>();
+                cauchy_matrix(&xs, &ys)
+            }
+            Self::Circulant(first_row) => {
+                if t == 0 || first_row.len() != t {
+                    return Err(Error::InvalidMdsMatrixLength {
+                        expected: t * t,
+                        found: first_row.len() * first_row.len(),
+                    });
+                }
+                Ok(SquareMatrix::new_unchecked(Matrix::new_unchecked(
+                    (0..t)
+                        .map(|i| {
+                            (0..t)
+                                .map(|j| F::from_u64(first_row[(j + t - i) % t]))
+                                .collect()
+                        })
+                        .collect(),
+                )))
+            }
+        }
+    }
+}
+
+/// Builds the Cauchy matrix with entries `1 / (xs[i] + ys[j])`, returning an error if `xs` and
+/// `ys` have different or zero lengths, or if some `xs[i] + ys[j]` is zero.
+///
+/// The matrix is MDS whenever the `xs` are distinct and the `ys` are distinct.
+#[inline]
+pub fn cauchy_matrix<F>(xs: &[F], ys: &[F]) -> Result<SquareMatrix<F>, Error>
+where
+    F: NativeField,
+{
+    if xs.is_empty() || xs.len() != ys.len() {
+        return Err(Error::InvalidMdsMatrixLength {
+            expected: xs.len() * xs.len(),
+            found: xs.len() * ys.len(),
+        });
+    }
+    let mut rows = Vec::with_capacity(xs.len());
+    for (row, x) in xs.iter().enumerate() {
+        let mut entries = Vec::with_capacity(ys.len());
+        for (column, y) in ys.iter().enumerate() {
+            entries.push(
+                F::add(x, y)
+                    .inverse()
+                    .ok_or(Error::InvalidMdsConstruction { row, column })?,
+            );
+        }
+        rows.push(entries);
+    }
+    Ok(SquareMatrix::new_unchecked(Matrix::new_unchecked(rows)))
+}
+
+/// Builds the circulant matrix whose `i`-th row is `first_row` shifted cyclically to the right by
+/// `i` positions, returning an error if `first_row` is empty.
+#[inline]
+pub fn circulant_matrix<F>(first_row: &[F]) -> Result<SquareMatrix<F>, Error>
+where
+    F: Clone + NativeField,
+{
+    let t = first_row.len();
+    if t == 0 {
+        return Err(Error::InvalidMdsMatrixLength {
+            expected: 1,
+            found: 0,
+        });
+    }
+    Ok(SquareMatrix::new_unchecked(Matrix::new_unchecked(
+        (0..t)
+            .map(|i| (0..t).map(|j| first_row[(j + t - i) % t].clone()).collect())
+            .collect(),
+    )))
+}
+
+/// Computes the determinant of `rows` by Gaussian elimination.
+#[inline]
+fn determinant<F>(mut rows: Vec<Vec<F>>) -> F
+where
+    F: Clone + NativeField,
+{
+    let size = rows.len();
+    let mut result = F::one();
+    for column in 0..size {
+        let pivot = match (column..size).find(|i| !rows[*i][column].is_zero()) {
+            Some(pivot) => pivot,
+            _ => return F::zero(),
+        };
+        if pivot != column {
+            rows.swap(pivot, column);
+            result = F::sub(&F::zero(), &result);
+        }
+        let pivot = rows[column][column].clone();
+        let inverse = pivot.inverse().expect("The pivot is never zero.");
+        result = F::mul(&result, &pivot);
+        for i in (column + 1)..size {
+            let factor = F::mul(&rows[i][column], &inverse);
+            for j in column..size {
+                let term = F::mul(&factor, &rows[column][j]);
+                rows[i][j] = F::sub(&rows[i][j], &term);
+            }
+        }
+    }
+    result
+}
+
+/// Checks that `m` is MDS, which means that all of its square submatrices are invertible,
+/// returning [`Error::SingularMdsSubmatrix`] with the first singular submatrix otherwise.
+///
+/// This check computes the determinant of every square submatrix, whose number is exponential in
+/// the width of `m`, so it is only meant for the small widths used by Poseidon.
+///
+/// # Panics
+///
+/// This function panics if `m` has more than 32 rows.
+#[inline]
+pub fn check_mds<F>(m: &SquareMatrix<F>) -> Result<(), Error>
+where
+    F: Clone + NativeField,
+{
+    let size = m.num_rows();
+    assert!(
+        size <= 32,
+        "The width of the MDS matrix is too large to check."
+    );
+    let mut subsets = vec![Vec::new(); size + 1];
+    for subset in 1..(1u64 << size) {
+        subsets[subset.count_ones() as usize].push(subset);
+    }
+    let entries = |subset: u64| (0..size).filter(move |i| subset & (1 << *i) != 0);
+    for subsets in &subsets[1..] {
+        for rows in subsets {
+            for columns in subsets {
+                let submatrix = entries(*rows)
+                    .map(|i| entries(*columns).map(|j| m[i][j].clone()).collect())
+                    .collect();
+                if determinant(submatrix).is_zero() {
+                    return Err(Error::SingularMdsSubmatrix {
+                        rows: *rows,
+                        columns: *columns,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// MDS Matrix for both naive Poseidon Hash and optimized Poseidon Hash
 /// For detailed descriptions, please refer to <https://hackmd.io/8MdoHwoKTPmQfZyIKEYWXQ>
 /// Note: Naive and optimized Poseidon Hash does not change #constraints in Groth16.
@@ -81,24 +287,15 @@ where
     /// Generates the mds matrix `m` for naive Poseidon Hash
     /// mds matrix is constructed to be symmetry so that row-major or col-major
     /// representation gives the same output.
+    ///
+    /// This is the [`MdsConstruction::Cauchy`] construction.
     pub fn generate_mds(t: usize) -> SquareMatrix<F>
     where
         F: FieldGeneration,
     {
-        let ys: Vec<F> = (t as u64..2 * t as u64).map(F::from_u64).collect();
-        SquareMatrix::new_unchecked(Matrix::new_unchecked(
-            (0..t as u64)
-                .map(|x| {
-                    ys.iter()
-                        .map(|y| {
-                            F::add(&F::from_u64(x), y)
-                                .inverse()
-                                .expect("`x+y` is invertible.")
-                        })
-                        .collect()
-                })
-                .collect(),
-        ))
+        MdsConstruction::Cauchy
+            .generate(t)
+            .expect("`x+y` is invertible.")
     }
 
     fn make_double_prime(m: &SquareMatrix<F>, m_hat_inv: &SquareMatrix<F>) -> SquareMatrix<F>
@@ -130,7 +327,57 @@ where
         ))
     }
 
+    /// Checks that `m` is a non-empty square matrix which is invertible and whose minor at `(0, 0)`
+    /// is invertible, which are the properties that [`derive_mds_matrices`] relies on.
+    ///
+    /// These checks are cheap but do not imply that `m` is MDS. See [`check_mds`] for the full
+    /// check.
+    ///
+    /// [`derive_mds_matrices`]: Self::derive_mds_matrices
+    #[inline]
+    pub fn validate(m: &SquareMatrix<F>) -> Result<(), Error>
+    where
+        F: Clone + PartialEq,
+    {
+        let size = m.num_rows();
+        if size == 0 || m.rows().any(|row| row.len() != size) {
+            return Err(Error::InvalidMdsMatrixLength {
+                expected: size * size,
+                found: m.rows().map(|row| row.len()).sum(),
+            });
+        }
+        if !m.is_invertible() {
+            return Err(Error::SingularMdsMatrix);
+        }
+        if size > 1
+            && !m
+                .minor(0, 0)
+                .expect("Matrices with more than one row have minors.")
+                .is_invertible()
+        {
+            return Err(Error::SingularMdsMinor);
+        }
+        Ok(())
+    }
+
+    /// Derives the mds matrices for optimized Poseidon Hash from `m`, returning an error if `m`
+    /// does not pass [`validate`](Self::validate).
+    #[inline]
+    pub fn try_derive_mds_matrices(m: SquareMatrix<F>) -> Result<Self, Error>
+    where
+        F: Clone + PartialEq,
+    {
+        Self::validate(&m)?;
+        Ok(Self::derive_mds_matrices(m))
+    }
+
     /// Derives the mds matrices for optimized Poseidon Hash. Start from mds matrix `m` in naive Poseidon Hash.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `m` does not pass [`validate`](Self::validate). See
+    /// [`try_derive_mds_matrices`](Self::try_derive_mds_matrices) for a fallible version of this
+    /// method.
     pub fn derive_mds_matrices(m: SquareMatrix<F>) -> Self
     where
         F: Clone + PartialEq,
@@ -4,12 +4,18 @@ use crate::{
     component,
     permutation::PseudorandomPermutation,
     poseidon::{
-        matrix::MatrixOperations, mds::MdsMatrices, round_constants::generate_round_constants,
+        matrix::{Matrix, MatrixOperations, SquareMatrix},
+        mds::{MdsConstruction, MdsMatrices},
+        round_constants::generate_round_constants,
     },
+    security::SecurityLevel,
 };
 use alloc::{boxed::Box, vec::Vec};
 use core::{fmt::Debug, hash::Hash, iter, marker::PhantomData, mem, slice};
-use eclair::alloc::{Allocate, Const, Constant};
+use eclair::{
+    alloc::{Allocate, Const, Constant},
+    linalg::linear_combination_with,
+};
 use openzl_util::{
     codec::{Decode, DecodeError, Encode, Read, Write},
     derivative,
@@ -19,15 +25,126 @@ use openzl_util::{
 #[cfg(feature = "serde")]
 use openzl_util::serde::{Deserialize, Serialize};
 
+pub mod batch;
 pub mod constants;
 pub mod encryption;
 pub mod hash;
+pub mod import;
 pub mod lfsr;
 pub mod matrix;
 pub mod mds;
 pub mod preprocessing;
 pub mod round_constants;
 
+/// Poseidon Error
+///
+/// This `enum` is the error state of the fallible constructors in this module, like
+/// [`Permutation::try_new`] and [`State::try_new`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Invalid State Length
+    InvalidStateLength {
+        /// Expected Length
+        expected: usize,
+
+        /// Given Length
+        found: usize,
+    },
+
+    /// Invalid Additive Round Keys Length
+    InvalidAdditiveRoundKeysLength {
+        /// Expected Length
+        expected: usize,
+
+        /// Given Length
+        found: usize,
+    },
+
+    /// Invalid MDS Matrix Length
+    InvalidMdsMatrixLength {
+        /// Expected Length
+        expected: usize,
+
+        /// Given Length
+        found: usize,
+    },
+
+    /// Invalid Hasher Arity
+    ///
+    /// The arity of a Poseidon hasher must be exactly one less than the width of the permutation.
+    InvalidArity {
+        /// Hasher Arity
+        arity: usize,
+
+        /// Permutation Width
+        width: usize,
+    },
+
+    /// Mismatched Round Constant
+    ///
+    /// The round constant at `index` of the given `round` differs from the one of the permutation.
+    MismatchedRoundConstant {
+        /// Round
+        round: usize,
+
+        /// Index in the Round
+        index: usize,
+    },
+
+    /// Mismatched MDS Matrix Entry
+    MismatchedMdsEntry {
+        /// Row
+        row: usize,
+
+        /// Column
+        column: usize,
+    },
+
+    /// Insecure Constants
+    ///
+    /// The constants do not satisfy the security conditions set out in the Poseidon paper. See
+    /// [`constants::security`] for more.
+    InsecureConstants,
+
+    /// Invalid MDS Construction
+    ///
+    /// The MDS matrix construction has no entry at `row` and `column` because its denominator is
+    /// zero. See [`mds::cauchy_matrix`] for more.
+    InvalidMdsConstruction {
+        /// Row
+        row: usize,
+
+        /// Column
+        column: usize,
+    },
+
+    /// Singular MDS Matrix
+    SingularMdsMatrix,
+
+    /// Singular MDS Matrix Minor
+    ///
+    /// The minor of the MDS matrix at `(0, 0)` is singular, so the matrix cannot be factored for
+    /// the optimized partial rounds. See [`MdsMatrices::validate`] for more.
+    SingularMdsMinor,
+
+    /// Singular MDS Submatrix
+    ///
+    /// The square submatrix of the MDS matrix with the given `rows` and `columns` is singular, so
+    /// the matrix is not MDS. See [`mds::check_mds`] for more.
+    SingularMdsSubmatrix {
+        /// Bitmask of the Submatrix Rows
+        rows: u64,
+
+        /// Bitmask of the Submatrix Columns
+        columns: u64,
+    },
+}
+
 /// Field Element
 ///
 /// This trait is intended to represent arithmetic operations performed on native
@@ -108,6 +225,13 @@ pub trait Constants {
     /// Number of Entries in the MDS Matrix
     const MDS_MATRIX_SIZE: usize = Self::WIDTH * Self::WIDTH;
 
+    /// MDS Matrix Construction
+    ///
+    /// The construction used to sample the MDS matrix of the permutation, which defaults to the
+    /// Cauchy matrix of the Poseidon reference implementation. Parameter sets ported from other
+    /// implementations should set this to the construction they were generated with.
+    const MDS_CONSTRUCTION: MdsConstruction = MdsConstruction::Cauchy;
+
     /// Total Number of Additive Rounds Keys
     const ADDITIVE_ROUND_KEYS_COUNT: usize = Self::ROUNDS * Self::WIDTH;
 }
@@ -168,21 +292,16 @@ pub trait Specification<COM = ()>: Field<COM> + Constants + Sized {
         compiler: &mut COM,
     ) {
         let mut next = Vec::with_capacity(Self::WIDTH);
-        for i in 0..Self::WIDTH {
-            // NOTE: clippy false-positive: Without `collect`, the two closures in `map` and
-            //       `reduce` will have simultaneous `&mut` access to `compiler`. Adding `collect`
-            //       allows `map` to be done before `reduce`.
-            #[allow(clippy::needless_collect)]
-            let linear_combination = state
-                .iter()
-                .enumerate()
-                .map(|(j, elem)| Self::mul_const(elem, &mds_matrix[Self::WIDTH * i + j], compiler))
-                .collect::<Vec<_>>();
+        for row in mds_matrix.chunks(Self::WIDTH) {
             next.push(
-                linear_combination
-                    .into_iter()
-                    .reduce(|acc, next| Self::add(&acc, &next, compiler))
-                    .unwrap(),
+                linear_combination_with(
+                    state.iter(),
+                    row,
+                    compiler,
+                    Self::mul_const,
+                    |lhs, rhs, compiler| Self::add(&lhs, &rhs, compiler),
+                )
+                .expect("The state is never empty."),
             );
         }
         mem::swap(&mut next.into_boxed_slice(), &mut state.0);
@@ -327,10 +446,30 @@ where
     S: Specification<COM>,
 {
     /// Builds a new [`State`] from `state`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `state` does not have length [`S::WIDTH`](Constants::WIDTH). See
+    /// [`try_new`](Self::try_new) for a fallible version of this method.
     #[inline]
     pub fn new(state: Box<[S::Field]>) -> Self {
-        assert_eq!(state.len(), S::WIDTH);
-        Self(state)
+        match Self::try_new(state) {
+            Ok(state) => state,
+            Err(err) => panic!("Unable to build Poseidon state: {err:?}"),
+        }
+    }
+
+    /// Builds a new [`State`] from `state`, returning an error if `state` does not have length
+    /// [`S::WIDTH`](Constants::WIDTH).
+    #[inline]
+    pub fn try_new(state: Box<[S::Field]>) -> Result<Self, Error> {
+        if state.len() != S::WIDTH {
+            return Err(Error::InvalidStateLength {
+                expected: S::WIDTH,
+                found: state.len(),
+            });
+        }
+        Ok(Self(state))
     }
 
     /// Returns a slice iterator over the state.
@@ -453,23 +592,38 @@ where
     /// # Panics
     ///
     /// This method panics if the input vectors are not the correct size for the specified
-    /// [`Specification`].
+    /// [`Specification`]. See [`try_new`](Self::try_new) for a fallible version of this method.
     #[inline]
     pub fn new(
         additive_round_keys: Box<[S::ParameterField]>,
         mds_matrix: Box<[S::ParameterField]>,
     ) -> Self {
-        assert_eq!(
-            additive_round_keys.len(),
-            S::ADDITIVE_ROUND_KEYS_COUNT,
-            "Additive Rounds Keys are not the correct size."
-        );
-        assert_eq!(
-            mds_matrix.len(),
-            S::MDS_MATRIX_SIZE,
-            "MDS Matrix is not the correct size."
-        );
-        Self::new_unchecked(additive_round_keys, mds_matrix)
+        match Self::try_new(additive_round_keys, mds_matrix) {
+            Ok(permutation) => permutation,
+            Err(err) => panic!("Unable to build Poseidon permutation: {err:?}"),
+        }
+    }
+
+    /// Builds a new [`Permutation`] from `additive_round_keys` and `mds_matrix`, returning an
+    /// error if the input vectors are not the correct size for the specified [`Specification`].
+    #[inline]
+    pub fn try_new(
+        additive_round_keys: Box<[S::ParameterField]>,
+        mds_matrix: Box<[S::ParameterField]>,
+    ) -> Result<Self, Error> {
+        if additive_round_keys.len() != S::ADDITIVE_ROUND_KEYS_COUNT {
+            return Err(Error::InvalidAdditiveRoundKeysLength {
+                expected: S::ADDITIVE_ROUND_KEYS_COUNT,
+                found: additive_round_keys.len(),
+            });
+        }
+        if mds_matrix.len() != S::MDS_MATRIX_SIZE {
+            return Err(Error::InvalidMdsMatrixLength {
+                expected: S::MDS_MATRIX_SIZE,
+                found: mds_matrix.len(),
+            });
+        }
+        Ok(Self::new_unchecked(additive_round_keys, mds_matrix))
     }
 
     /// Builds a new [`Permutation`] from `additive_round_keys` and `mds_matrix` without
@@ -492,6 +646,102 @@ where
         additive_keys::<S, S, _>(&self.additive_round_keys, round)
     }
 
+    /// Returns the additive round keys of all rounds, in round order.
+    #[inline]
+    pub fn additive_round_keys(&self) -> &[S::ParameterField] {
+        &self.additive_round_keys
+    }
+
+    /// Returns the MDS matrix flattened in row-major order.
+    #[inline]
+    pub fn mds_matrix(&self) -> &[S::ParameterField] {
+        &self.mds_matrix
+    }
+
+    /// Checks that `round_constants`, given as one row of [`S::WIDTH`](Constants::WIDTH)-many
+    /// constants per round, and `mds_matrix`, given as its rows, are equal to the parameters of
+    /// `self`.
+    ///
+    /// This is the layout other Poseidon implementations use for their parameters, so that they can
+    /// be instantiated from the same [`Permutation`] and checked to produce the same hashes.
+    #[inline]
+    pub fn check_parameters<R, M>(&self, round_constants: R, mds_matrix: M) -> Result<(), Error>
+    where
+        R: IntoIterator,
+        R::Item: AsRef<[S::ParameterField]>,
+        M: IntoIterator,
+        M::Item: AsRef<[S::ParameterField]>,
+        S::ParameterField: PartialEq,
+    {
+        let mut rounds = 0;
+        for (round, keys) in round_constants.into_iter().enumerate() {
+            let keys = keys.as_ref();
+            if round >= S::ROUNDS || keys.len() != S::WIDTH {
+                return Err(Error::InvalidAdditiveRoundKeysLength {
+                    expected: S::ADDITIVE_ROUND_KEYS_COUNT,
+                    found: round * S::WIDTH + keys.len(),
+                });
+            }
+            if let Some(index) = keys
+                .iter()
+                .zip(self.additive_keys(round))
+                .position(|(lhs, rhs)| lhs != rhs)
+            {
+                return Err(Error::MismatchedRoundConstant { round, index });
+            }
+            rounds += 1;
+        }
+        if rounds != S::ROUNDS {
+            return Err(Error::InvalidAdditiveRoundKeysLength {
+                expected: S::ADDITIVE_ROUND_KEYS_COUNT,
+                found: rounds * S::WIDTH,
+            });
+        }
+        let mut rows = 0;
+        for (row, entries) in mds_matrix.into_iter().enumerate() {
+            let entries = entries.as_ref();
+            if row >= S::WIDTH || entries.len() != S::WIDTH {
+                return Err(Error::InvalidMdsMatrixLength {
+                    expected: S::MDS_MATRIX_SIZE,
+                    found: row * S::WIDTH + entries.len(),
+                });
+            }
+            if let Some(column) = entries
+                .iter()
+                .zip(&self.mds_matrix[row * S::WIDTH..(row + 1) * S::WIDTH])
+                .position(|(lhs, rhs)| lhs != rhs)
+            {
+                return Err(Error::MismatchedMdsEntry { row, column });
+            }
+            rows += 1;
+        }
+        if rows != S::WIDTH {
+            return Err(Error::InvalidMdsMatrixLength {
+                expected: S::MDS_MATRIX_SIZE,
+                found: rows * S::WIDTH,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the MDS matrix of `self` is invertible, that it can be factored for the
+    /// optimized partial rounds, and that it is MDS. See [`MdsMatrices::validate`] and
+    /// [`mds::check_mds`] for more.
+    #[inline]
+    pub fn check_mds_matrix(&self) -> Result<(), Error>
+    where
+        S::ParameterField: Clone + NativeField + PartialEq,
+    {
+        let m = SquareMatrix::new_unchecked(Matrix::new_unchecked(
+            self.mds_matrix
+                .chunks(S::WIDTH)
+                .map(|row| row.to_vec())
+                .collect(),
+        ));
+        MdsMatrices::validate(&m)?;
+        mds::check_mds(&m)
+    }
+
     /// Computes a full round at the given `round` index on the internal permutation `state`.
     #[inline]
     pub fn full_round(&self, round: usize, state: &mut State<S, COM>, compiler: &mut COM) {
@@ -532,6 +782,13 @@ where
     }
 }
 
+impl<S, COM> SecurityLevel for Permutation<S, COM>
+where
+    S: Specification<COM> + SecurityLevel,
+{
+    const SECURITY_LEVEL: u32 = S::SECURITY_LEVEL;
+}
+
 impl<S, COM> Constant<COM> for Permutation<S, COM>
 where
     S: Specification<COM> + Constant<COM>,
@@ -611,23 +868,38 @@ where
     }
 }
 
-impl<S, COM> Sample for Permutation<S, COM>
+impl<S, COM> Permutation<S, COM>
 where
     S: Specification<COM>,
     S::ParameterField: NativeField + FieldGeneration,
 {
+    /// Generates the round constants of `S` from the Grain LFSR and its MDS matrix from its
+    /// [`MDS_CONSTRUCTION`](Constants::MDS_CONSTRUCTION). The result only depends on `S`.
     #[inline]
-    fn sample<R>(distribution: (), rng: &mut R) -> Self
-    where
-        R: RngCore + ?Sized,
-    {
-        let _ = (distribution, rng);
+    pub fn generate() -> Self {
         Self::new_unchecked(
             generate_round_constants(S::WIDTH, S::FULL_ROUNDS, S::PARTIAL_ROUNDS)
                 .into_boxed_slice(),
-            MdsMatrices::generate_mds(S::WIDTH)
+            S::MDS_CONSTRUCTION
+                .generate::<S::ParameterField>(S::WIDTH)
+                .expect("The MDS construction is valid for the width of the specification.")
                 .to_row_major()
                 .into_boxed_slice(),
         )
     }
 }
+
+impl<S, COM> Sample for Permutation<S, COM>
+where
+    S: Specification<COM>,
+    S::ParameterField: NativeField + FieldGeneration,
+{
+    #[inline]
+    fn sample<R>(distribution: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let _ = (distribution, rng);
+        Self::generate()
+    }
+}
@@ -1,6 +1,6 @@
 //! Round Constants Generation
 
-use crate::poseidon::{lfsr::GrainLFSR, FieldGeneration};
+use crate::{expansion::grain::GrainLFSR, poseidon::FieldGeneration};
 use alloc::vec::Vec;
 use core::iter;
 
@@ -0,0 +1,605 @@
+//! Merkle Airdrops
+//!
+//! An airdrop distributes a claim to every entry of an allowlist of addresses or keys. The
+//! [`Allowlist`] commits to all entries with a merkle tree and hands out a [`ClaimProof`] for every
+//! entry. The [`Airdrop`] only keeps the root of the allowlist and a [`ClaimedBitmap`] with one
+//! bit per entry, so that every entry can claim exactly once. Claims are checked either natively
+//! against a [`ClaimProof`] or with a proof of the [`Claim`](constraint::Claim) statement, which
+//! reveals the root, the entry, and its index in the allowlist but keeps the merkle path secret.
+
+use crate::merkle_tree::{
+    full::FullMerkleTree,
+    inner_tree::{BTreeMap, InnerMap},
+    path_length, Configuration, InnerDigest, Leaf, LeafDigest, Parameters, Path, Root,
+};
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::derivative;
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Allowlist
+///
+/// The entries of the allowlist are the leaves of a full merkle tree, in the order in which they
+/// were given to [`new`](Self::new).
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Parameters<C>: Clone, LeafDigest<C>: Clone, InnerDigest<C>: Clone, M: Clone"),
+    Debug(bound = "Parameters<C>: Debug, LeafDigest<C>: Debug, InnerDigest<C>: Debug, M: Debug")
+)]
+pub struct Allowlist<C, M = BTreeMap<C>>
+where
+    C: Configuration + ?Sized,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    /// Merkle Tree
+    tree: FullMerkleTree<C, M>,
+}
+
+impl<C, M> Allowlist<C, M>
+where
+    C: Configuration + ?Sized,
+    M: Default + InnerMap<C>,
+    LeafDigest<C>: Clone + Default + PartialEq,
+    InnerDigest<C>: Clone + Default + PartialEq,
+{
+    /// Builds a new [`Allowlist`] for `entries` using `parameters`, returning `None` if there are
+    /// more entries than fit into the merkle tree.
+    #[inline]
+    pub fn new(parameters: Parameters<C>, entries: &[Leaf<C>]) -> Option<Self> {
+        let mut tree = FullMerkleTree::new(parameters);
+        for entry in entries {
+            if !tree.push_provable(entry) {
+                return None;
+            }
+        }
+        Some(Self { tree })
+    }
+
+    /// Returns the merkle tree parameters of `self`.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        self.tree.parameters()
+    }
+
+    /// Returns the root of `self`.
+    #[inline]
+    pub fn root(&self) -> &Root<C> {
+        self.tree.root()
+    }
+
+    /// Returns the number of entries of `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns `true` if `self` has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the index of the first occurrence of `entry` in `self`.
+    #[inline]
+    pub fn position(&self, entry: &Leaf<C>) -> Option<usize> {
+        self.tree.position(&self.tree.parameters().digest(entry))
+    }
+
+    /// Returns the [`ClaimProof`] for the `entry` stored at `index`, returning `None` if `index`
+    /// is out of bounds.
+    #[inline]
+    pub fn prove(&self, index: usize, entry: Leaf<C>) -> Option<ClaimProof<C>> {
+        Some(ClaimProof::new(entry, self.tree.path(index).ok()?))
+    }
+
+    /// Builds the [`Airdrop`] which tracks the claims of the entries of `self`.
+    #[inline]
+    pub fn airdrop(&self) -> Airdrop<C>
+    where
+        Parameters<C>: Clone,
+    {
+        Airdrop::new(self.parameters().clone(), self.root().clone(), self.len())
+    }
+}
+
+/// Claim Proof
+///
+/// A claim proof opens the allowlist at one entry. Its index in the allowlist is the leaf index of
+/// its merkle `path`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                Leaf<C>: Deserialize<'de>,
+                LeafDigest<C>: Deserialize<'de>,
+                InnerDigest<C>: Deserialize<'de>
+            ",
+            serialize = "Leaf<C>: Serialize, LeafDigest<C>: Serialize, InnerDigest<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Leaf<C>: Clone, LeafDigest<C>: Clone, InnerDigest<C>: Clone"),
+    Debug(bound = "Leaf<C>: Debug, LeafDigest<C>: Debug, InnerDigest<C>: Debug"),
+    Eq(bound = "Leaf<C>: Eq, LeafDigest<C>: Eq, InnerDigest<C>: Eq"),
+    Hash(bound = "Leaf<C>: Hash, LeafDigest<C>: Hash, InnerDigest<C>: Hash"),
+    PartialEq(bound = "Leaf<C>: PartialEq, LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq")
+)]
+pub struct ClaimProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Allowlist Entry
+    pub entry: Leaf<C>,
+
+    /// Merkle Path of the Entry
+    pub path: Path<C>,
+}
+
+impl<C> ClaimProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`ClaimProof`] from `entry` and `path`.
+    #[inline]
+    pub fn new(entry: Leaf<C>, path: Path<C>) -> Self {
+        Self { entry, path }
+    }
+
+    /// Returns the index of the entry in the allowlist.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.path.leaf_index().0
+    }
+
+    /// Returns `true` if `self` opens the allowlist with `root` under `parameters`.
+    #[inline]
+    pub fn verify(&self, parameters: &Parameters<C>, root: &Root<C>) -> bool
+    where
+        InnerDigest<C>: PartialEq,
+    {
+        self.path.verify(parameters, root, &self.entry)
+    }
+
+    /// Returns the public input of the [`Claim`](constraint::Claim) statement for `self` against
+    /// the allowlist with `root`.
+    #[inline]
+    pub fn input(&self, root: Root<C>) -> ClaimInput<C>
+    where
+        Leaf<C>: Clone,
+    {
+        ClaimInput::new(root, self.entry.clone(), self.index())
+    }
+}
+
+/// Claim Input
+///
+/// This is the public input of the [`Claim`](constraint::Claim) statement. It is wired into the
+/// proof system input as the root, the entry, and then the index as
+/// `path_length::<C, _>() + 1` bits, least significant bit first.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Root<C>: Deserialize<'de>, Leaf<C>: Deserialize<'de>",
+            serialize = "Root<C>: Serialize, Leaf<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Root<C>: Clone, Leaf<C>: Clone"),
+    Copy(bound = "Root<C>: Copy, Leaf<C>: Copy"),
+    Debug(bound = "Root<C>: Debug, Leaf<C>: Debug"),
+    Default(bound = "Root<C>: Default, Leaf<C>: Default"),
+    Eq(bound = "Root<C>: Eq, Leaf<C>: Eq"),
+    Hash(bound = "Root<C>: Hash, Leaf<C>: Hash"),
+    PartialEq(bound = "Root<C>: PartialEq, Leaf<C>: PartialEq")
+)]
+pub struct ClaimInput<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Allowlist Root
+    pub root: Root<C>,
+
+    /// Allowlist Entry
+    pub entry: Leaf<C>,
+
+    /// Entry Index
+    pub index: usize,
+}
+
+impl<C> ClaimInput<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`ClaimInput`] from `root`, `entry`, and `index`.
+    #[inline]
+    pub fn new(root: Root<C>, entry: Leaf<C>, index: usize) -> Self {
+        Self { root, entry, index }
+    }
+
+    /// Returns the bits of the index, least significant bit first.
+    #[inline]
+    pub fn index_bits(&self) -> impl '_ + Iterator<Item = bool> {
+        (0..=path_length::<C, _>()).map(move |i| (self.index >> i) & 1 == 1)
+    }
+}
+
+impl<C, P> crate::constraint::Input<P> for ClaimInput<C>
+where
+    C: Configuration + ?Sized,
+    P: crate::constraint::HasInput<Root<C>>
+        + crate::constraint::HasInput<Leaf<C>>
+        + crate::constraint::HasInput<bool>
+        + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut P::Input) {
+        P::extend(input, &self.root);
+        P::extend(input, &self.entry);
+        for bit in self.index_bits() {
+            P::extend(input, &bit);
+        }
+    }
+}
+
+/// Claim Error
+///
+/// This `enum` is the error state of the claiming methods of [`Airdrop`] and [`ClaimedBitmap`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ClaimError {
+    /// Invalid Claim
+    ///
+    /// The claim does not open the allowlist of the airdrop.
+    Invalid,
+
+    /// Index Out of Bounds
+    IndexTooLarge {
+        /// Number of Entries
+        length: usize,
+    },
+
+    /// Double Claim
+    ///
+    /// The entry at the given index was already claimed.
+    AlreadyClaimed(usize),
+}
+
+/// Claimed Bitmap
+///
+/// A compact set of claimed indices which stores one bit per allowlist entry.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ClaimedBitmap {
+    /// Bitmap Words
+    words: Vec<u64>,
+
+    /// Number of Entries
+    len: usize,
+}
+
+impl ClaimedBitmap {
+    /// Builds a new [`ClaimedBitmap`] for `len` entries, none of which are claimed.
+    #[inline]
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Returns the number of entries tracked by `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if `self` tracks no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of claimed entries.
+    #[inline]
+    pub fn claimed(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if the entry at `index` was claimed.
+    #[inline]
+    pub fn is_claimed(&self, index: usize) -> bool {
+        index < self.len && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Marks the entry at `index` as claimed, returning an error if `index` is out of bounds or
+    /// if the entry was already claimed.
+    #[inline]
+    pub fn claim(&mut self, index: usize) -> Result<(), ClaimError> {
+        if index >= self.len {
+            return Err(ClaimError::IndexTooLarge { length: self.len });
+        }
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        if *word & mask != 0 {
+            return Err(ClaimError::AlreadyClaimed(index));
+        }
+        *word |= mask;
+        Ok(())
+    }
+}
+
+/// Airdrop
+///
+/// The airdrop keeps the root of the allowlist and the [`ClaimedBitmap`] of its entries. See
+/// [`Allowlist::airdrop`] to build one.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Parameters<C>: Deserialize<'de>, Root<C>: Deserialize<'de>",
+            serialize = "Parameters<C>: Serialize, Root<C>: Serialize"
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Parameters<C>: Clone, Root<C>: Clone"),
+    Debug(bound = "Parameters<C>: Debug, Root<C>: Debug"),
+    Eq(bound = "Parameters<C>: Eq, Root<C>: Eq"),
+    Hash(bound = "Parameters<C>: Hash, Root<C>: Hash"),
+    PartialEq(bound = "Parameters<C>: PartialEq, Root<C>: PartialEq")
+)]
+pub struct Airdrop<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Merkle Tree Parameters
+    parameters: Parameters<C>,
+
+    /// Allowlist Root
+    root: Root<C>,
+
+    /// Claimed Entries
+    claimed: ClaimedBitmap,
+}
+
+impl<C> Airdrop<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`Airdrop`] for the allowlist with `root` and `len` entries under
+    /// `parameters`.
+    #[inline]
+    pub fn new(parameters: Parameters<C>, root: Root<C>, len: usize) -> Self {
+        Self {
+            parameters,
+            root,
+            claimed: ClaimedBitmap::new(len),
+        }
+    }
+
+    /// Returns the merkle tree parameters of `self`.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        &self.parameters
+    }
+
+    /// Returns the root of the allowlist of `self`.
+    #[inline]
+    pub fn root(&self) -> &Root<C> {
+        &self.root
+    }
+
+    /// Returns the [`ClaimedBitmap`] of `self`.
+    #[inline]
+    pub fn claimed(&self) -> &ClaimedBitmap {
+        &self.claimed
+    }
+
+    /// Verifies `proof` natively and marks its entry as claimed, returning its index.
+    #[inline]
+    pub fn claim(&mut self, proof: &ClaimProof<C>) -> Result<usize, ClaimError>
+    where
+        InnerDigest<C>: PartialEq,
+    {
+        if !proof.verify(&self.parameters, &self.root) {
+            return Err(ClaimError::Invalid);
+        }
+        let index = proof.index();
+        self.claimed.claim(index)?;
+        Ok(index)
+    }
+
+    /// Marks the entry of `input` as claimed after its proof of the
+    /// [`Claim`](constraint::Claim) statement was verified, returning its index. The proof must
+    /// have been verified against `input`, see [`statements::verify`](crate::statements::verify).
+    #[inline]
+    pub fn claim_verified(&mut self, input: &ClaimInput<C>) -> Result<usize, ClaimError>
+    where
+        Root<C>: PartialEq,
+    {
+        if input.root != self.root {
+            return Err(ClaimError::Invalid);
+        }
+        self.claimed.claim(input.index)?;
+        Ok(input.index)
+    }
+}
+
+/// Constraint System Gadgets
+pub mod constraint {
+    use super::*;
+    use crate::{merkle_tree::path::constraint::PathVar, statements::Statement};
+    use eclair::{
+        alloc::{mode::Public, Allocate, Allocator, Constant, Variable},
+        bool::{AssertEq, Bool, ConditionalSwap},
+        cmp::PartialEq,
+        Has,
+    };
+
+    /// Claim Input Variable
+    pub struct ClaimInputVar<C, COM>
+    where
+        C: Configuration<COM> + ?Sized,
+        COM: Has<bool>,
+    {
+        /// Allowlist Root
+        pub root: Root<C, COM>,
+
+        /// Allowlist Entry
+        pub entry: Leaf<C, COM>,
+
+        /// Entry Index Bits
+        ///
+        /// The bits are stored least significant bit first.
+        pub index: Vec<Bool<COM>>,
+    }
+
+    impl<C, COM> Variable<Public, COM> for ClaimInputVar<C, COM>
+    where
+        COM: Has<bool>,
+        Bool<COM>: Variable<Public, COM, Type = bool>,
+        C: Configuration<COM> + Constant<COM> + ?Sized,
+        C::Type: Configuration,
+        Root<C, COM>: Variable<Public, COM, Type = Root<C::Type>>,
+        Leaf<C, COM>: Variable<Public, COM, Type = Leaf<C::Type>>,
+    {
+        type Type = ClaimInput<C::Type>;
+
+        #[inline]
+        fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+            Self {
+                root: this.root.as_known::<Public, _>(compiler),
+                entry: this.entry.as_known::<Public, _>(compiler),
+                index: this
+                    .index_bits()
+                    .map(|bit| bit.as_known::<Public, _>(compiler))
+                    .collect(),
+            }
+        }
+
+        #[inline]
+        fn new_unknown(compiler: &mut COM) -> Self {
+            Self {
+                root: compiler.allocate_unknown::<Public, _>(),
+                entry: compiler.allocate_unknown::<Public, _>(),
+                index: (0..=path_length::<C, _>())
+                    .map(|_| compiler.allocate_unknown::<Public, _>())
+                    .collect(),
+            }
+        }
+    }
+
+    /// Claim Statement
+    ///
+    /// Enforces that the secret merkle path opens the allowlist with the public root at the public
+    /// entry, and that the leaf index of the path is the public index. The verifier then checks
+    /// the index against its [`ClaimedBitmap`] with [`Airdrop::claim_verified`].
+    pub struct Claim<C, COM>(
+        /// Merkle Tree Parameters
+        pub Parameters<C, COM>,
+    )
+    where
+        C: Configuration<COM> + ?Sized;
+
+    impl<C, COM> Constant<COM> for Claim<C, COM>
+    where
+        C: Configuration<COM> + Constant<COM> + ?Sized,
+        C::Type: Configuration,
+        Parameters<C, COM>: Constant<COM, Type = Parameters<C::Type>>,
+    {
+        type Type = Claim<C::Type, ()>;
+
+        #[inline]
+        fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+            Self(this.0.as_constant(compiler))
+        }
+    }
+
+    impl<C, COM> Statement<COM> for Claim<C, COM>
+    where
+        C: Configuration<COM> + ?Sized,
+        COM: AssertEq + Has<bool>,
+        Bool<COM>: PartialEq<Bool<COM>, COM>,
+        InnerDigest<C, COM>: ConditionalSwap<COM> + PartialEq<InnerDigest<C, COM>, COM>,
+        LeafDigest<C, COM>: ConditionalSwap<COM>,
+    {
+        type Public = ClaimInputVar<C, COM>;
+        type Secret = PathVar<C, COM>;
+
+        #[inline]
+        fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+            let (leaf_bit, inner_bits) = public
+                .index
+                .split_first()
+                .expect("The index of a claim is not allowed to be empty.");
+            compiler.assert_eq(leaf_bit, &secret.inner_path.leaf_index);
+            for (bit, inner_index) in inner_bits.iter().zip(&secret.inner_path.inner_indices) {
+                compiler.assert_eq(bit, inner_index);
+            }
+            let leaf_digest = self.0.digest_with(&public.entry, compiler);
+            let root = secret.root(&self.0, &leaf_digest, compiler);
+            compiler.assert_eq(&public.root, &root);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::merkle_tree::test::Test;
+
+    /// Test Merkle Tree Configuration
+    type Config = Test<u64, 5>;
+
+    /// Tests that every allowlist entry claims exactly once and that forged claims are rejected.
+    #[test]
+    fn allowlist_entries_claim_exactly_once() {
+        let entries = [3, 14, 15, 92, 65];
+        let allowlist =
+            Allowlist::<Config>::new(Default::default(), &entries).expect("Entries should fit.");
+        let mut airdrop = allowlist.airdrop();
+        for (index, entry) in entries.into_iter().enumerate() {
+            let proof = allowlist.prove(index, entry).expect("Index is in bounds.");
+            assert_eq!(airdrop.claim(&proof), Ok(index));
+            assert_eq!(
+                airdrop.claim(&proof),
+                Err(ClaimError::AlreadyClaimed(index))
+            );
+        }
+        assert_eq!(airdrop.claimed().claimed(), entries.len());
+        let forged = allowlist.prove(0, 4).expect("Index is in bounds.");
+        assert_eq!(airdrop.claim(&forged), Err(ClaimError::Invalid));
+        assert!(allowlist.prove(entries.len(), 0).is_none());
+    }
+}
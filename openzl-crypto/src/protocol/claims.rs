@@ -0,0 +1,804 @@
+//! Signed JSON Claims
+//!
+//! Identity providers and other issuers sign JSON documents which hold claims about a subject,
+//! like the payloads of JSON Web Tokens. The [`SignedClaims`] statement proves that a secret
+//! document carries a valid signature of the issuer and contains some public claims, without
+//! revealing the rest of the document, its signature, or its length. Every claim is a serialized
+//! member `"key":value` of the document together with the byte right after it, which terminates
+//! values like numbers that could otherwise be extended. Claims are matched against the document
+//! with [`Bytes::substring_eq`] at offsets which are part of the secret witness.
+//!
+//! The offsets are computed natively by [`locate`], which parses the document and finds a member
+//! at its top level, and [`prepare`] builds the public input and secret witness of a statement
+//! from a document, its signature, and the keys of the claims which should be revealed.
+//!
+//! # Signatures
+//!
+//! The statement is generic over the [`Verify`] implementation of the signature scheme and over
+//! the [`HashFunction`] which digests the document into the signed message, so schemes like RSA or
+//! ECDSA over SHA-256 can be plugged in as soon as they are implemented for the compiler in use.
+//! The document has to be the exact signed byte string. Compact JSON Web Signatures sign the
+//! base64url encoding of the payload instead, so they are only covered if the hash function
+//! decodes the document itself or if the payload is signed unencoded.
+//!
+//! # Security
+//!
+//! The circuit only checks that every claim occurs somewhere in the document, so it does not rule
+//! out that the claim belongs to a nested object, or that the document holds a second member with
+//! the same key. [`locate`] rejects both cases for honest provers, but verifiers should only
+//! accept claims whose keys cannot occur in nested objects of the documents of the issuer, and
+//! issuers should never sign documents with duplicate keys. Claims are compared byte by byte, so
+//! issuers and verifiers have to agree on a compact serialization without whitespace.
+
+use crate::{
+    constraint::{HasInput, Input},
+    hash::HashFunction,
+    signature::Verify,
+    statements::Statement,
+};
+use core::{array, fmt::Debug, hash::Hash, ops::Range};
+use eclair::{
+    alloc::{
+        mode::{Derived, Public, Secret},
+        Allocate, Allocator, Constant, Variable,
+    },
+    bool::{Assert, Bool},
+    bytes::{Byte, Bytes, Length},
+    cmp::PartialEq,
+    num::{One, Zero},
+    ops::{Add, BitAnd, BitOr, Not},
+    Has,
+};
+use openzl_util::derivative;
+
+/// Maximum Nesting Depth
+///
+/// [`locate`] rejects documents whose arrays and objects are nested deeper than this.
+pub const MAX_DEPTH: usize = 64;
+
+/// Parse Error
+///
+/// This `enum` is the error state of [`locate`] and [`prepare`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ParseError {
+    /// Unexpected End
+    ///
+    /// The document ends inside of a value.
+    UnexpectedEnd,
+
+    /// Unexpected Byte
+    ///
+    /// The byte at `position` cannot occur at its place in a JSON document, or it follows the end
+    /// of the top-level object.
+    UnexpectedByte {
+        /// Position of the Byte in the Document
+        position: usize,
+    },
+
+    /// Nesting Too Deep
+    ///
+    /// The document is nested deeper than [`MAX_DEPTH`].
+    TooDeep,
+
+    /// Missing Key
+    ///
+    /// The top-level object of the document has no member with the given key.
+    MissingKey,
+
+    /// Duplicate Key
+    ///
+    /// The top-level object of the document has more than one member with the given key.
+    DuplicateKey,
+
+    /// Capacity Exceeded
+    ///
+    /// The document or one of its claims is longer than its capacity in the statement.
+    CapacityExceeded,
+}
+
+/// Byte Span
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Span {
+    /// Offset of the First Byte
+    pub offset: usize,
+
+    /// Number of Bytes
+    pub length: usize,
+}
+
+impl Span {
+    /// Builds a new [`Span`] from `offset` and `length`.
+    #[inline]
+    pub fn new(offset: usize, length: usize) -> Self {
+        Self { offset, length }
+    }
+
+    /// Returns the range of positions covered by `self`.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+}
+
+/// Document Member
+///
+/// The spans of a member of the top-level object of a document, as computed by [`locate`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Member {
+    /// Claim Span
+    ///
+    /// Covers the serialized member `"key":value` and the byte right after it.
+    pub claim: Span,
+
+    /// Value Span
+    pub value: Span,
+}
+
+impl Member {
+    /// Returns the claim of `self` in `document`, returning `None` if it is longer than `LEN`.
+    #[inline]
+    pub fn claim<const LEN: usize>(&self, document: &[u8]) -> Option<Bytes<(), LEN>> {
+        Bytes::from_slice(document.get(self.claim.range())?)
+    }
+}
+
+/// JSON Parser
+///
+/// Checks the structure of a document while keeping track of the current position in it.
+struct Parser<'d> {
+    /// Document
+    document: &'d [u8],
+
+    /// Current Position
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the byte at the current position without consuming it.
+    #[inline]
+    fn peek(&self) -> Result<u8, ParseError> {
+        self.document
+            .get(self.position)
+            .copied()
+            .ok_or(ParseError::UnexpectedEnd)
+    }
+
+    /// Consumes the byte at the current position.
+    #[inline]
+    fn next(&mut self) -> Result<u8, ParseError> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    /// Returns the error for the byte right before the current position.
+    #[inline]
+    fn unexpected(&self) -> ParseError {
+        ParseError::UnexpectedByte {
+            position: self.position - 1,
+        }
+    }
+
+    /// Consumes the byte at the current position, checking that it is equal to `expected`.
+    #[inline]
+    fn expect(&mut self, expected: u8) -> Result<(), ParseError> {
+        if self.next()? != expected {
+            return Err(self.unexpected());
+        }
+        Ok(())
+    }
+
+    /// Consumes the whitespace at the current position.
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.document.get(self.position) {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes a string, returning the span of its serialized contents between the quotes.
+    #[inline]
+    fn string(&mut self) -> Result<Span, ParseError> {
+        self.expect(b'"')?;
+        let offset = self.position;
+        loop {
+            match self.next()? {
+                b'"' => return Ok(Span::new(offset, self.position - 1 - offset)),
+                b'\\' => match self.next()? {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => {}
+                    b'u' => {
+                        for _ in 0..4 {
+                            if !self.next()?.is_ascii_hexdigit() {
+                                return Err(self.unexpected());
+                            }
+                        }
+                    }
+                    _ => return Err(self.unexpected()),
+                },
+                0..=0x1f => return Err(self.unexpected()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Consumes the bytes of `literal`.
+    #[inline]
+    fn literal(&mut self, literal: &[u8]) -> Result<(), ParseError> {
+        for byte in literal {
+            self.expect(*byte)?;
+        }
+        Ok(())
+    }
+
+    /// Consumes a number.
+    #[inline]
+    fn number(&mut self) -> Result<(), ParseError> {
+        let offset = self.position;
+        while let Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') =
+            self.document.get(self.position)
+        {
+            self.position += 1;
+        }
+        if self.position == offset {
+            self.next()?;
+            return Err(self.unexpected());
+        }
+        Ok(())
+    }
+
+    /// Consumes the separator after an element of an array or a member of an object, returning
+    /// `true` if it closes the container with the `close` byte.
+    #[inline]
+    fn separator(&mut self, close: u8) -> Result<bool, ParseError> {
+        self.skip_whitespace();
+        match self.next()? {
+            b',' => Ok(false),
+            byte if byte == close => Ok(true),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Consumes a value which is nested `depth` levels deep.
+    #[inline]
+    fn value(&mut self, depth: usize) -> Result<(), ParseError> {
+        match self.peek()? {
+            b'{' => self.object(depth + 1, |_, _, _| Ok(())),
+            b'[' => {
+                if depth + 1 > MAX_DEPTH {
+                    return Err(ParseError::TooDeep);
+                }
+                self.position += 1;
+                self.skip_whitespace();
+                if self.peek()? == b']' {
+                    self.position += 1;
+                    return Ok(());
+                }
+                loop {
+                    self.skip_whitespace();
+                    self.value(depth + 1)?;
+                    if self.separator(b']')? {
+                        return Ok(());
+                    }
+                }
+            }
+            b'"' => self.string().map(|_| ()),
+            b't' => self.literal(b"true"),
+            b'f' => self.literal(b"false"),
+            b'n' => self.literal(b"null"),
+            _ => self.number(),
+        }
+    }
+
+    /// Consumes an object which is nested `depth` levels deep, calling `visit` with the key span,
+    /// the start of the member, and the value span of every member.
+    #[inline]
+    fn object<F>(&mut self, depth: usize, mut visit: F) -> Result<(), ParseError>
+    where
+        F: FnMut(Span, usize, Span) -> Result<(), ParseError>,
+    {
+        if depth > MAX_DEPTH {
+            return Err(ParseError::TooDeep);
+        }
+        self.expect(b'{')?;
+        self.skip_whitespace();
+        if self.peek()? == b'}' {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            let offset = self.position;
+            self.value(depth)?;
+            visit(key, start, Span::new(offset, self.position - offset))?;
+            if self.separator(b'}')? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Locates the member with the serialized `key` in the top-level object of `document`.
+///
+/// The key is compared with the bytes between the quotes of every key of the top-level object, so
+/// keys with escape sequences have to be given in their escaped form. This function also checks
+/// that the whole document is well-formed JSON, up to the syntax of numbers, and fails if the key
+/// is missing or occurs more than once.
+#[inline]
+pub fn locate(document: &[u8], key: &[u8]) -> Result<Member, ParseError> {
+    let mut parser = Parser {
+        document,
+        position: 0,
+    };
+    let mut member = None;
+    parser.skip_whitespace();
+    parser.object(1, |key_span, start, value| {
+        if document[key_span.range()] != *key {
+            return Ok(());
+        }
+        if member.is_some() {
+            return Err(ParseError::DuplicateKey);
+        }
+        let end = value.offset + value.length;
+        member = Some(Member {
+            claim: Span::new(start, end + 1 - start),
+            value,
+        });
+        Ok(())
+    })?;
+    parser.skip_whitespace();
+    if parser.position < document.len() {
+        return Err(ParseError::UnexpectedByte {
+            position: parser.position,
+        });
+    }
+    member.ok_or(ParseError::MissingKey)
+}
+
+/// Builds the public input and the secret witness of a [`SignedClaims`] statement which reveals
+/// the members with the given `keys` of `document` under the `verifying_key` of the issuer.
+#[inline]
+pub fn prepare<K, S, const DOC_LEN: usize, const CLAIM_LEN: usize, const CLAIMS: usize>(
+    document: &[u8],
+    keys: [&[u8]; CLAIMS],
+    verifying_key: K,
+    signature: S,
+) -> Result<
+    (
+        Claims<K, (), CLAIM_LEN, CLAIMS>,
+        Witness<S, (), DOC_LEN, CLAIMS>,
+    ),
+    ParseError,
+> {
+    let padded = Bytes::from_slice(document).ok_or(ParseError::CapacityExceeded)?;
+    let mut members = [Member::default(); CLAIMS];
+    for (member, key) in members.iter_mut().zip(keys) {
+        *member = locate(document, key)?;
+    }
+    let claims = members.map(|member| member.claim(document));
+    if claims.iter().any(Option::is_none) {
+        return Err(ParseError::CapacityExceeded);
+    }
+    Ok((
+        Claims::new(
+            verifying_key,
+            claims.map(|claim| claim.expect("Claims were checked to fit into their capacity.")),
+        ),
+        Witness::new(padded, members.map(|member| member.claim.offset), signature),
+    ))
+}
+
+/// Public Claims
+///
+/// The verifying key of the issuer and the claims which are revealed from the signed document.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "K: Clone, Bytes<COM, CLAIM_LEN>: Clone"),
+    Debug(bound = "K: Debug, Bytes<COM, CLAIM_LEN>: Debug"),
+    Eq(bound = "K: Eq, Bytes<COM, CLAIM_LEN>: Eq"),
+    Hash(bound = "K: Hash, Bytes<COM, CLAIM_LEN>: Hash"),
+    PartialEq(bound = "K: core::cmp::PartialEq, Bytes<COM, CLAIM_LEN>: core::cmp::PartialEq")
+)]
+pub struct Claims<K, COM, const CLAIM_LEN: usize, const CLAIMS: usize>
+where
+    COM: Has<u8> + Has<usize>,
+{
+    /// Verifying Key of the Issuer
+    pub verifying_key: K,
+
+    /// Revealed Claims
+    pub claims: [Bytes<COM, CLAIM_LEN>; CLAIMS],
+}
+
+impl<K, COM, const CLAIM_LEN: usize, const CLAIMS: usize> Claims<K, COM, CLAIM_LEN, CLAIMS>
+where
+    COM: Has<u8> + Has<usize>,
+{
+    /// Builds a new [`Claims`] from `verifying_key` and `claims`.
+    #[inline]
+    pub fn new(verifying_key: K, claims: [Bytes<COM, CLAIM_LEN>; CLAIMS]) -> Self {
+        Self {
+            verifying_key,
+            claims,
+        }
+    }
+}
+
+impl<K, COM, const CLAIM_LEN: usize, const CLAIMS: usize> Variable<Public, COM>
+    for Claims<K, COM, CLAIM_LEN, CLAIMS>
+where
+    COM: Has<u8> + Has<usize>,
+    K: Variable<Public, COM>,
+    Bytes<COM, CLAIM_LEN>: Variable<Derived<(Public, Public)>, COM, Type = Bytes<(), CLAIM_LEN>>,
+{
+    type Type = Claims<K::Type, (), CLAIM_LEN, CLAIMS>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown(),
+            array::from_fn(|_| compiler.allocate_unknown::<Derived<(Public, Public)>, _>()),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.verifying_key.as_known(compiler),
+            array::from_fn(|index| {
+                this.claims[index].as_known::<Derived<(Public, Public)>, _>(compiler)
+            }),
+        )
+    }
+}
+
+impl<K, P, const CLAIM_LEN: usize, const CLAIMS: usize> Input<P>
+    for Claims<K, (), CLAIM_LEN, CLAIMS>
+where
+    P: HasInput<K> + HasInput<u8> + HasInput<usize> + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut P::Input) {
+        P::extend(input, &self.verifying_key);
+        for claim in &self.claims {
+            for byte in claim.bytes() {
+                P::extend(input, byte);
+            }
+            P::extend(input, claim.length());
+        }
+    }
+}
+
+/// Secret Witness
+///
+/// The signed document, the offsets of the claims in the document, and the signature.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "S: Clone, Bytes<COM, DOC_LEN>: Clone, Length<COM>: Clone"),
+    Debug(bound = "S: Debug, Bytes<COM, DOC_LEN>: Debug, Length<COM>: Debug")
+)]
+pub struct Witness<S, COM, const DOC_LEN: usize, const CLAIMS: usize>
+where
+    COM: Has<u8> + Has<usize>,
+{
+    /// Signed Document
+    pub document: Bytes<COM, DOC_LEN>,
+
+    /// Offsets of the Claims in the Document
+    pub offsets: [Length<COM>; CLAIMS],
+
+    /// Signature of the Document
+    pub signature: S,
+}
+
+impl<S, COM, const DOC_LEN: usize, const CLAIMS: usize> Witness<S, COM, DOC_LEN, CLAIMS>
+where
+    COM: Has<u8> + Has<usize>,
+{
+    /// Builds a new [`Witness`] from `document`, `offsets`, and `signature`.
+    #[inline]
+    pub fn new(
+        document: Bytes<COM, DOC_LEN>,
+        offsets: [Length<COM>; CLAIMS],
+        signature: S,
+    ) -> Self {
+        Self {
+            document,
+            offsets,
+            signature,
+        }
+    }
+}
+
+impl<S, COM, const DOC_LEN: usize, const CLAIMS: usize> Variable<Secret, COM>
+    for Witness<S, COM, DOC_LEN, CLAIMS>
+where
+    COM: Has<u8> + Has<usize>,
+    S: Variable<Secret, COM>,
+    Bytes<COM, DOC_LEN>: Variable<Derived<(Secret, Secret)>, COM, Type = Bytes<(), DOC_LEN>>,
+    Length<COM>: Variable<Secret, COM>,
+    <Length<COM> as Variable<Secret, COM>>::Type: From<usize>,
+{
+    type Type = Witness<S::Type, (), DOC_LEN, CLAIMS>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown::<Derived<(Secret, Secret)>, _>(),
+            array::from_fn(|_| compiler.allocate_unknown::<Secret, _>()),
+            compiler.allocate_unknown(),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.document
+                .as_known::<Derived<(Secret, Secret)>, _>(compiler),
+            this.offsets
+                .map(|offset| compiler.allocate_known::<Secret, Length<COM>>(&offset.into())),
+            this.signature.as_known(compiler),
+        )
+    }
+}
+
+/// Signed Claims Statement
+///
+/// Enforces that the secret document, digested by the `hasher`, is signed under the public
+/// verifying key of the issuer with the signature `scheme`, and that every public claim occurs in
+/// the document at its secret offset. Documents hold at most `DOC_LEN` bytes and every one of the
+/// `CLAIMS`-many claims holds at most `CLAIM_LEN` bytes. See the [module-level documentation](self)
+/// for more.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SignedClaims<S, H, const DOC_LEN: usize, const CLAIM_LEN: usize, const CLAIMS: usize> {
+    /// Signature Scheme
+    pub scheme: S,
+
+    /// Document Hash Function
+    pub hasher: H,
+}
+
+impl<S, H, const DOC_LEN: usize, const CLAIM_LEN: usize, const CLAIMS: usize>
+    SignedClaims<S, H, DOC_LEN, CLAIM_LEN, CLAIMS>
+{
+    /// Builds a new [`SignedClaims`] statement from the signature `scheme` and the `hasher`.
+    #[inline]
+    pub fn new(scheme: S, hasher: H) -> Self {
+        Self { scheme, hasher }
+    }
+}
+
+impl<S, H, COM, const DOC_LEN: usize, const CLAIM_LEN: usize, const CLAIMS: usize> Constant<COM>
+    for SignedClaims<S, H, DOC_LEN, CLAIM_LEN, CLAIMS>
+where
+    S: Constant<COM>,
+    H: Constant<COM>,
+{
+    type Type = SignedClaims<S::Type, H::Type, DOC_LEN, CLAIM_LEN, CLAIMS>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.scheme.as_constant(compiler),
+            this.hasher.as_constant(compiler),
+        )
+    }
+}
+
+impl<S, H, COM, const DOC_LEN: usize, const CLAIM_LEN: usize, const CLAIMS: usize> Statement<COM>
+    for SignedClaims<S, H, DOC_LEN, CLAIM_LEN, CLAIMS>
+where
+    S: Verify<COM, Verification = Bool<COM>>,
+    H: HashFunction<COM, Input = Bytes<COM, DOC_LEN>, Output = S::Message>,
+    COM: Assert + Has<bool> + Has<u8> + Has<usize>,
+    Bool<COM>: Clone
+        + Constant<COM, Type = bool>
+        + BitAnd<Bool<COM>, COM, Output = Bool<COM>>
+        + BitOr<Bool<COM>, COM, Output = Bool<COM>>
+        + Not<COM, Output = Bool<COM>>,
+    Byte<COM>: PartialEq<Byte<COM>, COM> + Zero<COM, Verification = Bool<COM>>,
+    Length<COM>: Add<Length<COM>, COM, Output = Length<COM>>
+        + Clone
+        + One<COM>
+        + PartialEq<Length<COM>, COM>
+        + Zero<COM>,
+{
+    type Public = Claims<S::VerifyingKey, COM, CLAIM_LEN, CLAIMS>;
+    type Secret = Witness<S::Signature, COM, DOC_LEN, CLAIMS>;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        let message = self.hasher.hash(&secret.document, compiler);
+        let is_valid =
+            self.scheme
+                .verify(&public.verifying_key, &message, &secret.signature, compiler);
+        compiler.assert(&is_valid);
+        for (claim, offset) in public.claims.iter().zip(&secret.offsets) {
+            let is_contained = secret.document.substring_eq(offset, claim, compiler);
+            compiler.assert(&is_contained);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signature::{MessageType, SignatureType, VerifyingKeyType};
+
+    /// Signed Document
+    const DOCUMENT: &[u8] =
+        br#"{"iss":"issuer","sub":"alice","age":21,"address":{"country":"CH"},"admin":false}"#;
+
+    /// Verifying Key of the Issuer
+    const VERIFYING_KEY: u64 = 0x5eed;
+
+    /// Document Capacity
+    const DOC_LEN: usize = 128;
+
+    /// Checksum Signature Scheme
+    ///
+    /// Accepts a signature of a digest if it is the sum of the digest and the verifying key. This
+    /// scheme is insecure and only stands in for a real signature scheme in tests.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct Checksum;
+
+    impl VerifyingKeyType for Checksum {
+        type VerifyingKey = u64;
+    }
+
+    impl MessageType for Checksum {
+        type Message = u64;
+    }
+
+    impl SignatureType for Checksum {
+        type Signature = u64;
+    }
+
+    impl Verify for Checksum {
+        type Verification = bool;
+
+        #[inline]
+        fn verify(
+            &self,
+            verifying_key: &Self::VerifyingKey,
+            message: &Self::Message,
+            signature: &Self::Signature,
+            compiler: &mut (),
+        ) -> Self::Verification {
+            let _ = compiler;
+            *signature == message.wrapping_add(*verifying_key)
+        }
+    }
+
+    /// Weighted Sum Hash Function
+    ///
+    /// Digests a document into the sum of its bytes weighted by their positions.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct WeightedSum;
+
+    impl HashFunction for WeightedSum {
+        type Input = Bytes<(), DOC_LEN>;
+        type Output = u64;
+
+        #[inline]
+        fn hash(&self, input: &Self::Input, compiler: &mut ()) -> Self::Output {
+            let _ = compiler;
+            input
+                .bytes()
+                .iter()
+                .zip(1..)
+                .fold(0, |digest, (byte, weight)| {
+                    digest.wrapping_add(weight * u64::from(*byte))
+                })
+        }
+    }
+
+    /// Signed Claims Statement over the Mock Signature Scheme
+    type TestClaims = SignedClaims<Checksum, WeightedSum, DOC_LEN, 16, 2>;
+
+    /// Prepares a statement which reveals the subject and the age claims of [`DOCUMENT`] under
+    /// a valid signature.
+    #[inline]
+    fn signed_claims() -> (Claims<u64, (), 16, 2>, Witness<u64, (), DOC_LEN, 2>) {
+        let digest = WeightedSum.hash(
+            &Bytes::from_slice(DOCUMENT).expect("The document fits."),
+            &mut (),
+        );
+        prepare(
+            DOCUMENT,
+            [b"sub", b"age"],
+            VERIFYING_KEY,
+            digest.wrapping_add(VERIFYING_KEY),
+        )
+        .expect("The document is well-formed and holds every key.")
+    }
+
+    /// Checks that [`SignedClaims`] accepts a signed document with claims at their offsets.
+    #[test]
+    fn signed_claims_accept_valid_documents() {
+        let (claims, witness) = signed_claims();
+        TestClaims::default().enforce(&claims, &witness, &mut ());
+    }
+
+    /// Checks that [`SignedClaims`] rejects claims at the wrong offset.
+    #[test]
+    #[should_panic(expected = "assertion failed")]
+    fn signed_claims_reject_wrong_offsets() {
+        let (claims, mut witness) = signed_claims();
+        witness.offsets[0] += 1;
+        TestClaims::default().enforce(&claims, &witness, &mut ());
+    }
+
+    /// Checks that [`SignedClaims`] rejects claims which differ from the document in one byte.
+    #[test]
+    #[should_panic(expected = "assertion failed")]
+    fn signed_claims_reject_altered_claims() {
+        let (mut claims, witness) = signed_claims();
+        claims.claims[1] = Bytes::from_slice(br#""age":31,"#).expect("The claim fits.");
+        TestClaims::default().enforce(&claims, &witness, &mut ());
+    }
+
+    /// Checks that [`SignedClaims`] rejects documents with an invalid signature.
+    #[test]
+    #[should_panic(expected = "assertion failed")]
+    fn signed_claims_reject_invalid_signatures() {
+        let (claims, mut witness) = signed_claims();
+        witness.signature += 1;
+        TestClaims::default().enforce(&claims, &witness, &mut ());
+    }
+
+    /// Checks that [`prepare`] computes claims which occur in the document at their offsets.
+    #[test]
+    fn prepared_claims_occur_at_their_offsets() {
+        let (claims, witness) =
+            prepare::<_, _, 128, 16, 3>(DOCUMENT, [b"sub", b"age", b"admin"], (), ())
+                .expect("The document is well-formed and holds every key.");
+        assert_eq!(claims.claims[0].as_slice(), br#""sub":"alice","#);
+        assert_eq!(claims.claims[1].as_slice(), br#""age":21,"#);
+        assert_eq!(claims.claims[2].as_slice(), br#""admin":false}"#);
+        for (claim, offset) in claims.claims.iter().zip(&witness.offsets) {
+            assert!(
+                witness.document.substring_eq(offset, claim, &mut ()),
+                "Claims should occur in the document at their offsets."
+            );
+        }
+        let forged = Bytes::<(), 16>::from_slice(br#""age":2,"#).expect("The claim fits.");
+        assert!(
+            !witness
+                .document
+                .substring_eq(&witness.offsets[1], &forged, &mut ()),
+            "Truncated values must not match their claims."
+        );
+    }
+
+    /// Checks that [`locate`] only finds members of the top-level object and rejects malformed
+    /// documents.
+    #[test]
+    fn locate_rejects_nested_duplicate_and_malformed_members() {
+        assert_eq!(
+            locate(DOCUMENT, b"address").map(|member| &DOCUMENT[member.value.range()]),
+            Ok(&br#"{"country":"CH"}"#[..])
+        );
+        assert_eq!(locate(DOCUMENT, b"country"), Err(ParseError::MissingKey));
+        assert_eq!(
+            locate(br#"{"sub":"alice","sub":"bob"}"#, b"sub"),
+            Err(ParseError::DuplicateKey)
+        );
+        assert_eq!(
+            locate(br#"{"sub":"alice""#, b"sub"),
+            Err(ParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            locate(br#"{"sub":"alice"} x"#, b"sub"),
+            Err(ParseError::UnexpectedByte { position: 16 })
+        );
+        assert_eq!(
+            prepare::<_, _, 16, 16, 1>(DOCUMENT, [b"sub"], (), ()).map(|_| ()),
+            Err(ParseError::CapacityExceeded)
+        );
+    }
+}
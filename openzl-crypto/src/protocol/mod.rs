@@ -0,0 +1,12 @@
+//! Protocol Building Blocks
+//!
+//! Typed payloads that compose several primitives of this crate into the canonical pieces of
+//! privacy-preserving protocols.
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod airdrop;
+
+pub mod claims;
+pub mod note;
+pub mod semaphore;
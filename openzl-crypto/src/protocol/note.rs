@@ -0,0 +1,389 @@
+//! Shielded Notes
+//!
+//! Shielded-transaction protocols keep value in notes. Creating a note commits to it, inserts the
+//! commitment into an accumulator, and encrypts the note to its owner. Spending a note proves
+//! membership of its commitment and reveals a nullifier derived from the secret key of the owner,
+//! which prevents the note from being spent twice. The [`Parameters`] bundle the hash functions
+//! for all three derivations, the [`Payload`] is the encrypt-then-commit output posted to the
+//! ledger, and [`Parameters::assert_spend`] enforces the spending statement inside of a circuit.
+
+use crate::{
+    accumulator::{Accumulator, AssertValidVerification, MembershipProof},
+    constraint::{HasInput, Input},
+    encryption::{CiphertextType, Decrypt, Encrypt, EncryptedMessage, HeaderType, Message},
+    hash::{prf::PseudorandomFunction, ArrayHashFunction},
+};
+use core::{fmt::Debug, hash::Hash};
+use eclair::{
+    alloc::{mode::Public, Allocate, Allocator, Constant, Variable},
+    bool::{Assert, AssertEq},
+};
+use openzl_util::{
+    codec::{Encode, Write},
+    derivative,
+    rand::{Rand, RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Note
+///
+/// A note assigns `value` to the owner with the address `owner`. The `trapdoor` is sampled
+/// uniformly at random for every note so that its commitment hides the other fields.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Note<F> {
+    /// Owner Address
+    pub owner: F,
+
+    /// Value
+    pub value: F,
+
+    /// Commitment Trapdoor
+    pub trapdoor: F,
+}
+
+impl<F> Note<F> {
+    /// Builds a new [`Note`] from `owner`, `value`, and `trapdoor`.
+    #[inline]
+    pub fn new(owner: F, value: F, trapdoor: F) -> Self {
+        Self {
+            owner,
+            value,
+            trapdoor,
+        }
+    }
+}
+
+impl<F, D> Sample<D> for Note<F>
+where
+    F: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(
+            rng.sample(distribution.clone()),
+            rng.sample(distribution.clone()),
+            rng.sample(distribution),
+        )
+    }
+}
+
+impl<F, M, COM> Variable<M, COM> for Note<F>
+where
+    F: Variable<M, COM>,
+{
+    type Type = Note<F::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown(),
+            compiler.allocate_unknown(),
+            compiler.allocate_unknown(),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.owner.as_known(compiler),
+            this.value.as_known(compiler),
+            this.trapdoor.as_known(compiler),
+        )
+    }
+}
+
+/// Note Parameters
+///
+/// The owner address of a note is `K(secret_key)`, its commitment is
+/// `H(owner, value, trapdoor)`, and its nullifier is `N(secret_key, commitment)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Parameters<K, H, N> {
+    /// Owner Key Derivation Function
+    pub owner_key: K,
+
+    /// Commitment Scheme
+    pub commitment: H,
+
+    /// Nullifier Function
+    pub nullifier: N,
+}
+
+impl<K, H, N> Parameters<K, H, N> {
+    /// Builds a new [`Parameters`] from `owner_key`, `commitment`, and `nullifier`.
+    #[inline]
+    pub fn new(owner_key: K, commitment: H, nullifier: N) -> Self {
+        Self {
+            owner_key,
+            commitment,
+            nullifier,
+        }
+    }
+
+    /// Derives the owner address of `secret_key`.
+    #[inline]
+    pub fn owner<F, COM>(&self, secret_key: &F, compiler: &mut COM) -> F
+    where
+        K: ArrayHashFunction<1, COM, Input = F, Output = F>,
+    {
+        self.owner_key.hash([secret_key], compiler)
+    }
+
+    /// Computes the commitment to `note`.
+    #[inline]
+    pub fn commit<F, COM>(&self, note: &Note<F>, compiler: &mut COM) -> F
+    where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+    {
+        self.commitment
+            .hash([&note.owner, &note.value, &note.trapdoor], compiler)
+    }
+
+    /// Computes the nullifier of the note with `commitment` owned by `secret_key`.
+    #[inline]
+    pub fn nullifier<F, COM>(&self, secret_key: &F, commitment: &F, compiler: &mut COM) -> F
+    where
+        N: PseudorandomFunction<COM, Key = F, Input = F, Output = F>,
+    {
+        self.nullifier.evaluate(secret_key, commitment, compiler)
+    }
+
+    /// Commits to `note` and encrypts it with `cipher` under `key`, `randomness`, and `header`,
+    /// returning the [`Payload`] to post to the ledger.
+    #[inline]
+    pub fn payload<F, E, COM>(
+        &self,
+        note: Note<F>,
+        cipher: &E,
+        key: &E::EncryptionKey,
+        randomness: &E::Randomness,
+        header: E::Header,
+        compiler: &mut COM,
+    ) -> Payload<F, E>
+    where
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+        E: Encrypt<COM, Plaintext = Note<F>>,
+    {
+        Payload::new(
+            self.commit(&note, compiler),
+            Message::new(header, note).encrypt(cipher, key, randomness, compiler),
+        )
+    }
+
+    /// Decrypts `payload` with `cipher` under `key`, returning the note only if it opens the
+    /// commitment of `payload`.
+    #[inline]
+    pub fn open<F, E>(
+        &self,
+        payload: &Payload<F, E>,
+        cipher: &E,
+        key: &E::DecryptionKey,
+    ) -> Option<Note<F>>
+    where
+        H: ArrayHashFunction<3, Input = F, Output = F>,
+        E: Decrypt<DecryptedPlaintext = Option<Note<F>>>,
+        F: PartialEq,
+    {
+        payload
+            .message
+            .decrypt(cipher, key, &mut ())
+            .filter(|note| self.commit(note, &mut ()) == payload.commitment)
+    }
+
+    /// Asserts that `secret_key` owns `note`, that the commitment to `note` is a member of the
+    /// accumulator with `model` according to `membership_proof`, and that `nullifier` is the
+    /// nullifier of `note`.
+    #[inline]
+    pub fn assert_spend<F, M, COM>(
+        &self,
+        secret_key: &F,
+        note: &Note<F>,
+        model: &M,
+        membership_proof: &MembershipProof<M>,
+        nullifier: &F,
+        compiler: &mut COM,
+    ) where
+        K: ArrayHashFunction<1, COM, Input = F, Output = F>,
+        H: ArrayHashFunction<3, COM, Input = F, Output = F>,
+        N: PseudorandomFunction<COM, Key = F, Input = F, Output = F>,
+        M: AssertValidVerification<COM, Item = F>,
+        F: eclair::cmp::PartialEq<F, COM>,
+        COM: Assert,
+    {
+        let owner = self.owner(secret_key, compiler);
+        compiler.assert_eq(&owner, &note.owner);
+        let commitment = self.commit(note, compiler);
+        membership_proof.assert_valid(model, &commitment, compiler);
+        let expected_nullifier = self.nullifier(secret_key, &commitment, compiler);
+        compiler.assert_eq(&expected_nullifier, nullifier);
+    }
+}
+
+impl<K, H, N, COM> Constant<COM> for Parameters<K, H, N>
+where
+    K: Constant<COM>,
+    H: Constant<COM>,
+    N: Constant<COM>,
+{
+    type Type = Parameters<K::Type, H::Type, N::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.owner_key.as_constant(compiler),
+            this.commitment.as_constant(compiler),
+            this.nullifier.as_constant(compiler),
+        )
+    }
+}
+
+impl<K, H, N, D> Sample<D> for Parameters<K, H, N>
+where
+    K: Sample<D>,
+    H: Sample<D>,
+    N: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(
+            rng.sample(distribution.clone()),
+            rng.sample(distribution.clone()),
+            rng.sample(distribution),
+        )
+    }
+}
+
+/// Note Payload
+///
+/// The payload of a new note consists of its commitment, which is inserted into the accumulator,
+/// and the note encrypted to its owner. See [`Parameters::payload`] to build one and
+/// [`Parameters::open`] to recover the note.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "F: Deserialize<'de>, EncryptedMessage<E>: Deserialize<'de>",
+            serialize = "F: Serialize, EncryptedMessage<E>: Serialize",
+        ),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "F: Clone, E::Header: Clone, E::Ciphertext: Clone"),
+    Copy(bound = "F: Copy, E::Header: Copy, E::Ciphertext: Copy"),
+    Debug(bound = "F: Debug, E::Header: Debug, E::Ciphertext: Debug"),
+    Default(bound = "F: Default, E::Header: Default, E::Ciphertext: Default"),
+    Hash(bound = "F: Hash, E::Header: Hash, E::Ciphertext: Hash")
+)]
+pub struct Payload<F, E>
+where
+    E: CiphertextType + HeaderType + ?Sized,
+{
+    /// Note Commitment
+    pub commitment: F,
+
+    /// Encrypted Note
+    pub message: EncryptedMessage<E>,
+}
+
+impl<F, E> Payload<F, E>
+where
+    E: CiphertextType + HeaderType + ?Sized,
+{
+    /// Builds a new [`Payload`] from `commitment` and `message`.
+    #[inline]
+    pub fn new(commitment: F, message: EncryptedMessage<E>) -> Self {
+        Self {
+            commitment,
+            message,
+        }
+    }
+
+    /// Inserts the commitment of `self` into `accumulator`, returning `false` if the accumulator
+    /// has exhausted its capacity. See [`Accumulator::insert`] for more.
+    #[inline]
+    pub fn insert<A>(&self, accumulator: &mut A) -> bool
+    where
+        A: Accumulator<Item = F> + ?Sized,
+    {
+        accumulator.insert(&self.commitment)
+    }
+}
+
+impl<F, E, COM> Variable<Public, COM> for Payload<F, E>
+where
+    F: Variable<Public, COM>,
+    E: CiphertextType + HeaderType + Constant<COM>,
+    E::Type: CiphertextType + HeaderType,
+    EncryptedMessage<E>: Variable<Public, COM, Type = EncryptedMessage<E::Type>>,
+{
+    type Type = Payload<F::Type, E::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown::<Public, _>(),
+            compiler.allocate_unknown::<Public, _>(),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.commitment.as_known::<Public, _>(compiler),
+            this.message.as_known::<Public, _>(compiler),
+        )
+    }
+}
+
+impl<F, E> Encode for Payload<F, E>
+where
+    F: Encode,
+    E: CiphertextType + HeaderType,
+    E::Header: Encode,
+    E::Ciphertext: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.commitment.encode(&mut writer)?;
+        self.message.encode(&mut writer)?;
+        Ok(())
+    }
+}
+
+impl<F, E, P> Input<P> for Payload<F, E>
+where
+    E: CiphertextType + HeaderType,
+    P: HasInput<F> + HasInput<EncryptedMessage<E>> + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut P::Input) {
+        P::extend(input, &self.commitment);
+        P::extend(input, &self.message);
+    }
+}
@@ -0,0 +1,477 @@
+//! Semaphore
+//!
+//! Semaphore lets a member of a group broadcast a signal anonymously. Every member holds an
+//! [`Identity`] whose commitment is stored in an accumulator of group members. Broadcasting a
+//! signal proves membership of the identity commitment and reveals a nullifier hash derived from
+//! the identity and an external nullifier, which prevents the same identity from signaling twice
+//! for the same external nullifier without revealing which member signaled. The [`Semaphore`]
+//! statement enforces this inside of a circuit and drives key generation, proving, and
+//! verification with any [`ProofSystem`].
+//!
+//! # Signal Binding
+//!
+//! The signal itself is hashed outside of the circuit into the [`signal_hash`] public input. The
+//! circuit does not constrain it any further, so it is only bound to the proof by proof systems
+//! that commit to every public input, like Groth16.
+//!
+//! [`signal_hash`]: Signal::signal_hash
+
+use crate::{
+    accumulator::{AssertValidVerification, MembershipProof, Types},
+    constraint::{HasInput, ProofSystem},
+    hash::ArrayHashFunction,
+    statements::{self, Circuit, Statement},
+    ProofInput,
+};
+use eclair::{
+    alloc::{
+        mode::{Public, Secret},
+        Allocate, Allocator, Constant, Variable,
+    },
+    bool::{Assert, AssertEq},
+};
+use openzl_util::rand::{CryptoRng, Rand, RngCore, Sample};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Identity
+///
+/// Both secrets of an identity are sampled uniformly at random. The `nullifier` is the secret
+/// behind the nullifier hashes of the signals of this identity and the `trapdoor` hides the
+/// identity commitment.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Identity<F> {
+    /// Identity Nullifier
+    pub nullifier: F,
+
+    /// Identity Trapdoor
+    pub trapdoor: F,
+}
+
+impl<F> Identity<F> {
+    /// Builds a new [`Identity`] from `nullifier` and `trapdoor`.
+    #[inline]
+    pub fn new(nullifier: F, trapdoor: F) -> Self {
+        Self {
+            nullifier,
+            trapdoor,
+        }
+    }
+}
+
+impl<F, D> Sample<D> for Identity<F>
+where
+    F: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution.clone()), rng.sample(distribution))
+    }
+}
+
+impl<F, M, COM> Variable<M, COM> for Identity<F>
+where
+    F: Variable<M, COM>,
+{
+    type Type = Identity<F::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.nullifier.as_known(compiler),
+            this.trapdoor.as_known(compiler),
+        )
+    }
+}
+
+/// Semaphore Parameters
+///
+/// The commitment of an identity is `C(nullifier, trapdoor)` and the nullifier hash of a signal is
+/// `N(external_nullifier, nullifier)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Parameters<C, N> {
+    /// Identity Commitment Scheme
+    pub commitment: C,
+
+    /// Nullifier Hash Function
+    pub nullifier: N,
+}
+
+impl<C, N> Parameters<C, N> {
+    /// Builds a new [`Parameters`] from `commitment` and `nullifier`.
+    #[inline]
+    pub fn new(commitment: C, nullifier: N) -> Self {
+        Self {
+            commitment,
+            nullifier,
+        }
+    }
+
+    /// Computes the commitment to `identity`.
+    #[inline]
+    pub fn commit<F, COM>(&self, identity: &Identity<F>, compiler: &mut COM) -> F
+    where
+        C: ArrayHashFunction<2, COM, Input = F, Output = F>,
+    {
+        self.commitment
+            .hash([&identity.nullifier, &identity.trapdoor], compiler)
+    }
+
+    /// Computes the nullifier hash of `identity` for `external_nullifier`.
+    #[inline]
+    pub fn nullifier_hash<F, COM>(
+        &self,
+        identity: &Identity<F>,
+        external_nullifier: &F,
+        compiler: &mut COM,
+    ) -> F
+    where
+        N: ArrayHashFunction<2, COM, Input = F, Output = F>,
+    {
+        self.nullifier
+            .hash([external_nullifier, &identity.nullifier], compiler)
+    }
+}
+
+impl<C, N, COM> Constant<COM> for Parameters<C, N>
+where
+    C: Constant<COM>,
+    N: Constant<COM>,
+{
+    type Type = Parameters<C::Type, N::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.commitment.as_constant(compiler),
+            this.nullifier.as_constant(compiler),
+        )
+    }
+}
+
+impl<C, N, D> Sample<D> for Parameters<C, N>
+where
+    C: Sample<D>,
+    N: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution.clone()), rng.sample(distribution))
+    }
+}
+
+/// Signal
+///
+/// This is the public input of the [`Semaphore`] statement. It is posted together with the proof
+/// and wired into the proof system input in field order.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, ProofInput)]
+#[proof_input(crate = "crate")]
+pub struct Signal<F, R> {
+    /// Accumulated Output of the Group
+    pub root: R,
+
+    /// Nullifier Hash
+    pub nullifier_hash: F,
+
+    /// External Nullifier
+    pub external_nullifier: F,
+
+    /// Signal Hash
+    pub signal_hash: F,
+}
+
+impl<F, R> Signal<F, R> {
+    /// Builds a new [`Signal`] from `root`, `nullifier_hash`, `external_nullifier`, and
+    /// `signal_hash`.
+    #[inline]
+    pub fn new(root: R, nullifier_hash: F, external_nullifier: F, signal_hash: F) -> Self {
+        Self {
+            root,
+            nullifier_hash,
+            external_nullifier,
+            signal_hash,
+        }
+    }
+}
+
+impl<F, R, COM> Variable<Public, COM> for Signal<F, R>
+where
+    F: Variable<Public, COM>,
+    R: Variable<Public, COM>,
+{
+    type Type = Signal<F::Type, R::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown::<Public, _>(),
+            compiler.allocate_unknown::<Public, _>(),
+            compiler.allocate_unknown::<Public, _>(),
+            compiler.allocate_unknown::<Public, _>(),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.root.as_known::<Public, _>(compiler),
+            this.nullifier_hash.as_known::<Public, _>(compiler),
+            this.external_nullifier.as_known::<Public, _>(compiler),
+            this.signal_hash.as_known::<Public, _>(compiler),
+        )
+    }
+}
+
+/// Signal Witness
+///
+/// This is the secret witness of the [`Semaphore`] statement, made up of the identity of the
+/// member and the `membership` witness of its commitment in the group accumulator.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Witness<F, W> {
+    /// Identity
+    pub identity: Identity<F>,
+
+    /// Membership Witness
+    pub membership: W,
+}
+
+impl<F, W> Witness<F, W> {
+    /// Builds a new [`Witness`] from `identity` and `membership`.
+    #[inline]
+    pub fn new(identity: Identity<F>, membership: W) -> Self {
+        Self {
+            identity,
+            membership,
+        }
+    }
+}
+
+impl<F, W, COM> Variable<Secret, COM> for Witness<F, W>
+where
+    F: Variable<Secret, COM>,
+    W: Variable<Secret, COM>,
+{
+    type Type = Witness<F::Type, W::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(
+            compiler.allocate_unknown::<Secret, _>(),
+            compiler.allocate_unknown::<Secret, _>(),
+        )
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.identity.as_known::<Secret, _>(compiler),
+            this.membership.as_known::<Secret, _>(compiler),
+        )
+    }
+}
+
+/// Semaphore Statement
+///
+/// Enforces that the commitment to the secret identity is stored in the group accumulator with the
+/// public root under `model`, and that the public nullifier hash is the nullifier hash of the
+/// identity for the public external nullifier.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Semaphore<C, N, M> {
+    /// Semaphore Parameters
+    pub parameters: Parameters<C, N>,
+
+    /// Group Accumulator Model
+    pub model: M,
+}
+
+impl<C, N, M> Semaphore<C, N, M> {
+    /// Builds a new [`Semaphore`] statement from `parameters` and `model`.
+    #[inline]
+    pub fn new(parameters: Parameters<C, N>, model: M) -> Self {
+        Self { parameters, model }
+    }
+
+    /// Computes the commitment to `identity`, which is the item to insert into the group
+    /// accumulator when `identity` joins the group.
+    #[inline]
+    pub fn identity_commitment<F>(&self, identity: &Identity<F>) -> F
+    where
+        C: ArrayHashFunction<2, Input = F, Output = F>,
+    {
+        self.parameters.commit(identity, &mut ())
+    }
+
+    /// Builds the public [`Signal`] and its secret [`Witness`] for `identity` broadcasting
+    /// `signal_hash` under `external_nullifier`, where `membership_proof` proves the membership of
+    /// the identity commitment in the group.
+    #[inline]
+    pub fn signal<F>(
+        &self,
+        identity: Identity<F>,
+        membership_proof: MembershipProof<M>,
+        external_nullifier: F,
+        signal_hash: F,
+    ) -> (Signal<F, M::Output>, Witness<F, M::Witness>)
+    where
+        N: ArrayHashFunction<2, Input = F, Output = F>,
+        M: Types<Item = F>,
+    {
+        let nullifier_hash =
+            self.parameters
+                .nullifier_hash(&identity, &external_nullifier, &mut ());
+        let (membership, root) = membership_proof.into_parts();
+        (
+            Signal::new(root, nullifier_hash, external_nullifier, signal_hash),
+            Witness::new(identity, membership),
+        )
+    }
+
+    /// Generates the proving and verifying keys of the Semaphore circuit for the proof system `P`,
+    /// where `V` is the in-circuit version of `self`.
+    #[inline]
+    pub fn generate_keys<P, V, R>(
+        &self,
+        public_parameters: &P::PublicParameters,
+        rng: &mut R,
+    ) -> Result<(P::ProvingContext, P::VerifyingContext), P::Error>
+    where
+        Self: Clone,
+        P: ProofSystem,
+        V: Statement<P::Compiler> + Constant<P::Compiler, Type = Self>,
+        V::Public: Variable<Public, P::Compiler>,
+        V::Secret: Variable<Secret, P::Compiler>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        Circuit::new(self.clone()).compile::<P, V, R>(public_parameters, rng)
+    }
+
+    /// Proves the broadcast of `signal` with its `witness` for the proof system `P` and the
+    /// proving `context`, where `V` is the in-circuit version of `self`.
+    #[inline]
+    pub fn prove<P, V, R>(
+        &self,
+        context: &P::ProvingContext,
+        signal: &statements::PublicInput<V, P::Compiler>,
+        witness: &statements::SecretWitness<V, P::Compiler>,
+        rng: &mut R,
+    ) -> Result<P::Proof, P::Error>
+    where
+        Self: Clone,
+        P: ProofSystem,
+        V: Statement<P::Compiler> + Constant<P::Compiler, Type = Self>,
+        V::Public: Variable<Public, P::Compiler>,
+        V::Secret: Variable<Secret, P::Compiler>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        Circuit::new(self.clone()).prove::<P, V, R>(context, signal, witness, rng)
+    }
+}
+
+impl<C, N, M, COM> Constant<COM> for Semaphore<C, N, M>
+where
+    C: Constant<COM>,
+    N: Constant<COM>,
+    M: Constant<COM>,
+{
+    type Type = Semaphore<C::Type, N::Type, M::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.parameters.as_constant(compiler),
+            this.model.as_constant(compiler),
+        )
+    }
+}
+
+impl<C, N, M, D> Sample<D> for Semaphore<C, N, M>
+where
+    C: Sample<D>,
+    N: Sample<D>,
+    M: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution.clone()), rng.sample(distribution))
+    }
+}
+
+impl<C, N, M, COM> Statement<COM> for Semaphore<C, N, M>
+where
+    C: ArrayHashFunction<2, COM, Input = M::Item, Output = M::Item>,
+    N: ArrayHashFunction<2, COM, Input = M::Item, Output = M::Item>,
+    M: AssertValidVerification<COM>,
+    M::Item: eclair::cmp::PartialEq<M::Item, COM>,
+    COM: Assert,
+{
+    type Public = Signal<M::Item, M::Output>;
+    type Secret = Witness<M::Item, M::Witness>;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        let commitment = self.parameters.commit(&secret.identity, compiler);
+        self.model
+            .assert_valid(&commitment, &secret.membership, &public.root, compiler);
+        let nullifier_hash =
+            self.parameters
+                .nullifier_hash(&secret.identity, &public.external_nullifier, compiler);
+        compiler.assert_eq(&nullifier_hash, &public.nullifier_hash);
+    }
+}
+
+/// Verifies that `proof` proves the broadcast of `signal` with the proof system `P` and the
+/// verifying `context`.
+#[inline]
+pub fn verify<P, F, R>(
+    context: &P::VerifyingContext,
+    signal: &Signal<F, R>,
+    proof: &P::Proof,
+) -> Result<bool, P::Error>
+where
+    P: HasInput<Signal<F, R>>,
+{
+    statements::verify::<P, _>(context, signal, proof)
+}
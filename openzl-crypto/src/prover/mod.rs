@@ -0,0 +1,21 @@
+//! Proof Generation Services
+
+use crate::constraint::ProofSystem;
+
+pub mod pool;
+
+/// Proving Circuit
+///
+/// A circuit builds its constraints into a proof compiler of the proof system `P` using a known
+/// witness. Implementations should allocate variables in the same order as the circuit that was
+/// used to generate the proving context.
+pub trait Circuit<P>
+where
+    P: ProofSystem + ?Sized,
+{
+    /// Witness Type
+    type Witness;
+
+    /// Builds the constraints of `self` with the known `witness` into `compiler`.
+    fn build(&self, witness: &Self::Witness, compiler: &mut P::Compiler);
+}
@@ -0,0 +1,711 @@
+//! Proving Worker Pool
+//!
+//! The [`Pool`] owns a fixed number of worker threads which share one proving context and pull
+//! `(circuit, witness)` tasks from a bounded queue. Each worker builds the proof compiler for its
+//! task, generates the proof with its own random number generator, and reports the proof together
+//! with the [`Metrics`] of that proof through the [`Ticket`] returned on submission. Once the queue
+//! is full, [`Pool::submit`] blocks and [`Pool::try_submit`] hands the task back, so producers are
+//! throttled by the speed of the prover instead of growing the queue without bound.
+
+use crate::{
+    constraint::{
+        measure::{Measure, Size},
+        ProofSystem,
+    },
+    prover::Circuit,
+};
+use alloc::vec::Vec;
+use core::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use openzl_util::{
+    derivative,
+    rand::{CryptoRng, RngCore},
+};
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender, SyncSender, TryRecvError, TrySendError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+/// Pool Configuration
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Config {
+    /// Number of Worker Threads
+    pub workers: usize,
+
+    /// Number of Tasks that can Wait in the Queue
+    pub queue_capacity: usize,
+}
+
+impl Config {
+    /// Builds a new [`Config`] with `workers` worker threads and room for `queue_capacity` waiting
+    /// tasks.
+    #[inline]
+    pub fn new(workers: usize, queue_capacity: usize) -> Self {
+        Self {
+            workers,
+            queue_capacity,
+        }
+    }
+}
+
+impl Default for Config {
+    /// Builds a [`Config`] with one worker per available core and a queue which holds two tasks
+    /// per worker.
+    #[inline]
+    fn default() -> Self {
+        let workers = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1);
+        Self::new(workers, 2 * workers)
+    }
+}
+
+/// Proof Metrics
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Metrics {
+    /// Time Spent in the Queue before a Worker Picked up the Task
+    pub queue_time: Duration,
+
+    /// Time Spent Building the Proof Compiler
+    pub synthesis_time: Duration,
+
+    /// Time Spent Generating the Proof
+    pub proving_time: Duration,
+
+    /// Size of the Proof Compiler
+    pub size: Size,
+}
+
+impl Metrics {
+    /// Returns the total time between submission and completion of the proof.
+    #[inline]
+    pub fn total_time(&self) -> Duration {
+        self.queue_time + self.synthesis_time + self.proving_time
+    }
+}
+
+/// Generated Proof
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "P::Proof: Clone"),
+    Debug(bound = "P::Proof: core::fmt::Debug")
+)]
+pub struct Proven<P>
+where
+    P: ProofSystem + ?Sized,
+{
+    /// Proof
+    pub proof: P::Proof,
+
+    /// Metrics
+    pub metrics: Metrics,
+}
+
+/// Pool Error
+///
+/// This `enum` is the error state of [`Ticket::wait`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error<E> {
+    /// Proof System Error
+    Proof(E),
+
+    /// Disconnected Error
+    ///
+    /// The worker which picked up the task stopped before reporting a result, which happens when
+    /// building the circuit or generating the proof panics.
+    Disconnected,
+}
+
+/// Submission Error
+///
+/// This `enum` is the error state of [`Pool::submit`] and [`Pool::try_submit`] and hands back the
+/// task which could not be queued.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SubmitError<T> {
+    /// Full Queue Error
+    Full(T),
+
+    /// Closed Pool Error
+    ///
+    /// All the workers of the pool have stopped.
+    Closed(T),
+}
+
+impl<T> SubmitError<T> {
+    /// Returns the task which could not be queued.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(task) | Self::Closed(task) => task,
+        }
+    }
+}
+
+/// Pool Statistics
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Statistics {
+    /// Number of Submitted Tasks
+    pub submitted: usize,
+
+    /// Number of Successfully Generated Proofs
+    pub completed: usize,
+
+    /// Number of Tasks which Returned a Proof System Error
+    pub failed: usize,
+}
+
+impl Statistics {
+    /// Returns the number of tasks which are queued or in progress.
+    ///
+    /// Tasks whose worker panicked are counted as pending.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.submitted
+            .saturating_sub(self.completed)
+            .saturating_sub(self.failed)
+    }
+}
+
+/// Shared Statistics Counters
+#[derive(Debug, Default)]
+struct Counters {
+    /// Number of Submitted Tasks
+    submitted: AtomicUsize,
+
+    /// Number of Successfully Generated Proofs
+    completed: AtomicUsize,
+
+    /// Number of Tasks which Returned a Proof System Error
+    failed: AtomicUsize,
+}
+
+impl Counters {
+    /// Returns a snapshot of the counters.
+    ///
+    /// Submissions are counted before their job is queued, so loading the number of submitted
+    /// tasks last makes it at least the number of finished tasks in the snapshot.
+    #[inline]
+    fn snapshot(&self) -> Statistics {
+        let completed = self.completed.load(Ordering::Acquire);
+        let failed = self.failed.load(Ordering::Acquire);
+        Statistics {
+            submitted: self.submitted.load(Ordering::Acquire),
+            completed,
+            failed,
+        }
+    }
+}
+
+/// Proof Result
+type ProofResult<P> = Result<Proven<P>, <P as ProofSystem>::Error>;
+
+/// Queued Task
+struct Job<P, C>
+where
+    P: ProofSystem,
+    C: Circuit<P>,
+{
+    /// Circuit
+    circuit: C,
+
+    /// Witness
+    witness: C::Witness,
+
+    /// Submission Time
+    submitted: Instant,
+
+    /// Result Sender
+    sender: Sender<ProofResult<P>>,
+}
+
+impl<P, C> Job<P, C>
+where
+    P: ProofSystem,
+    C: Circuit<P>,
+    P::Compiler: Measure,
+{
+    /// Builds the circuit and generates the proof with `context` and `rng`, sending the result
+    /// back to the submitter.
+    #[inline]
+    fn run<R>(self, context: &P::ProvingContext, counters: &Counters, rng: &mut R)
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let queue_time = self.submitted.elapsed();
+        let start = Instant::now();
+        let (compiler, size) = {
+            openzl_util::trace_span!("openzl::prover::synthesize");
+            let mut compiler = P::proof_compiler();
+            self.circuit.build(&self.witness, &mut compiler);
+            let size = compiler.measure();
+            (compiler, size)
+        };
+        let synthesis_time = start.elapsed();
+        let start = Instant::now();
+        let result = {
+            openzl_util::trace_span!("openzl::prover::prove");
+            P::prove(context, compiler, rng)
+        };
+        let proving_time = start.elapsed();
+        if result.is_ok() {
+            counters.completed.fetch_add(1, Ordering::AcqRel);
+        } else {
+            counters.failed.fetch_add(1, Ordering::AcqRel);
+        }
+        let _ = self.sender.send(result.map(move |proof| Proven {
+            proof,
+            metrics: Metrics {
+                queue_time,
+                synthesis_time,
+                proving_time,
+                size,
+            },
+        }));
+    }
+}
+
+/// Proof Ticket
+///
+/// This `struct` is returned by [`Pool::submit`] and [`Pool::try_submit`] and receives the result
+/// of the submitted task.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct Ticket<P>
+where
+    P: ProofSystem,
+{
+    /// Result Receiver
+    receiver: Receiver<ProofResult<P>>,
+}
+
+impl<P> Ticket<P>
+where
+    P: ProofSystem,
+{
+    /// Blocks until the task of this ticket is finished and returns its result.
+    #[inline]
+    pub fn wait(self) -> Result<Proven<P>, Error<P::Error>> {
+        match self.receiver.recv() {
+            Ok(result) => result.map_err(Error::Proof),
+            _ => Err(Error::Disconnected),
+        }
+    }
+
+    /// Returns the result of the task of this ticket if it is finished, without blocking.
+    ///
+    /// The result can only be taken once; once this method has returned a result, every later
+    /// call returns [`Error::Disconnected`].
+    #[inline]
+    pub fn try_wait(&self) -> Option<Result<Proven<P>, Error<P::Error>>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result.map_err(Error::Proof)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(Error::Disconnected)),
+        }
+    }
+}
+
+/// Proving Worker Pool
+///
+/// See the [module-level documentation](self) for more.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct Pool<P, C>
+where
+    P: ProofSystem,
+    C: Circuit<P>,
+{
+    /// Task Queue Sender
+    sender: Option<SyncSender<Job<P, C>>>,
+
+    /// Worker Threads
+    workers: Vec<JoinHandle<()>>,
+
+    /// Statistics Counters
+    counters: Arc<Counters>,
+
+    /// Configuration
+    config: Config,
+}
+
+impl<P, C> Pool<P, C>
+where
+    P: ProofSystem + 'static,
+    C: Circuit<P> + Send + 'static,
+    C::Witness: Send,
+    P::Compiler: Measure,
+    P::ProvingContext: Send + Sync,
+    P::Proof: Send,
+    P::Error: Send,
+{
+    /// Starts a new [`Pool`] proving with `context` according to `config`, calling `rng` once per
+    /// worker to build its random number generator.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `config` requests zero workers.
+    #[inline]
+    pub fn new<R, F>(context: P::ProvingContext, config: Config, mut rng: F) -> Self
+    where
+        R: CryptoRng + RngCore + Send + 'static,
+        F: FnMut() -> R,
+    {
+        assert!(config.workers > 0, "The pool needs at least one worker.");
+        let (sender, receiver) = mpsc::sync_channel(config.queue_capacity);
+        let context = Arc::new(context);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let counters = Arc::new(Counters::default());
+        let workers = (0..config.workers)
+            .map(|_| {
+                let context = context.clone();
+                let receiver = receiver.clone();
+                let counters = counters.clone();
+                let mut rng = rng();
+                thread::spawn(move || loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        _ => return,
+                    };
+                    match job {
+                        Ok(job) => job.run(&context, &counters, &mut rng),
+                        _ => return,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+            counters,
+            config,
+        }
+    }
+
+    /// Submits `circuit` with `witness` to the queue, blocking while the queue is full.
+    #[inline]
+    pub fn submit(
+        &self,
+        circuit: C,
+        witness: C::Witness,
+    ) -> Result<Ticket<P>, SubmitError<(C, C::Witness)>> {
+        let (job, ticket) = Self::job(circuit, witness);
+        self.count_submission();
+        match self.sender().send(job) {
+            Ok(()) => Ok(ticket),
+            Err(mpsc::SendError(job)) => {
+                self.uncount_submission();
+                Err(SubmitError::Closed((job.circuit, job.witness)))
+            }
+        }
+    }
+
+    /// Submits `circuit` with `witness` to the queue, returning them back if the queue is full.
+    #[inline]
+    pub fn try_submit(
+        &self,
+        circuit: C,
+        witness: C::Witness,
+    ) -> Result<Ticket<P>, SubmitError<(C, C::Witness)>> {
+        let (job, ticket) = Self::job(circuit, witness);
+        self.count_submission();
+        match self.sender().try_send(job) {
+            Ok(()) => Ok(ticket),
+            Err(TrySendError::Full(job)) => {
+                self.uncount_submission();
+                Err(SubmitError::Full((job.circuit, job.witness)))
+            }
+            Err(TrySendError::Disconnected(job)) => {
+                self.uncount_submission();
+                Err(SubmitError::Closed((job.circuit, job.witness)))
+            }
+        }
+    }
+}
+
+impl<P, C> Pool<P, C>
+where
+    P: ProofSystem,
+    C: Circuit<P>,
+{
+    /// Builds a new job for `circuit` and `witness` and the ticket which receives its result.
+    #[inline]
+    fn job(circuit: C, witness: C::Witness) -> (Job<P, C>, Ticket<P>) {
+        let (sender, receiver) = mpsc::channel();
+        (
+            Job {
+                circuit,
+                witness,
+                submitted: Instant::now(),
+                sender,
+            },
+            Ticket { receiver },
+        )
+    }
+
+    /// Returns the queue sender which is only taken when the pool is dropped.
+    #[inline]
+    fn sender(&self) -> &SyncSender<Job<P, C>> {
+        self.sender
+            .as_ref()
+            .expect("The sender is only taken when the pool is dropped.")
+    }
+
+    /// Counts a submission before its job is queued, so that no worker can finish a task which
+    /// is not counted yet.
+    #[inline]
+    fn count_submission(&self) {
+        self.counters.submitted.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Reverts [`count_submission`](Self::count_submission) for a job which could not be queued.
+    #[inline]
+    fn uncount_submission(&self) {
+        self.counters.submitted.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Returns the configuration of this pool.
+    #[inline]
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Returns a snapshot of the statistics of this pool.
+    #[inline]
+    pub fn statistics(&self) -> Statistics {
+        self.counters.snapshot()
+    }
+
+    /// Closes the queue, waits for the workers to finish the remaining tasks, and returns the
+    /// final statistics of this pool.
+    #[inline]
+    pub fn shutdown(mut self) -> Statistics {
+        self.close();
+        self.statistics()
+    }
+
+    /// Closes the queue and joins all the workers.
+    #[inline]
+    fn close(&mut self) {
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<P, C> Drop for Pool<P, C>
+where
+    P: ProofSystem,
+    C: Circuit<P>,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constraint::measure::Count;
+    use core::sync::atomic::AtomicBool;
+    use openzl_util::rand::TestRng;
+
+    /// Test Compiler
+    ///
+    /// Accumulates a running sum and counts one constraint per summand.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct Tally {
+        /// Running Sum
+        sum: u64,
+
+        /// Number of Summands
+        constraints: usize,
+    }
+
+    impl<M> Count<M> for Tally {}
+
+    impl Measure for Tally {
+        #[inline]
+        fn constraint_count(&self) -> usize {
+            self.constraints
+        }
+    }
+
+    /// Test Proof System
+    ///
+    /// A proof is the sum in the compiler offset by the proving context, and proving fails for
+    /// empty sums.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct Offset;
+
+    impl ProofSystem for Offset {
+        type Compiler = Tally;
+        type PublicParameters = u64;
+        type ProvingContext = u64;
+        type VerifyingContext = u64;
+        type Input = u64;
+        type Proof = u64;
+        type Error = ();
+
+        #[inline]
+        fn context_compiler() -> Self::Compiler {
+            Default::default()
+        }
+
+        #[inline]
+        fn proof_compiler() -> Self::Compiler {
+            Default::default()
+        }
+
+        #[inline]
+        fn compile<R>(
+            public_parameters: &Self::PublicParameters,
+            compiler: Self::Compiler,
+            rng: &mut R,
+        ) -> Result<(Self::ProvingContext, Self::VerifyingContext), Self::Error>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let _ = (compiler, rng);
+            Ok((*public_parameters, *public_parameters))
+        }
+
+        #[inline]
+        fn prove<R>(
+            context: &Self::ProvingContext,
+            compiler: Self::Compiler,
+            rng: &mut R,
+        ) -> Result<Self::Proof, Self::Error>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let _ = rng;
+            match compiler.constraints {
+                0 => Err(()),
+                _ => Ok(context + compiler.sum),
+            }
+        }
+
+        #[inline]
+        fn verify(
+            context: &Self::VerifyingContext,
+            input: &Self::Input,
+            proof: &Self::Proof,
+        ) -> Result<bool, Self::Error> {
+            Ok(*proof == context + input)
+        }
+    }
+
+    /// Sum of Squares Circuit
+    #[derive(Clone, Copy, Debug, Default)]
+    struct SumOfSquares;
+
+    impl Circuit<Offset> for SumOfSquares {
+        type Witness = Vec<u64>;
+
+        #[inline]
+        fn build(&self, witness: &Self::Witness, compiler: &mut Tally) {
+            for value in witness {
+                compiler.sum += value * value;
+                compiler.constraints += 1;
+            }
+        }
+    }
+
+    /// Tests that every submitted task is proven with its own metrics.
+    #[test]
+    fn pool_proves_all_tasks() {
+        let pool = Pool::<Offset, SumOfSquares>::new(7, Config::new(4, 2), TestRng::new);
+        let tickets = (1..=16u64)
+            .map(|n| {
+                let witness = (1..=n).collect::<Vec<_>>();
+                let ticket = pool
+                    .submit(SumOfSquares, witness)
+                    .expect("The pool is running.");
+                (n, ticket)
+            })
+            .collect::<Vec<_>>();
+        for (n, ticket) in tickets {
+            let proven = ticket.wait().expect("Proving should succeed.");
+            let input = n * (n + 1) * (2 * n + 1) / 6;
+            assert!(Offset::verify(&7, &input, &proven.proof).expect("Unable to verify proof."));
+            assert_eq!(proven.metrics.size.constraint_count, n as usize);
+        }
+        assert_eq!(
+            pool.shutdown(),
+            Statistics {
+                submitted: 16,
+                completed: 16,
+                failed: 0,
+            }
+        );
+    }
+
+    /// Tests that proof system errors are reported through the ticket and counted as failures.
+    #[test]
+    fn pool_reports_failures() {
+        let pool = Pool::<Offset, SumOfSquares>::new(0, Config::new(1, 1), TestRng::new);
+        let ticket = pool
+            .submit(SumOfSquares, Vec::new())
+            .expect("The pool is running.");
+        assert_eq!(
+            ticket.wait().map(|proven| proven.proof),
+            Err(Error::Proof(()))
+        );
+        assert_eq!(pool.shutdown().failed, 1);
+    }
+
+    /// Tests that the statistics stay consistent while several threads submit tasks and the
+    /// workers finish them concurrently.
+    #[test]
+    fn statistics_are_consistent_under_concurrent_submission() {
+        let pool = Pool::<Offset, SumOfSquares>::new(0, Config::new(4, 1), TestRng::new);
+        let is_done = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let observer = scope.spawn(|| {
+                while !is_done.load(Ordering::Acquire) {
+                    let statistics = pool.statistics();
+                    assert!(
+                        statistics.completed + statistics.failed <= statistics.submitted,
+                        "Finished tasks must never outnumber submitted tasks: {statistics:?}."
+                    );
+                }
+            });
+            let submitters = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        (1..=32u64)
+                            .map(|n| {
+                                pool.submit(SumOfSquares, Vec::from([n]))
+                                    .expect("The pool is running.")
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            for submitter in submitters {
+                for ticket in submitter.join().expect("Submitters should not panic.") {
+                    ticket.wait().expect("Proving should succeed.");
+                }
+            }
+            is_done.store(true, Ordering::Release);
+            observer.join().expect("The observer should not panic.");
+        });
+        assert_eq!(
+            pool.shutdown(),
+            Statistics {
+                submitted: 128,
+                completed: 128,
+                failed: 0,
+            }
+        );
+    }
+}
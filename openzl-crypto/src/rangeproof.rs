@@ -0,0 +1,926 @@
+//! Bulletproofs Range Proofs
+//!
+//! This module implements the range proofs of [Bünz et al.](https://eprint.iacr.org/2017/1066)
+//! natively over the [`algebra`](crate::algebra) traits. A prover who knows the opening of a
+//! Pedersen commitment `V = v * g + gamma * h` can convince a verifier that `v` lies in
+//! `[0, 2^n)` with a proof of `2 * log(n) + 4` group elements and `5` scalars, without a trusted
+//! setup. The proofs are made non-interactive with a [`Transcript`] and many proofs can be
+//! checked together with [`verify_batch`](Parameters::verify_batch).
+//!
+//! # Crypto Safety
+//!
+//! All the generators in the [`Parameters`] must be independent, i.e. nobody may know a discrete
+//! logarithm of one of them with respect to the others. Sampling them as multiples of a known
+//! point is only acceptable for testing.
+
+use crate::{
+    algebra::{Group, ScalarMul},
+    poseidon::{FieldGeneration, NativeField},
+    Decode, Encode,
+};
+
+#[doc(inline)]
+pub use crate::transcript::Transcript;
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    iter,
+};
+use openzl_util::{
+    codec::{self, Decode as _, DecodeError, Read},
+    derivative,
+    rand::{RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{de, Deserialize, Deserializer, Serialize};
+
+/// Maximum Number of Bits of a Range
+pub const MAX_BITS: usize = 64;
+
+/// Range Proof Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Number of bits is not a power of two between `1` and [`MAX_BITS`]
+    InvalidBitLength(usize),
+
+    /// The vector generators have different lengths
+    GeneratorsLengthMismatch {
+        /// Number of `G` Generators
+        g: usize,
+
+        /// Number of `H` Generators
+        h: usize,
+    },
+
+    /// The value does not lie in the range of the parameters
+    ValueOutOfRange,
+}
+
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidBitLength(bits) => write!(
+                f,
+                "the number of bits {bits} is not a power of two between 1 and {MAX_BITS}"
+            ),
+            Self::GeneratorsLengthMismatch { g, h } => write!(
+                f,
+                "the vector generators have different lengths {g} and {h}"
+            ),
+            Self::ValueOutOfRange => write!(f, "the value does not lie in the range"),
+        }
+    }
+}
+
+/// Inner-Product Argument
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "G: Clone, F: Clone"),
+    Debug(bound = "G: Debug, F: Debug"),
+    Eq(bound = "G: Eq, F: Eq"),
+    Hash(bound = "G: Hash, F: Hash"),
+    PartialEq(bound = "G: PartialEq, F: PartialEq")
+)]
+pub struct InnerProductProof<G, F> {
+    /// Left Commitments of each Round
+    pub left: Vec<G>,
+
+    /// Right Commitments of each Round
+    pub right: Vec<G>,
+
+    /// Final Left Scalar
+    pub a: F,
+
+    /// Final Right Scalar
+    pub b: F,
+}
+
+/// Range Proof
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "G: Clone, F: Clone"),
+    Debug(bound = "G: Debug, F: Debug"),
+    Eq(bound = "G: Eq, F: Eq"),
+    Hash(bound = "G: Hash, F: Hash"),
+    PartialEq(bound = "G: PartialEq, F: PartialEq")
+)]
+pub struct Proof<G, F> {
+    /// Commitment to the Bits of the Value
+    pub a: G,
+
+    /// Commitment to the Blinding Vectors
+    pub s: G,
+
+    /// Commitment to the Linear Coefficient of `t(X)`
+    pub t_1: G,
+
+    /// Commitment to the Quadratic Coefficient of `t(X)`
+    pub t_2: G,
+
+    /// Blinding Factor of `t(x)`
+    pub tau_x: F,
+
+    /// Blinding Factor of the Vector Commitments
+    pub mu: F,
+
+    /// Evaluation `t(x)`
+    pub t_hat: F,
+
+    /// Inner-Product Argument for `t(x)`
+    pub inner_product: InnerProductProof<G, F>,
+}
+
+/// Range Proof Parameters
+///
+/// The parameters fix the number of bits `n` of the range and hold the Pedersen generators `g` and
+/// `h`, the inner-product generator `u` and the `n` pairs of vector generators. Decoded and
+/// deserialized parameters are checked by [`Parameters::new`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative, Encode)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Debug(bound = "G: Debug"),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: PartialEq")
+)]
+pub struct Parameters<G> {
+    /// Value Generator
+    g: G,
+
+    /// Blinding Generator
+    h: G,
+
+    /// Inner-Product Generator
+    u: G,
+
+    /// Left Vector Generators
+    g_vec: Vec<G>,
+
+    /// Right Vector Generators
+    h_vec: Vec<G>,
+}
+
+impl<G> Parameters<G> {
+    /// Builds new [`Parameters`] for ranges of `g_vec.len()` bits, returning an error if the
+    /// vector generators have different lengths or if their length is not a power of two between
+    /// `1` and [`MAX_BITS`].
+    #[inline]
+    pub fn new(g: G, h: G, u: G, g_vec: Vec<G>, h_vec: Vec<G>) -> Result<Self, Error> {
+        if g_vec.len() != h_vec.len() {
+            return Err(Error::GeneratorsLengthMismatch {
+                g: g_vec.len(),
+                h: h_vec.len(),
+            });
+        }
+        if g_vec.is_empty() || g_vec.len() > MAX_BITS || !g_vec.len().is_power_of_two() {
+            return Err(Error::InvalidBitLength(g_vec.len()));
+        }
+        Ok(Self {
+            g,
+            h,
+            u,
+            g_vec,
+            h_vec,
+        })
+    }
+
+    /// Decodes a vector of generators, rejecting lengths larger than [`MAX_BITS`] before
+    /// allocating.
+    #[inline]
+    fn decode_generators<R>(
+        mut reader: R,
+    ) -> Result<Vec<G>, DecodeError<R::Error, ParametersDecodeError<G::Error>>>
+    where
+        G: codec::Decode,
+        R: Read,
+    {
+        let len = u64::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| ParametersDecodeError::MissingLength))?;
+        if len > MAX_BITS as u64 {
+            return Err(DecodeError::Decode(ParametersDecodeError::Parameters(
+                Error::InvalidBitLength(len as usize),
+            )));
+        }
+        (0..len)
+            .map(|_| {
+                G::decode(&mut reader)
+                    .map_err(|err| err.map_decode(ParametersDecodeError::Generator))
+            })
+            .collect()
+    }
+
+    /// Returns the number of bits of the range proven by `self`.
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.g_vec.len()
+    }
+
+    /// Returns the number of rounds of the inner-product argument.
+    #[inline]
+    fn rounds(&self) -> usize {
+        self.bits().trailing_zeros() as usize
+    }
+
+    /// Returns `true` if `value` lies in the range proven by `self`.
+    #[inline]
+    pub fn contains(&self, value: u64) -> bool {
+        self.bits() == MAX_BITS || value >> self.bits() == 0
+    }
+
+    /// Returns the Pedersen commitment to `value` with the given `blinding` factor.
+    #[inline]
+    pub fn commit<F>(&self, value: u64, blinding: &F) -> G
+    where
+        F: FieldGeneration,
+        G: Group + ScalarMul<F, Output = G>,
+    {
+        self.g
+            .scalar_mul(&F::from_u64(value), &mut ())
+            .add(&self.h.scalar_mul(blinding, &mut ()), &mut ())
+    }
+
+    /// Proves that the commitment [`commit(value, blinding)`](Self::commit) opens to a value in
+    /// the range of `self`, returning an error if `value` is out of range.
+    #[inline]
+    pub fn prove<F, T, R>(
+        &self,
+        value: u64,
+        blinding: &F,
+        mut transcript: T,
+        rng: &mut R,
+    ) -> Result<Proof<G, F>, Error>
+    where
+        F: Clone + FieldGeneration + NativeField + Sample,
+        G: Clone + Group + ScalarMul<F, Output = G>,
+        T: Transcript<G, F>,
+        R: RngCore + ?Sized,
+    {
+        if !self.contains(value) {
+            return Err(Error::ValueOutOfRange);
+        }
+        let n = self.bits();
+        let commitment = self.commit(value, blinding);
+        transcript.append_scalar(b"bits", &F::from_u64(n as u64));
+        transcript.append_point(b"V", &commitment);
+        let a_l = (0..n)
+            .map(|i| F::from_u64((value >> i) & 1))
+            .collect::<Vec<_>>();
+        let a_r = a_l.iter().map(|a| a.sub(&F::one())).collect::<Vec<_>>();
+        let alpha = F::gen(rng);
+        let a = self.h.scalar_mul(&alpha, &mut ()).add(
+            &linear_combination(a_l.iter().chain(&a_r), self.g_vec.iter().chain(&self.h_vec)),
+            &mut (),
+        );
+        let s_l = (0..n).map(|_| F::gen(rng)).collect::<Vec<_>>();
+        let s_r = (0..n).map(|_| F::gen(rng)).collect::<Vec<_>>();
+        let rho = F::gen(rng);
+        let s = self.h.scalar_mul(&rho, &mut ()).add(
+            &linear_combination(s_l.iter().chain(&s_r), self.g_vec.iter().chain(&self.h_vec)),
+            &mut (),
+        );
+        transcript.append_point(b"A", &a);
+        transcript.append_point(b"S", &s);
+        let y = transcript.challenge_scalar(b"y");
+        let z = transcript.challenge_scalar(b"z");
+        let z_squared = z.mul(&z);
+        let y_powers = powers(&y, n);
+        let two_powers = powers(&F::from_u64(2), n);
+        let l_0 = a_l.iter().map(|a| a.sub(&z)).collect::<Vec<_>>();
+        let r_0 = a_r
+            .iter()
+            .zip(&y_powers)
+            .zip(&two_powers)
+            .map(|((a, y), two)| a.add(&z).mul(y).add(&z_squared.mul(two)))
+            .collect::<Vec<_>>();
+        let r_1 = s_r
+            .iter()
+            .zip(&y_powers)
+            .map(|(s, y)| s.mul(y))
+            .collect::<Vec<_>>();
+        let t_1 = inner_product(&l_0, &r_1).add(&inner_product(&s_l, &r_0));
+        let t_2 = inner_product(&s_l, &r_1);
+        let tau_1 = F::gen(rng);
+        let tau_2 = F::gen(rng);
+        let t_1_commitment = self.pedersen(&t_1, &tau_1);
+        let t_2_commitment = self.pedersen(&t_2, &tau_2);
+        transcript.append_point(b"T_1", &t_1_commitment);
+        transcript.append_point(b"T_2", &t_2_commitment);
+        let x = transcript.challenge_scalar(b"x");
+        let l = l_0
+            .iter()
+            .zip(&s_l)
+            .map(|(l, s)| l.add(&s.mul(&x)))
+            .collect::<Vec<_>>();
+        let r = r_0
+            .iter()
+            .zip(&r_1)
+            .map(|(r, s)| r.add(&s.mul(&x)))
+            .collect::<Vec<_>>();
+        let t_hat = inner_product(&l, &r);
+        let tau_x = tau_2
+            .mul(&x.mul(&x))
+            .add(&tau_1.mul(&x))
+            .add(&z_squared.mul(blinding));
+        let mu = alpha.add(&rho.mul(&x));
+        transcript.append_scalar(b"tau_x", &tau_x);
+        transcript.append_scalar(b"mu", &mu);
+        transcript.append_scalar(b"t_hat", &t_hat);
+        let q = self
+            .u
+            .scalar_mul(&transcript.challenge_scalar(b"w"), &mut ());
+        let y_inverse = y
+            .inverse()
+            .expect("Challenges are non-zero with overwhelming probability.");
+        let h_prime = self.h_prime(&y_inverse);
+        Ok(Proof {
+            a,
+            s,
+            t_1: t_1_commitment,
+            t_2: t_2_commitment,
+            tau_x,
+            mu,
+            t_hat,
+            inner_product: prove_inner_product(
+                self.g_vec.clone(),
+                h_prime,
+                &q,
+                l,
+                r,
+                &mut transcript,
+            ),
+        })
+    }
+
+    /// Returns the Pedersen commitment to the scalar `value` with the given `blinding` factor.
+    #[inline]
+    fn pedersen<F>(&self, value: &F, blinding: &F) -> G
+    where
+        G: Group + ScalarMul<F, Output = G>,
+    {
+        self.g
+            .scalar_mul(value, &mut ())
+            .add(&self.h.scalar_mul(blinding, &mut ()), &mut ())
+    }
+
+    /// Returns the right vector generators rescaled by the powers of `y_inverse`.
+    #[inline]
+    fn h_prime<F>(&self, y_inverse: &F) -> Vec<G>
+    where
+        F: NativeField,
+        G: ScalarMul<F, Output = G>,
+    {
+        self.h_vec
+            .iter()
+            .zip(powers(y_inverse, self.bits()))
+            .map(|(h, y)| h.scalar_mul(&y, &mut ()))
+            .collect()
+    }
+
+    /// Computes the two equations which hold for a valid `proof` of `commitment`, returning `None`
+    /// if `proof` is malformed.
+    #[inline]
+    fn equations<F, T>(
+        &self,
+        commitment: &G,
+        proof: &Proof<G, F>,
+        mut transcript: T,
+    ) -> Option<[(G, G); 2]>
+    where
+        F: Clone + FieldGeneration + NativeField,
+        G: Clone + Group + ScalarMul<F, Output = G>,
+        T: Transcript<G, F>,
+    {
+        let n = self.bits();
+        if proof.inner_product.left.len() != self.rounds()
+            || proof.inner_product.right.len() != self.rounds()
+        {
+            return None;
+        }
+        transcript.append_scalar(b"bits", &F::from_u64(n as u64));
+        transcript.append_point(b"V", commitment);
+        transcript.append_point(b"A", &proof.a);
+        transcript.append_point(b"S", &proof.s);
+        let y = transcript.challenge_scalar(b"y");
+        let z = transcript.challenge_scalar(b"z");
+        transcript.append_point(b"T_1", &proof.t_1);
+        transcript.append_point(b"T_2", &proof.t_2);
+        let x = transcript.challenge_scalar(b"x");
+        transcript.append_scalar(b"tau_x", &proof.tau_x);
+        transcript.append_scalar(b"mu", &proof.mu);
+        transcript.append_scalar(b"t_hat", &proof.t_hat);
+        let q = self
+            .u
+            .scalar_mul(&transcript.challenge_scalar(b"w"), &mut ());
+        let z_squared = z.mul(&z);
+        let y_powers = powers(&y, n);
+        let two_powers = powers(&F::from_u64(2), n);
+        let delta = z
+            .sub(&z_squared)
+            .mul(&sum(&y_powers))
+            .sub(&z_squared.mul(&z).mul(&sum(&two_powers)));
+        let polynomial = (
+            self.pedersen(&proof.t_hat, &proof.tau_x),
+            linear_combination(
+                [z_squared.clone(), delta, x.clone(), x.mul(&x)].iter(),
+                [commitment, &self.g, &proof.t_1, &proof.t_2],
+            ),
+        );
+        let h_prime = self.h_prime(&y.inverse()?);
+        let minus_z = F::zero().sub(&z);
+        let h_scalars = y_powers
+            .iter()
+            .zip(&two_powers)
+            .map(|(y, two)| z.mul(y).add(&z_squared.mul(two)))
+            .collect::<Vec<_>>();
+        let p = linear_combination(
+            [F::one(), x, F::zero().sub(&proof.mu), proof.t_hat.clone()]
+                .iter()
+                .chain(iter::repeat(&minus_z).take(n))
+                .chain(&h_scalars),
+            [&proof.a, &proof.s, &self.h, &q]
+                .into_iter()
+                .chain(&self.g_vec)
+                .chain(&h_prime),
+        );
+        let inner_product = verify_inner_product(
+            self.g_vec.clone(),
+            h_prime,
+            &q,
+            p,
+            &proof.inner_product,
+            &mut transcript,
+        )?;
+        Some([polynomial, inner_product])
+    }
+
+    /// Verifies that `proof` shows that `commitment` opens to a value in the range of `self`.
+    #[inline]
+    pub fn verify<F, T>(&self, commitment: &G, proof: &Proof<G, F>, transcript: T) -> bool
+    where
+        F: Clone + FieldGeneration + NativeField,
+        G: Clone + Group + ScalarMul<F, Output = G> + PartialEq,
+        T: Transcript<G, F>,
+    {
+        match self.equations(commitment, proof, transcript) {
+            Some(equations) => equations.iter().all(|(lhs, rhs)| lhs == rhs),
+            _ => false,
+        }
+    }
+
+    /// Verifies all the `proofs` for their commitments at once.
+    ///
+    /// The verification equations of all the proofs are combined into a single equation with
+    /// random weights sampled from `rng`, so a batch with an invalid proof is only accepted with
+    /// negligible probability. Every proof comes with the transcript it was proven with. Empty
+    /// batches are valid.
+    #[inline]
+    pub fn verify_batch<'p, F, T, I, R>(&self, proofs: I, rng: &mut R) -> bool
+    where
+        F: 'p + Clone + FieldGeneration + NativeField + Sample,
+        G: 'p + Clone + Group + ScalarMul<F, Output = G> + PartialEq,
+        T: Transcript<G, F>,
+        I: IntoIterator<Item = (&'p G, &'p Proof<G, F>, T)>,
+        R: RngCore + ?Sized,
+    {
+        let mut lhs = Vec::new();
+        let mut rhs = Vec::new();
+        for (commitment, proof, transcript) in proofs {
+            match self.equations(commitment, proof, transcript) {
+                Some(equations) => {
+                    for (l, r) in equations {
+                        lhs.push(l);
+                        rhs.push(r);
+                    }
+                }
+                _ => return false,
+            }
+        }
+        if lhs.is_empty() {
+            return true;
+        }
+        let weights = (0..lhs.len()).map(|_| F::gen(rng)).collect::<Vec<_>>();
+        linear_combination(weights.iter(), &lhs) == linear_combination(weights.iter(), &rhs)
+    }
+}
+
+/// Range Proof Parameters Decode Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ParametersDecodeError<E> {
+    /// Generator Decoding Error
+    Generator(E),
+
+    /// Missing Length Prefix
+    ///
+    /// The length prefix of one of the vectors of generators could not be read.
+    MissingLength,
+
+    /// Invalid Parameters
+    ///
+    /// The decoded generators do not form valid [`Parameters`]. See [`Parameters::new`] for the
+    /// checks performed on them.
+    Parameters(Error),
+}
+
+impl<G> codec::Decode for Parameters<G>
+where
+    G: codec::Decode,
+{
+    type Error = ParametersDecodeError<G::Error>;
+
+    /// Decodes the generators of the [`Parameters`] and checks them with [`Parameters::new`].
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let mut generator = || {
+            G::decode(&mut reader).map_err(|err| err.map_decode(ParametersDecodeError::Generator))
+        };
+        let g = generator()?;
+        let h = generator()?;
+        let u = generator()?;
+        let g_vec = Self::decode_generators(&mut reader)?;
+        let h_vec = Self::decode_generators(&mut reader)?;
+        Self::new(g, h, u, g_vec, h_vec)
+            .map_err(|err| DecodeError::Decode(ParametersDecodeError::Parameters(err)))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<'de, G> Deserialize<'de> for Parameters<G>
+where
+    G: Deserialize<'de>,
+{
+    /// Deserializes the generators of the [`Parameters`] and checks them with
+    /// [`Parameters::new`].
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Unchecked Range Proof Parameters
+        #[derive(Deserialize)]
+        #[serde(
+            crate = "openzl_util::serde",
+            deny_unknown_fields,
+            rename = "Parameters"
+        )]
+        struct Unchecked<G> {
+            /// Value Generator
+            g: G,
+
+            /// Blinding Generator
+            h: G,
+
+            /// Inner-Product Generator
+            u: G,
+
+            /// Left Vector Generators
+            g_vec: Vec<G>,
+
+            /// Right Vector Generators
+            h_vec: Vec<G>,
+        }
+
+        let Unchecked {
+            g,
+            h,
+            u,
+            g_vec,
+            h_vec,
+        } = Unchecked::deserialize(deserializer)?;
+        Self::new(g, h, u, g_vec, h_vec).map_err(de::Error::custom)
+    }
+}
+
+/// Returns `sum_i scalars[i] * points[i]`.
+///
+/// # Panics
+///
+/// This function panics if `scalars` or `points` is empty.
+#[inline]
+fn linear_combination<'s, 'g, F, G, S, P>(scalars: S, points: P) -> G
+where
+    F: 's,
+    G: 'g + Group + ScalarMul<F, Output = G>,
+    S: IntoIterator<Item = &'s F>,
+    P: IntoIterator<Item = &'g G>,
+{
+    scalars
+        .into_iter()
+        .zip(points)
+        .map(|(s, p)| p.scalar_mul(s, &mut ()))
+        .reduce(|acc, term| acc.add(&term, &mut ()))
+        .expect("Linear combinations are never empty.")
+}
+
+/// Returns the inner product of `lhs` and `rhs`.
+#[inline]
+fn inner_product<F>(lhs: &[F], rhs: &[F]) -> F
+where
+    F: NativeField,
+{
+    lhs.iter()
+        .zip(rhs)
+        .fold(F::zero(), |acc, (l, r)| acc.add(&l.mul(r)))
+}
+
+/// Returns the sum of `values`.
+#[inline]
+fn sum<F>(values: &[F]) -> F
+where
+    F: NativeField,
+{
+    values.iter().fold(F::zero(), |acc, v| acc.add(v))
+}
+
+/// Returns the first `n` powers of `x` starting from `x^0`.
+#[inline]
+fn powers<F>(x: &F, n: usize) -> Vec<F>
+where
+    F: NativeField,
+{
+    iter::successors(Some(F::one()), |power| Some(power.mul(x)))
+        .take(n)
+        .collect()
+}
+
+/// Folds the generators `points` in half, multiplying the lower half by `lo` and the upper half by
+/// `hi`.
+#[inline]
+fn fold_points<F, G>(points: Vec<G>, lo: &F, hi: &F) -> Vec<G>
+where
+    G: Group + ScalarMul<F, Output = G>,
+{
+    let (points_lo, points_hi) = points.split_at(points.len() / 2);
+    points_lo
+        .iter()
+        .zip(points_hi)
+        .map(|(l, h)| {
+            l.scalar_mul(lo, &mut ())
+                .add(&h.scalar_mul(hi, &mut ()), &mut ())
+        })
+        .collect()
+}
+
+/// Folds the scalars `values` in half, multiplying the lower half by `lo` and the upper half by
+/// `hi`.
+#[inline]
+fn fold_scalars<F>(values: Vec<F>, lo: &F, hi: &F) -> Vec<F>
+where
+    F: NativeField,
+{
+    let (values_lo, values_hi) = values.split_at(values.len() / 2);
+    values_lo
+        .iter()
+        .zip(values_hi)
+        .map(|(l, h)| l.mul(lo).add(&h.mul(hi)))
+        .collect()
+}
+
+/// Proves the inner product `<a, b>` for the commitment `<a, g_vec> + <b, h_vec> + <a, b> * q`.
+#[inline]
+fn prove_inner_product<F, G, T>(
+    mut g_vec: Vec<G>,
+    mut h_vec: Vec<G>,
+    q: &G,
+    mut a: Vec<F>,
+    mut b: Vec<F>,
+    transcript: &mut T,
+) -> InnerProductProof<G, F>
+where
+    F: Clone + NativeField,
+    G: Group + ScalarMul<F, Output = G>,
+    T: Transcript<G, F>,
+{
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    while a.len() > 1 {
+        let k = a.len() / 2;
+        let (a_lo, a_hi) = a.split_at(k);
+        let (b_lo, b_hi) = b.split_at(k);
+        let (g_lo, g_hi) = g_vec.split_at(k);
+        let (h_lo, h_hi) = h_vec.split_at(k);
+        let c_l = inner_product(a_lo, b_hi);
+        let c_r = inner_product(a_hi, b_lo);
+        let l = linear_combination(
+            a_lo.iter().chain(b_hi).chain(iter::once(&c_l)),
+            g_hi.iter().chain(h_lo).chain(iter::once(q)),
+        );
+        let r = linear_combination(
+            a_hi.iter().chain(b_lo).chain(iter::once(&c_r)),
+            g_lo.iter().chain(h_hi).chain(iter::once(q)),
+        );
+        transcript.append_point(b"L", &l);
+        transcript.append_point(b"R", &r);
+        left.push(l);
+        right.push(r);
+        let u = transcript.challenge_scalar(b"u");
+        let u_inverse = u
+            .inverse()
+            .expect("Challenges are non-zero with overwhelming probability.");
+        a = fold_scalars(a, &u, &u_inverse);
+        b = fold_scalars(b, &u_inverse, &u);
+        g_vec = fold_points(g_vec, &u_inverse, &u);
+        h_vec = fold_points(h_vec, &u, &u_inverse);
+    }
+    InnerProductProof {
+        left,
+        right,
+        a: a[0].clone(),
+        b: b[0].clone(),
+    }
+}
+
+/// Computes the final equation of the inner-product argument `proof` for the commitment `p`,
+/// returning `None` if a challenge is zero.
+#[inline]
+fn verify_inner_product<F, G, T>(
+    mut g_vec: Vec<G>,
+    mut h_vec: Vec<G>,
+    q: &G,
+    mut p: G,
+    proof: &InnerProductProof<G, F>,
+    transcript: &mut T,
+) -> Option<(G, G)>
+where
+    F: Clone + NativeField,
+    G: Group + ScalarMul<F, Output = G>,
+    T: Transcript<G, F>,
+{
+    for (l, r) in proof.left.iter().zip(&proof.right) {
+        transcript.append_point(b"L", l);
+        transcript.append_point(b"R", r);
+        let u = transcript.challenge_scalar(b"u");
+        let u_inverse = u.inverse()?;
+        let u_squared = u.mul(&u);
+        let u_inverse_squared = u_inverse.mul(&u_inverse);
+        p = p
+            .add(&l.scalar_mul(&u_squared, &mut ()), &mut ())
+            .add(&r.scalar_mul(&u_inverse_squared, &mut ()), &mut ());
+        g_vec = fold_points(g_vec, &u_inverse, &u);
+        h_vec = fold_points(h_vec, &u, &u_inverse);
+    }
+    Some((
+        p,
+        linear_combination(
+            [proof.a.clone(), proof.b.clone(), proof.a.mul(&proof.b)].iter(),
+            [&g_vec[0], &h_vec[0], q],
+        ),
+    ))
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+
+    /// Asserts that proofs for every one of the `values` verify one by one and in a batch, using
+    /// fresh transcripts from `transcript`, and that they do not verify for other commitments.
+    #[inline]
+    pub fn assert_valid_range_proofs<F, G, T, R>(
+        parameters: &Parameters<G>,
+        values: &[u64],
+        mut transcript: impl FnMut() -> T,
+        rng: &mut R,
+    ) where
+        F: Clone + FieldGeneration + NativeField + Sample,
+        G: Clone + Debug + Group + ScalarMul<F, Output = G> + PartialEq,
+        T: Transcript<G, F>,
+        R: RngCore + ?Sized,
+    {
+        let mut proofs = Vec::with_capacity(values.len());
+        for value in values {
+            let blinding = F::gen(rng);
+            let commitment = parameters.commit(*value, &blinding);
+            let proof = parameters
+                .prove(*value, &blinding, transcript(), rng)
+                .expect("Values must be in the range of the parameters.");
+            assert!(
+                parameters.verify(&commitment, &proof, transcript()),
+                "Range proof for {value} failed to verify."
+            );
+            let other = parameters.commit(value.wrapping_add(1), &blinding);
+            assert!(
+                !parameters.verify(&other, &proof, transcript()),
+                "Range proof for {value} verified for a different commitment."
+            );
+            proofs.push((commitment, proof));
+        }
+        assert!(
+            parameters.verify_batch(
+                proofs
+                    .iter()
+                    .map(|(commitment, proof)| (commitment, proof, transcript())),
+                rng
+            ),
+            "Batch of range proofs failed to verify."
+        );
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openzl_util::codec::{Decode, Encode};
+
+    /// Encodes range proof parameters over `u64` generators with the vector generators `g_vec` and
+    /// `h_vec`, without checking them.
+    #[inline]
+    fn encode_unchecked(g_vec: &[u64], h_vec: &[u64]) -> Vec<u8> {
+        [
+            1u64.to_vec(),
+            2u64.to_vec(),
+            3u64.to_vec(),
+            Encode::to_vec(&g_vec.to_vec()),
+            Encode::to_vec(&h_vec.to_vec()),
+        ]
+        .concat()
+    }
+
+    /// Tests that valid parameters survive an encoding round-trip and that malformed encodings are
+    /// rejected with an error instead of building invalid parameters.
+    #[test]
+    fn malformed_parameters_are_rejected() {
+        let g_vec = (4..8).collect::<Vec<u64>>();
+        let h_vec = (8..12).collect::<Vec<u64>>();
+        let parameters = Parameters::new(1, 2, 3, g_vec.clone(), h_vec.clone())
+            .expect("The parameters should be valid.");
+        let bytes = encode_unchecked(&g_vec, &h_vec);
+        assert_eq!(
+            bytes,
+            parameters.to_vec(),
+            "The unchecked encoding should match the encoding of the parameters."
+        );
+        assert_eq!(
+            Parameters::from_vec(bytes.clone()),
+            Ok(parameters),
+            "Valid parameters should be decoded."
+        );
+        let cases = [
+            (
+                encode_unchecked(&g_vec, &h_vec[..2]),
+                ParametersDecodeError::Parameters(Error::GeneratorsLengthMismatch { g: 4, h: 2 }),
+            ),
+            (
+                encode_unchecked(&[], &[]),
+                ParametersDecodeError::Parameters(Error::InvalidBitLength(0)),
+            ),
+            (
+                encode_unchecked(&g_vec[..3], &h_vec[..3]),
+                ParametersDecodeError::Parameters(Error::InvalidBitLength(3)),
+            ),
+            (
+                [&bytes[..24], &(MAX_BITS as u64 + 1).to_vec()[..]].concat(),
+                ParametersDecodeError::Parameters(Error::InvalidBitLength(MAX_BITS + 1)),
+            ),
+            (bytes[..24].to_vec(), ParametersDecodeError::MissingLength),
+            (
+                bytes[..bytes.len() - 1].to_vec(),
+                ParametersDecodeError::Generator(()),
+            ),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(
+                Parameters::<u64>::from_vec(bytes),
+                Err(expected),
+                "Malformed parameters must be rejected."
+            );
+        }
+        assert!(
+            matches!(
+                Parameters::<u64>::from_vec([&bytes[..24], &u64::MAX.to_vec()[..]].concat()),
+                Err(ParametersDecodeError::Parameters(Error::InvalidBitLength(
+                    _
+                )))
+            ),
+            "Huge length prefixes must be rejected before allocating."
+        );
+    }
+}
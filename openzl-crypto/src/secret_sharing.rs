@@ -0,0 +1,414 @@
+//! Shamir Secret Sharing
+//!
+//! A secret field element is split into shares which are evaluations of a random polynomial whose
+//! constant term is the secret. Any `threshold` shares reconstruct the secret by Lagrange
+//! interpolation at zero, while fewer shares reveal nothing about it. For verifiable sharing, the
+//! dealer also publishes Feldman commitments to the coefficients of the polynomial in some group,
+//! which every shareholder can use to check their share without learning the secret.
+
+use crate::{
+    algebra::{Group, ScalarMul},
+    poseidon::{FieldGeneration, NativeField},
+    Decode, Encode,
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::{
+    derivative,
+    rand::{RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Secret Sharing Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Threshold is zero or larger than the number of shares
+    InvalidThreshold {
+        /// Threshold
+        threshold: usize,
+
+        /// Number of Shares
+        shares: usize,
+    },
+
+    /// Fewer shares than the threshold were given
+    NotEnoughShares {
+        /// Threshold
+        threshold: usize,
+
+        /// Number of Shares
+        shares: usize,
+    },
+
+    /// Two shares have the same identifier
+    DuplicateIdentifier,
+
+    /// A share has the zero identifier, which would reveal the secret
+    ZeroIdentifier,
+}
+
+/// Secret Share
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Decode, Default, Encode, Eq, Hash, PartialEq)]
+pub struct Share<F> {
+    /// Share Identifier
+    ///
+    /// This is the point at which the sharing polynomial was evaluated.
+    pub identifier: F,
+
+    /// Share Value
+    pub value: F,
+}
+
+impl<F> Share<F> {
+    /// Builds a new [`Share`] from `identifier` and `value`.
+    #[inline]
+    pub fn new(identifier: F, value: F) -> Self {
+        Self { identifier, value }
+    }
+}
+
+/// Feldman Commitments
+///
+/// The commitment at index `j` is the generator multiplied by the `j`-th coefficient of the
+/// sharing polynomial, so the first commitment is a commitment to the secret itself.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative, Decode, Encode)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Debug(bound = "G: Debug"),
+    Default(bound = ""),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: PartialEq")
+)]
+pub struct Commitments<G>(
+    /// Coefficient Commitments
+    pub Vec<G>,
+);
+
+impl<G> Commitments<G> {
+    /// Returns the threshold of the sharing committed to by `self`.
+    #[inline]
+    pub fn threshold(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks that `share` is an evaluation of the polynomial committed to by `self`, using the
+    /// same `generator` that was used to build the commitments.
+    #[inline]
+    pub fn verify<F>(&self, generator: &G, share: &Share<F>) -> bool
+    where
+        G: Clone + Group + ScalarMul<F, Output = G> + PartialEq,
+    {
+        let mut commitments = self.0.iter().rev();
+        let last = match commitments.next() {
+            Some(last) => last.clone(),
+            _ => return false,
+        };
+        let expected = commitments.fold(last, |acc, commitment| {
+            acc.scalar_mul(&share.identifier, &mut ())
+                .add(commitment, &mut ())
+        });
+        generator.scalar_mul(&share.value, &mut ()) == expected
+    }
+}
+
+/// Shamir Secret Sharing Scheme
+///
+/// This configuration splits secrets into `shares` shares, any `threshold` of which reconstruct
+/// the secret.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Shamir {
+    /// Threshold
+    threshold: usize,
+
+    /// Number of Shares
+    shares: usize,
+}
+
+impl Shamir {
+    /// Builds a new [`Shamir`] scheme which splits secrets into `shares` shares, any `threshold`
+    /// of which reconstruct the secret.
+    #[inline]
+    pub fn new(threshold: usize, shares: usize) -> Result<Self, Error> {
+        if threshold == 0 || threshold > shares {
+            return Err(Error::InvalidThreshold { threshold, shares });
+        }
+        Ok(Self { threshold, shares })
+    }
+
+    /// Returns the number of shares needed to reconstruct a secret.
+    #[inline]
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the number of shares a secret is split into.
+    #[inline]
+    pub fn shares(&self) -> usize {
+        self.shares
+    }
+
+    /// Samples the coefficients of a sharing polynomial whose constant term is `secret`.
+    #[inline]
+    fn sample_polynomial<F, R>(&self, secret: F, rng: &mut R) -> Vec<F>
+    where
+        F: Sample,
+        R: RngCore + ?Sized,
+    {
+        let mut coefficients = Vec::with_capacity(self.threshold);
+        coefficients.push(secret);
+        coefficients.extend((1..self.threshold).map(|_| F::gen(rng)));
+        coefficients
+    }
+
+    /// Evaluates the sharing polynomial with `coefficients` at every one of the `identifiers`.
+    #[inline]
+    fn evaluate<F>(coefficients: &[F], identifiers: Vec<F>) -> Vec<Share<F>>
+    where
+        F: NativeField,
+    {
+        identifiers
+            .into_iter()
+            .map(|identifier| {
+                let value = coefficients
+                    .iter()
+                    .rev()
+                    .fold(F::zero(), |acc, c| acc.mul(&identifier).add(c));
+                Share::new(identifier, value)
+            })
+            .collect()
+    }
+
+    /// Checks that `identifiers` are non-zero, distinct, and that there is one for each share.
+    #[inline]
+    fn check_identifiers<F>(&self, identifiers: &[F]) -> Result<(), Error>
+    where
+        F: NativeField + PartialEq,
+    {
+        if identifiers.len() != self.shares {
+            return Err(Error::InvalidThreshold {
+                threshold: self.threshold,
+                shares: identifiers.len(),
+            });
+        }
+        check_distinct_non_zero(identifiers.iter())
+    }
+
+    /// Returns the default share identifiers `1, 2, ..., shares`.
+    #[inline]
+    fn default_identifiers<F>(&self) -> Vec<F>
+    where
+        F: FieldGeneration,
+    {
+        (1..=self.shares as u64).map(F::from_u64).collect()
+    }
+
+    /// Splits `secret` into shares identified by `1, 2, ..., shares`.
+    #[inline]
+    pub fn split<F, R>(&self, secret: F, rng: &mut R) -> Vec<Share<F>>
+    where
+        F: FieldGeneration + NativeField + Sample,
+        R: RngCore + ?Sized,
+    {
+        Self::evaluate(
+            &self.sample_polynomial(secret, rng),
+            self.default_identifiers(),
+        )
+    }
+
+    /// Splits `secret` into shares with the given `identifiers`, which must be non-zero, distinct,
+    /// and as many as the number of shares of `self`.
+    #[inline]
+    pub fn split_with_identifiers<F, R>(
+        &self,
+        secret: F,
+        identifiers: Vec<F>,
+        rng: &mut R,
+    ) -> Result<Vec<Share<F>>, Error>
+    where
+        F: NativeField + PartialEq + Sample,
+        R: RngCore + ?Sized,
+    {
+        self.check_identifiers(&identifiers)?;
+        Ok(Self::evaluate(
+            &self.sample_polynomial(secret, rng),
+            identifiers,
+        ))
+    }
+
+    /// Splits `secret` into shares identified by `1, 2, ..., shares`, together with the Feldman
+    /// commitments to the sharing polynomial under `generator`.
+    #[inline]
+    pub fn split_verifiable<F, G, R>(
+        &self,
+        secret: F,
+        generator: &G,
+        rng: &mut R,
+    ) -> (Vec<Share<F>>, Commitments<G>)
+    where
+        F: FieldGeneration + NativeField + Sample,
+        G: ScalarMul<F, Output = G>,
+        R: RngCore + ?Sized,
+    {
+        let coefficients = self.sample_polynomial(secret, rng);
+        let commitments = commit(&coefficients, generator);
+        (
+            Self::evaluate(&coefficients, self.default_identifiers()),
+            commitments,
+        )
+    }
+
+    /// Splits `secret` into shares with the given `identifiers`, together with the Feldman
+    /// commitments to the sharing polynomial under `generator`.
+    #[inline]
+    pub fn split_verifiable_with_identifiers<F, G, R>(
+        &self,
+        secret: F,
+        identifiers: Vec<F>,
+        generator: &G,
+        rng: &mut R,
+    ) -> Result<(Vec<Share<F>>, Commitments<G>), Error>
+    where
+        F: NativeField + PartialEq + Sample,
+        G: ScalarMul<F, Output = G>,
+        R: RngCore + ?Sized,
+    {
+        self.check_identifiers(&identifiers)?;
+        let coefficients = self.sample_polynomial(secret, rng);
+        let commitments = commit(&coefficients, generator);
+        Ok((Self::evaluate(&coefficients, identifiers), commitments))
+    }
+
+    /// Reconstructs the secret from `shares` by Lagrange interpolation at zero.
+    ///
+    /// Only the first `threshold` shares are used. If the shares do not come from the same
+    /// sharing, the result is some unrelated field element.
+    #[inline]
+    pub fn reconstruct<F>(&self, shares: &[Share<F>]) -> Result<F, Error>
+    where
+        F: NativeField + PartialEq,
+    {
+        if shares.len() < self.threshold {
+            return Err(Error::NotEnoughShares {
+                threshold: self.threshold,
+                shares: shares.len(),
+            });
+        }
+        let shares = &shares[..self.threshold];
+        check_distinct_non_zero(shares.iter().map(|share| &share.identifier))?;
+        let mut secret = F::zero();
+        for (i, share) in shares.iter().enumerate() {
+            let mut numerator = F::one();
+            let mut denominator = F::one();
+            for (j, other) in shares.iter().enumerate() {
+                if i != j {
+                    numerator = numerator.mul(&other.identifier);
+                    denominator = denominator.mul(&other.identifier.sub(&share.identifier));
+                }
+            }
+            let coefficient =
+                numerator.mul(&denominator.inverse().ok_or(Error::DuplicateIdentifier)?);
+            secret.add_assign(&share.value.mul(&coefficient));
+        }
+        Ok(secret)
+    }
+}
+
+/// Commits to every one of the `coefficients` by multiplying `generator` with them.
+#[inline]
+fn commit<F, G>(coefficients: &[F], generator: &G) -> Commitments<G>
+where
+    G: ScalarMul<F, Output = G>,
+{
+    Commitments(
+        coefficients
+            .iter()
+            .map(|c| generator.scalar_mul(c, &mut ()))
+            .collect(),
+    )
+}
+
+/// Checks that `identifiers` are non-zero and pairwise distinct.
+#[inline]
+fn check_distinct_non_zero<'f, F, I>(identifiers: I) -> Result<(), Error>
+where
+    F: 'f + NativeField + PartialEq,
+    I: Clone + Iterator<Item = &'f F>,
+{
+    for (i, identifier) in identifiers.clone().enumerate() {
+        if identifier.is_zero() {
+            return Err(Error::ZeroIdentifier);
+        }
+        if identifiers
+            .clone()
+            .skip(i + 1)
+            .any(|other| other == identifier)
+        {
+            return Err(Error::DuplicateIdentifier);
+        }
+    }
+    Ok(())
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+
+    /// Asserts that splitting `secret` with `scheme` gives shares which reconstruct `secret` from
+    /// any window of `threshold` consecutive shares and which verify against their Feldman
+    /// commitments under `generator`.
+    #[inline]
+    pub fn assert_valid_sharing<F, G, R>(scheme: &Shamir, secret: F, generator: &G, rng: &mut R)
+    where
+        F: Clone + Debug + FieldGeneration + NativeField + PartialEq + Sample,
+        G: Clone + Group + ScalarMul<F, Output = G> + PartialEq,
+        R: RngCore + ?Sized,
+    {
+        let (shares, commitments) = scheme.split_verifiable(secret.clone(), generator, rng);
+        assert_eq!(shares.len(), scheme.shares(), "Wrong number of shares.");
+        assert_eq!(
+            commitments.threshold(),
+            scheme.threshold(),
+            "Wrong number of commitments."
+        );
+        for share in &shares {
+            assert!(
+                commitments.verify(generator, share),
+                "Share failed to verify against its commitments."
+            );
+        }
+        for window in shares.windows(scheme.threshold()) {
+            assert_eq!(
+                scheme
+                    .reconstruct(window)
+                    .expect("Windows have exactly as many shares as the threshold."),
+                secret,
+                "Reconstructed secret does not match the original secret."
+            );
+        }
+    }
+}
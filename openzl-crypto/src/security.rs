@@ -0,0 +1,84 @@
+//! Security Levels
+//!
+//! The [`hash::security`](crate::hash::security) and [`algebra::security`](crate::algebra::security)
+//! modules describe which assumptions a primitive relies on, while this module records how strong
+//! the primitive is under those assumptions. Every primitive declares its
+//! [`SECURITY_LEVEL`](SecurityLevel::SECURITY_LEVEL) in bits, composed constructions take the
+//! minimum over their components, and [`assert_security_level`] turns a misconfigured
+//! instantiation into a compile-time error.
+
+use core::marker::PhantomData;
+
+/// 128-bit Security Level
+pub const BITS_128: u32 = 128;
+
+/// 192-bit Security Level
+pub const BITS_192: u32 = 192;
+
+/// 256-bit Security Level
+pub const BITS_256: u32 = 256;
+
+/// Security Level
+///
+/// The security level of a primitive is the binary logarithm of the expected number of operations
+/// an attacker needs to break it, under the assumptions attached to the primitive. Hash functions,
+/// permutations, encryption schemes and signature schemes implement this trait alongside the
+/// traits of their hierarchies, and constructions built from several primitives are only as
+/// strong as their weakest component.
+pub trait SecurityLevel {
+    /// Security Level in Bits
+    const SECURITY_LEVEL: u32;
+}
+
+impl<T> SecurityLevel for &T
+where
+    T: SecurityLevel + ?Sized,
+{
+    const SECURITY_LEVEL: u32 = T::SECURITY_LEVEL;
+}
+
+/// Returns the minimum of the security levels `lhs` and `rhs`.
+///
+/// Composed constructions use this function to compute their security level from the levels of
+/// their components.
+#[inline]
+pub const fn min(lhs: u32, rhs: u32) -> u32 {
+    if lhs < rhs {
+        lhs
+    } else {
+        rhs
+    }
+}
+
+/// Security Level Check
+///
+/// This type evaluates the check that `T` reaches a security level of `BITS` bits at compile time.
+/// See [`assert_security_level`] for the recommended way to use it.
+pub struct Check<T, const BITS: u32>(PhantomData<T>)
+where
+    T: ?Sized;
+
+impl<T, const BITS: u32> Check<T, BITS>
+where
+    T: SecurityLevel + ?Sized,
+{
+    /// Successful Check
+    ///
+    /// Evaluating this constant fails to compile if `T` does not reach `BITS` bits of security.
+    pub const PASSED: () = assert!(
+        T::SECURITY_LEVEL >= BITS,
+        "The security level of the instantiation is below the required level."
+    );
+}
+
+/// Asserts at compile time that `T` reaches a security level of at least `BITS` bits.
+///
+/// The check is evaluated when this function is instantiated, so any call site of this function
+/// with a misconfigured `T` fails to build, even if the call is never executed.
+#[inline]
+pub fn assert_security_level<T, const BITS: u32>()
+where
+    T: SecurityLevel + ?Sized,
+{
+    let () = Check::<T, BITS>::PASSED;
+}
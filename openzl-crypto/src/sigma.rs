@@ -0,0 +1,851 @@
+//! Sigma Protocols
+//!
+//! A sigma protocol is a three-move proof of knowledge: the prover sends a commitment, the
+//! verifier answers with a random challenge, and the prover sends a response that can be checked
+//! against the statement. This module implements Schnorr-style protocols for knowledge of a
+//! discrete logarithm, equality of discrete logarithms and Pedersen openings over the
+//! [`algebra`](crate::algebra) traits, together with their [`And`] and [`Or`] compositions. Every
+//! [`Protocol`] is made non-interactive with a [`Transcript`] using
+//! [`prove`](Protocol::prove) and [`verify`](Protocol::verify).
+
+use crate::{
+    algebra::{Group, ScalarMul},
+    poseidon::NativeField,
+    transcript::Transcript,
+};
+use core::{fmt::Debug, hash::Hash};
+use openzl_util::{
+    derivative,
+    rand::{RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Sigma Protocol
+///
+/// The protocol proves knowledge of a [`Witness`](Self::Witness) for a
+/// [`Statement`](Self::Statement) with challenges in the scalar field `F` of the group `G`.
+pub trait Protocol<G, F> {
+    /// Statement Type
+    type Statement;
+
+    /// Witness Type
+    type Witness;
+
+    /// Commitment Type
+    type Commitment;
+
+    /// Prover Nonce Type
+    ///
+    /// This is the secret state the prover keeps between [`commit`](Self::commit) and
+    /// [`respond`](Self::respond). It must never be reused for another challenge.
+    type Nonce;
+
+    /// Response Type
+    type Response;
+
+    /// Samples the first message of the prover for `statement` and `witness`.
+    fn commit<R>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Nonce)
+    where
+        R: RngCore + ?Sized;
+
+    /// Computes the response of the prover to `challenge` using the `nonce` of its commitment.
+    fn respond(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &F,
+    ) -> Self::Response;
+
+    /// Checks that `response` answers `challenge` for `commitment` and `statement`.
+    fn check(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &F,
+        response: &Self::Response,
+    ) -> bool;
+
+    /// Samples a commitment and response which [`check`](Self::check) for `challenge` and
+    /// `statement` without knowing a witness.
+    fn simulate<R>(
+        &self,
+        statement: &Self::Statement,
+        challenge: &F,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response)
+    where
+        R: RngCore + ?Sized;
+
+    /// Appends the public parameters of `self` and `statement` to `transcript`.
+    fn append_statement<T>(&self, statement: &Self::Statement, transcript: &mut T)
+    where
+        T: Transcript<G, F>;
+
+    /// Appends `commitment` to `transcript`.
+    fn append_commitment<T>(&self, commitment: &Self::Commitment, transcript: &mut T)
+    where
+        T: Transcript<G, F>;
+
+    /// Proves knowledge of `witness` for `statement`, deriving the challenge from `transcript`.
+    #[inline]
+    fn prove<T, R>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        mut transcript: T,
+        rng: &mut R,
+    ) -> Proof<Self::Commitment, Self::Response>
+    where
+        T: Transcript<G, F>,
+        R: RngCore + ?Sized,
+    {
+        let (commitment, nonce) = self.commit(statement, witness, rng);
+        self.append_statement(statement, &mut transcript);
+        self.append_commitment(&commitment, &mut transcript);
+        let challenge = transcript.challenge_scalar(b"c");
+        Proof {
+            response: self.respond(statement, witness, nonce, &challenge),
+            commitment,
+        }
+    }
+
+    /// Verifies `proof` for `statement`, deriving the challenge from `transcript`.
+    #[inline]
+    fn verify<T>(
+        &self,
+        statement: &Self::Statement,
+        proof: &Proof<Self::Commitment, Self::Response>,
+        mut transcript: T,
+    ) -> bool
+    where
+        T: Transcript<G, F>,
+    {
+        self.append_statement(statement, &mut transcript);
+        self.append_commitment(&proof.commitment, &mut transcript);
+        let challenge = transcript.challenge_scalar(b"c");
+        self.check(statement, &proof.commitment, &challenge, &proof.response)
+    }
+}
+
+/// Non-Interactive Sigma Proof
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Proof<C, R> {
+    /// Commitment
+    pub commitment: C,
+
+    /// Response
+    pub response: R,
+}
+
+/// Returns the additive inverse of `value`.
+#[inline]
+fn negate<F>(value: &F) -> F
+where
+    F: NativeField,
+{
+    F::zero().sub(value)
+}
+
+/// Proof of Knowledge of a Discrete Logarithm
+///
+/// The statement is a point `X = x * g` and the witness is the scalar `x`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DiscreteLog<G> {
+    /// Generator
+    pub generator: G,
+}
+
+impl<G> DiscreteLog<G> {
+    /// Builds a new [`DiscreteLog`] protocol over `generator`.
+    #[inline]
+    pub fn new(generator: G) -> Self {
+        Self { generator }
+    }
+}
+
+impl<G, F> Protocol<G, F> for DiscreteLog<G>
+where
+    G: Group + ScalarMul<F, Output = G> + PartialEq,
+    F: NativeField + Sample,
+{
+    type Statement = G;
+    type Witness = F;
+    type Commitment = G;
+    type Nonce = F;
+    type Response = F;
+
+    #[inline]
+    fn commit<R>(&self, statement: &G, witness: &F, rng: &mut R) -> (G, F)
+    where
+        R: RngCore + ?Sized,
+    {
+        let _ = (statement, witness);
+        let nonce = F::gen(rng);
+        (self.generator.scalar_mul(&nonce, &mut ()), nonce)
+    }
+
+    #[inline]
+    fn respond(&self, statement: &G, witness: &F, nonce: F, challenge: &F) -> F {
+        let _ = statement;
+        nonce.add(&challenge.mul(witness))
+    }
+
+    #[inline]
+    fn check(&self, statement: &G, commitment: &G, challenge: &F, response: &F) -> bool {
+        self.generator.scalar_mul(response, &mut ())
+            == commitment.add(&statement.scalar_mul(challenge, &mut ()), &mut ())
+    }
+
+    #[inline]
+    fn simulate<R>(&self, statement: &G, challenge: &F, rng: &mut R) -> (G, F)
+    where
+        R: RngCore + ?Sized,
+    {
+        let response = F::gen(rng);
+        (
+            self.generator
+                .scalar_mul(&response, &mut ())
+                .add(&statement.scalar_mul(&negate(challenge), &mut ()), &mut ()),
+            response,
+        )
+    }
+
+    #[inline]
+    fn append_statement<T>(&self, statement: &G, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        transcript.append_point(b"g", &self.generator);
+        transcript.append_point(b"X", statement);
+    }
+
+    #[inline]
+    fn append_commitment<T>(&self, commitment: &G, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        transcript.append_point(b"K", commitment);
+    }
+}
+
+/// Proof of Equality of Discrete Logarithms
+///
+/// The statement is a pair of points `(X, Y) = (x * g, x * h)` and the witness is the common scalar
+/// `x`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct EqualDiscreteLog<G> {
+    /// First Generator
+    pub g: G,
+
+    /// Second Generator
+    pub h: G,
+}
+
+impl<G> EqualDiscreteLog<G> {
+    /// Builds a new [`EqualDiscreteLog`] protocol over the generators `g` and `h`.
+    #[inline]
+    pub fn new(g: G, h: G) -> Self {
+        Self { g, h }
+    }
+}
+
+impl<G, F> Protocol<G, F> for EqualDiscreteLog<G>
+where
+    G: Group + ScalarMul<F, Output = G> + PartialEq,
+    F: NativeField + Sample,
+{
+    type Statement = (G, G);
+    type Witness = F;
+    type Commitment = (G, G);
+    type Nonce = F;
+    type Response = F;
+
+    #[inline]
+    fn commit<R>(&self, statement: &(G, G), witness: &F, rng: &mut R) -> ((G, G), F)
+    where
+        R: RngCore + ?Sized,
+    {
+        let _ = (statement, witness);
+        let nonce = F::gen(rng);
+        (
+            (
+                self.g.scalar_mul(&nonce, &mut ()),
+                self.h.scalar_mul(&nonce, &mut ()),
+            ),
+            nonce,
+        )
+    }
+
+    #[inline]
+    fn respond(&self, statement: &(G, G), witness: &F, nonce: F, challenge: &F) -> F {
+        let _ = statement;
+        nonce.add(&challenge.mul(witness))
+    }
+
+    #[inline]
+    fn check(&self, statement: &(G, G), commitment: &(G, G), challenge: &F, response: &F) -> bool {
+        self.g.scalar_mul(response, &mut ())
+            == commitment
+                .0
+                .add(&statement.0.scalar_mul(challenge, &mut ()), &mut ())
+            && self.h.scalar_mul(response, &mut ())
+                == commitment
+                    .1
+                    .add(&statement.1.scalar_mul(challenge, &mut ()), &mut ())
+    }
+
+    #[inline]
+    fn simulate<R>(&self, statement: &(G, G), challenge: &F, rng: &mut R) -> ((G, G), F)
+    where
+        R: RngCore + ?Sized,
+    {
+        let response = F::gen(rng);
+        let challenge = negate(challenge);
+        (
+            (
+                self.g
+                    .scalar_mul(&response, &mut ())
+                    .add(&statement.0.scalar_mul(&challenge, &mut ()), &mut ()),
+                self.h
+                    .scalar_mul(&response, &mut ())
+                    .add(&statement.1.scalar_mul(&challenge, &mut ()), &mut ()),
+            ),
+            response,
+        )
+    }
+
+    #[inline]
+    fn append_statement<T>(&self, statement: &(G, G), transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        transcript.append_point(b"g", &self.g);
+        transcript.append_point(b"h", &self.h);
+        transcript.append_point(b"X", &statement.0);
+        transcript.append_point(b"Y", &statement.1);
+    }
+
+    #[inline]
+    fn append_commitment<T>(&self, commitment: &(G, G), transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        transcript.append_point(b"K_g", &commitment.0);
+        transcript.append_point(b"K_h", &commitment.1);
+    }
+}
+
+/// Proof of Knowledge of a Pedersen Opening
+///
+/// The statement is a commitment `C = v * g + r * h` and the witness is the opening `(v, r)`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PedersenOpening<G> {
+    /// Value Generator
+    pub g: G,
+
+    /// Blinding Generator
+    pub h: G,
+}
+
+impl<G> PedersenOpening<G> {
+    /// Builds a new [`PedersenOpening`] protocol over the value generator `g` and the blinding
+    /// generator `h`.
+    #[inline]
+    pub fn new(g: G, h: G) -> Self {
+        Self { g, h }
+    }
+
+    /// Returns the commitment `v * g + r * h` to `value` with `blinding` factor.
+    #[inline]
+    pub fn commitment<F>(&self, value: &F, blinding: &F) -> G
+    where
+        G: Group + ScalarMul<F, Output = G>,
+    {
+        self.g
+            .scalar_mul(value, &mut ())
+            .add(&self.h.scalar_mul(blinding, &mut ()), &mut ())
+    }
+}
+
+impl<G, F> Protocol<G, F> for PedersenOpening<G>
+where
+    G: Group + ScalarMul<F, Output = G> + PartialEq,
+    F: NativeField + Sample,
+{
+    type Statement = G;
+    type Witness = (F, F);
+    type Commitment = G;
+    type Nonce = (F, F);
+    type Response = (F, F);
+
+    #[inline]
+    fn commit<R>(&self, statement: &G, witness: &(F, F), rng: &mut R) -> (G, (F, F))
+    where
+        R: RngCore + ?Sized,
+    {
+        let _ = (statement, witness);
+        let nonce = (F::gen(rng), F::gen(rng));
+        (self.commitment(&nonce.0, &nonce.1), nonce)
+    }
+
+    #[inline]
+    fn respond(&self, statement: &G, witness: &(F, F), nonce: (F, F), challenge: &F) -> (F, F) {
+        let _ = statement;
+        (
+            nonce.0.add(&challenge.mul(&witness.0)),
+            nonce.1.add(&challenge.mul(&witness.1)),
+        )
+    }
+
+    #[inline]
+    fn check(&self, statement: &G, commitment: &G, challenge: &F, response: &(F, F)) -> bool {
+        self.commitment(&response.0, &response.1)
+            == commitment.add(&statement.scalar_mul(challenge, &mut ()), &mut ())
+    }
+
+    #[inline]
+    fn simulate<R>(&self, statement: &G, challenge: &F, rng: &mut R) -> (G, (F, F))
+    where
+        R: RngCore + ?Sized,
+    {
+        let response = (F::gen(rng), F::gen(rng));
+        (
+            self.commitment(&response.0, &response.1)
+                .add(&statement.scalar_mul(&negate(challenge), &mut ()), &mut ()),
+            response,
+        )
+    }
+
+    #[inline]
+    fn append_statement<T>(&self, statement: &G, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        transcript.append_point(b"g", &self.g);
+        transcript.append_point(b"h", &self.h);
+        transcript.append_point(b"C", statement);
+    }
+
+    #[inline]
+    fn append_commitment<T>(&self, commitment: &G, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        transcript.append_point(b"K", commitment);
+    }
+}
+
+/// Conjunction of Sigma Protocols
+///
+/// The prover knows witnesses for both statements, and both sub-protocols answer the same
+/// challenge.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct And<A, B>(
+    /// Left Protocol
+    pub A,
+    /// Right Protocol
+    pub B,
+);
+
+impl<A, B, G, F> Protocol<G, F> for And<A, B>
+where
+    A: Protocol<G, F>,
+    B: Protocol<G, F>,
+{
+    type Statement = (A::Statement, B::Statement);
+    type Witness = (A::Witness, B::Witness);
+    type Commitment = (A::Commitment, B::Commitment);
+    type Nonce = (A::Nonce, B::Nonce);
+    type Response = (A::Response, B::Response);
+
+    #[inline]
+    fn commit<R>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Nonce)
+    where
+        R: RngCore + ?Sized,
+    {
+        let (left_commitment, left_nonce) = self.0.commit(&statement.0, &witness.0, rng);
+        let (right_commitment, right_nonce) = self.1.commit(&statement.1, &witness.1, rng);
+        (
+            (left_commitment, right_commitment),
+            (left_nonce, right_nonce),
+        )
+    }
+
+    #[inline]
+    fn respond(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &F,
+    ) -> Self::Response {
+        (
+            self.0.respond(&statement.0, &witness.0, nonce.0, challenge),
+            self.1.respond(&statement.1, &witness.1, nonce.1, challenge),
+        )
+    }
+
+    #[inline]
+    fn check(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &F,
+        response: &Self::Response,
+    ) -> bool {
+        self.0
+            .check(&statement.0, &commitment.0, challenge, &response.0)
+            && self
+                .1
+                .check(&statement.1, &commitment.1, challenge, &response.1)
+    }
+
+    #[inline]
+    fn simulate<R>(
+        &self,
+        statement: &Self::Statement,
+        challenge: &F,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response)
+    where
+        R: RngCore + ?Sized,
+    {
+        let (left_commitment, left_response) = self.0.simulate(&statement.0, challenge, rng);
+        let (right_commitment, right_response) = self.1.simulate(&statement.1, challenge, rng);
+        (
+            (left_commitment, right_commitment),
+            (left_response, right_response),
+        )
+    }
+
+    #[inline]
+    fn append_statement<T>(&self, statement: &Self::Statement, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        self.0.append_statement(&statement.0, transcript);
+        self.1.append_statement(&statement.1, transcript);
+    }
+
+    #[inline]
+    fn append_commitment<T>(&self, commitment: &Self::Commitment, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        self.0.append_commitment(&commitment.0, transcript);
+        self.1.append_commitment(&commitment.1, transcript);
+    }
+}
+
+/// Witness Choice
+///
+/// This is the witness of an [`Or`] protocol, which is a witness for exactly one of its two
+/// statements.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Choice<L, R> {
+    /// Left Witness
+    Left(L),
+
+    /// Right Witness
+    Right(R),
+}
+
+/// Disjunction Response
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct OrResponse<F, L, R> {
+    /// Challenge of the Left Protocol
+    ///
+    /// The challenge of the right protocol is the difference between the overall challenge and
+    /// this challenge.
+    pub challenge: F,
+
+    /// Left Response
+    pub left: L,
+
+    /// Right Response
+    pub right: R,
+}
+
+/// Disjunction Nonce
+///
+/// The prover keeps the nonce of the protocol it knows a witness for and the simulated challenge
+/// and response of the other protocol.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(
+        bound = "A::Nonce: Clone, B::Nonce: Clone, A::Response: Clone, B::Response: Clone, F: Clone"
+    ),
+    Debug(
+        bound = "A::Nonce: Debug, B::Nonce: Debug, A::Response: Debug, B::Response: Debug, F: Debug"
+    ),
+    Eq(bound = "A::Nonce: Eq, B::Nonce: Eq, A::Response: Eq, B::Response: Eq, F: Eq"),
+    Hash(bound = "A::Nonce: Hash, B::Nonce: Hash, A::Response: Hash, B::Response: Hash, F: Hash"),
+    PartialEq(
+        bound = "A::Nonce: PartialEq, B::Nonce: PartialEq, A::Response: PartialEq, B::Response: PartialEq, F: PartialEq"
+    )
+)]
+pub struct OrNonce<A, B, G, F>
+where
+    A: Protocol<G, F>,
+    B: Protocol<G, F>,
+{
+    /// Nonce of the Known Side, and Simulated Challenge and Response of the Other Side
+    state: Choice<(A::Nonce, F, B::Response), (B::Nonce, F, A::Response)>,
+}
+
+/// Disjunction of Sigma Protocols
+///
+/// The prover knows a witness for one of the two statements without revealing which one. The
+/// other side is simulated with a challenge of the prover's choice, and the two challenges are
+/// constrained to add up to the challenge of the verifier.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Or<A, B>(
+    /// Left Protocol
+    pub A,
+    /// Right Protocol
+    pub B,
+);
+
+impl<A, B, G, F> Protocol<G, F> for Or<A, B>
+where
+    A: Protocol<G, F>,
+    B: Protocol<G, F>,
+    F: NativeField + Sample,
+{
+    type Statement = (A::Statement, B::Statement);
+    type Witness = Choice<A::Witness, B::Witness>;
+    type Commitment = (A::Commitment, B::Commitment);
+    type Nonce = OrNonce<A, B, G, F>;
+    type Response = OrResponse<F, A::Response, B::Response>;
+
+    #[inline]
+    fn commit<R>(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Nonce)
+    where
+        R: RngCore + ?Sized,
+    {
+        let challenge = F::gen(rng);
+        match witness {
+            Choice::Left(witness) => {
+                let (right_commitment, right_response) =
+                    self.1.simulate(&statement.1, &challenge, rng);
+                let (left_commitment, left_nonce) = self.0.commit(&statement.0, witness, rng);
+                (
+                    (left_commitment, right_commitment),
+                    OrNonce {
+                        state: Choice::Left((left_nonce, challenge, right_response)),
+                    },
+                )
+            }
+            Choice::Right(witness) => {
+                let (left_commitment, left_response) =
+                    self.0.simulate(&statement.0, &challenge, rng);
+                let (right_commitment, right_nonce) = self.1.commit(&statement.1, witness, rng);
+                (
+                    (left_commitment, right_commitment),
+                    OrNonce {
+                        state: Choice::Right((right_nonce, challenge, left_response)),
+                    },
+                )
+            }
+        }
+    }
+
+    /// # Panics
+    ///
+    /// This method panics if `nonce` was not produced by [`commit`](Self::commit) with a witness
+    /// on the same side as `witness`.
+    #[inline]
+    fn respond(
+        &self,
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &F,
+    ) -> Self::Response {
+        match (witness, nonce.state) {
+            (Choice::Left(witness), Choice::Left((nonce, right_challenge, right))) => {
+                let left_challenge = challenge.sub(&right_challenge);
+                OrResponse {
+                    left: self
+                        .0
+                        .respond(&statement.0, witness, nonce, &left_challenge),
+                    challenge: left_challenge,
+                    right,
+                }
+            }
+            (Choice::Right(witness), Choice::Right((nonce, left_challenge, left))) => {
+                let right_challenge = challenge.sub(&left_challenge);
+                OrResponse {
+                    challenge: left_challenge,
+                    left,
+                    right: self
+                        .1
+                        .respond(&statement.1, witness, nonce, &right_challenge),
+                }
+            }
+            _ => panic!("The nonce does not belong to the side of the witness."),
+        }
+    }
+
+    #[inline]
+    fn check(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &F,
+        response: &Self::Response,
+    ) -> bool {
+        self.0.check(
+            &statement.0,
+            &commitment.0,
+            &response.challenge,
+            &response.left,
+        ) && self.1.check(
+            &statement.1,
+            &commitment.1,
+            &challenge.sub(&response.challenge),
+            &response.right,
+        )
+    }
+
+    #[inline]
+    fn simulate<R>(
+        &self,
+        statement: &Self::Statement,
+        challenge: &F,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::Response)
+    where
+        R: RngCore + ?Sized,
+    {
+        let left_challenge = F::gen(rng);
+        let (left_commitment, left) = self.0.simulate(&statement.0, &left_challenge, rng);
+        let (right_commitment, right) =
+            self.1
+                .simulate(&statement.1, &challenge.sub(&left_challenge), rng);
+        (
+            (left_commitment, right_commitment),
+            OrResponse {
+                challenge: left_challenge,
+                left,
+                right,
+            },
+        )
+    }
+
+    #[inline]
+    fn append_statement<T>(&self, statement: &Self::Statement, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        self.0.append_statement(&statement.0, transcript);
+        self.1.append_statement(&statement.1, transcript);
+    }
+
+    #[inline]
+    fn append_commitment<T>(&self, commitment: &Self::Commitment, transcript: &mut T)
+    where
+        T: Transcript<G, F>,
+    {
+        self.0.append_commitment(&commitment.0, transcript);
+        self.1.append_commitment(&commitment.1, transcript);
+    }
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+
+    /// Asserts that a proof of `witness` for `statement` verifies, that a simulated transcript for
+    /// `statement` checks, and that the proof does not verify for `other` statement.
+    #[inline]
+    pub fn assert_valid_proof<P, G, F, T, R>(
+        protocol: &P,
+        statement: &P::Statement,
+        witness: &P::Witness,
+        other: &P::Statement,
+        mut transcript: impl FnMut() -> T,
+        rng: &mut R,
+    ) where
+        P: Protocol<G, F>,
+        F: Sample,
+        T: Transcript<G, F>,
+        R: RngCore + ?Sized,
+    {
+        let proof = protocol.prove(statement, witness, transcript(), rng);
+        assert!(
+            protocol.verify(statement, &proof, transcript()),
+            "Valid proof failed to verify."
+        );
+        assert!(
+            !protocol.verify(other, &proof, transcript()),
+            "Proof verified for a different statement."
+        );
+        let challenge = F::gen(rng);
+        let (commitment, response) = protocol.simulate(statement, &challenge, rng);
+        assert!(
+            protocol.check(statement, &commitment, &challenge, &response),
+            "Simulated transcript failed to check."
+        );
+    }
+}
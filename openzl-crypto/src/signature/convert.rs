@@ -215,3 +215,392 @@ where
         Self::new(rng.sample(distribution))
     }
 }
+
+/// BIP340-Style Schnorr Signatures
+///
+/// Bitcoin's [BIP340] Schnorr signatures identify public keys and nonce points by their
+/// x-coordinates only, and derive the challenge and the nonce with tagged SHA-256 hashes. The
+/// [`Bip340`] adapter produces and verifies signatures in that style on top of the generic
+/// [`Schnorr`](crate::signature::schnorr::Schnorr) scheme, by normalizing the signing key and the nonce so
+/// that their points have an even y-coordinate.
+///
+/// The curve is abstracted by the [`XOnly`] and [`ScalarBytes`] traits. This crate does not ship
+/// an implementation for secp256k1, and the adapter is only tested on Baby Jubjub, so it is not
+/// checked against the official BIP340 test vectors. Do not rely on it to interoperate with
+/// Bitcoin tooling.
+///
+/// [BIP340]: https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
+#[cfg(all(feature = "alloc", feature = "sha256"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", feature = "sha256"))))]
+pub mod bip340 {
+    use super::*;
+    use crate::{
+        algebra::{
+            security::DiscreteLogarithmHardness, MultiScalarMul, Ring, ScalarMul, ScalarMulGroup,
+        },
+        hash::security::PreimageResistance,
+        signature::schnorr::{self, Schnorr},
+    };
+    use alloc::vec::Vec;
+    use core::{fmt::Debug, hash::Hash};
+    use eclair::{cmp::PartialEq, ops::Neg};
+    use sha2::{Digest, Sha256};
+
+    /// Challenge Tag
+    pub const CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+
+    /// Auxiliary Randomness Tag
+    pub const AUX_TAG: &[u8] = b"BIP0340/aux";
+
+    /// Nonce Tag
+    pub const NONCE_TAG: &[u8] = b"BIP0340/nonce";
+
+    /// X-Only Point Encoding
+    pub trait XOnly: Sized {
+        /// Returns `true` if the y-coordinate of `self` is even.
+        fn has_even_y(&self) -> bool;
+
+        /// Returns the 32-byte big-endian encoding of the x-coordinate of `self`.
+        fn to_x_bytes(&self) -> [u8; 32];
+
+        /// Returns the point with x-coordinate `bytes` and an even y-coordinate, if it exists.
+        fn lift_x(bytes: &[u8; 32]) -> Option<Self>;
+    }
+
+    /// Scalar Byte Encoding
+    pub trait ScalarBytes: Sized {
+        /// Returns the scalar encoded by the big-endian `bytes`, reduced modulo the group order.
+        fn from_be_bytes_mod_order(bytes: &[u8; 32]) -> Self;
+
+        /// Returns the scalar encoded by the big-endian `bytes` if it is less than the group order.
+        fn from_canonical_be_bytes(bytes: &[u8; 32]) -> Option<Self>;
+
+        /// Returns the 32-byte big-endian encoding of `self`.
+        fn to_be_bytes(&self) -> [u8; 32];
+    }
+
+    /// Computes the tagged hash `SHA-256(SHA-256(tag) || SHA-256(tag) || chunks)`.
+    #[inline]
+    pub fn tagged_hash<'c, I>(tag: &[u8], chunks: I) -> [u8; 32]
+    where
+        I: IntoIterator<Item = &'c [u8]>,
+    {
+        let tag = Sha256::digest(tag);
+        let mut hasher = Sha256::new().chain_update(tag).chain_update(tag);
+        for chunk in chunks {
+            hasher.update(chunk);
+        }
+        hasher.finalize().into()
+    }
+
+    /// Challenge Hash Function
+    ///
+    /// The [`schnorr::HashFunction`] which computes the BIP340-style challenge
+    /// `int(tagged_hash("BIP0340/challenge", x(R) || x(P) || m)) mod n`.
+    #[derive(derivative::Derivative)]
+    #[derivative(
+        Clone(bound = ""),
+        Copy(bound = ""),
+        Debug(bound = ""),
+        Default(bound = ""),
+        Eq(bound = ""),
+        Hash(bound = ""),
+        Ord(bound = ""),
+        PartialEq(bound = ""),
+        PartialOrd(bound = "")
+    )]
+    pub struct ChallengeHash<S, G>(PhantomData<(S, G)>);
+
+    impl<S, G> PreimageResistance for ChallengeHash<S, G> {}
+
+    impl<S, G> schnorr::HashFunction for ChallengeHash<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        type Scalar = S;
+        type Group = G;
+        type Message = Vec<u8>;
+
+        #[inline]
+        fn hash(
+            &self,
+            verifying_key: &Self::Group,
+            nonce_point: &Self::Group,
+            message: &Self::Message,
+            compiler: &mut (),
+        ) -> Self::Scalar {
+            let _ = compiler;
+            S::from_be_bytes_mod_order(&tagged_hash(
+                CHALLENGE_TAG,
+                [
+                    &nonce_point.to_x_bytes()[..],
+                    &verifying_key.to_x_bytes()[..],
+                    &message[..],
+                ],
+            ))
+        }
+    }
+
+    /// BIP340-Style Signature Scheme
+    ///
+    /// Verifying keys are the 32-byte x-only encodings of public keys and signatures are the
+    /// 64-byte encodings `x(R) || s`. The randomness of a signature is the 32-byte auxiliary
+    /// randomness of BIP340, which may be all zeroes for deterministic signatures.
+    ///
+    /// See the [module-level documentation](self) for the limits of the compatibility with BIP340.
+    #[derive(derivative::Derivative)]
+    #[derivative(
+        Clone(bound = "G: Clone"),
+        Debug(bound = "G: Debug"),
+        Eq(bound = "G: Eq"),
+        Hash(bound = "G: Hash"),
+        PartialEq(bound = "G: core::cmp::PartialEq")
+    )]
+    pub struct Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        /// Underlying Schnorr Signature Scheme
+        pub schnorr: Schnorr<ChallengeHash<S, G>>,
+    }
+
+    impl<S, G> Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        /// Builds a new [`Bip340`] signature scheme over the group `generator`.
+        #[inline]
+        pub fn new(generator: G) -> Self {
+            Self {
+                schnorr: Schnorr::new(ChallengeHash(PhantomData), generator),
+            }
+        }
+
+        /// Returns `scalar` or its negation, whichever multiplies the generator into a point with
+        /// an even y-coordinate, together with that point.
+        #[inline]
+        fn normalize(&self, scalar: &S) -> (S, G)
+        where
+            S: Clone + Neg<Output = S>,
+            G: Neg<Output = G>,
+        {
+            let point = self.schnorr.generator.scalar_mul(scalar, &mut ());
+            if point.has_even_y() {
+                (scalar.clone(), point)
+            } else {
+                (scalar.clone().neg(&mut ()), point.neg(&mut ()))
+            }
+        }
+    }
+
+    impl<S, G> SigningKeyType for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        type SigningKey = S;
+    }
+
+    impl<S, G> VerifyingKeyType for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        type VerifyingKey = [u8; 32];
+    }
+
+    impl<S, G> MessageType for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        type Message = Vec<u8>;
+    }
+
+    impl<S, G> SignatureType for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        type Signature = [u8; 64];
+    }
+
+    impl<S, G> RandomnessType for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        type Randomness = [u8; 32];
+    }
+
+    impl<S, G> Derive for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + XOnly,
+    {
+        #[inline]
+        fn derive(&self, signing_key: &Self::SigningKey, compiler: &mut ()) -> Self::VerifyingKey {
+            self.schnorr.derive(signing_key, compiler).to_x_bytes()
+        }
+    }
+
+    impl<S, G> Sign for Bip340<S, G>
+    where
+        S: Clone + Ring + Neg<Output = S> + ScalarBytes,
+        G: ScalarMulGroup<S, Output = G> + DiscreteLogarithmHardness + Neg<Output = G> + XOnly,
+    {
+        /// Signs `message` following the steps of the BIP340 signing algorithm, deriving the nonce
+        /// from the auxiliary `randomness`.
+        ///
+        /// # Panics
+        ///
+        /// This method panics if the signing key or the derived nonce is zero, which happens with
+        /// negligible probability for uniformly random signing keys.
+        #[inline]
+        fn sign(
+            &self,
+            signing_key: &Self::SigningKey,
+            randomness: &Self::Randomness,
+            message: &Self::Message,
+            compiler: &mut (),
+        ) -> Self::Signature {
+            let (signing_key, verifying_key) = self.normalize(signing_key);
+            let zero = [0; 32];
+            let mut masked_key = signing_key.to_be_bytes();
+            assert_ne!(masked_key, zero, "The signing key must not be zero.");
+            let mask = tagged_hash(AUX_TAG, [&randomness[..]]);
+            for (byte, mask) in masked_key.iter_mut().zip(mask) {
+                *byte ^= mask;
+            }
+            let nonce = S::from_be_bytes_mod_order(&tagged_hash(
+                NONCE_TAG,
+                [
+                    &masked_key[..],
+                    &verifying_key.to_x_bytes()[..],
+                    &message[..],
+                ],
+            ));
+            assert_ne!(nonce.to_be_bytes(), zero, "The nonce must not be zero.");
+            let (nonce, _) = self.normalize(&nonce);
+            let schnorr::Signature {
+                scalar,
+                nonce_point,
+            } = self.schnorr.sign(&signing_key, &nonce, message, compiler);
+            let mut signature = [0; 64];
+            signature[..32].copy_from_slice(&nonce_point.to_x_bytes());
+            signature[32..].copy_from_slice(&scalar.to_be_bytes());
+            signature
+        }
+    }
+
+    impl<S, G> Verify for Bip340<S, G>
+    where
+        S: Ring + ScalarBytes,
+        G: Clone
+            + ScalarMulGroup<S, Output = G>
+            + DiscreteLogarithmHardness
+            + MultiScalarMul<S>
+            + Neg<Output = G>
+            + PartialEq<G>
+            + XOnly,
+    {
+        type Verification = bool;
+
+        /// Verifies `signature` over `message` following the steps of the BIP340 verification
+        /// algorithm. The point `R` is lifted from its x-coordinate with an even y-coordinate, so
+        /// checking that `s * G - e * P` equals it is equivalent to checking its x-coordinate and
+        /// the parity of its y-coordinate.
+        #[inline]
+        fn verify(
+            &self,
+            verifying_key: &Self::VerifyingKey,
+            message: &Self::Message,
+            signature: &Self::Signature,
+            compiler: &mut (),
+        ) -> Self::Verification {
+            let mut nonce_point = [0; 32];
+            let mut scalar = [0; 32];
+            nonce_point.copy_from_slice(&signature[..32]);
+            scalar.copy_from_slice(&signature[32..]);
+            match (
+                G::lift_x(verifying_key),
+                G::lift_x(&nonce_point),
+                S::from_canonical_be_bytes(&scalar),
+            ) {
+                (Some(verifying_key), Some(nonce_point), Some(scalar)) => self.schnorr.verify(
+                    &verifying_key,
+                    message,
+                    &schnorr::Signature {
+                        scalar,
+                        nonce_point,
+                    },
+                    compiler,
+                ),
+                _ => false,
+            }
+        }
+    }
+
+    /// Testing Suite
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        /// Decodes the 64-digit hexadecimal string `hex` into 32 bytes.
+        #[inline]
+        fn decode(hex: &str) -> [u8; 32] {
+            let mut bytes = [0; 32];
+            for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+                *byte = u8::from_str_radix(
+                    core::str::from_utf8(digits).expect("Hexadecimal digits are ASCII."),
+                    16,
+                )
+                .expect("The digits must be hexadecimal.");
+            }
+            bytes
+        }
+
+        /// Checks that [`tagged_hash`] matches reference digests computed independently as
+        /// `SHA-256(SHA-256(tag) || SHA-256(tag) || message)`.
+        #[test]
+        fn tagged_hash_matches_test_vectors() {
+            let vectors: [(&[u8], &[u8], &str); 4] = [
+                (
+                    CHALLENGE_TAG,
+                    b"",
+                    "c216d352f5818b7b4beacd4ae0a26fe888080823d2a598856661bcd54f1b3713",
+                ),
+                (
+                    AUX_TAG,
+                    &[0; 32],
+                    "54f169cfc9e2e5727480441f90ba25c488f461c70b5ea5dcaaf7af69270aa514",
+                ),
+                (
+                    NONCE_TAG,
+                    b"openzl",
+                    "17051327c552ea82caed49362b19cd7a14c720f297370d5e89cb6f35bbb79ddb",
+                ),
+                (
+                    b"TapLeaf",
+                    &[0xc0, 0x01, 0x51],
+                    "a85b2107f791b26a84e7586c28cec7cb61202ed3d01944d832500f363782d675",
+                ),
+            ];
+            for (tag, message, digest) in vectors {
+                assert_eq!(
+                    tagged_hash(tag, [message]),
+                    decode(digest),
+                    "The tagged hash must match the test vector."
+                );
+            }
+            assert_eq!(
+                tagged_hash(NONCE_TAG, [&b"open"[..], &b""[..], &b"zl"[..]]),
+                tagged_hash(NONCE_TAG, [&b"openzl"[..]]),
+                "The tagged hash must only depend on the concatenation of the chunks."
+            );
+        }
+    }
+}
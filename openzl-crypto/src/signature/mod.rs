@@ -16,16 +16,32 @@
 //! }
 //! ```
 //!
-//! See the [`correctness`](test::correctness) test for more.
+//! See the [`correctness`](test::correctness) test for more. For `n`-of-`n` Schnorr
+//! multi-signatures see the [`multisig`] module. Signatures which are checked in bulk, like the
+//! transactions of a block, can be verified together with [`BatchVerify`].
 
 use crate::component;
 use core::{fmt::Debug, hash::Hash};
-use openzl_util::derivative;
+use openzl_util::{derivative, rand::RngCore};
+
+#[cfg(feature = "alloc")]
+use openzl_util::vec::Vec;
+
+#[cfg(feature = "prototyping")]
+use {
+    crate::Prototype,
+    eclair::{
+        alloc::{Allocate, Constant},
+        bool::Bool,
+        Has,
+    },
+};
 
 #[cfg(feature = "serde")]
 use openzl_util::serde::{Deserialize, Serialize};
 
 pub mod convert;
+pub mod multisig;
 
 /// Signing Key
 #[component]
@@ -192,21 +208,236 @@ where
     }
 }
 
+/// Batch Signature Verification
+///
+/// Checks many signatures at once, which is usually much cheaper than checking them one by one.
+/// A failed batch only reports that some signature is invalid. Use [`verify_batch`] to find the
+/// invalid signatures of a batch.
+///
+/// # Crypto Safety
+///
+/// Implementations can only accept a batch with negligible probability if some signature in it is
+/// invalid. Randomized implementations must sample their randomness from `rng` and must accept
+/// every batch of valid signatures.
+pub trait BatchVerify: Verify {
+    /// Returns `true` if every signature in `batch` verifies against its verifying key and
+    /// message.
+    fn batch_verify<R>(
+        &self,
+        batch: &[(&Self::VerifyingKey, &Self::Message, &Self::Signature)],
+        rng: &mut R,
+    ) -> bool
+    where
+        R: RngCore + ?Sized;
+}
+
+impl<V> BatchVerify for &V
+where
+    V: BatchVerify,
+{
+    #[inline]
+    fn batch_verify<R>(
+        &self,
+        batch: &[(&Self::VerifyingKey, &Self::Message, &Self::Signature)],
+        rng: &mut R,
+    ) -> bool
+    where
+        R: RngCore + ?Sized,
+    {
+        (*self).batch_verify(batch, rng)
+    }
+}
+
+/// Verifies every signature in `batch` with [`BatchVerify::batch_verify`], falling back to
+/// verifying every signature on its own if the batch fails, and returning the indices of the
+/// invalid signatures in that case.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn verify_batch<S, R>(
+    scheme: &S,
+    batch: &[(&S::VerifyingKey, &S::Message, &S::Signature)],
+    rng: &mut R,
+) -> Result<(), Vec<usize>>
+where
+    S: BatchVerify + Verify<Verification = bool>,
+    R: RngCore + ?Sized,
+{
+    if scheme.batch_verify(batch, rng) {
+        return Ok(());
+    }
+    Err(batch
+        .iter()
+        .enumerate()
+        .filter(|(_, (verifying_key, message, signature))| {
+            !scheme.verify(verifying_key, message, signature, &mut ())
+        })
+        .map(|(index, _)| index)
+        .collect())
+}
+
+/// Unsafe Assume-Valid Signature Scheme
+///
+/// # Crypto Safety
+///
+/// This scheme derives keys and signs messages with `S` but accepts every signature without
+/// checking it, both natively and in-circuit. It can stand in for `S` while prototyping the rest
+/// of a protocol, but it must never be used in production. See [`Prototype`] for more.
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UnsafeAssumeValid<S>(
+    /// Underlying Signature Scheme
+    pub S,
+);
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S> Prototype for UnsafeAssumeValid<S> {
+    const WARNING: &'static str = "signatures are assumed to be valid without being verified";
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S, COM> Constant<COM> for UnsafeAssumeValid<S>
+where
+    S: Constant<COM>,
+{
+    type Type = UnsafeAssumeValid<S::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S> SigningKeyType for UnsafeAssumeValid<S>
+where
+    S: SigningKeyType,
+{
+    type SigningKey = S::SigningKey;
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S> VerifyingKeyType for UnsafeAssumeValid<S>
+where
+    S: VerifyingKeyType,
+{
+    type VerifyingKey = S::VerifyingKey;
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S> MessageType for UnsafeAssumeValid<S>
+where
+    S: MessageType,
+{
+    type Message = S::Message;
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S> SignatureType for UnsafeAssumeValid<S>
+where
+    S: SignatureType,
+{
+    type Signature = S::Signature;
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S> RandomnessType for UnsafeAssumeValid<S>
+where
+    S: RandomnessType,
+{
+    type Randomness = S::Randomness;
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S, COM> Derive<COM> for UnsafeAssumeValid<S>
+where
+    S: Derive<COM>,
+{
+    #[inline]
+    fn derive(&self, signing_key: &Self::SigningKey, compiler: &mut COM) -> Self::VerifyingKey {
+        self.0.derive(signing_key, compiler)
+    }
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S, COM> Sign<COM> for UnsafeAssumeValid<S>
+where
+    S: Sign<COM>,
+{
+    #[inline]
+    fn sign(
+        &self,
+        signing_key: &Self::SigningKey,
+        randomness: &Self::Randomness,
+        message: &Self::Message,
+        compiler: &mut COM,
+    ) -> Self::Signature {
+        self.0.sign(signing_key, randomness, message, compiler)
+    }
+}
+
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+impl<S, COM> Verify<COM> for UnsafeAssumeValid<S>
+where
+    S: MessageType + SignatureType + VerifyingKeyType,
+    COM: Has<bool>,
+    Bool<COM>: Constant<COM, Type = bool>,
+{
+    type Verification = Bool<COM>;
+
+    #[inline]
+    fn verify(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        message: &Self::Message,
+        signature: &Self::Signature,
+        compiler: &mut COM,
+    ) -> Self::Verification {
+        let _ = (verifying_key, message, signature);
+        Bool::<COM>::new_constant(&true, compiler)
+    }
+}
+
+/// Wraps `scheme` into an [`UnsafeAssumeValid`] scheme which accepts every signature.
+///
+/// # Crypto Safety
+///
+/// See [`UnsafeAssumeValid`] for more.
+#[cfg(feature = "prototyping")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "prototyping")))]
+#[inline]
+pub fn unsafe_assume_valid<S>(scheme: S) -> UnsafeAssumeValid<S> {
+    UnsafeAssumeValid(scheme)
+}
+
 /// Schnorr Signatures
 pub mod schnorr {
     use super::*;
     use crate::{
         algebra::{
-            security::DiscreteLogarithmHardness, Group as _, HasGenerator, Ring, ScalarMul,
-            ScalarMulGroup,
+            security::DiscreteLogarithmHardness, Group as _, HasGenerator, MultiScalarMul, Ring,
+            ScalarMul, ScalarMulGroup,
         },
-        hash::security::PreimageResistance,
+        hash::{security::PreimageResistance, ArrayHashFunction},
+        security::{self, SecurityLevel},
     };
-    use core::{cmp, fmt::Debug, hash::Hash, marker::PhantomData};
+    use core::{cmp, fmt::Debug, hash::Hash, iter, marker::PhantomData};
     use eclair::{
-        alloc::{Const, Constant},
+        alloc::{Allocate, Allocator, Const, Constant, Variable},
         bool::Bool,
         cmp::PartialEq,
+        ops::Neg,
         Has,
     };
     use openzl_util::rand::{Rand, RngCore, Sample};
@@ -243,6 +474,142 @@ pub mod schnorr {
     /// Message Type
     pub type Message<H, COM = ()> = <H as HashFunction<COM>>::Message;
 
+    /// Challenge Encoding
+    ///
+    /// Makes explicit how the inputs of the Schnorr challenge are encoded into the input type `I`
+    /// of an [`ArrayHashFunction`] and how its output type `O` is reduced to a scalar. Any
+    /// domain separation between the challenge and other uses of the same hash function should
+    /// also be applied by the encoding. See [`ArrayHashAdapter`] for more.
+    pub trait ChallengeEncoding<I, O, COM = ()> {
+        /// Scalar Type
+        type Scalar: Ring<COM>;
+
+        /// Group Type
+        type Group: ScalarMulGroup<Self::Scalar, COM, Output = Self::Group>
+            + DiscreteLogarithmHardness;
+
+        /// Message Type
+        type Message;
+
+        /// Encodes `point` as an input to the hash function.
+        fn encode_group(&self, point: &Self::Group, compiler: &mut COM) -> I;
+
+        /// Encodes `message` as an input to the hash function.
+        fn encode_message(&self, message: &Self::Message, compiler: &mut COM) -> I;
+
+        /// Reduces the hash function `output` to a scalar.
+        fn decode_scalar(&self, output: O, compiler: &mut COM) -> Self::Scalar;
+    }
+
+    /// Array Hash Adapter
+    ///
+    /// Implements [`HashFunction`] for any [`ArrayHashFunction`] of arity three with a
+    /// [`ChallengeEncoding`] between the Schnorr types and the hash function domain. The challenge
+    /// is computed as
+    ///
+    /// ```text
+    /// decode_scalar(H([
+    ///     encode_group(verifying_key),
+    ///     encode_group(nonce_point),
+    ///     encode_message(message),
+    /// ]))
+    /// ```
+    #[derive(derivative::Derivative)]
+    #[derivative(
+        Clone(bound = "H: Clone, E: Clone"),
+        Copy(bound = "H: Copy, E: Copy"),
+        Debug(bound = "H: Debug, E: Debug"),
+        Default(bound = "H: Default, E: Default"),
+        Eq(bound = "H: Eq, E: Eq"),
+        Hash(bound = "H: Hash, E: Hash"),
+        PartialEq(bound = "H: cmp::PartialEq, E: cmp::PartialEq")
+    )]
+    pub struct ArrayHashAdapter<H, E> {
+        /// Hash Function
+        pub hash_function: H,
+
+        /// Challenge Encoding
+        pub encoding: E,
+    }
+
+    impl<H, E> ArrayHashAdapter<H, E> {
+        /// Builds a new [`ArrayHashAdapter`] over `hash_function` and `encoding`.
+        #[inline]
+        pub fn new(hash_function: H, encoding: E) -> Self {
+            Self {
+                hash_function,
+                encoding,
+            }
+        }
+    }
+
+    impl<H, E> PreimageResistance for ArrayHashAdapter<H, E> where H: PreimageResistance {}
+
+    impl<H, E> SecurityLevel for ArrayHashAdapter<H, E>
+    where
+        H: SecurityLevel,
+    {
+        const SECURITY_LEVEL: u32 = H::SECURITY_LEVEL;
+    }
+
+    impl<H, E, COM> HashFunction<COM> for ArrayHashAdapter<H, E>
+    where
+        H: ArrayHashFunction<3, COM> + PreimageResistance,
+        H::Input: Sized,
+        E: ChallengeEncoding<H::Input, H::Output, COM>,
+    {
+        type Scalar = E::Scalar;
+        type Group = E::Group;
+        type Message = E::Message;
+
+        #[inline]
+        fn hash(
+            &self,
+            verifying_key: &Self::Group,
+            nonce_point: &Self::Group,
+            message: &Self::Message,
+            compiler: &mut COM,
+        ) -> Self::Scalar {
+            let verifying_key = self.encoding.encode_group(verifying_key, compiler);
+            let nonce_point = self.encoding.encode_group(nonce_point, compiler);
+            let message = self.encoding.encode_message(message, compiler);
+            let output = self
+                .hash_function
+                .hash([&verifying_key, &nonce_point, &message], compiler);
+            self.encoding.decode_scalar(output, compiler)
+        }
+    }
+
+    impl<H, E, COM> Constant<COM> for ArrayHashAdapter<H, E>
+    where
+        H: Constant<COM>,
+        E: Constant<COM>,
+    {
+        type Type = ArrayHashAdapter<H::Type, E::Type>;
+
+        #[inline]
+        fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+            Self::new(
+                H::new_constant(&this.hash_function, compiler),
+                E::new_constant(&this.encoding, compiler),
+            )
+        }
+    }
+
+    impl<H, E, DH, DE> Sample<(DH, DE)> for ArrayHashAdapter<H, E>
+    where
+        H: Sample<DH>,
+        E: Sample<DE>,
+    {
+        #[inline]
+        fn sample<R>(distribution: (DH, DE), rng: &mut R) -> Self
+        where
+            R: RngCore + ?Sized,
+        {
+            Self::new(rng.sample(distribution.0), rng.sample(distribution.1))
+        }
+    }
+
     /// Schnorr Signature
     #[derive(derivative::Derivative)]
     #[derivative(
@@ -266,6 +633,30 @@ pub mod schnorr {
         pub nonce_point: G,
     }
 
+    impl<S, G, M, COM> Variable<M, COM> for Signature<S, G>
+    where
+        S: Variable<M, COM>,
+        G: Variable<M, COM>,
+    {
+        type Type = Signature<S::Type, G::Type>;
+
+        #[inline]
+        fn new_unknown(compiler: &mut COM) -> Self {
+            Self {
+                scalar: compiler.allocate_unknown(),
+                nonce_point: compiler.allocate_unknown(),
+            }
+        }
+
+        #[inline]
+        fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+            Self {
+                scalar: this.scalar.as_known(compiler),
+                nonce_point: this.nonce_point.as_known(compiler),
+            }
+        }
+    }
+
     /// Schnorr Signature Scheme
     #[derive(derivative::Derivative)]
     #[derivative(
@@ -331,6 +722,17 @@ pub mod schnorr {
         }
     }
 
+    impl<H, COM> SecurityLevel for Schnorr<H, COM>
+    where
+        H: HashFunction<COM> + SecurityLevel,
+        H::Group: SecurityLevel,
+    {
+        const SECURITY_LEVEL: u32 = security::min(
+            H::SECURITY_LEVEL,
+            <H::Group as SecurityLevel>::SECURITY_LEVEL,
+        );
+    }
+
     impl<H, COM> SigningKeyType for Schnorr<H, COM>
     where
         H: HashFunction<COM>,
@@ -411,7 +813,10 @@ pub mod schnorr {
     where
         COM: Has<bool>,
         H: HashFunction<COM>,
-        H::Group: PartialEq<H::Group, COM>,
+        H::Group: Clone
+            + MultiScalarMul<H::Scalar, COM>
+            + Neg<COM, Output = H::Group>
+            + PartialEq<H::Group, COM>,
     {
         type Verification = Bool<COM>;
 
@@ -427,18 +832,73 @@ pub mod schnorr {
                 scalar,
                 nonce_point,
             } = signature;
-            self.generator.scalar_mul(scalar, compiler).eq(
-                &nonce_point.add(
-                    &verifying_key.scalar_mul(
-                        &self
-                            .hash_function
-                            .hash(verifying_key, nonce_point, message, compiler),
-                        compiler,
-                    ),
-                    compiler,
-                ),
+            let challenge = self
+                .hash_function
+                .hash(verifying_key, nonce_point, message, compiler);
+            let negated_key = verifying_key.clone().neg(compiler);
+            H::Group::multi_scalar_mul(
+                &[(&self.generator, scalar), (&negated_key, &challenge)],
                 compiler,
             )
+            .eq(nonce_point, compiler)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    impl<H> BatchVerify for Schnorr<H>
+    where
+        H: HashFunction,
+        H::Scalar: Clone + Sample,
+        H::Group: Clone + MultiScalarMul<H::Scalar> + Neg<Output = H::Group> + PartialEq<H::Group>,
+    {
+        /// Checks the sum of the verification equations of `batch`, weighted by random scalars
+        /// sampled from `rng`, with a single [`MultiScalarMul`] over `2n - 1` bases for a batch of
+        /// `n` signatures. The weight of the first equation is one, which keeps its nonce point on
+        /// the right-hand side of the combined equation.
+        #[inline]
+        fn batch_verify<R>(
+            &self,
+            batch: &[(&Self::VerifyingKey, &Self::Message, &Self::Signature)],
+            rng: &mut R,
+        ) -> bool
+        where
+            R: RngCore + ?Sized,
+        {
+            let ((first_key, first_message, first_signature), rest) = match batch.split_first() {
+                Some(split) => split,
+                _ => return true,
+            };
+            let compiler = &mut ();
+            let mut generator_scalar = first_signature.scalar.clone();
+            let mut bases = Vec::with_capacity(2 * batch.len() - 1);
+            let mut scalars = Vec::with_capacity(2 * batch.len() - 1);
+            bases.push((*first_key).clone().neg(compiler));
+            scalars.push(self.hash_function.hash(
+                first_key,
+                &first_signature.nonce_point,
+                first_message,
+                compiler,
+            ));
+            for (verifying_key, message, signature) in rest {
+                let weight = H::Scalar::gen(rng);
+                let challenge = self.hash_function.hash(
+                    verifying_key,
+                    &signature.nonce_point,
+                    message,
+                    compiler,
+                );
+                generator_scalar =
+                    generator_scalar.add(&weight.mul(&signature.scalar, compiler), compiler);
+                bases.push((*verifying_key).clone().neg(compiler));
+                scalars.push(weight.mul(&challenge, compiler));
+                bases.push(signature.nonce_point.clone().neg(compiler));
+                scalars.push(weight);
+            }
+            let terms = iter::once((&self.generator, &generator_scalar))
+                .chain(bases.iter().zip(&scalars))
+                .collect::<Vec<_>>();
+            H::Group::multi_scalar_mul(&terms, compiler).eq(&first_signature.nonce_point, compiler)
         }
     }
 
@@ -447,7 +907,6 @@ pub mod schnorr {
         H: Constant<COM> + HashFunction<COM>,
         H::Type: HashFunction<Group = Const<H::Group, COM>>,
         H::Group: Constant<COM>,
-        Const<H::Group, COM>: ScalarMulGroup<H::Scalar> + DiscreteLogarithmHardness,
     {
         type Type = Schnorr<H::Type>;
 
@@ -466,6 +925,9 @@ pub mod schnorr {
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test {
     use super::*;
+    use alloc::format;
+    use eclair::bool::{AssertLabeled, Bool};
+    use openzl_util::vec::Vec;
 
     /// Verifies that `scheme` produces self-consistent results on the given `signing_key`,
     /// `randomness`, and `message`.
@@ -487,4 +949,75 @@ pub mod test {
             compiler,
         )
     }
+
+    /// Runs the [`MuSig2`](multisig::MuSig2) protocol for `signing_keys` and `nonces` over
+    /// `message`, asserting that every partial signature is valid, and then verifies the aggregate
+    /// signature against the aggregate key.
+    #[inline]
+    pub fn multisig_correctness<H, COM>(
+        scheme: &multisig::MuSig2<H, COM>,
+        signing_keys: &[H::Scalar],
+        nonces: Vec<multisig::SecretNonce<H::Scalar>>,
+        message: &H::Message,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        COM: AssertLabeled,
+        H: multisig::HashFunction<COM>,
+        H::Scalar: Clone,
+        H::Group: Clone
+            + crate::algebra::MultiScalarMul<H::Scalar, COM>
+            + eclair::ops::Neg<COM, Output = H::Group>
+            + eclair::cmp::PartialEq<H::Group, COM>,
+    {
+        assert_eq!(
+            signing_keys.len(),
+            nonces.len(),
+            "Every signer must provide exactly one nonce."
+        );
+        let keys = signing_keys
+            .iter()
+            .map(|signing_key| scheme.schnorr.derive(signing_key, compiler))
+            .collect::<Vec<_>>();
+        let commitments = nonces
+            .iter()
+            .map(|nonce| scheme.commit_nonce(nonce, compiler))
+            .collect::<Vec<_>>();
+        let aggregate_nonce = scheme
+            .aggregate_nonces(&commitments, compiler)
+            .expect("The set of signers should not be empty.");
+        let session = scheme
+            .session(&keys, &aggregate_nonce, message, compiler)
+            .expect("The set of signers should not be empty.");
+        let partial_signatures = signing_keys
+            .iter()
+            .zip(nonces)
+            .map(|(signing_key, nonce)| {
+                scheme.partial_sign(&keys, &session, signing_key, nonce, compiler)
+            })
+            .collect::<Vec<_>>();
+        for (signer, ((key, commitment), partial_signature)) in keys
+            .iter()
+            .zip(&commitments)
+            .zip(&partial_signatures)
+            .enumerate()
+        {
+            let is_valid = scheme.verify_partial(
+                &keys,
+                &session,
+                key,
+                commitment,
+                partial_signature,
+                compiler,
+            );
+            compiler.assert_labeled(
+                &is_valid,
+                &format!("partial signature of signer {signer} is valid"),
+            );
+        }
+        let signature = scheme
+            .aggregate(&session, &partial_signatures, compiler)
+            .expect("The set of signers should not be empty.");
+        scheme.verify(&session.aggregate_key, message, &signature, compiler)
+    }
 }
@@ -0,0 +1,458 @@
+//! MuSig2 Multi-Signatures
+//!
+//! This module implements the [MuSig2] `n`-of-`n` multi-signature protocol on top of the
+//! [`Schnorr`] signature scheme. The protocol has the following steps:
+//!
+//! 1. Every signer publishes their verifying key and the signers compute the aggregate key with
+//!    [`MuSig2::aggregate_keys`].
+//! 2. Every signer samples a [`SecretNonce`] and broadcasts its [`NonceCommitment`] from
+//!    [`MuSig2::commit_nonce`]. This round does not depend on the message and can be performed
+//!    ahead of time.
+//! 3. Once all of the commitments are known, every signer builds the same [`Session`] with
+//!    [`MuSig2::session`] and computes a [`PartialSignature`] with [`MuSig2::partial_sign`].
+//! 4. The partial signatures are checked with [`MuSig2::verify_partial`] and combined with
+//!    [`MuSig2::aggregate`] into a standard [`Signature`] which verifies against the aggregate key
+//!    with the [`Verify`] implementation of [`Schnorr`], natively or in-circuit.
+//!
+//! # Nonce Reuse
+//!
+//! A [`SecretNonce`] must never be used in more than one signing session, otherwise the signing
+//! key can be recovered from the partial signatures. For this reason, [`SecretNonce`] does not
+//! implement [`Clone`] or any serialization traits, and is consumed by [`MuSig2::partial_sign`].
+//! A nonce which could be written to storage could also be restored into a second session.
+//!
+//! [MuSig2]: https://eprint.iacr.org/2020/1261
+
+use crate::{
+    algebra::{Group, MultiScalarMul, Ring, ScalarMul},
+    signature::{
+        schnorr::{self, Schnorr, Signature},
+        Derive, Verify,
+    },
+};
+use core::{cmp, fmt::Debug, hash::Hash};
+use eclair::{bool::Bool, cmp::PartialEq, ops::Neg, Has};
+use openzl_util::{
+    derivative,
+    rand::{Rand, RngCore, Sample},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// MuSig2 Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// Empty Key Set
+    ///
+    /// Keys can only be aggregated for a non-empty set of signers.
+    EmptyKeySet,
+
+    /// Empty Nonce Set
+    ///
+    /// Nonce commitments can only be aggregated for a non-empty set of signers.
+    EmptyNonceSet,
+
+    /// Empty Partial Signature Set
+    ///
+    /// Partial signatures can only be aggregated for a non-empty set of signers.
+    EmptyPartialSignatureSet,
+}
+
+/// MuSig2 Hash Function
+///
+/// In addition to the Schnorr challenge hash, MuSig2 requires two extra hash functions: one to
+/// compute the key aggregation coefficients and one to compute the nonce coefficient. All three
+/// must be domain-separated from each other.
+pub trait HashFunction<COM = ()>: schnorr::HashFunction<COM> {
+    /// Computes the key aggregation coefficient of `key` with respect to the full set of signer
+    /// `keys`.
+    fn key_coefficient(
+        &self,
+        keys: &[Self::Group],
+        key: &Self::Group,
+        compiler: &mut COM,
+    ) -> Self::Scalar;
+
+    /// Computes the nonce coefficient from the `aggregate_key`, the aggregate `nonce`
+    /// commitment, and the `message`.
+    fn nonce_coefficient(
+        &self,
+        aggregate_key: &Self::Group,
+        nonce: &NonceCommitment<Self::Group>,
+        message: &Self::Message,
+        compiler: &mut COM,
+    ) -> Self::Scalar;
+}
+
+/// Secret Nonce
+///
+/// This is the private half of a signer's contribution to the first round of the protocol. See
+/// the [module-level documentation](self) for the restrictions on its use.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = "S: Debug"))]
+pub struct SecretNonce<S> {
+    /// First Nonce Scalar
+    first: S,
+
+    /// Second Nonce Scalar
+    second: S,
+}
+
+impl<S> SecretNonce<S> {
+    /// Builds a new [`SecretNonce`] from its `first` and `second` scalars.
+    #[inline]
+    pub fn new(first: S, second: S) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<S, D> Sample<D> for SecretNonce<S>
+where
+    S: Sample<D>,
+    D: Clone,
+{
+    #[inline]
+    fn sample<R>(distribution: D, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution.clone()), rng.sample(distribution))
+    }
+}
+
+/// Nonce Commitment
+///
+/// This is the public half of a signer's contribution to the first round of the protocol, or the
+/// sum of all of the signers' contributions after calling [`MuSig2::aggregate_nonces`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Copy(bound = "G: Copy"),
+    Debug(bound = "G: Debug"),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: cmp::PartialEq")
+)]
+pub struct NonceCommitment<G> {
+    /// First Nonce Point
+    pub first: G,
+
+    /// Second Nonce Point
+    pub second: G,
+}
+
+/// Partial Signature
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "S: Clone"),
+    Copy(bound = "S: Copy"),
+    Debug(bound = "S: Debug"),
+    Eq(bound = "S: Eq"),
+    Hash(bound = "S: Hash"),
+    PartialEq(bound = "S: cmp::PartialEq")
+)]
+pub struct PartialSignature<S> {
+    /// Partial Signature Scalar
+    pub scalar: S,
+}
+
+/// Signing Session
+///
+/// The session holds the values which every signer derives identically from the signer keys, the
+/// aggregate nonce commitment, and the message.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "S: Clone, G: Clone"),
+    Copy(bound = "S: Copy, G: Copy"),
+    Debug(bound = "S: Debug, G: Debug"),
+    Eq(bound = "S: Eq, G: Eq"),
+    Hash(bound = "S: Hash, G: Hash"),
+    PartialEq(bound = "S: cmp::PartialEq, G: cmp::PartialEq")
+)]
+pub struct Session<S, G> {
+    /// Aggregate Verifying Key
+    pub aggregate_key: G,
+
+    /// Nonce Coefficient
+    pub nonce_coefficient: S,
+
+    /// Final Nonce Point
+    pub nonce_point: G,
+
+    /// Schnorr Challenge
+    pub challenge: S,
+}
+
+/// MuSig2 Multi-Signature Scheme
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "H: Clone, H::Group: Clone"),
+    Copy(bound = "H: Copy, H::Group: Copy"),
+    Debug(bound = "H: Debug, H::Group: Debug"),
+    Eq(bound = "H: Eq, H::Group: Eq"),
+    Hash(bound = "H: Hash, H::Group: Hash"),
+    PartialEq(bound = "H: cmp::PartialEq, H::Group: cmp::PartialEq")
+)]
+pub struct MuSig2<H, COM = ()>
+where
+    H: HashFunction<COM>,
+{
+    /// Underlying Schnorr Signature Scheme
+    pub schnorr: Schnorr<H, COM>,
+}
+
+impl<H, COM> MuSig2<H, COM>
+where
+    H: HashFunction<COM>,
+{
+    /// Builds a new [`MuSig2`] multi-signature scheme over `schnorr`.
+    #[inline]
+    pub fn new(schnorr: Schnorr<H, COM>) -> Self {
+        Self { schnorr }
+    }
+
+    /// Multiplies `key` by its key aggregation coefficient with respect to `keys`.
+    #[inline]
+    fn weighted_key(&self, keys: &[H::Group], key: &H::Group, compiler: &mut COM) -> H::Group {
+        key.scalar_mul(
+            &self
+                .schnorr
+                .hash_function
+                .key_coefficient(keys, key, compiler),
+            compiler,
+        )
+    }
+
+    /// Aggregates the signer `keys` into a single verifying key.
+    ///
+    /// The order of `keys` is significant and must be the same for every signer.
+    #[inline]
+    pub fn aggregate_keys(&self, keys: &[H::Group], compiler: &mut COM) -> Result<H::Group, Error> {
+        let (first, rest) = keys.split_first().ok_or(Error::EmptyKeySet)?;
+        let mut aggregate_key = self.weighted_key(keys, first, compiler);
+        for key in rest {
+            let weighted_key = self.weighted_key(keys, key, compiler);
+            aggregate_key.add_assign(&weighted_key, compiler);
+        }
+        Ok(aggregate_key)
+    }
+
+    /// Computes the [`NonceCommitment`] for `nonce` which is broadcast to the other signers.
+    #[inline]
+    pub fn commit_nonce(
+        &self,
+        nonce: &SecretNonce<H::Scalar>,
+        compiler: &mut COM,
+    ) -> NonceCommitment<H::Group> {
+        NonceCommitment {
+            first: self.schnorr.generator.scalar_mul(&nonce.first, compiler),
+            second: self.schnorr.generator.scalar_mul(&nonce.second, compiler),
+        }
+    }
+
+    /// Aggregates the nonce `commitments` of every signer into a single [`NonceCommitment`].
+    #[inline]
+    pub fn aggregate_nonces(
+        &self,
+        commitments: &[NonceCommitment<H::Group>],
+        compiler: &mut COM,
+    ) -> Result<NonceCommitment<H::Group>, Error>
+    where
+        H::Group: Clone,
+    {
+        let (first, rest) = commitments.split_first().ok_or(Error::EmptyNonceSet)?;
+        Ok(rest.iter().fold(first.clone(), |sum, commitment| {
+            NonceCommitment {
+                first: sum.first.add(&commitment.first, compiler),
+                second: sum.second.add(&commitment.second, compiler),
+            }
+        }))
+    }
+
+    /// Builds the signing [`Session`] for `message` from the signer `keys` and the `aggregate_nonce`
+    /// returned by [`aggregate_nonces`](Self::aggregate_nonces).
+    #[inline]
+    pub fn session(
+        &self,
+        keys: &[H::Group],
+        aggregate_nonce: &NonceCommitment<H::Group>,
+        message: &H::Message,
+        compiler: &mut COM,
+    ) -> Result<Session<H::Scalar, H::Group>, Error> {
+        let aggregate_key = self.aggregate_keys(keys, compiler)?;
+        let nonce_coefficient = self.schnorr.hash_function.nonce_coefficient(
+            &aggregate_key,
+            aggregate_nonce,
+            message,
+            compiler,
+        );
+        let nonce_point = aggregate_nonce.first.add(
+            &aggregate_nonce
+                .second
+                .scalar_mul(&nonce_coefficient, compiler),
+            compiler,
+        );
+        let challenge =
+            self.schnorr
+                .hash_function
+                .hash(&aggregate_key, &nonce_point, message, compiler);
+        Ok(Session {
+            aggregate_key,
+            nonce_coefficient,
+            nonce_point,
+            challenge,
+        })
+    }
+
+    /// Computes the partial signature of the signer with `signing_key` over `session`, consuming
+    /// their secret `nonce`.
+    #[inline]
+    pub fn partial_sign(
+        &self,
+        keys: &[H::Group],
+        session: &Session<H::Scalar, H::Group>,
+        signing_key: &H::Scalar,
+        nonce: SecretNonce<H::Scalar>,
+        compiler: &mut COM,
+    ) -> PartialSignature<H::Scalar> {
+        let verifying_key = self.schnorr.derive(signing_key, compiler);
+        let key_coefficient =
+            self.schnorr
+                .hash_function
+                .key_coefficient(keys, &verifying_key, compiler);
+        PartialSignature {
+            scalar: nonce
+                .first
+                .add(
+                    &nonce.second.mul(&session.nonce_coefficient, compiler),
+                    compiler,
+                )
+                .add(
+                    &session
+                        .challenge
+                        .mul(&key_coefficient, compiler)
+                        .mul(signing_key, compiler),
+                    compiler,
+                ),
+        }
+    }
+
+    /// Verifies that `partial_signature` was produced over `session` by the signer with
+    /// `verifying_key` who committed to `nonce` in the first round.
+    #[inline]
+    pub fn verify_partial(
+        &self,
+        keys: &[H::Group],
+        session: &Session<H::Scalar, H::Group>,
+        verifying_key: &H::Group,
+        nonce: &NonceCommitment<H::Group>,
+        partial_signature: &PartialSignature<H::Scalar>,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        COM: Has<bool>,
+        H::Group: PartialEq<H::Group, COM>,
+    {
+        let key_coefficient = self
+            .schnorr
+            .hash_function
+            .key_coefficient(keys, verifying_key, compiler);
+        let expected = nonce
+            .first
+            .add(
+                &nonce.second.scalar_mul(&session.nonce_coefficient, compiler),
+                compiler,
+            )
+            .add(
+                &verifying_key.scalar_mul(
+                    &session.challenge.mul(&key_coefficient, compiler),
+                    compiler,
+                ),
+                compiler,
+            );
+        self.schnorr
+            .generator
+            .scalar_mul(&partial_signature.scalar, compiler)
+            .eq(&expected, compiler)
+    }
+
+    /// Aggregates the `partial_signatures` of every signer into a standard Schnorr [`Signature`]
+    /// over `session`.
+    #[inline]
+    pub fn aggregate(
+        &self,
+        session: &Session<H::Scalar, H::Group>,
+        partial_signatures: &[PartialSignature<H::Scalar>],
+        compiler: &mut COM,
+    ) -> Result<Signature<H::Scalar, H::Group>, Error>
+    where
+        H::Scalar: Clone,
+        H::Group: Clone,
+    {
+        let (first, rest) = partial_signatures
+            .split_first()
+            .ok_or(Error::EmptyPartialSignatureSet)?;
+        Ok(Signature {
+            scalar: rest.iter().fold(first.scalar.clone(), |sum, partial| {
+                sum.add(&partial.scalar, compiler)
+            }),
+            nonce_point: session.nonce_point.clone(),
+        })
+    }
+
+    /// Verifies `signature` over `message` against the `aggregate_key` using the underlying
+    /// [`Schnorr`] verification.
+    #[inline]
+    pub fn verify(
+        &self,
+        aggregate_key: &H::Group,
+        message: &H::Message,
+        signature: &Signature<H::Scalar, H::Group>,
+        compiler: &mut COM,
+    ) -> Bool<COM>
+    where
+        COM: Has<bool>,
+        H::Group: Clone
+            + MultiScalarMul<H::Scalar, COM>
+            + Neg<COM, Output = H::Group>
+            + PartialEq<H::Group, COM>,
+    {
+        self.schnorr
+            .verify(aggregate_key, message, signature, compiler)
+    }
+}
+
+impl<H, DH, DG> Sample<(DH, DG)> for MuSig2<H>
+where
+    H: HashFunction + Sample<DH>,
+    H::Group: Sample<DG>,
+{
+    #[inline]
+    fn sample<R>(distribution: (DH, DG), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self::new(rng.sample(distribution))
+    }
+}
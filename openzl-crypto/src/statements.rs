@@ -0,0 +1,691 @@
+//! Statement Kit
+//!
+//! Most circuits are a conjunction of a few standard sub-statements like accumulator membership or
+//! signature verification. A [`Statement`] describes one sub-statement by its public input, its
+//! secret witness, and the constraints it enforces over them. Statements compose with [`Both`],
+//! and a [`Circuit`] allocates the public input and secret witness of the composed statement in
+//! the right modes, wires the public input into the [`ProofSystem`] input in allocation order, and
+//! drives compilation, proving, and verification, so no allocation or assertion code has to be
+//! written by hand.
+//!
+//! Proofs of plain statements can be replayed by anyone who sees them. Wrapping a statement in
+//! [`Bound`] binds its proofs to a message, like the hash of the transaction which carries the
+//! proof, with [`Circuit::prove_bound`] and [`verify_bound`].
+
+use crate::{
+    accumulator::AssertValidVerification,
+    constraint::{HasInput, ProofSystem},
+    encryption::{verifiable, Encrypt, Header, HeaderType},
+    hash::HashFunction,
+    signature::Verify,
+    ProofInput,
+};
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use eclair::{
+    alloc::{
+        mode::{Public, Secret},
+        Allocate, Allocator, Constant, Variable,
+    },
+    bool::{Assert, Bool},
+    num::AssertWithinBitRange,
+    ops::Mul,
+};
+use openzl_util::{
+    derivative,
+    rand::{CryptoRng, RngCore},
+};
+
+#[cfg(feature = "serde")]
+use openzl_util::serde::{Deserialize, Serialize};
+
+/// Statement
+pub trait Statement<COM = ()> {
+    /// Public Input Type
+    type Public;
+
+    /// Secret Witness Type
+    type Secret;
+
+    /// Enforces the constraints of `self` over the allocated `public` input and `secret` witness.
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM);
+}
+
+/// Native Public Input Type of the Statement `V` in the Compiler `COM`
+pub type PublicInput<V, COM> = <<V as Statement<COM>>::Public as Variable<Public, COM>>::Type;
+
+/// Native Secret Witness Type of the Statement `V` in the Compiler `COM`
+pub type SecretWitness<V, COM> = <<V as Statement<COM>>::Secret as Variable<Secret, COM>>::Type;
+
+/// Conjunction
+///
+/// As a statement, this `struct` enforces both the `left` and the `right` statement. It is also
+/// used to pair up their public inputs and secret witnesses, which are allocated and wired into
+/// the proof system input left first.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, ProofInput)]
+#[proof_input(crate = "crate")]
+pub struct Both<L, R> {
+    /// Left Component
+    pub left: L,
+
+    /// Right Component
+    pub right: R,
+}
+
+impl<L, R> Both<L, R> {
+    /// Builds a new [`Both`] from `left` and `right`.
+    #[inline]
+    pub fn new(left: L, right: R) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<L, R, COM> Constant<COM> for Both<L, R>
+where
+    L: Constant<COM>,
+    R: Constant<COM>,
+{
+    type Type = Both<L::Type, R::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.left.as_constant(compiler),
+            this.right.as_constant(compiler),
+        )
+    }
+}
+
+impl<L, R, M, COM> Variable<M, COM> for Both<L, R>
+where
+    L: Variable<M, COM>,
+    R: Variable<M, COM>,
+{
+    type Type = Both<L::Type, R::Type>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.left.as_known(compiler), this.right.as_known(compiler))
+    }
+}
+
+impl<L, R, COM> Statement<COM> for Both<L, R>
+where
+    L: Statement<COM>,
+    R: Statement<COM>,
+{
+    type Public = Both<L::Public, R::Public>;
+    type Secret = Both<L::Secret, R::Secret>;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        self.left.enforce(&public.left, &secret.left, compiler);
+        self.right.enforce(&public.right, &secret.right, compiler);
+    }
+}
+
+/// Accumulator Membership Statement
+///
+/// Enforces that the secret item, the left component of the witness, is stored in the accumulator
+/// with the public output under the accumulator `model`, using the right component of the witness
+/// as the membership witness.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Membership<M>(
+    /// Accumulator Model
+    pub M,
+);
+
+impl<M, COM> Constant<COM> for Membership<M>
+where
+    M: Constant<COM>,
+{
+    type Type = Membership<M::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+impl<M, COM> Statement<COM> for Membership<M>
+where
+    M: AssertValidVerification<COM>,
+{
+    type Public = M::Output;
+    type Secret = Both<M::Item, M::Witness>;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        self.0
+            .assert_valid(&secret.left, &secret.right, public, compiler)
+    }
+}
+
+/// Signature Verification Statement
+///
+/// Enforces that the secret signature is a valid signature of the message, the right component of
+/// the public input, under the verifying key, the left component of the public input.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SignatureVerification<S>(
+    /// Signature Scheme
+    pub S,
+);
+
+impl<S, COM> Constant<COM> for SignatureVerification<S>
+where
+    S: Constant<COM>,
+{
+    type Type = SignatureVerification<S::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+impl<S, COM> Statement<COM> for SignatureVerification<S>
+where
+    S: Verify<COM, Verification = Bool<COM>>,
+    COM: Assert,
+{
+    type Public = Both<S::VerifyingKey, S::Message>;
+    type Secret = S::Signature;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        let is_valid = self.0.verify(&public.left, &public.right, secret, compiler);
+        compiler.assert(&is_valid);
+    }
+}
+
+/// Range Check Statement
+///
+/// Enforces that the secret value fits into `BITS` bits. This statement has no public input.
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RangeCheck<T, const BITS: usize>(PhantomData<T>);
+
+impl<T, const BITS: usize> RangeCheck<T, BITS> {
+    /// Builds a new [`RangeCheck`].
+    #[inline]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, const BITS: usize, COM> Constant<COM> for RangeCheck<T, BITS>
+where
+    T: Variable<Secret, COM>,
+{
+    type Type = RangeCheck<T::Type, BITS>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        let _ = (this, compiler);
+        Self::new()
+    }
+}
+
+impl<T, const BITS: usize, COM> Statement<COM> for RangeCheck<T, BITS>
+where
+    COM: AssertWithinBitRange<T, BITS>,
+{
+    type Public = ();
+    type Secret = T;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        let _ = public;
+        compiler.assert_within_range(secret);
+    }
+}
+
+/// Message-Bound Statement
+///
+/// Enforces the statement `S` and binds its proofs to a message through an extra public input of
+/// type `T`, the binding, which is a commitment to the message that is wired into the proof system
+/// input after the public input of `S`. The binding is squared in-circuit so that it appears in
+/// the constraints, since otherwise proof systems like Groth16 accept the proof for any value of
+/// an unconstrained public input.
+///
+/// Proofs of a [`Bound`] statement can not be reused for a different message without knowing a
+/// secret witness for `S`. They can still be rerandomized with
+/// [`Rerandomize`](crate::constraint::Rerandomize) when it is supported, which unlinks
+/// re-submissions of the same proof but keeps them bound to the same message.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "S: Clone"),
+    Copy(bound = "S: Copy"),
+    Debug(bound = "S: Debug"),
+    Default(bound = "S: Default"),
+    Eq(bound = "S: Eq"),
+    Hash(bound = "S: Hash"),
+    Ord(bound = "S: Ord"),
+    PartialEq(bound = "S: PartialEq"),
+    PartialOrd(bound = "S: PartialOrd")
+)]
+pub struct Bound<S, T>(
+    /// Statement
+    pub S,
+    /// Type Parameter Marker
+    PhantomData<T>,
+);
+
+impl<S, T> Bound<S, T> {
+    /// Builds a new [`Bound`] over `statement`.
+    #[inline]
+    pub fn new(statement: S) -> Self {
+        Self(statement, PhantomData)
+    }
+}
+
+impl<S, T, COM> Constant<COM> for Bound<S, T>
+where
+    S: Constant<COM>,
+    T: Variable<Public, COM>,
+{
+    type Type = Bound<S::Type, T::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(this.0.as_constant(compiler))
+    }
+}
+
+impl<S, T, COM> Statement<COM> for Bound<S, T>
+where
+    S: Statement<COM>,
+    T: Clone + Mul<T, COM>,
+{
+    type Public = Both<S::Public, T>;
+    type Secret = S::Secret;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        self.0.enforce(&public.left, secret, compiler);
+        let _ = public.right.clone().mul(public.right.clone(), compiler);
+    }
+}
+
+/// Encryption Consistency Statement
+///
+/// Enforces that the public ciphertext is the encryption of the secret plaintext under the public
+/// encryption key, the secret randomness, and the `header`. See [`verifiable`] for the public input
+/// and secret witness types.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "E: Clone, E::Header: Clone"),
+    Copy(bound = "E: Copy, E::Header: Copy"),
+    Debug(bound = "E: Debug, E::Header: Debug"),
+    Default(bound = "E: Default, E::Header: Default"),
+    Eq(bound = "E: Eq, E::Header: Eq"),
+    Hash(bound = "E: Hash, E::Header: Hash"),
+    PartialEq(bound = "E: PartialEq, E::Header: PartialEq")
+)]
+pub struct EncryptionConsistency<E>
+where
+    E: HeaderType,
+{
+    /// Encryption Scheme
+    pub cipher: E,
+
+    /// Encryption Header
+    pub header: E::Header,
+}
+
+impl<E> EncryptionConsistency<E>
+where
+    E: HeaderType,
+{
+    /// Builds a new [`EncryptionConsistency`] statement from `cipher` and `header`.
+    #[inline]
+    pub fn new(cipher: E, header: E::Header) -> Self {
+        Self { cipher, header }
+    }
+}
+
+impl<E, COM> Constant<COM> for EncryptionConsistency<E>
+where
+    E: Constant<COM> + HeaderType,
+    E::Type: HeaderType,
+    E::Header: Constant<COM, Type = Header<E::Type>>,
+{
+    type Type = EncryptionConsistency<E::Type>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self::new(
+            this.cipher.as_constant(compiler),
+            this.header.as_constant(compiler),
+        )
+    }
+}
+
+impl<E, COM> Statement<COM> for EncryptionConsistency<E>
+where
+    E: Encrypt<COM>,
+    E::Ciphertext: eclair::cmp::PartialEq<E::Ciphertext, COM>,
+    COM: Assert,
+{
+    type Public = verifiable::Statement<E>;
+    type Secret = verifiable::Witness<E>;
+
+    #[inline]
+    fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut COM) {
+        let ciphertext = self.cipher.encrypt(
+            &public.encryption_key,
+            &secret.randomness,
+            &self.header,
+            &secret.plaintext,
+            compiler,
+        );
+        eclair::cmp::PartialEq::assert_equal(&ciphertext, &public.ciphertext, compiler);
+    }
+}
+
+/// Statement Circuit
+///
+/// Wraps the native statement `S`, which is compiled into the circuit as the constant statement
+/// `V`. The public input of `V` is allocated as [`Public`] before its secret witness is allocated
+/// as [`Secret`], and [`input`] extends the proof system input in the same order.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Circuit<S>(
+    /// Native Statement
+    pub S,
+);
+
+impl<S> Circuit<S> {
+    /// Builds a new [`Circuit`] for `statement`.
+    #[inline]
+    pub fn new(statement: S) -> Self {
+        Self(statement)
+    }
+
+    /// Allocates an unknown public input and secret witness into `compiler` and enforces the
+    /// statement over them. This is used to build the circuit for context generation.
+    #[inline]
+    pub fn build_unknown<V, COM>(&self, compiler: &mut COM)
+    where
+        V: Statement<COM> + Constant<COM, Type = S>,
+        V::Public: Variable<Public, COM>,
+        V::Secret: Variable<Secret, COM>,
+    {
+        let statement = self.0.as_constant::<V>(compiler);
+        let public = compiler.allocate_unknown::<Public, V::Public>();
+        let secret = compiler.allocate_unknown::<Secret, V::Secret>();
+        statement.enforce(&public, &secret, compiler);
+    }
+
+    /// Allocates the known `public` input and `secret` witness into `compiler` and enforces the
+    /// statement over them. This is used to build the circuit for proof generation.
+    #[inline]
+    pub fn build_known<V, COM>(
+        &self,
+        public: &PublicInput<V, COM>,
+        secret: &SecretWitness<V, COM>,
+        compiler: &mut COM,
+    ) where
+        V: Statement<COM> + Constant<COM, Type = S>,
+        V::Public: Variable<Public, COM>,
+        V::Secret: Variable<Secret, COM>,
+    {
+        let statement = self.0.as_constant::<V>(compiler);
+        let public = public.as_known::<Public, V::Public>(compiler);
+        let secret = secret.as_known::<Secret, V::Secret>(compiler);
+        statement.enforce(&public, &secret, compiler);
+    }
+
+    /// Compiles the circuit into proving and verifying contexts for the proof system `P` using
+    /// `public_parameters`.
+    #[inline]
+    pub fn compile<P, V, R>(
+        &self,
+        public_parameters: &P::PublicParameters,
+        rng: &mut R,
+    ) -> Result<(P::ProvingContext, P::VerifyingContext), P::Error>
+    where
+        P: ProofSystem,
+        V: Statement<P::Compiler> + Constant<P::Compiler, Type = S>,
+        V::Public: Variable<Public, P::Compiler>,
+        V::Secret: Variable<Secret, P::Compiler>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let mut compiler = P::context_compiler();
+        self.build_unknown::<V, _>(&mut compiler);
+        P::compile(public_parameters, compiler, rng)
+    }
+
+    /// Proves the statement for the `public` input and `secret` witness with the proof system `P`
+    /// and the proving `context`.
+    #[inline]
+    pub fn prove<P, V, R>(
+        &self,
+        context: &P::ProvingContext,
+        public: &PublicInput<V, P::Compiler>,
+        secret: &SecretWitness<V, P::Compiler>,
+        rng: &mut R,
+    ) -> Result<P::Proof, P::Error>
+    where
+        P: ProofSystem,
+        V: Statement<P::Compiler> + Constant<P::Compiler, Type = S>,
+        V::Public: Variable<Public, P::Compiler>,
+        V::Secret: Variable<Secret, P::Compiler>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let mut compiler = P::proof_compiler();
+        self.build_known::<V, _>(public, secret, &mut compiler);
+        P::prove(context, compiler, rng)
+    }
+}
+
+impl<S, T> Circuit<Bound<S, T>> {
+    /// Proves the statement for the `public` input and `secret` witness like [`prove`](Self::prove)
+    /// and binds the proof to `message`, whose binding is computed natively with `hasher`.
+    #[inline]
+    pub fn prove_bound<P, V, U, H, R>(
+        &self,
+        context: &P::ProvingContext,
+        hasher: &H,
+        message: &H::Input,
+        public: &PublicInput<V, P::Compiler>,
+        secret: &SecretWitness<V, P::Compiler>,
+        rng: &mut R,
+    ) -> Result<P::Proof, P::Error>
+    where
+        P: ProofSystem,
+        V: Statement<P::Compiler> + Constant<P::Compiler, Type = S>,
+        V::Public: Variable<Public, P::Compiler>,
+        V::Secret: Variable<Secret, P::Compiler>,
+        PublicInput<V, P::Compiler>: Clone,
+        U: Clone + Mul<U, P::Compiler> + Variable<Public, P::Compiler, Type = T>,
+        H: HashFunction<Output = T>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        self.prove::<P, Bound<V, U>, R>(
+            context,
+            &Both::new(public.clone(), hasher.hash(message, &mut ())),
+            secret,
+            rng,
+        )
+    }
+}
+
+/// Builds the input of the proof system `P` for the `public` input of a statement.
+#[inline]
+pub fn input<P, T>(public: &T) -> P::Input
+where
+    P: HasInput<T>,
+    T: ?Sized,
+{
+    let mut input = P::Input::default();
+    P::extend(&mut input, public);
+    input
+}
+
+/// Verifies that `proof` proves a statement for the `public` input with the proof system `P` and
+/// the verifying `context`.
+#[inline]
+pub fn verify<P, T>(
+    context: &P::VerifyingContext,
+    public: &T,
+    proof: &P::Proof,
+) -> Result<bool, P::Error>
+where
+    P: HasInput<T>,
+    T: ?Sized,
+{
+    P::verify(context, &input::<P, T>(public), proof)
+}
+
+/// Verifies that `proof` proves a [`Bound`] statement for the `public` input which is bound to
+/// `message`, whose binding is computed natively with `hasher`, with the proof system `P` and the
+/// verifying `context`.
+#[inline]
+pub fn verify_bound<P, T, H>(
+    context: &P::VerifyingContext,
+    hasher: &H,
+    message: &H::Input,
+    public: &T,
+    proof: &P::Proof,
+) -> Result<bool, P::Error>
+where
+    P: HasInput<T> + HasInput<H::Output>,
+    H: HashFunction,
+    T: ?Sized,
+{
+    let mut input = P::Input::default();
+    <P as HasInput<T>>::extend(&mut input, public);
+    <P as HasInput<H::Output>>::extend(&mut input, &hasher.hash(message, &mut ()));
+    P::verify(context, &input, proof)
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use crate::constraint::Input;
+    use alloc::{vec, vec::Vec};
+
+    /// Test Proof System
+    ///
+    /// The verification input is the list of public values, and every proof is valid.
+    struct Collector;
+
+    impl ProofSystem for Collector {
+        type Compiler = ();
+        type PublicParameters = ();
+        type ProvingContext = ();
+        type VerifyingContext = ();
+        type Input = Vec<u64>;
+        type Proof = ();
+        type Error = ();
+
+        #[inline]
+        fn context_compiler() -> Self::Compiler {}
+
+        #[inline]
+        fn proof_compiler() -> Self::Compiler {}
+
+        #[inline]
+        fn compile<R>(
+            public_parameters: &Self::PublicParameters,
+            compiler: Self::Compiler,
+            rng: &mut R,
+        ) -> Result<(Self::ProvingContext, Self::VerifyingContext), Self::Error>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let _ = (public_parameters, compiler, rng);
+            Ok(((), ()))
+        }
+
+        #[inline]
+        fn prove<R>(
+            context: &Self::ProvingContext,
+            compiler: Self::Compiler,
+            rng: &mut R,
+        ) -> Result<Self::Proof, Self::Error>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let _ = (context, compiler, rng);
+            Ok(())
+        }
+
+        #[inline]
+        fn verify(
+            context: &Self::VerifyingContext,
+            input: &Self::Input,
+            proof: &Self::Proof,
+        ) -> Result<bool, Self::Error> {
+            let _ = (context, input, proof);
+            Ok(true)
+        }
+    }
+
+    impl Input<Collector> for u64 {
+        #[inline]
+        fn extend(&self, input: &mut Vec<u64>) {
+            input.push(*self);
+        }
+    }
+
+    /// Transfer with a Memo
+    ///
+    /// The memo does not implement [`Input`], so this `struct` only implements [`Input`] if the
+    /// memo is skipped.
+    #[derive(ProofInput)]
+    #[proof_input(crate = "crate")]
+    struct Transfer {
+        /// Amount
+        amount: u64,
+
+        /// Memo
+        #[proof_input(skip)]
+        memo: &'static str,
+
+        /// Recipient
+        recipient: u64,
+    }
+
+    /// Tests that derived inputs extend the input with the fields in declaration order and leave
+    /// out skipped fields.
+    #[test]
+    fn derived_input_skips_fields() {
+        let transfer = Transfer {
+            amount: 3,
+            memo: "rent",
+            recipient: 7,
+        };
+        assert_eq!(transfer.memo, "rent");
+        assert_eq!(
+            input::<Collector, _>(&transfer),
+            vec![3, 7],
+            "Skipped fields must not contribute to the public input."
+        );
+        assert_eq!(
+            input::<Collector, _>(&Both::new(transfer, 11)),
+            vec![3, 7, 11],
+            "Composite inputs should extend the input left first."
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Fiat-Shamir Transcripts
+//!
+//! Interactive public-coin protocols are made non-interactive by replacing the challenges of the
+//! verifier with values derived from everything the prover sent so far. The [`Transcript`] trait
+//! abstracts over the way those challenges are derived, so that protocols like range proofs and
+//! sigma protocols can be instantiated with any hash function or sponge.
+
+/// Fiat-Shamir Transcript
+///
+/// The transcript absorbs every message of the prover and derives the challenges of the verifier
+/// from them. Prover and verifier must start from transcripts in the same state, which is also how
+/// proofs are bound to their application context.
+pub trait Transcript<G, F> {
+    /// Appends `point` to the transcript with the given `label`.
+    fn append_point(&mut self, label: &'static [u8], point: &G);
+
+    /// Appends `scalar` to the transcript with the given `label`.
+    fn append_scalar(&mut self, label: &'static [u8], scalar: &F);
+
+    /// Returns a challenge scalar derived from the transcript with the given `label`.
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> F;
+}
+
+impl<G, F, T> Transcript<G, F> for &mut T
+where
+    T: Transcript<G, F> + ?Sized,
+{
+    #[inline]
+    fn append_point(&mut self, label: &'static [u8], point: &G) {
+        (**self).append_point(label, point)
+    }
+
+    #[inline]
+    fn append_scalar(&mut self, label: &'static [u8], scalar: &F) {
+        (**self).append_scalar(label, scalar)
+    }
+
+    #[inline]
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> F {
+        (**self).challenge_scalar(label)
+    }
+}
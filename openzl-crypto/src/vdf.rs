@@ -0,0 +1,473 @@
+//! Verifiable Delay Functions
+//!
+//! A verifiable delay function evaluates `y = x^(2^t)` in a [`Group`] of unknown order, where the
+//! `t` squarings have to be computed one after the other, and attaches a short proof that `y` is
+//! correct which can be checked much faster than recomputing it. This module implements the
+//! [`Wesolowski`] proof of exponentiation over any group of unknown order, like the [`RsaGroup`],
+//! with challenges derived from a [`Transcript`].
+//!
+//! Other proofs of exponentiation, like the one by Pietrzak, and other groups of unknown order,
+//! like class groups of imaginary quadratic fields, can be added by implementing
+//! [`ProofOfExponentiation`] and [`Group`] respectively.
+
+use crate::transcript::Transcript;
+use openzl_util::num::big::Uint;
+
+/// Group of Unknown Order
+///
+/// The group is written multiplicatively. The security of the proofs in this module relies on the
+/// order of the group being infeasible to compute for the prover.
+pub trait Group {
+    /// Group Element Type
+    type Element: Clone + PartialEq;
+
+    /// Returns the identity element of the group.
+    fn identity(&self) -> Self::Element;
+
+    /// Returns `true` if `element` is the canonical representation of an element of the group.
+    fn is_element(&self, element: &Self::Element) -> bool;
+
+    /// Computes the group operation on `lhs` and `rhs`.
+    fn mul(&self, lhs: &Self::Element, rhs: &Self::Element) -> Self::Element;
+
+    /// Computes the square of `element`.
+    #[inline]
+    fn square(&self, element: &Self::Element) -> Self::Element {
+        self.mul(element, element)
+    }
+
+    /// Computes `base^exponent` by square-and-multiply.
+    #[inline]
+    fn pow(&self, base: &Self::Element, exponent: u128) -> Self::Element {
+        let mut result = self.identity();
+        for i in (0..(128 - exponent.leading_zeros())).rev() {
+            result = self.square(&result);
+            if (exponent >> i) & 1 == 1 {
+                result = self.mul(&result, base);
+            }
+        }
+        result
+    }
+
+    /// Computes `input^(2^iterations)` by repeated squaring.
+    #[inline]
+    fn repeated_squaring(&self, input: &Self::Element, iterations: u64) -> Self::Element {
+        let mut output = input.clone();
+        for _ in 0..iterations {
+            output = self.square(&output);
+        }
+        output
+    }
+}
+
+/// RSA Group
+///
+/// The group of units modulo an RSA modulus `N`, quotiented by `{1, -1}` so that the element `-1`,
+/// whose order is known, can't be used to forge proofs. Every element is represented by the
+/// smaller of `x` and `N - x`. The factorization of `N` must be unknown to the prover, which
+/// requires either a trusted setup or a modulus like RSA-2048 whose factors were never revealed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RsaGroup<const LIMBS: usize> {
+    /// Modulus
+    modulus: Uint<LIMBS>,
+}
+
+impl<const LIMBS: usize> RsaGroup<LIMBS> {
+    /// Builds a new [`RsaGroup`] over `modulus`, returning `None` if `modulus` is even or smaller
+    /// than five.
+    #[inline]
+    pub fn new(modulus: Uint<LIMBS>) -> Option<Self> {
+        (modulus.is_odd() && modulus > Uint::from_u64(4)).then_some(Self { modulus })
+    }
+
+    /// Returns the modulus of `self`.
+    #[inline]
+    pub fn modulus(&self) -> &Uint<LIMBS> {
+        &self.modulus
+    }
+
+    /// Returns the canonical representative of `value` modulo the modulus of `self`, returning
+    /// `None` if `value` is not a unit.
+    #[inline]
+    pub fn element(&self, value: &Uint<LIMBS>) -> Option<Uint<LIMBS>> {
+        let value = value.rem(&self.modulus);
+        value.inv_mod(&self.modulus)?;
+        Some(self.canonicalize(value))
+    }
+
+    /// Returns the smaller of `value` and `-value` modulo the modulus of `self`.
+    #[inline]
+    fn canonicalize(&self, value: Uint<LIMBS>) -> Uint<LIMBS> {
+        let negated = value.neg_mod(&self.modulus);
+        if negated < value {
+            negated
+        } else {
+            value
+        }
+    }
+}
+
+impl<const LIMBS: usize> Group for RsaGroup<LIMBS> {
+    type Element = Uint<LIMBS>;
+
+    #[inline]
+    fn identity(&self) -> Self::Element {
+        Uint::ONE
+    }
+
+    #[inline]
+    fn is_element(&self, element: &Self::Element) -> bool {
+        self.element(element).as_ref() == Some(element)
+    }
+
+    #[inline]
+    fn mul(&self, lhs: &Self::Element, rhs: &Self::Element) -> Self::Element {
+        self.canonicalize(lhs.mul_mod(rhs, &self.modulus))
+    }
+}
+
+/// Proof of Exponentiation
+///
+/// Proves that `output = input^(2^iterations)` in the [`Group`] `G`.
+pub trait ProofOfExponentiation<G>
+where
+    G: Group,
+{
+    /// Proof Type
+    type Proof;
+
+    /// Proves that `output = input^(2^iterations)`, deriving the challenges from `transcript`.
+    fn prove<T>(
+        &self,
+        group: &G,
+        input: &G::Element,
+        output: &G::Element,
+        iterations: u64,
+        transcript: T,
+    ) -> Self::Proof
+    where
+        T: Transcript<G::Element, u128>;
+
+    /// Verifies that `proof` shows `output = input^(2^iterations)`, deriving the challenges from
+    /// `transcript`.
+    fn verify<T>(
+        &self,
+        group: &G,
+        input: &G::Element,
+        output: &G::Element,
+        iterations: u64,
+        proof: &Self::Proof,
+        transcript: T,
+    ) -> bool
+    where
+        T: Transcript<G::Element, u128>;
+
+    /// Evaluates `input^(2^iterations)` and proves that the result is correct.
+    #[inline]
+    fn evaluate<T>(
+        &self,
+        group: &G,
+        input: &G::Element,
+        iterations: u64,
+        transcript: T,
+    ) -> (G::Element, Self::Proof)
+    where
+        T: Transcript<G::Element, u128>,
+    {
+        let output = group.repeated_squaring(input, iterations);
+        let proof = self.prove(group, input, &output, iterations, transcript);
+        (output, proof)
+    }
+}
+
+/// Wesolowski Proof of Exponentiation
+///
+/// The verifier challenges the prover with a prime `l` and the prover answers with
+/// `proof = input^floor(2^t / l)`, which is checked by `proof^l * input^(2^t mod l) = output`.
+/// The proof is a single group element and verification takes a logarithmic number of group
+/// operations in the number of iterations. Challenges are 125-bit primes derived from the
+/// transcript.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Wesolowski;
+
+impl Wesolowski {
+    /// Number of Bits in the Challenge Primes
+    pub const CHALLENGE_BITS: u32 = 125;
+
+    /// Appends the statement to `transcript` and derives the challenge prime.
+    #[inline]
+    fn challenge<E, T>(input: &E, output: &E, iterations: u64, mut transcript: T) -> u128
+    where
+        T: Transcript<E, u128>,
+    {
+        transcript.append_point(b"x", input);
+        transcript.append_point(b"y", output);
+        transcript.append_scalar(b"t", &u128::from(iterations));
+        let challenge = transcript.challenge_scalar(b"l");
+        let candidate =
+            (challenge >> (129 - Self::CHALLENGE_BITS)) | (1 << (Self::CHALLENGE_BITS - 1));
+        next_prime(candidate)
+    }
+}
+
+impl<G> ProofOfExponentiation<G> for Wesolowski
+where
+    G: Group,
+{
+    type Proof = G::Element;
+
+    #[inline]
+    fn prove<T>(
+        &self,
+        group: &G,
+        input: &G::Element,
+        output: &G::Element,
+        iterations: u64,
+        transcript: T,
+    ) -> Self::Proof
+    where
+        T: Transcript<G::Element, u128>,
+    {
+        let prime = Self::challenge(input, output, iterations, transcript);
+        let mut remainder = 1u128;
+        let mut proof = group.identity();
+        for _ in 0..iterations {
+            remainder <<= 1;
+            proof = group.square(&proof);
+            if remainder >= prime {
+                remainder -= prime;
+                proof = group.mul(&proof, input);
+            }
+        }
+        proof
+    }
+
+    #[inline]
+    fn verify<T>(
+        &self,
+        group: &G,
+        input: &G::Element,
+        output: &G::Element,
+        iterations: u64,
+        proof: &Self::Proof,
+        transcript: T,
+    ) -> bool
+    where
+        T: Transcript<G::Element, u128>,
+    {
+        if !(group.is_element(input) && group.is_element(output) && group.is_element(proof)) {
+            return false;
+        }
+        let prime = Self::challenge(input, output, iterations, transcript);
+        let remainder = pow_mod(2, u128::from(iterations), prime);
+        group.mul(&group.pow(proof, prime), &group.pow(input, remainder)) == *output
+    }
+}
+
+/// Converts `value` into a two-limb [`Uint`].
+#[inline]
+fn to_uint(value: u128) -> Uint<2> {
+    Uint::from_limbs([value as u64, (value >> 64) as u64])
+}
+
+/// Converts the two-limb `value` into a [`u128`].
+#[inline]
+fn from_uint(value: Uint<2>) -> u128 {
+    let [low, high] = value.into_limbs();
+    (u128::from(high) << 64) | u128::from(low)
+}
+
+/// Computes `base^exponent mod modulus`.
+#[inline]
+fn pow_mod(base: u128, exponent: u128, modulus: u128) -> u128 {
+    from_uint(to_uint(base).pow_mod(&to_uint(exponent), &to_uint(modulus)))
+}
+
+/// Returns `true` if `n` is prime.
+///
+/// This is the Miller-Rabin test with the first twenty primes as bases, which is deterministic
+/// for `n < 2^81` and has no known counterexamples below `2^128`.
+#[inline]
+pub fn is_prime(n: u128) -> bool {
+    const BASES: [u128; 20] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+    ];
+    if n < 2 {
+        return false;
+    }
+    for base in BASES {
+        if n % base == 0 {
+            return n == base;
+        }
+    }
+    let modulus = to_uint(n);
+    let minus_one = to_uint(n - 1);
+    let shift = (n - 1).trailing_zeros();
+    let odd = to_uint((n - 1) >> shift);
+    BASES.iter().all(|base| {
+        let mut x = to_uint(*base).pow_mod(&odd, &modulus);
+        if x == Uint::ONE || x == minus_one {
+            return true;
+        }
+        for _ in 1..shift {
+            x = x.mul_mod(&x, &modulus);
+            if x == minus_one {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Returns the smallest prime greater than or equal to `n`.
+///
+/// # Panics
+///
+/// This function panics if there is no such prime below `2^128`.
+#[inline]
+pub fn next_prime(mut n: u128) -> u128 {
+    loop {
+        if is_prime(n) {
+            return n;
+        }
+        n = n
+            .checked_add(1)
+            .expect("There are no primes left below 2^128.");
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use openzl_util::rand::{RngCore, TestRng};
+
+    /// Test Transcript
+    ///
+    /// Mixes every appended value into a running state. This is only meant for tests and is not a
+    /// secure Fiat-Shamir transcript.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct MixingTranscript(u128);
+
+    impl MixingTranscript {
+        /// Mixes `label` and `words` into the state.
+        #[inline]
+        fn mix(&mut self, label: &[u8], words: impl IntoIterator<Item = u128>) {
+            for word in label.iter().map(|byte| u128::from(*byte)).chain(words) {
+                self.0 = (self.0 ^ word)
+                    .wrapping_mul(0x0000000001000000000000000000013b)
+                    .rotate_left(29);
+            }
+        }
+    }
+
+    impl<const LIMBS: usize> Transcript<Uint<LIMBS>, u128> for MixingTranscript {
+        #[inline]
+        fn append_point(&mut self, label: &'static [u8], point: &Uint<LIMBS>) {
+            self.mix(label, point.limbs().iter().map(|limb| u128::from(*limb)));
+        }
+
+        #[inline]
+        fn append_scalar(&mut self, label: &'static [u8], scalar: &u128) {
+            self.mix(label, [*scalar]);
+        }
+
+        #[inline]
+        fn challenge_scalar(&mut self, label: &'static [u8]) -> u128 {
+            self.mix(label, []);
+            self.0
+        }
+    }
+
+    /// Checks that the primality test agrees with trial division on small numbers and finds
+    /// known primes.
+    #[test]
+    fn primality_matches_trial_division() {
+        for n in 0..2000u128 {
+            let expected = n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0);
+            assert_eq!(is_prime(n), expected, "Primality of {n} should match.");
+        }
+        assert!(
+            is_prime((1 << 127) - 1),
+            "The Mersenne number 2^127 - 1 should be prime."
+        );
+        assert!(
+            !is_prime(3215031751),
+            "The strong pseudoprime to the bases 2, 3, 5, and 7 should be composite."
+        );
+        assert_eq!(
+            next_prime((1 << 64) + 1),
+            (1 << 64) + 13,
+            "The next prime after 2^64 should be 2^64 + 13."
+        );
+    }
+
+    /// Checks that Wesolowski proofs verify for correct evaluations and are rejected for wrong
+    /// outputs, proofs, and iteration counts.
+    #[test]
+    fn wesolowski_proofs_verify() {
+        let mut rng = TestRng::new();
+        // NOTE: This is the product of the primes `2^61 - 1` and `2^89 - 1`, so it is only safe
+        //       for testing since its factorization is known.
+        let modulus = Uint::<4>::from_limbs([0xe000000000000001, 0xfffffffffdffffff, 0x3fffff, 0]);
+        let group = RsaGroup::new(modulus).expect("The modulus is odd.");
+        let input = group
+            .element(&Uint::from_u128(u128::from(rng.next_u64()) | 2).expect("Fits in four limbs."))
+            .expect("Small values are units modulo the product of two large primes.");
+        for iterations in [0, 1, 2, 100, 1000] {
+            let (output, proof) =
+                Wesolowski.evaluate(&group, &input, iterations, MixingTranscript::default());
+            assert_eq!(
+                output,
+                group.repeated_squaring(&input, iterations),
+                "The evaluation should be the repeated squaring of the input."
+            );
+            assert!(
+                Wesolowski.verify(
+                    &group,
+                    &input,
+                    &output,
+                    iterations,
+                    &proof,
+                    MixingTranscript::default()
+                ),
+                "A correct evaluation should verify."
+            );
+            let wrong_output = group.mul(&output, &Uint::from_u64(2));
+            assert!(
+                !Wesolowski.verify(
+                    &group,
+                    &input,
+                    &wrong_output,
+                    iterations,
+                    &proof,
+                    MixingTranscript::default()
+                ),
+                "A wrong output should not verify."
+            );
+            let wrong_proof = group.mul(&proof, &Uint::from_u64(3));
+            assert!(
+                !Wesolowski.verify(
+                    &group,
+                    &input,
+                    &output,
+                    iterations,
+                    &wrong_proof,
+                    MixingTranscript::default()
+                ),
+                "A wrong proof should not verify."
+            );
+            assert!(
+                !Wesolowski.verify(
+                    &group,
+                    &input,
+                    &output,
+                    iterations + 1,
+                    &proof,
+                    MixingTranscript::default()
+                ),
+                "A proof should not verify for a different number of iterations."
+            );
+        }
+    }
+}
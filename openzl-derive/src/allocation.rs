@@ -0,0 +1,411 @@
+//! `#[derive(Allocation)]` Derive Macro
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma,
+    Attribute, Data, DeriveInput, Error, GenericParam, Generics, Ident, Index, Lit, Meta,
+    NestedMeta, Result, Type, WherePredicate,
+};
+
+/// Compiler Type Parameter Name
+const COMPILER: &str = "COM";
+
+/// Allocation Mode
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    /// Constant Allocation
+    Constant,
+
+    /// Public Allocation
+    Public,
+
+    /// Secret Allocation
+    Secret,
+}
+
+impl Mode {
+    /// Parses the allocation mode of a field from its `attrs`.
+    #[inline]
+    fn from_attributes(attrs: &[Attribute], span: Span) -> Result<Self> {
+        let mut modes = attrs.iter().filter_map(|attr| {
+            if attr.path.is_ident("constant") {
+                Some(Self::Constant)
+            } else if attr.path.is_ident("public") {
+                Some(Self::Public)
+            } else if attr.path.is_ident("secret") {
+                Some(Self::Secret)
+            } else {
+                None
+            }
+        });
+        match (modes.next(), modes.next()) {
+            (Some(mode), None) => Ok(mode),
+            (None, _) => Err(Error::new(
+                span,
+                "Every field must be annotated with one of `#[constant]`, `#[public]`, or `#[secret]`.",
+            )),
+            _ => Err(Error::new(
+                span,
+                "Fields can only be annotated with a single allocation mode.",
+            )),
+        }
+    }
+
+    /// Returns the path to the `eclair` allocation mode marker for `self`.
+    #[inline]
+    fn marker(&self) -> TokenStream2 {
+        match self {
+            Self::Constant => quote!(::eclair::alloc::mode::Constant),
+            Self::Public => quote!(::eclair::alloc::mode::Public),
+            Self::Secret => quote!(::eclair::alloc::mode::Secret),
+        }
+    }
+}
+
+/// Container Options
+#[derive(Default)]
+struct Options {
+    /// Underlying Type
+    native: Option<Type>,
+
+    /// Compiler Type
+    compiler: Option<Type>,
+
+    /// Extra Where Predicates
+    bounds: Vec<WherePredicate>,
+}
+
+impl Options {
+    /// Parses the container options from the `#[allocation(...)]` attributes in `attrs`.
+    #[inline]
+    fn from_attributes(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("allocation")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "Expected `#[allocation(...)]`.")),
+            };
+            for nested in list.nested {
+                let pair = match nested {
+                    NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+                    nested => {
+                        return Err(Error::new_spanned(nested, "Expected `key = \"value\"`."))
+                    }
+                };
+                let value = match &pair.lit {
+                    Lit::Str(value) => value,
+                    lit => return Err(Error::new_spanned(lit, "Expected a string literal.")),
+                };
+                if pair.path.is_ident("type") {
+                    options.native = Some(value.parse()?);
+                } else if pair.path.is_ident("compiler") {
+                    options.compiler = Some(value.parse()?);
+                } else if pair.path.is_ident("bound") {
+                    options.bounds.extend(
+                        value.parse_with(Punctuated::<WherePredicate, Comma>::parse_terminated)?,
+                    );
+                } else {
+                    return Err(Error::new_spanned(
+                        pair.path,
+                        "Expected one of `type`, `compiler`, or `bound`.",
+                    ));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Allocated Field
+struct Field {
+    /// Field Member
+    member: TokenStream2,
+
+    /// Field Type
+    ty: Type,
+
+    /// Allocation Mode
+    mode: Mode,
+}
+
+/// Transforms `input` according to the macro definition.
+#[inline]
+pub fn transform(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+/// Expands the `Constant` and `Variable` implementations for `input`.
+#[inline]
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let DeriveInput {
+        attrs,
+        ident,
+        generics,
+        data,
+        ..
+    } = input;
+    let fields = match data {
+        Data::Struct(data) => data.fields,
+        _ => {
+            return Err(Error::new(
+                ident.span(),
+                "`Allocation` can only be derived for `struct`s.",
+            ))
+        }
+    };
+    let options = Options::from_attributes(&attrs)?;
+    let native = options.native.ok_or_else(|| {
+        Error::new(
+            ident.span(),
+            "Missing the underlying type in `#[allocation(type = \"...\")]`.",
+        )
+    })?;
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            Ok(Field {
+                member: match &field.ident {
+                    Some(ident) => quote!(#ident),
+                    _ => {
+                        let index = Index::from(i);
+                        quote!(#index)
+                    }
+                },
+                ty: field.ty.clone(),
+                mode: Mode::from_attributes(&field.attrs, field.span())?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (_, type_generics, _) = generics.split_for_impl();
+    let type_generics = quote!(#type_generics);
+    let (generics, compiler) = compiler_generics(generics, options.compiler, &options.bounds);
+    let constant_impl = constant_impl(
+        &ident,
+        &type_generics,
+        &generics,
+        &native,
+        &compiler,
+        &fields,
+    );
+    let derived = quote!(::eclair::alloc::mode::Derived);
+    let mut variable_impls = vec![variable_impl(
+        &ident,
+        &type_generics,
+        &generics,
+        &native,
+        &compiler,
+        &fields,
+        &derived,
+        None,
+    )];
+    let mut modes = fields
+        .iter()
+        .map(|field| field.mode)
+        .filter(|mode| *mode != Mode::Constant);
+    if let Some(mode) = modes.next() {
+        if modes.all(|m| m == mode) {
+            variable_impls.push(variable_impl(
+                &ident,
+                &type_generics,
+                &generics,
+                &native,
+                &compiler,
+                &fields,
+                &mode.marker(),
+                Some(&derived),
+            ));
+        }
+    }
+    Ok(quote!(
+        #constant_impl
+        #(#variable_impls)*
+    ))
+}
+
+/// Extends `generics` with the compiler type parameter if no `compiler` type was given and the
+/// `generics` have no `COM` type parameter, adding the extra `bounds` to the where clause.
+#[inline]
+fn compiler_generics(
+    mut generics: Generics,
+    compiler: Option<Type>,
+    bounds: &[WherePredicate],
+) -> (Generics, Type) {
+    let compiler = match compiler {
+        Some(compiler) => compiler,
+        _ => {
+            let ident = Ident::new(COMPILER, Span::call_site());
+            let has_compiler = generics.params.iter().any(|param| match param {
+                GenericParam::Type(param) => param.ident == ident,
+                _ => false,
+            });
+            if !has_compiler {
+                generics.params.push(parse_quote!(#ident));
+                generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#ident: ?Sized));
+            }
+            parse_quote!(#ident)
+        }
+    };
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds.iter().cloned());
+    (generics, compiler)
+}
+
+/// Builds the `Constant` implementation allocating every field as a constant.
+#[inline]
+fn constant_impl(
+    ident: &Ident,
+    type_generics: &TokenStream2,
+    generics: &Generics,
+    native: &Type,
+    compiler: &Type,
+    fields: &[Field],
+) -> TokenStream2 {
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    for Field { ty, .. } in fields {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::eclair::alloc::Constant<#compiler>));
+    }
+    let allocations = fields.iter().map(|Field { member, ty, .. }| {
+        quote!(
+            #member: <#ty as ::eclair::alloc::Constant<#compiler>>::new_constant(
+                &this.#member,
+                compiler,
+            )
+        )
+    });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics ::eclair::alloc::Constant<#compiler> for #ident #type_generics
+        #where_clause
+        {
+            type Type = #native;
+
+            #[inline]
+            fn new_constant(this: &Self::Type, compiler: &mut #compiler) -> Self {
+                let _ = (this, &compiler);
+                Self { #(#allocations,)* }
+            }
+        }
+    )
+}
+
+/// Builds the `Variable` implementation for `mode`, allocating every field with its own mode. If
+/// `delegate` is given, the implementation forwards to the implementation for the `delegate` mode.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn variable_impl(
+    ident: &Ident,
+    type_generics: &TokenStream2,
+    generics: &Generics,
+    native: &Type,
+    compiler: &Type,
+    fields: &[Field],
+    mode: &TokenStream2,
+    delegate: Option<&TokenStream2>,
+) -> TokenStream2 {
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    for Field { ty, mode, .. } in fields {
+        match mode {
+            Mode::Constant => {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#ty: ::eclair::alloc::Constant<#compiler>));
+                where_clause.predicates.push(parse_quote!(
+                    <#ty as ::eclair::alloc::Constant<#compiler>>::Type: ::core::default::Default
+                ));
+            }
+            _ => {
+                let marker = mode.marker();
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#ty: ::eclair::alloc::Variable<#marker, #compiler>));
+            }
+        }
+    }
+    let (new_unknown, new_known) = match delegate {
+        Some(delegate) => (
+            quote!(<Self as ::eclair::alloc::Variable<#delegate, #compiler>>::new_unknown(compiler)),
+            quote!(
+                <Self as ::eclair::alloc::Variable<#delegate, #compiler>>::new_known(this, compiler)
+            ),
+        ),
+        _ => {
+            let unknown = fields.iter().map(|Field { member, ty, mode }| match mode {
+                Mode::Constant => quote!(
+                    #member: <#ty as ::eclair::alloc::Constant<#compiler>>::new_constant(
+                        &::core::default::Default::default(),
+                        compiler,
+                    )
+                ),
+                _ => {
+                    let marker = mode.marker();
+                    quote!(
+                        #member: <#ty as ::eclair::alloc::Variable<#marker, #compiler>>::new_unknown(
+                            compiler,
+                        )
+                    )
+                }
+            });
+            let known = fields.iter().map(|Field { member, ty, mode }| match mode {
+                Mode::Constant => quote!(
+                    #member: <#ty as ::eclair::alloc::Constant<#compiler>>::new_constant(
+                        &this.#member,
+                        compiler,
+                    )
+                ),
+                _ => {
+                    let marker = mode.marker();
+                    quote!(
+                        #member: <#ty as ::eclair::alloc::Variable<#marker, #compiler>>::new_known(
+                            &this.#member,
+                            compiler,
+                        )
+                    )
+                }
+            });
+            (
+                quote!({
+                    let _ = &compiler;
+                    Self { #(#unknown,)* }
+                }),
+                quote!({
+                    let _ = (this, &compiler);
+                    Self { #(#known,)* }
+                }),
+            )
+        }
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics ::eclair::alloc::Variable<#mode, #compiler> for #ident #type_generics
+        #where_clause
+        {
+            type Type = #native;
+
+            #[inline]
+            fn new_unknown(compiler: &mut #compiler) -> Self {
+                #new_unknown
+            }
+
+            #[inline]
+            fn new_known(this: &Self::Type, compiler: &mut #compiler) -> Self {
+                #new_known
+            }
+        }
+    )
+}
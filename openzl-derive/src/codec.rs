@@ -0,0 +1,452 @@
+//! `#[derive(Encode)]` and `#[derive(Decode)]` Derive Macros
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Fields, Generics, Ident,
+    Index, Lit, Meta, NestedMeta, Path, Result, Type,
+};
+
+/// Parsed `#[codec(...)]` Attribute Options
+#[derive(Default)]
+struct Options {
+    /// Path to the `openzl_util` Crate
+    krate: Option<Path>,
+
+    /// Version Byte
+    version: Option<u8>,
+
+    /// Variant Tag
+    tag: Option<u8>,
+
+    /// Skip Flag
+    skip: bool,
+}
+
+/// Attribute Location
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Location {
+    /// Container Attribute
+    Container,
+
+    /// Variant Attribute
+    Variant,
+
+    /// Field Attribute
+    Field,
+}
+
+impl Location {
+    /// Returns the error message for unsupported options at `self`.
+    #[inline]
+    fn expected(&self) -> &'static str {
+        match self {
+            Self::Container => "Expected `crate = \"...\"` or `version = ...`.",
+            Self::Variant => "Expected `tag = ...`.",
+            Self::Field => "Expected `skip`.",
+        }
+    }
+}
+
+impl Options {
+    /// Parses the options from the `#[codec(...)]` attributes in `attrs`, only allowing the `crate`
+    /// and `version` options on containers, the `tag` option on variants, and the `skip` option on
+    /// fields.
+    #[inline]
+    fn from_attributes(attrs: &[Attribute], location: Location) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "Expected `#[codec(...)]`.")),
+            };
+            for nested in list.nested {
+                match (&nested, location) {
+                    (NestedMeta::Meta(Meta::Path(path)), Location::Field)
+                        if path.is_ident("skip") =>
+                    {
+                        options.skip = true;
+                    }
+                    (NestedMeta::Meta(Meta::NameValue(pair)), Location::Container)
+                        if pair.path.is_ident("crate") =>
+                    {
+                        match &pair.lit {
+                            Lit::Str(value) => options.krate = Some(value.parse()?),
+                            lit => {
+                                return Err(Error::new_spanned(lit, "Expected a string literal."))
+                            }
+                        }
+                    }
+                    (NestedMeta::Meta(Meta::NameValue(pair)), Location::Container)
+                        if pair.path.is_ident("version") =>
+                    {
+                        options.version = Some(parse_byte(&pair.lit)?);
+                    }
+                    (NestedMeta::Meta(Meta::NameValue(pair)), Location::Variant)
+                        if pair.path.is_ident("tag") =>
+                    {
+                        options.tag = Some(parse_byte(&pair.lit)?);
+                    }
+                    _ => return Err(Error::new_spanned(nested, location.expected())),
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Parses a byte from the integer literal `lit`.
+#[inline]
+fn parse_byte(lit: &Lit) -> Result<u8> {
+    match lit {
+        Lit::Int(value) => value.base10_parse(),
+        lit => Err(Error::new_spanned(lit, "Expected an integer literal.")),
+    }
+}
+
+/// Encoded Field
+struct Field {
+    /// Field Member
+    member: TokenStream2,
+
+    /// Field Binding
+    binding: Ident,
+
+    /// Field Name used in Error Messages
+    name: String,
+
+    /// Field Type
+    ty: Type,
+
+    /// Skip Flag
+    skip: bool,
+}
+
+/// Parses the `fields` of a `struct` or variant, where `prefix` is prepended to the field names.
+#[inline]
+fn parse_fields(fields: &Fields, prefix: &str) -> Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (member, name) = match &field.ident {
+                Some(ident) => (quote!(#ident), ident.to_string()),
+                _ => {
+                    let index = Index::from(i);
+                    (quote!(#index), i.to_string())
+                }
+            };
+            Ok(Field {
+                member,
+                binding: format_ident!("__field{}", i),
+                name: format!("{prefix}{name}"),
+                ty: field.ty.clone(),
+                skip: Options::from_attributes(&field.attrs, Location::Field)?.skip,
+            })
+        })
+        .collect()
+}
+
+/// Encoded Variant
+struct Variant {
+    /// Variant Path
+    path: TokenStream2,
+
+    /// Variant Tag
+    tag: Option<u8>,
+
+    /// Variant Fields
+    fields: Vec<Field>,
+}
+
+/// Derived Container
+struct Container {
+    /// Container Identifier
+    ident: Ident,
+
+    /// Container Generics
+    generics: Generics,
+
+    /// Path to the `openzl_util` Crate
+    krate: Path,
+
+    /// Version Byte
+    version: Option<u8>,
+
+    /// Container Variants
+    ///
+    /// For `struct`s, this is a single untagged variant.
+    variants: Vec<Variant>,
+}
+
+impl Container {
+    /// Parses the container from `input`.
+    #[inline]
+    fn parse(input: DeriveInput) -> Result<Self> {
+        let DeriveInput {
+            attrs,
+            ident,
+            generics,
+            data,
+            ..
+        } = input;
+        let options = Options::from_attributes(&attrs, Location::Container)?;
+        let variants = match data {
+            Data::Struct(data) => vec![Variant {
+                path: quote!(Self),
+                tag: None,
+                fields: parse_fields(&data.fields, "")?,
+            }],
+            Data::Enum(data) => {
+                if data.variants.len() > usize::from(u8::MAX) + 1 {
+                    return Err(Error::new(
+                        ident.span(),
+                        "Enums with more than 256 variants are not supported.",
+                    ));
+                }
+                let mut variants = Vec::with_capacity(data.variants.len());
+                for (i, variant) in data.variants.iter().enumerate() {
+                    let tag = Options::from_attributes(&variant.attrs, Location::Variant)?
+                        .tag
+                        .unwrap_or(i as u8);
+                    if variants.iter().any(|v: &Variant| v.tag == Some(tag)) {
+                        return Err(Error::new_spanned(
+                            &variant.ident,
+                            format!("Duplicate variant tag `{tag}`."),
+                        ));
+                    }
+                    let name = &variant.ident;
+                    variants.push(Variant {
+                        path: quote!(Self::#name),
+                        tag: Some(tag),
+                        fields: parse_fields(&variant.fields, &format!("{name}."))?,
+                    });
+                }
+                variants
+            }
+            Data::Union(_) => {
+                return Err(Error::new(
+                    ident.span(),
+                    "`Encode` and `Decode` cannot be derived for `union`s.",
+                ))
+            }
+        };
+        Ok(Self {
+            ident,
+            generics,
+            krate: options.krate.unwrap_or_else(|| parse_quote!(::openzl_util)),
+            version: options.version,
+            variants,
+        })
+    }
+
+    /// Returns an iterator over all the fields of `self`.
+    #[inline]
+    fn fields(&self) -> impl Iterator<Item = &Field> {
+        self.variants.iter().flat_map(|variant| &variant.fields)
+    }
+}
+
+/// Transforms `input` according to the `Encode` macro definition.
+#[inline]
+pub fn transform_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Container::parse(input).map(|container| expand_encode(&container)) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+/// Transforms `input` according to the `Decode` macro definition.
+#[inline]
+pub fn transform_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Container::parse(input).map(|container| expand_decode(&container)) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+/// Expands the `Encode` implementation for `container`.
+#[inline]
+fn expand_encode(container: &Container) -> TokenStream2 {
+    let Container {
+        ident,
+        generics,
+        krate,
+        version,
+        variants,
+    } = container;
+    let codec = quote!(#krate::codec);
+    let writer = Ident::new("__W", Span::call_site());
+    let (_, type_generics, _) = generics.split_for_impl();
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    for Field { ty, .. } in container.fields().filter(|field| !field.skip) {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: #codec::Encode));
+    }
+    let version_len = version.map(|version| quote!(+ #codec::Encode::encoded_len(&#version)));
+    let version = version.map(|version| quote!(#codec::Encode::encode(&#version, &mut writer)?;));
+    let arms = variants.iter().map(|Variant { path, tag, fields }| {
+        let fields = fields.iter().filter(|field| !field.skip);
+        let members = fields.clone().map(|field| &field.member);
+        let bindings = fields.clone().map(|field| &field.binding);
+        let encodings = fields
+            .map(|Field { binding, .. }| quote!(#codec::Encode::encode(#binding, &mut writer)?;));
+        let tag = tag.map(|tag| quote!(#codec::Encode::encode(&#tag, &mut writer)?;));
+        quote!(
+            #path { #(#members: #bindings,)* .. } => {
+                #tag
+                #(#encodings)*
+            }
+        )
+    });
+    let len_arms = variants.iter().map(|Variant { path, tag, fields }| {
+        let fields = fields.iter().filter(|field| !field.skip);
+        let members = fields.clone().map(|field| &field.member);
+        let bindings = fields.clone().map(|field| &field.binding);
+        let lengths =
+            fields.map(|Field { binding, .. }| quote!(+ #codec::Encode::encoded_len(#binding)));
+        let tag = tag.map(|tag| quote!(+ #codec::Encode::encoded_len(&#tag)));
+        quote!(
+            #path { #(#members: #bindings,)* .. } => 0usize #tag #(#lengths)*,
+        )
+    });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics #codec::Encode for #ident #type_generics
+        #where_clause
+        {
+            #[allow(unreachable_code)]
+            #[inline]
+            fn encode<#writer>(&self, mut writer: #writer) -> ::core::result::Result<(), #writer::Error>
+            where
+                #writer: #codec::Write,
+            {
+                let _ = &mut writer;
+                #version
+                match self {
+                    #(#arms)*
+                }
+                Ok(())
+            }
+
+            #[allow(unreachable_code)]
+            #[inline]
+            fn encoded_len(&self) -> usize {
+                0usize #version_len + match self {
+                    #(#len_arms)*
+                }
+            }
+        }
+    )
+}
+
+/// Expands the `Decode` implementation for `container`.
+#[inline]
+fn expand_decode(container: &Container) -> TokenStream2 {
+    let Container {
+        ident,
+        generics,
+        krate,
+        version,
+        variants,
+    } = container;
+    let codec = quote!(#krate::codec);
+    let reader = Ident::new("__R", Span::call_site());
+    let (_, type_generics, _) = generics.split_for_impl();
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    for Field { ty, skip, .. } in container.fields() {
+        where_clause.predicates.push(if *skip {
+            parse_quote!(#ty: ::core::default::Default)
+        } else {
+            parse_quote!(#ty: #codec::Decode)
+        });
+    }
+    let read_byte = quote!(
+        <u8 as #codec::Decode>::decode(&mut reader).map_err(|err| {
+            err.map_decode(|_| #codec::DerivedDecodeError::MissingByte)
+        })?
+    );
+    let version = version.map(|version| {
+        quote!(
+            match #read_byte {
+                #version => {}
+                version => {
+                    return Err(#codec::DecodeError::Decode(
+                        #codec::DerivedDecodeError::InvalidVersion(version),
+                    ))
+                }
+            }
+        )
+    });
+    let constructors = variants.iter().map(|Variant { path, tag, fields }| {
+        let decodings = fields.iter().map(
+            |Field {
+                 member,
+                 name,
+                 ty,
+                 skip,
+                 ..
+             }| {
+                if *skip {
+                    quote!(#member: ::core::default::Default::default())
+                } else {
+                    quote!(
+                        #member: <#ty as #codec::Decode>::decode(&mut reader).map_err(|err| {
+                            err.map_decode(|_| #codec::DerivedDecodeError::Field(#name))
+                        })?
+                    )
+                }
+            },
+        );
+        (tag, quote!(#path { #(#decodings,)* }))
+    });
+    let body = match variants.first() {
+        Some(Variant { tag: None, .. }) => {
+            let (_, constructor) = constructors
+                .into_iter()
+                .next()
+                .expect("Structs have exactly one variant.");
+            quote!(Ok(#constructor))
+        }
+        _ => {
+            let arms = constructors.map(|(tag, constructor)| {
+                let tag = tag.expect("Enum variants are always tagged.");
+                quote!(#tag => Ok(#constructor),)
+            });
+            quote!(
+                match #read_byte {
+                    #(#arms)*
+                    tag => Err(#codec::DecodeError::Decode(
+                        #codec::DerivedDecodeError::InvalidTag(tag),
+                    )),
+                }
+            )
+        }
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics #codec::Decode for #ident #type_generics
+        #where_clause
+        {
+            type Error = #codec::DerivedDecodeError;
+
+            #[inline]
+            fn decode<#reader>(
+                mut reader: #reader,
+            ) -> ::core::result::Result<Self, #codec::DecodeError<#reader::Error, Self::Error>>
+            where
+                #reader: #codec::Read,
+            {
+                let _ = &mut reader;
+                #version
+                #body
+            }
+        }
+    )
+}
@@ -0,0 +1,92 @@
+//! `#[derive(ConditionalSelect)]` Derive Macro
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Ident, Index,
+};
+
+/// Compiler Type Parameter Name
+const COMPILER: &str = "COM";
+
+/// Transforms `input` according to the macro definition.
+#[inline]
+pub fn transform(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident,
+        mut generics,
+        data,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
+    let fields = match data {
+        Data::Struct(data) => data.fields,
+        _ => {
+            return Error::new(
+                ident.span(),
+                "`ConditionalSelect` can only be derived for `struct`s.",
+            )
+            .into_compile_error()
+            .into()
+        }
+    };
+    let (_, type_generics, _) = generics.split_for_impl();
+    let type_generics = quote!(#type_generics);
+    let compiler = Ident::new(COMPILER, Span::call_site());
+    let has_compiler = generics.params.iter().any(|param| match param {
+        GenericParam::Type(param) => param.ident == compiler,
+        _ => false,
+    });
+    if !has_compiler {
+        generics.params.push(parse_quote!(#compiler));
+    }
+    let where_clause = generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote!(#compiler: ::eclair::Has<bool> + ?Sized));
+    for field in fields.iter() {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::eclair::bool::ConditionalSelect<#compiler>));
+    }
+    let selections = fields.iter().enumerate().map(|(i, field)| {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            _ => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+        let ty = &field.ty;
+        quote!(
+            #member: <#ty as ::eclair::bool::ConditionalSelect<#compiler>>::select(
+                bit,
+                &true_value.#member,
+                &false_value.#member,
+                compiler,
+            )
+        )
+    });
+    let body = match fields {
+        Fields::Unit => quote!(Self),
+        _ => quote!(Self { #(#selections,)* }),
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    TokenStream::from(quote!(
+        impl #impl_generics ::eclair::bool::ConditionalSelect<#compiler> for #ident #type_generics
+        #where_clause
+        {
+            #[inline]
+            fn select(
+                bit: &::eclair::bool::Bool<#compiler>,
+                true_value: &Self,
+                false_value: &Self,
+                compiler: &mut #compiler,
+            ) -> Self {
+                let _ = (bit, true_value, false_value, &compiler);
+                #body
+            }
+        }
+    ))
+}
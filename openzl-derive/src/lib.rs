@@ -6,7 +6,41 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
+mod allocation;
+mod codec;
 mod component;
+mod conditional_select;
+mod proof_input;
+
+/// Derives [`Constant`] and [`Variable`] for `struct`s of allocatable fields.
+///
+/// # Attributes
+///
+/// The underlying type is given by `#[allocation(type = "...")]` and must have the same fields as
+/// the deriving `struct`. The compiler type can be fixed with `#[allocation(compiler = "...")]`,
+/// otherwise the type parameter named `COM` is used if the `struct` has one, or a new one is
+/// introduced. Extra where predicates can be added with `#[allocation(bound = "...")]`.
+///
+/// Every field must be annotated with its allocation mode, one of `#[constant]`, `#[public]`, or
+/// `#[secret]`. Fields are allocated in declaration order.
+///
+/// # Implementations
+///
+/// This macro generates the following implementations:
+///
+/// - [`Constant`] allocating every field as a constant,
+/// - [`Variable<Derived>`](Variable) allocating every field with its annotated mode, where
+///   `#[constant]` fields are allocated from the default value of their underlying type during
+///   unknown allocation,
+/// - [`Variable<Public>`](Variable) or [`Variable<Secret>`](Variable) if all the non-constant
+///   fields share the same allocation mode.
+///
+/// [`Constant`]: https://docs.rs/eclair/latest/eclair/alloc/trait.Constant.html
+/// [`Variable`]: https://docs.rs/eclair/latest/eclair/alloc/trait.Variable.html
+#[proc_macro_derive(Allocation, attributes(allocation, constant, public, secret))]
+pub fn allocation(input: TokenStream) -> TokenStream {
+    allocation::transform(input)
+}
 
 /// Defines a _component type_.
 ///
@@ -17,3 +51,65 @@ mod component;
 pub fn component(args: TokenStream, input: TokenStream) -> TokenStream {
     component::transform(args, input)
 }
+
+/// Derives [`Decode`] for `struct`s and `enum`s whose fields all implement [`Decode`].
+///
+/// This macro reads the canonical encoding written by `#[derive(Encode)]`. See [`macro@Encode`]
+/// for the encoding format and the supported attributes. The generated implementation uses
+/// [`DerivedDecodeError`] as its error type, rejecting unknown version bytes and variant tags.
+///
+/// [`Decode`]: https://docs.rs/openzl-util/latest/openzl_util/codec/trait.Decode.html
+/// [`DerivedDecodeError`]: https://docs.rs/openzl-util/latest/openzl_util/codec/enum.DerivedDecodeError.html
+#[proc_macro_derive(Decode, attributes(codec))]
+pub fn decode(input: TokenStream) -> TokenStream {
+    codec::transform_decode(input)
+}
+
+/// Derives [`Encode`] for `struct`s and `enum`s whose fields all implement [`Encode`].
+///
+/// # Encoding
+///
+/// Fields are encoded in declaration order using their own [`Encode`] implementations, so
+/// collections like `Vec<T>` are length-prefixed. The encoding of an `enum` starts with a one byte
+/// variant tag, which defaults to the index of the variant. The generated implementation also
+/// overrides [`encoded_len`] with the sum of the encoded lengths of the version, tag, and fields.
+///
+/// # Attributes
+///
+/// - `#[codec(version = N)]` on the container writes the version byte `N` before everything else.
+/// - `#[codec(crate = "...")]` on the container changes the path to the `openzl_util` crate.
+/// - `#[codec(tag = N)]` on a variant overrides its variant tag.
+/// - `#[codec(skip)]` on a field leaves it out of the encoding. Skipped fields are decoded with
+///   their [`Default`] value.
+///
+/// [`Encode`]: https://docs.rs/openzl-util/latest/openzl_util/codec/trait.Encode.html
+/// [`encoded_len`]: https://docs.rs/openzl-util/latest/openzl_util/codec/trait.Encode.html#method.encoded_len
+#[proc_macro_derive(Encode, attributes(codec))]
+pub fn encode(input: TokenStream) -> TokenStream {
+    codec::transform_encode(input)
+}
+
+/// Derives [`ConditionalSelect`] for `struct`s whose fields all implement [`ConditionalSelect`].
+///
+/// The selection is performed field by field using the same bit. The compiler type is taken from
+/// the type parameter named `COM` if the `struct` has one, otherwise the implementation is generic
+/// over all compilers for which the fields implement [`ConditionalSelect`].
+///
+/// [`ConditionalSelect`]: https://docs.rs/eclair/latest/eclair/bool/trait.ConditionalSelect.html
+#[proc_macro_derive(ConditionalSelect)]
+pub fn conditional_select(input: TokenStream) -> TokenStream {
+    conditional_select::transform(input)
+}
+
+/// Derives [`Input`] for `struct`s whose fields can extend the public input of a proof system.
+///
+/// The public input is extended with the fields in declaration order, so the order of the input
+/// only changes if the order of the fields changes. Fields annotated with `#[proof_input(skip)]`
+/// are not part of the public input. The path to the `openzl_crypto` crate can be changed with
+/// `#[proof_input(crate = "...")]` on the `struct`.
+///
+/// [`Input`]: https://docs.rs/openzl-crypto/latest/openzl_crypto/constraint/trait.Input.html
+#[proc_macro_derive(ProofInput, attributes(proof_input))]
+pub fn proof_input(input: TokenStream) -> TokenStream {
+    proof_input::transform(input)
+}
@@ -0,0 +1,146 @@
+//! `#[derive(ProofInput)]` Derive Macro
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Ident, Index, Lit, Meta,
+    NestedMeta, Path, Result,
+};
+
+/// Proof System Type Parameter Name
+const PROOF_SYSTEM: &str = "__P";
+
+/// Parsed `#[proof_input(...)]` Attribute Options
+#[derive(Default)]
+struct Options {
+    /// Path to the `openzl_crypto` Crate
+    krate: Option<Path>,
+
+    /// Skip Flag
+    skip: bool,
+}
+
+impl Options {
+    /// Parses the options from the `#[proof_input(...)]` attributes in `attrs`, only allowing the
+    /// `crate` option on containers and the `skip` option on fields.
+    #[inline]
+    fn from_attributes(attrs: &[Attribute], is_field: bool) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("proof_input"))
+        {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "Expected `#[proof_input(...)]`.")),
+            };
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if is_field && path.is_ident("skip") => {
+                        options.skip = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(pair))
+                        if !is_field && pair.path.is_ident("crate") =>
+                    {
+                        match &pair.lit {
+                            Lit::Str(value) => options.krate = Some(value.parse()?),
+                            lit => {
+                                return Err(Error::new_spanned(lit, "Expected a string literal."))
+                            }
+                        }
+                    }
+                    nested if is_field => {
+                        return Err(Error::new_spanned(nested, "Expected `skip`."));
+                    }
+                    nested => {
+                        return Err(Error::new_spanned(nested, "Expected `crate = \"...\"`."));
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Transforms `input` according to the macro definition.
+#[inline]
+pub fn transform(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+/// Expands the `Input` implementation for `input`.
+#[inline]
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let DeriveInput {
+        attrs,
+        ident,
+        mut generics,
+        data,
+        ..
+    } = input;
+    let fields = match data {
+        Data::Struct(data) => data.fields,
+        _ => {
+            return Err(Error::new(
+                ident.span(),
+                "`ProofInput` can only be derived for `struct`s.",
+            ))
+        }
+    };
+    let krate = Options::from_attributes(&attrs, false)?
+        .krate
+        .unwrap_or_else(|| parse_quote!(::openzl_crypto));
+    let mut members = Vec::new();
+    let mut types = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        if Options::from_attributes(&field.attrs, true)?.skip {
+            continue;
+        }
+        members.push(match &field.ident {
+            Some(ident) => quote!(#ident),
+            _ => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        });
+        types.push(&field.ty);
+    }
+    let (_, type_generics, _) = generics.split_for_impl();
+    let type_generics = quote!(#type_generics);
+    let proof_system = Ident::new(PROOF_SYSTEM, Span::call_site());
+    generics.params.push(parse_quote!(#proof_system));
+    let where_clause = generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(parse_quote!(#proof_system: #krate::constraint::ProofSystem + ?Sized));
+    for ty in &types {
+        where_clause
+            .predicates
+            .push(parse_quote!(#proof_system: #krate::constraint::HasInput<#ty>));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote!(
+        impl #impl_generics #krate::constraint::Input<#proof_system> for #ident #type_generics
+        #where_clause
+        {
+            #[inline]
+            fn extend(
+                &self,
+                input: &mut <#proof_system as #krate::constraint::ProofSystem>::Input,
+            ) {
+                let _ = &input;
+                #(
+                    <#proof_system as #krate::constraint::HasInput<#types>>::extend(
+                        input,
+                        &self.#members,
+                    );
+                )*
+            }
+        }
+    ))
+}
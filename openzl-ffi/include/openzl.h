@@ -0,0 +1,77 @@
+/*
+ * OpenZL C Foreign Function Interface
+ *
+ * Field elements are encoded as OZL_FIELD_ELEMENT_BYTES little-endian bytes and sequences of
+ * field elements are concatenated. Keys and proofs use the compressed arkworks canonical encoding.
+ * Buffers returned by this library must be released with ozl_buffer_free and handles with
+ * ozl_prover_free and ozl_verifier_free.
+ */
+
+#ifndef OPENZL_H
+#define OPENZL_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define OZL_ABI_VERSION 1
+#define OZL_FIELD_ELEMENT_BYTES 32
+
+/* Fixed Circuits */
+#define OZL_CIRCUIT_POSEIDON_PREIMAGE 0
+#define OZL_CIRCUIT_RANGE_COMMITMENT 1
+
+/* Status Codes */
+typedef enum ozl_status {
+    OZL_STATUS_OK = 0,
+    OZL_STATUS_NULL_POINTER = 1,
+    OZL_STATUS_UNKNOWN_CIRCUIT = 2,
+    OZL_STATUS_INVALID_LENGTH = 3,
+    OZL_STATUS_INVALID_FIELD_ELEMENT = 4,
+    OZL_STATUS_INVALID_ENCODING = 5,
+    OZL_STATUS_UNSATISFIED_WITNESS = 6,
+    OZL_STATUS_PROOF_SYSTEM = 7,
+    OZL_STATUS_PANIC = 8,
+} ozl_status;
+
+/* Byte Buffer */
+typedef struct ozl_buffer {
+    uint8_t *data;
+    size_t len;
+} ozl_buffer;
+
+/* Opaque Handles */
+typedef struct ozl_prover ozl_prover;
+typedef struct ozl_verifier ozl_verifier;
+
+uint32_t ozl_abi_version(void);
+
+void ozl_buffer_free(ozl_buffer buffer);
+
+ozl_status ozl_setup(uint32_t circuit_id, ozl_buffer *proving_key, ozl_buffer *verifying_key);
+
+ozl_status ozl_prover_load(uint32_t circuit_id, const uint8_t *data, size_t len,
+                           ozl_prover **prover);
+
+void ozl_prover_free(ozl_prover *prover);
+
+ozl_status ozl_verifier_load(uint32_t circuit_id, const uint8_t *data, size_t len,
+                             ozl_verifier **verifier);
+
+void ozl_verifier_free(ozl_verifier *verifier);
+
+ozl_status ozl_prove(const ozl_prover *prover, const uint8_t *witness, size_t witness_len,
+                     ozl_buffer *proof, ozl_buffer *input);
+
+ozl_status ozl_verify(const ozl_verifier *verifier, const uint8_t *input, size_t input_len,
+                      const uint8_t *proof, size_t proof_len, bool *valid);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OPENZL_H */
@@ -0,0 +1,155 @@
+//! Fixed Circuits
+//!
+//! Every circuit exposed over the foreign function interface is identified by a stable numeric
+//! identifier and takes its witness and public input as sequences of BN254 scalar field elements.
+//! Both circuits hash with the arity-two Poseidon permutation, whose parameters are generated
+//! deterministically, so that keys generated by one caller can be used by any other caller.
+
+use arkworks::{
+    bn254::{Bn254, Fr},
+    constraint::{fp::Fp, FpVar, R1CS},
+    groth16::Groth16,
+    poseidon::{Spec, TwoPowerMinusOneDomainTag},
+};
+use eclair::{
+    alloc::{
+        mode::{Public, Secret},
+        Allocate, Allocator,
+    },
+    bool::AssertEq,
+    num::AssertWithinBitRange,
+};
+use openzl_crypto::{
+    constraint::ProofSystem,
+    hash::ArrayHashFunction,
+    poseidon::{hash::Hasher, Permutation},
+};
+
+/// Poseidon Hasher over BN254 with Arity Two
+pub type Poseidon2<COM = ()> = Hasher<Spec<Fr, 2>, TwoPowerMinusOneDomainTag, 2, COM>;
+
+/// Proof System
+pub type ProofSystemType = Groth16<Bn254>;
+
+/// Field Element
+pub type Field = Fp<Fr>;
+
+/// Number of Bits of the Value in the [`RangeCommitment`](Circuit::RangeCommitment) Circuit
+pub const RANGE_BITS: usize = 64;
+
+/// Returns the Poseidon hasher shared by all the circuits.
+#[inline]
+pub fn hasher() -> Poseidon2 {
+    Poseidon2::from_permutation(Permutation::generate())
+}
+
+/// Fixed Circuit
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Circuit {
+    /// Poseidon Preimage
+    ///
+    /// Proves knowledge of a secret `(left, right)` pair whose Poseidon hash is the public input.
+    PoseidonPreimage,
+
+    /// Range Commitment
+    ///
+    /// Proves that the secret `value` of the public Poseidon commitment to `(value, blinding)` is
+    /// smaller than `2^RANGE_BITS`.
+    RangeCommitment,
+}
+
+impl Circuit {
+    /// Returns the circuit with the given stable `id`.
+    #[inline]
+    pub fn from_id(id: u32) -> Option<Self> {
+        match id {
+            0 => Some(Self::PoseidonPreimage),
+            1 => Some(Self::RangeCommitment),
+            _ => None,
+        }
+    }
+
+    /// Returns the stable identifier of `self`.
+    #[inline]
+    pub fn id(self) -> u32 {
+        match self {
+            Self::PoseidonPreimage => 0,
+            Self::RangeCommitment => 1,
+        }
+    }
+
+    /// Returns the number of field elements in the witness of `self`.
+    #[inline]
+    pub fn witness_len(self) -> usize {
+        2
+    }
+
+    /// Returns the number of field elements in the public input of `self`.
+    #[inline]
+    pub fn input_len(self) -> usize {
+        1
+    }
+
+    /// Computes the public input of `self` for `witness` with `hasher`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `witness` does not have [`witness_len`](Self::witness_len) elements.
+    #[inline]
+    pub fn public_input(self, hasher: &Poseidon2, witness: &[Field]) -> Vec<Field> {
+        assert_eq!(witness.len(), self.witness_len(), "Invalid witness length.");
+        vec![hasher.hash([&witness[0], &witness[1]], &mut ())]
+    }
+
+    /// Builds the constraints of `self` into `compiler`, allocating known variables if `assignment`
+    /// holds the public input and witness and unknown variables otherwise.
+    #[inline]
+    pub fn build(
+        self,
+        hasher: &Poseidon2,
+        assignment: Option<(&[Field], &[Field])>,
+        compiler: &mut R1CS<Fr>,
+    ) {
+        let hasher: Poseidon2<R1CS<Fr>> = hasher.as_constant(compiler);
+        let (output, left, right): (FpVar<Fr>, FpVar<Fr>, FpVar<Fr>) = match assignment {
+            Some((input, witness)) => (
+                input[0].as_known::<Public, _>(compiler),
+                witness[0].as_known::<Secret, _>(compiler),
+                witness[1].as_known::<Secret, _>(compiler),
+            ),
+            _ => (
+                compiler.allocate_unknown::<Public, _>(),
+                compiler.allocate_unknown::<Secret, _>(),
+                compiler.allocate_unknown::<Secret, _>(),
+            ),
+        };
+        if self == Self::RangeCommitment {
+            AssertWithinBitRange::<FpVar<Fr>, RANGE_BITS>::assert_within_range(compiler, &left);
+        }
+        let digest = hasher.hash([&left, &right], compiler);
+        compiler.assert_eq(&digest, &output);
+    }
+
+    /// Returns a compiler with the unknown constraints of `self` for key generation.
+    #[inline]
+    pub fn context_compiler(self, hasher: &Poseidon2) -> R1CS<Fr> {
+        openzl_util::trace_span!("openzl::ffi::constraints", circuit = self.id());
+        let mut compiler = ProofSystemType::context_compiler();
+        self.build(hasher, None, &mut compiler);
+        compiler
+    }
+
+    /// Returns a compiler with the constraints of `self` over the known `input` and `witness`.
+    #[inline]
+    pub fn proof_compiler(
+        self,
+        hasher: &Poseidon2,
+        input: &[Field],
+        witness: &[Field],
+    ) -> R1CS<Fr> {
+        openzl_util::trace_span!("openzl::ffi::synthesize", circuit = self.id());
+        let mut compiler = ProofSystemType::proof_compiler();
+        self.build(hasher, Some((input, witness)), &mut compiler);
+        compiler
+    }
+}
@@ -0,0 +1,84 @@
+//! Byte Encodings
+//!
+//! Values cross the C interface of this crate and the WebAssembly bindings built on top of it as
+//! byte strings. Field elements are encoded in their canonical little-endian form of
+//! [`FIELD_ELEMENT_BYTES`] bytes and sequences of field elements are concatenated. Keys and proofs
+//! use the compressed `arkworks` canonical encoding and are checked for validity when decoded.
+
+use crate::{circuit::Field, FIELD_ELEMENT_BYTES};
+use arkworks::serialize::{CanonicalDeserialize, CanonicalSerialize};
+use openzl_crypto::field::convert::CanonicalBytes;
+
+/// Decoding Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// A byte string does not have the length expected for its contents.
+    InvalidLength,
+
+    /// A byte string does not encode a canonical field element.
+    InvalidFieldElement,
+
+    /// A key or proof could not be decoded.
+    InvalidEncoding,
+}
+
+/// Decodes exactly `count` field elements from `bytes`.
+#[inline]
+pub fn field_elements(bytes: &[u8], count: usize) -> Result<Vec<Field>, Error> {
+    if bytes.len() != count * FIELD_ELEMENT_BYTES {
+        return Err(Error::InvalidLength);
+    }
+    bytes
+        .chunks(FIELD_ELEMENT_BYTES)
+        .map(|chunk| Field::from_canonical_bytes(chunk).map_err(|_| Error::InvalidFieldElement))
+        .collect()
+}
+
+/// Decodes a nonempty sequence of batches of `count` field elements each from `bytes`.
+#[inline]
+pub fn field_element_batches(bytes: &[u8], count: usize) -> Result<Vec<Vec<Field>>, Error> {
+    let width = count * FIELD_ELEMENT_BYTES;
+    if width == 0 || bytes.is_empty() || bytes.len() % width != 0 {
+        return Err(Error::InvalidLength);
+    }
+    bytes
+        .chunks(width)
+        .map(|batch| field_elements(batch, count))
+        .collect()
+}
+
+/// Encodes `elements` as the concatenation of their canonical byte strings.
+#[inline]
+pub fn field_bytes(elements: &[Field]) -> Vec<u8> {
+    elements
+        .iter()
+        .flat_map(CanonicalBytes::to_canonical_bytes)
+        .collect()
+}
+
+/// Decodes a value of type `T` which spans all of `bytes` with the compressed canonical encoding,
+/// checking that the decoded value is valid.
+#[inline]
+pub fn canonical<T>(mut bytes: &[u8]) -> Result<T, Error>
+where
+    T: CanonicalDeserialize,
+{
+    let value = T::deserialize(&mut bytes).map_err(|_| Error::InvalidEncoding)?;
+    if !bytes.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    Ok(value)
+}
+
+/// Encodes `value` with the compressed canonical encoding.
+#[inline]
+pub fn to_canonical<T>(value: &T) -> Vec<u8>
+where
+    T: CanonicalSerialize,
+{
+    let mut buffer = Vec::with_capacity(value.serialized_size());
+    value
+        .serialize(&mut buffer)
+        .expect("Serialization is not allowed to fail.");
+    buffer
+}
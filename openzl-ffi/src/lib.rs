@@ -0,0 +1,556 @@
+//! OpenZL C Foreign Function Interface
+//!
+//! This crate exposes key generation, key loading, proving, and verification for the fixed set of
+//! [`Circuit`]s over a C ABI. All values cross the boundary as byte strings:
+//!
+//! - Field elements are encoded in their canonical little-endian form of [`FIELD_ELEMENT_BYTES`]
+//!   bytes, and sequences of field elements are concatenated.
+//! - Proving keys, verifying keys, and proofs use the compressed `arkworks` canonical encoding.
+//!   They are decoded with all validity checks, so encodings of points which are not on their
+//!   curve or not in its prime-order subgroup are rejected with [`Status::InvalidEncoding`].
+//!
+//! The [`encoding`] module implements these encodings.
+//!
+//! Every function returns a [`Status`] code and writes its results through output pointers. Bytes
+//! returned in a [`Buffer`] are owned by the caller and must be released with [`ozl_buffer_free`],
+//! and handles must be released with [`ozl_prover_free`] and [`ozl_verifier_free`]. The C
+//! declarations are in `include/openzl.h`.
+
+#![cfg_attr(doc_cfg, feature(doc_cfg))]
+#![forbid(rustdoc::broken_intra_doc_links)]
+#![forbid(missing_docs)]
+
+use arkworks::{
+    bn254::Bn254,
+    groth16::{Proof, ProvingContext, VerifyingContext, VerifyingKey},
+};
+use core::{ptr, slice};
+use openzl_crypto::{constraint::ProofSystem, field::convert::CanonicalBytes};
+use openzl_util::rand::OsRng;
+use std::panic::{self, AssertUnwindSafe};
+
+pub mod circuit;
+pub mod encoding;
+
+#[doc(inline)]
+pub use circuit::Circuit;
+
+use circuit::{Field, Poseidon2, ProofSystemType};
+use encoding::{canonical, field_bytes, field_elements, to_canonical};
+
+/// ABI Version
+///
+/// This version is incremented whenever the signature of an exported function or the layout of an
+/// exported type changes.
+pub const ABI_VERSION: u32 = 1;
+
+/// Number of Bytes in an Encoded Field Element
+pub const FIELD_ELEMENT_BYTES: usize = <Field as CanonicalBytes>::BYTES;
+
+/// Status Code
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum Status {
+    /// Success
+    Ok = 0,
+
+    /// A required pointer argument was null.
+    NullPointer = 1,
+
+    /// The circuit identifier does not name a known circuit.
+    UnknownCircuit = 2,
+
+    /// A byte string does not have the length expected for its contents.
+    InvalidLength = 3,
+
+    /// A byte string does not encode a canonical field element.
+    InvalidFieldElement = 4,
+
+    /// A key or proof could not be decoded.
+    InvalidEncoding = 5,
+
+    /// The witness does not satisfy the constraints of the circuit.
+    UnsatisfiedWitness = 6,
+
+    /// The proof system returned an error.
+    ProofSystem = 7,
+
+    /// The library panicked while handling the call.
+    Panic = 8,
+}
+
+impl From<encoding::Error> for Status {
+    #[inline]
+    fn from(error: encoding::Error) -> Self {
+        match error {
+            encoding::Error::InvalidLength => Self::InvalidLength,
+            encoding::Error::InvalidFieldElement => Self::InvalidFieldElement,
+            encoding::Error::InvalidEncoding => Self::InvalidEncoding,
+        }
+    }
+}
+
+/// Byte Buffer
+///
+/// Buffers are allocated by this library and handed to the caller, who must release them with
+/// [`ozl_buffer_free`].
+#[derive(Debug)]
+#[repr(C)]
+pub struct Buffer {
+    /// Pointer to the First Byte
+    pub data: *mut u8,
+
+    /// Number of Bytes
+    pub len: usize,
+}
+
+impl Buffer {
+    /// Returns the empty buffer.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+}
+
+impl From<Vec<u8>> for Buffer {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        Self {
+            data: Box::into_raw(bytes.into_boxed_slice()).cast(),
+            len,
+        }
+    }
+}
+
+/// Prover Handle
+///
+/// A proving key loaded for one of the fixed circuits.
+#[derive(Debug)]
+pub struct Prover {
+    /// Circuit
+    circuit: Circuit,
+
+    /// Poseidon Hasher
+    hasher: Poseidon2,
+
+    /// Proving Context
+    context: ProvingContext<Bn254>,
+}
+
+/// Verifier Handle
+///
+/// A verifying key loaded for one of the fixed circuits.
+#[derive(Debug)]
+pub struct Verifier {
+    /// Circuit
+    circuit: Circuit,
+
+    /// Verifying Context
+    context: VerifyingContext<Bn254>,
+}
+
+/// Runs `f`, converting its result and any panic into a [`Status`].
+#[inline]
+fn guard<F>(f: F) -> Status
+where
+    F: FnOnce() -> Result<(), Status>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => Status::Ok,
+        Ok(Err(status)) => status,
+        _ => Status::Panic,
+    }
+}
+
+/// Returns the circuit with the given `id`.
+#[inline]
+fn circuit(id: u32) -> Result<Circuit, Status> {
+    Circuit::from_id(id).ok_or(Status::UnknownCircuit)
+}
+
+/// Returns the byte slice of length `len` starting at `data`.
+///
+/// # Safety
+///
+/// If `len` is nonzero, `data` must either be null or valid for reads of `len` bytes.
+#[inline]
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], Status> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(Status::NullPointer),
+        _ => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+/// Writes `value` to `target`.
+///
+/// # Safety
+///
+/// The `target` pointer must either be null or valid for writes.
+#[inline]
+unsafe fn write<T>(target: *mut T, value: T) -> Result<(), Status> {
+    if target.is_null() {
+        return Err(Status::NullPointer);
+    }
+    target.write(value);
+    Ok(())
+}
+
+/// Returns the [`ABI_VERSION`] of this library.
+#[no_mangle]
+pub extern "C" fn ozl_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Releases the bytes held by `buffer`.
+///
+/// # Safety
+///
+/// The `buffer` must have been returned by this library and must not have been released already.
+#[no_mangle]
+pub unsafe extern "C" fn ozl_buffer_free(buffer: Buffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
+
+/// Generates a fresh proving key and verifying key for the circuit with the given `circuit_id`,
+/// writing their encodings to `proving_key` and `verifying_key`.
+///
+/// # Safety
+///
+/// The `proving_key` and `verifying_key` pointers must either be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ozl_setup(
+    circuit_id: u32,
+    proving_key: *mut Buffer,
+    verifying_key: *mut Buffer,
+) -> Status {
+    guard(|| {
+        if proving_key.is_null() || verifying_key.is_null() {
+            return Err(Status::NullPointer);
+        }
+        let circuit = circuit(circuit_id)?;
+        let (proving_context, verifying_context) = ProofSystemType::compile(
+            &(),
+            circuit.context_compiler(&circuit::hasher()),
+            &mut OsRng,
+        )
+        .map_err(|_| Status::ProofSystem)?;
+        write(proving_key, to_canonical(&proving_context).into())?;
+        write(verifying_key, to_canonical(&verifying_context.0.vk).into())
+    })
+}
+
+/// Loads the proving key encoded in the `len` bytes at `data` for the circuit with the given
+/// `circuit_id`, writing a new handle to `prover`.
+///
+/// # Safety
+///
+/// The `data` pointer must be valid for reads of `len` bytes and the `prover` pointer must either
+/// be null or valid for writes. The handle must be released with [`ozl_prover_free`].
+#[no_mangle]
+pub unsafe extern "C" fn ozl_prover_load(
+    circuit_id: u32,
+    data: *const u8,
+    len: usize,
+    prover: *mut *mut Prover,
+) -> Status {
+    guard(|| {
+        let circuit = circuit(circuit_id)?;
+        let context = canonical::<ProvingContext<Bn254>>(bytes(data, len)?)?;
+        write(
+            prover,
+            Box::into_raw(Box::new(Prover {
+                circuit,
+                hasher: circuit::hasher(),
+                context,
+            })),
+        )
+    })
+}
+
+/// Releases the `prover` handle.
+///
+/// # Safety
+///
+/// The `prover` handle must either be null or have been returned by [`ozl_prover_load`] and must
+/// not have been released already.
+#[no_mangle]
+pub unsafe extern "C" fn ozl_prover_free(prover: *mut Prover) {
+    if !prover.is_null() {
+        drop(Box::from_raw(prover));
+    }
+}
+
+/// Loads the verifying key encoded in the `len` bytes at `data` for the circuit with the given
+/// `circuit_id`, writing a new handle to `verifier`.
+///
+/// # Safety
+///
+/// The `data` pointer must be valid for reads of `len` bytes and the `verifier` pointer must
+/// either be null or valid for writes. The handle must be released with [`ozl_verifier_free`].
+#[no_mangle]
+pub unsafe extern "C" fn ozl_verifier_load(
+    circuit_id: u32,
+    data: *const u8,
+    len: usize,
+    verifier: *mut *mut Verifier,
+) -> Status {
+    guard(|| {
+        let circuit = circuit(circuit_id)?;
+        let verifying_key = canonical::<VerifyingKey<Bn254>>(bytes(data, len)?)?;
+        let context = VerifyingContext::from_verifying_key(&verifying_key);
+        if context.input_len() != circuit.input_len() {
+            return Err(Status::InvalidLength);
+        }
+        write(
+            verifier,
+            Box::into_raw(Box::new(Verifier { circuit, context })),
+        )
+    })
+}
+
+/// Releases the `verifier` handle.
+///
+/// # Safety
+///
+/// The `verifier` handle must either be null or have been returned by [`ozl_verifier_load`] and
+/// must not have been released already.
+#[no_mangle]
+pub unsafe extern "C" fn ozl_verifier_free(verifier: *mut Verifier) {
+    if !verifier.is_null() {
+        drop(Box::from_raw(verifier));
+    }
+}
+
+/// Proves the circuit of `prover` for the witness encoded in the `witness_len` bytes at `witness`,
+/// writing the encoded proof to `proof` and the encoded public input to `input`.
+///
+/// # Safety
+///
+/// The `prover` handle must either be null or be a live handle returned by [`ozl_prover_load`],
+/// the `witness` pointer must be valid for reads of `witness_len` bytes, and the `proof` and
+/// `input` pointers must either be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ozl_prove(
+    prover: *const Prover,
+    witness: *const u8,
+    witness_len: usize,
+    proof: *mut Buffer,
+    input: *mut Buffer,
+) -> Status {
+    guard(|| {
+        if proof.is_null() || input.is_null() {
+            return Err(Status::NullPointer);
+        }
+        let prover = prover.as_ref().ok_or(Status::NullPointer)?;
+        let circuit = prover.circuit;
+        let witness = field_elements(bytes(witness, witness_len)?, circuit.witness_len())?;
+        let public_input = circuit.public_input(&prover.hasher, &witness);
+        let compiler = circuit.proof_compiler(&prover.hasher, &public_input, &witness);
+        if !compiler.is_satisfied() {
+            return Err(Status::UnsatisfiedWitness);
+        }
+        let generated = ProofSystemType::prove(&prover.context, compiler, &mut OsRng)
+            .map_err(|_| Status::ProofSystem)?;
+        write(proof, to_canonical(&generated.0).into())?;
+        write(input, field_bytes(&public_input).into())
+    })
+}
+
+/// Verifies the proof encoded in the `proof_len` bytes at `proof` against the public input
+/// encoded in the `input_len` bytes at `input`, writing the result to `valid`.
+///
+/// # Safety
+///
+/// The `verifier` handle must either be null or be a live handle returned by
+/// [`ozl_verifier_load`], the `input` and `proof` pointers must be valid for reads of `input_len`
+/// and `proof_len` bytes, and the `valid` pointer must either be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ozl_verify(
+    verifier: *const Verifier,
+    input: *const u8,
+    input_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+    valid: *mut bool,
+) -> Status {
+    guard(|| {
+        let verifier = verifier.as_ref().ok_or(Status::NullPointer)?;
+        let input = field_elements(bytes(input, input_len)?, verifier.circuit.input_len())?
+            .into_iter()
+            .map(|element| element.0)
+            .collect::<Vec<_>>();
+        let proof = Proof(canonical(bytes(proof, proof_len)?)?);
+        let result = ProofSystemType::verify_proof(&verifier.context, &input, &proof)
+            .map_err(|_| Status::ProofSystem)?;
+        write(valid, result)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Takes the bytes out of `buffer` and releases it.
+    #[inline]
+    fn take(buffer: Buffer) -> Vec<u8> {
+        let bytes = unsafe { bytes(buffer.data, buffer.len) }
+            .expect("Buffers are never null.")
+            .to_vec();
+        unsafe { ozl_buffer_free(buffer) };
+        bytes
+    }
+
+    /// Encodes `values` as a sequence of field elements.
+    #[inline]
+    fn encode(values: &[u64]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| Field::from(u128::from(*value)).to_canonical_bytes())
+            .collect()
+    }
+
+    /// Runs key generation, key loading, proving, and verification for `circuit` and `witness`
+    /// through the C interface, returning the status of proving and the validity of the proof.
+    #[inline]
+    fn round_trip(circuit: Circuit, witness: &[u8]) -> (Status, bool) {
+        let (mut proving_key, mut verifying_key) = (Buffer::empty(), Buffer::empty());
+        assert_eq!(
+            unsafe { ozl_setup(circuit.id(), &mut proving_key, &mut verifying_key) },
+            Status::Ok
+        );
+        let (proving_key, verifying_key) = (take(proving_key), take(verifying_key));
+        let mut prover = ptr::null_mut();
+        assert_eq!(
+            unsafe {
+                ozl_prover_load(
+                    circuit.id(),
+                    proving_key.as_ptr(),
+                    proving_key.len(),
+                    &mut prover,
+                )
+            },
+            Status::Ok
+        );
+        let mut verifier = ptr::null_mut();
+        assert_eq!(
+            unsafe {
+                ozl_verifier_load(
+                    circuit.id(),
+                    verifying_key.as_ptr(),
+                    verifying_key.len(),
+                    &mut verifier,
+                )
+            },
+            Status::Ok
+        );
+        let (mut proof, mut input) = (Buffer::empty(), Buffer::empty());
+        let status = unsafe {
+            ozl_prove(
+                prover,
+                witness.as_ptr(),
+                witness.len(),
+                &mut proof,
+                &mut input,
+            )
+        };
+        let mut valid = false;
+        if status == Status::Ok {
+            let (proof, input) = (take(proof), take(input));
+            assert_eq!(
+                unsafe {
+                    ozl_verify(
+                        verifier,
+                        input.as_ptr(),
+                        input.len(),
+                        proof.as_ptr(),
+                        proof.len(),
+                        &mut valid,
+                    )
+                },
+                Status::Ok
+            );
+        }
+        unsafe {
+            ozl_prover_free(prover);
+            ozl_verifier_free(verifier);
+        }
+        (status, valid)
+    }
+
+    /// Tests that proofs for every fixed circuit verify through the C interface.
+    #[test]
+    fn fixed_circuits_prove_and_verify() {
+        for circuit in [Circuit::PoseidonPreimage, Circuit::RangeCommitment] {
+            assert_eq!(
+                round_trip(circuit, &encode(&[3, 5])),
+                (Status::Ok, true),
+                "Proofs for {circuit:?} should be valid."
+            );
+        }
+    }
+
+    /// Tests that keys which do not encode valid curve points are rejected when they are loaded.
+    #[test]
+    fn invalid_keys_are_rejected() {
+        let circuit = Circuit::PoseidonPreimage;
+        let (mut proving_key, mut verifying_key) = (Buffer::empty(), Buffer::empty());
+        assert_eq!(
+            unsafe { ozl_setup(circuit.id(), &mut proving_key, &mut verifying_key) },
+            Status::Ok
+        );
+        let (proving_key, verifying_key) = (take(proving_key), take(verifying_key));
+        let invalid = vec![0xff; proving_key.len()];
+        let mut prover = ptr::null_mut();
+        assert_eq!(
+            unsafe { ozl_prover_load(circuit.id(), invalid.as_ptr(), invalid.len(), &mut prover) },
+            Status::InvalidEncoding
+        );
+        let invalid = vec![0xff; verifying_key.len()];
+        let mut verifier = ptr::null_mut();
+        assert_eq!(
+            unsafe {
+                ozl_verifier_load(circuit.id(), invalid.as_ptr(), invalid.len(), &mut verifier)
+            },
+            Status::InvalidEncoding
+        );
+    }
+
+    /// Tests that witnesses which violate a circuit and malformed arguments are rejected with the
+    /// matching status codes.
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        let mut witness = encode(&[0, 5]);
+        witness[8] = 1;
+        assert_eq!(
+            round_trip(Circuit::RangeCommitment, &witness),
+            (Status::UnsatisfiedWitness, false)
+        );
+        assert_eq!(
+            round_trip(Circuit::PoseidonPreimage, &encode(&[3])),
+            (Status::InvalidLength, false)
+        );
+        assert_eq!(
+            round_trip(Circuit::PoseidonPreimage, &[0xff; 2 * FIELD_ELEMENT_BYTES]),
+            (Status::InvalidFieldElement, false)
+        );
+        let mut prover = ptr::null_mut();
+        assert_eq!(
+            unsafe { ozl_prover_load(u32::MAX, ptr::null(), 0, &mut prover) },
+            Status::UnknownCircuit
+        );
+        assert_eq!(
+            unsafe { ozl_verify(ptr::null(), ptr::null(), 0, ptr::null(), 0, ptr::null_mut()) },
+            Status::NullPointer
+        );
+    }
+}
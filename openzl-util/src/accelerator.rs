@@ -0,0 +1,123 @@
+//! Hardware Acceleration
+//!
+//! Expensive batched computations like multi-scalar multiplications and batched permutations are
+//! described as [`Job`]s which are submitted to an [`Accelerator`] and polled for their results.
+//! Integrators can implement [`Accelerator`] for GPU or other external backends, while the
+//! [`Cpu`] accelerator is the reference implementation which executes every job on submission.
+
+use core::{convert::Infallible, hint, task::Poll};
+
+/// Accelerator Job
+pub trait Job {
+    /// Job Output Type
+    type Output;
+}
+
+/// Natively Executable Job
+///
+/// This is the reference implementation of a [`Job`] which the [`Cpu`] accelerator runs.
+pub trait Execute: Job {
+    /// Executes `self` on the current thread, returning its output.
+    fn execute(self) -> Self::Output;
+}
+
+/// Accelerator
+pub trait Accelerator<J>
+where
+    J: Job,
+{
+    /// Job Handle Type
+    type Handle;
+
+    /// Error Type
+    type Error;
+
+    /// Submits `job` to the accelerator, returning a handle to poll for its output.
+    fn submit(&mut self, job: J) -> Result<Self::Handle, Self::Error>;
+
+    /// Polls the job behind `handle`, returning [`Poll::Pending`] while it is still running.
+    ///
+    /// # Implementation Note
+    ///
+    /// Once this method has returned [`Poll::Ready`] for `handle`, it is not allowed to be polled
+    /// again.
+    fn poll(&mut self, handle: &mut Self::Handle) -> Poll<Result<J::Output, Self::Error>>;
+
+    /// Blocks on the job behind `handle` until its output is ready.
+    #[inline]
+    fn wait(&mut self, mut handle: Self::Handle) -> Result<J::Output, Self::Error> {
+        loop {
+            if let Poll::Ready(output) = self.poll(&mut handle) {
+                return output;
+            }
+            hint::spin_loop();
+        }
+    }
+
+    /// Submits `job` and blocks until its output is ready.
+    #[inline]
+    fn run(&mut self, job: J) -> Result<J::Output, Self::Error> {
+        let handle = self.submit(job)?;
+        self.wait(handle)
+    }
+}
+
+impl<A, J> Accelerator<J> for &mut A
+where
+    A: Accelerator<J> + ?Sized,
+    J: Job,
+{
+    type Handle = A::Handle;
+    type Error = A::Error;
+
+    #[inline]
+    fn submit(&mut self, job: J) -> Result<Self::Handle, Self::Error> {
+        (**self).submit(job)
+    }
+
+    #[inline]
+    fn poll(&mut self, handle: &mut Self::Handle) -> Poll<Result<J::Output, Self::Error>> {
+        (**self).poll(handle)
+    }
+}
+
+/// Completed Job Handle
+///
+/// Handle of the [`Cpu`] accelerator which stores the output of the job until it is polled.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Completed<T>(Option<T>);
+
+impl<T> Completed<T> {
+    /// Returns the output stored in `self` if it has not been polled yet.
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+/// CPU Reference Accelerator
+///
+/// This accelerator runs every [`Execute`] job on the current thread as soon as it is submitted.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Cpu;
+
+impl<J> Accelerator<J> for Cpu
+where
+    J: Execute,
+{
+    type Handle = Completed<J::Output>;
+    type Error = Infallible;
+
+    #[inline]
+    fn submit(&mut self, job: J) -> Result<Self::Handle, Self::Error> {
+        Ok(Completed(Some(job.execute())))
+    }
+
+    #[inline]
+    fn poll(&mut self, handle: &mut Self::Handle) -> Poll<Result<J::Output, Self::Error>> {
+        Poll::Ready(Ok(handle
+            .0
+            .take()
+            .expect("Completed handles are not allowed to be polled twice.")))
+    }
+}
@@ -38,6 +38,11 @@ macro_rules! impl_empty_codec {
                 let _ = writer;
                 Ok(())
             }
+
+            #[inline]
+            fn encoded_len(&self) -> usize {
+                0
+            }
         }
     };
 }
@@ -460,6 +465,39 @@ where
     }
 }
 
+/// Size Counter
+///
+/// This writer discards its input and only counts the number of bytes written to it. See
+/// [`Encode::encoded_len`] for more.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SizeCounter(usize);
+
+impl SizeCounter {
+    /// Returns the number of bytes written to `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0
+    }
+
+    /// Returns `true` if no bytes were written to `self`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Write for SizeCounter {
+    type Error = Infallible;
+
+    #[inline]
+    fn write(&mut self, input: &mut &[u8]) -> Result<usize, Self::Error> {
+        let len = input.len();
+        self.0 += len;
+        *input = &input[..0];
+        Ok(len)
+    }
+}
+
 /// Pipelined Reader/Writer
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Pipeline<T>(pub T);
@@ -551,6 +589,21 @@ pub trait Encode {
     where
         W: Write;
 
+    /// Returns the number of bytes that [`encode`](Self::encode) writes for `self`.
+    ///
+    /// # Implementation Note
+    ///
+    /// The default implementation encodes `self` into a [`SizeCounter`], so implementations
+    /// should override it whenever the length can be computed without encoding.
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        let mut counter = SizeCounter::default();
+        match self.encode(&mut counter) {
+            Ok(()) => counter.len(),
+            Err(err) => match err {},
+        }
+    }
+
     /// Converts `self` into a vector of bytes.
     #[cfg(feature = "alloc")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
@@ -561,6 +614,18 @@ pub trait Encode {
             .expect("Writing to a `Vec<u8>` cannot fail.");
         buffer
     }
+
+    /// Converts `self` into a vector of bytes, allocating [`encoded_len`](Self::encoded_len)
+    /// bytes up front so that the buffer is never reallocated.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn to_vec_exact(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.encoded_len());
+        self.encode(&mut buffer)
+            .expect("Writing to a `Vec<u8>` cannot fail.");
+        buffer
+    }
 }
 
 impl<T> Encode for PhantomData<T> {
@@ -572,6 +637,11 @@ impl<T> Encode for PhantomData<T> {
         let _ = writer;
         Ok(())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0
+    }
 }
 
 impl Encode for bool {
@@ -582,6 +652,11 @@ impl Encode for bool {
     {
         (*self as u8).encode(writer)
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1
+    }
 }
 
 /// Defines an [`Encode`] implemention for the given integer type `$type`.
@@ -597,6 +672,11 @@ macro_rules! encode_int {
                     writer.write_ref(&self.to_le_bytes())?;
                     Ok(())
                 }
+
+                #[inline]
+                fn encoded_len(&self) -> usize {
+                    core::mem::size_of::<$type>()
+                }
             }
         )*
     }
@@ -619,6 +699,11 @@ where
         }
         Ok(())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0u64.encoded_len() + self.iter().map(Encode::encoded_len).sum::<usize>()
+    }
 }
 
 impl<T, const N: usize> Encode for [T; N]
@@ -635,6 +720,11 @@ where
         }
         Ok(())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.iter().map(Encode::encoded_len).sum()
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -654,6 +744,11 @@ where
         }
         Ok(())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        0u64.encoded_len() + self.iter().map(Encode::encoded_len).sum::<usize>()
+    }
 }
 
 impl<T> Encode for Option<T>
@@ -673,6 +768,11 @@ where
             _ => 0u8.encode(&mut writer),
         }
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1 + self.as_ref().map_or(0, Encode::encoded_len)
+    }
 }
 
 impl<T, E> Encode for Result<T, E>
@@ -696,6 +796,14 @@ where
             }
         }
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        1 + match self {
+            Ok(value) => value.encoded_len(),
+            Err(err) => err.encoded_len(),
+        }
+    }
 }
 
 /// Exact Size Encoding
@@ -948,6 +1056,28 @@ where
     }
 }
 
+/// Derived [`Decode`] Error
+///
+/// This is the error type of the implementations generated by `#[derive(Decode)]` in
+/// `openzl-derive`. Since every field can fail with a different error type, field errors only
+/// record the name of the field that could not be decoded.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DerivedDecodeError {
+    /// Missing Byte
+    ///
+    /// The version byte or the variant tag could not be read.
+    MissingByte,
+
+    /// Invalid Version
+    InvalidVersion(u8),
+
+    /// Invalid Variant Tag
+    InvalidTag(u8),
+
+    /// Field Error
+    Field(&'static str),
+}
+
 /// Exact Size Decoding
 pub trait DecodeExactSize<const N: usize>: Decode {
     /// Converts a fixed-length byte array into a concrete value of type `Self`.
@@ -966,6 +1096,102 @@ impl DecodeExactSize<0> for () {
     }
 }
 
+/// Encoding Mode
+///
+/// Some types, like elliptic curve points, have more than one wire format. Bandwidth-sensitive
+/// applications can pick the [`Compressed`](Self::Compressed) format, while data which never
+/// leaves a trusted boundary, like a local cache, can be decoded quickly in the
+/// [`Unchecked`](Self::Unchecked) format. Values must be decoded in the same mode that they were
+/// encoded in. See [`EncodeWithMode`] and [`DecodeWithMode`] for more.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Mode {
+    /// Compressed Mode
+    ///
+    /// Values are encoded in their smallest form and are validated when they are decoded.
+    Compressed,
+
+    /// Uncompressed Mode
+    ///
+    /// Values are encoded in their uncompressed form and are validated when they are decoded.
+    Uncompressed,
+
+    /// Unchecked Mode
+    ///
+    /// Values are encoded in their uncompressed form and are **not** validated when they are
+    /// decoded, so this mode should only be used for data coming from a trusted source.
+    Unchecked,
+}
+
+impl Mode {
+    /// Returns `true` if `self` uses the compressed encoding.
+    #[inline]
+    pub const fn is_compressed(self) -> bool {
+        matches!(self, Self::Compressed)
+    }
+
+    /// Returns `true` if values decoded in `self` are validated.
+    #[inline]
+    pub const fn is_validated(self) -> bool {
+        !matches!(self, Self::Unchecked)
+    }
+}
+
+/// Encoding with a [`Mode`]
+///
+/// The [`Encode`] implementation of a type which implements this trait should agree with one of
+/// the modes, usually [`Mode::Compressed`].
+pub trait EncodeWithMode: Encode {
+    /// Appends the representation of `self` in `mode` to `writer`.
+    fn encode_with_mode<W>(&self, mode: Mode, writer: W) -> Result<(), W::Error>
+    where
+        W: Write;
+
+    /// Returns the number of bytes that [`encode_with_mode`](Self::encode_with_mode) writes for
+    /// `self` in `mode`. See [`Encode::encoded_len`] for more.
+    #[inline]
+    fn encoded_len_with_mode(&self, mode: Mode) -> usize {
+        let mut counter = SizeCounter::default();
+        match self.encode_with_mode(mode, &mut counter) {
+            Ok(()) => counter.len(),
+            Err(err) => match err {},
+        }
+    }
+
+    /// Converts `self` into a vector of bytes in `mode`.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn to_vec_with_mode(&self, mode: Mode) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.encoded_len_with_mode(mode));
+        self.encode_with_mode(mode, &mut buffer)
+            .expect("Writing to a `Vec<u8>` cannot fail.");
+        buffer
+    }
+}
+
+/// Decoding with a [`Mode`]
+///
+/// The [`Decode`] implementation of a type which implements this trait should agree with one of
+/// the modes, usually [`Mode::Compressed`].
+pub trait DecodeWithMode: Decode {
+    /// Parses the input `reader` in `mode` into a concrete value of type `Self` if possible.
+    fn decode_with_mode<R>(
+        mode: Mode,
+        reader: R,
+    ) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read;
+
+    /// Converts a byte vector in `mode` into a concrete value of type `Self` if possible.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    fn from_vec_with_mode(mode: Mode, buffer: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::decode_with_mode(mode, buffer)
+            .map_err(move |err| err.decode().expect("Reading from `[u8]` cannot fail."))
+    }
+}
+
 /// Decoding Error
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum DecodeError<R, D> {
@@ -1023,3 +1249,166 @@ impl<R, D> DecodeError<R, D> {
         }
     }
 }
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use openzl_derive::{Decode, Encode};
+
+    /// Versioned Record
+    #[derive(Clone, Debug, Decode, Default, Encode, Eq, PartialEq)]
+    #[codec(crate = "crate", version = 2)]
+    struct Record {
+        /// Identifier
+        id: u64,
+
+        /// Entries
+        entries: Vec<u8>,
+
+        /// Cached Value
+        #[codec(skip)]
+        cache: Option<u64>,
+    }
+
+    /// Tagged Message
+    #[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+    #[codec(crate = "crate")]
+    enum Message {
+        /// Ping Message
+        Ping,
+
+        /// Data Message
+        #[codec(tag = 7)]
+        Data(Vec<u64>),
+
+        /// Record Message
+        Record {
+            /// Record
+            record: Record,
+
+            /// Seen Flag
+            #[codec(skip)]
+            seen: bool,
+        },
+    }
+
+    /// Returns the encoding of a [`Record`] with `id` and `entries`.
+    #[inline]
+    fn record_bytes(id: u64, entries: &[u8]) -> Vec<u8> {
+        [
+            &[2][..],
+            &id.to_le_bytes(),
+            &(entries.len() as u64).to_le_bytes(),
+            entries,
+        ]
+        .concat()
+    }
+
+    /// Checks that `value` encodes to `bytes`, that its encoded length matches, and that decoding
+    /// `bytes` returns `decoded`.
+    #[inline]
+    fn assert_round_trip<T>(value: &T, bytes: &[u8], decoded: &T)
+    where
+        T: Debug + Decode<Error = DerivedDecodeError> + Encode + PartialEq,
+    {
+        assert_eq!(value.to_vec(), bytes, "The encoding should match.");
+        assert_eq!(
+            value.encoded_len(),
+            bytes.len(),
+            "The encoded length should match the length of the encoding."
+        );
+        assert_eq!(
+            T::from_vec(bytes.to_vec()).as_ref(),
+            Ok(decoded),
+            "Decoding should invert encoding up to skipped fields."
+        );
+    }
+
+    /// Tests that derived codecs write the version byte, variant tags, and length-prefixed
+    /// collections, and leave out skipped fields.
+    #[test]
+    fn derived_codecs_round_trip() {
+        let record = Record {
+            id: 5,
+            entries: vec![1, 2, 3],
+            cache: Some(9),
+        };
+        let decoded_record = Record {
+            cache: None,
+            ..record.clone()
+        };
+        assert_round_trip(&record, &record_bytes(5, &[1, 2, 3]), &decoded_record);
+        assert_round_trip(
+            &Record::default(),
+            &record_bytes(0, &[]),
+            &Record::default(),
+        );
+        assert_round_trip(&Message::Ping, &[0], &Message::Ping);
+        assert_round_trip(
+            &Message::Data(vec![3, 4]),
+            &[
+                &[7][..],
+                &2u64.to_le_bytes(),
+                &3u64.to_le_bytes(),
+                &4u64.to_le_bytes(),
+            ]
+            .concat(),
+            &Message::Data(vec![3, 4]),
+        );
+        assert_round_trip(
+            &Message::Record { record, seen: true },
+            &[&[2][..], &record_bytes(5, &[1, 2, 3])].concat(),
+            &Message::Record {
+                record: decoded_record,
+                seen: false,
+            },
+        );
+    }
+
+    /// Tests that derived decoders reject unknown version bytes and variant tags, missing bytes,
+    /// and fields which cannot be decoded.
+    #[test]
+    fn derived_decoders_reject_malformed_input() {
+        let mut bytes = record_bytes(5, &[1, 2, 3]);
+        bytes[0] = 3;
+        assert_eq!(
+            Record::from_vec(bytes),
+            Err(DerivedDecodeError::InvalidVersion(3)),
+            "Unknown version bytes must be rejected."
+        );
+        assert_eq!(
+            Record::from_vec(Vec::new()),
+            Err(DerivedDecodeError::MissingByte),
+            "Missing version bytes must be rejected."
+        );
+        let bytes = record_bytes(5, &[1, 2, 3]);
+        assert_eq!(
+            Record::from_vec(bytes[..bytes.len() - 1].to_vec()),
+            Err(DerivedDecodeError::Field("entries")),
+            "Truncated collections must be rejected."
+        );
+        for tag in [1, 3, 6, 255] {
+            assert_eq!(
+                Message::from_vec(vec![tag]),
+                Err(DerivedDecodeError::InvalidTag(tag)),
+                "Unknown variant tags must be rejected."
+            );
+        }
+        assert_eq!(
+            Message::from_vec(Vec::new()),
+            Err(DerivedDecodeError::MissingByte),
+            "Missing variant tags must be rejected."
+        );
+        assert_eq!(
+            Message::from_vec(vec![7, 1]),
+            Err(DerivedDecodeError::Field("Data.0")),
+            "Truncated variant fields must be rejected."
+        );
+        assert_eq!(
+            Message::from_vec([&[2][..], &[3], &bytes[1..]].concat()),
+            Err(DerivedDecodeError::Field("Record.record")),
+            "Nested records with unknown versions must be rejected."
+        );
+    }
+}
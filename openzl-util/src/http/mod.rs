@@ -1,5 +1,9 @@
 //! HTTP Utilities
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod proving;
+
 #[cfg(all(feature = "serde", feature = "tide"))]
 #[cfg_attr(doc_cfg, doc(cfg(all(feature = "serde", feature = "tide"))))]
 pub mod tide;
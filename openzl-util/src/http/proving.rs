@@ -0,0 +1,181 @@
+//! Proving Service Interface
+//!
+//! Proving services accept proofs from their clients and publish the public parameters which the
+//! clients need to build those proofs. The [`ProvingService`] trait describes both endpoints, so
+//! that the `ProvingClient` in the `reqwest` module and the handlers registered by
+//! `register_proving_service` in the `tide` module speak the same protocol over the paths
+//! [`PROOF_PATH`] and [`PARAMETERS_PATH`].
+//!
+//! Public parameters can be large and rarely change, so they are always served with an
+//! [`EntityTag`]. Clients send the tag of their cached parameters back to the server, which
+//! answers with `304 Not Modified` if the parameters have not changed since.
+
+use crate::future::BoxFutureResult;
+use alloc::{format, string::String};
+
+#[cfg(feature = "serde")]
+use crate::serde::{Deserialize, Serialize};
+
+/// Proof Submission Path
+///
+/// Proofs are submitted to this path with a `POST` request whose JSON body is the proof.
+pub const PROOF_PATH: &str = "proof";
+
+/// Parameter Fetch Path
+///
+/// Parameters are fetched from this path with a `GET` request.
+pub const PARAMETERS_PATH: &str = "parameters";
+
+/// Entity Tag
+///
+/// An entity tag is an opaque identifier of a version of the public parameters, which is sent in
+/// the `ETag` and `If-None-Match` HTTP headers.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", transparent)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct EntityTag(String);
+
+impl EntityTag {
+    /// Builds a new [`EntityTag`] from the opaque `tag`.
+    #[inline]
+    pub fn new(tag: String) -> Self {
+        Self(tag)
+    }
+
+    /// Parses an [`EntityTag`] from the value of an `ETag` header, returning `None` if `value` is
+    /// not a quoted string. Weak tags are parsed as strong tags.
+    #[inline]
+    pub fn from_header(value: &str) -> Option<Self> {
+        let value = value.strip_prefix("W/").unwrap_or(value);
+        Some(Self(value.strip_prefix('"')?.strip_suffix('"')?.into()))
+    }
+
+    /// Returns the opaque tag of `self`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the value of the `ETag` header for `self`.
+    #[inline]
+    pub fn to_header(&self) -> String {
+        format!("\"{}\"", self.0)
+    }
+
+    /// Returns `true` if `self` is matched by the value of an `If-None-Match` header.
+    #[inline]
+    pub fn matches(&self, value: &str) -> bool {
+        value.split(',').map(str::trim).any(|candidate| {
+            candidate == "*" || Self::from_header(candidate).as_ref() == Some(self)
+        })
+    }
+}
+
+/// Tagged Value
+///
+/// This `struct` pairs a value with the [`EntityTag`] of its version.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Tagged<T> {
+    /// Entity Tag
+    pub tag: EntityTag,
+
+    /// Value
+    pub value: T,
+}
+
+impl<T> Tagged<T> {
+    /// Builds a new [`Tagged`] value from `tag` and `value`.
+    #[inline]
+    pub fn new(tag: EntityTag, value: T) -> Self {
+        Self { tag, value }
+    }
+}
+
+/// Proving Service
+///
+/// See the [module-level documentation](self) for more.
+pub trait ProvingService {
+    /// Proof Type
+    type Proof;
+
+    /// Proof Receipt Type
+    ///
+    /// This is the response of the service to a submitted proof.
+    type Receipt;
+
+    /// Public Parameters Type
+    type Parameters;
+
+    /// Error Type
+    type Error;
+
+    /// Submits `proof` to the service, returning its receipt.
+    fn submit_proof(&self, proof: Self::Proof) -> BoxFutureResult<'_, Self::Receipt, Self::Error>;
+
+    /// Returns the current public parameters of the service with their entity tag.
+    fn parameters(&self) -> BoxFutureResult<'_, Tagged<Self::Parameters>, Self::Error>;
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests that entity tags are parsed from quoted strong and weak `ETag` values only.
+    #[test]
+    fn entity_tags_are_parsed_from_headers() {
+        let tag = EntityTag::new("v1".into());
+        assert_eq!(EntityTag::from_header(&tag.to_header()), Some(tag.clone()));
+        assert_eq!(
+            EntityTag::from_header("W/\"v1\""),
+            Some(tag),
+            "Weak tags should be parsed as strong tags."
+        );
+        assert_eq!(
+            EntityTag::from_header("\"\""),
+            Some(EntityTag::default()),
+            "The empty quoted string should be a valid tag."
+        );
+        for value in ["v1", "\"v1", "v1\"", "W/v1", "", "\""] {
+            assert_eq!(
+                EntityTag::from_header(value),
+                None,
+                "Unquoted tags must be rejected: {value:?}."
+            );
+        }
+    }
+
+    /// Tests that entity tags are matched by weak tags, tag lists and the `*` wildcard in
+    /// `If-None-Match` values.
+    #[test]
+    fn entity_tags_match_if_none_match_values() {
+        let tag = EntityTag::new("v1".into());
+        for value in [
+            "\"v1\"",
+            "W/\"v1\"",
+            "\"v0\", \"v1\"",
+            "\"v0\",W/\"v1\"",
+            "*",
+            "\"v0\", *",
+        ] {
+            assert!(
+                tag.matches(value),
+                "The tag should be matched by {value:?}."
+            );
+        }
+        for value in ["", "\"v0\"", "v1", "\"v0\", \"v2\"", "\"V1\"", "\"v1 \""] {
+            assert!(
+                !tag.matches(value),
+                "The tag must not be matched by {value:?}."
+            );
+        }
+    }
+}
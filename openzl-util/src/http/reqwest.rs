@@ -1,7 +1,19 @@
 //! Reqwest HTTP Client Utilities
 
+use crate::time::Duration;
+use core::future::Future;
+
 #[cfg(feature = "serde")]
-use crate::serde::{de::DeserializeOwned, Serialize};
+use {
+    crate::{
+        future::BoxFutureResult,
+        http::proving::{EntityTag, ProvingService, Tagged, PARAMETERS_PATH, PROOF_PATH},
+        serde::{de::DeserializeOwned, Serialize},
+    },
+    alloc::boxed::Box,
+    core::marker::PhantomData,
+    std::sync::Mutex,
+};
 
 #[doc(inline)]
 pub use reqwest::*;
@@ -80,3 +92,466 @@ impl KnownUrlClient {
         self.request(Method::POST, command, request).await
     }
 }
+
+/// Retry Backoff Policy
+///
+/// Requests which fail with a connection error, a timeout, or a server error are retried up to
+/// `retries` times. The delay before the first retry is `initial` and doubles for every
+/// subsequent retry up to `max`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Backoff {
+    /// Maximum Number of Retries
+    pub retries: u32,
+
+    /// Initial Delay
+    pub initial: Duration,
+
+    /// Maximum Delay
+    pub max: Duration,
+}
+
+impl Backoff {
+    /// Builds a new [`Backoff`] policy which never retries.
+    #[inline]
+    pub const fn none() -> Self {
+        Self {
+            retries: 0,
+            initial: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    /// Returns the delay before the retry with the given `attempt` number, starting from zero.
+    #[inline]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial
+            .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+
+    /// Returns `true` if `error` is transient, so that the request which caused it should be
+    /// retried.
+    #[inline]
+    pub fn is_transient(error: &Error) -> bool {
+        error.is_connect()
+            || error.is_timeout()
+            || error
+                .status()
+                .map_or(false, |status| status.is_server_error())
+    }
+
+    /// Runs the request returned by `f` until it succeeds, fails with an error which is not
+    /// [transient](Self::is_transient), or runs out of retries.
+    #[inline]
+    pub async fn retry<T, F, Fut>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(err) if attempt < self.retries && Self::is_transient(&err) => {
+                    tokio::time::sleep(self.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for Backoff {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Proving Service Client
+///
+/// This client implements [`ProvingService`] over HTTP against a server which registered the
+/// matching handlers with `register_proving_service` from the `tide` module.
+/// Submissions and fetches are retried according to a [`Backoff`] policy, and the parameters are
+/// cached with their [`EntityTag`] so that they are only downloaded again when they change.
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+pub struct ProvingClient<P, R, Q> {
+    /// Base Client
+    client: KnownUrlClient,
+
+    /// Retry Policy
+    backoff: Backoff,
+
+    /// Parameter Cache
+    cache: Mutex<Option<Tagged<Q>>>,
+
+    /// Type Parameter Marker
+    __: PhantomData<fn(P) -> R>,
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<P, R, Q> ProvingClient<P, R, Q> {
+    /// Builds a new [`ProvingClient`] for the service at `server_url` with the `backoff` policy.
+    #[inline]
+    pub fn new<U>(server_url: U, backoff: Backoff) -> Result<Self>
+    where
+        U: IntoUrl,
+    {
+        Ok(Self::from_client(KnownUrlClient::new(server_url)?, backoff))
+    }
+
+    /// Builds a new [`ProvingClient`] from `client` with the `backoff` policy.
+    #[inline]
+    pub fn from_client(client: KnownUrlClient, backoff: Backoff) -> Self {
+        Self {
+            client,
+            backoff,
+            cache: Mutex::new(None),
+            __: PhantomData,
+        }
+    }
+
+    /// Returns a shared reference to the underlying client.
+    #[inline]
+    pub fn client(&self) -> &KnownUrlClient {
+        &self.client
+    }
+
+    /// Returns the retry policy of `self`.
+    #[inline]
+    pub fn backoff(&self) -> Backoff {
+        self.backoff
+    }
+
+    /// Drops the cached parameters, so that the next fetch downloads them again.
+    #[inline]
+    pub fn clear_cache(&self) {
+        *self
+            .cache
+            .lock()
+            .expect("The cache lock is never poisoned.") = None;
+    }
+
+    /// Submits `proof` to the service, returning its receipt.
+    #[inline]
+    pub async fn submit(&self, proof: &P) -> Result<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        self.backoff
+            .retry(|| async move {
+                self.client
+                    .client
+                    .post(self.url(PROOF_PATH))
+                    .json(proof)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await
+            })
+            .await
+    }
+
+    /// Fetches the current parameters of the service, reusing the cached parameters if the
+    /// service reports that they have not changed.
+    ///
+    /// If the cache was cleared while the request was in flight, the service can report that the
+    /// parameters have not changed when there is nothing left to reuse. In that case the
+    /// parameters are requested again without an entity tag.
+    #[inline]
+    pub async fn fetch_parameters(&self) -> Result<Tagged<Q>>
+    where
+        Q: Clone + DeserializeOwned,
+    {
+        let cached_tag = self.cached().map(|cached| cached.tag.to_header());
+        let mut response = self.request_parameters(cached_tag.as_deref()).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            match self.cached() {
+                Some(cached) => return Ok(cached),
+                None => response = self.request_parameters(None).await?,
+            }
+        }
+        let tag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .and_then(EntityTag::from_header)
+            .unwrap_or_default();
+        let parameters = Tagged::new(tag, response.json().await?);
+        *self
+            .cache
+            .lock()
+            .expect("The cache lock is never poisoned.") = Some(parameters.clone());
+        Ok(parameters)
+    }
+
+    /// Requests the parameters from the service, sending `tag` in the `If-None-Match` header if it
+    /// is given.
+    #[inline]
+    async fn request_parameters(&self, tag: Option<&str>) -> Result<Response> {
+        self.backoff
+            .retry(|| async move {
+                let mut request = self.client.client.get(self.url(PARAMETERS_PATH));
+                if let Some(tag) = tag {
+                    request = request.header(header::IF_NONE_MATCH, tag);
+                }
+                request.send().await?.error_for_status()
+            })
+            .await
+    }
+
+    /// Returns a copy of the cached parameters.
+    #[inline]
+    fn cached(&self) -> Option<Tagged<Q>>
+    where
+        Q: Clone,
+    {
+        self.cache
+            .lock()
+            .expect("The cache lock is never poisoned.")
+            .clone()
+    }
+
+    /// Returns the URL of the endpoint at `path`.
+    #[inline]
+    fn url(&self, path: &str) -> Url {
+        self.client
+            .server_url
+            .join(path)
+            .expect("Building the URL is not allowed to fail.")
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<P, R, Q> ProvingService for ProvingClient<P, R, Q>
+where
+    P: Serialize + Send + Sync,
+    R: DeserializeOwned + Send,
+    Q: Clone + DeserializeOwned + Send,
+{
+    type Proof = P;
+    type Receipt = R;
+    type Parameters = Q;
+    type Error = Error;
+
+    #[inline]
+    fn submit_proof(&self, proof: Self::Proof) -> BoxFutureResult<'_, Self::Receipt, Self::Error> {
+        Box::pin(async move { self.submit(&proof).await })
+    }
+
+    #[inline]
+    fn parameters(&self) -> BoxFutureResult<'_, Tagged<Self::Parameters>, Self::Error> {
+        Box::pin(async move { self.fetch_parameters().await })
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests that the backoff delay doubles with every attempt and saturates at the maximum delay.
+    #[test]
+    fn backoff_delay_saturates() {
+        let backoff = Backoff {
+            retries: 8,
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+        };
+        for (attempt, millis) in [100, 200, 400, 800, 1000, 1000].into_iter().enumerate() {
+            assert_eq!(
+                backoff.delay(attempt as u32),
+                Duration::from_millis(millis),
+                "The delay should double up to the maximum delay."
+            );
+        }
+        for attempt in [31, 32, 64, u32::MAX] {
+            assert_eq!(
+                backoff.delay(attempt),
+                backoff.max,
+                "Overflowing multipliers must saturate at the maximum delay."
+            );
+        }
+        let backoff = Backoff {
+            initial: Duration::MAX,
+            max: Duration::MAX,
+            ..backoff
+        };
+        assert_eq!(
+            backoff.delay(1),
+            Duration::MAX,
+            "Overflowing delays must saturate at the maximum delay."
+        );
+        assert_eq!(Backoff::none().delay(0), Duration::ZERO);
+        assert_eq!(Backoff::none().delay(u32::MAX), Duration::ZERO);
+    }
+
+    /// Parameter Cache Tests
+    #[cfg(feature = "serde")]
+    mod cache {
+        use super::*;
+        use alloc::{format, string::String, sync::Arc, vec, vec::Vec};
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+            thread::{self, JoinHandle},
+        };
+
+        /// Test Client Type
+        type TestClient = ProvingClient<u64, u64, Vec<u64>>;
+
+        /// Returns a `200 OK` response with the parameters `body` tagged with `tag`.
+        #[inline]
+        fn ok(tag: &str, body: &str) -> String {
+            format!(
+                "HTTP/1.1 200 OK\r\nETag: \"{tag}\"\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+
+        /// Returns a `304 Not Modified` response for the parameters tagged with `tag`.
+        #[inline]
+        fn not_modified(tag: &str) -> String {
+            format!("HTTP/1.1 304 Not Modified\r\nETag: \"{tag}\"\r\nConnection: close\r\n\r\n")
+        }
+
+        /// Binds a new listener on a local port, returning it with a client for the listener.
+        #[inline]
+        fn bind() -> (TcpListener, Arc<TestClient>) {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind listener.");
+            let url = format!(
+                "http://{}/",
+                listener.local_addr().expect("Unable to get local address.")
+            );
+            let client =
+                TestClient::new(url, Backoff::none()).expect("Unable to build the client.");
+            (listener, Arc::new(client))
+        }
+
+        /// Answers one request per connection on `listener` with each of the `responses` in
+        /// order, calling `before` with the index of every request before answering it. The
+        /// server returns the `If-None-Match` header of every request it answered.
+        #[inline]
+        fn serve<F>(
+            listener: TcpListener,
+            responses: Vec<String>,
+            mut before: F,
+        ) -> JoinHandle<Vec<Option<String>>>
+        where
+            F: FnMut(usize) + Send + 'static,
+        {
+            thread::spawn(move || {
+                let mut tags = Vec::new();
+                for (index, response) in responses.into_iter().enumerate() {
+                    let (mut stream, _) = listener.accept().expect("Unable to accept connection.");
+                    let mut reader =
+                        BufReader::new(stream.try_clone().expect("Unable to clone stream."));
+                    let mut tag = None;
+                    loop {
+                        let mut line = String::new();
+                        reader
+                            .read_line(&mut line)
+                            .expect("Unable to read request.");
+                        let line = line.trim_end();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("if-none-match") {
+                                tag = Some(value.trim().into());
+                            }
+                        }
+                    }
+                    tags.push(tag);
+                    before(index);
+                    stream
+                        .write_all(response.as_bytes())
+                        .expect("Unable to write response.");
+                }
+                tags
+            })
+        }
+
+        /// Tests that the cached parameters are reused when the service reports that they have
+        /// not changed.
+        #[tokio::test]
+        async fn not_modified_reuses_cache() {
+            let (listener, client) = bind();
+            let server = serve(
+                listener,
+                vec![ok("v1", "[1,2]"), not_modified("v1")],
+                |_| {},
+            );
+            let fetched = client
+                .fetch_parameters()
+                .await
+                .expect("Fetching the parameters should succeed.");
+            assert_eq!(
+                fetched,
+                Tagged::new(EntityTag::new("v1".into()), vec![1, 2])
+            );
+            assert_eq!(
+                client
+                    .fetch_parameters()
+                    .await
+                    .expect("Fetching the parameters should succeed."),
+                fetched,
+                "The cached parameters should be reused."
+            );
+            assert_eq!(
+                server.join().expect("The server should not panic."),
+                vec![None, Some("\"v1\"".into())],
+                "Only the second request should send the cached tag."
+            );
+        }
+
+        /// Tests that the parameters are requested again without an entity tag when the cache is
+        /// cleared before the service reports that they have not changed.
+        #[tokio::test]
+        async fn not_modified_with_empty_cache_refetches() {
+            let (listener, client) = bind();
+            let server_client = client.clone();
+            let server = serve(
+                listener,
+                vec![ok("v1", "[1,2]"), not_modified("v1"), ok("v2", "[3]")],
+                move |index| {
+                    if index == 1 {
+                        server_client.clear_cache();
+                    }
+                },
+            );
+            client
+                .fetch_parameters()
+                .await
+                .expect("Fetching the parameters should succeed.");
+            assert_eq!(
+                client
+                    .fetch_parameters()
+                    .await
+                    .expect("Fetching the parameters should succeed."),
+                Tagged::new(EntityTag::new("v2".into()), vec![3]),
+                "The parameters must be downloaded again if the cache is empty."
+            );
+            assert_eq!(
+                client.cached(),
+                Some(Tagged::new(EntityTag::new("v2".into()), vec![3])),
+                "The downloaded parameters should be cached."
+            );
+            assert_eq!(
+                server.join().expect("The server should not panic."),
+                vec![None, Some("\"v1\"".into()), None],
+                "The retried request must not send an entity tag."
+            );
+        }
+    }
+}
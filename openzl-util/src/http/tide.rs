@@ -3,6 +3,9 @@
 use crate::serde::{de::DeserializeOwned, Serialize};
 use core::{future::Future, result::Result};
 
+#[cfg(feature = "alloc")]
+use crate::http::proving::{ProvingService, PARAMETERS_PATH, PROOF_PATH};
+
 #[doc(inline)]
 pub use tide::*;
 
@@ -46,3 +49,175 @@ where
 {
     api.at(path).post(move |r| execute(r, f.clone()));
 }
+
+/// Registers the handlers of the proving `service` with `api`.
+///
+/// Proofs are accepted with a `POST` request at [`PROOF_PATH`], and the parameters are served at
+/// [`PARAMETERS_PATH`] with their `ETag`. Requests whose `If-None-Match` header matches the current
+/// entity tag are answered with `304 Not Modified` and no body. See the
+/// [`proving`](crate::http::proving) module for more.
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+#[inline]
+pub fn register_proving_service<S>(api: &mut Server<S>)
+where
+    S: Clone + ProvingService + Send + Sync + 'static,
+    S::Proof: DeserializeOwned + Send + 'static,
+    S::Receipt: Serialize + 'static,
+    S::Parameters: Serialize + 'static,
+    S::Error: Into<Error> + 'static,
+{
+    register_post(api, PROOF_PATH, |service: S, proof| async move {
+        service.submit_proof(proof).await
+    });
+    api.at(PARAMETERS_PATH).get(serve_parameters::<S>);
+}
+
+/// Serves the parameters of the proving service in the state of `request`, answering with
+/// `304 Not Modified` if the `If-None-Match` header of `request` matches their entity tag.
+#[cfg(feature = "alloc")]
+#[inline]
+async fn serve_parameters<S>(request: Request<S>) -> Result<Response, Error>
+where
+    S: ProvingService,
+    S::Parameters: Serialize,
+    S::Error: Into<Error>,
+{
+    let parameters = request.state().parameters().await.map_err(Into::into)?;
+    if let Some(values) = request.header(http::headers::IF_NONE_MATCH) {
+        if values
+            .iter()
+            .any(|value| parameters.tag.matches(value.as_str()))
+        {
+            return Ok(Response::new(StatusCode::NotModified));
+        }
+    }
+    let mut response: Response = Body::from_json(&parameters.value)?.into();
+    response.insert_header(http::headers::ETAG, parameters.tag.to_header());
+    Ok(response)
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use crate::{
+        future::BoxFutureResult,
+        http::proving::{EntityTag, Tagged},
+    };
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    /// Test Proving Service
+    ///
+    /// This service answers every proof with its successor and always serves the same tagged
+    /// parameters.
+    #[derive(Clone)]
+    struct Fixed;
+
+    impl ProvingService for Fixed {
+        type Proof = u64;
+        type Receipt = u64;
+        type Parameters = Vec<u64>;
+        type Error = Error;
+
+        #[inline]
+        fn submit_proof(&self, proof: Self::Proof) -> BoxFutureResult<'_, Self::Receipt, Error> {
+            Box::pin(async move { Ok(proof + 1) })
+        }
+
+        #[inline]
+        fn parameters(&self) -> BoxFutureResult<'_, Tagged<Self::Parameters>, Error> {
+            Box::pin(async move { Ok(Tagged::new(EntityTag::new("v1".into()), vec![1, 2])) })
+        }
+    }
+
+    /// Returns a server with the handlers of the [`Fixed`] service.
+    #[inline]
+    fn server() -> Server<Fixed> {
+        let mut api = Server::with_state(Fixed);
+        register_proving_service(&mut api);
+        api
+    }
+
+    /// Returns a request to the endpoint at `path` with the given `method`.
+    #[inline]
+    fn request(method: http::Method, path: &str) -> http::Request {
+        http::Request::new(
+            method,
+            http::Url::parse("http://localhost/")
+                .and_then(|url| url.join(path))
+                .expect("Building the URL is not allowed to fail."),
+        )
+    }
+
+    /// Tests that submitted proofs are answered with the receipt of the service.
+    #[tokio::test]
+    async fn proofs_are_submitted() {
+        let mut request = request(http::Method::Post, PROOF_PATH);
+        request.set_body(Body::from_json(&5u64).expect("Encoding the proof should succeed."));
+        let mut response: http::Response = server()
+            .respond(request)
+            .await
+            .expect("The server should respond.");
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert_eq!(
+            response
+                .body_json::<u64>()
+                .await
+                .expect("Decoding the receipt should succeed."),
+            6,
+            "The receipt should be the one returned by the service."
+        );
+    }
+
+    /// Tests that parameters are served with their entity tag unless the `If-None-Match` header
+    /// matches it.
+    #[tokio::test]
+    async fn parameters_are_served_with_tags() {
+        let api = server();
+        for value in [None, Some("\"v0\""), Some("W/\"v0\", \"v2\"")] {
+            let mut request = request(http::Method::Get, PARAMETERS_PATH);
+            if let Some(value) = value {
+                request.insert_header(http::headers::IF_NONE_MATCH, value);
+            }
+            let mut response: http::Response = api
+                .respond(request)
+                .await
+                .expect("The server should respond.");
+            assert_eq!(
+                response.status(),
+                StatusCode::Ok,
+                "Unmatched tags should be answered with the parameters: {value:?}."
+            );
+            assert_eq!(
+                response.header(http::headers::ETAG).map(|values| values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Vec<_>>()),
+                Some(vec!["\"v1\""]),
+                "The parameters should be served with their entity tag."
+            );
+            assert_eq!(
+                response
+                    .body_json::<Vec<u64>>()
+                    .await
+                    .expect("Decoding the parameters should succeed."),
+                vec![1, 2],
+                "The parameters should be the ones returned by the service."
+            );
+        }
+        for value in ["\"v1\"", "W/\"v1\"", "\"v0\", \"v1\"", "*"] {
+            let mut request = request(http::Method::Get, PARAMETERS_PATH);
+            request.insert_header(http::headers::IF_NONE_MATCH, value);
+            let response: http::Response = api
+                .respond(request)
+                .await
+                .expect("The server should respond.");
+            assert_eq!(
+                response.status(),
+                StatusCode::NotModified,
+                "Matched tags must be answered with `304 Not Modified`: {value:?}."
+            );
+        }
+    }
+}
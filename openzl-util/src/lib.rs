@@ -13,6 +13,7 @@ mod bytes;
 mod macros;
 mod sealed;
 
+pub mod accelerator;
 pub mod cmp;
 pub mod codec;
 pub mod convert;
@@ -25,6 +26,7 @@ pub mod persistence;
 pub mod pointer;
 pub mod rand;
 pub mod time;
+pub mod trace;
 
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
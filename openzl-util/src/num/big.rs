@@ -0,0 +1,633 @@
+//! Fixed-Width Big Integers
+//!
+//! Constant generation, non-native arithmetic, and witness computation for modular gadgets all
+//! need integers wider than the native ones. [`Uint`] is a fixed-width unsigned integer made of
+//! `LIMBS`-many little-endian 64-bit limbs which lives on the stack, so it works without `alloc`,
+//! together with modular arithmetic helpers over an arbitrary modulus.
+
+use crate::num::{CheckedAdd, CheckedSub};
+use core::cmp::Ordering;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Fixed-Width Unsigned Integer
+///
+/// The integer is stored as `LIMBS`-many 64-bit limbs in little-endian order.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Uint<const LIMBS: usize>([u64; LIMBS]);
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    /// Number of Bits
+    pub const BITS: usize = 64 * LIMBS;
+
+    /// Number of Bytes
+    pub const BYTES: usize = 8 * LIMBS;
+
+    /// Zero
+    pub const ZERO: Self = Self([0; LIMBS]);
+
+    /// One
+    pub const ONE: Self = Self::from_u64(1);
+
+    /// Maximum Value
+    pub const MAX: Self = Self([u64::MAX; LIMBS]);
+
+    /// Builds a new [`Uint`] from its little-endian `limbs`.
+    #[inline]
+    pub const fn from_limbs(limbs: [u64; LIMBS]) -> Self {
+        Self(limbs)
+    }
+
+    /// Builds a new [`Uint`] from `value`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `LIMBS` is zero.
+    #[inline]
+    pub const fn from_u64(value: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = value;
+        Self(limbs)
+    }
+
+    /// Builds a new [`Uint`] from `value`, returning `None` if it does not fit.
+    #[inline]
+    pub fn from_u128(value: u128) -> Option<Self> {
+        Self::from_le_bytes(&value.to_le_bytes())
+    }
+
+    /// Returns the little-endian limbs of `self`.
+    #[inline]
+    pub const fn limbs(&self) -> &[u64; LIMBS] {
+        &self.0
+    }
+
+    /// Returns the little-endian limbs of `self`.
+    #[inline]
+    pub const fn into_limbs(self) -> [u64; LIMBS] {
+        self.0
+    }
+
+    /// Builds a new [`Uint`] from the little-endian `bytes`, returning `None` if the encoded
+    /// integer does not fit into `LIMBS` limbs.
+    #[inline]
+    pub fn from_le_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.iter().skip(Self::BYTES).any(|byte| *byte != 0) {
+            return None;
+        }
+        let mut limbs = [0; LIMBS];
+        for (i, byte) in bytes.iter().take(Self::BYTES).enumerate() {
+            limbs[i / 8] |= (*byte as u64) << (8 * (i % 8));
+        }
+        Some(Self(limbs))
+    }
+
+    /// Writes the little-endian encoding of `self` into `bytes`, returning `false` if `bytes` is
+    /// too short to hold the integer. Bytes beyond the encoding of `self` are set to zero.
+    #[inline]
+    pub fn write_le_bytes(&self, bytes: &mut [u8]) -> bool {
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = if i < Self::BYTES {
+                (self.0[i / 8] >> (8 * (i % 8))) as u8
+            } else {
+                0
+            };
+        }
+        self.bits() <= 8 * bytes.len()
+    }
+
+    /// Returns the little-endian encoding of `self` with [`BYTES`](Self::BYTES) bytes.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; Self::BYTES];
+        self.write_le_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Builds a new [`Uint`] from the big-endian `bits`, returning `None` if the encoded integer
+    /// does not fit into `LIMBS` limbs.
+    #[inline]
+    pub fn from_bits_be(bits: &[bool]) -> Option<Self> {
+        let mut result = Self::ZERO;
+        for bit in bits {
+            let (shifted, overflow) = result.overflowing_shl1();
+            if overflow {
+                return None;
+            }
+            result = shifted;
+            result.0[0] |= *bit as u64;
+        }
+        Some(result)
+    }
+
+    /// Returns `true` if `self` is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|limb| *limb == 0)
+    }
+
+    /// Returns `true` if `self` is odd.
+    #[inline]
+    pub fn is_odd(&self) -> bool {
+        LIMBS > 0 && self.0[0] & 1 == 1
+    }
+
+    /// Returns the bit of `self` at `index`, counting from the least significant bit.
+    #[inline]
+    pub fn bit(&self, index: usize) -> bool {
+        index < Self::BITS && (self.0[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    /// Returns the number of bits required to represent `self`.
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.0
+            .iter()
+            .rposition(|limb| *limb != 0)
+            .map_or(0, |i| 64 * i + 64 - self.0[i].leading_zeros() as usize)
+    }
+
+    /// Computes `self + rhs`, returning the wrapped sum and whether an overflow occurred.
+    #[inline]
+    pub fn overflowing_add(&self, rhs: &Self) -> (Self, bool) {
+        let mut result = [0; LIMBS];
+        let mut carry = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (Self(result), carry)
+    }
+
+    /// Computes `self - rhs`, returning the wrapped difference and whether an underflow occurred.
+    #[inline]
+    pub fn overflowing_sub(&self, rhs: &Self) -> (Self, bool) {
+        let mut result = [0; LIMBS];
+        let mut borrow = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (difference, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (difference, b2) = difference.overflowing_sub(borrow as u64);
+            *limb = difference;
+            borrow = b1 || b2;
+        }
+        (Self(result), borrow)
+    }
+
+    /// Computes the full product of `self` and `rhs`, returning its low and high halves.
+    #[inline]
+    pub fn widening_mul(&self, rhs: &Self) -> (Self, Self) {
+        let mut low = [0; LIMBS];
+        let mut high = [0; LIMBS];
+        for i in 0..LIMBS {
+            let mut carry = 0u64;
+            for j in 0..LIMBS {
+                let k = i + j;
+                let current = if k < LIMBS { low[k] } else { high[k - LIMBS] };
+                let product =
+                    (self.0[i] as u128) * (rhs.0[j] as u128) + (current as u128) + (carry as u128);
+                if k < LIMBS {
+                    low[k] = product as u64;
+                } else {
+                    high[k - LIMBS] = product as u64;
+                }
+                carry = (product >> 64) as u64;
+            }
+            high[i] = carry;
+        }
+        (Self(low), Self(high))
+    }
+
+    /// Computes `self * rhs`, returning `None` if an overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let (low, high) = self.widening_mul(rhs);
+        high.is_zero().then_some(low)
+    }
+
+    /// Shifts `self` left by one bit, returning the shifted value and the bit shifted out.
+    #[inline]
+    fn overflowing_shl1(&self) -> (Self, bool) {
+        let mut result = [0; LIMBS];
+        let mut carry = 0;
+        for (i, limb) in result.iter_mut().enumerate() {
+            *limb = (self.0[i] << 1) | carry;
+            carry = self.0[i] >> 63;
+        }
+        (Self(result), carry == 1)
+    }
+
+    /// Shifts `self` left by `shift` bits, dropping the bits shifted out.
+    #[inline]
+    pub fn shl(&self, shift: usize) -> Self {
+        let mut result = [0; LIMBS];
+        let (limbs, bits) = (shift / 64, shift % 64);
+        for i in (limbs..LIMBS).rev() {
+            result[i] = self.0[i - limbs] << bits;
+            if bits > 0 && i > limbs {
+                result[i] |= self.0[i - limbs - 1] >> (64 - bits);
+            }
+        }
+        Self(result)
+    }
+
+    /// Shifts `self` right by `shift` bits, dropping the bits shifted out.
+    #[inline]
+    pub fn shr(&self, shift: usize) -> Self {
+        let mut result = [0; LIMBS];
+        let (limbs, bits) = (shift / 64, shift % 64);
+        for i in 0..LIMBS.saturating_sub(limbs) {
+            result[i] = self.0[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < LIMBS {
+                result[i] |= self.0[i + limbs + 1] << (64 - bits);
+            }
+        }
+        Self(result)
+    }
+
+    /// Divides `self` by `divisor`, returning the quotient and the remainder, or `None` if
+    /// `divisor` is zero.
+    #[inline]
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let mut quotient = Self::ZERO;
+        let mut remainder = Self::ZERO;
+        for i in (0..self.bits()).rev() {
+            let (shifted, overflow) = remainder.overflowing_shl1();
+            remainder = shifted;
+            remainder.0[0] |= self.bit(i) as u64;
+            if overflow || remainder >= *divisor {
+                remainder = remainder.overflowing_sub(divisor).0;
+                quotient.0[i / 64] |= 1 << (i % 64);
+            }
+        }
+        Some((quotient, remainder))
+    }
+
+    /// Returns `self` reduced modulo `modulus`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `modulus` is zero.
+    #[inline]
+    pub fn rem(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus)
+            .expect("The modulus is not allowed to be zero.")
+            .1
+    }
+
+    /// Computes `(self + rhs) mod modulus` for `self` and `rhs` smaller than `modulus`.
+    #[inline]
+    pub fn add_mod(&self, rhs: &Self, modulus: &Self) -> Self {
+        let (sum, overflow) = self.overflowing_add(rhs);
+        if overflow || sum >= *modulus {
+            sum.overflowing_sub(modulus).0
+        } else {
+            sum
+        }
+    }
+
+    /// Computes `(self - rhs) mod modulus` for `self` and `rhs` smaller than `modulus`.
+    #[inline]
+    pub fn sub_mod(&self, rhs: &Self, modulus: &Self) -> Self {
+        let (difference, underflow) = self.overflowing_sub(rhs);
+        if underflow {
+            difference.overflowing_add(modulus).0
+        } else {
+            difference
+        }
+    }
+
+    /// Computes `-self mod modulus` for `self` smaller than `modulus`.
+    #[inline]
+    pub fn neg_mod(&self, modulus: &Self) -> Self {
+        Self::ZERO.sub_mod(self, modulus)
+    }
+
+    /// Reduces the double-width integer with halves `low` and `high` modulo `modulus`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `modulus` is zero.
+    #[inline]
+    pub fn reduce_wide(low: &Self, high: &Self, modulus: &Self) -> Self {
+        assert!(!modulus.is_zero(), "The modulus is not allowed to be zero.");
+        let mut remainder = high.rem(modulus);
+        for i in (0..Self::BITS).rev() {
+            let (shifted, overflow) = remainder.overflowing_shl1();
+            remainder = shifted;
+            remainder.0[0] |= low.bit(i) as u64;
+            if overflow || remainder >= *modulus {
+                remainder = remainder.overflowing_sub(modulus).0;
+            }
+        }
+        remainder
+    }
+
+    /// Computes `(self * rhs) mod modulus`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `modulus` is zero.
+    #[inline]
+    pub fn mul_mod(&self, rhs: &Self, modulus: &Self) -> Self {
+        let (low, high) = self.widening_mul(rhs);
+        Self::reduce_wide(&low, &high, modulus)
+    }
+
+    /// Computes `self^exponent mod modulus` by square-and-multiply.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `modulus` is zero.
+    #[inline]
+    pub fn pow_mod(&self, exponent: &Self, modulus: &Self) -> Self {
+        let base = self.rem(modulus);
+        let mut result = Self::ONE.rem(modulus);
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul_mod(&result, modulus);
+            if exponent.bit(i) {
+                result = result.mul_mod(&base, modulus);
+            }
+        }
+        result
+    }
+
+    /// Computes the inverse of `self` modulo `modulus` with the extended Euclidean algorithm,
+    /// returning `None` if `self` and `modulus` are not coprime.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `modulus` is zero.
+    #[inline]
+    pub fn inv_mod(&self, modulus: &Self) -> Option<Self> {
+        let (mut r0, mut r1) = (*modulus, self.rem(modulus));
+        let (mut t0, mut t1) = (Self::ZERO, Self::ONE.rem(modulus));
+        while !r1.is_zero() {
+            let (quotient, remainder) = r0.div_rem(&r1).expect("The divisor is never zero.");
+            let t2 = t0.sub_mod(&quotient.rem(modulus).mul_mod(&t1, modulus), modulus);
+            (r0, r1) = (r1, remainder);
+            (t0, t1) = (t1, t2);
+        }
+        (r0 == Self::ONE).then_some(t0)
+    }
+
+    /// Divides `self` by `divisor` and rounds the quotient to the nearest integer, rounding ties
+    /// up, returning `None` if `divisor` is zero.
+    #[inline]
+    pub fn rounded_div(&self, divisor: &Self) -> Option<Self> {
+        let (quotient, remainder) = self.div_rem(divisor)?;
+        let (twice, overflow) = remainder.overflowing_shl1();
+        if overflow || twice >= *divisor {
+            Some(quotient.overflowing_add(&Self::ONE).0)
+        } else {
+            Some(quotient)
+        }
+    }
+}
+
+impl<const LIMBS: usize> Default for Uint<LIMBS> {
+    #[inline]
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for Uint<LIMBS> {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self::from_u64(value)
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> CheckedAdd for Uint<LIMBS> {
+    type Output = Self;
+
+    #[inline]
+    fn checked_add(self, rhs: Self) -> Option<Self::Output> {
+        let (sum, overflow) = self.overflowing_add(&rhs);
+        (!overflow).then_some(sum)
+    }
+}
+
+impl<const LIMBS: usize> CheckedSub for Uint<LIMBS> {
+    type Output = Self;
+
+    #[inline]
+    fn checked_sub(self, rhs: Self) -> Option<Self::Output> {
+        let (difference, underflow) = self.overflowing_sub(&rhs);
+        (!underflow).then_some(difference)
+    }
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "test"))]
+mod test {
+    use super::*;
+    use crate::rand::{RngCore, TestRng};
+    use num_bigint::BigUint;
+
+    /// Test Integer Type
+    type U256 = Uint<4>;
+
+    /// Converts `value` into a [`BigUint`].
+    #[inline]
+    fn big<const LIMBS: usize>(value: &Uint<LIMBS>) -> BigUint {
+        BigUint::from_bytes_le(&value.to_le_bytes())
+    }
+
+    /// Converts `value` into a [`U256`], returning `None` if it does not fit.
+    #[inline]
+    fn uint(value: &BigUint) -> Option<U256> {
+        U256::from_le_bytes(&value.to_bytes_le())
+    }
+
+    /// Samples an integer whose bit length is uniform over the possible lengths using `rng`, so
+    /// that small integers and single-limb integers are sampled as often as full-width ones.
+    #[inline]
+    fn sample(rng: &mut TestRng) -> U256 {
+        let limbs = [(); 4].map(|_| rng.next_u64());
+        U256::from_limbs(limbs).shr((rng.next_u64() % (U256::BITS as u64 + 1)) as usize)
+    }
+
+    /// Samples a nonzero integer using `rng`.
+    #[inline]
+    fn sample_nonzero(rng: &mut TestRng) -> U256 {
+        loop {
+            let value = sample(rng);
+            if !value.is_zero() {
+                return value;
+            }
+        }
+    }
+
+    /// Checks that additions, subtractions, multiplications, and shifts carry across limb
+    /// boundaries and report overflows out of the top limb.
+    #[test]
+    fn carries_cross_limb_boundaries() {
+        let low_limbs = Uint::<3>::from_limbs([u64::MAX, u64::MAX, 0]);
+        let top_limb = Uint::<3>::from_limbs([0, 0, 1]);
+        assert_eq!(
+            low_limbs.overflowing_add(&Uint::ONE),
+            (top_limb, false),
+            "Carries must propagate into the next limbs."
+        );
+        assert_eq!(
+            top_limb.overflowing_sub(&Uint::ONE),
+            (low_limbs, false),
+            "Borrows must propagate from the next limbs."
+        );
+        assert_eq!(
+            Uint::<3>::MAX.overflowing_add(&Uint::ONE),
+            (Uint::ZERO, true)
+        );
+        assert_eq!(
+            Uint::<3>::ZERO.overflowing_sub(&Uint::ONE),
+            (Uint::MAX, true)
+        );
+        assert_eq!(
+            Uint::<3>::MAX.widening_mul(&Uint::MAX),
+            (
+                Uint::ONE,
+                Uint::from_limbs([u64::MAX - 1, u64::MAX, u64::MAX])
+            ),
+            "The square of the maximum must fill both halves of the product."
+        );
+        assert_eq!(Uint::<3>::MAX.checked_mul(&Uint::from_u64(2)), None);
+        assert_eq!(
+            Uint::<3>::from_limbs([1 << 63, 0, 0]).shl(1),
+            Uint::from_limbs([0, 1, 0])
+        );
+        assert_eq!(
+            Uint::<3>::from_limbs([0, 1, 0]).shr(1),
+            Uint::from_limbs([1 << 63, 0, 0])
+        );
+        assert_eq!(
+            Uint::<3>::from_limbs([u64::MAX, 0, 0]).shl(64),
+            Uint::from_limbs([0, u64::MAX, 0])
+        );
+        assert_eq!(Uint::<3>::MAX.shl(Uint::<3>::BITS), Uint::ZERO);
+        assert_eq!(
+            Uint::<2>::from_bits_be(&[true; 128]),
+            Some(Uint::MAX),
+            "Bit strings of the full width must fit."
+        );
+        assert_eq!(
+            Uint::<2>::from_bits_be(&[&[true][..], &[false; 128][..]].concat()),
+            None,
+            "Bit strings which are wider than the integer must be rejected."
+        );
+        assert_eq!(
+            Uint::<2>::from_bits_be(&[&[false][..], &[true; 128][..]].concat()),
+            Some(Uint::MAX),
+            "Leading zero bits must be ignored."
+        );
+        assert_eq!(
+            Uint::<2>::from_u128(u128::MAX),
+            Some(Uint::MAX),
+            "Integers of two limbs must round-trip through `u128`."
+        );
+    }
+
+    /// Checks that [`Uint::inv_mod`] rejects integers which are not coprime to the modulus and
+    /// maps every integer to zero modulo one.
+    #[test]
+    fn inverses_respect_degenerate_moduli() {
+        let modulus = U256::from_u64(9);
+        for value in [0, 3, 6, 9, 12] {
+            assert_eq!(
+                U256::from_u64(value).inv_mod(&modulus),
+                None,
+                "Integers which are not coprime to the modulus must have no inverse."
+            );
+        }
+        assert_eq!(U256::from_u64(7).inv_mod(&modulus), Some(U256::from_u64(4)));
+        for value in [U256::ZERO, U256::ONE, U256::MAX] {
+            assert_eq!(
+                value.inv_mod(&U256::ONE),
+                Some(U256::ZERO),
+                "Every integer must be invertible modulo one, with inverse zero."
+            );
+            assert_eq!(value.pow_mod(&U256::MAX, &U256::ONE), U256::ZERO);
+        }
+        assert_eq!(U256::MAX.div_rem(&U256::ZERO), None);
+        assert_eq!(U256::MAX.rounded_div(&U256::ZERO), None);
+    }
+
+    /// Checks that division, reduction, inversion, exponentiation, rounded division, and bit
+    /// decoding agree with [`BigUint`] on random integers.
+    #[test]
+    fn arithmetic_matches_num_bigint() {
+        let mut rng = TestRng::new();
+        let two = BigUint::from(2u8);
+        for _ in 0..256 {
+            let (lhs, rhs, modulus) =
+                (sample(&mut rng), sample(&mut rng), sample_nonzero(&mut rng));
+            let (big_lhs, big_rhs, big_modulus) = (big(&lhs), big(&rhs), big(&modulus));
+            assert_eq!(
+                lhs.div_rem(&modulus),
+                Some((
+                    uint(&(&big_lhs / &big_modulus)).expect("The quotient fits."),
+                    uint(&(&big_lhs % &big_modulus)).expect("The remainder fits."),
+                )),
+                "Division with remainder must match."
+            );
+            assert_eq!(
+                big(&U256::reduce_wide(&lhs, &rhs, &modulus)),
+                ((&big_rhs << U256::BITS) + &big_lhs) % &big_modulus,
+                "Reduction of double-width integers must match."
+            );
+            assert_eq!(
+                big(&lhs.mul_mod(&rhs, &modulus)),
+                &big_lhs * &big_rhs % &big_modulus,
+                "Modular multiplication must match."
+            );
+            assert_eq!(
+                big(&lhs.pow_mod(&rhs, &modulus)),
+                big_lhs.modpow(&big_rhs, &big_modulus),
+                "Modular exponentiation must match."
+            );
+            if modulus != U256::ONE {
+                assert_eq!(
+                    lhs.inv_mod(&modulus).map(|inverse| big(&inverse)),
+                    big_lhs.modinv(&big_modulus),
+                    "Modular inversion must match."
+                );
+            }
+            assert_eq!(
+                lhs.rounded_div(&modulus).map(|quotient| big(&quotient)),
+                Some((&two * &big_lhs + &big_modulus) / (&two * &big_modulus)),
+                "Rounded division must round to the nearest integer with ties rounded up."
+            );
+            let bits = (0..lhs.bits())
+                .rev()
+                .map(|index| lhs.bit(index))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                U256::from_bits_be(&bits),
+                Some(lhs),
+                "Integers must round-trip through their big-endian bits."
+            );
+        }
+    }
+}
@@ -1,5 +1,7 @@
 //! Numeric Utilities
 
+pub mod big;
+
 /// Tries to convert `n` into a `usize` depending on how big the `usize` type is.
 #[inline]
 pub const fn u64_as_usize(n: u64) -> Result<usize, u64> {
@@ -39,6 +39,9 @@ pub trait PointerFamily<T> {
 
     /// Checks if two strong pointers point to the same allocation.
     fn strong_ptr_eq(lhs: &Self::Strong, rhs: &Self::Strong) -> bool;
+
+    /// Returns the number of weak pointers which are associated to `strong`.
+    fn weak_count(strong: &Self::Strong) -> usize;
 }
 
 /// Implements [`PointerFamily`] for `$type` with `$strong` and `$weak` pointers.
@@ -74,6 +77,11 @@ macro_rules! impl_pointer_family {
             fn strong_ptr_eq(lhs: &Self::Strong, rhs: &Self::Strong) -> bool {
                 $strong::ptr_eq(lhs, rhs)
             }
+
+            #[inline]
+            fn weak_count(strong: &Self::Strong) -> usize {
+                $strong::weak_count(strong)
+            }
         }
     };
 }
@@ -129,4 +137,10 @@ impl<T> PointerFamily<T> for NoPointer {
     fn strong_ptr_eq(lhs: &Self::Strong, rhs: &Self::Strong) -> bool {
         core::ptr::eq(lhs, rhs)
     }
+
+    #[inline]
+    fn weak_count(strong: &Self::Strong) -> usize {
+        let _ = strong;
+        0
+    }
 }
@@ -12,10 +12,23 @@ use crate::serde::{Deserialize, Serialize};
 
 pub use rand_core::{block, CryptoRng, Error, RngCore, SeedableRng};
 
+#[cfg(feature = "hkdf")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hkdf")))]
+pub mod policy;
+
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test;
+
 #[cfg(feature = "rand_chacha")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "rand_chacha")))]
 pub use rand_chacha::*;
 
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+#[doc(inline)]
+pub use test::{TestRng, TestTranscript};
+
 #[cfg(feature = "getrandom")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "getrandom")))]
 #[doc(inline)]
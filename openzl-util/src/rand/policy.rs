@@ -0,0 +1,274 @@
+//! Reseeding Policies and Deterministic Derivation
+//!
+//! Long-lived provers keep a random number generator around for a long time, and signing or
+//! encryption randomness drawn from it should be both refreshed with new entropy and reproducible
+//! in tests. The [`HkdfRng`] in this module keeps its state as an HKDF pseudorandom key: it mixes
+//! fresh entropy from an [`EntropySource`] into the key whenever its [`SeedableRngPolicy`] asks for
+//! it, and it derives independent child generators from labels with
+//! [`derive_child`](HkdfRng::derive_child), so every consumer of randomness can be given its own
+//! auditable stream.
+
+use crate::rand::{CryptoRng, Error, RngCore, SeedableRng};
+use core::num::NonZeroU32;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+#[cfg(feature = "serde")]
+use crate::serde::{Deserialize, Serialize};
+
+/// Entropy Source
+pub trait EntropySource {
+    /// Fills `dest` with fresh entropy, returning an error if the source is unavailable.
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Error>;
+}
+
+impl<R> EntropySource for R
+where
+    R: CryptoRng + RngCore + ?Sized,
+{
+    #[inline]
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.try_fill_bytes(dest)
+    }
+}
+
+/// Missing Entropy Source
+///
+/// This source always fails to produce entropy. It can be used for generators which are never
+/// reseeded, like those with the [`NeverReseed`] policy.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NoEntropy;
+
+impl EntropySource for NoEntropy {
+    #[inline]
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let _ = dest;
+        Err(Error::from(
+            NonZeroU32::new(Error::CUSTOM_START).expect("This value is non-zero."),
+        ))
+    }
+}
+
+/// Seedable Random Number Generator Policy
+///
+/// A policy decides when a random number generator must mix fresh entropy into its state.
+pub trait SeedableRngPolicy {
+    /// Returns `true` if the generator must be reseeded before producing more randomness, given
+    /// that it has `generated` bytes since it was last seeded.
+    fn should_reseed(&self, generated: u64) -> bool;
+}
+
+/// Never Reseed Policy
+///
+/// Generators with this policy are fully determined by their seed, which makes them suitable for
+/// reproducing randomness in tests.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NeverReseed;
+
+impl SeedableRngPolicy for NeverReseed {
+    #[inline]
+    fn should_reseed(&self, generated: u64) -> bool {
+        let _ = generated;
+        false
+    }
+}
+
+/// Reseed After Policy
+///
+/// Generators with this policy are reseeded every time they have generated the given number of
+/// bytes.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ReseedAfter(
+    /// Number of Bytes Generated between Reseeds
+    pub u64,
+);
+
+impl SeedableRngPolicy for ReseedAfter {
+    #[inline]
+    fn should_reseed(&self, generated: u64) -> bool {
+        generated >= self.0
+    }
+}
+
+/// HKDF Salt used to Extract the Initial Key
+const SEED_SALT: &[u8] = b"openzl/rand/seed";
+
+/// HKDF Info used to Expand the Generator Seed
+const GENERATOR_INFO: &[u8] = b"openzl/rand/generator";
+
+/// HKDF Info Prefix used to Expand Child Keys
+const CHILD_INFO: &[u8] = b"openzl/rand/child";
+
+/// HKDF Random Number Generator
+///
+/// This generator keeps an HKDF-SHA256 pseudorandom key and expands it into the seed of the
+/// underlying generator `R`. Reseeding extracts a new key from fresh entropy using the current key
+/// as salt, so the key never loses the entropy it has already absorbed, and
+/// [`derive_child`](Self::derive_child) expands the key with a label into the key of a child
+/// generator.
+#[derive(Clone)]
+pub struct HkdfRng<R, P = NeverReseed, E = NoEntropy> {
+    /// Pseudorandom Key
+    key: [u8; 32],
+
+    /// Underlying Generator
+    rng: R,
+
+    /// Reseeding Policy
+    policy: P,
+
+    /// Entropy Source
+    entropy: E,
+
+    /// Number of Bytes Generated since the last Reseed
+    generated: u64,
+
+    /// Number of Reseeds
+    generation: u64,
+}
+
+impl<R, P, E> HkdfRng<R, P, E>
+where
+    R: SeedableRng<Seed = [u8; 32]>,
+    P: SeedableRngPolicy,
+    E: EntropySource,
+{
+    /// Builds a new [`HkdfRng`] by extracting its key from the input keying material `seed`.
+    #[inline]
+    pub fn new(seed: &[u8], policy: P, entropy: E) -> Self {
+        Self::from_key(extract(SEED_SALT, seed), policy, entropy)
+    }
+
+    /// Builds a new [`HkdfRng`] from its pseudorandom `key`.
+    #[inline]
+    fn from_key(key: [u8; 32], policy: P, entropy: E) -> Self {
+        Self {
+            rng: R::from_seed(expand(&key, &[GENERATOR_INFO])),
+            key,
+            policy,
+            entropy,
+            generated: 0,
+            generation: 0,
+        }
+    }
+
+    /// Returns the number of times `self` has been reseeded.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Mixes fresh entropy into the key of `self` and reseeds the underlying generator.
+    #[inline]
+    pub fn reseed(&mut self) -> Result<(), Error> {
+        let mut entropy = [0; 32];
+        self.entropy.fill_entropy(&mut entropy)?;
+        self.key = extract(&self.key, &entropy);
+        self.rng = R::from_seed(expand(&self.key, &[GENERATOR_INFO]));
+        self.generated = 0;
+        self.generation += 1;
+        Ok(())
+    }
+
+    /// Derives the child generator of `self` with the given `label`.
+    ///
+    /// The child only depends on the current key of `self` and on `label`, so the same hierarchy of
+    /// labels always reproduces the same randomness from the same seed. Distinct labels give
+    /// independent generators. The child starts with a copy of the policy and entropy source of
+    /// `self`.
+    #[inline]
+    pub fn derive_child(&self, label: &[u8]) -> Self
+    where
+        P: Clone,
+        E: Clone,
+    {
+        Self::from_key(
+            expand(&self.key, &[CHILD_INFO, label]),
+            self.policy.clone(),
+            self.entropy.clone(),
+        )
+    }
+
+    /// Reseeds `self` if its policy requires it and accounts for `len` more generated bytes.
+    #[inline]
+    fn prepare(&mut self, len: usize) -> Result<(), Error> {
+        if self.policy.should_reseed(self.generated) {
+            self.reseed()?;
+        }
+        self.generated = self.generated.saturating_add(len as u64);
+        Ok(())
+    }
+}
+
+impl<R, P, E> CryptoRng for HkdfRng<R, P, E>
+where
+    R: CryptoRng + SeedableRng<Seed = [u8; 32]>,
+    P: SeedableRngPolicy,
+    E: EntropySource,
+{
+}
+
+impl<R, P, E> RngCore for HkdfRng<R, P, E>
+where
+    R: RngCore + SeedableRng<Seed = [u8; 32]>,
+    P: SeedableRngPolicy,
+    E: EntropySource,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.prepare(4)
+            .expect("Reseeding from the entropy source failed.");
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.prepare(8)
+            .expect("Reseeding from the entropy source failed.");
+        self.rng.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("Reseeding from the entropy source failed.");
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.prepare(dest.len())?;
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Extracts an HKDF-SHA256 pseudorandom key from `input` with the given `salt`.
+#[inline]
+fn extract(salt: &[u8], input: &[u8]) -> [u8; 32] {
+    Hkdf::<Sha256>::extract(Some(salt), input).0.into()
+}
+
+/// Expands the HKDF-SHA256 pseudorandom `key` with the concatenation of `info` into 32 bytes.
+#[inline]
+fn expand(key: &[u8; 32], info: &[&[u8]]) -> [u8; 32] {
+    let mut output = [0; 32];
+    Hkdf::<Sha256>::from_prk(key)
+        .expect("The key has the length of the SHA-256 output.")
+        .expand_multi_info(info, &mut output)
+        .expect("The output is shorter than the HKDF maximum output length.");
+    output
+}
@@ -0,0 +1,332 @@
+//! Reproducible Test Randomness
+
+use crate::{
+    codec::Encode,
+    rand::{ChaCha20Rng, CryptoRng, Error, OsRng, RngCore, Sample, SeedableRng},
+};
+use std::{env, thread, vec::Vec};
+
+/// Seed Environment Variable
+///
+/// When this variable holds a `u64`, every [`TestRng`] built with [`TestRng::new`] is seeded from
+/// it, so that a failing test can be replayed exactly.
+pub const SEED_VARIABLE: &str = "OPENZL_TEST_SEED";
+
+/// Test Random Number Generator
+///
+/// A [`ChaCha20Rng`] which is seeded from the [`SEED_VARIABLE`] environment variable, or from a
+/// fresh [`OsRng`] seed when the variable is not set. If the current thread panics while the
+/// generator is alive, its seed is printed to standard error together with the command-line
+/// setting which replays it.
+#[derive(Debug)]
+pub struct TestRng {
+    /// Seed
+    seed: u64,
+
+    /// Random Number Generator
+    rng: ChaCha20Rng,
+}
+
+impl TestRng {
+    /// Builds a new [`TestRng`] from the [`SEED_VARIABLE`] environment variable, or from a fresh
+    /// seed if it is not set.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the environment variable is set but does not hold a `u64`.
+    #[inline]
+    pub fn new() -> Self {
+        match env::var(SEED_VARIABLE) {
+            Ok(seed) => Self::from_seed_value(seed.trim().parse().unwrap_or_else(|_| {
+                panic!("The {SEED_VARIABLE} environment variable must hold a `u64`.")
+            })),
+            _ => Self::from_seed_value(OsRng.next_u64()),
+        }
+    }
+
+    /// Builds a new [`TestRng`] from `seed`, ignoring the [`SEED_VARIABLE`] environment variable.
+    #[inline]
+    pub fn from_seed_value(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha20Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the seed of `self`.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for TestRng {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestRng {
+    #[inline]
+    fn drop(&mut self) {
+        if thread::panicking() {
+            eprintln!(
+                "note: the failing test used the seed {seed}, run it again with {SEED_VARIABLE}={seed} to reproduce the failure",
+                seed = self.seed,
+            );
+        }
+    }
+}
+
+impl CryptoRng for TestRng {}
+
+impl RngCore for TestRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Test Transcript Interaction
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Interaction {
+    /// Observed Value
+    Observe(&'static str),
+
+    /// Derived Challenge
+    Challenge(&'static str),
+}
+
+/// Test Transcript
+///
+/// A duplex over [`ChaCha20Rng`] which derives the inputs of a protocol test from a seed and
+/// from everything the test has observed so far. Values produced by one party of the protocol are
+/// [`observe`](Self::observe)d and the inputs of the next party are drawn with
+/// [`challenge`](Self::challenge) or [`sample`](Self::sample), so the inputs of every step depend
+/// on the steps before it and the order of interactions is recorded in
+/// [`interactions`](Self::interactions).
+///
+/// The seed is taken from a [`TestRng`], so a failing test prints the seed which replays the
+/// whole transcript.
+///
+/// # Warning
+///
+/// This is only meant for testing purposes, and should not be used as a Fiat-Shamir transcript in
+/// production or cryptographically secure environments.
+#[derive(Debug)]
+pub struct TestTranscript {
+    /// Seed Generator
+    rng: TestRng,
+
+    /// Duplex State
+    state: [u8; 32],
+
+    /// Interactions
+    interactions: Vec<Interaction>,
+}
+
+impl TestTranscript {
+    /// Builds a new [`TestTranscript`] for the protocol named by `domain`, seeded by a
+    /// [`TestRng`] built with [`TestRng::new`].
+    #[inline]
+    pub fn new(domain: &'static str) -> Self {
+        Self::from_rng(domain, TestRng::new())
+    }
+
+    /// Builds a new [`TestTranscript`] for the protocol named by `domain` from `seed`, ignoring
+    /// the [`SEED_VARIABLE`] environment variable.
+    #[inline]
+    pub fn from_seed_value(domain: &'static str, seed: u64) -> Self {
+        Self::from_rng(domain, TestRng::from_seed_value(seed))
+    }
+
+    /// Builds a new [`TestTranscript`] for the protocol named by `domain`, seeding its state from
+    /// `rng`.
+    #[inline]
+    fn from_rng(domain: &'static str, mut rng: TestRng) -> Self {
+        let mut state = [0; 32];
+        rng.fill_bytes(&mut state);
+        let mut transcript = Self {
+            rng,
+            state,
+            interactions: Vec::new(),
+        };
+        transcript.absorb(domain.as_bytes());
+        transcript
+    }
+
+    /// Returns the seed of `self`.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Returns the interactions with `self` in the order in which they happened.
+    #[inline]
+    pub fn interactions(&self) -> &[Interaction] {
+        &self.interactions
+    }
+
+    /// Replaces the state with the first block of the key stream which it seeds, returning the
+    /// rest of the key stream.
+    #[inline]
+    fn permute(&mut self) -> ChaCha20Rng {
+        let mut rng = ChaCha20Rng::from_seed(self.state);
+        rng.fill_bytes(&mut self.state);
+        rng
+    }
+
+    /// Absorbs the length of `bytes` and `bytes` into the state.
+    #[inline]
+    fn absorb(&mut self, bytes: &[u8]) {
+        let len = (bytes.len() as u64).to_le_bytes();
+        for chunk in [&len[..]].into_iter().chain(bytes.chunks(32)) {
+            for (state, byte) in self.state.iter_mut().zip(chunk) {
+                *state ^= byte;
+            }
+            self.permute();
+        }
+    }
+
+    /// Absorbs `value` into the transcript under `label`.
+    #[inline]
+    pub fn observe<T>(&mut self, label: &'static str, value: &T)
+    where
+        T: Encode + ?Sized,
+    {
+        self.interactions.push(Interaction::Observe(label));
+        self.absorb(label.as_bytes());
+        self.absorb(&value.to_vec());
+    }
+
+    /// Returns a random number generator derived from the transcript under `label`. All the
+    /// randomness of the challenge should be drawn from the returned generator.
+    #[inline]
+    pub fn challenge(&mut self, label: &'static str) -> ChaCha20Rng {
+        self.interactions.push(Interaction::Challenge(label));
+        self.absorb(label.as_bytes());
+        self.permute()
+    }
+
+    /// Samples a value of type `T` from the default distribution with the randomness of
+    /// [`challenge`](Self::challenge) under `label`.
+    #[inline]
+    pub fn sample<T, D>(&mut self, label: &'static str) -> T
+    where
+        T: Sample<D>,
+        D: Default,
+    {
+        T::gen(&mut self.challenge(label))
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    /// Returns the first values of the stream of `rng`.
+    #[inline]
+    fn stream(mut rng: TestRng) -> (Vec<u64>, [u8; 37]) {
+        let words = (0..8).map(|_| rng.next_u64()).collect();
+        let mut bytes = [0; 37];
+        rng.fill_bytes(&mut bytes);
+        (words, bytes)
+    }
+
+    /// Runs the same protocol steps on `transcript`, returning the drawn challenges.
+    #[inline]
+    fn run(mut transcript: TestTranscript, commitment: u64) -> (Vec<u64>, TestTranscript) {
+        transcript.observe("commitment", &commitment);
+        let first = transcript.challenge("alpha").next_u64();
+        transcript.observe("response", &[1u8, 2, 3][..]);
+        let second = transcript.sample::<u64, ()>("beta");
+        (vec![first, second], transcript)
+    }
+
+    /// Checks that setting the [`SEED_VARIABLE`] environment variable makes [`TestRng::new`]
+    /// reproduce the stream of [`TestRng::from_seed_value`] for that seed.
+    #[test]
+    fn seed_variable_reproduces_stream() {
+        let previous = env::var_os(SEED_VARIABLE);
+        env::set_var(SEED_VARIABLE, " 42 ");
+        let (first, second) = (TestRng::new(), TestRng::new());
+        match previous {
+            Some(previous) => env::set_var(SEED_VARIABLE, previous),
+            None => env::remove_var(SEED_VARIABLE),
+        }
+        assert_eq!(first.seed(), 42, "The seed must be read from the variable.");
+        assert_eq!(
+            second.seed(),
+            42,
+            "The seed must be read from the variable."
+        );
+        let expected = stream(TestRng::from_seed_value(42));
+        assert_eq!(
+            stream(first),
+            expected,
+            "Generators built from the variable must reproduce the stream of their seed."
+        );
+        assert_eq!(
+            stream(second),
+            expected,
+            "Generators built from the variable must reproduce the stream of their seed."
+        );
+        assert_ne!(
+            stream(TestRng::from_seed_value(43)),
+            expected,
+            "Different seeds should produce different streams."
+        );
+    }
+
+    /// Checks that transcripts with the same seed and the same interactions derive the same
+    /// challenges, and that the challenges depend on the seed, the domain and the observed values.
+    #[test]
+    fn transcript_is_deterministic() {
+        let (challenges, transcript) = run(TestTranscript::from_seed_value("protocol", 7), 5);
+        assert_eq!(transcript.seed(), 7, "The transcript should keep its seed.");
+        assert_eq!(
+            transcript.interactions(),
+            [
+                Interaction::Observe("commitment"),
+                Interaction::Challenge("alpha"),
+                Interaction::Observe("response"),
+                Interaction::Challenge("beta"),
+            ],
+            "The interactions must be recorded in order."
+        );
+        assert_eq!(
+            run(TestTranscript::from_seed_value("protocol", 7), 5).0,
+            challenges,
+            "Replaying a transcript must derive the same challenges."
+        );
+        for (transcript, commitment) in [
+            (TestTranscript::from_seed_value("protocol", 8), 5),
+            (TestTranscript::from_seed_value("other protocol", 7), 5),
+            (TestTranscript::from_seed_value("protocol", 7), 6),
+        ] {
+            assert_ne!(
+                run(transcript, commitment).0,
+                challenges,
+                "Challenges should depend on the seed, the domain and the observed values."
+            );
+        }
+    }
+}
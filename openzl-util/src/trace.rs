@@ -0,0 +1,39 @@
+//! Tracing Instrumentation
+//!
+//! The proving pipeline marks its expensive stages, like parameter generation, witness synthesis,
+//! constraint generation, and proof creation, with [`trace_span!`](crate::trace_span). With the
+//! `tracing` feature enabled, every mark enters a [`tracing`] span at the `INFO` level which lasts
+//! until the end of the enclosing block, so that services can forward proving latency to their
+//! existing subscribers. Without the `tracing` feature, the macro expands to nothing, and neither
+//! the span nor its fields are ever evaluated.
+//!
+//! Span names are prefixed with `openzl::` followed by the crate or plugin which emits them.
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "tracing")))]
+#[doc(inline)]
+pub use tracing;
+
+/// Enters an `INFO` level span with the name `$name` and the optional `$fields` until the end of
+/// the enclosing block.
+///
+/// The fields follow the [`tracing::span!`] syntax. See the [`trace`](crate::trace) module for
+/// more.
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! trace_span {
+    ($name:expr $(, $($fields:tt)*)?) => {
+        let __openzl_span = $crate::trace::tracing::info_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
+/// Enters an `INFO` level span with the name `$name` and the optional `$fields` until the end of
+/// the enclosing block.
+///
+/// The `tracing` feature is disabled, so this macro expands to nothing. See the
+/// [`trace`](crate::trace) module for more.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! trace_span {
+    ($name:expr $(, $($fields:tt)*)?) => {};
+}
@@ -0,0 +1,329 @@
+//! OpenZL WebAssembly Bindings
+//!
+//! This crate exposes the fixed circuits of [`openzl_ffi`] to JavaScript with `wasm-bindgen`. Keys
+//! and proofs are passed as `Uint8Array`s with the same [`encoding`]s as the C interface: field
+//! elements are concatenated canonical little-endian byte strings, and proving keys, verifying
+//! keys, and proofs use the compressed `arkworks` canonical encoding, which is checked for
+//! validity when decoded.
+//!
+//! Generating a proof blocks the calling thread, so browser applications should prove inside of a
+//! web worker. A [`ProvingSession`] splits the proofs for a batch of witnesses into small steps so
+//! that the worker can post progress messages or handle cancellation between steps.
+
+#![cfg_attr(doc_cfg, feature(doc_cfg))]
+#![forbid(rustdoc::broken_intra_doc_links)]
+#![forbid(missing_docs)]
+
+use arkworks::{
+    bn254::Bn254,
+    groth16::{Proof, ProvingContext, VerifyingContext, VerifyingKey},
+};
+use core::fmt;
+use openzl_crypto::constraint::ProofSystem;
+use openzl_ffi::{
+    circuit::{self, Circuit, Poseidon2, ProofSystemType},
+    encoding::{self, canonical, field_element_batches, field_elements, to_canonical},
+};
+use openzl_util::rand::OsRng;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+mod session;
+
+pub use session::*;
+
+/// Binding Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// The circuit identifier does not name a known circuit.
+    UnknownCircuit,
+
+    /// A byte string does not have the length expected for its contents.
+    InvalidLength,
+
+    /// A byte string does not encode a canonical field element.
+    InvalidFieldElement,
+
+    /// A key or proof could not be decoded.
+    InvalidEncoding,
+
+    /// The witness does not satisfy the constraints of the circuit.
+    UnsatisfiedWitness,
+
+    /// The proof system returned an error.
+    ProofSystem,
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnknownCircuit => "unknown circuit",
+            Self::InvalidLength => "invalid length",
+            Self::InvalidFieldElement => "invalid field element",
+            Self::InvalidEncoding => "invalid encoding",
+            Self::UnsatisfiedWitness => "unsatisfied witness",
+            Self::ProofSystem => "proof system error",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<encoding::Error> for Error {
+    #[inline]
+    fn from(error: encoding::Error) -> Self {
+        match error {
+            encoding::Error::InvalidLength => Self::InvalidLength,
+            encoding::Error::InvalidFieldElement => Self::InvalidFieldElement,
+            encoding::Error::InvalidEncoding => Self::InvalidEncoding,
+        }
+    }
+}
+
+impl From<Error> for JsValue {
+    #[inline]
+    fn from(error: Error) -> Self {
+        JsError::from(error).into()
+    }
+}
+
+/// Returns the circuit with the given `id`.
+#[inline]
+fn circuit(id: u32) -> Result<Circuit, Error> {
+    Circuit::from_id(id).ok_or(Error::UnknownCircuit)
+}
+
+/// Encoded Proving and Verifying Keys
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Keys {
+    /// Encoded Proving Key
+    proving_key: Vec<u8>,
+
+    /// Encoded Verifying Key
+    verifying_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Keys {
+    /// Returns the encoded proving key.
+    #[wasm_bindgen(getter, js_name = provingKey)]
+    pub fn proving_key(&self) -> Vec<u8> {
+        self.proving_key.clone()
+    }
+
+    /// Returns the encoded verifying key.
+    #[wasm_bindgen(getter, js_name = verifyingKey)]
+    pub fn verifying_key(&self) -> Vec<u8> {
+        self.verifying_key.clone()
+    }
+}
+
+/// Generates a fresh proving key and verifying key for the circuit with the given `circuit_id`.
+#[wasm_bindgen]
+pub fn setup(circuit_id: u32) -> Result<Keys, Error> {
+    let circuit = circuit(circuit_id)?;
+    let (proving_context, verifying_context) = ProofSystemType::compile(
+        &(),
+        circuit.context_compiler(&circuit::hasher()),
+        &mut OsRng,
+    )
+    .map_err(|_| Error::ProofSystem)?;
+    Ok(Keys {
+        proving_key: to_canonical(&proving_context),
+        verifying_key: to_canonical(&verifying_context.0.vk),
+    })
+}
+
+/// Generated Proof
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ProofOutput {
+    /// Encoded Proof
+    proof: Vec<u8>,
+
+    /// Encoded Public Input
+    input: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ProofOutput {
+    /// Returns the encoded proof.
+    #[wasm_bindgen(getter)]
+    pub fn proof(&self) -> Vec<u8> {
+        self.proof.clone()
+    }
+
+    /// Returns the encoded public input.
+    #[wasm_bindgen(getter)]
+    pub fn input(&self) -> Vec<u8> {
+        self.input.clone()
+    }
+}
+
+/// Prover State
+#[derive(Debug)]
+struct ProverState {
+    /// Circuit
+    circuit: Circuit,
+
+    /// Poseidon Hasher
+    hasher: Poseidon2,
+
+    /// Proving Context
+    context: ProvingContext<Bn254>,
+}
+
+/// Prover
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Prover {
+    /// Prover State
+    state: Rc<ProverState>,
+}
+
+#[wasm_bindgen]
+impl Prover {
+    /// Loads the encoded `proving_key` for the circuit with the given `circuit_id`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(circuit_id: u32, proving_key: &[u8]) -> Result<Prover, Error> {
+        Ok(Self {
+            state: Rc::new(ProverState {
+                circuit: circuit(circuit_id)?,
+                hasher: circuit::hasher(),
+                context: canonical::<ProvingContext<Bn254>>(proving_key)?,
+            }),
+        })
+    }
+
+    /// Generates a proof for the encoded `witness`, which must hold exactly one witness.
+    pub fn prove(&self, witness: &[u8]) -> Result<ProofOutput, Error> {
+        let witness = field_elements(witness, self.state.circuit.witness_len())?;
+        let mut session = ProvingSession::new(self.state.clone(), vec![witness]);
+        while session.step()? {}
+        Ok(session
+            .take(0)
+            .expect("The session has finished its only proof."))
+    }
+
+    /// Starts a [`ProvingSession`] for the concatenation of encoded `witnesses`.
+    pub fn session(&self, witnesses: &[u8]) -> Result<ProvingSession, Error> {
+        let witnesses = field_element_batches(witnesses, self.state.circuit.witness_len())?;
+        Ok(ProvingSession::new(self.state.clone(), witnesses))
+    }
+}
+
+/// Verifier
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Verifier {
+    /// Circuit
+    circuit: Circuit,
+
+    /// Verifying Context
+    context: VerifyingContext<Bn254>,
+}
+
+#[wasm_bindgen]
+impl Verifier {
+    /// Loads the encoded `verifying_key` for the circuit with the given `circuit_id`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(circuit_id: u32, verifying_key: &[u8]) -> Result<Verifier, Error> {
+        let circuit = circuit(circuit_id)?;
+        let context =
+            VerifyingContext::from_verifying_key(&canonical::<VerifyingKey<Bn254>>(verifying_key)?);
+        if context.input_len() != circuit.input_len() {
+            return Err(Error::InvalidLength);
+        }
+        Ok(Self { circuit, context })
+    }
+
+    /// Verifies the encoded `proof` against the encoded public `input`.
+    pub fn verify(&self, input: &[u8], proof: &[u8]) -> Result<bool, Error> {
+        let input = field_elements(input, self.circuit.input_len())?
+            .into_iter()
+            .map(|element| element.0)
+            .collect::<Vec<_>>();
+        ProofSystemType::verify_proof(&self.context, &input, &Proof(canonical(proof)?))
+            .map_err(|_| Error::ProofSystem)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use openzl_ffi::{circuit::Field, encoding::field_bytes};
+
+    /// Encodes `values` as a sequence of field elements.
+    #[inline]
+    fn encode(values: &[u64]) -> Vec<u8> {
+        field_bytes(
+            &values
+                .iter()
+                .map(|value| Field::from(u128::from(*value)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Tests that proofs generated in one call and in a stepped session verify.
+    #[test]
+    fn proofs_verify() {
+        let circuit = Circuit::RangeCommitment.id();
+        let keys = setup(circuit).expect("Unable to generate keys.");
+        let prover = Prover::new(circuit, &keys.proving_key()).expect("Unable to load prover.");
+        let verifier =
+            Verifier::new(circuit, &keys.verifying_key()).expect("Unable to load verifier.");
+        let output = prover
+            .prove(&encode(&[3, 5]))
+            .expect("Unable to generate proof.");
+        assert!(verifier
+            .verify(&output.input(), &output.proof())
+            .expect("Unable to verify proof."));
+        assert!(!verifier
+            .verify(&encode(&[7]), &output.proof())
+            .expect("Unable to verify proof."));
+        let mut session = prover
+            .session(&encode(&[1, 2, 3, 4, 5, 6]))
+            .expect("Unable to start session.");
+        assert_eq!(session.total(), 3);
+        let mut steps = 0;
+        while session.step().expect("Unable to generate proofs.") {
+            steps += 1;
+            assert!(session.progress() < 1.0);
+        }
+        assert_eq!(steps + 1, 2 * session.total());
+        assert_eq!(session.progress(), 1.0);
+        for index in 0..session.total() {
+            let output = session
+                .take(index)
+                .expect("Every proof has been generated.");
+            assert!(verifier
+                .verify(&output.input(), &output.proof())
+                .expect("Unable to verify proof."));
+        }
+    }
+
+    /// Tests that witnesses which violate the circuit are rejected before proving.
+    #[test]
+    fn unsatisfied_witness_is_rejected() {
+        let circuit = Circuit::RangeCommitment.id();
+        let keys = setup(circuit).expect("Unable to generate keys.");
+        let prover = Prover::new(circuit, &keys.proving_key()).expect("Unable to load prover.");
+        let mut witness = encode(&[0, 5]);
+        witness[8] = 1;
+        assert_eq!(
+            prover.prove(&witness).map(|_| ()),
+            Err(Error::UnsatisfiedWitness)
+        );
+        assert_eq!(
+            prover.prove(&encode(&[3])).map(|_| ()),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            prover.prove(&encode(&[1, 2, 3, 4])).map(|_| ()),
+            Err(Error::InvalidLength),
+            "Proving must reject encodings of more than one witness."
+        );
+    }
+}
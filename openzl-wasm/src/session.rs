@@ -0,0 +1,172 @@
+//! Chunked Proving
+
+use crate::{Error, ProofOutput, ProverState};
+use arkworks::{bn254::Fr, constraint::R1CS};
+use core::mem;
+use openzl_crypto::constraint::ProofSystem;
+use openzl_ffi::{
+    circuit::{Field, ProofSystemType},
+    encoding::{field_bytes, to_canonical},
+};
+use openzl_util::rand::OsRng;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/// Proving Stage
+#[derive(Debug)]
+enum Stage {
+    /// Synthesis of the Proof Compiler for the Witness at the given Index
+    Synthesize(usize),
+
+    /// Proof Generation for the Witness at the given Index
+    Prove {
+        /// Witness Index
+        index: usize,
+
+        /// Public Input
+        input: Vec<Field>,
+
+        /// Proof Compiler
+        compiler: R1CS<Fr>,
+    },
+
+    /// Finished Session
+    Done,
+
+    /// Failed Session
+    Failed(Error),
+}
+
+/// Proving Session
+///
+/// A session proves a batch of witnesses in steps. Every call to [`step`](Self::step) either
+/// builds the constraint system for the next witness or generates its proof, so a web worker can
+/// yield to its event loop between steps. A session stops at the first failing step and returns
+/// the same error from every later call to [`step`](Self::step).
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct ProvingSession {
+    /// Prover State
+    prover: Rc<ProverState>,
+
+    /// Witnesses
+    witnesses: Vec<Vec<Field>>,
+
+    /// Generated Proofs
+    outputs: Vec<Option<ProofOutput>>,
+
+    /// Number of Finished Steps
+    steps: usize,
+
+    /// Current Stage
+    stage: Stage,
+}
+
+impl ProvingSession {
+    /// Builds a new [`ProvingSession`] for `witnesses` with `prover`.
+    #[inline]
+    pub(crate) fn new(prover: Rc<ProverState>, witnesses: Vec<Vec<Field>>) -> Self {
+        let stage = if witnesses.is_empty() {
+            Stage::Done
+        } else {
+            Stage::Synthesize(0)
+        };
+        Self {
+            prover,
+            witnesses,
+            outputs: Vec::new(),
+            steps: 0,
+            stage,
+        }
+    }
+
+    /// Runs the current stage and returns the next one.
+    #[inline]
+    fn advance(&mut self, stage: Stage) -> Result<Stage, Error> {
+        let prover = &self.prover;
+        match stage {
+            Stage::Synthesize(index) => {
+                let witness = &self.witnesses[index];
+                let input = prover.circuit.public_input(&prover.hasher, witness);
+                let compiler = prover
+                    .circuit
+                    .proof_compiler(&prover.hasher, &input, witness);
+                if !compiler.is_satisfied() {
+                    return Err(Error::UnsatisfiedWitness);
+                }
+                Ok(Stage::Prove {
+                    index,
+                    input,
+                    compiler,
+                })
+            }
+            Stage::Prove {
+                index,
+                input,
+                compiler,
+            } => {
+                let proof = ProofSystemType::prove(&prover.context, compiler, &mut OsRng)
+                    .map_err(|_| Error::ProofSystem)?;
+                self.outputs.push(Some(ProofOutput {
+                    proof: to_canonical(&proof.0),
+                    input: field_bytes(&input),
+                }));
+                if index + 1 < self.witnesses.len() {
+                    Ok(Stage::Synthesize(index + 1))
+                } else {
+                    Ok(Stage::Done)
+                }
+            }
+            stage => Ok(stage),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ProvingSession {
+    /// Runs the next step of this session, returning `true` if there are steps left.
+    pub fn step(&mut self) -> Result<bool, Error> {
+        match mem::replace(&mut self.stage, Stage::Done) {
+            Stage::Done => Ok(false),
+            Stage::Failed(error) => {
+                self.stage = Stage::Failed(error);
+                Err(error)
+            }
+            stage => match self.advance(stage) {
+                Ok(stage) => {
+                    self.steps += 1;
+                    self.stage = stage;
+                    Ok(!matches!(self.stage, Stage::Done))
+                }
+                Err(error) => {
+                    self.stage = Stage::Failed(error);
+                    Err(error)
+                }
+            },
+        }
+    }
+
+    /// Returns the fraction of the steps of this session which have finished.
+    pub fn progress(&self) -> f64 {
+        match self.witnesses.len() {
+            0 => 1.0,
+            len => self.steps as f64 / (2 * len) as f64,
+        }
+    }
+
+    /// Returns the number of witnesses in this session.
+    pub fn total(&self) -> u32 {
+        self.witnesses.len() as u32
+    }
+
+    /// Returns the number of proofs generated so far.
+    pub fn completed(&self) -> u32 {
+        self.outputs.len() as u32
+    }
+
+    /// Takes the proof for the witness at `index` out of this session, returning `None` if it has
+    /// not been generated or was already taken.
+    pub fn take(&mut self, index: u32) -> Option<ProofOutput> {
+        self.outputs.get_mut(index as usize)?.take()
+    }
+}
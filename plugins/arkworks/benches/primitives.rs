@@ -0,0 +1,339 @@
+//! Core Primitive Benchmarks
+//!
+//! Every benchmark in this suite runs over BN254 and is parameterized by the constants below so
+//! that measurements are comparable across runs and releases.
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use eclair::{
+    alloc::{
+        mode::{Public, Secret},
+        Allocate, Allocator,
+    },
+    bool::{AssertEq, ConditionalSelect},
+    num::Zero,
+};
+use openzl_crypto::{
+    algebra::{Group, Window},
+    constraint::ProofSystem,
+    hash::ArrayHashFunction,
+    merkle_tree::{
+        full::FullMerkleTree, Configuration, HashConfiguration, IdentityLeafHash, InnerHash,
+        Parameters,
+    },
+    permutation::PseudorandomPermutation,
+    poseidon::{self, hash::Hasher, Permutation, State},
+};
+use openzl_plugin_arkworks::{
+    bn254::{Bn254, Fr, G1Projective},
+    constraint::{fp::Fp, FpVar, R1CS},
+    ec::ProjectiveCurve,
+    ff::{self, BigInteger, PrimeField, UniformRand},
+    groth16::Groth16,
+    poseidon::{Spec, TwoPowerMinusOneDomainTag},
+};
+use openzl_util::rand::{ChaCha20Rng, Sample, SeedableRng};
+
+/// Poseidon Hasher with Arity Two
+type Poseidon2<COM = ()> = Hasher<Spec<Fr, 2>, TwoPowerMinusOneDomainTag, 2, COM>;
+
+/// Numbers of Inputs Hashed by each Batched Poseidon Benchmark
+const POSEIDON_BATCH_SIZES: [usize; 3] = [64, 1024, 16384];
+
+/// Number of Leaves Inserted into each Merkle Tree before Proving
+const MERKLE_TREE_LEAF_COUNT: usize = 128;
+
+/// Number of Poseidon Hashes in each Groth16 Benchmark Circuit
+const GROTH16_HASH_COUNTS: [usize; 3] = [1, 8, 32];
+
+/// Window Sizes for Windowed Scalar Multiplication
+const WINDOW_SIZES: [usize; 4] = [1, 2, 4, 8];
+
+/// Seed of the Benchmark Randomness
+const SEED: u64 = 0x6f70_656e_7a6c;
+
+/// Returns a random number generator seeded with [`SEED`], so that every run benchmarks the same
+/// inputs.
+#[inline]
+fn rng() -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(SEED)
+}
+
+/// Poseidon Merkle Tree Configuration with Fixed `HEIGHT`
+struct MerkleConfig<const HEIGHT: usize>;
+
+impl<const HEIGHT: usize> InnerHash for MerkleConfig<HEIGHT> {
+    type LeafDigest = Fp<Fr>;
+    type Parameters = Poseidon2;
+    type Output = Fp<Fr>;
+
+    #[inline]
+    fn join(
+        parameters: &Self::Parameters,
+        lhs: &Self::Output,
+        rhs: &Self::Output,
+        compiler: &mut (),
+    ) -> Self::Output {
+        parameters.hash([lhs, rhs], compiler)
+    }
+
+    #[inline]
+    fn join_leaves(
+        parameters: &Self::Parameters,
+        lhs: &Self::LeafDigest,
+        rhs: &Self::LeafDigest,
+        compiler: &mut (),
+    ) -> Self::Output {
+        parameters.hash([lhs, rhs], compiler)
+    }
+}
+
+impl<const HEIGHT: usize> HashConfiguration for MerkleConfig<HEIGHT> {
+    type LeafHash = IdentityLeafHash<Fp<Fr>>;
+    type InnerHash = Self;
+}
+
+impl<const HEIGHT: usize> Configuration for MerkleConfig<HEIGHT> {
+    const HEIGHT: usize = HEIGHT;
+}
+
+/// Native BN254 G1 Point
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Point(G1Projective);
+
+impl Group for Point {
+    #[inline]
+    fn add(&self, rhs: &Self, _: &mut ()) -> Self {
+        Self(self.0 + rhs.0)
+    }
+
+    #[inline]
+    fn double_assign(&mut self, _: &mut ()) -> &mut Self {
+        self.0.double_in_place();
+        self
+    }
+}
+
+impl Zero for Point {
+    type Verification = bool;
+
+    #[inline]
+    fn zero(_: &mut ()) -> Self {
+        Self(ff::Zero::zero())
+    }
+
+    #[inline]
+    fn is_zero(&self, _: &mut ()) -> Self::Verification {
+        ff::Zero::is_zero(&self.0)
+    }
+}
+
+impl ConditionalSelect for Point {
+    #[inline]
+    fn select(bit: &bool, true_value: &Self, false_value: &Self, _: &mut ()) -> Self {
+        if *bit {
+            *true_value
+        } else {
+            *false_value
+        }
+    }
+}
+
+/// Adds the Poseidon permutation benchmark for `Spec<Fr, ARITY>` to `criterion`.
+#[inline]
+fn poseidon_permutation_width<const ARITY: usize>(c: &mut Criterion)
+where
+    Spec<Fr, ARITY>: poseidon::Specification<Field = Fp<Fr>, ParameterField = Fp<Fr>>,
+{
+    let mut rng = rng();
+    let permutation = Permutation::<Spec<Fr, ARITY>>::gen(&mut rng);
+    let state = State::<Spec<Fr, ARITY>>::gen(&mut rng);
+    c.benchmark_group("poseidon/permutation").bench_with_input(
+        BenchmarkId::from_parameter(ARITY + 1),
+        &state,
+        |b, state| {
+            b.iter_batched(
+                || state.clone(),
+                |mut state| {
+                    permutation.permute(black_box(&mut state), &mut ());
+                    state
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+}
+
+/// Benchmarks the Poseidon permutation for every supported width.
+fn poseidon_permutation(c: &mut Criterion) {
+    poseidon_permutation_width::<2>(c);
+    poseidon_permutation_width::<3>(c);
+    poseidon_permutation_width::<4>(c);
+    poseidon_permutation_width::<5>(c);
+}
+
+/// Benchmarks hashing batches of inputs one by one against hashing them with the batched
+/// structure-of-arrays permutation.
+fn poseidon_batch(c: &mut Criterion) {
+    let mut rng = rng();
+    let hasher = Poseidon2::gen(&mut rng);
+    let mut group = c.benchmark_group("poseidon/batch");
+    for size in POSEIDON_BATCH_SIZES {
+        let inputs = (0..size)
+            .map(|_| <[Fp<Fr>; 2]>::gen(&mut rng))
+            .collect::<Vec<_>>();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("sequential", size),
+            &inputs,
+            |b, inputs| {
+                b.iter(|| {
+                    black_box(inputs)
+                        .iter()
+                        .map(|input| hasher.hash(input.each_ref(), &mut ()))
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("batched", size), &inputs, |b, inputs| {
+            b.iter(|| hasher.hash_batch(black_box(inputs), &mut ()))
+        });
+    }
+    group.finish();
+}
+
+/// Adds the merkle tree insertion and proving benchmarks for `MerkleConfig<HEIGHT>` to
+/// `criterion`.
+#[inline]
+fn merkle_tree_height<const HEIGHT: usize>(c: &mut Criterion) {
+    let mut rng = rng();
+    let parameters = Parameters::<MerkleConfig<HEIGHT>>::new((), Poseidon2::gen(&mut rng));
+    let mut tree = FullMerkleTree::<MerkleConfig<HEIGHT>>::new(parameters);
+    c.benchmark_group("merkle_tree/insert").bench_function(
+        BenchmarkId::from_parameter(HEIGHT),
+        |b| {
+            b.iter_batched(
+                || (tree.clone(), Fp::<Fr>::gen(&mut rng)),
+                |(mut tree, leaf)| {
+                    tree.push_provable(black_box(&leaf));
+                    tree
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+    for _ in 0..MERKLE_TREE_LEAF_COUNT {
+        assert!(
+            tree.push_provable(&Fp::gen(&mut rng)),
+            "The merkle tree should have enough capacity for the benchmark leaves."
+        );
+    }
+    c.benchmark_group("merkle_tree/prove").bench_function(
+        BenchmarkId::from_parameter(HEIGHT),
+        |b| b.iter(|| tree.path(black_box(MERKLE_TREE_LEAF_COUNT / 2))),
+    );
+}
+
+/// Benchmarks merkle tree insertion and proving for a range of tree heights.
+fn merkle_tree(c: &mut Criterion) {
+    merkle_tree_height::<8>(c);
+    merkle_tree_height::<16>(c);
+    merkle_tree_height::<20>(c);
+}
+
+/// Builds the Groth16 benchmark circuit which hashes the secret `seed` `count`-many times and
+/// asserts that the result is equal to the public `output`.
+#[inline]
+fn hash_chain_circuit(
+    hasher: &Poseidon2,
+    seed: Option<&Fp<Fr>>,
+    output: Option<&Fp<Fr>>,
+    count: usize,
+    compiler: &mut R1CS<Fr>,
+) {
+    let hasher = hasher.as_constant::<Poseidon2<R1CS<Fr>>>(compiler);
+    let mut state: FpVar<Fr> = match seed {
+        Some(seed) => seed.as_known::<Secret, _>(compiler),
+        _ => compiler.allocate_unknown::<Secret, _>(),
+    };
+    for _ in 0..count {
+        state = hasher.hash([&state, &state], compiler);
+    }
+    let output: FpVar<Fr> = match output {
+        Some(output) => output.as_known::<Public, _>(compiler),
+        _ => compiler.allocate_unknown::<Public, _>(),
+    };
+    compiler.assert_eq(&state, &output);
+}
+
+/// Benchmarks Groth16 proving and verification for circuits of increasing size.
+fn groth16(c: &mut Criterion) {
+    let mut rng = rng();
+    let hasher = Poseidon2::gen(&mut rng);
+    for count in GROTH16_HASH_COUNTS {
+        let seed = Fp::<Fr>::gen(&mut rng);
+        let mut output = seed;
+        for _ in 0..count {
+            output = hasher.hash([&output, &output], &mut ());
+        }
+        let mut compiler = Groth16::<Bn254>::context_compiler();
+        hash_chain_circuit(&hasher, None, None, count, &mut compiler);
+        let (proving_context, verifying_context) =
+            Groth16::<Bn254>::compile(&(), compiler, &mut rng).expect("Unable to compile circuit.");
+        let mut prove = c.benchmark_group("groth16/prove");
+        prove.sample_size(10);
+        prove.bench_function(BenchmarkId::from_parameter(count), |b| {
+            b.iter_batched(
+                || {
+                    let mut compiler = Groth16::<Bn254>::proof_compiler();
+                    hash_chain_circuit(&hasher, Some(&seed), Some(&output), count, &mut compiler);
+                    compiler
+                },
+                |compiler| Groth16::<Bn254>::prove(&proving_context, compiler, &mut rng),
+                BatchSize::LargeInput,
+            )
+        });
+        prove.finish();
+        let mut compiler = Groth16::<Bn254>::proof_compiler();
+        hash_chain_circuit(&hasher, Some(&seed), Some(&output), count, &mut compiler);
+        let proof = Groth16::<Bn254>::prove(&proving_context, compiler, &mut rng)
+            .expect("Unable to generate proof.");
+        let input = vec![output.0];
+        assert!(
+            Groth16::<Bn254>::verify(&verifying_context, &input, &proof)
+                .expect("Unable to verify proof."),
+            "The benchmark proof should be valid."
+        );
+        c.benchmark_group("groth16/verify").bench_function(
+            BenchmarkId::from_parameter(count),
+            |b| b.iter(|| Groth16::<Bn254>::verify(&verifying_context, black_box(&input), &proof)),
+        );
+    }
+}
+
+/// Benchmarks windowed scalar multiplication over BN254 G1 for a range of window sizes.
+fn windowed_scalar_mul(c: &mut Criterion) {
+    let mut rng = rng();
+    let point = Point(G1Projective::prime_subgroup_generator());
+    let scalar = Fr::rand(&mut rng).into_repr().to_bits_be();
+    let mut group = c.benchmark_group("algebra/windowed_scalar_mul");
+    for window_size in WINDOW_SIZES {
+        let window = Window::new(window_size, point, &mut ());
+        group.bench_with_input(
+            BenchmarkId::from_parameter(window_size),
+            &scalar,
+            |b, scalar| b.iter(|| window.scalar_mul(black_box(scalar).iter(), &mut ())),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    poseidon_permutation,
+    poseidon_batch,
+    merkle_tree,
+    groth16,
+    windowed_scalar_mul
+);
+criterion_main!(benches);
@@ -0,0 +1,1514 @@
+//! Baby Jubjub Curve
+//!
+//! Baby Jubjub is the twisted Edwards curve whose base field is the scalar field of BN254, so its
+//! group operations are native arithmetic in circuits over BN254. Its scalar field is smaller than
+//! its base field, which makes [`ScalarVar`](super::ScalarVar) safe to use with this curve.
+
+use crate::ed_on_bn254;
+
+#[cfg(feature = "constraint")]
+use crate::constraint::fp::Fp;
+
+/// Projective Curve Type
+pub type Projective = ed_on_bn254::EdwardsProjective;
+
+/// Affine Curve Type
+pub type Affine = ed_on_bn254::EdwardsAffine;
+
+/// Group Element Type
+pub type Group = super::Group<Projective>;
+
+/// Scalar Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type Scalar = Fp<ed_on_bn254::Fr>;
+
+/// Projective Curve Variable Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type ProjectiveVar = ed_on_bn254::constraints::EdwardsVar;
+
+/// Group Element Variable Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type GroupVar = super::GroupVar<Projective, ProjectiveVar>;
+
+/// Scalar Variable Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type ScalarVar = super::ScalarVar<Projective, ProjectiveVar>;
+
+/// Testing Suite
+#[cfg(all(test, feature = "constraint"))]
+mod test {
+    use super::*;
+    use crate::{
+        constraint::R1CS,
+        ff::{BigInteger, PrimeField, UniformRand},
+        r1cs_std::ToBitsGadget,
+    };
+    use alloc::vec::Vec;
+    use eclair::alloc::{mode::Secret, Allocate};
+    use openzl_crypto::{
+        algebra::{
+            test::{multi_window_correctness, window_correctness, wnaf_correctness},
+            Error as AlgebraError, Group as _, MultiWindow, Window, Wnaf,
+        },
+        hash::security::PreimageResistance,
+        signature::{
+            schnorr::{self, Schnorr},
+            verify_batch, Derive, Sign,
+        },
+        transcript::Transcript,
+    };
+    use openzl_util::rand::{RngCore, Sample, TestRng, TestTranscript};
+
+    #[cfg(feature = "alloc")]
+    use openzl_crypto::{
+        algebra::ScalarMul,
+        rangeproof::{self, Error as RangeProofError, Parameters as RangeParameters},
+        secret_sharing::{self, Error as SharingError, Shamir},
+        sigma::{
+            self, And, Choice, DiscreteLog, EqualDiscreteLog, Or, PedersenOpening,
+            Proof as SigmaProof, Protocol,
+        },
+    };
+
+    /// Returns `label` as a string for the [`TestTranscript`].
+    #[inline]
+    fn label_str(label: &'static [u8]) -> &'static str {
+        core::str::from_utf8(label).expect("Transcript labels must be valid UTF-8.")
+    }
+
+    impl Transcript<Group, Scalar> for TestTranscript {
+        #[inline]
+        fn append_point(&mut self, label: &'static [u8], point: &Group) {
+            self.observe(
+                label_str(label),
+                crate::algebra::affine_point_as_bytes::<Projective>(&point.0).as_slice(),
+            );
+        }
+
+        #[inline]
+        fn append_scalar(&mut self, label: &'static [u8], scalar: &Scalar) {
+            self.observe(
+                label_str(label),
+                scalar.0.into_repr().to_bytes_le().as_slice(),
+            );
+        }
+
+        #[inline]
+        fn challenge_scalar(&mut self, label: &'static [u8]) -> Scalar {
+            self.sample::<Scalar, ()>(label_str(label))
+        }
+    }
+
+    /// Returns a factory of transcripts which all start in the same state, seeded from `rng`.
+    #[inline]
+    fn transcripts(domain: &'static str, rng: &mut TestRng) -> impl Fn() -> TestTranscript {
+        let seed = rng.next_u64();
+        move || TestTranscript::from_seed_value(domain, seed)
+    }
+
+    /// Adds one to `scalar`.
+    #[inline]
+    fn increment(scalar: &Scalar) -> Scalar {
+        Fp(scalar.0 + ed_on_bn254::Fr::from(1u8))
+    }
+
+    /// Checks that windowed and signed-digit windowed scalar multiplication agree with plain
+    /// scalar multiplication in-circuit for a range of window sizes.
+    #[test]
+    fn windowed_scalar_mul_matches_plain_scalar_mul() {
+        let mut rng = TestRng::new();
+        for window_size in 1..5 {
+            let mut compiler = R1CS::<ed_on_bn254::Fq>::for_proofs();
+            let point = Group::sample((), &mut rng).as_known::<Secret, GroupVar>(&mut compiler);
+            let scalar =
+                Fp(ed_on_bn254::Fr::rand(&mut rng)).as_known::<Secret, ScalarVar>(&mut compiler);
+            let bit_conversion = |scalar: &ScalarVar, _: &mut R1CS<_>| {
+                let mut bits = ToBitsGadget::to_bits_le(&scalar.0)
+                    .expect("Bit decomposition is not allowed to fail.");
+                bits.reverse();
+                bits
+            };
+            window_correctness(
+                window_size,
+                &scalar,
+                point.clone(),
+                bit_conversion,
+                &mut compiler,
+            );
+            wnaf_correctness(window_size, &scalar, point, bit_conversion, &mut compiler);
+            assert!(
+                compiler.is_satisfied(),
+                "Windowed scalar multiplication must match plain scalar multiplication."
+            );
+        }
+    }
+
+    /// Checks that window tables are only built for window sizes between `1` and the number of bits
+    /// of a `usize`, and from precomputed tables whose length is a power of two greater than `1`.
+    #[test]
+    fn window_constructors_reject_invalid_sizes() {
+        let mut rng = TestRng::new();
+        let mut compiler = R1CS::<ed_on_bn254::Fq>::for_proofs();
+        let point = Group::sample((), &mut rng).as_known::<Secret, GroupVar>(&mut compiler);
+        for window_size in [0, usize::BITS as usize] {
+            let expected = Some(AlgebraError::InvalidWindowSize(window_size));
+            assert_eq!(
+                Window::try_new(window_size, point.clone(), &mut compiler).err(),
+                expected,
+                "Window tables of invalid sizes must be rejected."
+            );
+            assert_eq!(
+                Wnaf::try_new(window_size, point.clone(), &mut compiler).err(),
+                expected,
+                "wNAF tables of invalid sizes must be rejected."
+            );
+            assert_eq!(
+                MultiWindow::try_new(window_size, [point.clone()], &mut compiler).err(),
+                expected,
+                "Multi-window tables of invalid sizes must be rejected."
+            );
+        }
+        for length in [0, 1, 3, 6] {
+            let table = (0..length)
+                .map(|_| Group::sample((), &mut rng))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                Window::try_from_table(table).err(),
+                Some(AlgebraError::InvalidTableLength(length)),
+                "Tables whose length is not a power of two greater than one must be rejected."
+            );
+        }
+        let table = (0..4)
+            .map(|_| Group::sample((), &mut rng))
+            .collect::<Vec<_>>();
+        assert!(
+            Window::try_from_table(table).is_ok(),
+            "Tables of length four should be accepted."
+        );
+    }
+
+    /// Checks that interleaved multi-base scalar multiplication agrees with the sum of plain
+    /// scalar multiplications, natively and in-circuit.
+    #[test]
+    fn multi_scalar_mul_matches_plain_scalar_mul() {
+        let mut rng = TestRng::new();
+        let points = [(); 3].map(|_| Group::sample((), &mut rng));
+        let scalars = [(); 3].map(|_| Fp(ed_on_bn254::Fr::rand(&mut rng)));
+        multi_window_correctness(
+            2,
+            &scalars,
+            &points,
+            |scalar: &Scalar, _: &mut ()| {
+                let mut bits = scalar.0.into_repr().to_bits_le();
+                bits.reverse();
+                bits
+            },
+            &mut (),
+        );
+        for window_size in 1..4 {
+            let mut compiler = R1CS::<ed_on_bn254::Fq>::for_proofs();
+            let point_vars = points
+                .iter()
+                .map(|point| point.as_known::<Secret, GroupVar>(&mut compiler))
+                .collect::<Vec<_>>();
+            let scalar_vars = scalars
+                .iter()
+                .map(|scalar| scalar.as_known::<Secret, ScalarVar>(&mut compiler))
+                .collect::<Vec<_>>();
+            multi_window_correctness(
+                window_size,
+                &scalar_vars,
+                &point_vars,
+                |scalar: &ScalarVar, _: &mut R1CS<_>| {
+                    let mut bits = ToBitsGadget::to_bits_le(&scalar.0)
+                        .expect("Bit decomposition is not allowed to fail.");
+                    bits.reverse();
+                    bits
+                },
+                &mut compiler,
+            );
+            assert!(
+                compiler.is_satisfied(),
+                "Multi-base scalar multiplication must match plain scalar multiplication."
+            );
+        }
+    }
+
+    /// Checks that checked deserialization rejects truncated encodings, points off the curve, and
+    /// points with a small-order component, while unchecked deserialization accepts the latter two.
+    #[test]
+    fn checked_deserialization_rejects_malformed_points() {
+        use crate::{
+            ff::{One, Zero},
+            serialize::{CanonicalSerialize, ValidationError},
+        };
+        let encode = |point: &Affine| {
+            let mut bytes = Vec::new();
+            point
+                .serialize_unchecked(&mut bytes)
+                .expect("Serializing into a vector is not allowed to fail.");
+            bytes
+        };
+        let mut rng = TestRng::new();
+        let point = Group::sample((), &mut rng);
+        assert_eq!(
+            Group::deserialize_checked(encode(&point.0).as_slice())
+                .expect("Checked deserialization should accept valid points."),
+            point,
+            "Valid points should round-trip through checked deserialization."
+        );
+        let mut truncated = encode(&point.0);
+        truncated.pop();
+        assert!(
+            Group::deserialize_checked(truncated.as_slice()).is_err(),
+            "Checked deserialization should reject truncated encodings."
+        );
+        assert!(
+            Group::deserialize_unchecked(truncated.as_slice()).is_err(),
+            "Unchecked deserialization should reject truncated encodings."
+        );
+        let off_curve = Group(Affine::new(point.0.x, point.0.y + ed_on_bn254::Fq::one()));
+        assert_eq!(
+            off_curve.validate(),
+            Err(ValidationError::NotOnCurve),
+            "Validation should reject points which are not on the curve."
+        );
+        let small_order = Group(Affine::new(
+            ed_on_bn254::Fq::zero(),
+            -ed_on_bn254::Fq::one(),
+        ));
+        assert!(
+            small_order.has_small_order(),
+            "The point `(0, -1)` should have order two."
+        );
+        let mut torsion = point.0;
+        torsion += &small_order.0;
+        let torsion = Group(torsion);
+        assert!(
+            !torsion.has_small_order(),
+            "Adding a small-order point should keep the prime-order component."
+        );
+        for malformed in [off_curve, small_order, torsion] {
+            assert!(
+                !malformed.is_valid(),
+                "Validation should reject malformed points."
+            );
+            assert!(
+                Group::deserialize_checked(encode(&malformed.0).as_slice()).is_err(),
+                "Checked deserialization should reject malformed points."
+            );
+            assert_eq!(
+                Group::deserialize_unchecked(encode(&malformed.0).as_slice())
+                    .expect("Unchecked deserialization should accept malformed points."),
+                malformed,
+                "Unchecked deserialization should return the encoded point."
+            );
+        }
+        assert_eq!(
+            torsion.validate(),
+            Err(ValidationError::NotInSubgroup),
+            "Validation should reject points outside of the prime-order subgroup."
+        );
+    }
+
+    /// Schnorr Challenge for Tests
+    ///
+    /// # Warning
+    ///
+    /// This is not a cryptographic hash function and is only meant for testing.
+    struct TestChallenge;
+
+    impl PreimageResistance for TestChallenge {}
+
+    impl schnorr::HashFunction for TestChallenge {
+        type Scalar = Scalar;
+        type Group = Group;
+        type Message = Scalar;
+
+        #[inline]
+        fn hash(
+            &self,
+            verifying_key: &Self::Group,
+            nonce_point: &Self::Group,
+            message: &Self::Message,
+            _: &mut (),
+        ) -> Self::Scalar {
+            let reduce = |point: &Group| {
+                ed_on_bn254::Fr::from_le_bytes_mod_order(&point.0.x.into_repr().to_bytes_le())
+            };
+            let nonce = reduce(nonce_point);
+            Fp(reduce(verifying_key) * nonce + nonce + message.0)
+        }
+    }
+
+    /// Returns the entries of a signature batch from `keys`, `messages`, and `signatures`.
+    fn batch<'a, K, M, S>(
+        keys: &'a [K],
+        messages: &'a [M],
+        signatures: &'a [S],
+    ) -> Vec<(&'a K, &'a M, &'a S)> {
+        keys.iter()
+            .zip(messages)
+            .zip(signatures)
+            .map(|((key, message), signature)| (key, message, signature))
+            .collect()
+    }
+
+    /// Checks that batch verification of Schnorr signatures accepts batches of valid signatures
+    /// and that its fallback finds the invalid signatures of a batch.
+    #[test]
+    fn schnorr_batch_verification_finds_invalid_signatures() {
+        let mut rng = TestRng::new();
+        let scheme = Schnorr::new(TestChallenge, Group::sample((), &mut rng));
+        let signing_keys = (0..8).map(|_| Scalar::gen(&mut rng)).collect::<Vec<_>>();
+        let keys = signing_keys
+            .iter()
+            .map(|signing_key| scheme.derive(signing_key, &mut ()))
+            .collect::<Vec<_>>();
+        let messages = (0..8).map(|_| Scalar::gen(&mut rng)).collect::<Vec<_>>();
+        let mut signatures = signing_keys
+            .iter()
+            .zip(&messages)
+            .map(|(signing_key, message)| {
+                scheme.sign(signing_key, &Scalar::gen(&mut rng), message, &mut ())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            verify_batch(&scheme, &batch(&keys, &messages, &signatures), &mut rng),
+            Ok(()),
+            "Batches of valid signatures should verify."
+        );
+        assert_eq!(verify_batch(&scheme, &[], &mut rng), Ok(()));
+        signatures[2].scalar = Fp(signatures[2].scalar.0 + ed_on_bn254::Fr::from(1u8));
+        signatures[5].nonce_point = signatures[6].nonce_point;
+        assert_eq!(
+            verify_batch(&scheme, &batch(&keys, &messages, &signatures), &mut rng),
+            Err(vec![2, 5]),
+            "The fallback should find exactly the invalid signatures."
+        );
+        for (index, expected) in [(0, Ok(())), (2, Err(vec![0]))] {
+            let range = index..index + 1;
+            let entries = batch(
+                &keys[range.clone()],
+                &messages[range.clone()],
+                &signatures[range],
+            );
+            assert_eq!(
+                verify_batch(&scheme, &entries, &mut rng),
+                expected,
+                "Batches of one signature should verify exactly when the signature is valid."
+            );
+        }
+    }
+
+    /// Samples range proof parameters for `bits`-bit ranges with independent generators.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn range_parameters(bits: usize, rng: &mut TestRng) -> RangeParameters<Group> {
+        let mut sample = || Group::sample((), &mut *rng);
+        RangeParameters::new(
+            sample(),
+            sample(),
+            sample(),
+            (0..bits).map(|_| sample()).collect(),
+            (0..bits).map(|_| sample()).collect(),
+        )
+        .expect("The number of bits is a power of two smaller than the maximum.")
+    }
+
+    /// Checks that range proofs verify one by one and in batches, that values of `2^n` or more
+    /// cannot be proven, and that tampered proofs and batches with one bad proof are rejected.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn range_proofs_verify_and_reject_forgeries() {
+        let mut rng = TestRng::new();
+        let parameters = range_parameters(8, &mut rng);
+        let transcript = transcripts("openzl/rangeproof/test", &mut rng);
+        rangeproof::test::assert_valid_range_proofs::<Scalar, _, _, _>(
+            &parameters,
+            &[0, 1, 42, 254, 255],
+            &transcript,
+            &mut rng,
+        );
+        for value in [256, 257, 1 << 40, u64::MAX] {
+            assert_eq!(
+                parameters
+                    .prove(value, &Scalar::gen(&mut rng), transcript(), &mut rng)
+                    .err(),
+                Some(RangeProofError::ValueOutOfRange),
+                "Values of 2^n or more must be rejected."
+            );
+        }
+        let blinding = Scalar::gen(&mut rng);
+        let commitment = parameters.commit(200, &blinding);
+        let proof = parameters
+            .prove(200, &blinding, transcript(), &mut rng)
+            .expect("The value is in range.");
+        assert!(
+            !parameters.verify(
+                &parameters.commit(200 + 256, &blinding),
+                &proof,
+                transcript()
+            ),
+            "Proofs must not verify for the value shifted by 2^n."
+        );
+        let offset = Group::sample((), &mut rng);
+        let mut tampered = [proof.clone(), proof.clone(), proof.clone()];
+        tampered[0].t_hat = increment(&tampered[0].t_hat);
+        tampered[1].inner_product.left[0] = tampered[1].inner_product.left[0].add(&offset, &mut ());
+        tampered[2].inner_product.right[2] =
+            tampered[2].inner_product.right[2].add(&offset, &mut ());
+        for tampered in &tampered {
+            assert!(
+                !parameters.verify(&commitment, tampered, transcript()),
+                "Tampered range proofs must be rejected."
+            );
+        }
+        let mut proofs = [10, 20, 30, 40].map(|value| {
+            let blinding = Scalar::gen(&mut rng);
+            let proof = parameters
+                .prove(value, &blinding, transcript(), &mut rng)
+                .expect("The value is in range.");
+            (parameters.commit(value, &blinding), proof)
+        });
+        assert!(
+            parameters.verify_batch(
+                proofs
+                    .iter()
+                    .map(|(commitment, proof)| (commitment, proof, transcript())),
+                &mut rng
+            ),
+            "Batches of valid range proofs must verify."
+        );
+        proofs[2].1.t_hat = increment(&proofs[2].1.t_hat);
+        assert!(
+            !parameters.verify_batch(
+                proofs
+                    .iter()
+                    .map(|(commitment, proof)| (commitment, proof, transcript())),
+                &mut rng
+            ),
+            "Batches with one bad range proof must be rejected."
+        );
+    }
+
+    /// Returns `scalar * point`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn mul(point: &Group, scalar: &Scalar) -> Group {
+        point.scalar_mul(scalar, &mut ())
+    }
+
+    /// Checks that the sigma protocols and their compositions prove and verify on Baby Jubjub,
+    /// and that proofs for a wrong witness, fully simulated disjunctions, and tampered responses
+    /// are rejected.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sigma_protocols_verify_and_reject_forgeries() {
+        let mut rng = TestRng::new();
+        let transcript = transcripts("openzl/sigma/test", &mut rng);
+        let [g, h, unknown] = [(); 3].map(|_| Group::sample((), &mut rng));
+        let [x, y, blinding] = [(); 3].map(|_| Scalar::gen(&mut rng));
+        let discrete_log = DiscreteLog::new(g);
+        sigma::test::assert_valid_proof::<_, Group, Scalar, _, _>(
+            &discrete_log,
+            &mul(&g, &x),
+            &x,
+            &mul(&g, &y),
+            &transcript,
+            &mut rng,
+        );
+        let equal_discrete_log = EqualDiscreteLog::new(g, h);
+        sigma::test::assert_valid_proof::<_, Group, Scalar, _, _>(
+            &equal_discrete_log,
+            &(mul(&g, &x), mul(&h, &x)),
+            &x,
+            &(mul(&g, &x), mul(&h, &y)),
+            &transcript,
+            &mut rng,
+        );
+        let opening = PedersenOpening::new(g, h);
+        sigma::test::assert_valid_proof::<_, Group, Scalar, _, _>(
+            &opening,
+            &opening.commitment(&x, &blinding),
+            &(x, blinding),
+            &opening.commitment(&y, &blinding),
+            &transcript,
+            &mut rng,
+        );
+        let and = And(discrete_log, opening);
+        sigma::test::assert_valid_proof::<_, Group, Scalar, _, _>(
+            &and,
+            &(mul(&g, &y), opening.commitment(&x, &blinding)),
+            &(y, (x, blinding)),
+            &(mul(&g, &x), opening.commitment(&x, &blinding)),
+            &transcript,
+            &mut rng,
+        );
+        let or = Or(discrete_log, DiscreteLog::new(h));
+        let statement = (mul(&g, &x), unknown);
+        sigma::test::assert_valid_proof::<_, Group, Scalar, _, _>(
+            &or,
+            &statement,
+            &Choice::Left(x),
+            &(mul(&g, &y), unknown),
+            &transcript,
+            &mut rng,
+        );
+        sigma::test::assert_valid_proof::<_, Group, Scalar, _, _>(
+            &or,
+            &(unknown, mul(&h, &y)),
+            &Choice::Right(y),
+            &(unknown, mul(&h, &x)),
+            &transcript,
+            &mut rng,
+        );
+        assert!(
+            !Protocol::<Group, Scalar>::verify(
+                &discrete_log,
+                &mul(&g, &x),
+                &Protocol::<Group, Scalar>::prove(
+                    &discrete_log,
+                    &mul(&g, &x),
+                    &y,
+                    transcript(),
+                    &mut rng
+                ),
+                transcript()
+            ),
+            "Proofs with a wrong witness must be rejected."
+        );
+        assert!(
+            !Protocol::<Group, Scalar>::verify(
+                &or,
+                &statement,
+                &Protocol::<Group, Scalar>::prove(
+                    &or,
+                    &statement,
+                    &Choice::Left(y),
+                    transcript(),
+                    &mut rng
+                ),
+                transcript()
+            ),
+            "Disjunctions with a wrong witness for the known branch must be rejected."
+        );
+        let (commitment, response) =
+            Protocol::<Group, Scalar>::simulate(&or, &statement, &Scalar::gen(&mut rng), &mut rng);
+        assert!(
+            !Protocol::<Group, Scalar>::verify(
+                &or,
+                &statement,
+                &SigmaProof {
+                    commitment,
+                    response
+                },
+                transcript()
+            ),
+            "Disjunctions with both branches simulated must be rejected."
+        );
+        let mut proof = Protocol::<Group, Scalar>::prove(
+            &discrete_log,
+            &mul(&g, &x),
+            &x,
+            transcript(),
+            &mut rng,
+        );
+        proof.response = increment(&proof.response);
+        assert!(
+            !Protocol::<Group, Scalar>::verify(&discrete_log, &mul(&g, &x), &proof, transcript()),
+            "Proofs with a tampered response must be rejected."
+        );
+        let proof = Protocol::<Group, Scalar>::prove(
+            &or,
+            &statement,
+            &Choice::Left(x),
+            transcript(),
+            &mut rng,
+        );
+        let mut tampered = [proof, proof];
+        tampered[0].response.challenge = increment(&tampered[0].response.challenge);
+        tampered[1].response.right = increment(&tampered[1].response.right);
+        for tampered in &tampered {
+            assert!(
+                !Protocol::<Group, Scalar>::verify(&or, &statement, tampered, transcript()),
+                "Disjunctions with a tampered response must be rejected."
+            );
+        }
+    }
+
+    /// Checks that Shamir sharings reconstruct the secret from any threshold of shares, that
+    /// fewer shares are rejected, and that Feldman commitments reject corrupted shares.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn secret_sharing_reconstructs_and_rejects_corrupted_shares() {
+        let mut rng = TestRng::new();
+        let generator = Group::sample((), &mut rng);
+        for (threshold, shares) in [(1, 1), (1, 3), (2, 3), (3, 5), (5, 5)] {
+            let scheme = Shamir::new(threshold, shares).expect("The threshold is valid.");
+            secret_sharing::test::assert_valid_sharing(
+                &scheme,
+                Scalar::gen(&mut rng),
+                &generator,
+                &mut rng,
+            );
+        }
+        for (threshold, shares) in [(0, 3), (4, 3)] {
+            assert_eq!(
+                Shamir::new(threshold, shares),
+                Err(SharingError::InvalidThreshold { threshold, shares }),
+                "Thresholds must be positive and at most the number of shares."
+            );
+        }
+        let scheme = Shamir::new(3, 5).expect("The threshold is valid.");
+        let secret = Scalar::gen(&mut rng);
+        let (mut shares, commitments) = scheme.split_verifiable(secret, &generator, &mut rng);
+        for window in [
+            &shares[..3],
+            &shares[2..],
+            &[shares[4], shares[0], shares[2]][..],
+        ] {
+            assert_eq!(
+                scheme.reconstruct(window),
+                Ok(secret),
+                "Any threshold of shares must reconstruct the secret."
+            );
+        }
+        assert_eq!(
+            scheme.reconstruct(&shares[..2]),
+            Err(SharingError::NotEnoughShares {
+                threshold: 3,
+                shares: 2
+            }),
+            "Fewer shares than the threshold must be rejected."
+        );
+        shares[1].value = increment(&shares[1].value);
+        shares[3].identifier = increment(&shares[3].identifier);
+        for (index, share) in shares.iter().enumerate() {
+            assert_eq!(
+                commitments.verify(&generator, share),
+                index != 1 && index != 3,
+                "Feldman commitments must reject exactly the corrupted shares."
+            );
+        }
+        assert_ne!(
+            scheme.reconstruct(&shares[..3]),
+            Ok(secret),
+            "Sets of shares with a corrupted share must not reconstruct the secret."
+        );
+    }
+
+    /// BIP340-Style Signature Tests
+    ///
+    /// Baby Jubjub points are encoded by their y-coordinate, which is kept by negation on twisted
+    /// Edwards curves, so the parity of their x-coordinate takes the place of the parity of the
+    /// y-coordinate in BIP340.
+    #[cfg(feature = "alloc")]
+    mod bip340 {
+        use super::*;
+        use crate::{
+            ec::TEModelParameters,
+            ff::{Field, One, SquareRootField},
+        };
+        use openzl_crypto::signature::{
+            convert::bip340::{Bip340, ScalarBytes, XOnly},
+            Verify,
+        };
+
+        /// Base Field Type
+        type Fq = ed_on_bn254::Fq;
+
+        /// Curve Parameters
+        type Parameters = ed_on_bn254::EdwardsParameters;
+
+        /// Returns the 32-byte big-endian encoding of `element`.
+        #[inline]
+        fn be_bytes<F>(element: &F) -> [u8; 32]
+        where
+            F: PrimeField,
+        {
+            let repr = element.into_repr().to_bytes_be();
+            let mut bytes = [0; 32];
+            bytes[32 - repr.len()..].copy_from_slice(&repr);
+            bytes
+        }
+
+        /// Returns the element with the big-endian encoding `bytes` if the encoding is canonical.
+        #[inline]
+        fn from_canonical_be_bytes<F>(bytes: &[u8; 32]) -> Option<F>
+        where
+            F: PrimeField,
+        {
+            let element = F::from_be_bytes_mod_order(bytes);
+            (be_bytes(&element) == *bytes).then_some(element)
+        }
+
+        impl XOnly for Group {
+            #[inline]
+            fn has_even_y(&self) -> bool {
+                self.0.x.into_repr().is_even()
+            }
+
+            #[inline]
+            fn to_x_bytes(&self) -> [u8; 32] {
+                be_bytes(&self.0.y)
+            }
+
+            #[inline]
+            fn lift_x(bytes: &[u8; 32]) -> Option<Self> {
+                let y = from_canonical_be_bytes::<Fq>(bytes)?;
+                let y2 = y.square();
+                let x =
+                    ((Fq::one() - y2) / (Parameters::COEFF_A - Parameters::COEFF_D * y2)).sqrt()?;
+                let x = if x.into_repr().is_even() { x } else { -x };
+                let point = Group(Affine::new(x, y));
+                point.is_valid().then_some(point)
+            }
+        }
+
+        impl ScalarBytes for Scalar {
+            #[inline]
+            fn from_be_bytes_mod_order(bytes: &[u8; 32]) -> Self {
+                Fp(ed_on_bn254::Fr::from_be_bytes_mod_order(bytes))
+            }
+
+            #[inline]
+            fn from_canonical_be_bytes(bytes: &[u8; 32]) -> Option<Self> {
+                from_canonical_be_bytes(bytes).map(Fp)
+            }
+
+            #[inline]
+            fn to_be_bytes(&self) -> [u8; 32] {
+                be_bytes(&self.0)
+            }
+        }
+
+        /// Checks that BIP340-style signatures verify, that a signing key and its negation derive
+        /// the same x-only verifying key and signatures, and that forged signatures are rejected.
+        #[test]
+        fn signatures_round_trip_and_normalize_parity() {
+            let mut rng = TestRng::new();
+            let scheme = Bip340::<Scalar, Group>::new(Group::sample((), &mut rng));
+            let mut parities = [false; 2];
+            for _ in 0..16 {
+                let signing_key = Scalar::gen(&mut rng);
+                let point = scheme.schnorr.derive(&signing_key, &mut ());
+                parities[usize::from(point.has_even_y())] = true;
+                let verifying_key = scheme.derive(&signing_key, &mut ());
+                assert_eq!(
+                    verifying_key,
+                    scheme.derive(&-signing_key, &mut ()),
+                    "A signing key and its negation must derive the same verifying key."
+                );
+                let lifted =
+                    Group::lift_x(&verifying_key).expect("Verifying keys must lift to points.");
+                assert!(
+                    lifted.has_even_y() && (lifted == point || lifted == -point),
+                    "Verifying keys must lift to the point of the signing key with even parity."
+                );
+                let mut message = vec![0; 32];
+                let mut randomness = [0; 32];
+                rng.fill_bytes(&mut message);
+                rng.fill_bytes(&mut randomness);
+                let signature = scheme.sign(&signing_key, &randomness, &message, &mut ());
+                assert!(
+                    scheme.verify(&verifying_key, &message, &signature, &mut ()),
+                    "Signatures must verify under the verifying key of their signing key."
+                );
+                assert_eq!(
+                    signature,
+                    scheme.sign(&-signing_key, &randomness, &message, &mut ()),
+                    "A signing key and its negation must produce the same signatures."
+                );
+                let mut forged = signature;
+                forged[63] ^= 1;
+                assert!(
+                    !scheme.verify(&verifying_key, &message, &forged, &mut ()),
+                    "Signatures with a tampered scalar must be rejected."
+                );
+                message[0] ^= 1;
+                assert!(
+                    !scheme.verify(&verifying_key, &message, &signature, &mut ()),
+                    "Signatures must be rejected for other messages."
+                );
+            }
+            assert_eq!(
+                parities,
+                [true, true],
+                "Signing keys with both parities must have been sampled."
+            );
+        }
+    }
+
+    #[cfg(all(feature = "alloc", feature = "bn254"))]
+    mod musig {
+        use super::*;
+        use crate::{
+            constraint::{Boolean, FpVar},
+            poseidon::{Spec, TwoPowerMinusOneDomainTag},
+            r1cs_std::R1CSVar,
+        };
+        use eclair::{alloc::Constant, bool::Assert};
+        use openzl_crypto::{
+            algebra::{security::DiscreteLogarithmHardness, Ring, ScalarMulGroup},
+            constraint::test::differential::signature_agreement,
+            hash::ArrayHashFunction,
+            poseidon::hash::Hasher,
+            signature::{
+                multisig::{self, MuSig2, NonceCommitment, PartialSignature, SecretNonce},
+                schnorr::Signature,
+                test::multisig_correctness,
+            },
+        };
+
+        /// Poseidon Hasher with Arity Five
+        type Poseidon5<COM = ()> = Hasher<Spec<bn254::Fr, 5>, TwoPowerMinusOneDomainTag, 5, COM>;
+
+        /// Constraint Compiler
+        type Compiler = R1CS<ed_on_bn254::Fq>;
+
+        /// Domain Tag of Point Digests
+        const POINT_TAG: u64 = 1;
+
+        /// Domain Tag of the Digest of the Signer Keys
+        const KEY_SET_TAG: u64 = 2;
+
+        /// Domain Tag of Key Aggregation Coefficients
+        const KEY_COEFFICIENT_TAG: u64 = 3;
+
+        /// Domain Tag of Nonce Coefficients
+        const NONCE_COEFFICIENT_TAG: u64 = 4;
+
+        /// Domain Tag of Schnorr Challenges
+        const CHALLENGE_TAG: u64 = 5;
+
+        /// Baby Jubjub Encoding for Poseidon over the Base Field
+        trait Encoding: Sized {
+            /// Base Field Element Type
+            type Field;
+
+            /// Scalar Type
+            type Scalar: Ring<Self>;
+
+            /// Group Type
+            type Group: ScalarMulGroup<Self::Scalar, Self, Output = Self::Group>
+                + DiscreteLogarithmHardness;
+
+            /// Returns the affine coordinates of `point`.
+            fn coordinates(point: &Self::Group) -> [Self::Field; 2];
+
+            /// Returns `value` as a base field element.
+            fn constant(value: u64, compiler: &mut Self) -> Self::Field;
+
+            /// Reduces the base field element `value` into a scalar.
+            fn reduce(value: &Self::Field, compiler: &mut Self) -> Self::Scalar;
+        }
+
+        impl Encoding for () {
+            type Field = Fp<ed_on_bn254::Fq>;
+            type Scalar = Scalar;
+            type Group = Group;
+
+            #[inline]
+            fn coordinates(point: &Self::Group) -> [Self::Field; 2] {
+                [Fp(point.0.x), Fp(point.0.y)]
+            }
+
+            #[inline]
+            fn constant(value: u64, _: &mut Self) -> Self::Field {
+                Fp(value.into())
+            }
+
+            #[inline]
+            fn reduce(value: &Self::Field, _: &mut Self) -> Self::Scalar {
+                Fp(ed_on_bn254::Fr::from_le_bytes_mod_order(
+                    &value.0.into_repr().to_bytes_le(),
+                ))
+            }
+        }
+
+        impl Encoding for Compiler {
+            type Field = FpVar<ed_on_bn254::Fq>;
+            type Scalar = ScalarVar;
+            type Group = GroupVar;
+
+            #[inline]
+            fn coordinates(point: &Self::Group) -> [Self::Field; 2] {
+                [point.0.x.clone(), point.0.y.clone()]
+            }
+
+            #[inline]
+            fn constant(value: u64, _: &mut Self) -> Self::Field {
+                FpVar::Constant(value.into())
+            }
+
+            #[inline]
+            fn reduce(value: &Self::Field, _: &mut Self) -> Self::Scalar {
+                ScalarVar::reduce(value)
+            }
+        }
+
+        /// Poseidon MuSig2 Hash Function
+        ///
+        /// Every input is hashed with a single Poseidon instance behind a distinct domain tag.
+        /// Points are absorbed through the digest of their affine coordinates.
+        struct PoseidonChallenge<COM = ()> {
+            /// Poseidon Hasher
+            hasher: Poseidon5<COM>,
+        }
+
+        impl<COM> PoseidonChallenge<COM>
+        where
+            COM: Encoding,
+            Poseidon5<COM>: ArrayHashFunction<5, COM, Input = COM::Field, Output = COM::Field>,
+        {
+            /// Hashes `inputs` behind the domain `tag`.
+            #[inline]
+            fn hash_tagged(
+                &self,
+                tag: u64,
+                inputs: [&COM::Field; 4],
+                compiler: &mut COM,
+            ) -> COM::Field {
+                let tag = COM::constant(tag, compiler);
+                let [a, b, c, d] = inputs;
+                self.hasher.hash([&tag, a, b, c, d], compiler)
+            }
+
+            /// Hashes the affine coordinates of `point`.
+            #[inline]
+            fn point_digest(&self, point: &COM::Group, compiler: &mut COM) -> COM::Field {
+                let [x, y] = COM::coordinates(point);
+                let zero = COM::constant(0, compiler);
+                self.hash_tagged(POINT_TAG, [&x, &y, &zero, &zero], compiler)
+            }
+        }
+
+        impl<COM> PreimageResistance for PoseidonChallenge<COM> {}
+
+        impl<COM> schnorr::HashFunction<COM> for PoseidonChallenge<COM>
+        where
+            COM: Encoding,
+            Poseidon5<COM>: ArrayHashFunction<5, COM, Input = COM::Field, Output = COM::Field>,
+        {
+            type Scalar = COM::Scalar;
+            type Group = COM::Group;
+            type Message = COM::Field;
+
+            #[inline]
+            fn hash(
+                &self,
+                verifying_key: &Self::Group,
+                nonce_point: &Self::Group,
+                message: &Self::Message,
+                compiler: &mut COM,
+            ) -> Self::Scalar {
+                let verifying_key = self.point_digest(verifying_key, compiler);
+                let nonce_point = self.point_digest(nonce_point, compiler);
+                let zero = COM::constant(0, compiler);
+                let challenge = self.hash_tagged(
+                    CHALLENGE_TAG,
+                    [&verifying_key, &nonce_point, message, &zero],
+                    compiler,
+                );
+                COM::reduce(&challenge, compiler)
+            }
+        }
+
+        impl<COM> multisig::HashFunction<COM> for PoseidonChallenge<COM>
+        where
+            COM: Encoding,
+            Poseidon5<COM>: ArrayHashFunction<5, COM, Input = COM::Field, Output = COM::Field>,
+        {
+            #[inline]
+            fn key_coefficient(
+                &self,
+                keys: &[Self::Group],
+                key: &Self::Group,
+                compiler: &mut COM,
+            ) -> Self::Scalar {
+                let zero = COM::constant(0, compiler);
+                let mut key_set = COM::constant(0, compiler);
+                for signer in keys {
+                    let signer = self.point_digest(signer, compiler);
+                    key_set =
+                        self.hash_tagged(KEY_SET_TAG, [&key_set, &signer, &zero, &zero], compiler);
+                }
+                let key = self.point_digest(key, compiler);
+                let coefficient = self.hash_tagged(
+                    KEY_COEFFICIENT_TAG,
+                    [&key_set, &key, &zero, &zero],
+                    compiler,
+                );
+                COM::reduce(&coefficient, compiler)
+            }
+
+            #[inline]
+            fn nonce_coefficient(
+                &self,
+                aggregate_key: &Self::Group,
+                nonce: &NonceCommitment<Self::Group>,
+                message: &Self::Message,
+                compiler: &mut COM,
+            ) -> Self::Scalar {
+                let aggregate_key = self.point_digest(aggregate_key, compiler);
+                let first = self.point_digest(&nonce.first, compiler);
+                let second = self.point_digest(&nonce.second, compiler);
+                let coefficient = self.hash_tagged(
+                    NONCE_COEFFICIENT_TAG,
+                    [&aggregate_key, &first, &second, message],
+                    compiler,
+                );
+                COM::reduce(&coefficient, compiler)
+            }
+        }
+
+        impl Constant<Compiler> for PoseidonChallenge<Compiler> {
+            type Type = PoseidonChallenge;
+
+            #[inline]
+            fn new_constant(this: &Self::Type, compiler: &mut Compiler) -> Self {
+                Self {
+                    hasher: this.hasher.as_constant(compiler),
+                }
+            }
+        }
+
+        /// Native MuSig2 Scheme
+        type Scheme = MuSig2<PoseidonChallenge>;
+
+        /// In-Circuit MuSig2 Scheme
+        type SchemeVar = MuSig2<PoseidonChallenge<Compiler>, Compiler>;
+
+        /// Samples a native MuSig2 scheme with a random Poseidon instance and generator.
+        #[inline]
+        fn sample_scheme(rng: &mut TestRng) -> Scheme {
+            MuSig2::new(Schnorr::new(
+                PoseidonChallenge {
+                    hasher: Poseidon5::gen(rng),
+                },
+                Group::sample((), rng),
+            ))
+        }
+
+        /// Allocates `scheme` as a constant in `compiler`.
+        #[inline]
+        fn scheme_var(scheme: &Scheme, compiler: &mut Compiler) -> SchemeVar {
+            MuSig2::new(Schnorr::new(
+                scheme
+                    .schnorr
+                    .hash_function
+                    .as_constant::<PoseidonChallenge<Compiler>>(compiler),
+                scheme.schnorr.generator.as_constant::<GroupVar>(compiler),
+            ))
+        }
+
+        /// Samples `count` signing keys and the scalars of one secret nonce for each of them.
+        #[inline]
+        fn sample_signers(count: usize, rng: &mut TestRng) -> (Vec<Scalar>, Vec<[Scalar; 2]>) {
+            (
+                (0..count).map(|_| Scalar::gen(rng)).collect(),
+                (0..count)
+                    .map(|_| [Scalar::gen(rng), Scalar::gen(rng)])
+                    .collect(),
+            )
+        }
+
+        /// Runs the signing rounds of `scheme` natively for `signing_keys` and `nonces` over
+        /// `message`, returning the signer keys, the nonce commitments, and the partial
+        /// signatures.
+        #[inline]
+        fn sign(
+            scheme: &Scheme,
+            signing_keys: &[Scalar],
+            nonces: &[[Scalar; 2]],
+            message: &Fp<ed_on_bn254::Fq>,
+        ) -> (
+            Vec<Group>,
+            Vec<NonceCommitment<Group>>,
+            Vec<PartialSignature<Scalar>>,
+        ) {
+            let keys = signing_keys
+                .iter()
+                .map(|signing_key| scheme.schnorr.derive(signing_key, &mut ()))
+                .collect::<Vec<_>>();
+            let nonces = nonces
+                .iter()
+                .map(|[first, second]| SecretNonce::new(*first, *second))
+                .collect::<Vec<_>>();
+            let commitments = nonces
+                .iter()
+                .map(|nonce| scheme.commit_nonce(nonce, &mut ()))
+                .collect::<Vec<_>>();
+            let aggregate_nonce = scheme
+                .aggregate_nonces(&commitments, &mut ())
+                .expect("The set of signers is not empty.");
+            let session = scheme
+                .session(&keys, &aggregate_nonce, message, &mut ())
+                .expect("The set of signers is not empty.");
+            let partial_signatures = signing_keys
+                .iter()
+                .zip(nonces)
+                .map(|(signing_key, nonce)| {
+                    scheme.partial_sign(&keys, &session, signing_key, nonce, &mut ())
+                })
+                .collect();
+            (keys, commitments, partial_signatures)
+        }
+
+        /// Checks that the native and in-circuit verifications of Schnorr signatures over Baby
+        /// Jubjub and Poseidon agree on valid signatures, wrong messages, and wrong scalars.
+        #[test]
+        fn schnorr_native_and_circuit_agree() {
+            let mut rng = TestRng::new();
+            let scheme = sample_scheme(&mut rng).schnorr;
+            for _ in 0..2 {
+                let signing_key = Scalar::gen(&mut rng);
+                let verifying_key = scheme.derive(&signing_key, &mut ());
+                let message = Fp::gen(&mut rng);
+                let signature =
+                    scheme.sign(&signing_key, &Scalar::gen(&mut rng), &message, &mut ());
+                let wrong_message = Fp(message.0 + ed_on_bn254::Fq::from(1u8));
+                let wrong_scalar = Signature {
+                    scalar: increment(&signature.scalar),
+                    nonce_point: signature.nonce_point,
+                };
+                for (message, signature, expected) in [
+                    (&message, &signature, true),
+                    (&wrong_message, &signature, false),
+                    (&message, &wrong_scalar, false),
+                ] {
+                    assert_eq!(
+                        signature_agreement::<_, Schnorr<PoseidonChallenge<Compiler>, Compiler>, _>(
+                            &scheme,
+                            &verifying_key,
+                            message,
+                            signature,
+                            &mut Compiler::for_proofs(),
+                        ),
+                        expected,
+                        "Signatures should only verify for their own message and scalar."
+                    );
+                }
+            }
+        }
+
+        /// Checks that MuSig2 over Baby Jubjub and Poseidon produces valid partial and aggregate
+        /// signatures natively.
+        #[test]
+        fn musig_signatures_verify_natively() {
+            let mut rng = TestRng::new();
+            let scheme = sample_scheme(&mut rng);
+            for count in 1..4 {
+                let (signing_keys, nonces) = sample_signers(count, &mut rng);
+                let nonces = nonces
+                    .into_iter()
+                    .map(|[first, second]| SecretNonce::new(first, second))
+                    .collect();
+                assert!(
+                    multisig_correctness(
+                        &scheme,
+                        &signing_keys,
+                        nonces,
+                        &Fp::gen(&mut rng),
+                        &mut ()
+                    ),
+                    "The aggregate signature must verify against the aggregate key."
+                );
+            }
+        }
+
+        /// Checks that MuSig2 over Baby Jubjub and Poseidon produces valid partial and aggregate
+        /// signatures in-circuit.
+        #[test]
+        fn musig_signatures_verify_in_circuit() {
+            let mut rng = TestRng::new();
+            let scheme = sample_scheme(&mut rng);
+            let (signing_keys, nonces) = sample_signers(2, &mut rng);
+            let message = Fp::gen(&mut rng);
+            let mut compiler = Compiler::for_proofs();
+            let scheme = scheme_var(&scheme, &mut compiler);
+            let signing_keys = signing_keys
+                .iter()
+                .map(|key| key.as_known::<Secret, ScalarVar>(&mut compiler))
+                .collect::<Vec<_>>();
+            let nonces = nonces
+                .iter()
+                .map(|[first, second]| {
+                    SecretNonce::new(
+                        first.as_known::<Secret, ScalarVar>(&mut compiler),
+                        second.as_known::<Secret, ScalarVar>(&mut compiler),
+                    )
+                })
+                .collect();
+            let message = message.as_known::<Secret, FpVar<_>>(&mut compiler);
+            let is_valid =
+                multisig_correctness(&scheme, &signing_keys, nonces, &message, &mut compiler);
+            compiler.assert(&is_valid);
+            assert!(
+                compiler.is_satisfied(),
+                "The aggregate signature must verify against the aggregate key in-circuit."
+            );
+        }
+
+        /// Checks that a wrong partial signature of one cosigner is rejected natively and
+        /// in-circuit without affecting the other cosigners, and that it invalidates the
+        /// aggregate signature.
+        #[test]
+        fn musig_rejects_wrong_partial_signature() {
+            let mut rng = TestRng::new();
+            let scheme = sample_scheme(&mut rng);
+            let (signing_keys, nonces) = sample_signers(3, &mut rng);
+            let message = Fp::gen(&mut rng);
+            let (keys, commitments, mut partial_signatures) =
+                sign(&scheme, &signing_keys, &nonces, &message);
+            partial_signatures[1].scalar = increment(&partial_signatures[1].scalar);
+            let aggregate_nonce = scheme
+                .aggregate_nonces(&commitments, &mut ())
+                .expect("The set of signers is not empty.");
+            let session = scheme
+                .session(&keys, &aggregate_nonce, &message, &mut ())
+                .expect("The set of signers is not empty.");
+            for (signer, ((key, commitment), partial_signature)) in keys
+                .iter()
+                .zip(&commitments)
+                .zip(&partial_signatures)
+                .enumerate()
+            {
+                assert_eq!(
+                    scheme.verify_partial(
+                        &keys,
+                        &session,
+                        key,
+                        commitment,
+                        partial_signature,
+                        &mut ()
+                    ),
+                    signer != 1,
+                    "Only the partial signature of the dishonest cosigner must be rejected."
+                );
+            }
+            let signature = scheme
+                .aggregate(&session, &partial_signatures, &mut ())
+                .expect("The set of signers is not empty.");
+            assert!(
+                !scheme.verify(&session.aggregate_key, &message, &signature, &mut ()),
+                "An aggregate with a wrong partial signature must not verify."
+            );
+            let mut compiler = Compiler::for_proofs();
+            let scheme = scheme_var(&scheme, &mut compiler);
+            let keys = keys
+                .iter()
+                .map(|key| key.as_known::<Secret, GroupVar>(&mut compiler))
+                .collect::<Vec<_>>();
+            let commitments = commitments
+                .iter()
+                .map(|commitment| NonceCommitment {
+                    first: commitment.first.as_known::<Secret, GroupVar>(&mut compiler),
+                    second: commitment
+                        .second
+                        .as_known::<Secret, GroupVar>(&mut compiler),
+                })
+                .collect::<Vec<_>>();
+            let partial_signatures = partial_signatures
+                .iter()
+                .map(|partial_signature| PartialSignature {
+                    scalar: partial_signature
+                        .scalar
+                        .as_known::<Secret, ScalarVar>(&mut compiler),
+                })
+                .collect::<Vec<_>>();
+            let message = message.as_known::<Secret, FpVar<_>>(&mut compiler);
+            let aggregate_nonce = scheme
+                .aggregate_nonces(&commitments, &mut compiler)
+                .expect("The set of signers is not empty.");
+            let session = scheme
+                .session(&keys, &aggregate_nonce, &message, &mut compiler)
+                .expect("The set of signers is not empty.");
+            let verifications = keys
+                .iter()
+                .zip(&commitments)
+                .zip(&partial_signatures)
+                .map(|((key, commitment), partial_signature)| {
+                    scheme.verify_partial(
+                        &keys,
+                        &session,
+                        key,
+                        commitment,
+                        partial_signature,
+                        &mut compiler,
+                    )
+                })
+                .collect::<Vec<Boolean<_>>>();
+            assert_eq!(
+                verifications
+                    .iter()
+                    .map(|bit| bit.value().expect("The verification bits are known."))
+                    .collect::<Vec<_>>(),
+                [true, false, true],
+                "Only the partial signature of the dishonest cosigner must be rejected in-circuit."
+            );
+            assert!(
+                compiler.is_satisfied(),
+                "Computing the verification bits must not violate any constraints."
+            );
+            compiler.assert(&verifications[1]);
+            assert!(
+                !compiler.is_satisfied(),
+                "Asserting the wrong partial signature must not be satisfied."
+            );
+        }
+    }
+
+    #[cfg(all(feature = "alloc", feature = "bn254"))]
+    mod blinding {
+        use super::*;
+        use crate::poseidon::{Spec, TwoPowerMinusOneDomainTag};
+        use openzl_crypto::{
+            key::blinding::{
+                test::correctness, BlindingHash, KeyBlinding, PoseidonBlindingHash,
+                PoseidonConversion,
+            },
+            poseidon::hash::Hasher,
+        };
+
+        /// Poseidon Hasher with Arity Two
+        type Poseidon2 = Hasher<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, 2>;
+
+        /// Baby Jubjub Conversion for Poseidon over the Base Field
+        ///
+        /// Shared secrets are hashed through their affine `x`-coordinate and digests are reduced
+        /// into scalars.
+        struct Conversion;
+
+        impl PoseidonConversion<Fp<ed_on_bn254::Fq>> for Conversion {
+            type SharedSecret = Group;
+            type BlindingFactor = Scalar;
+
+            #[inline]
+            fn to_field(shared_secret: &Self::SharedSecret, _: &mut ()) -> Fp<ed_on_bn254::Fq> {
+                Fp(shared_secret.0.x)
+            }
+
+            #[inline]
+            fn from_field(digest: Fp<ed_on_bn254::Fq>, _: &mut ()) -> Self::BlindingFactor {
+                Fp(ed_on_bn254::Fr::from_le_bytes_mod_order(
+                    &digest.0.into_repr().to_bytes_le(),
+                ))
+            }
+        }
+
+        /// Poseidon Key Blinding Scheme over Baby Jubjub
+        type Scheme = KeyBlinding<
+            Scalar,
+            Group,
+            PoseidonBlindingHash<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, Conversion>,
+        >;
+
+        /// Samples a key blinding scheme.
+        #[inline]
+        fn sample_scheme(rng: &mut TestRng) -> Scheme {
+            KeyBlinding::new(
+                Group::gen(rng),
+                PoseidonBlindingHash::new(Poseidon2::gen(rng)),
+            )
+        }
+
+        /// Returns the diversifier index `index`.
+        #[inline]
+        fn index(index: u64) -> Fp<ed_on_bn254::Fq> {
+            Fp(index.into())
+        }
+
+        /// Checks that the recipient recovers the one-time secret key of every blinded key built for
+        /// their public key.
+        #[test]
+        fn blinded_keys_verify() {
+            let mut rng = TestRng::new();
+            let scheme = sample_scheme(&mut rng);
+            for _ in 0..4 {
+                let secret_key = Scalar::gen(&mut rng);
+                let ephemeral_secret_key = Scalar::gen(&mut rng);
+                for i in 0..3 {
+                    correctness(
+                        &scheme,
+                        &secret_key,
+                        &ephemeral_secret_key,
+                        &index(i),
+                        &mut (),
+                    );
+                }
+            }
+        }
+
+        /// Checks that blinded keys with different blinding factors share no key with each other or
+        /// with the long-term public key, and that only their recipient can recognize them.
+        #[test]
+        fn blinded_keys_are_unlinkable() {
+            let mut rng = TestRng::new();
+            let scheme = sample_scheme(&mut rng);
+            let secret_key = Scalar::gen(&mut rng);
+            let public_key = scheme.derive(&secret_key, &mut ());
+            let other_secret_key = Scalar::gen(&mut rng);
+            let ephemeral_secret_keys = [Scalar::gen(&mut rng), Scalar::gen(&mut rng)];
+            let blindings = ephemeral_secret_keys
+                .iter()
+                .flat_map(|ephemeral_secret_key| [0, 1].map(|i| (*ephemeral_secret_key, index(i))))
+                .collect::<Vec<_>>();
+            let blinded_keys = blindings
+                .iter()
+                .map(|(ephemeral_secret_key, index)| {
+                    scheme.blind(&public_key, ephemeral_secret_key, index, &mut ())
+                })
+                .collect::<Vec<_>>();
+            let blinding_factors = blindings
+                .iter()
+                .zip(&blinded_keys)
+                .map(|((_, index), blinded_key)| {
+                    scheme.recover_blinding_factor(
+                        &secret_key,
+                        &blinded_key.ephemeral_public_key,
+                        index,
+                        &mut (),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for (i, ((_, index), blinded_key)) in blindings.iter().zip(&blinded_keys).enumerate() {
+                assert_ne!(
+                    blinded_key.one_time_public_key, public_key,
+                    "One-time public keys must differ from the long-term public key."
+                );
+                assert!(
+                    scheme.is_recipient(&secret_key, blinded_key, index, &mut ()),
+                    "The recipient must recognize their blinded keys."
+                );
+                assert!(
+                    !scheme.is_recipient(&other_secret_key, blinded_key, index, &mut ()),
+                    "Other recipients must not recognize the blinded keys."
+                );
+                for (j, other) in blinded_keys.iter().enumerate().skip(i + 1) {
+                    assert_ne!(
+                        blinding_factors[i], blinding_factors[j],
+                        "Different blindings must use different blinding factors."
+                    );
+                    assert_ne!(
+                        blinded_key.one_time_public_key, other.one_time_public_key,
+                        "Different blinding factors must give different one-time public keys."
+                    );
+                }
+            }
+            let shared_secret = public_key.scalar_mul(&ephemeral_secret_keys[0], &mut ());
+            assert_eq!(
+                blinding_factors[0],
+                scheme
+                    .hash
+                    .blinding_factor(&shared_secret, &index(0), &mut ()),
+                "The recipient must recover the blinding factor of the sender."
+            );
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! GLV Scalar Multiplication
+//!
+//! Short Weierstrass curves with `j`-invariant zero have the endomorphism
+//! `phi(x, y) = (beta * x, y)` which acts as the scalar multiplication by a cube root of unity
+//! `lambda`. Every scalar `k` then splits into `k = k_1 + lambda * k_2` with half-width `k_1` and
+//! `k_2`, and `k * P = k_1 * P + k_2 * phi(P)` is computed with a joint double-and-add over half
+//! as many bits. In-circuit, the decomposition is a secret witness which is checked against `k`
+//! with non-native arithmetic over the scalar field. See [`GroupVar::glv_scalar_mul`] for more.
+
+use crate::{
+    algebra::{Compiler, Group, GroupVar, ScalarVar},
+    constraint::{fp::Fp, Boolean, FpVar, SynthesisError, R1CS},
+    ec::{
+        models::SWModelParameters,
+        short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+        AffineCurve, ProjectiveCurve,
+    },
+    ff::{BigInteger, Field, FpParameters, PrimeField, Zero},
+    r1cs_std::{
+        alloc::AllocVar,
+        eq::EqGadget,
+        fields::{nonnative::NonNativeFieldVar, FieldVar},
+        groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+        select::CondSelectGadget,
+        R1CSVar, ToBitsGadget,
+    },
+    relations::ns,
+};
+use alloc::vec::Vec;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use openzl_crypto::algebra::Endomorphism;
+
+/// Bit Length of the Halves of a GLV Decomposition
+pub const HALF_BITS: usize = 128;
+
+/// GLV Parameters
+pub trait GlvParameters: SWModelParameters {
+    /// Endomorphism Coefficient
+    ///
+    /// This is the cube root of unity `beta` in the base field with `phi(x, y) = (beta * x, y)`.
+    const ENDOMORPHISM_COEFFICIENT: Self::BaseField;
+
+    /// Endomorphism Eigenvalue
+    ///
+    /// This is the cube root of unity `lambda` in the scalar field with `phi(P) = lambda * P`.
+    const LAMBDA: Self::ScalarField;
+
+    /// Short Lattice Basis
+    ///
+    /// Both `(a, b)` pairs satisfy `a + b * lambda = 0` modulo the scalar field modulus and are
+    /// short enough that every decomposition has halves of at most [`HALF_BITS`] bits.
+    const BASIS: [(i128, i128); 2];
+}
+
+/// Half-Width Scalar
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HalfScalar {
+    /// Sign Bit
+    pub is_negative: bool,
+
+    /// Absolute Value
+    pub magnitude: u128,
+}
+
+impl HalfScalar {
+    /// Builds a new [`HalfScalar`] from `value`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the absolute value of `value` does not fit into [`HALF_BITS`] bits.
+    #[inline]
+    fn from_bigint(value: BigInt) -> Self {
+        let (sign, magnitude) = value.into_parts();
+        Self {
+            is_negative: sign == Sign::Minus,
+            magnitude: u128::try_from(&magnitude)
+                .expect("GLV decompositions always have half-width components."),
+        }
+    }
+
+    /// Returns the `i`-th bit of the magnitude of `self` in little-endian order.
+    #[inline]
+    pub fn bit(&self, i: usize) -> bool {
+        (self.magnitude >> i) & 1 == 1
+    }
+
+    /// Converts `self` into an element of the field `F`.
+    #[inline]
+    pub fn to_field<F>(&self) -> F
+    where
+        F: PrimeField,
+    {
+        let magnitude = F::from(self.magnitude);
+        if self.is_negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+/// GLV Decomposition
+///
+/// The scalar `k` is equal to `k_1 + lambda * k_2` modulo the scalar field modulus.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Decomposition {
+    /// First Component
+    pub k1: HalfScalar,
+
+    /// Second Component
+    pub k2: HalfScalar,
+}
+
+/// Converts `integer` into a non-negative [`BigInt`].
+#[inline]
+fn to_bigint<B>(integer: B) -> BigInt
+where
+    B: BigInteger,
+{
+    BigInt::from_bytes_le(Sign::Plus, &integer.to_bytes_le())
+}
+
+/// Divides `numerator` by `denominator` and rounds to the nearest integer.
+#[inline]
+fn rounded_div(numerator: BigInt, denominator: &BigInt) -> BigInt {
+    (&numerator + &numerator + denominator).div_floor(&(denominator + denominator))
+}
+
+/// Decomposes `scalar` into two half-width components with respect to the parameters `P`.
+#[inline]
+pub fn decompose<P>(scalar: &P::ScalarField) -> Decomposition
+where
+    P: GlvParameters,
+{
+    let modulus = to_bigint(<P::ScalarField as PrimeField>::Params::MODULUS);
+    let scalar = to_bigint(scalar.into_repr());
+    let [(a1, b1), (a2, b2)] = P::BASIS.map(|(a, b)| (BigInt::from(a), BigInt::from(b)));
+    let c1 = rounded_div(&b2 * &scalar, &modulus);
+    let c2 = rounded_div(-&b1 * &scalar, &modulus);
+    Decomposition {
+        k1: HalfScalar::from_bigint(scalar - &c1 * &a1 - &c2 * &a2),
+        k2: HalfScalar::from_bigint(-(&c1 * &b1) - &c2 * &b2),
+    }
+}
+
+/// Applies the endomorphism of `P` to `point`.
+#[inline]
+pub fn endomorphism<P>(point: &GroupAffine<P>) -> GroupAffine<P>
+where
+    P: GlvParameters,
+{
+    GroupAffine::new(
+        point.x * P::ENDOMORPHISM_COEFFICIENT,
+        point.y,
+        point.infinity,
+    )
+}
+
+/// Multiplies `point` by `scalar` using the GLV decomposition of `scalar`.
+#[inline]
+pub fn glv_scalar_mul<P>(point: &GroupAffine<P>, scalar: &P::ScalarField) -> GroupProjective<P>
+where
+    P: GlvParameters,
+{
+    let Decomposition { k1, k2 } = decompose::<P>(scalar);
+    let lhs = if k1.is_negative { -*point } else { *point };
+    let rhs = endomorphism(point);
+    let rhs = if k2.is_negative { -rhs } else { rhs };
+    let mut sum = lhs.into_projective();
+    sum.add_assign_mixed(&rhs);
+    let mut result = GroupProjective::<P>::zero();
+    for i in (0..HALF_BITS).rev() {
+        result.double_in_place();
+        match (k1.bit(i), k2.bit(i)) {
+            (true, false) => result.add_assign_mixed(&lhs),
+            (false, true) => result.add_assign_mixed(&rhs),
+            (true, true) => result += &sum,
+            _ => {}
+        }
+    }
+    result
+}
+
+impl<P> Group<GroupProjective<P>>
+where
+    P: GlvParameters,
+{
+    /// Multiplies `self` by `scalar` using the GLV decomposition of `scalar`.
+    #[inline]
+    pub fn glv_scalar_mul(&self, scalar: &Fp<P::ScalarField>) -> Self {
+        Self(glv_scalar_mul(&self.0, &scalar.0).into_affine())
+    }
+}
+
+impl<P> Endomorphism for Group<GroupProjective<P>>
+where
+    P: GlvParameters,
+{
+    #[inline]
+    fn endomorphism(&self, _: &mut ()) -> Self {
+        Self(endomorphism(&self.0))
+    }
+}
+
+/// Half-Width Scalar Variable
+struct HalfScalarVar<F>
+where
+    F: PrimeField,
+{
+    /// Sign Bit
+    is_negative: Boolean<F>,
+
+    /// Little-Endian Magnitude Bits
+    bits: Vec<Boolean<F>>,
+}
+
+impl<F> HalfScalarVar<F>
+where
+    F: PrimeField,
+{
+    /// Allocates `half` as a secret witness in `compiler`.
+    #[inline]
+    fn new_witness(half: Option<HalfScalar>, compiler: &mut R1CS<F>) -> Self {
+        Self {
+            is_negative: Boolean::new_witness(ns!(compiler.0, "GLV sign secret witness"), || {
+                half.map(|half| half.is_negative)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })
+            .expect("Variable allocation is not allowed to fail."),
+            bits: (0..HALF_BITS)
+                .map(|i| {
+                    Boolean::new_witness(ns!(compiler.0, "GLV bit secret witness"), || {
+                        half.map(|half| half.bit(i))
+                            .ok_or(SynthesisError::AssignmentMissing)
+                    })
+                    .expect("Variable allocation is not allowed to fail.")
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the signed value of `self` as a non-native field variable.
+    #[inline]
+    fn to_nonnative<T>(&self) -> NonNativeFieldVar<T, F>
+    where
+        T: PrimeField,
+    {
+        let magnitude = nonnative_from_bits_le(&self.bits);
+        let negated = magnitude
+            .negate()
+            .expect("Negation is not allowed to fail.");
+        NonNativeFieldVar::conditionally_select(&self.is_negative, &negated, &magnitude)
+            .expect("Conditional selection is not allowed to fail.")
+    }
+}
+
+/// Recomposes the little-endian `bits` into a non-native field variable.
+#[inline]
+pub(super) fn nonnative_from_bits_le<T, F>(bits: &[Boolean<F>]) -> NonNativeFieldVar<T, F>
+where
+    T: PrimeField,
+    F: PrimeField,
+{
+    let zero = NonNativeFieldVar::zero();
+    let mut power = T::one();
+    let mut result = zero.clone();
+    for bit in bits {
+        result += &NonNativeFieldVar::conditionally_select(
+            bit,
+            &NonNativeFieldVar::Constant(power),
+            &zero,
+        )
+        .expect("Conditional selection is not allowed to fail.");
+        power.double_in_place();
+    }
+    result
+}
+
+impl<P, F> GroupVar<GroupProjective<P>, ProjectiveVar<P, FpVar<F>>>
+where
+    P: GlvParameters<BaseField = F>,
+    F: PrimeField,
+{
+    /// Multiplies `self` by `scalar` using the GLV decomposition of `scalar`.
+    ///
+    /// The decomposition is allocated as a secret witness and checked against the bits of
+    /// `scalar` with non-native arithmetic over the scalar field, so that the joint double-and-add
+    /// only runs over [`HALF_BITS`] bits. This roughly halves the number of doublings and additions
+    /// of [`ScalarMul`](openzl_crypto::algebra::ScalarMul) at the fixed cost of the non-native
+    /// decomposition check.
+    #[inline]
+    pub fn glv_scalar_mul(
+        &self,
+        scalar: &ScalarVar<GroupProjective<P>, ProjectiveVar<P, FpVar<F>>>,
+        compiler: &mut Compiler<GroupProjective<P>>,
+    ) -> Self {
+        let decomposition = scalar.0.value().ok().map(|scalar| {
+            decompose::<P>(&P::ScalarField::from_le_bytes_mod_order(
+                &scalar.into_repr().to_bytes_le(),
+            ))
+        });
+        let k1 = HalfScalarVar::new_witness(decomposition.map(|d| d.k1), compiler);
+        let k2 = HalfScalarVar::new_witness(decomposition.map(|d| d.k2), compiler);
+        let scalar_bits =
+            ToBitsGadget::to_bits_le(&scalar.0).expect("Bit decomposition is not allowed to fail.");
+        (k1.to_nonnative::<P::ScalarField>() + k2.to_nonnative::<P::ScalarField>() * P::LAMBDA)
+            .enforce_equal(&nonnative_from_bits_le(&scalar_bits))
+            .expect("Enforcing equality is not allowed to fail.");
+        let point = &self.0;
+        let lhs = ProjectiveVar::conditionally_select(
+            &k1.is_negative,
+            &point.negate().expect("Negation is not allowed to fail."),
+            point,
+        )
+        .expect("Conditional selection is not allowed to fail.");
+        let rhs = self.endomorphism(compiler).0;
+        let rhs = ProjectiveVar::conditionally_select(
+            &k2.is_negative,
+            &rhs.negate().expect("Negation is not allowed to fail."),
+            &rhs,
+        )
+        .expect("Conditional selection is not allowed to fail.");
+        let sum = lhs.clone() + &rhs;
+        let zero = ProjectiveVar::zero();
+        let mut result = zero.clone();
+        for (lhs_bit, rhs_bit) in k1.bits.iter().zip(&k2.bits).rev() {
+            result
+                .double_in_place()
+                .expect("Doubling is not allowed to fail.");
+            let without_rhs = ProjectiveVar::conditionally_select(lhs_bit, &lhs, &zero)
+                .expect("Conditional selection is not allowed to fail.");
+            let with_rhs = ProjectiveVar::conditionally_select(lhs_bit, &sum, &rhs)
+                .expect("Conditional selection is not allowed to fail.");
+            result += &ProjectiveVar::conditionally_select(rhs_bit, &with_rhs, &without_rhs)
+                .expect("Conditional selection is not allowed to fail.");
+        }
+        Self::new(result)
+    }
+}
+
+impl<P, F> Endomorphism<Compiler<GroupProjective<P>>>
+    for GroupVar<GroupProjective<P>, ProjectiveVar<P, FpVar<F>>>
+where
+    P: GlvParameters<BaseField = F>,
+    F: PrimeField,
+{
+    #[inline]
+    fn endomorphism(&self, compiler: &mut Compiler<GroupProjective<P>>) -> Self {
+        let _ = compiler;
+        Self::new(ProjectiveVar::new(
+            &self.0.x * P::ENDOMORPHISM_COEFFICIENT,
+            self.0.y.clone(),
+            self.0.z.clone(),
+        ))
+    }
+}
@@ -0,0 +1,257 @@
+//! Grumpkin Curve
+//!
+//! Grumpkin is the short Weierstrass curve `y^2 = x^3 - 17` whose base field is the scalar field
+//! of BN254 and whose scalar field is the base field of BN254, forming a cycle with BN254. Its
+//! group operations are native arithmetic in circuits over BN254.
+//!
+//! # Safety
+//!
+//! The scalar field of Grumpkin is **larger** than its base field, so
+//! [`ScalarVar`](super::ScalarVar) is only sound for scalars which are smaller than the modulus of
+//! the BN254 scalar field. Larger scalars are reduced when they are allocated.
+
+use crate::{
+    bn254::{Fq, Fr},
+    ec::{
+        models::{ModelParameters, SWModelParameters},
+        short_weierstrass_jacobian::{GroupAffine, GroupProjective},
+    },
+    ff::{field_new, Zero},
+};
+
+#[cfg(feature = "constraint")]
+use crate::{
+    algebra::glv::GlvParameters,
+    constraint::fp::Fp,
+    r1cs_std::{fields::fp::FpVar, groups::curves::short_weierstrass},
+};
+
+/// Grumpkin Curve Parameters
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Parameters;
+
+impl ModelParameters for Parameters {
+    type BaseField = Fr;
+    type ScalarField = Fq;
+}
+
+impl SWModelParameters for Parameters {
+    const COEFF_A: Fr = field_new!(Fr, "0");
+
+    const COEFF_B: Fr = field_new!(Fr, "-17");
+
+    const COFACTOR: &'static [u64] = &[1];
+
+    const COFACTOR_INV: Fq = field_new!(Fq, "1");
+
+    /// The generator is `(1, sqrt(-16))`.
+    const AFFINE_GENERATOR_COEFFS: (Fr, Fr) = (
+        field_new!(Fr, "1"),
+        field_new!(
+            Fr,
+            "17631683881184975370165255887551781615748388533673675138860"
+        ),
+    );
+
+    #[inline(always)]
+    fn mul_by_a(_: &Self::BaseField) -> Self::BaseField {
+        Self::BaseField::zero()
+    }
+}
+
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+impl GlvParameters for Parameters {
+    const ENDOMORPHISM_COEFFICIENT: Fr = field_new!(
+        Fr,
+        "21888242871839275217838484774961031246154997185409878258781734729429964517155"
+    );
+
+    const LAMBDA: Fq = field_new!(
+        Fq,
+        "21888242871839275220042445260109153167277707414472061641714758635765020556616"
+    );
+
+    const BASIS: [(i128, i128); 2] = [
+        (
+            147946756881789319000765030803803410729,
+            -9931322734385697762,
+        ),
+        (9931322734385697762, 147946756881789319010696353538189108491),
+    ];
+}
+
+/// Projective Curve Type
+pub type Projective = GroupProjective<Parameters>;
+
+/// Affine Curve Type
+pub type Affine = GroupAffine<Parameters>;
+
+/// Group Element Type
+pub type Group = super::Group<Projective>;
+
+/// Scalar Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type Scalar = Fp<Fq>;
+
+/// Projective Curve Variable Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type ProjectiveVar = short_weierstrass::ProjectiveVar<Parameters, FpVar<Fr>>;
+
+/// Group Element Variable Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type GroupVar = super::GroupVar<Projective, ProjectiveVar>;
+
+/// Scalar Variable Type
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub type ScalarVar = super::ScalarVar<Projective, ProjectiveVar>;
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ec::{AffineCurve, ProjectiveCurve},
+        ff::{FpParameters, PrimeField},
+    };
+
+    /// Checks that the generator is on the curve and has the scalar field as its order.
+    #[test]
+    fn generator_is_valid() {
+        let generator = Affine::prime_subgroup_generator();
+        assert!(
+            generator.is_on_curve(),
+            "The generator must be on the curve."
+        );
+        assert!(
+            generator.mul(<Fq as PrimeField>::Params::MODULUS).is_zero(),
+            "The generator must have the scalar field modulus as its order."
+        );
+        assert_eq!(
+            generator.into_projective().into_affine(),
+            generator,
+            "The generator must survive a projective round trip."
+        );
+    }
+
+    /// Checks that points round-trip through every encoding mode and that compressed encodings
+    /// are smaller than uncompressed ones.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn group_codec_modes_round_trip() {
+        use openzl_util::{
+            codec::{Decode, DecodeWithMode, Encode, EncodeWithMode, Mode},
+            rand::{Sample, TestRng},
+        };
+        let mut rng = TestRng::new();
+        let point = Group::sample((), &mut rng);
+        for mode in [Mode::Compressed, Mode::Uncompressed, Mode::Unchecked] {
+            assert_eq!(
+                Group::from_vec_with_mode(mode, point.to_vec_with_mode(mode))
+                    .expect("Decoding a freshly encoded point should succeed."),
+                point,
+                "The point should round-trip in {mode:?} mode."
+            );
+        }
+        assert_eq!(
+            point.to_vec(),
+            point.to_vec_with_mode(Mode::Compressed),
+            "The default encoding should be the compressed encoding."
+        );
+        assert!(
+            point.to_vec_with_mode(Mode::Compressed).len()
+                < point.to_vec_with_mode(Mode::Uncompressed).len(),
+            "The compressed encoding should be smaller than the uncompressed encoding."
+        );
+        let mut invalid = point.to_vec_with_mode(Mode::Uncompressed);
+        invalid[0] ^= 1;
+        assert!(
+            Group::from_vec_with_mode(Mode::Uncompressed, invalid).is_err(),
+            "Validated decoding should reject points which are not on the curve."
+        );
+        assert_eq!(
+            Group::from_vec(point.to_vec()).expect("Decoding should succeed."),
+            point,
+            "The default decoding should read the compressed encoding."
+        );
+    }
+
+    /// Checks that checked deserialization rejects points which are not on the curve, while
+    /// unchecked deserialization accepts them.
+    #[test]
+    fn checked_deserialization_rejects_points_off_the_curve() {
+        use crate::{ff::One, serialize::CanonicalSerialize};
+        use alloc::vec::Vec;
+        use openzl_util::rand::{Sample, TestRng};
+        let mut rng = TestRng::new();
+        let point = Group::sample((), &mut rng);
+        let off_curve = Affine::new(point.0.x, point.0.y + Fr::one(), false);
+        let mut bytes = Vec::new();
+        off_curve
+            .serialize_unchecked(&mut bytes)
+            .expect("Serializing into a vector is not allowed to fail.");
+        assert!(
+            Group::deserialize_checked(bytes.as_slice()).is_err(),
+            "Checked deserialization should reject points which are not on the curve."
+        );
+        assert_eq!(
+            Group::deserialize_unchecked(bytes.as_slice())
+                .expect("Unchecked deserialization should accept points off the curve."),
+            Group(off_curve),
+            "Unchecked deserialization should return the encoded point."
+        );
+    }
+
+    /// Checks that GLV scalar multiplication agrees with plain scalar multiplication, natively and
+    /// in-circuit.
+    #[cfg(feature = "constraint")]
+    #[test]
+    fn glv_scalar_mul_matches_plain_scalar_mul() {
+        use crate::{
+            algebra::glv::decompose, constraint::R1CS, ff::UniformRand, r1cs_std::R1CSVar,
+        };
+        use eclair::alloc::{mode::Secret, Allocate};
+        use openzl_crypto::algebra::{Endomorphism, ScalarMul};
+        use openzl_util::rand::{Sample, TestRng};
+        let mut rng = TestRng::new();
+        let point = Group::sample((), &mut rng);
+        assert_eq!(
+            point.endomorphism(&mut ()),
+            point.scalar_mul(&Fp(Parameters::LAMBDA), &mut ()),
+            "The endomorphism must act as the scalar multiplication by its eigenvalue."
+        );
+        for _ in 0..4 {
+            let scalar = Fq::rand(&mut rng);
+            let decomposition = decompose::<Parameters>(&scalar);
+            assert_eq!(
+                decomposition.k1.to_field::<Fq>()
+                    + Parameters::LAMBDA * decomposition.k2.to_field::<Fq>(),
+                scalar,
+                "The decomposition must recompose into the scalar."
+            );
+            assert_eq!(
+                point.glv_scalar_mul(&Fp(scalar)),
+                point.scalar_mul(&Fp(scalar), &mut ()),
+                "Native GLV scalar multiplication must match plain scalar multiplication."
+            );
+        }
+        let scalar = Fp(Fq::from(u128::rand(&mut rng)));
+        let mut compiler = R1CS::<Fr>::for_proofs();
+        let point_var = point.as_known::<Secret, GroupVar>(&mut compiler);
+        let scalar_var = scalar.as_known::<Secret, ScalarVar>(&mut compiler);
+        let product = point_var.glv_scalar_mul(&scalar_var, &mut compiler);
+        assert_eq!(
+            product.0.value().expect("The product must have a value."),
+            point.scalar_mul(&scalar, &mut ()).0.into_projective(),
+            "In-circuit GLV scalar multiplication must match plain scalar multiplication."
+        );
+        assert!(
+            compiler.is_satisfied(),
+            "The GLV constraints must be satisfied."
+        );
+    }
+}
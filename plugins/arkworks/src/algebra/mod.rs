@@ -0,0 +1,969 @@
+//! Arkworks Algebra
+
+use crate::{
+    ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve},
+    ff::{BigInteger, Field, FpParameters, PrimeField, UniformRand},
+    r1cs_std::{fields::fp::FpVar, groups::CurveVar},
+    serialize::{self, CanonicalSerialize, Read, SerializationError, Validate, ValidationError},
+};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use openzl_crypto::algebra::{self, security};
+use openzl_util::{
+    derivative,
+    rand::{RngCore, Sample},
+};
+
+#[cfg(feature = "constraint")]
+use {
+    crate::{
+        constraint::{empty, fp::Fp, full, Boolean, R1CS},
+        r1cs_std::{
+            alloc::AllocVar, eq::EqGadget, fields::nonnative::NonNativeFieldVar,
+            select::CondSelectGadget, ToBitsGadget,
+        },
+        relations::ns,
+    },
+    core::borrow::Borrow,
+    eclair::{
+        alloc::{
+            mode::{self, Public, Secret},
+            Constant, Variable,
+        },
+        audit::Audit,
+        bool::{BitDecomposition, Bool, ConditionalSelect},
+        num::Zero,
+        ops::Neg,
+    },
+    openzl_crypto::algebra::{FixedBaseScalarMul, MultiScalarMul, ScalarMul},
+};
+
+#[cfg(all(feature = "alloc", feature = "constraint"))]
+use openzl_crypto::algebra::{MultiWindow, ScalarMulStrategy, Wnaf};
+
+#[cfg(feature = "serde")]
+use {
+    crate::serialize::{ArkReader, ArkWriter, CanonicalDeserialize},
+    openzl_util::{
+        codec::{Decode, DecodeError, DecodeWithMode, Encode, EncodeWithMode, Mode, Read, Write},
+        serde::Serializer,
+    },
+};
+
+#[cfg(feature = "ed-on-bn254")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "ed-on-bn254")))]
+pub mod baby_jubjub;
+
+#[cfg(feature = "bn254")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bn254")))]
+pub mod grumpkin;
+
+#[cfg(feature = "constraint")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
+pub mod glv;
+
+/// Constraint Field Type
+pub type ConstraintField<C> = <<C as ProjectiveCurve>::BaseField as Field>::BasePrimeField;
+
+/// Compiler Type
+#[cfg(feature = "constraint")]
+pub type Compiler<C> = R1CS<ConstraintField<C>>;
+
+/// Converts `scalar` to the bit representation of `O`.
+#[inline]
+pub fn convert_bits<T, O>(scalar: T) -> O::BigInt
+where
+    T: BigInteger,
+    O: PrimeField,
+{
+    O::BigInt::from_bits_le(&scalar.to_bits_le())
+}
+
+/// Checks that the modulus of `A` is smaller than that of `B`.
+#[inline]
+pub fn modulus_is_smaller<A, B>() -> bool
+where
+    A: PrimeField,
+    B: PrimeField,
+{
+    let modulus_a = A::Params::MODULUS;
+    let modulus_b = B::Params::MODULUS;
+    if modulus_a.num_bits() <= modulus_b.num_bits() {
+        convert_bits::<_, B>(modulus_a) < modulus_b
+    } else {
+        modulus_a < convert_bits::<_, A>(modulus_b)
+    }
+}
+
+/// Converts `point` into its canonical byte-representation.
+#[inline]
+pub fn affine_point_as_bytes<C>(point: &C::Affine) -> Vec<u8>
+where
+    C: ProjectiveCurve,
+{
+    let mut buffer = Vec::with_capacity(point.serialized_size());
+    point
+        .serialize(&mut buffer)
+        .expect("Serialization is not allowed to fail.");
+    buffer
+}
+
+/// Uses `serializer` to serialize `point`.
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+#[inline]
+pub fn serialize_group_element<C, S>(point: &C::Affine, serializer: S) -> Result<S::Ok, S::Error>
+where
+    C: ProjectiveCurve,
+    S: Serializer,
+{
+    serializer.serialize_bytes(&affine_point_as_bytes::<C>(point))
+}
+
+/// Elliptic Curve Scalar Element Variable
+///
+/// # Safety
+///
+/// This type can only be used whenever the embedded scalar field is **smaller** than the
+/// outer scalar field.
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Debug)]
+pub struct ScalarVar<C, CV>(pub(crate) FpVar<ConstraintField<C>>, PhantomData<CV>)
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>;
+
+impl<C, CV> ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    /// Builds a new [`ScalarVar`] from a given `scalar`.
+    #[inline]
+    pub fn new(scalar: FpVar<ConstraintField<C>>) -> Self {
+        Self(scalar, PhantomData)
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    /// Reduces `value` from the constraint field modulo the order of the embedded scalar field.
+    ///
+    /// This is the in-circuit counterpart of [`PrimeField::from_le_bytes_mod_order`] over the
+    /// canonical bytes of `value`.
+    #[inline]
+    pub fn reduce(value: &FpVar<ConstraintField<C>>) -> Self {
+        Self::from_nonnative(&glv::nonnative_from_bits_le(
+            &ToBitsGadget::to_bits_le(value).expect("Bit decomposition is not allowed to fail."),
+        ))
+    }
+
+    /// Returns `self` as a non-native variable over the embedded scalar field.
+    #[inline]
+    fn to_nonnative(&self) -> NonNativeFieldVar<C::ScalarField, ConstraintField<C>> {
+        glv::nonnative_from_bits_le(
+            &ToBitsGadget::to_bits_le(&self.0).expect("Bit decomposition is not allowed to fail."),
+        )
+    }
+
+    /// Builds a new [`ScalarVar`] from the canonical representative of `scalar`.
+    #[inline]
+    fn from_nonnative(scalar: &NonNativeFieldVar<C::ScalarField, ConstraintField<C>>) -> Self {
+        Self::new(
+            Boolean::le_bits_to_fp_var(
+                &scalar
+                    .to_bits_le()
+                    .expect("Bit decomposition is not allowed to fail."),
+            )
+            .expect("Bit recomposition is not allowed to fail."),
+        )
+    }
+}
+
+/// Returns the modulus bits of scalar field of a given curve `C`.
+pub const fn scalar_bits<C>() -> usize
+where
+    C: ProjectiveCurve,
+{
+    <<C as ProjectiveCurve>::ScalarField as PrimeField>::Params::MODULUS_BITS as usize
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV, const BITS: usize> BitDecomposition<BITS, Compiler<C>> for ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    #[inline]
+    fn to_bits_le(&self, compiler: &mut Compiler<C>) -> [Bool<Compiler<C>>; BITS] {
+        self.0.to_bits_le(compiler)
+    }
+}
+
+/// Converts `scalar` into an element of the constraint field of `C`.
+///
+/// # Safety
+///
+/// This conversion is only injective whenever the embedded scalar field is **smaller** than the
+/// outer scalar field. See [`ScalarVar`] for more.
+#[cfg(feature = "constraint")]
+#[inline]
+pub fn scalar_to_constraint_field<C>(scalar: &C::ScalarField) -> ConstraintField<C>
+where
+    C: ProjectiveCurve,
+{
+    ConstraintField::<C>::from_le_bytes_mod_order(&scalar.into_repr().to_bytes_le())
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> algebra::Group<Compiler<C>> for ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    /// Adds `rhs` to `self` modulo the order of the embedded scalar field.
+    ///
+    /// Both summands are smaller than the embedded scalar field, so their sum does not overflow
+    /// the larger constraint field and is reduced with [`reduce`](ScalarVar::reduce).
+    #[inline]
+    fn add(&self, rhs: &Self, compiler: &mut Compiler<C>) -> Self {
+        let _ = compiler;
+        Self::reduce(&(&self.0 + &rhs.0))
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> algebra::Ring<Compiler<C>> for ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    /// Multiplies `self` by `rhs` with non-native arithmetic over the embedded scalar field.
+    #[inline]
+    fn mul(&self, rhs: &Self, compiler: &mut Compiler<C>) -> Self {
+        let _ = compiler;
+        Self::from_nonnative(&(self.to_nonnative() * rhs.to_nonnative()))
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Constant<Compiler<C>> for ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = Fp<C::ScalarField>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, mode::Constant>();
+        Self::new(
+            AllocVar::new_constant(
+                ns!(compiler.0, "embedded scalar constant"),
+                scalar_to_constraint_field::<C>(&this.0),
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Variable<Public, Compiler<C>> for ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = Fp<C::ScalarField>;
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Public>();
+        Self::new(
+            FpVar::new_input(
+                ns!(compiler.0, "embedded scalar public input"),
+                full(scalar_to_constraint_field::<C>(&this.0)),
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+
+    #[inline]
+    fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Public>();
+        Self::new(
+            FpVar::new_input(
+                ns!(compiler.0, "embedded scalar public input"),
+                empty::<ConstraintField<C>>,
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Variable<Secret, Compiler<C>> for ScalarVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = Fp<C::ScalarField>;
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
+        Self::new(
+            FpVar::new_witness(
+                ns!(compiler.0, "embedded scalar secret witness"),
+                full(scalar_to_constraint_field::<C>(&this.0)),
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+
+    #[inline]
+    fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
+        Self::new(
+            FpVar::new_witness(
+                ns!(compiler.0, "embedded scalar secret witness"),
+                empty::<ConstraintField<C>>,
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+}
+
+/// Elliptic Curve Group Element
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Group<C>(
+    /// Affine Point
+    pub C::Affine,
+)
+where
+    C: ProjectiveCurve;
+
+impl<C> Group<C>
+where
+    C: ProjectiveCurve,
+{
+    /// Deserializes a group element from its uncompressed encoding in `reader` without checking
+    /// that it is on the curve or in the prime-order subgroup.
+    ///
+    /// # Crypto Safety
+    ///
+    /// This method should only be used for points coming from a trusted source. See
+    /// [`deserialize_checked`](Self::deserialize_checked) for the validated variant.
+    #[inline]
+    pub fn deserialize_unchecked<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        serialize::deserialize_unchecked(reader).map(Self)
+    }
+}
+
+impl<C> Group<C>
+where
+    C: ProjectiveCurve,
+    C::Affine: Validate,
+{
+    /// Checks that `self` is on the curve and in the prime-order subgroup.
+    #[inline]
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.0.validate()
+    }
+
+    /// Returns `true` if `self` is on the curve and in the prime-order subgroup.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Returns `true` if multiplying `self` by the cofactor gives the identity. See
+    /// [`Validate::has_small_order`] for more.
+    #[inline]
+    pub fn has_small_order(&self) -> bool {
+        self.0.has_small_order()
+    }
+
+    /// Deserializes a group element from its uncompressed encoding in `reader`, checking that it
+    /// is on the curve and in the prime-order subgroup.
+    #[inline]
+    pub fn deserialize_checked<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        serialize::deserialize_checked(reader).map(Self)
+    }
+}
+
+impl<C> algebra::Group for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn add(&self, rhs: &Self, _: &mut ()) -> Self {
+        let mut sum = self.0.into_projective();
+        sum.add_assign_mixed(&rhs.0);
+        Self(sum.into_affine())
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C> ScalarMul<Fp<C::ScalarField>> for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    type Output = Self;
+
+    #[inline]
+    fn scalar_mul(&self, scalar: &Fp<C::ScalarField>, _: &mut ()) -> Self::Output {
+        Self(self.0.mul(scalar.0.into_repr()).into_affine())
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C> MultiScalarMul<Fp<C::ScalarField>> for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn multi_scalar_mul(terms: &[(&Self, &Fp<C::ScalarField>)], _: &mut ()) -> Self {
+        assert!(
+            !terms.is_empty(),
+            "Multi-base scalar multiplication needs at least one term."
+        );
+        let (bases, scalars): (Vec<_>, Vec<_>) = terms
+            .iter()
+            .map(|(base, scalar)| (base.0, scalar.0.into_repr()))
+            .unzip();
+        Self(VariableBaseMSM::multi_scalar_mul(&bases, &scalars).into_affine())
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C> FixedBaseScalarMul<Fp<C::ScalarField>> for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    type Base = Self;
+
+    #[inline]
+    fn fixed_base_scalar_mul<I>(
+        precomputed_bases: I,
+        scalar: &Fp<C::ScalarField>,
+        _: &mut (),
+    ) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Self::Base>,
+    {
+        let mut result = C::zero();
+        for (bit, base) in scalar
+            .0
+            .into_repr()
+            .to_bits_le()
+            .into_iter()
+            .zip(precomputed_bases)
+        {
+            if bit {
+                result.add_assign_mixed(&base.borrow().0);
+            }
+        }
+        Self(result.into_affine())
+    }
+}
+
+impl<C> core::ops::Neg for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl<C> eclair::num::Zero for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    type Verification = bool;
+
+    #[inline]
+    fn zero(_: &mut ()) -> Self {
+        Self(crate::ff::Zero::zero())
+    }
+
+    #[inline]
+    fn is_zero(&self, _: &mut ()) -> Self::Verification {
+        crate::ff::Zero::is_zero(&self.0)
+    }
+}
+
+impl<C> eclair::bool::ConditionalSelect for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn select(bit: &bool, true_value: &Self, false_value: &Self, _: &mut ()) -> Self {
+        if *bit {
+            *true_value
+        } else {
+            *false_value
+        }
+    }
+}
+
+impl<C> eclair::cmp::PartialEq<Self> for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self, _: &mut ()) -> bool {
+        PartialEq::eq(self, rhs)
+    }
+}
+
+impl<C> Sample for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self(<C as UniformRand>::rand(rng).into_affine())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C> Decode for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    type Error = SerializationError;
+
+    /// Decodes a compressed and validated point. See [`DecodeWithMode`] for the other formats.
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Self::decode_with_mode(Mode::Compressed, reader)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C> DecodeWithMode for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn decode_with_mode<R>(
+        mode: Mode,
+        reader: R,
+    ) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let mut reader = ArkReader::new(reader);
+        let point = match mode {
+            Mode::Compressed => C::Affine::deserialize(&mut reader),
+            Mode::Uncompressed => C::Affine::deserialize_uncompressed(&mut reader),
+            Mode::Unchecked => C::Affine::deserialize_unchecked(&mut reader),
+        };
+        match point {
+            Ok(point) => reader
+                .finish()
+                .map(move |_| Self(point))
+                .map_err(DecodeError::Read),
+            Err(err) => Err(DecodeError::Decode(err)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C> Encode for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    /// Encodes `self` as a compressed point. See [`EncodeWithMode`] for the other formats.
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.encode_with_mode(Mode::Compressed, writer)
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.encoded_len_with_mode(Mode::Compressed)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C> EncodeWithMode for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn encode_with_mode<W>(&self, mode: Mode, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        let mut writer = ArkWriter::new(writer);
+        let _ = match mode {
+            Mode::Compressed => self.0.serialize(&mut writer),
+            Mode::Uncompressed => self.0.serialize_uncompressed(&mut writer),
+            Mode::Unchecked => self.0.serialize_unchecked(&mut writer),
+        };
+        writer.finish().map(move |_| ())
+    }
+
+    #[inline]
+    fn encoded_len_with_mode(&self, mode: Mode) -> usize {
+        match mode {
+            Mode::Compressed => self.0.serialized_size(),
+            Mode::Uncompressed | Mode::Unchecked => self.0.uncompressed_size(),
+        }
+    }
+}
+
+impl<C> security::DiscreteLogarithmHardness for Group<C> where C: ProjectiveCurve {}
+
+impl<C> security::ComputationalDiffieHellmanHardness for Group<C> where C: ProjectiveCurve {}
+
+impl<C> security::DecisionalDiffieHellmanHardness for Group<C> where C: ProjectiveCurve {}
+
+/// Elliptic Curve Group Element Variable
+#[cfg(feature = "constraint")]
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Debug)]
+pub struct GroupVar<C, CV>(
+    /// Curve Variable
+    pub CV,
+    /// Type Parameter Marker
+    PhantomData<C>,
+)
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>;
+
+#[cfg(feature = "constraint")]
+impl<C, CV> GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    /// Builds a new [`GroupVar`] from a given `point`.
+    #[inline]
+    pub fn new(point: CV) -> Self {
+        Self(point, PhantomData)
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> algebra::Group<Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    #[inline]
+    fn add(&self, rhs: &Self, compiler: &mut Compiler<C>) -> Self {
+        let _ = compiler;
+        Self::new(self.0.clone() + &rhs.0)
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> ScalarMul<ScalarVar<C, CV>, Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn scalar_mul(&self, scalar: &ScalarVar<C, CV>, compiler: &mut Compiler<C>) -> Self::Output {
+        let _ = compiler;
+        Self::new(
+            self.0
+                .scalar_mul_le(
+                    ToBitsGadget::to_bits_le(&scalar.0)
+                        .expect("Bit decomposition is not allowed to fail.")
+                        .iter(),
+                )
+                .expect("Scalar multiplication is not allowed to fail."),
+        )
+    }
+}
+
+/// Window Size for Interleaved Multi-Base Scalar Multiplication
+#[cfg(all(feature = "alloc", feature = "constraint"))]
+const MULTI_SCALAR_MUL_WINDOW_SIZE: usize = 2;
+
+#[cfg(feature = "constraint")]
+impl<C, CV> MultiScalarMul<ScalarVar<C, CV>, Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn multi_scalar_mul(terms: &[(&Self, &ScalarVar<C, CV>)], compiler: &mut Compiler<C>) -> Self {
+        assert!(
+            !terms.is_empty(),
+            "Multi-base scalar multiplication needs at least one term."
+        );
+        let windows = MultiWindow::new(
+            MULTI_SCALAR_MUL_WINDOW_SIZE,
+            terms.iter().map(|(base, _)| (*base).clone()),
+            compiler,
+        );
+        let bit_vectors = terms
+            .iter()
+            .map(|(_, scalar)| {
+                let mut bits = ToBitsGadget::to_bits_le(&scalar.0)
+                    .expect("Bit decomposition is not allowed to fail.");
+                bits.reverse();
+                bits
+            })
+            .collect::<Vec<_>>();
+        windows.scalar_mul(&bit_vectors, compiler)
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> FixedBaseScalarMul<ScalarVar<C, CV>, Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Base = Group<C>;
+
+    #[inline]
+    fn fixed_base_scalar_mul<I>(
+        precomputed_bases: I,
+        scalar: &ScalarVar<C, CV>,
+        compiler: &mut Compiler<C>,
+    ) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Self::Base>,
+    {
+        let _ = compiler;
+        let bases = precomputed_bases
+            .into_iter()
+            .map(|base| base.borrow().0.into_projective())
+            .collect::<Vec<_>>();
+        let mut result = CV::zero();
+        result
+            .precomputed_base_scalar_mul_le(
+                ToBitsGadget::to_bits_le(&scalar.0)
+                    .expect("Bit decomposition is not allowed to fail.")
+                    .iter()
+                    .zip(&bases),
+            )
+            .expect("Scalar multiplication is not allowed to fail.");
+        Self::new(result)
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> eclair::cmp::PartialEq<Self, Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self, compiler: &mut Compiler<C>) -> Bool<Compiler<C>> {
+        let _ = compiler;
+        self.0
+            .is_eq(&rhs.0)
+            .expect("Equality checking is not allowed to fail.")
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Neg<Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn neg(self, compiler: &mut Compiler<C>) -> Self::Output {
+        let _ = compiler;
+        Self::new(self.0.negate().expect("Negation is not allowed to fail."))
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Zero<Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Verification = Bool<Compiler<C>>;
+
+    #[inline]
+    fn zero(compiler: &mut Compiler<C>) -> Self {
+        let _ = compiler;
+        Self::new(CV::zero())
+    }
+
+    #[inline]
+    fn is_zero(&self, compiler: &mut Compiler<C>) -> Self::Verification {
+        let _ = compiler;
+        self.0
+            .is_eq(&CV::zero())
+            .expect("Equality checking is not allowed to fail.")
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> ConditionalSelect<Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    #[inline]
+    fn select(
+        bit: &Bool<Compiler<C>>,
+        true_value: &Self,
+        false_value: &Self,
+        compiler: &mut Compiler<C>,
+    ) -> Self {
+        let _ = compiler;
+        Self::new(
+            CV::conditionally_select(bit, &true_value.0, &false_value.0)
+                .expect("Conditional selection is not allowed to fail."),
+        )
+    }
+}
+
+/// Scalar multiplication in R1CS prefers [`Wnaf`] tables: every entry of a lookup table costs
+/// constraints while negating a curve point is linear, so halving the tables pays for the sign
+/// handling of the signed digits.
+#[cfg(all(feature = "alloc", feature = "constraint"))]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<C, CV> ScalarMulStrategy<GroupVar<C, CV>> for Compiler<C>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Strategy = Wnaf<GroupVar<C, CV>>;
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Constant<Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = Group<C>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, mode::Constant>();
+        Self::new(
+            AllocVar::new_constant(ns!(compiler.0, "group constant"), this.0.into_projective())
+                .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Variable<Public, Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = Group<C>;
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Public>();
+        Self::new(
+            CV::new_input(
+                ns!(compiler.0, "group public input"),
+                full(this.0.into_projective()),
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+
+    #[inline]
+    fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Public>();
+        Self::new(
+            CV::new_input(ns!(compiler.0, "group public input"), empty::<C>)
+                .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> Variable<Secret, Compiler<C>> for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = Group<C>;
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
+        Self::new(
+            CV::new_witness(
+                ns!(compiler.0, "group secret witness"),
+                full(this.0.into_projective()),
+            )
+            .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+
+    #[inline]
+    fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
+        Self::new(
+            CV::new_witness(ns!(compiler.0, "group secret witness"), empty::<C>)
+                .expect("Variable allocation is not allowed to fail."),
+        )
+    }
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> security::DiscreteLogarithmHardness for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> security::ComputationalDiffieHellmanHardness for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+}
+
+#[cfg(feature = "constraint")]
+impl<C, CV> security::DecisionalDiffieHellmanHardness for GroupVar<C, CV>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+}
@@ -6,18 +6,26 @@ use core::iter;
 use eclair::{
     self,
     bool::{BitDecomposition, Bool, ConditionalSelect},
+    num::{FromBits, ToBytes},
 };
 use ff::BigInteger;
 use openzl_crypto::{
     algebra::{Group, Ring},
     constraint::{Input, ProofSystem},
+    hash::incremental::EncodeLength,
 };
 use openzl_util::{
-    byte_count, derivative,
+    byte_count, derivative, into_array_unchecked,
     rand::{RngCore, Sample},
     SizeLimit,
 };
 
+#[cfg(feature = "alloc")]
+use openzl_crypto::{
+    constraint::packing::{PackBytes, UnpackBytes},
+    field::convert::{CanonicalBytes, ConversionError, LeBytes},
+};
+
 #[cfg(feature = "serde")]
 use {
     crate::serialize::{ArkReader, ArkWriter, SerializationError},
@@ -49,6 +57,26 @@ pub struct Fp<F>(
 where
     F: Field;
 
+impl<F> From<u8> for Fp<F>
+where
+    F: Field,
+{
+    #[inline]
+    fn from(value: u8) -> Self {
+        Self(value.into())
+    }
+}
+
+impl<F> From<usize> for Fp<F>
+where
+    F: Field,
+{
+    #[inline]
+    fn from(value: usize) -> Self {
+        Self((value as u64).into())
+    }
+}
+
 impl<F> From<u128> for Fp<F>
 where
     F: Field,
@@ -81,6 +109,142 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> PackBytes for Fp<F>
+where
+    F: PrimeField,
+{
+    const BYTES: usize = ((F::Params::MODULUS_BITS - 1) / 8) as usize;
+
+    #[inline]
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        assert!(
+            bytes.len() <= <Self as PackBytes>::BYTES,
+            "At most {} bytes can be packed into a field element.",
+            <Self as PackBytes>::BYTES,
+        );
+        Self(F::from_le_bytes_mod_order(bytes))
+    }
+
+    #[inline]
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.0.into_repr().to_bytes_le()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> UnpackBytes for Fp<F>
+where
+    F: PrimeField,
+{
+    type Byte = u8;
+
+    const BYTES: usize = <Self as PackBytes>::BYTES;
+
+    #[inline]
+    fn unpack_bytes(&self, len: usize, _: &mut ()) -> Vec<u8> {
+        assert!(
+            len <= <Self as UnpackBytes>::BYTES,
+            "At most {} bytes can be unpacked from a field element.",
+            <Self as UnpackBytes>::BYTES,
+        );
+        let mut bytes = self.to_le_bytes();
+        assert!(
+            bytes.iter().skip(len).all(|byte| *byte == 0),
+            "The field element does not fit into {len} bytes."
+        );
+        bytes.resize(len, 0);
+        bytes
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> CanonicalBytes for Fp<F>
+where
+    F: PrimeField,
+{
+    const BYTES: usize = byte_count(<F::Params as FpParameters>::MODULUS_BITS) as usize;
+
+    #[inline]
+    fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.0.into_repr().to_bytes_le();
+        bytes.truncate(<Self as CanonicalBytes>::BYTES);
+        bytes
+    }
+
+    #[inline]
+    fn from_canonical_bytes(bytes: &[u8]) -> Result<Self, ConversionError> {
+        let expected = <Self as CanonicalBytes>::BYTES;
+        if bytes.len() > expected {
+            return Err(ConversionError::InvalidLength {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        let element = Self(F::from_le_bytes_mod_order(bytes));
+        let canonical = element.to_canonical_bytes();
+        if canonical[..bytes.len()] != *bytes || canonical[bytes.len()..].iter().any(|b| *b != 0) {
+            return Err(ConversionError::NonCanonical);
+        }
+        Ok(element)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> From<&Fp<F>> for LeBytes
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn from(element: &Fp<F>) -> Self {
+        Self::from_element(element)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> From<Fp<F>> for LeBytes
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn from(element: Fp<F>) -> Self {
+        Self::from_element(&element)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> TryFrom<&LeBytes> for Fp<F>
+where
+    F: PrimeField,
+{
+    type Error = ConversionError;
+
+    #[inline]
+    fn try_from(bytes: &LeBytes) -> Result<Self, Self::Error> {
+        bytes.to_element()
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> TryFrom<LeBytes> for Fp<F>
+where
+    F: PrimeField,
+{
+    type Error = ConversionError;
+
+    #[inline]
+    fn try_from(bytes: LeBytes) -> Result<Self, Self::Error> {
+        bytes.to_element()
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<F> Decode for Fp<F>
 where
@@ -118,6 +282,11 @@ where
         let _ = self.0.serialize(&mut writer);
         writer.finish().map(move |_| ())
     }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.0.serialized_size()
+    }
 }
 
 impl<F> eclair::cmp::PartialEq<Self> for Fp<F>
@@ -130,6 +299,31 @@ where
     }
 }
 
+impl<F> eclair::cmp::PartialOrd<Self> for Fp<F>
+where
+    F: Field,
+{
+    #[inline]
+    fn lt(&self, rhs: &Self, _: &mut ()) -> bool {
+        PartialOrd::lt(self, rhs)
+    }
+
+    #[inline]
+    fn gt(&self, rhs: &Self, _: &mut ()) -> bool {
+        PartialOrd::gt(self, rhs)
+    }
+
+    #[inline]
+    fn le(&self, rhs: &Self, _: &mut ()) -> bool {
+        PartialOrd::le(self, rhs)
+    }
+
+    #[inline]
+    fn ge(&self, rhs: &Self, _: &mut ()) -> bool {
+        PartialOrd::ge(self, rhs)
+    }
+}
+
 impl<F, const BITS: usize> BitDecomposition<BITS> for Fp<F>
 where
     F: PrimeField,
@@ -147,6 +341,72 @@ where
     }
 }
 
+impl<F, const BYTES: usize> ToBytes<BYTES> for Fp<F>
+where
+    F: PrimeField,
+{
+    type Byte = u8;
+
+    #[inline]
+    fn to_bytes_le(&self, _: &mut ()) -> [u8; BYTES] {
+        assert_eq!(
+            BYTES,
+            (F::Params::MODULUS_BITS as usize + 7) / 8,
+            "BYTES must be equal to the number of bytes of MODULUS BITS"
+        );
+        let mut bytes = self.0.into_repr().to_bytes_le();
+        bytes.resize(BYTES, 0);
+        into_array_unchecked(bytes)
+    }
+
+    #[inline]
+    fn to_bytes_le_unchecked(&self, compiler: &mut ()) -> [u8; BYTES] {
+        self.to_bytes_le(compiler)
+    }
+}
+
+impl<F, const BITS: usize> FromBits<BITS> for Fp<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn from_bits_le(bits: [bool; BITS], compiler: &mut ()) -> Self {
+        let value = Self::from_bits_le_unchecked(bits, compiler);
+        let mut canonical_bits = value.0.into_repr().to_bits_le();
+        canonical_bits.resize(canonical_bits.len().max(BITS), false);
+        let (low, high) = canonical_bits.split_at(BITS);
+        assert!(
+            low == bits && high.iter().all(|bit| !bit),
+            "The bits must be the canonical representation of a field element."
+        );
+        value
+    }
+
+    #[inline]
+    fn from_bits_le_unchecked(bits: [bool; BITS], _: &mut ()) -> Self {
+        let bytes = bits
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0u8, |byte, bit| (byte << 1) | *bit as u8)
+            })
+            .collect::<Vec<_>>();
+        Self(F::from_le_bytes_mod_order(&bytes))
+    }
+}
+
+impl<F> EncodeLength for Fp<F>
+where
+    F: Field,
+{
+    #[inline]
+    fn encode_length(length: u64, _: &mut ()) -> Self {
+        Self(F::from(length))
+    }
+}
+
 impl<F> eclair::num::Zero for Fp<F>
 where
     F: Field,
@@ -181,6 +441,42 @@ where
     }
 }
 
+impl<F> eclair::ops::Inverse for Fp<F>
+where
+    F: Field,
+{
+    type Verification = bool;
+
+    #[inline]
+    fn inverse(&self, _: &mut ()) -> Self {
+        Self(
+            self.0
+                .inverse()
+                .expect("Zero has no multiplicative inverse."),
+        )
+    }
+
+    #[inline]
+    fn checked_inverse(&self, _: &mut ()) -> (Self, Self::Verification) {
+        match self.0.inverse() {
+            Some(inverse) => (Self(inverse), true),
+            _ => (Self(F::zero()), false),
+        }
+    }
+}
+
+impl<F> core::ops::Neg for Fp<F>
+where
+    F: Field,
+{
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
 impl<F> ConditionalSelect for Fp<F>
 where
     F: Field,
@@ -255,7 +551,7 @@ pub fn field_element_as_bytes<F>(element: &F) -> Vec<u8>
 where
     F: Field,
 {
-    Fp(*element).to_vec()
+    Fp(*element).to_vec_exact()
 }
 
 /// Uses `serializer` to serialize `element`.
@@ -268,3 +564,39 @@ where
 {
     serializer.serialize_bytes(&field_element_as_bytes(element))
 }
+
+#[cfg(all(test, feature = "bls12-381", feature = "bn254"))]
+mod test {
+    use super::*;
+    use openzl_crypto::field::convert::{
+        convert,
+        test::{assert_conversion_round_trip, assert_rejects_invalid, assert_round_trip},
+    };
+    use openzl_util::rand::{Rand, TestRng};
+
+    /// Tests that BN254 and BLS12-381 scalars round-trip through their canonical encodings and that
+    /// every BN254 scalar converts into a BLS12-381 scalar and back.
+    #[test]
+    fn canonical_bytes_round_trip() {
+        let mut rng = TestRng::new();
+        for _ in 0..32 {
+            let element = rng.gen::<_, Fp<bn254::Fr>>();
+            assert_round_trip(&element);
+            assert_round_trip(&rng.gen::<_, Fp<bls12_381::Fr>>());
+            assert_conversion_round_trip::<_, Fp<bls12_381::Fr>>(&element);
+            assert_eq!(Fp::try_from(LeBytes::from(element)), Ok(element));
+        }
+        assert_rejects_invalid::<Fp<bn254::Fr>>();
+        assert_rejects_invalid::<Fp<bls12_381::Fr>>();
+    }
+
+    /// Tests that BLS12-381 scalars above the BN254 modulus do not convert into BN254 scalars.
+    #[test]
+    fn conversion_rejects_larger_integers() {
+        let element = Fp(-bls12_381::Fr::from(1u64));
+        assert_eq!(
+            convert::<_, Fp<bn254::Fr>>(&element),
+            Err(ConversionError::NonCanonical)
+        );
+    }
+}
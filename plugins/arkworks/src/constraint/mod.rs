@@ -2,9 +2,10 @@
 
 use crate::{
     constraint::fp::Fp,
-    ff::{BigInteger, FpParameters, PrimeField},
+    ff::{BigInteger, Field, FpParameters, PrimeField},
     r1cs_std::{
-        alloc::AllocVar, eq::EqGadget, fields::FieldVar, select::CondSelectGadget, ToBitsGadget,
+        alloc::AllocVar, eq::EqGadget, fields::FieldVar, select::CondSelectGadget, R1CSVar,
+        ToBitsGadget,
     },
     relations::{
         ns,
@@ -14,19 +15,27 @@ use crate::{
         },
     },
 };
-use core::marker::PhantomData;
+use core::{cmp::Ordering, marker::PhantomData};
 use eclair::{
     alloc::{
         mode::{self, Public, Secret},
         Constant, Variable,
     },
-    bool::{Assert, BitDecomposition, ConditionalSelect, ConditionalSwap},
-    num::{AssertWithinBitRange, Zero},
-    ops::Add,
+    audit::{Allocation, Audit},
+    bool::{Assert, AssertLabeled, BitDecomposition, ConditionalSelect, ConditionalSwap},
+    num::{AssertWithinBitRange, FromBits, One, Zero},
+    ops::{Add, BitAnd, BitOr, Div, Inverse, Mul, Not},
     Has,
 };
 use num_integer::Integer;
-use openzl_crypto::constraint::measure::{Count, Measure};
+use openzl_crypto::{
+    constraint::{
+        measure::{Count, Measure, Size},
+        Satisfied,
+    },
+    hash::incremental::EncodeLength,
+    NonNative,
+};
 use openzl_util::derivative;
 
 pub use crate::{
@@ -34,9 +43,18 @@ pub use crate::{
     relations::r1cs::SynthesisError,
 };
 
+#[cfg(feature = "alloc")]
+use {
+    crate::r1cs_std::uint8::UInt8,
+    alloc::{boxed::Box, rc::Rc, vec::Vec},
+    eclair::{audit::AuditLog, num::ToBytes},
+    openzl_crypto::constraint::packing::{PackBytes, UnpackBytes},
+    openzl_util::into_array_unchecked,
+};
+
 #[cfg(feature = "algebra")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "algebra")))]
-use {crate::algebra::modulus_is_smaller, crate::r1cs_std::R1CSVar, eclair::ops::Rem};
+use {crate::algebra::modulus_is_smaller, eclair::ops::Rem};
 
 pub mod fp;
 
@@ -61,10 +79,62 @@ pub fn full<T>(value: T) -> impl FnOnce() -> SynthesisResult<T> {
     move || Ok(value)
 }
 
+/// Simplification Pass
+///
+/// A pass transforms the constraints of an [`R1CS`] constraint system after synthesis, for
+/// example by removing redundant constraints or merging variables. Passes are registered with
+/// `R1CSBuilder::pass` and run in registration order by [`R1CS::finalize`].
+pub trait Pass<F>
+where
+    F: PrimeField,
+{
+    /// Returns the name of this pass, which labels its [`PassMeasurement`].
+    fn name(&self) -> &str;
+
+    /// Runs this pass over `constraint_system`.
+    fn run(&self, constraint_system: &ConstraintSystemRef<F>);
+}
+
+/// Pass Measurement
+///
+/// The [`Size`] of a constraint system before and after running a [`Pass`], which is given to
+/// every measurement hook registered with `R1CSBuilder::measure_with`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PassMeasurement<'n> {
+    /// Name of the Pass
+    pub name: &'n str,
+
+    /// Size Before the Pass
+    pub before: Size,
+
+    /// Size After the Pass
+    pub after: Size,
+}
+
+/// Pass Pipeline
+#[cfg(feature = "alloc")]
+struct Pipeline<F>
+where
+    F: PrimeField,
+{
+    /// Passes
+    passes: Vec<Box<dyn Pass<F>>>,
+
+    /// Measurement Hooks
+    hooks: Vec<Box<dyn Fn(&PassMeasurement)>>,
+}
+
 /// Arkworks Rank-1 Constraint System
 #[derive(derivative::Derivative)]
 #[derivative(Clone, Debug)]
-pub struct R1CS<F>(ConstraintSystemRef<F>)
+pub struct R1CS<F>(
+    pub(crate) ConstraintSystemRef<F>,
+    bool,
+    #[cfg(feature = "alloc")] Option<AuditLog>,
+    #[cfg(feature = "alloc")]
+    #[derivative(Debug = "ignore")]
+    Option<Rc<Pipeline<F>>>,
+)
 where
     F: PrimeField;
 
@@ -73,29 +143,95 @@ where
     F: PrimeField,
 {
     /// Builds a new [`R1CS`] constraint system from `constraint_system` without checking its
-    /// optimization goal or synthesis mode.
+    /// optimization goal or synthesis mode. Its linear combinations are inlined or outlined
+    /// according to its optimization goal.
     #[inline]
     pub fn new_unchecked(constraint_system: ConstraintSystemRef<F>) -> Self {
-        Self(constraint_system)
+        Self(
+            constraint_system,
+            true,
+            #[cfg(feature = "alloc")]
+            None,
+            #[cfg(feature = "alloc")]
+            None,
+        )
+    }
+
+    /// Returns a new [`R1CSBuilder`] with the default configuration.
+    #[inline]
+    pub fn builder() -> R1CSBuilder<F> {
+        R1CSBuilder::new()
     }
 
     /// Constructs a new constraint system which is ready for unknown variables.
+    ///
+    /// Use [`R1CSBuilder`] to choose a different optimization goal.
     #[inline]
     pub fn for_contexts() -> Self {
-        // FIXME: This might not be the right setup for all proof systems.
-        let constraint_system = ConstraintSystem::new_ref();
-        constraint_system.set_optimization_goal(OptimizationGoal::Constraints);
-        constraint_system.set_mode(SynthesisMode::Setup);
-        Self::new_unchecked(constraint_system)
+        Self::builder().mode(SynthesisMode::Setup).build()
     }
 
     /// Constructs a new constraint system which is ready for known variables.
+    ///
+    /// Use [`R1CSBuilder`] to choose a different optimization goal.
     #[inline]
     pub fn for_proofs() -> Self {
-        // FIXME: This might not be the right setup for all proof systems.
-        let constraint_system = ConstraintSystem::new_ref();
-        constraint_system.set_optimization_goal(OptimizationGoal::Constraints);
-        Self::new_unchecked(constraint_system)
+        Self::builder().build()
+    }
+
+    /// Constructs a new constraint system which is ready for known variables but only records the
+    /// variable assignment, skipping the constraint matrices.
+    ///
+    /// # Warning
+    ///
+    /// Since no constraints are stored, [`is_satisfied`](Self::is_satisfied) trivially returns
+    /// `true` for this constraint system. It is meant to be paired with constraint matrices that
+    /// were cached at setup time.
+    #[inline]
+    pub fn for_witnesses() -> Self {
+        Self::builder()
+            .mode(SynthesisMode::Prove {
+                construct_matrices: false,
+            })
+            .build()
+    }
+
+    /// Runs the simplification passes of `self`, reporting their measurements to the hooks
+    /// registered with `R1CSBuilder::measure_with`. The passes are removed from `self` so that
+    /// they only run once.
+    #[inline]
+    fn run_passes(&mut self) {
+        #[cfg(feature = "alloc")]
+        if let Some(pipeline) = self.3.take() {
+            for pass in &pipeline.passes {
+                let before = self.measure();
+                pass.run(&self.0);
+                let measurement = PassMeasurement {
+                    name: pass.name(),
+                    before,
+                    after: self.measure(),
+                };
+                for hook in &pipeline.hooks {
+                    hook(&measurement);
+                }
+            }
+        }
+    }
+
+    /// Finalizes `self` after synthesis by running its simplification passes and then inlining
+    /// or outlining its linear combinations.
+    ///
+    /// Linear combinations are only outlined if outlining was enabled with
+    /// [`R1CSBuilder::outline_linear_combinations`] and the optimization goal is
+    /// [`OptimizationGoal::Weight`]. Otherwise, they are all inlined.
+    #[inline]
+    pub fn finalize(&mut self) {
+        self.run_passes();
+        if self.1 {
+            self.0.finalize();
+        } else {
+            self.0.inline_all_lcs();
+        }
     }
 
     /// Check if all constraints are satisfied.
@@ -105,8 +241,191 @@ where
             .is_satisfied()
             .expect("Checking circuit satisfaction is not allowed to fail.")
     }
+
+    /// Enables allocation auditing for `self`, recording every subsequent allocation into an
+    /// [`AuditLog`].
+    ///
+    /// Auditing the constraint systems which build proofs and verifying keys and comparing their
+    /// logs with [`AuditLog::diff`] finds public inputs which are allocated out of order.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn with_audit(mut self) -> Self {
+        self.2 = Some(AuditLog::new());
+        self
+    }
+
+    /// Returns the allocation audit log of `self` if auditing is enabled.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.2.as_ref()
+    }
+
+    /// Takes the allocation audit log out of `self`, disabling auditing.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn take_audit_log(&mut self) -> Option<AuditLog> {
+        self.2.take()
+    }
 }
 
+/// Arkworks Rank-1 Constraint System Builder
+///
+/// By default, the constraint system is built for proofs with the
+/// [`OptimizationGoal::Constraints`] goal, without outlining linear combinations, and without any
+/// simplification passes.
+pub struct R1CSBuilder<F>
+where
+    F: PrimeField,
+{
+    /// Optimization Goal
+    optimization_goal: OptimizationGoal,
+
+    /// Synthesis Mode
+    mode: SynthesisMode,
+
+    /// Linear Combination Outlining Flag
+    outline_linear_combinations: bool,
+
+    /// Simplification Passes
+    #[cfg(feature = "alloc")]
+    passes: Vec<Box<dyn Pass<F>>>,
+
+    /// Measurement Hooks
+    #[cfg(feature = "alloc")]
+    hooks: Vec<Box<dyn Fn(&PassMeasurement)>>,
+
+    /// Type Parameter Marker
+    __: PhantomData<F>,
+}
+
+impl<F> R1CSBuilder<F>
+where
+    F: PrimeField,
+{
+    /// Builds a new [`R1CSBuilder`] with the default configuration.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            optimization_goal: OptimizationGoal::Constraints,
+            mode: SynthesisMode::Prove {
+                construct_matrices: true,
+            },
+            outline_linear_combinations: false,
+            #[cfg(feature = "alloc")]
+            passes: Vec::new(),
+            #[cfg(feature = "alloc")]
+            hooks: Vec::new(),
+            __: PhantomData,
+        }
+    }
+
+    /// Sets the optimization goal which gadgets use to choose between fewer constraints and
+    /// lower constraint weight.
+    #[inline]
+    pub fn optimization_goal(mut self, optimization_goal: OptimizationGoal) -> Self {
+        self.optimization_goal = optimization_goal;
+        self
+    }
+
+    /// Sets the synthesis mode of the constraint system.
+    #[inline]
+    pub fn mode(mut self, mode: SynthesisMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Enables or disables outlining of linear combinations in [`R1CS::finalize`]. Outlining only
+    /// takes effect with the [`OptimizationGoal::Weight`] goal.
+    #[inline]
+    pub fn outline_linear_combinations(mut self, enabled: bool) -> Self {
+        self.outline_linear_combinations = enabled;
+        self
+    }
+
+    /// Registers `pass` to run after every previously registered pass.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn pass<P>(mut self, pass: P) -> Self
+    where
+        P: 'static + Pass<F>,
+    {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Registers `hook` to receive the [`PassMeasurement`] of every pass.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+    #[inline]
+    pub fn measure_with<H>(mut self, hook: H) -> Self
+    where
+        H: 'static + Fn(&PassMeasurement),
+    {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Builds the [`R1CS`] constraint system.
+    #[inline]
+    pub fn build(self) -> R1CS<F> {
+        let constraint_system = ConstraintSystem::new_ref();
+        constraint_system.set_optimization_goal(self.optimization_goal);
+        constraint_system.set_mode(self.mode);
+        let mut r1cs = R1CS::new_unchecked(constraint_system);
+        r1cs.1 = self.outline_linear_combinations;
+        #[cfg(feature = "alloc")]
+        if !self.passes.is_empty() {
+            r1cs.3 = Some(Rc::new(Pipeline {
+                passes: self.passes,
+                hooks: self.hooks,
+            }));
+        }
+        r1cs
+    }
+}
+
+impl<F> Default for R1CSBuilder<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> Audit for R1CS<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn record(&mut self, allocation: Allocation) {
+        #[cfg(feature = "alloc")]
+        if let Some(log) = &mut self.2 {
+            log.record(allocation);
+        }
+        #[cfg(not(feature = "alloc"))]
+        let _ = allocation;
+    }
+}
+
+impl<F> Satisfied for R1CS<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn is_satisfied(&self) -> bool {
+        self.is_satisfied()
+    }
+}
+
+impl<F> NonNative for R1CS<F> where F: PrimeField {}
+
 impl<F> Has<bool> for R1CS<F>
 where
     F: PrimeField,
@@ -114,6 +433,20 @@ where
     type Type = Boolean<F>;
 }
 
+impl<F> Has<u8> for R1CS<F>
+where
+    F: PrimeField,
+{
+    type Type = FpVar<F>;
+}
+
+impl<F> Has<usize> for R1CS<F>
+where
+    F: PrimeField,
+{
+    type Type = FpVar<F>;
+}
+
 impl<F> Assert for R1CS<F>
 where
     F: PrimeField,
@@ -125,6 +458,8 @@ where
     }
 }
 
+impl<F> AssertLabeled for R1CS<F> where F: PrimeField {}
+
 impl<F, const BITS: usize> AssertWithinBitRange<FpVar<F>, BITS> for R1CS<F>
 where
     F: PrimeField,
@@ -144,6 +479,34 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F> UnpackBytes<R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    type Byte = UInt8<F>;
+
+    const BYTES: usize = <Fp<F> as PackBytes>::BYTES;
+
+    #[inline]
+    fn unpack_bytes(&self, len: usize, compiler: &mut R1CS<F>) -> Vec<UInt8<F>> {
+        let _ = compiler;
+        assert!(
+            len <= Self::BYTES,
+            "At most {} bytes can be unpacked from a field element.",
+            Self::BYTES,
+        );
+        let bits =
+            ToBitsGadget::to_bits_le(self).expect("Bit decomposition is not allowed to fail.");
+        for bit in &bits[8 * len..] {
+            bit.enforce_equal(&Boolean::FALSE)
+                .expect("Enforcing equality is not allowed to fail.");
+        }
+        bits[..8 * len].chunks(8).map(UInt8::from_bits_le).collect()
+    }
+}
+
 impl<F> Count<mode::Constant> for R1CS<F> where F: PrimeField {}
 
 impl<F> Count<Public> for R1CS<F>
@@ -183,7 +546,8 @@ where
     /// Generates constraints for `self` by copying them into `cs`. This method is necessary to hook
     /// into the proof system traits defined in `arkworks`.
     #[inline]
-    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> SynthesisResult {
+    fn generate_constraints(mut self, cs: ConstraintSystemRef<F>) -> SynthesisResult {
+        self.run_passes();
         let precomputed_cs = self
             .0
             .into_inner()
@@ -215,6 +579,7 @@ where
 
     #[inline]
     fn new_constant(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, mode::Constant>();
         AllocVar::new_constant(ns!(compiler.0, "boolean constant"), this)
             .expect("Variable allocation is not allowed to fail.")
     }
@@ -228,12 +593,14 @@ where
 
     #[inline]
     fn new_known(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Public>();
         Self::new_input(ns!(compiler.0, "boolean public input"), full(this))
             .expect("Variable allocation is not allowed to fail.")
     }
 
     #[inline]
     fn new_unknown(compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Public>();
         Self::new_input(ns!(compiler.0, "boolean public input"), empty::<bool>)
             .expect("Variable allocation is not allowed to fail.")
     }
@@ -247,12 +614,14 @@ where
 
     #[inline]
     fn new_known(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
         Self::new_witness(ns!(compiler.0, "boolean secret witness"), full(this))
             .expect("Variable allocation is not allowed to fail.")
     }
 
     #[inline]
     fn new_unknown(compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
         Self::new_witness(ns!(compiler.0, "boolean secret witness"), empty::<bool>)
             .expect("Variable allocation is not allowed to fail.")
     }
@@ -270,6 +639,45 @@ where
     }
 }
 
+impl<F> BitAnd<Self, R1CS<F>> for Boolean<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self, compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        self.and(&rhs).expect("Conjunction is not allowed to fail.")
+    }
+}
+
+impl<F> BitOr<Self, R1CS<F>> for Boolean<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self, compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        self.or(&rhs).expect("Disjunction is not allowed to fail.")
+    }
+}
+
+impl<F> Not<R1CS<F>> for Boolean<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn not(self, compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        Boolean::not(&self)
+    }
+}
+
 impl<F, const BITS: usize> BitDecomposition<BITS, R1CS<F>> for FpVar<F>
 where
     F: PrimeField,
@@ -286,6 +694,91 @@ where
     }
 }
 
+/// Returns the `BYTES`-many little-endian bytes of the little-endian `bits` of a field element,
+/// padded with zeroes.
+#[cfg(feature = "alloc")]
+#[inline]
+fn bits_to_bytes_le<F, const BYTES: usize>(mut bits: Vec<Boolean<F>>) -> [UInt8<F>; BYTES]
+where
+    F: PrimeField,
+{
+    assert_eq!(
+        BYTES,
+        (F::Params::MODULUS_BITS as usize + 7) / 8,
+        "BYTES must be equal to the number of bytes of MODULUS BITS"
+    );
+    bits.resize(8 * BYTES, Boolean::FALSE);
+    into_array_unchecked(bits.chunks(8).map(UInt8::from_bits_le).collect::<Vec<_>>())
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<F, const BYTES: usize> ToBytes<BYTES, R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    type Byte = UInt8<F>;
+
+    #[inline]
+    fn to_bytes_le(&self, compiler: &mut R1CS<F>) -> [UInt8<F>; BYTES] {
+        let _ = compiler;
+        bits_to_bytes_le(
+            ToBitsGadget::to_bits_le(self).expect("Bit decomposition is not allowed to fail."),
+        )
+    }
+
+    #[inline]
+    fn to_bytes_le_unchecked(&self, compiler: &mut R1CS<F>) -> [UInt8<F>; BYTES] {
+        let _ = compiler;
+        bits_to_bytes_le(
+            self.to_non_unique_bits_le()
+                .expect("Bit decomposition is not allowed to fail."),
+        )
+    }
+}
+
+/// Recomposes the little-endian `bits` into a field element, reducing it modulo the modulus of
+/// `F`. The recomposition is a linear combination, so it does not add any constraints.
+#[inline]
+fn recompose_le<F>(bits: &[Boolean<F>]) -> FpVar<F>
+where
+    F: PrimeField,
+{
+    let mut power = F::one();
+    let mut result = FpVar::Constant(F::zero());
+    for bit in bits {
+        result += FpVar::from(bit.clone()) * power;
+        power.double_in_place();
+    }
+    result
+}
+
+impl<F, const BITS: usize> FromBits<BITS, R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn from_bits_le(bits: [Boolean<F>; BITS], compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        let modulus_bits = F::Params::MODULUS_BITS as usize;
+        if BITS >= modulus_bits {
+            for bit in &bits[modulus_bits..] {
+                bit.enforce_equal(&Boolean::FALSE)
+                    .expect("Enforcing equality is not allowed to fail.");
+            }
+            Boolean::enforce_in_field_le(&bits[..modulus_bits])
+                .expect("Enforcing the field range is not allowed to fail.");
+        }
+        recompose_le(&bits)
+    }
+
+    #[inline]
+    fn from_bits_le_unchecked(bits: [Boolean<F>; BITS], compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        recompose_le(&bits)
+    }
+}
+
 impl<F> Constant<R1CS<F>> for FpVar<F>
 where
     F: PrimeField,
@@ -294,11 +787,22 @@ where
 
     #[inline]
     fn new_constant(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, mode::Constant>();
         AllocVar::new_constant(ns!(compiler.0, "field constant"), this.0)
             .expect("Variable allocation is not allowed to fail.")
     }
 }
 
+impl<F> EncodeLength<R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn encode_length(length: u64, compiler: &mut R1CS<F>) -> Self {
+        Constant::new_constant(&Fp(F::from(length)), compiler)
+    }
+}
+
 impl<F> Variable<Public, R1CS<F>> for FpVar<F>
 where
     F: PrimeField,
@@ -307,12 +811,14 @@ where
 
     #[inline]
     fn new_known(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Public>();
         Self::new_input(ns!(compiler.0, "field public input"), full(this.0))
             .expect("Variable allocation is not allowed to fail.")
     }
 
     #[inline]
     fn new_unknown(compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Public>();
         Self::new_input(ns!(compiler.0, "field public input"), empty::<F>)
             .expect("Variable allocation is not allowed to fail.")
     }
@@ -326,12 +832,14 @@ where
 
     #[inline]
     fn new_known(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
         Self::new_witness(ns!(compiler.0, "field secret witness"), full(this.0))
             .expect("Variable allocation is not allowed to fail.")
     }
 
     #[inline]
     fn new_unknown(compiler: &mut R1CS<F>) -> Self {
+        compiler.record_allocation::<Self, Secret>();
         Self::new_witness(ns!(compiler.0, "field secret witness"), empty::<F>)
             .expect("Variable allocation is not allowed to fail.")
     }
@@ -349,6 +857,51 @@ where
     }
 }
 
+/// Compares `lhs` and `rhs` as integers with respect to `ordering`, also accepting equal values
+/// when `or_equal` is `true`.
+///
+/// # Limitations
+///
+/// The comparison bit-decomposes both operands and constrains them to be at most `(p - 1) / 2`,
+/// where `p` is the modulus of `F`, so the constraint system is not satisfied for larger values.
+#[inline]
+fn compare<F>(lhs: &FpVar<F>, rhs: &FpVar<F>, ordering: Ordering, or_equal: bool) -> Boolean<F>
+where
+    F: PrimeField,
+{
+    lhs.is_cmp(rhs, ordering, or_equal)
+        .expect("Comparing two values is not allowed to fail.")
+}
+
+impl<F> eclair::cmp::PartialOrd<Self, R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn lt(&self, rhs: &Self, compiler: &mut R1CS<F>) -> Boolean<F> {
+        let _ = compiler;
+        compare(self, rhs, Ordering::Less, false)
+    }
+
+    #[inline]
+    fn gt(&self, rhs: &Self, compiler: &mut R1CS<F>) -> Boolean<F> {
+        let _ = compiler;
+        compare(self, rhs, Ordering::Greater, false)
+    }
+
+    #[inline]
+    fn le(&self, rhs: &Self, compiler: &mut R1CS<F>) -> Boolean<F> {
+        let _ = compiler;
+        compare(self, rhs, Ordering::Less, true)
+    }
+
+    #[inline]
+    fn ge(&self, rhs: &Self, compiler: &mut R1CS<F>) -> Boolean<F> {
+        let _ = compiler;
+        compare(self, rhs, Ordering::Greater, true)
+    }
+}
+
 /// Conditionally select from `lhs` and `rhs` depending on the value of `bit`.
 #[inline]
 fn conditionally_select<F>(bit: &Boolean<F>, lhs: &FpVar<F>, rhs: &FpVar<F>) -> FpVar<F>
@@ -402,6 +955,19 @@ where
     }
 }
 
+impl<F> Mul<Self, R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self, compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        self * rhs
+    }
+}
+
 impl<F> Zero<R1CS<F>> for FpVar<F>
 where
     F: PrimeField,
@@ -421,6 +987,69 @@ where
     }
 }
 
+impl<F> One<R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    type Verification = Boolean<F>;
+
+    #[inline]
+    fn one(compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        FieldVar::one()
+    }
+
+    #[inline]
+    fn is_one(&self, compiler: &mut R1CS<F>) -> Self::Verification {
+        let _ = compiler;
+        FieldVar::is_one(self).expect("Comparison with one is not allowed to fail.")
+    }
+}
+
+impl<F> Div<Self, R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self, compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        self.mul_by_inverse(&rhs)
+            .expect("Division is not allowed to fail.")
+    }
+}
+
+impl<F> Inverse<R1CS<F>> for FpVar<F>
+where
+    F: PrimeField,
+{
+    type Verification = Boolean<F>;
+
+    #[inline]
+    fn inverse(&self, compiler: &mut R1CS<F>) -> Self {
+        let _ = compiler;
+        FieldVar::inverse(self).expect("Inversion is not allowed to fail.")
+    }
+
+    #[inline]
+    fn checked_inverse(&self, compiler: &mut R1CS<F>) -> (Self, Self::Verification) {
+        let is_zero = Zero::is_zero(self, compiler);
+        let inverse = FpVar::new_witness(self.cs(), || {
+            Ok(self.value()?.inverse().unwrap_or_else(F::zero))
+        })
+        .expect("Allocating a witness is not allowed to fail.");
+        let is_invertible = !is_zero.clone();
+        (self * &inverse)
+            .enforce_equal(&is_invertible.clone().into())
+            .expect("Enforcing equality is not allowed to fail.");
+        (&inverse * FpVar::from(is_zero))
+            .enforce_equal(&FieldVar::zero())
+            .expect("Enforcing equality is not allowed to fail.");
+        (inverse, is_invertible)
+    }
+}
+
 /// Prime Modulus
 #[derive(derivative::Derivative)]
 #[derivative(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
@@ -518,11 +1147,18 @@ mod tests {
         constraint::fp::Fp,
         ff::BigInteger,
         r1cs_std::R1CSVar,
-        rand::{OsRng, Rand, RngCore},
+        rand::{Rand, RngCore, TestRng},
     };
     use alloc::vec::Vec;
     use core::iter::repeat_with;
-    use eclair::alloc::{mode::Secret, Allocate};
+    use eclair::{
+        alloc::{
+            mode::{Derived, Public, Secret},
+            Allocate,
+        },
+        bytes::Bytes,
+        pattern::{Dfa, Mode, PatternError},
+    };
 
     /// Checks if `assert_within_range` passes when `should_pass` is `true` and fails when
     /// `should_pass` is `false`.
@@ -589,7 +1225,7 @@ mod tests {
     /// Tests if `assert_within_range` works correctly for U8, U16, U32, U64, and U128.
     #[test]
     fn assert_within_range_is_correct() {
-        let mut rng = OsRng;
+        let mut rng = TestRng::new();
         test_assert_within_range::<_, Fr, 8, 32>(&mut rng);
         test_assert_within_range::<_, Fr, 16, 32>(&mut rng);
         test_assert_within_range::<_, Fr, 32, 32>(&mut rng);
@@ -604,7 +1240,7 @@ mod tests {
             let bit_decomposition_le = compare_bit_decomposition(number);
             println!("Number: {number}\nDecomposition: {bit_decomposition_le:?}");
         }
-        let mut rng = OsRng;
+        let mut rng = TestRng::new();
         let random_number = rng.gen();
         let bit_decomposition_le = compare_bit_decomposition(random_number);
         println!("Number: {random_number}\nDecomposition: {bit_decomposition_le:?}");
@@ -633,4 +1269,834 @@ mod tests {
         );
         bit_decomposition_le
     }
+
+    /// Simplification pass which appends a trivially satisfied constraint.
+    #[cfg(feature = "alloc")]
+    struct AppendConstraint;
+
+    #[cfg(feature = "alloc")]
+    impl Pass<Fr> for AppendConstraint {
+        #[inline]
+        fn name(&self) -> &str {
+            "append-constraint"
+        }
+
+        #[inline]
+        fn run(&self, constraint_system: &ConstraintSystemRef<Fr>) {
+            constraint_system
+                .enforce_constraint(
+                    crate::relations::lc!(),
+                    crate::relations::lc!(),
+                    crate::relations::lc!(),
+                )
+                .expect("Enforcing a constraint is not allowed to fail.");
+        }
+    }
+
+    /// Checks that the passes registered with [`R1CSBuilder`] run once, in order, and that every
+    /// measurement hook observes their effect on the constraint system.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn builder_runs_passes_with_measurements() {
+        use alloc::{rc::Rc, string::String};
+        use core::cell::RefCell;
+        let measurements = Rc::new(RefCell::new(Vec::<(String, usize, usize)>::new()));
+        let hook_measurements = measurements.clone();
+        let mut cs = R1CS::<Fr>::builder()
+            .optimization_goal(OptimizationGoal::Weight)
+            .outline_linear_combinations(true)
+            .pass(AppendConstraint)
+            .pass(AppendConstraint)
+            .measure_with(move |measurement| {
+                hook_measurements.borrow_mut().push((
+                    measurement.name.into(),
+                    measurement.before.constraint_count,
+                    measurement.after.constraint_count,
+                ))
+            })
+            .build();
+        let value = Fp(Fr::from(7u64)).as_known::<Secret, FpVar<Fr>>(&mut cs);
+        value
+            .enforce_equal(&value)
+            .expect("Enforcing equality is not allowed to fail.");
+        let initial = cs.constraint_count();
+        cs.finalize();
+        cs.finalize();
+        assert_eq!(
+            *measurements.borrow(),
+            [
+                (String::from("append-constraint"), initial, initial + 1),
+                (String::from("append-constraint"), initial + 1, initial + 2),
+            ],
+            "Every pass should run exactly once and be measured by the hook."
+        );
+        assert!(
+            cs.is_satisfied(),
+            "The passes must preserve satisfiability."
+        );
+    }
+
+    /// Checks that byte strings packed natively are unpacked to the same bytes in-circuit.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn packed_bytes_unpack_in_circuit() {
+        use openzl_crypto::constraint::packing::{pack, unpack, unpack_with};
+        let mut rng = TestRng::new();
+        for len in [0, 1, 30, 31, 32, 62, 100] {
+            let bytes = repeat_with(|| rng.gen::<_, u8>())
+                .take(len)
+                .collect::<Vec<_>>();
+            let packed = pack::<Fp<Fr>>(&bytes);
+            assert_eq!(
+                Some(&bytes),
+                unpack(&packed, len).as_ref(),
+                "Native unpacking must invert packing."
+            );
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let variables = packed
+                .iter()
+                .map(|element| element.as_known::<Public, FpVar<Fr>>(&mut cs))
+                .collect::<Vec<_>>();
+            let unpacked = unpack_with(&variables, len, &mut cs)
+                .into_iter()
+                .map(|byte| byte.value().expect("Unable to get the byte value."))
+                .collect::<Vec<_>>();
+            assert_eq!(bytes, unpacked, "In-circuit unpacking must invert packing.");
+            assert!(
+                cs.is_satisfied(),
+                "The unpacking constraints must be satisfied."
+            );
+        }
+    }
+
+    /// Checks that the in-circuit digest of committed inputs matches the native digest which is
+    /// used as the public input.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn committed_inputs_match_native_digest() {
+        use crate::poseidon::{Spec, TwoPowerMinusOneDomainTag};
+        use openzl_crypto::{
+            constraint::committed_inputs::{assert_committed, digest},
+            poseidon::hash::Hasher,
+        };
+        let mut rng = TestRng::new();
+        let hasher = rng.gen::<_, Hasher<Spec<Fr, 2>, TwoPowerMinusOneDomainTag, 2>>();
+        let inputs = repeat_with(|| rng.gen::<_, Fp<Fr>>())
+            .take(5)
+            .collect::<Vec<_>>();
+        let committed = digest(&hasher, &inputs, &mut ());
+        assert_ne!(
+            committed,
+            digest(&hasher, &inputs[..4], &mut ()),
+            "Every input should change the digest."
+        );
+        for (committed, should_pass) in [(committed, true), (inputs[0], false)] {
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let hasher: Hasher<Spec<Fr, 2>, TwoPowerMinusOneDomainTag, 2, R1CS<Fr>> =
+                hasher.as_constant(&mut cs);
+            let variables = inputs
+                .iter()
+                .map(|input| input.as_known::<Secret, FpVar<_>>(&mut cs))
+                .collect::<Vec<_>>();
+            let committed = committed.as_known::<Public, FpVar<_>>(&mut cs);
+            assert_committed(&hasher, &variables, &committed, &mut cs);
+            assert_eq!(
+                should_pass,
+                cs.is_satisfied(),
+                "The commitment constraints should hold exactly for the native digest."
+            );
+        }
+    }
+
+    /// Checks that the in-circuit ordered comparisons of [`FpVar`]s agree with the native ones.
+    #[test]
+    fn ordered_comparison_matches_native() {
+        use eclair::cmp::PartialOrd;
+        let mut rng = TestRng::new();
+        for _ in 0..16 {
+            let lhs = sample_smaller_than::<_, Fr, 128>(&mut rng);
+            let rhs = sample_smaller_than::<_, Fr, 128>(&mut rng);
+            for (lhs, rhs) in [(lhs, rhs), (lhs, lhs)] {
+                let mut cs = R1CS::<Fr>::for_proofs();
+                let lhs_var = lhs.as_known::<Secret, FpVar<_>>(&mut cs);
+                let rhs_var = rhs.as_known::<Secret, FpVar<_>>(&mut cs);
+                let circuit = [
+                    lhs_var.lt(&rhs_var, &mut cs),
+                    lhs_var.le(&rhs_var, &mut cs),
+                    lhs_var.gt(&rhs_var, &mut cs),
+                    lhs_var.ge(&rhs_var, &mut cs),
+                ]
+                .map(|bit| bit.value().expect("Unable to get the comparison value."));
+                let native = [
+                    PartialOrd::lt(&lhs, &rhs, &mut ()),
+                    PartialOrd::le(&lhs, &rhs, &mut ()),
+                    PartialOrd::gt(&lhs, &rhs, &mut ()),
+                    PartialOrd::ge(&lhs, &rhs, &mut ()),
+                ];
+                assert_eq!(
+                    native, circuit,
+                    "Comparisons of {lhs:?} and {rhs:?} diverged."
+                );
+                assert!(
+                    cs.is_satisfied(),
+                    "The comparison constraints must be satisfied."
+                );
+            }
+        }
+    }
+    /// Checks that in-circuit checked inversion agrees with native inversion, including on zero.
+    #[test]
+    fn checked_inverse_matches_native() {
+        let mut rng = TestRng::new();
+        for value in [Fp(Fr::from(0u64)), Fp(Fr::from(1u64)), rng.gen()] {
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let variable = value.as_known::<Secret, FpVar<_>>(&mut cs);
+            let (inverse, is_invertible) = Inverse::checked_inverse(&variable, &mut cs);
+            assert_eq!(
+                Inverse::checked_inverse(&value, &mut ()),
+                (
+                    Fp(inverse.value().expect("Unable to get the inverse value.")),
+                    is_invertible
+                        .value()
+                        .expect("Unable to get the invertibility value.")
+                ),
+                "Checked inversion of {value:?} diverged."
+            );
+            assert!(
+                cs.is_satisfied(),
+                "The inversion constraints must be satisfied."
+            );
+        }
+    }
+
+    /// Checks that allocation audits find the first public input which the prover and the
+    /// verifier allocate in different order.
+    #[test]
+    fn allocation_audit_reports_first_divergence() {
+        use eclair::{alloc::Allocator, audit::Kind};
+        let mut rng = TestRng::new();
+        let values = rng.gen::<_, [Fp<Fr>; 2]>();
+        let mut prover = R1CS::<Fr>::for_proofs().with_audit();
+        let _ = values[0].as_known::<Public, FpVar<_>>(&mut prover);
+        let _ = values[1].as_known::<Secret, FpVar<_>>(&mut prover);
+        let _ = true.as_known::<Public, Boolean<_>>(&mut prover);
+        let mut verifier = R1CS::<Fr>::for_contexts().with_audit();
+        let _ = verifier.allocate_unknown::<Public, FpVar<_>>();
+        let _ = verifier.allocate_unknown::<Public, Boolean<_>>();
+        let _ = verifier.allocate_unknown::<Secret, FpVar<_>>();
+        let prover = prover
+            .take_audit_log()
+            .expect("Auditing should be enabled.");
+        let verifier = verifier.audit_log().expect("Auditing should be enabled.");
+        assert_eq!(prover.len(), 3, "Every allocation should be recorded.");
+        assert_eq!(
+            prover.of_kind(Kind::Public).count(),
+            2,
+            "The prover should have allocated two public inputs."
+        );
+        let divergence = prover
+            .diff(verifier)
+            .expect("The allocation sequences should diverge.");
+        assert_eq!(divergence.index, 1, "The second allocation should diverge.");
+        assert_eq!(
+            divergence.prover,
+            Some(Allocation::new::<FpVar<Fr>, Secret>()),
+            "The prover should have allocated a secret field element."
+        );
+        assert_eq!(
+            divergence.verifier,
+            Some(Allocation::new::<Boolean<Fr>, Public>()),
+            "The verifier should have allocated a public boolean."
+        );
+        assert_eq!(
+            prover.diff(&prover),
+            None,
+            "An allocation sequence should not diverge from itself."
+        );
+    }
+
+    /// Checks that field elements round-trip through their byte and bit representations and that
+    /// only the unchecked recomposition accepts non-canonical bits.
+    #[test]
+    fn byte_and_bit_decomposition_round_trip() {
+        const BITS: usize = <Fr as PrimeField>::Params::MODULUS_BITS as usize;
+        const BYTES: usize = (BITS + 7) / 8;
+        let mut rng = TestRng::new();
+        let value = rng.gen::<_, Fp<Fr>>();
+        let bits: [bool; BITS] = value.to_bits_le(&mut ());
+        assert_eq!(
+            Fp::from_bits_le(bits, &mut ()),
+            value,
+            "The value should round-trip through its bits."
+        );
+        let bytes: [u8; BYTES] = value.to_bytes_le(&mut ());
+        assert_eq!(
+            bytes.to_vec(),
+            value.0.into_repr().to_bytes_le(),
+            "The bytes should match the canonical little-endian encoding."
+        );
+        let mut cs = R1CS::<Fr>::for_proofs();
+        let variable = value.as_known::<Secret, FpVar<_>>(&mut cs);
+        let byte_vars: [UInt8<Fr>; BYTES] = variable.to_bytes_be(&mut cs);
+        let mut expected = bytes;
+        expected.reverse();
+        assert_eq!(
+            byte_vars.map(|byte| byte.value().expect("Unable to get the byte value.")),
+            expected,
+            "The in-circuit bytes should match the native bytes."
+        );
+        let bit_vars: [Boolean<Fr>; BITS] = BitDecomposition::to_bits_le(&variable, &mut cs);
+        let recomposed = FpVar::from_bits_le(bit_vars, &mut cs);
+        assert_eq!(
+            recomposed
+                .value()
+                .expect("Unable to get the recomposed value."),
+            value.0,
+            "The recomposed value should match the original value."
+        );
+        assert!(
+            cs.is_satisfied(),
+            "The decomposition constraints must hold."
+        );
+        let modulus_bits: [bool; BITS] =
+            into_array_unchecked(<Fr as PrimeField>::Params::MODULUS.to_bits_le()[..BITS].to_vec());
+        assert_eq!(
+            Fp::<Fr>::from_bits_le_unchecked(modulus_bits, &mut ()),
+            Fp(Fr::from(0u64)),
+            "The bits of the modulus should reduce to zero."
+        );
+        for (checked, should_pass) in [(false, true), (true, false)] {
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let bit_vars = modulus_bits.map(|bit| bit.as_known::<Secret, Boolean<_>>(&mut cs));
+            let recomposed = if checked {
+                FpVar::from_bits_le(bit_vars, &mut cs)
+            } else {
+                FpVar::from_bits_le_unchecked(bit_vars, &mut cs)
+            };
+            assert_eq!(
+                recomposed
+                    .value()
+                    .expect("Unable to get the recomposed value."),
+                Fr::from(0u64),
+                "The bits of the modulus should recompose into zero."
+            );
+            assert_eq!(
+                should_pass,
+                cs.is_satisfied(),
+                "Only the unchecked recomposition should accept the bits of the modulus."
+            );
+        }
+    }
+
+    /// Checks that [`Bytes::substring_eq`] agrees with native slices at every offset of `haystack`,
+    /// allocating `haystack` and the offsets as secrets and `needle` as a constant.
+    #[inline]
+    fn check_substrings<const MAX_LEN: usize, const N: usize>(haystack: &[u8], needle: &[u8]) {
+        let mut cs = R1CS::<Fr>::for_proofs();
+        let haystack_var = Bytes::<(), MAX_LEN>::from_slice(haystack)
+            .expect("The haystack must fit into the byte string.")
+            .as_known::<Derived<(Secret, Secret)>, Bytes<_, MAX_LEN>>(&mut cs);
+        let needle_var = Bytes::<(), N>::from_slice(needle)
+            .expect("The needle must fit into the byte string.")
+            .as_constant::<Bytes<_, N>>(&mut cs);
+        for offset in 0..=MAX_LEN {
+            let offset_var = Fp::from(offset).as_known::<Secret, FpVar<_>>(&mut cs);
+            let expected = haystack.get(offset..offset + needle.len()) == Some(needle);
+            let is_match = haystack_var
+                .substring_eq(&offset_var, &needle_var, &mut cs)
+                .value()
+                .expect("Unable to get the substring comparison value.");
+            assert_eq!(
+                expected, is_match,
+                "Matching {needle:?} at offset {offset} diverged."
+            );
+        }
+        assert!(
+            cs.is_satisfied(),
+            "The byte string constraints must be satisfied."
+        );
+    }
+
+    /// Checks that padded byte strings match substrings and compare for equality like native
+    /// slices, and that malformed padding and lengths are rejected.
+    #[test]
+    fn bytes_match_native_slices() {
+        check_substrings::<16, 4>(b"openzl.org/docs", b"docs");
+        check_substrings::<16, 4>(b"openzl.org/docs", b"org/");
+        check_substrings::<16, 2>(b"openzl.org/docs", b"s\0");
+        check_substrings::<16, 0>(b"openzl.org/docs", b"");
+        let mut cs = R1CS::<Fr>::for_proofs();
+        let url = Bytes::<(), 16>::from_slice(b"openzl.org/docs")
+            .expect("The URL must fit into the byte string.");
+        let url_var = url.as_known::<Derived<(Secret, Public)>, Bytes<_, 16>>(&mut cs);
+        let prefix = Bytes::<(), 6>::from_slice(b"openzl")
+            .expect("The prefix must fit into the byte string.")
+            .as_constant::<Bytes<_, 6>>(&mut cs);
+        let other = Bytes::<(), 16>::from_slice(b"openzl.org/doc")
+            .expect("The URL must fit into the byte string.")
+            .as_known::<Derived<(Secret, Secret)>, Bytes<_, 16>>(&mut cs);
+        let same = url.as_known::<Derived<(Secret, Secret)>, Bytes<_, 16>>(&mut cs);
+        let bits = [
+            url_var.starts_with(&prefix, &mut cs),
+            eclair::cmp::PartialEq::eq(&url_var, &same, &mut cs),
+            eclair::cmp::PartialEq::eq(&url_var, &other, &mut cs),
+        ]
+        .map(|bit| bit.value().expect("Unable to get the comparison value."));
+        assert_eq!(
+            bits,
+            [true, true, false],
+            "Byte string comparisons must agree with the native ones."
+        );
+        assert!(
+            cs.is_satisfied(),
+            "The byte string constraints must be satisfied."
+        );
+        for malformed in [
+            Bytes::<(), 4>::new_unchecked(*b"ab\0c", 2),
+            Bytes::<(), 4>::new_unchecked(*b"abcd", 5),
+        ] {
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let _ = malformed.as_known::<Derived<(Secret, Secret)>, Bytes<_, 4>>(&mut cs);
+            assert!(
+                !cs.is_satisfied(),
+                "Malformed byte strings must not satisfy the constraints."
+            );
+        }
+    }
+
+    /// Checks that compiled patterns accept the same padded byte strings in-circuit as natively,
+    /// and that unsupported patterns are rejected.
+    #[test]
+    fn pattern_matches_native_automaton() {
+        let inputs: [&[u8]; 4] = [br#"{"amount":100}"#, br#"{"amount" :1}"#, b"amount", b""];
+        let patterns: [(&[u8], Mode, [bool; 4]); 2] = [
+            (
+                br#""amount":[0-9]+"#,
+                Mode::Contains,
+                [true, false, false, false],
+            ),
+            (
+                br#"\{"[a-z]*":.*\}"#,
+                Mode::Full,
+                [true, false, false, false],
+            ),
+        ];
+        for (pattern, mode, expected) in patterns {
+            let dfa = Dfa::compile(pattern, mode).expect("The pattern must compile.");
+            assert_eq!(
+                inputs.map(|input| dfa.is_match(input)),
+                expected,
+                "The automaton must accept exactly the expected inputs."
+            );
+            for (input, expected) in inputs.into_iter().zip(expected) {
+                let mut cs = R1CS::<Fr>::for_proofs();
+                let input_var = Bytes::<(), 16>::from_slice(input)
+                    .expect("The input must fit into the byte string.")
+                    .as_known::<Derived<(Secret, Secret)>, Bytes<_, 16>>(&mut cs);
+                let is_match = dfa
+                    .matches(&input_var, &mut cs)
+                    .value()
+                    .expect("Unable to get the pattern matching value.");
+                assert_eq!(
+                    expected, is_match,
+                    "Matching {input:?} in-circuit diverged from the automaton."
+                );
+                assert!(
+                    cs.is_satisfied(),
+                    "The pattern matching constraints must be satisfied."
+                );
+            }
+        }
+        assert_eq!(
+            Dfa::compile(b"a(b)", Mode::Full),
+            Err(PatternError::UnexpectedByte { position: 1 })
+        );
+        assert_eq!(
+            Dfa::compile(b"[z-a]", Mode::Full),
+            Err(PatternError::UnexpectedByte { position: 3 })
+        );
+        assert_eq!(
+            Dfa::compile(b"ab\\", Mode::Contains),
+            Err(PatternError::UnexpectedEnd)
+        );
+    }
+
+    /// Signed Claims Tests
+    mod claims {
+        use super::*;
+        use openzl_crypto::{
+            hash::HashFunction,
+            protocol::claims::{prepare, Claims, SignedClaims, Witness},
+            signature::{MessageType, SignatureType, Verify, VerifyingKeyType},
+            statements::Statement,
+        };
+
+        /// Signed Document
+        const DOCUMENT: &[u8] = br#"{"iss":"issuer","sub":"alice","age":21,"admin":false}"#;
+
+        /// Document Capacity
+        const DOC_LEN: usize = 64;
+
+        /// Checksum Signature Scheme
+        ///
+        /// Accepts a signature of a digest if it is the sum of the digest and the verifying key.
+        /// This scheme is insecure and only stands in for a real signature scheme in tests.
+        struct Checksum;
+
+        impl VerifyingKeyType for Checksum {
+            type VerifyingKey = FpVar<Fr>;
+        }
+
+        impl MessageType for Checksum {
+            type Message = FpVar<Fr>;
+        }
+
+        impl SignatureType for Checksum {
+            type Signature = FpVar<Fr>;
+        }
+
+        impl Verify<R1CS<Fr>> for Checksum {
+            type Verification = Boolean<Fr>;
+
+            #[inline]
+            fn verify(
+                &self,
+                verifying_key: &Self::VerifyingKey,
+                message: &Self::Message,
+                signature: &Self::Signature,
+                compiler: &mut R1CS<Fr>,
+            ) -> Self::Verification {
+                let _ = compiler;
+                signature
+                    .is_eq(&(message + verifying_key))
+                    .expect("Equality checking is not allowed to fail.")
+            }
+        }
+
+        /// Weighted Sum Hash Function
+        ///
+        /// Digests a document into the sum of its bytes weighted by their positions.
+        struct WeightedSum;
+
+        impl HashFunction<R1CS<Fr>> for WeightedSum {
+            type Input = Bytes<R1CS<Fr>, DOC_LEN>;
+            type Output = FpVar<Fr>;
+
+            #[inline]
+            fn hash(&self, input: &Self::Input, compiler: &mut R1CS<Fr>) -> Self::Output {
+                let _ = compiler;
+                input
+                    .bytes()
+                    .iter()
+                    .zip(1u64..)
+                    .fold(FpVar::Constant(Fr::from(0u64)), |digest, (byte, weight)| {
+                        digest + byte * Fr::from(weight)
+                    })
+            }
+        }
+
+        /// Computes the native digest of `document` under [`WeightedSum`].
+        #[inline]
+        fn digest(document: &[u8]) -> Fr {
+            document
+                .iter()
+                .zip(1u64..)
+                .fold(Fr::from(0u64), |digest, (byte, weight)| {
+                    digest + Fr::from(weight) * Fr::from(u64::from(*byte))
+                })
+        }
+
+        /// Returns `true` if [`SignedClaims`] accepts `claims` and `witness` in-circuit.
+        #[inline]
+        fn is_satisfied(
+            claims: &Claims<Fp<Fr>, (), 16, 2>,
+            witness: &Witness<Fp<Fr>, (), DOC_LEN, 2>,
+        ) -> bool {
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let claims = claims.as_known::<Public, Claims<FpVar<Fr>, _, 16, 2>>(&mut cs);
+            let witness = witness.as_known::<Secret, Witness<FpVar<Fr>, _, DOC_LEN, 2>>(&mut cs);
+            SignedClaims::<_, _, DOC_LEN, 16, 2>::new(Checksum, WeightedSum)
+                .enforce(&claims, &witness, &mut cs);
+            cs.is_satisfied()
+        }
+
+        /// Checks that [`SignedClaims`] accepts a signed document with claims at their offsets
+        /// and rejects wrong offsets, altered claims, and invalid signatures.
+        #[test]
+        fn signed_claims_reject_forgeries() {
+            let verifying_key = Fr::from(0x5eedu64);
+            let (claims, witness) = prepare::<_, _, DOC_LEN, 16, 2>(
+                DOCUMENT,
+                [b"sub", b"age"],
+                Fp(verifying_key),
+                Fp(digest(DOCUMENT) + verifying_key),
+            )
+            .expect("The document is well-formed and holds every key.");
+            assert!(
+                is_satisfied(&claims, &witness),
+                "Signed documents with claims at their offsets must be accepted."
+            );
+            let mut wrong_offset = witness.clone();
+            wrong_offset.offsets[0] += 1;
+            assert!(
+                !is_satisfied(&claims, &wrong_offset),
+                "Claims at the wrong offset must be rejected."
+            );
+            let mut altered = claims.clone();
+            altered.claims[1] = Bytes::from_slice(br#""age":31,"#).expect("The claim fits.");
+            assert!(
+                !is_satisfied(&altered, &witness),
+                "Claims which differ from the document must be rejected."
+            );
+            let mut forged = witness;
+            forged.signature = Fp(forged.signature.0 + Fr::from(1u64));
+            assert!(
+                !is_satisfied(&claims, &forged),
+                "Documents with an invalid signature must be rejected."
+            );
+        }
+    }
+
+    /// Allocation Derive Tests
+    mod allocation {
+        use super::*;
+        use eclair::alloc::Allocator;
+        use openzl_crypto::Allocation;
+
+        /// Native Credential
+        #[derive(Clone, Debug, Default)]
+        struct Credential {
+            /// Issuer Key
+            issuer: Fp<Fr>,
+
+            /// Holder Secret
+            secret: Fp<Fr>,
+
+            /// Domain Separator
+            domain: Fp<Fr>,
+        }
+
+        /// In-Circuit Credential with a Public, a Secret, and a Constant Field
+        #[derive(Allocation)]
+        #[allocation(type = "Credential", compiler = "R1CS<Fr>")]
+        struct CredentialVar {
+            /// Issuer Key
+            #[public]
+            issuer: FpVar<Fr>,
+
+            /// Holder Secret
+            #[secret]
+            secret: FpVar<Fr>,
+
+            /// Domain Separator
+            #[constant]
+            domain: FpVar<Fr>,
+        }
+
+        /// In-Circuit Credential without a Public Field
+        #[derive(Allocation)]
+        #[allocation(type = "Credential", compiler = "R1CS<Fr>")]
+        struct HiddenCredentialVar {
+            /// Issuer Key
+            #[secret]
+            issuer: FpVar<Fr>,
+
+            /// Holder Secret
+            #[secret]
+            secret: FpVar<Fr>,
+
+            /// Domain Separator
+            #[constant]
+            domain: FpVar<Fr>,
+        }
+
+        /// Samples a credential with distinct field values.
+        fn sample_credential() -> Credential {
+            let mut rng = TestRng::new();
+            Credential {
+                issuer: rng.gen(),
+                secret: rng.gen(),
+                domain: rng.gen(),
+            }
+        }
+
+        /// Checks that the derived allocation of a struct with mixed fields allocates every field
+        /// with its annotated mode and keeps its value.
+        #[test]
+        fn derived_allocation_uses_field_modes() {
+            let credential = sample_credential();
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let instances = cs.0.num_instance_variables();
+            let witnesses = cs.0.num_witness_variables();
+            let variable = credential.as_known::<Derived, CredentialVar>(&mut cs);
+            assert_eq!(
+                cs.0.num_instance_variables(),
+                instances + 1,
+                "The public field should be the only new public input."
+            );
+            assert_eq!(
+                cs.0.num_witness_variables(),
+                witnesses + 1,
+                "The secret field should be the only new witness."
+            );
+            assert_eq!(variable.issuer.value().ok(), Some(credential.issuer.0));
+            assert_eq!(variable.secret.value().ok(), Some(credential.secret.0));
+            assert!(
+                variable.domain.is_constant(),
+                "The constant field must be allocated as a constant."
+            );
+            assert_eq!(variable.domain.value().ok(), Some(credential.domain.0));
+        }
+
+        /// Checks that a struct whose non-constant fields share a mode can also be allocated with
+        /// that mode, agreeing with its derived allocation.
+        #[test]
+        fn shared_mode_allocation_matches_derived_allocation() {
+            let credential = sample_credential();
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let instances = cs.0.num_instance_variables();
+            let witnesses = cs.0.num_witness_variables();
+            let secret = credential.as_known::<Secret, HiddenCredentialVar>(&mut cs);
+            let derived = credential.as_known::<Derived, HiddenCredentialVar>(&mut cs);
+            assert_eq!(
+                cs.0.num_instance_variables(),
+                instances,
+                "Secret allocations must not add public inputs."
+            );
+            assert_eq!(cs.0.num_witness_variables(), witnesses + 4);
+            for (lhs, rhs) in [
+                (&secret.issuer, &derived.issuer),
+                (&secret.secret, &derived.secret),
+                (&secret.domain, &derived.domain),
+            ] {
+                assert_eq!(
+                    lhs.value().ok(),
+                    rhs.value().ok(),
+                    "Secret and derived allocations should agree."
+                );
+            }
+            let mut cs = R1CS::<Fr>::for_contexts();
+            let unknown = cs.allocate_unknown::<Secret, HiddenCredentialVar>();
+            assert!(
+                unknown.domain.is_constant(),
+                "Unknown allocation must keep constant fields constant."
+            );
+        }
+    }
+
+    /// Conditional Select Derive Tests
+    mod conditional_select {
+        use super::*;
+        use eclair::ops::select_many;
+        use openzl_crypto::ConditionalSelect;
+
+        /// Account State
+        #[derive(ConditionalSelect)]
+        struct Account<T> {
+            /// Balance
+            balance: T,
+
+            /// Deposit and Withdrawal Counters
+            counters: [T; 2],
+        }
+
+        impl Account<u64> {
+            /// Allocates `self` into `cs` with secret fields.
+            fn as_secret(&self, cs: &mut R1CS<Fr>) -> Account<FpVar<Fr>> {
+                let mut allocate =
+                    |value: u64| Fp(Fr::from(value)).as_known::<Secret, FpVar<_>>(cs);
+                Account {
+                    balance: allocate(self.balance),
+                    counters: [allocate(self.counters[0]), allocate(self.counters[1])],
+                }
+            }
+        }
+
+        /// Deposits `amount` into `account`.
+        fn deposit(account: &Account<u64>, amount: u64) -> Account<u64> {
+            Account {
+                balance: account.balance + amount,
+                counters: [account.counters[0] + 1, account.counters[1]],
+            }
+        }
+
+        /// Withdraws `amount` from `account`.
+        fn withdraw(account: &Account<u64>, amount: u64) -> Account<u64> {
+            Account {
+                balance: account.balance - amount,
+                counters: [account.counters[0], account.counters[1] + 1],
+            }
+        }
+
+        /// Deposits `amount` into `account` in-circuit.
+        fn deposit_var(account: &Account<FpVar<Fr>>, amount: &FpVar<Fr>) -> Account<FpVar<Fr>> {
+            Account {
+                balance: &account.balance + amount,
+                counters: [
+                    &account.counters[0] + Fr::from(1u64),
+                    account.counters[1].clone(),
+                ],
+            }
+        }
+
+        /// Withdraws `amount` from `account` in-circuit.
+        fn withdraw_var(account: &Account<FpVar<Fr>>, amount: &FpVar<Fr>) -> Account<FpVar<Fr>> {
+            Account {
+                balance: &account.balance - amount,
+                counters: [
+                    account.counters[0].clone(),
+                    &account.counters[1] + Fr::from(1u64),
+                ],
+            }
+        }
+
+        /// Checks that the derived selection of a `struct` selects every field with the same bit,
+        /// natively and in-circuit, when merging the branches of [`select_many`].
+        #[test]
+        fn derived_select_merges_branches() {
+            let account = Account {
+                balance: 100,
+                counters: [3, 5],
+            };
+            for bit in [false, true] {
+                let expected = select_many(
+                    &bit,
+                    |_| deposit(&account, 7),
+                    |_| withdraw(&account, 7),
+                    &mut (),
+                );
+                let branch = if bit {
+                    deposit(&account, 7)
+                } else {
+                    withdraw(&account, 7)
+                };
+                assert_eq!(expected.balance, branch.balance);
+                assert_eq!(expected.counters, branch.counters);
+                let mut cs = R1CS::<Fr>::for_proofs();
+                let account_var = account.as_secret(&mut cs);
+                let amount = Fp(Fr::from(7u64)).as_known::<Secret, FpVar<_>>(&mut cs);
+                let bit_var = bit.as_known::<Secret, Boolean<_>>(&mut cs);
+                let selected = select_many(
+                    &bit_var,
+                    |_| deposit_var(&account_var, &amount),
+                    |_| withdraw_var(&account_var, &amount),
+                    &mut cs,
+                );
+                assert_eq!(
+                    [
+                        selected.balance.value().ok(),
+                        selected.counters[0].value().ok(),
+                        selected.counters[1].value().ok(),
+                    ],
+                    [
+                        Some(Fr::from(expected.balance)),
+                        Some(Fr::from(expected.counters[0])),
+                        Some(Fr::from(expected.counters[1])),
+                    ],
+                    "The in-circuit selection must match the native one."
+                );
+                assert!(cs.is_satisfied());
+            }
+        }
+    }
 }
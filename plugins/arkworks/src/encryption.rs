@@ -0,0 +1,483 @@
+//! Arkworks Encryption Schemes
+//!
+//! This module instantiates the [`Hybrid`] encryption framework with an ElGamal key encapsulation
+//! mechanism over an embedded elliptic curve and a Poseidon duplex data encapsulation mechanism
+//! over the constraint field of that curve. Both the native [`ElGamal`] scheme and its in-circuit
+//! counterpart [`ElGamalVar`] implement [`Derive`], [`Encrypt`], and [`Decrypt`], so that a circuit
+//! can prove that a ciphertext is the correct encryption of a plaintext under a given key.
+//!
+//! [`Derive`]: openzl_crypto::encryption::Derive
+//! [`Encrypt`]: openzl_crypto::encryption::Encrypt
+//! [`Decrypt`]: openzl_crypto::encryption::Decrypt
+
+use crate::{
+    algebra::{Compiler, ConstraintField, Group, GroupVar, ScalarVar},
+    constraint::{fp::Fp, FpVar},
+    ec::ProjectiveCurve,
+    ff::ToConstraintField,
+    poseidon::Spec,
+    r1cs_std::{groups::CurveVar, ToConstraintFieldGadget},
+};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use eclair::alloc::Constant;
+use openzl_crypto::{
+    algebra::diffie_hellman::StandardDiffieHellman,
+    encryption::{
+        convert::key::{self, Decryption, Encryption},
+        hybrid::Hybrid,
+        DecryptionKeyType, EncryptionKeyType,
+    },
+    poseidon::encryption::FixedDuplexer,
+};
+use openzl_util::derivative;
+
+/// Shared Secret Key Derivation
+///
+/// Converts the shared secret point of the ElGamal key encapsulation into the key of the Poseidon
+/// duplex data encapsulation by taking its coordinates over the constraint field.
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SharedSecretKey<G>(PhantomData<G>);
+
+impl<G> EncryptionKeyType for SharedSecretKey<G> {
+    type EncryptionKey = G;
+}
+
+impl<G> DecryptionKeyType for SharedSecretKey<G> {
+    type DecryptionKey = G;
+}
+
+impl<C> Encryption for SharedSecretKey<Group<C>>
+where
+    C: ProjectiveCurve,
+    C::Affine: ToConstraintField<ConstraintField<C>>,
+{
+    type TargetEncryptionKey = Vec<Fp<ConstraintField<C>>>;
+
+    #[inline]
+    fn as_target(source: &Self::EncryptionKey, _: &mut ()) -> Self::TargetEncryptionKey {
+        point_as_key(source)
+    }
+}
+
+impl<C> Decryption for SharedSecretKey<Group<C>>
+where
+    C: ProjectiveCurve,
+    C::Affine: ToConstraintField<ConstraintField<C>>,
+{
+    type TargetDecryptionKey = Vec<Fp<ConstraintField<C>>>;
+
+    #[inline]
+    fn as_target(source: &Self::DecryptionKey, _: &mut ()) -> Self::TargetDecryptionKey {
+        point_as_key(source)
+    }
+}
+
+impl<C, CV> Encryption<Compiler<C>> for SharedSecretKey<GroupVar<C, CV>>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>> + ToConstraintFieldGadget<ConstraintField<C>>,
+{
+    type TargetEncryptionKey = Vec<FpVar<ConstraintField<C>>>;
+
+    #[inline]
+    fn as_target(
+        source: &Self::EncryptionKey,
+        compiler: &mut Compiler<C>,
+    ) -> Self::TargetEncryptionKey {
+        let _ = compiler;
+        point_var_as_key(source)
+    }
+}
+
+impl<C, CV> Decryption<Compiler<C>> for SharedSecretKey<GroupVar<C, CV>>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>> + ToConstraintFieldGadget<ConstraintField<C>>,
+{
+    type TargetDecryptionKey = Vec<FpVar<ConstraintField<C>>>;
+
+    #[inline]
+    fn as_target(
+        source: &Self::DecryptionKey,
+        compiler: &mut Compiler<C>,
+    ) -> Self::TargetDecryptionKey {
+        let _ = compiler;
+        point_var_as_key(source)
+    }
+}
+
+impl<C, CV> Constant<Compiler<C>> for SharedSecretKey<GroupVar<C, CV>>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>>,
+{
+    type Type = SharedSecretKey<Group<C>>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+        let _ = (this, compiler);
+        Self::default()
+    }
+}
+
+/// Converts `point` into the duplex encryption key made of its coordinates.
+#[inline]
+fn point_as_key<C>(point: &Group<C>) -> Vec<Fp<ConstraintField<C>>>
+where
+    C: ProjectiveCurve,
+    C::Affine: ToConstraintField<ConstraintField<C>>,
+{
+    point
+        .0
+        .to_field_elements()
+        .expect("Conversion to constraint field elements is not allowed to fail.")
+        .into_iter()
+        .map(Fp)
+        .collect()
+}
+
+/// Converts `point` into the duplex encryption key variable made of its coordinates.
+#[inline]
+fn point_var_as_key<C, CV>(point: &GroupVar<C, CV>) -> Vec<FpVar<ConstraintField<C>>>
+where
+    C: ProjectiveCurve,
+    CV: CurveVar<C, ConstraintField<C>> + ToConstraintFieldGadget<ConstraintField<C>>,
+{
+    point
+        .0
+        .to_constraint_field()
+        .expect("Conversion to constraint field elements is not allowed to fail.")
+}
+
+/// ElGamal Key Encapsulation Mechanism
+pub type ElGamalKem<C> = StandardDiffieHellman<Fp<<C as ProjectiveCurve>::ScalarField>, Group<C>>;
+
+/// ElGamal Key Encapsulation Mechanism Variable
+pub type ElGamalKemVar<C, CV> = StandardDiffieHellman<ScalarVar<C, CV>, GroupVar<C, CV>>;
+
+/// Poseidon Data Encapsulation Mechanism
+///
+/// The duplex runs over the Poseidon specification with the given `ARITY` and encrypts plaintexts
+/// made of `N` blocks.
+pub type PoseidonDem<C, const ARITY: usize, const N: usize> =
+    key::Converter<FixedDuplexer<N, Spec<ConstraintField<C>, ARITY>>, SharedSecretKey<Group<C>>>;
+
+/// Poseidon Data Encapsulation Mechanism Variable
+pub type PoseidonDemVar<C, CV, const ARITY: usize, const N: usize> = key::Converter<
+    FixedDuplexer<N, Spec<ConstraintField<C>, ARITY>, Compiler<C>>,
+    SharedSecretKey<GroupVar<C, CV>>,
+>;
+
+/// ElGamal Hybrid Encryption Scheme
+pub type ElGamal<C, const ARITY: usize, const N: usize> =
+    Hybrid<ElGamalKem<C>, PoseidonDem<C, ARITY, N>>;
+
+/// ElGamal Hybrid Encryption Scheme Variable
+pub type ElGamalVar<C, CV, const ARITY: usize, const N: usize> =
+    Hybrid<ElGamalKemVar<C, CV>, PoseidonDemVar<C, CV, ARITY, N>>;
+
+/// Testing Suite
+#[cfg(all(test, feature = "bn254", feature = "ed-on-bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        constraint::R1CS,
+        ed_on_bn254::{constraints::EdwardsVar, EdwardsProjective, Fq},
+        ff::PrimeField,
+    };
+    use alloc::boxed::Box;
+    use eclair::alloc::{
+        mode::{Public, Secret},
+        Allocate,
+    };
+    use openzl_crypto::{
+        accumulator::{
+            lookup::{EncryptedAccumulator, ProofBundle, Query},
+            ItemHashFunction,
+        },
+        constraint::Satisfied,
+        encryption::{
+            hybrid::Randomness,
+            test::{assert_encryption_laws, assert_key_privacy, key_privacy_game},
+            verifiable::{Statement, Verifiable, Witness},
+            Ciphertext, Decrypt, Derive, Encrypt, EncryptedMessage, Plaintext,
+        },
+        merkle_tree::{full::FullMerkleTree, test::Test, Parameters},
+        poseidon::encryption::{BlockArray, PlaintextBlock},
+    };
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Native Scheme
+    type Scheme = ElGamal<EdwardsProjective, 2, 1>;
+
+    /// Circuit Scheme
+    type SchemeVar = ElGamalVar<EdwardsProjective, EdwardsVar, 2, 1>;
+
+    /// Samples a random plaintext for [`Scheme`].
+    fn sample_plaintext(rng: &mut TestRng) -> Plaintext<Scheme> {
+        BlockArray([PlaintextBlock(Box::new(<[Fp<_>; 2]>::gen(rng)) as Box<[_]>)].into())
+    }
+
+    /// Tests that the native scheme decrypts its own ciphertexts and that the circuit accepts the
+    /// natively computed ciphertext as the encryption of the plaintext.
+    #[test]
+    fn encryption_in_circuit_matches_native() {
+        let mut rng = TestRng::new();
+        let scheme = Scheme::sample(((), ((), ())), &mut rng);
+        let decryption_key = Fp::gen(&mut rng);
+        let encryption_key = scheme.derive(&decryption_key, &mut ());
+        let randomness = Randomness::from_key(Fp::gen(&mut rng));
+        let header = vec![];
+        let plaintext = BlockArray::<_, 1>(
+            [PlaintextBlock(
+                Box::new(<[Fp<_>; 2]>::gen(&mut rng)) as Box<[_]>
+            )]
+            .into(),
+        );
+        let ciphertext = scheme.encrypt(&encryption_key, &randomness, &header, &plaintext, &mut ());
+        let (tag_matches, decrypted_plaintext) =
+            scheme.decrypt(&decryption_key, &header, &ciphertext, &mut ());
+        assert!(tag_matches, "Tag doesn't match.");
+        assert_eq!(
+            plaintext, decrypted_plaintext,
+            "Decrypted plaintext is not equal to the original one."
+        );
+        let mut compiler = R1CS::for_proofs();
+        let scheme_var = scheme.as_constant::<SchemeVar>(&mut compiler);
+        let encryption_key_var = encryption_key.as_known::<Public, _>(&mut compiler);
+        let randomness_var = randomness.as_known::<Secret, _>(&mut compiler);
+        let header_var = header.as_constant(&mut compiler);
+        let plaintext_var = plaintext.as_known::<Secret, _>(&mut compiler);
+        let ciphertext_var = ciphertext.as_known::<Public, _>(&mut compiler);
+        let computed_ciphertext_var = scheme_var.encrypt(
+            &encryption_key_var,
+            &randomness_var,
+            &header_var,
+            &plaintext_var,
+            &mut compiler,
+        );
+        eclair::cmp::PartialEq::assert_equal(
+            &computed_ciphertext_var,
+            &ciphertext_var,
+            &mut compiler,
+        );
+        assert!(
+            compiler.is_satisfied(),
+            "The circuit must accept the natively computed ciphertext."
+        );
+    }
+
+    /// Tests that the verifiable encryption circuit accepts a ciphertext whose plaintext satisfies
+    /// the predicate and rejects it when the predicate does not hold.
+    #[test]
+    fn verifiable_encryption_enforces_predicate() {
+        let mut rng = TestRng::new();
+        let scheme = Scheme::sample(((), ((), ())), &mut rng);
+        let encryption_key = scheme.derive(&Fp::gen(&mut rng), &mut ());
+        let randomness = Randomness::from_key(Fp::gen(&mut rng));
+        let plaintext = BlockArray::<_, 1>(
+            [PlaintextBlock(
+                Box::new(<[Fp<_>; 2]>::gen(&mut rng)) as Box<[_]>
+            )]
+            .into(),
+        );
+        let ciphertext = scheme.encrypt(&encryption_key, &randomness, &vec![], &plaintext, &mut ());
+        let statement = Statement::new(encryption_key, ciphertext);
+        let witness = Witness::new(randomness, plaintext);
+        let expected = witness.plaintext.0[0].0[0];
+        for (first_element, should_accept) in
+            [(expected, true), (Fp(expected.0 + Fq::from(1u8)), false)]
+        {
+            let circuit = Verifiable::new(
+                scheme.clone(),
+                vec![],
+                move |plaintext: &Plaintext<SchemeVar>, compiler: &mut R1CS<Fq>| {
+                    let first_element = first_element.as_constant::<FpVar<_>>(compiler);
+                    eclair::cmp::PartialEq::eq(&plaintext.0[0].0[0], &first_element, compiler)
+                },
+            );
+            let mut compiler = R1CS::for_proofs();
+            let _ = circuit.build_known::<SchemeVar, _>(&statement, &witness, &mut compiler);
+            assert_eq!(
+                compiler.is_satisfied(),
+                should_accept,
+                "The circuit must accept exactly the plaintexts satisfying the predicate."
+            );
+        }
+    }
+
+    /// Tests that random ciphertexts decrypt to their plaintexts and that ciphertexts with a
+    /// tampered tag or ephemeral key are rejected.
+    #[test]
+    fn hybrid_encryption_satisfies_encryption_laws() {
+        let mut rng = TestRng::new();
+        let scheme = Scheme::sample(((), ((), ())), &mut rng);
+        let sample = |rng: &mut TestRng| {
+            (
+                Fp::gen(rng),
+                Randomness::from_key(Fp::gen(rng)),
+                vec![Fp::gen(rng)],
+                sample_plaintext(rng),
+            )
+        };
+        let accepts = |plaintext: &Plaintext<Scheme>, (tag_matches, decrypted): &(bool, _)| {
+            *tag_matches && decrypted == plaintext
+        };
+        assert_encryption_laws(
+            &scheme,
+            16,
+            sample,
+            |ciphertext: &mut Ciphertext<Scheme>, _| {
+                ciphertext.ciphertext.tag.0 = Fp(ciphertext.ciphertext.tag.0 .0 + Fq::from(1u8))
+            },
+            accepts,
+            &mut rng,
+        );
+        assert_encryption_laws(
+            &scheme,
+            16,
+            sample,
+            |ciphertext: &mut Ciphertext<Scheme>, rng| {
+                ciphertext.ephemeral_public_key = Group::gen(rng)
+            },
+            accepts,
+            &mut rng,
+        );
+    }
+
+    /// Tests that simulated adversaries cannot tell which receiver a ciphertext of the key-private
+    /// scheme targets, unless the header commits to the encryption key.
+    #[test]
+    fn hybrid_encryption_is_key_private() {
+        const ROUNDS: usize = 128;
+        const TOLERANCE: usize = 24;
+        let mut rng = TestRng::new();
+        let Hybrid {
+            key_agreement_scheme,
+            encryption_scheme,
+        } = Scheme::sample(((), ((), ())), &mut rng);
+        let scheme = Scheme::key_private(key_agreement_scheme, encryption_scheme);
+        let sample = |_: &Group<EdwardsProjective>, rng: &mut TestRng| {
+            (
+                Randomness::from_key(Fp::gen(rng)),
+                vec![],
+                sample_plaintext(rng),
+            )
+        };
+        assert_key_privacy(
+            &scheme,
+            ROUNDS,
+            TOLERANCE,
+            sample,
+            |first, second, _, _, ciphertext| {
+                let ephemeral_key = point_as_key(&ciphertext.ephemeral_public_key)[0];
+                (ephemeral_key < point_as_key(first)[0])
+                    != (ephemeral_key < point_as_key(second)[0])
+            },
+            &mut rng,
+        );
+        assert_key_privacy(
+            &scheme,
+            ROUNDS,
+            TOLERANCE,
+            sample,
+            |_, second, _, _, ciphertext| ciphertext.ciphertext.tag.0 < point_as_key(second)[0],
+            &mut rng,
+        );
+        assert_eq!(
+            key_privacy_game(
+                &scheme,
+                ROUNDS,
+                |receiver, rng| {
+                    (
+                        Randomness::from_key(Fp::gen(rng)),
+                        point_as_key(receiver),
+                        sample_plaintext(rng),
+                    )
+                },
+                |_, second, header, _, _| header == &point_as_key(second),
+                &mut rng,
+            ),
+            ROUNDS,
+            "A header which commits to the encryption key should reveal the receiver."
+        );
+    }
+
+    /// Item Hash for Lookup Tests
+    ///
+    /// Reads the lowest limb of the authentication tag of a message, which is enough to tell the
+    /// messages of a test apart.
+    struct TagItemHash;
+
+    impl ItemHashFunction<EncryptedMessage<Scheme>> for TagItemHash {
+        type Item = u64;
+
+        #[inline]
+        fn item_hash(&self, value: &EncryptedMessage<Scheme>, _: &mut ()) -> Self::Item {
+            value.ciphertext.ciphertext.tag.0 .0.into_repr().0[0]
+        }
+    }
+
+    /// Tests that an encrypted accumulator answers queries hidden among decoys with proof bundles
+    /// which open to the queried message, and that tampered bundles are rejected.
+    #[test]
+    fn encrypted_accumulator_serves_decoy_queries() {
+        let mut rng = TestRng::new();
+        let scheme = Scheme::sample(((), ((), ())), &mut rng);
+        let decryption_key = Fp::gen(&mut rng);
+        let encryption_key = scheme.derive(&decryption_key, &mut ());
+        let mut server = EncryptedAccumulator::new(
+            FullMerkleTree::<Test<u64, 4>>::new(Parameters::default()),
+            TagItemHash,
+        );
+        let plaintexts = (0..8)
+            .map(|_| sample_plaintext(&mut rng))
+            .collect::<Vec<_>>();
+        for (index, plaintext) in plaintexts.iter().enumerate() {
+            let randomness = Randomness::from_key(Fp::gen(&mut rng));
+            let message =
+                scheme.encrypt_into(&encryption_key, &randomness, vec![], plaintext, &mut ());
+            assert_eq!(server.insert(message).ok(), Some(index));
+        }
+        assert!(Query::sample(server.len(), 3, server.len(), &mut rng).is_none());
+        let model = server.accumulator().parameters();
+        let root = server.accumulator().root();
+        for (index, plaintext) in plaintexts.iter().enumerate() {
+            let (query, position) = Query::sample(index, 3, server.len(), &mut rng)
+                .expect("Stored indices can be queried.");
+            assert_eq!(query.indices().len(), 4);
+            assert_eq!(query.indices()[position], index);
+            let bundle = server
+                .serve(&query)
+                .expect("Every queried index is stored.");
+            let message = bundle
+                .open(&query, position, model, root, &TagItemHash, &mut ())
+                .expect("The bundle should open at the queried position.");
+            assert_eq!(
+                message.decrypt(&scheme, &decryption_key, &mut ()),
+                (true, plaintext.clone()),
+                "The opened message should decrypt to the stored plaintext."
+            );
+            let mut responses = bundle.clone().into_inner();
+            responses[position].message = server
+                .get((index + 1) % server.len())
+                .expect("The index is stored.")
+                .clone();
+            assert!(
+                ProofBundle::new(responses)
+                    .open(&query, position, model, root, &TagItemHash, &mut ())
+                    .is_none(),
+                "Bundles with swapped messages must be rejected."
+            );
+            let other = Query::new(vec![index]);
+            assert!(
+                bundle
+                    .open(&other, 0, model, root, &TagItemHash, &mut ())
+                    .is_none(),
+                "Bundles for other queries must be rejected."
+            );
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Ethereum Groth16 Verifier Export
+//!
+//! Ethereum verifiers check Groth16 proofs over BN254 with the `ecAdd`, `ecMul`, and `ecPairing`
+//! precompiles, which use the point encoding of [EIP-197]. Base field elements are encoded as
+//! big-endian `uint256` values, [`G1Affine`] points as `(x, y)`, and [`G2Affine`] points as
+//! `((x.c1, x.c0), (y.c1, y.c0))`, so the imaginary part of each coordinate comes first. The point
+//! at infinity is encoded as all zeros.
+//!
+//! [EIP-197]: https://eips.ethereum.org/EIPS/eip-197
+
+use crate::{
+    bn254::{Bn254, Fq, Fr, G1Affine, G2Affine},
+    ff::{BigInteger, PrimeField},
+    groth16::{self, VerifyingContext},
+};
+use alloc::vec::Vec;
+
+/// EVM Word
+///
+/// A `uint256` value in big-endian byte order.
+pub type Uint256 = [u8; 32];
+
+/// Encoded [`G1Affine`] Point
+pub type G1 = [Uint256; 2];
+
+/// Encoded [`G2Affine`] Point
+pub type G2 = [[Uint256; 2]; 2];
+
+/// Converts `element` into its big-endian `uint256` encoding.
+#[inline]
+pub fn uint256<F>(element: &F) -> Uint256
+where
+    F: PrimeField,
+{
+    let bytes = element.into_repr().to_bytes_be();
+    let mut word = [0; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+/// Converts `point` into its [EIP-197] encoding.
+///
+/// [EIP-197]: https://eips.ethereum.org/EIPS/eip-197
+#[inline]
+pub fn g1(point: &G1Affine) -> G1 {
+    if point.infinity {
+        return Default::default();
+    }
+    [uint256::<Fq>(&point.x), uint256::<Fq>(&point.y)]
+}
+
+/// Converts `point` into its [EIP-197] encoding, placing the imaginary part of each coordinate
+/// first.
+///
+/// [EIP-197]: https://eips.ethereum.org/EIPS/eip-197
+#[inline]
+pub fn g2(point: &G2Affine) -> G2 {
+    if point.infinity {
+        return Default::default();
+    }
+    [
+        [uint256(&point.x.c1), uint256(&point.x.c0)],
+        [uint256(&point.y.c1), uint256(&point.y.c0)],
+    ]
+}
+
+/// Converts the public `input` into the `uint256` array passed to the verifier contract.
+#[inline]
+pub fn public_inputs(input: &[Fr]) -> Vec<Uint256> {
+    input.iter().map(uint256::<Fr>).collect()
+}
+
+/// Ethereum Verifying Key
+///
+/// The verifier contract checks the pairing product
+/// `e(A, B) * e(-alpha, beta) * e(-vk_x, gamma) * e(-C, delta) = 1` where
+/// `vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1])`, so `alpha` is stored negated to save
+/// the contract a point negation.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct VerifyingKey {
+    /// Negated `alpha` in G1
+    pub negated_alpha: G1,
+
+    /// `beta` in G2
+    pub beta: G2,
+
+    /// `gamma` in G2
+    pub gamma: G2,
+
+    /// `delta` in G2
+    pub delta: G2,
+
+    /// Public Input Commitment Bases in G1
+    ///
+    /// This vector has one more element than the number of public inputs.
+    pub gamma_abc: Vec<G1>,
+}
+
+impl VerifyingKey {
+    /// Builds the [`VerifyingKey`] export of `context`.
+    #[inline]
+    pub fn from_context(context: &VerifyingContext<Bn254>) -> Self {
+        Self::from(&context.0.vk)
+    }
+
+    /// Returns the number of public inputs the verifying key accepts.
+    #[inline]
+    pub fn input_len(&self) -> usize {
+        self.gamma_abc.len().saturating_sub(1)
+    }
+}
+
+impl From<&groth16::VerifyingKey<Bn254>> for VerifyingKey {
+    #[inline]
+    fn from(key: &groth16::VerifyingKey<Bn254>) -> Self {
+        Self {
+            negated_alpha: g1(&-key.alpha_g1),
+            beta: g2(&key.beta_g2),
+            gamma: g2(&key.gamma_g2),
+            delta: g2(&key.delta_g2),
+            gamma_abc: key.gamma_abc_g1.iter().map(g1).collect(),
+        }
+    }
+}
+
+/// Ethereum Proof
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Proof {
+    /// `A` in G1
+    pub a: G1,
+
+    /// `B` in G2
+    pub b: G2,
+
+    /// `C` in G1
+    pub c: G1,
+}
+
+impl Proof {
+    /// Returns the proof as the `uint256[8]` calldata array `[a, b, c]` expected by the verifier
+    /// contract.
+    #[inline]
+    pub fn to_calldata(&self) -> [Uint256; 8] {
+        let [a_x, a_y] = self.a;
+        let [[b_x1, b_x0], [b_y1, b_y0]] = self.b;
+        let [c_x, c_y] = self.c;
+        [a_x, a_y, b_x1, b_x0, b_y1, b_y0, c_x, c_y]
+    }
+}
+
+impl From<&groth16::Proof<Bn254>> for Proof {
+    #[inline]
+    fn from(proof: &groth16::Proof<Bn254>) -> Self {
+        Self::from(&proof.0)
+    }
+}
+
+impl From<&ark_groth16::Proof<Bn254>> for Proof {
+    #[inline]
+    fn from(proof: &ark_groth16::Proof<Bn254>) -> Self {
+        Self {
+            a: g1(&proof.a),
+            b: g2(&proof.b),
+            c: g1(&proof.c),
+        }
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ec::AffineCurve, ff::Zero};
+
+    /// Checks that the BN254 generators are encoded as in EIP-197.
+    #[test]
+    fn generators_match_eip197() {
+        let mut one = [0; 32];
+        one[31] = 1;
+        let mut two = [0; 32];
+        two[31] = 2;
+        assert_eq!(g1(&G1Affine::prime_subgroup_generator()), [one, two]);
+        let generator = G2Affine::prime_subgroup_generator();
+        assert_eq!(
+            g2(&generator),
+            [
+                [uint256(&generator.x.c1), uint256(&generator.x.c0)],
+                [uint256(&generator.y.c1), uint256(&generator.y.c0)],
+            ]
+        );
+        assert_eq!(g1(&G1Affine::zero()), [[0; 32]; 2]);
+        assert_eq!(
+            g1(&-G1Affine::prime_subgroup_generator())[0],
+            one,
+            "Negation must only change the y-coordinate."
+        );
+    }
+}
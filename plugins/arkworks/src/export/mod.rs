@@ -0,0 +1,8 @@
+//! Proof System Exports
+//!
+//! This module contains conversions from the arkworks proof systems into the formats expected by
+//! external verifiers.
+
+#[cfg(feature = "bn254")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bn254")))]
+pub mod ethereum;
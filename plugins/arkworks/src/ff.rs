@@ -44,7 +44,7 @@ mod test {
     use crate::bn254::Fr;
     use alloc::vec::Vec;
     use core::fmt::Debug;
-    use openzl_util::rand::{OsRng, Rand, RngCore, Sample};
+    use openzl_util::rand::{Rand, RngCore, Sample, TestRng};
 
     /// Asserts that a single conversion of `value` specified by `convert` is correct.
     #[inline]
@@ -89,7 +89,7 @@ mod test {
                 assert_valid_integer_conversions::<Fr, _, _, _, 0xFFFF>(
                     $convert,
                     vec![0, 1, 2, $type::MAX - 2, $type::MAX - 1, $type::MAX],
-                    &mut OsRng,
+                    &mut TestRng::new(),
                 );
             }
         };
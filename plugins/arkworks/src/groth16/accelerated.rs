@@ -0,0 +1,316 @@
+//! Accelerated Groth16 Proving
+//!
+//! Most of the time spent in the Groth16 prover goes into the multi-scalar multiplications over the
+//! proving key. The prover in this module computes the same proofs as the `arkworks` prover but
+//! submits every multi-scalar multiplication as an [`Msm`] job to an [`Accelerator`], so that GPU
+//! backends can be plugged in without forking this crate.
+
+use crate::{
+    constraint::R1CS,
+    ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve},
+    ff::{PrimeField, UniformRand, Zero},
+    groth16::{Error, Proof, ProvingContext, ProvingKey},
+    poly::{EvaluationDomain, GeneralEvaluationDomain},
+    relations::r1cs::{ConstraintMatrices, SynthesisError},
+};
+use alloc::{vec, vec::Vec};
+use openzl_util::{
+    accelerator::{Accelerator, Execute, Job},
+    rand::{CryptoRng, RngCore, SizedRng},
+};
+
+/// Scalar Representation of the Scalar Field of `G`
+pub type BigInt<G> = <<G as AffineCurve>::ScalarField as PrimeField>::BigInt;
+
+/// Multi-Scalar Multiplication Job
+///
+/// This [`Job`] computes the sum of `scalars[i] * bases[i]` over all `i` for which both the base
+/// and the scalar exist.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Msm<'j, G>
+where
+    G: AffineCurve,
+{
+    /// Bases
+    pub bases: &'j [G],
+
+    /// Scalars
+    pub scalars: &'j [BigInt<G>],
+}
+
+impl<'j, G> Msm<'j, G>
+where
+    G: AffineCurve,
+{
+    /// Builds a new [`Msm`] job over `bases` and `scalars`.
+    #[inline]
+    pub fn new(bases: &'j [G], scalars: &'j [BigInt<G>]) -> Self {
+        Self { bases, scalars }
+    }
+}
+
+impl<'j, G> Job for Msm<'j, G>
+where
+    G: AffineCurve,
+{
+    type Output = G::Projective;
+}
+
+impl<'j, G> Execute for Msm<'j, G>
+where
+    G: AffineCurve,
+{
+    #[inline]
+    fn execute(self) -> Self::Output {
+        VariableBaseMSM::multi_scalar_mul(self.bases, self.scalars)
+    }
+}
+
+/// Runs the multi-scalar multiplication of `bases` and `scalars` on `accelerator`.
+#[inline]
+fn msm<G, A>(
+    accelerator: &mut A,
+    bases: &[G],
+    scalars: &[BigInt<G>],
+) -> Result<G::Projective, Error>
+where
+    G: AffineCurve,
+    A: for<'j> Accelerator<Msm<'j, G>>,
+{
+    openzl_util::trace_span!("openzl::groth16::msm", size = scalars.len());
+    accelerator.run(Msm::new(bases, scalars)).map_err(|_| Error)
+}
+
+/// Computes `initial + query[0] + vk_param` plus the multi-scalar multiplication of the rest of
+/// `query` with `assignment` on `accelerator`.
+#[inline]
+fn calculate_coefficient<G, A>(
+    accelerator: &mut A,
+    initial: G::Projective,
+    query: &[G],
+    vk_param: G,
+    assignment: &[BigInt<G>],
+) -> Result<G::Projective, Error>
+where
+    G: AffineCurve,
+    A: for<'j> Accelerator<Msm<'j, G>>,
+{
+    let (first, rest) = query.split_first().ok_or(Error)?;
+    let mut result = initial;
+    result.add_assign_mixed(first);
+    result += &msm(accelerator, rest, assignment)?;
+    result.add_assign_mixed(&vk_param);
+    Ok(result)
+}
+
+/// Evaluates the linear combination `terms` over `assignment`.
+#[inline]
+fn evaluate_constraint<F>(terms: &[(F, usize)], assignment: &[F]) -> F
+where
+    F: PrimeField,
+{
+    terms.iter().fold(F::zero(), |acc, (coefficient, index)| {
+        acc + assignment[*index] * coefficient
+    })
+}
+
+/// Computes the coefficients of the quotient polynomial `h` of the QAP reduction of `matrices` and
+/// `assignment` with the same domain and coset as the `arkworks` prover.
+#[inline]
+fn witness_map<F>(
+    matrices: &ConstraintMatrices<F>,
+    assignment: &[F],
+) -> Result<Vec<F>, SynthesisError>
+where
+    F: PrimeField,
+{
+    let num_inputs = matrices.num_instance_variables;
+    let num_constraints = matrices.num_constraints;
+    let domain = GeneralEvaluationDomain::<F>::new(num_constraints + num_inputs)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let mut a = vec![F::zero(); domain.size()];
+    let mut b = vec![F::zero(); domain.size()];
+    for (i, (a_i, b_i)) in matrices.a.iter().zip(&matrices.b).enumerate() {
+        a[i] = evaluate_constraint(a_i, assignment);
+        b[i] = evaluate_constraint(b_i, assignment);
+    }
+    a[num_constraints..num_constraints + num_inputs].clone_from_slice(&assignment[..num_inputs]);
+    domain.ifft_in_place(&mut a);
+    domain.ifft_in_place(&mut b);
+    domain.coset_fft_in_place(&mut a);
+    domain.coset_fft_in_place(&mut b);
+    let mut ab = domain.mul_polynomials_in_evaluation_domain(&a, &b);
+    drop(a);
+    drop(b);
+    let mut c = vec![F::zero(); domain.size()];
+    for (c_i, terms) in c.iter_mut().zip(&matrices.c) {
+        *c_i = evaluate_constraint(terms, assignment);
+    }
+    domain.ifft_in_place(&mut c);
+    domain.coset_fft_in_place(&mut c);
+    for (ab_i, c_i) in ab.iter_mut().zip(c) {
+        *ab_i -= &c_i;
+    }
+    domain.divide_by_vanishing_poly_on_coset_in_place(&mut ab);
+    domain.coset_ifft_in_place(&mut ab);
+    Ok(ab)
+}
+
+/// Generates a Groth16 proof for the constraints and assignment in `compiler` with the proving key
+/// in `context`, running every multi-scalar multiplication on `accelerator`.
+#[inline]
+pub fn prove_with<E, R, A>(
+    context: &ProvingContext<E>,
+    mut compiler: R1CS<E::Fr>,
+    rng: &mut R,
+    accelerator: &mut A,
+) -> Result<Proof<E>, Error>
+where
+    E: PairingEngine,
+    R: CryptoRng + RngCore + ?Sized,
+    A: for<'j> Accelerator<Msm<'j, E::G1Affine>> + for<'j> Accelerator<Msm<'j, E::G2Affine>>,
+{
+    openzl_util::trace_span!("openzl::groth16::prove");
+    let matrices = {
+        openzl_util::trace_span!("openzl::r1cs::matrices");
+        compiler.finalize();
+        compiler.0.to_matrices().ok_or(Error)?
+    };
+    let prover = compiler.0.borrow().ok_or(Error)?;
+    prove_assignment(
+        &context.0,
+        &matrices,
+        &prover.instance_assignment,
+        &prover.witness_assignment,
+        rng,
+        accelerator,
+    )
+}
+
+/// Generates a Groth16 proof for the constraint `matrices` with the `instance` and `witness`
+/// assignments and the `proving_key`, running every multi-scalar multiplication on `accelerator`.
+///
+/// The `instance` assignment starts with the constant one variable, matching the layout of the
+/// `arkworks` constraint system.
+#[inline]
+pub(crate) fn prove_assignment<E, R, A>(
+    proving_key: &ProvingKey<E>,
+    matrices: &ConstraintMatrices<E::Fr>,
+    instance: &[E::Fr],
+    witness: &[E::Fr],
+    rng: &mut R,
+    accelerator: &mut A,
+) -> Result<Proof<E>, Error>
+where
+    E: PairingEngine,
+    R: CryptoRng + RngCore + ?Sized,
+    A: for<'j> Accelerator<Msm<'j, E::G1Affine>> + for<'j> Accelerator<Msm<'j, E::G2Affine>>,
+{
+    if instance.len() != matrices.num_instance_variables
+        || witness.len() != matrices.num_witness_variables
+    {
+        return Err(Error);
+    }
+    openzl_util::trace_span!(
+        "openzl::groth16::prove_assignment",
+        constraints = matrices.num_constraints,
+        variables = instance.len() + witness.len(),
+    );
+    let mut rng = SizedRng(rng);
+    let r = E::Fr::rand(&mut rng);
+    let s = E::Fr::rand(&mut rng);
+    let h = {
+        openzl_util::trace_span!("openzl::groth16::witness_map");
+        witness_map(matrices, &[instance, witness].concat()).map_err(|_| Error)?
+    };
+    let h_assignment = h.into_iter().map(|h| h.into_repr()).collect::<Vec<_>>();
+    let input_assignment = instance[1..]
+        .iter()
+        .map(|input| input.into_repr())
+        .collect::<Vec<_>>();
+    let aux_assignment = witness
+        .iter()
+        .map(|witness| witness.into_repr())
+        .collect::<Vec<_>>();
+    let assignment = [input_assignment.as_slice(), aux_assignment.as_slice()].concat();
+    let h_acc = msm(accelerator, &proving_key.h_query, &h_assignment)?;
+    let l_aux_acc = msm(accelerator, &proving_key.l_query, &aux_assignment)?;
+    let delta_g1 = proving_key.delta_g1.into_projective();
+    let r_s_delta_g1 = delta_g1.mul(r.into_repr()).mul(s.into_repr());
+    let g_a = calculate_coefficient(
+        accelerator,
+        delta_g1.mul(r.into_repr()),
+        &proving_key.a_query,
+        proving_key.vk.alpha_g1,
+        &assignment,
+    )?;
+    let g1_b = if r.is_zero() {
+        E::G1Projective::zero()
+    } else {
+        calculate_coefficient(
+            accelerator,
+            delta_g1.mul(s.into_repr()),
+            &proving_key.b_g1_query,
+            proving_key.beta_g1,
+            &assignment,
+        )?
+    };
+    let g2_b = calculate_coefficient(
+        accelerator,
+        proving_key.vk.delta_g2.into_projective().mul(s.into_repr()),
+        &proving_key.b_g2_query,
+        proving_key.vk.beta_g2,
+        &assignment,
+    )?;
+    let mut g_c = g_a.mul(s.into_repr());
+    g_c += &g1_b.mul(r.into_repr());
+    g_c -= &r_s_delta_g1;
+    g_c += &l_aux_acc;
+    g_c += &h_acc;
+    Ok(Proof(ark_groth16::Proof {
+        a: g_a.into_affine(),
+        b: g2_b.into_affine(),
+        c: g_c.into_affine(),
+    }))
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        bn254::{Bn254, Fr},
+        constraint::fp::Fp,
+        groth16::{cube::cube_circuit, Groth16},
+    };
+    use openzl_crypto::constraint::ProofSystem;
+    use openzl_util::{
+        accelerator::Cpu,
+        rand::{Sample, TestRng},
+    };
+
+    /// Tests that proofs generated on the CPU reference accelerator verify.
+    #[test]
+    fn cpu_accelerated_proofs_verify() {
+        let mut rng = TestRng::new();
+        let mut compiler = Groth16::<Bn254>::context_compiler();
+        cube_circuit(None, None, &mut compiler);
+        let (proving_context, verifying_context) =
+            Groth16::<Bn254>::compile(&(), compiler, &mut rng).expect("Unable to compile circuit.");
+        let x = Fp::<Fr>::gen(&mut rng);
+        let output = Fp(x.0 * x.0 * x.0);
+        let mut compiler = Groth16::<Bn254>::proof_compiler();
+        cube_circuit(Some(&x), Some(&output), &mut compiler);
+        let proof = prove_with(&proving_context, compiler, &mut rng, &mut Cpu)
+            .expect("Unable to generate proof.");
+        assert!(
+            Groth16::<Bn254>::verify(&verifying_context, &vec![output.0], &proof)
+                .expect("Unable to verify proof."),
+            "Accelerated proofs should be valid."
+        );
+        assert!(
+            !Groth16::<Bn254>::verify(&verifying_context, &vec![x.0], &proof)
+                .expect("Unable to verify proof."),
+            "Accelerated proofs should not be valid for other inputs."
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Cached Groth16 Proving
+//!
+//! The [`ProofSystem`] implementation for [`Groth16`] receives a fully synthesized constraint
+//! system for every proof, which means that the constraint matrices are rebuilt and inlined each
+//! time even though they never change between proofs of the same circuit. The
+//! [`ProvingKeyedCircuit`] in this module keeps the constraint matrices computed at setup time next
+//! to the proving key, so that proofs only need the variable assignment which can be computed with
+//! the cheaper [`R1CS::for_witnesses`] compiler.
+
+use crate::{
+    constraint::R1CS,
+    ec::PairingEngine,
+    groth16::{
+        accelerated::{prove_assignment, Msm},
+        Error, Groth16, Proof, ProvingContext, VerifyingContext,
+    },
+    relations::r1cs::ConstraintMatrices,
+};
+use openzl_crypto::constraint::ProofSystem;
+use openzl_util::{
+    accelerator::{Accelerator, Cpu},
+    derivative,
+    rand::{CryptoRng, RngCore},
+};
+
+/// Proving-Keyed Circuit
+///
+/// This type pairs a [`ProvingContext`] with the constraint matrices of the circuit it was
+/// generated for. See the [module-level documentation](self) for more.
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Debug)]
+pub struct ProvingKeyedCircuit<E>
+where
+    E: PairingEngine,
+{
+    /// Proving Context
+    context: ProvingContext<E>,
+
+    /// Constraint Matrices
+    matrices: ConstraintMatrices<E::Fr>,
+}
+
+impl<E> ProvingKeyedCircuit<E>
+where
+    E: PairingEngine,
+{
+    /// Builds a new [`ProvingKeyedCircuit`] from `context` and the constraints in `compiler`,
+    /// returning an error if `compiler` does not record constraint matrices.
+    ///
+    /// The `compiler` should be built from [`Groth16::context_compiler`] with the same circuit
+    /// that was used to generate `context`.
+    #[inline]
+    pub fn new(context: ProvingContext<E>, mut compiler: R1CS<E::Fr>) -> Result<Self, Error> {
+        openzl_util::trace_span!("openzl::r1cs::matrices");
+        compiler.finalize();
+        Ok(Self {
+            context,
+            matrices: compiler.0.to_matrices().ok_or(Error)?,
+        })
+    }
+
+    /// Generates the proving and verifying contexts for the constraints in `compiler` like
+    /// [`ProofSystem::compile`], caching the constraint matrices alongside the proving context.
+    #[inline]
+    pub fn setup<R>(
+        mut compiler: R1CS<E::Fr>,
+        rng: &mut R,
+    ) -> Result<(Self, VerifyingContext<E>), Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let matrices = {
+            openzl_util::trace_span!("openzl::r1cs::matrices");
+            compiler.finalize();
+            compiler.0.to_matrices().ok_or(Error)?
+        };
+        let (context, verifying_context) = Groth16::<E>::compile(&(), compiler, rng)?;
+        Ok((Self { context, matrices }, verifying_context))
+    }
+
+    /// Returns the proving context for `self`.
+    #[inline]
+    pub fn context(&self) -> &ProvingContext<E> {
+        &self.context
+    }
+
+    /// Returns the cached constraint matrices for `self`.
+    #[inline]
+    pub fn matrices(&self) -> &ConstraintMatrices<E::Fr> {
+        &self.matrices
+    }
+
+    /// Returns the number of constraints in the cached circuit.
+    #[inline]
+    pub fn num_constraints(&self) -> usize {
+        self.matrices.num_constraints
+    }
+
+    /// Returns a new compiler for computing the variable assignment of a proof.
+    ///
+    /// See [`R1CS::for_witnesses`] for more.
+    #[inline]
+    pub fn witness_compiler() -> R1CS<E::Fr> {
+        R1CS::for_witnesses()
+    }
+
+    /// Generates a proof for the variable assignment in `compiler` against the cached constraint
+    /// matrices, returning an error if the assignment does not fit the cached circuit.
+    ///
+    /// The `compiler` is expected to be built from [`witness_compiler`](Self::witness_compiler),
+    /// although any compiler with known variables can be used. The constraints themselves are not
+    /// checked, so an assignment which does not satisfy them yields an invalid proof.
+    #[inline]
+    pub fn prove_with_witness<R>(
+        &self,
+        compiler: R1CS<E::Fr>,
+        rng: &mut R,
+    ) -> Result<Proof<E>, Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        self.prove_with_witness_on(compiler, rng, &mut Cpu)
+    }
+
+    /// Generates a proof like [`prove_with_witness`](Self::prove_with_witness) but runs every
+    /// multi-scalar multiplication on `accelerator`.
+    #[inline]
+    pub fn prove_with_witness_on<R, A>(
+        &self,
+        compiler: R1CS<E::Fr>,
+        rng: &mut R,
+        accelerator: &mut A,
+    ) -> Result<Proof<E>, Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+        A: for<'j> Accelerator<Msm<'j, E::G1Affine>> + for<'j> Accelerator<Msm<'j, E::G2Affine>>,
+    {
+        openzl_util::trace_span!("openzl::groth16::prove");
+        let prover = compiler.0.borrow().ok_or(Error)?;
+        prove_assignment(
+            &self.context.0,
+            &self.matrices,
+            &prover.instance_assignment,
+            &prover.witness_assignment,
+            rng,
+            accelerator,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        bn254::{Bn254, Fr},
+        constraint::{fp::Fp, FpVar},
+        groth16::cube::cube_circuit,
+    };
+    use alloc::vec;
+    use eclair::alloc::{mode::Public, Allocate};
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Tests that repeated proofs from cached constraint matrices verify.
+    #[test]
+    fn cached_proofs_verify() {
+        let mut rng = TestRng::new();
+        let mut compiler = Groth16::<Bn254>::context_compiler();
+        cube_circuit(None, None, &mut compiler);
+        let (circuit, verifying_context) = ProvingKeyedCircuit::<Bn254>::setup(compiler, &mut rng)
+            .expect("Unable to compile circuit.");
+        for _ in 0..3 {
+            let x = Fp::<Fr>::gen(&mut rng);
+            let output = Fp(x.0 * x.0 * x.0);
+            let mut compiler = ProvingKeyedCircuit::<Bn254>::witness_compiler();
+            cube_circuit(Some(&x), Some(&output), &mut compiler);
+            let proof = circuit
+                .prove_with_witness(compiler, &mut rng)
+                .expect("Unable to generate proof.");
+            assert!(
+                Groth16::<Bn254>::verify(&verifying_context, &vec![output.0], &proof)
+                    .expect("Unable to verify proof."),
+                "Cached proofs should be valid."
+            );
+            assert!(
+                !Groth16::<Bn254>::verify(&verifying_context, &vec![x.0], &proof)
+                    .expect("Unable to verify proof."),
+                "Cached proofs should not be valid for other inputs."
+            );
+        }
+    }
+
+    /// Tests that assignments which do not match the cached circuit are rejected.
+    #[test]
+    fn mismatched_assignment_is_rejected() {
+        let mut rng = TestRng::new();
+        let mut compiler = Groth16::<Bn254>::context_compiler();
+        cube_circuit(None, None, &mut compiler);
+        let (circuit, _) = ProvingKeyedCircuit::<Bn254>::setup(compiler, &mut rng)
+            .expect("Unable to compile circuit.");
+        let x = Fp::<Fr>::gen(&mut rng);
+        let mut compiler = ProvingKeyedCircuit::<Bn254>::witness_compiler();
+        let _: FpVar<Fr> = x.as_known::<Public, _>(&mut compiler);
+        assert_eq!(
+            circuit.prove_with_witness(compiler, &mut rng),
+            Err(Error),
+            "Assignments with the wrong shape should be rejected."
+        );
+    }
+}
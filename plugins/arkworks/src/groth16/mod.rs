@@ -0,0 +1,1104 @@
+//! Groth16 Proof System
+//!
+//! With only the `verify` feature, this module contains the proof and verifying key types, their
+//! serialization, and proof verification. The `groth16` feature adds the
+//! [`ProofSystem`](openzl_crypto::constraint::ProofSystem) implementation for [`Groth16`] along
+//! with the provers and the recursion gadgets.
+//!
+//! # Limitations
+//!
+//! The `verify` feature only trims the API of this crate. Verification is implemented by
+//! `ark-groth16` 0.3, which has no feature to leave out its prover, so the `ark-groth16` prover and
+//! its `ark-poly` and `ark-relations` dependencies are still compiled into verifier-only builds.
+
+use crate::ec::PairingEngine;
+use alloc::vec::Vec;
+use ark_groth16::{PreparedVerifyingKey, ProvingKey, VerifyingKey};
+use core::marker::PhantomData;
+use openzl_util::{
+    derivative,
+    rand::{CryptoRng, RngCore, SizedRng},
+};
+
+#[cfg(feature = "groth16")]
+use {
+    crate::{constraint::R1CS, groth16::accelerated::Msm},
+    ark_groth16::Groth16 as ArkGroth16,
+    openzl_crypto::constraint::{Input, ProofSystem, Rerandomize},
+    openzl_util::accelerator::Accelerator,
+    snark::SNARK,
+};
+
+#[cfg(feature = "serde")]
+use {
+    crate::serialize::{canonical_deserialize, canonical_serialize},
+    openzl_util::serde::{Deserialize, Deserializer, Serialize, Serializer},
+};
+
+#[cfg(feature = "serialize")]
+use {
+    crate::serialize::{
+        CanonicalDeserialize, CanonicalSerialize, HasDeserialization, HasSerialization, Read,
+        SerializationError, Write,
+    },
+    openzl_util::codec,
+};
+
+#[cfg(all(feature = "ark-std", feature = "serialize"))]
+use {
+    crate::serialize::{ArkReader, ArkWriter},
+    openzl_util::codec::DecodeError,
+};
+
+#[cfg(feature = "groth16")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+pub mod accelerated;
+
+#[cfg(feature = "groth16")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+pub mod cached;
+
+#[cfg(all(feature = "alloc", feature = "groth16"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", feature = "groth16"))))]
+pub mod recursive;
+
+#[doc(inline)]
+pub use ark_groth16::*;
+
+/// Proof System Error
+///
+/// This is the error state of the [`Groth16`] proof system methods. This type is intentionally
+/// opaque so that error details are not revealed.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "openzl_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Error;
+
+/// Groth16 Proof
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "", serialize = ""),
+        crate = "openzl_util::serde",
+        deny_unknown_fields,
+        try_from = "Vec<u8>"
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Proof<E>(
+    /// Groth16 Proof
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_proof::<E, _>"))]
+    pub ark_groth16::Proof<E>,
+)
+where
+    E: PairingEngine;
+
+#[cfg(feature = "serialize")]
+impl<E> codec::Encode for Proof<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: codec::Write,
+    {
+        proof_as_bytes(&self.0).encode(writer)
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        core::mem::size_of::<u64>() + self.0.serialized_size()
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<E> TryFrom<Vec<u8>> for Proof<E>
+where
+    E: PairingEngine,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        CanonicalDeserialize::deserialize(&mut bytes.as_slice()).map(Self)
+    }
+}
+
+/// Converts `proof` into its canonical byte-representation.
+#[cfg(feature = "serialize")]
+#[inline]
+pub fn proof_as_bytes<E>(proof: &ark_groth16::Proof<E>) -> Vec<u8>
+where
+    E: PairingEngine,
+{
+    let mut buffer = Vec::with_capacity(proof.serialized_size());
+    proof
+        .serialize(&mut buffer)
+        .expect("Serialization is not allowed to fail.");
+    buffer
+}
+
+/// Uses `serializer` to serialize `proof`.
+#[cfg(feature = "serde")]
+#[inline]
+fn serialize_proof<E, S>(proof: &ark_groth16::Proof<E>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    E: PairingEngine,
+    S: Serializer,
+{
+    serializer.serialize_bytes(&proof_as_bytes::<E>(proof))
+}
+
+/// Proving Context
+///
+/// With the `serde` feature, the proving key is serialized with the compressed
+/// [`CanonicalSerialize`] encoding and is validated on deserialization.
+#[cfg_attr(
+    feature = "serialize",
+    derive(CanonicalDeserialize, CanonicalSerialize)
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "", serialize = ""),
+        crate = "openzl_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Debug, Eq, PartialEq)]
+pub struct ProvingContext<E>(
+    /// Proving Key
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "canonical_serialize",
+            deserialize_with = "canonical_deserialize"
+        )
+    )]
+    pub ProvingKey<E>,
+)
+where
+    E: PairingEngine;
+
+impl<E> ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    /// Builds a new [`ProvingContext`] from `proving_key`.
+    #[inline]
+    pub fn new(proving_key: ProvingKey<E>) -> Self {
+        Self(proving_key)
+    }
+}
+
+#[cfg(all(feature = "ark-std", feature = "serialize"))]
+impl<E> codec::Decode for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        let mut reader = ArkReader::new(reader);
+        match CanonicalDeserialize::deserialize_unchecked(&mut reader) {
+            Ok(value) => reader
+                .finish()
+                .map(move |_| Self(value))
+                .map_err(DecodeError::Read),
+            Err(err) => Err(DecodeError::Decode(err)),
+        }
+    }
+}
+
+#[cfg(all(feature = "ark-std", feature = "serialize"))]
+impl<E> codec::Encode for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: codec::Write,
+    {
+        let mut writer = ArkWriter::new(writer);
+        let _ = self.0.serialize_unchecked(&mut writer);
+        writer.finish().map(move |_| ())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        self.0.uncompressed_size()
+    }
+}
+
+/// Verifying Context
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Debug, Default)]
+pub struct VerifyingContext<E>(pub PreparedVerifyingKey<E>)
+where
+    E: PairingEngine;
+
+impl<E> VerifyingContext<E>
+where
+    E: PairingEngine,
+{
+    /// Builds a new [`VerifyingContext`] by preparing `verifying_key` for pairing checks.
+    #[inline]
+    pub fn from_verifying_key(verifying_key: &VerifyingKey<E>) -> Self {
+        Self(ark_groth16::prepare_verifying_key(verifying_key))
+    }
+
+    /// Returns the number of public input field elements expected by this context.
+    #[inline]
+    pub fn input_len(&self) -> usize {
+        self.0.vk.gamma_abc_g1.len().saturating_sub(1)
+    }
+}
+
+#[cfg(feature = "serialize")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serialize")))]
+impl<E> CanonicalSerialize for VerifyingContext<E>
+where
+    E: PairingEngine,
+    for<'s> E::G2Prepared: HasSerialization<'s>,
+{
+    #[inline]
+    fn serialize<W>(&self, mut writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        let PreparedVerifyingKey {
+            vk,
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+        } = &self.0;
+        vk.serialize(&mut writer)?;
+        alpha_g1_beta_g2.serialize(&mut writer)?;
+        <E::G2Prepared as HasSerialization<'_>>::Serialize::from(gamma_g2_neg_pc)
+            .serialize(&mut writer)?;
+        <E::G2Prepared as HasSerialization<'_>>::Serialize::from(delta_g2_neg_pc)
+            .serialize(&mut writer)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        let PreparedVerifyingKey {
+            vk,
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+        } = &self.0;
+        vk.serialized_size()
+            + alpha_g1_beta_g2.serialized_size()
+            + <E::G2Prepared as HasSerialization<'_>>::Serialize::from(gamma_g2_neg_pc)
+                .serialized_size()
+            + <E::G2Prepared as HasSerialization<'_>>::Serialize::from(delta_g2_neg_pc)
+                .serialized_size()
+    }
+
+    #[inline]
+    fn serialize_uncompressed<W>(&self, mut writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        let PreparedVerifyingKey {
+            vk,
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+        } = &self.0;
+        vk.serialize_uncompressed(&mut writer)?;
+        alpha_g1_beta_g2.serialize_uncompressed(&mut writer)?;
+        <E::G2Prepared as HasSerialization<'_>>::Serialize::from(gamma_g2_neg_pc)
+            .serialize_uncompressed(&mut writer)?;
+        <E::G2Prepared as HasSerialization<'_>>::Serialize::from(delta_g2_neg_pc)
+            .serialize_uncompressed(&mut writer)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_unchecked<W>(&self, mut writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        let PreparedVerifyingKey {
+            vk,
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+        } = &self.0;
+        vk.serialize_unchecked(&mut writer)?;
+        alpha_g1_beta_g2.serialize_unchecked(&mut writer)?;
+        <E::G2Prepared as HasSerialization<'_>>::Serialize::from(gamma_g2_neg_pc)
+            .serialize_unchecked(&mut writer)?;
+        <E::G2Prepared as HasSerialization<'_>>::Serialize::from(delta_g2_neg_pc)
+            .serialize_unchecked(&mut writer)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn uncompressed_size(&self) -> usize {
+        let PreparedVerifyingKey {
+            vk,
+            alpha_g1_beta_g2,
+            gamma_g2_neg_pc,
+            delta_g2_neg_pc,
+        } = &self.0;
+        vk.uncompressed_size()
+            + alpha_g1_beta_g2.uncompressed_size()
+            + <E::G2Prepared as HasSerialization<'_>>::Serialize::from(gamma_g2_neg_pc)
+                .uncompressed_size()
+            + <E::G2Prepared as HasSerialization<'_>>::Serialize::from(delta_g2_neg_pc)
+                .uncompressed_size()
+    }
+}
+
+#[cfg(feature = "serialize")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serialize")))]
+impl<E> CanonicalDeserialize for VerifyingContext<E>
+where
+    E: PairingEngine,
+    E::G2Prepared: HasDeserialization,
+{
+    #[inline]
+    fn deserialize<R>(mut reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        Ok(Self(PreparedVerifyingKey {
+            vk: CanonicalDeserialize::deserialize(&mut reader)?,
+            alpha_g1_beta_g2: CanonicalDeserialize::deserialize(&mut reader)?,
+            gamma_g2_neg_pc: <E::G2Prepared as HasDeserialization>::Deserialize::deserialize(
+                &mut reader,
+            )?
+            .into(),
+            delta_g2_neg_pc: <E::G2Prepared as HasDeserialization>::Deserialize::deserialize(
+                &mut reader,
+            )?
+            .into(),
+        }))
+    }
+
+    #[inline]
+    fn deserialize_uncompressed<R>(mut reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        Ok(Self(PreparedVerifyingKey {
+            vk: CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+            alpha_g1_beta_g2: CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+            gamma_g2_neg_pc:
+                <E::G2Prepared as HasDeserialization>::Deserialize::deserialize_uncompressed(
+                    &mut reader,
+                )?
+                .into(),
+            delta_g2_neg_pc:
+                <E::G2Prepared as HasDeserialization>::Deserialize::deserialize_uncompressed(
+                    &mut reader,
+                )?
+                .into(),
+        }))
+    }
+
+    #[inline]
+    fn deserialize_unchecked<R>(mut reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        Ok(Self(PreparedVerifyingKey {
+            vk: CanonicalDeserialize::deserialize_unchecked(&mut reader)?,
+            alpha_g1_beta_g2: CanonicalDeserialize::deserialize_unchecked(&mut reader)?,
+            gamma_g2_neg_pc:
+                <E::G2Prepared as HasDeserialization>::Deserialize::deserialize_unchecked(
+                    &mut reader,
+                )?
+                .into(),
+            delta_g2_neg_pc:
+                <E::G2Prepared as HasDeserialization>::Deserialize::deserialize_unchecked(
+                    &mut reader,
+                )?
+                .into(),
+        }))
+    }
+}
+
+#[cfg(all(feature = "ark-std", feature = "serde"))]
+impl<E> codec::Decode for VerifyingContext<E>
+where
+    E: PairingEngine,
+    E::G2Prepared: HasDeserialization,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        let mut reader = ArkReader::new(reader);
+        match CanonicalDeserialize::deserialize(&mut reader) {
+            Ok(value) => reader
+                .finish()
+                .map(move |_| value)
+                .map_err(DecodeError::Read),
+            Err(err) => Err(DecodeError::Decode(err)),
+        }
+    }
+}
+
+#[cfg(all(feature = "ark-std", feature = "serde"))]
+impl<E> codec::Encode for VerifyingContext<E>
+where
+    E: PairingEngine,
+    for<'s> E::G2Prepared: HasSerialization<'s>,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: codec::Write,
+    {
+        let mut writer = ArkWriter::new(writer);
+        let _ = CanonicalSerialize::serialize(self, &mut writer);
+        writer.finish().map(move |_| ())
+    }
+
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        CanonicalSerialize::serialized_size(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<E> Serialize for VerifyingContext<E>
+where
+    E: PairingEngine,
+    for<'s> E::G2Prepared: HasSerialization<'s>,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        canonical_serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<'de, E> Deserialize<'de> for VerifyingContext<E>
+where
+    E: PairingEngine,
+    E::G2Prepared: HasDeserialization,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        canonical_deserialize(deserializer)
+    }
+}
+
+/// Rerandomizes `proof` for the verifying `context`, returning a fresh proof of the same statement
+/// which cannot be linked to `proof`.
+///
+/// Relayers which broadcast proofs on behalf of their users can rerandomize them before every
+/// broadcast so that re-submissions of the same proof cannot be tracked. The rerandomized proof
+/// verifies against the same public input as `proof`. Rerandomizing an invalid proof gives an
+/// invalid proof.
+#[inline]
+pub fn rerandomize_proof<E, R>(
+    proof: &Proof<E>,
+    context: &VerifyingContext<E>,
+    rng: &mut R,
+) -> Proof<E>
+where
+    E: PairingEngine,
+    R: CryptoRng + RngCore + ?Sized,
+{
+    Proof(ark_groth16::rerandomize_proof(
+        &mut SizedRng(rng),
+        &context.0.vk,
+        &proof.0,
+    ))
+}
+
+/// Arkworks Groth16 Proof System
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Groth16<E>(PhantomData<E>)
+where
+    E: PairingEngine;
+
+impl<E> Groth16<E>
+where
+    E: PairingEngine,
+{
+    /// Verifies that `proof` is valid for the public `input` under the verifying `context`,
+    /// returning an error if `input` does not have the length expected by `context`.
+    ///
+    /// This is the same check as `ProofSystem::verify` but it is available with only the `verify`
+    /// feature.
+    #[inline]
+    pub fn verify_proof(
+        context: &VerifyingContext<E>,
+        input: &[E::Fr],
+        proof: &Proof<E>,
+    ) -> Result<bool, Error> {
+        openzl_util::trace_span!("openzl::groth16::verify", inputs = input.len());
+        ark_groth16::verify_proof(&context.0, &proof.0, input).map_err(|_| Error)
+    }
+
+    /// Generates a proof like [`ProofSystem::prove`] but runs every multi-scalar multiplication on
+    /// `accelerator`. See [`accelerated::prove_with`] for more.
+    #[cfg(feature = "groth16")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+    #[inline]
+    pub fn prove_with<R, A>(
+        context: &ProvingContext<E>,
+        compiler: R1CS<E::Fr>,
+        rng: &mut R,
+        accelerator: &mut A,
+    ) -> Result<Proof<E>, Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+        A: for<'j> Accelerator<Msm<'j, E::G1Affine>> + for<'j> Accelerator<Msm<'j, E::G2Affine>>,
+    {
+        accelerated::prove_with(context, compiler, rng, accelerator)
+    }
+}
+
+#[cfg(feature = "groth16")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+impl<E> ProofSystem for Groth16<E>
+where
+    E: PairingEngine,
+{
+    type Compiler = R1CS<E::Fr>;
+    type PublicParameters = ();
+    type ProvingContext = ProvingContext<E>;
+    type VerifyingContext = VerifyingContext<E>;
+    type Input = Vec<E::Fr>;
+    type Proof = Proof<E>;
+    type Error = Error;
+
+    #[inline]
+    fn context_compiler() -> Self::Compiler {
+        Self::Compiler::for_contexts()
+    }
+
+    #[inline]
+    fn proof_compiler() -> Self::Compiler {
+        Self::Compiler::for_proofs()
+    }
+
+    #[inline]
+    fn compile<R>(
+        public_parameters: &Self::PublicParameters,
+        compiler: Self::Compiler,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingContext, Self::VerifyingContext), Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let _ = public_parameters;
+        openzl_util::trace_span!("openzl::groth16::setup");
+        let (proving_key, verifying_key) =
+            ArkGroth16::circuit_specific_setup(compiler, &mut SizedRng(rng)).map_err(|_| Error)?;
+        Ok((
+            ProvingContext(proving_key),
+            VerifyingContext::from_verifying_key(&verifying_key),
+        ))
+    }
+
+    #[inline]
+    fn prove<R>(
+        context: &Self::ProvingContext,
+        compiler: Self::Compiler,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        openzl_util::trace_span!("openzl::groth16::prove");
+        ArkGroth16::prove(&context.0, compiler, &mut SizedRng(rng))
+            .map(Proof)
+            .map_err(|_| Error)
+    }
+
+    #[inline]
+    fn verify(
+        context: &Self::VerifyingContext,
+        input: &Self::Input,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        Self::verify_proof(context, input, proof)
+    }
+}
+
+#[cfg(feature = "groth16")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+impl<E> Rerandomize for Groth16<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn rerandomize<R>(
+        context: &Self::VerifyingContext,
+        proof: &Self::Proof,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        Ok(rerandomize_proof(proof, context, rng))
+    }
+}
+
+/// Implements [`Input`] over [`Groth16`] for `$type` that can convert to a field element.
+#[cfg(feature = "groth16")]
+macro_rules! public_input_impl {
+    ($($type:tt),* $(,)?) => {
+        $(
+            impl<E> Input<Groth16<E>> for $type
+            where
+                E: PairingEngine,
+            {
+                #[inline]
+                fn extend(&self, input: &mut Vec<E::Fr>) {
+                    input.push((*self).into());
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "groth16")]
+public_input_impl!(bool, u8, u16, u32, u64, u128);
+
+/// Cube Test Circuit
+#[cfg(all(
+    test,
+    feature = "groth16",
+    any(
+        feature = "bn254",
+        all(feature = "alloc", feature = "bls12-377", feature = "bw6-761")
+    )
+))]
+mod cube {
+    use crate::{
+        constraint::{fp::Fp, FpVar, R1CS},
+        ff::PrimeField,
+    };
+    use eclair::{
+        alloc::{
+            mode::{Public, Secret},
+            Allocate, Allocator, Constant,
+        },
+        bool::AssertEq,
+    };
+    use openzl_crypto::statements::Statement;
+
+    /// Cube Statement
+    ///
+    /// Asserts that the public input is the cube of the secret witness.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Cube;
+
+    impl<F> Constant<R1CS<F>> for Cube
+    where
+        F: PrimeField,
+    {
+        type Type = Self;
+
+        #[inline]
+        fn new_constant(this: &Self::Type, compiler: &mut R1CS<F>) -> Self {
+            let _ = compiler;
+            *this
+        }
+    }
+
+    impl<F> Statement<R1CS<F>> for Cube
+    where
+        F: PrimeField,
+    {
+        type Public = FpVar<F>;
+        type Secret = FpVar<F>;
+
+        #[inline]
+        fn enforce(&self, public: &Self::Public, secret: &Self::Secret, compiler: &mut R1CS<F>) {
+            compiler.assert_eq(&(secret * secret * secret), public);
+        }
+    }
+
+    /// Builds the [`Cube`] circuit into `compiler`, allocating the secret `x` and the public
+    /// `output` as known variables if they are given and as unknown variables otherwise.
+    #[inline]
+    pub fn cube_circuit<F>(x: Option<&Fp<F>>, output: Option<&Fp<F>>, compiler: &mut R1CS<F>)
+    where
+        F: PrimeField,
+    {
+        let x: FpVar<F> = match x {
+            Some(x) => x.as_known::<Secret, _>(compiler),
+            _ => compiler.allocate_unknown::<Secret, _>(),
+        };
+        let output: FpVar<F> = match output {
+            Some(output) => output.as_known::<Public, _>(compiler),
+            _ => compiler.allocate_unknown::<Public, _>(),
+        };
+        Cube.enforce(&output, &x, compiler);
+    }
+}
+
+#[cfg(all(test, feature = "bn254", feature = "groth16"))]
+mod test {
+    use super::{cube::Cube, *};
+    use crate::{
+        bn254::{Bn254, Fr},
+        constraint::{fp::Fp, FpVar},
+    };
+    use openzl_crypto::statements::{self, Both, Circuit, RangeCheck, Statement};
+    use openzl_util::rand::TestRng;
+
+    /// Tests that a composed statement proves and verifies with its public input wired in
+    /// automatically.
+    #[test]
+    fn composed_statement_proves_and_verifies() {
+        type Native = Both<Cube, RangeCheck<Fp<Fr>, 64>>;
+        type Compiled = Both<Cube, RangeCheck<FpVar<Fr>, 64>>;
+        let mut rng = TestRng::new();
+        let circuit = Circuit::<Native>::new(Both::new(Cube, RangeCheck::new()));
+        let (proving_context, verifying_context) = circuit
+            .compile::<Groth16<Bn254>, Compiled, _>(&(), &mut rng)
+            .expect("Unable to compile circuit.");
+        let x = Fp(Fr::from(rng.next_u64()));
+        let output = Fp(x.0 * x.0 * x.0);
+        let proof = circuit
+            .prove::<Groth16<Bn254>, Compiled, _>(
+                &proving_context,
+                &Both::new(output, ()),
+                &Both::new(x, x),
+                &mut rng,
+            )
+            .expect("Unable to generate proof.");
+        assert!(
+            statements::verify::<Groth16<Bn254>, _>(
+                &verifying_context,
+                &Both::new(output, ()),
+                &proof
+            )
+            .expect("Unable to verify proof."),
+            "Proofs of the composed statement should be valid."
+        );
+        assert!(
+            !statements::verify::<Groth16<Bn254>, _>(&verifying_context, &Both::new(x, ()), &proof)
+                .expect("Unable to verify proof."),
+            "Proofs of the composed statement should not be valid for other inputs."
+        );
+    }
+
+    /// Tests that proofs of the [`Cube`] statement do not reveal which of two cube roots of the
+    /// public input was used as the witness.
+    #[test]
+    fn cube_roots_are_indistinguishable() {
+        use crate::ff::{Field, SquareRootField};
+        use eclair::alloc::{
+            mode::{Public, Secret},
+            Allocate,
+        };
+        use openzl_crypto::constraint::test::zk;
+        let mut rng = TestRng::new();
+        let sqrt = (-Fr::from(3u64))
+            .sqrt()
+            .expect("The BN254 scalar field has a primitive cube root of unity.");
+        let root_of_unity = (sqrt - Fr::from(1u64)) / Fr::from(2u64);
+        assert_eq!(
+            root_of_unity.pow([3]),
+            Fr::from(1u64),
+            "The root of unity must have order three."
+        );
+        let x = Fp(Fr::from(rng.next_u64()));
+        let witnesses = [x, Fp(x.0 * root_of_unity)];
+        let output = Fp(x.0 * x.0 * x.0);
+        let circuit = |witness: &Fp<Fr>, compiler: &mut R1CS<Fr>| {
+            let public = output.as_known::<Public, FpVar<Fr>>(compiler);
+            let secret = witness.as_known::<Secret, FpVar<Fr>>(compiler);
+            Cube.enforce(&public, &secret, compiler);
+            alloc::vec![output.0]
+        };
+        let mut compiler = Groth16::<Bn254>::context_compiler();
+        let public = compiler.allocate_unknown::<Public, FpVar<Fr>>();
+        let secret = compiler.allocate_unknown::<Secret, FpVar<Fr>>();
+        Cube.enforce(&public, &secret, &mut compiler);
+        let (proving_context, verifying_context) =
+            Groth16::<Bn254>::compile(&(), compiler, &mut rng).expect("Unable to compile circuit.");
+        zk::assert_witness_indistinguishable::<Groth16<Bn254>, _, _, _>(
+            &proving_context,
+            &verifying_context,
+            [&witnesses[0], &witnesses[1]],
+            8,
+            circuit,
+            &mut rng,
+        );
+        zk::assert_rerandomized_indistinguishable::<Groth16<Bn254>, _, _, _>(
+            &proving_context,
+            &verifying_context,
+            [&witnesses[0], &witnesses[1]],
+            8,
+            circuit,
+            &mut rng,
+        );
+    }
+
+    /// Tests that rerandomized proofs of the [`Cube`] statement still verify and differ byte-wise
+    /// from the original proof and from each other.
+    #[test]
+    fn rerandomized_proofs_verify_and_differ() {
+        use eclair::alloc::{
+            mode::{Public, Secret},
+            Allocate,
+        };
+        use openzl_util::codec::Encode;
+        let mut rng = TestRng::new();
+        let mut compiler = Groth16::<Bn254>::context_compiler();
+        let public = compiler.allocate_unknown::<Public, FpVar<Fr>>();
+        let secret = compiler.allocate_unknown::<Secret, FpVar<Fr>>();
+        Cube.enforce(&public, &secret, &mut compiler);
+        let (proving_context, verifying_context) =
+            Groth16::<Bn254>::compile(&(), compiler, &mut rng).expect("Unable to compile circuit.");
+        let x = Fp(Fr::from(rng.next_u64()));
+        let output = Fp(x.0 * x.0 * x.0);
+        let mut compiler = Groth16::<Bn254>::proof_compiler();
+        let public = output.as_known::<Public, FpVar<Fr>>(&mut compiler);
+        let secret = x.as_known::<Secret, FpVar<Fr>>(&mut compiler);
+        Cube.enforce(&public, &secret, &mut compiler);
+        let proof = Groth16::<Bn254>::prove(&proving_context, compiler, &mut rng)
+            .expect("Unable to generate proof.");
+        let first = rerandomize_proof(&proof, &verifying_context, &mut rng);
+        let second = rerandomize_proof(&first, &verifying_context, &mut rng);
+        for rerandomized in [&first, &second] {
+            assert!(
+                Groth16::verify_proof(&verifying_context, &[output.0], rerandomized)
+                    .expect("Unable to verify proof."),
+                "Rerandomized proofs should be valid."
+            );
+            assert!(
+                !Groth16::verify_proof(&verifying_context, &[x.0], rerandomized)
+                    .expect("Unable to verify proof."),
+                "Rerandomized proofs should not be valid for other inputs."
+            );
+        }
+        assert_ne!(
+            proof.to_vec(),
+            first.to_vec(),
+            "Rerandomized proofs should differ from the original proof."
+        );
+        assert_ne!(
+            first.to_vec(),
+            second.to_vec(),
+            "Rerandomizing twice should give different proofs."
+        );
+    }
+
+    /// Message Hash
+    ///
+    /// Reduces a byte string modulo the BN254 scalar field, which is enough to test message
+    /// binding but is not collision resistant.
+    #[derive(Clone, Copy, Debug, Default)]
+    struct MessageHash;
+
+    impl openzl_crypto::hash::HashFunction for MessageHash {
+        type Input = [u8];
+        type Output = Fp<Fr>;
+
+        #[inline]
+        fn hash(&self, input: &Self::Input, compiler: &mut ()) -> Self::Output {
+            let _ = compiler;
+            Fp(crate::ff::PrimeField::from_le_bytes_mod_order(input))
+        }
+    }
+
+    /// Tests that proofs of a [`Bound`](statements::Bound) statement only verify for the message
+    /// they were bound to, also after rerandomization.
+    #[test]
+    fn bound_proofs_are_not_valid_for_other_messages() {
+        type Native = statements::Bound<Cube, Fp<Fr>>;
+        type Compiled = statements::Bound<Cube, FpVar<Fr>>;
+        let mut rng = TestRng::new();
+        let circuit = Circuit::<Native>::new(statements::Bound::new(Cube));
+        let (proving_context, verifying_context) = circuit
+            .compile::<Groth16<Bn254>, Compiled, _>(&(), &mut rng)
+            .expect("Unable to compile circuit.");
+        let x = Fp(Fr::from(rng.next_u64()));
+        let output = Fp(x.0 * x.0 * x.0);
+        let proof = circuit
+            .prove_bound::<Groth16<Bn254>, Cube, FpVar<Fr>, _, _>(
+                &proving_context,
+                &MessageHash,
+                &b"transaction"[..],
+                &output,
+                &x,
+                &mut rng,
+            )
+            .expect("Unable to generate proof.");
+        let rerandomized = rerandomize_proof(&proof, &verifying_context, &mut rng);
+        for proof in [&proof, &rerandomized] {
+            assert!(
+                statements::verify_bound::<Groth16<Bn254>, _, _>(
+                    &verifying_context,
+                    &MessageHash,
+                    &b"transaction"[..],
+                    &output,
+                    proof
+                )
+                .expect("Unable to verify proof."),
+                "Bound proofs should be valid for their message."
+            );
+            assert!(
+                !statements::verify_bound::<Groth16<Bn254>, _, _>(
+                    &verifying_context,
+                    &MessageHash,
+                    &b"other transaction"[..],
+                    &output,
+                    proof
+                )
+                .expect("Unable to verify proof."),
+                "Bound proofs should not be valid for other messages."
+            );
+        }
+    }
+
+    /// Semaphore Tests
+    #[cfg(feature = "alloc")]
+    mod semaphore {
+        use super::*;
+        use crate::poseidon::{Spec, TwoPowerMinusOneDomainTag};
+        use core::marker::PhantomData;
+        use eclair::{
+            alloc::Constant,
+            bool::{AssertEq, Bool},
+        };
+        use openzl_crypto::{
+            accumulator::{AssertValidVerification, MembershipProof, Model, Types},
+            poseidon::hash::Hasher,
+            protocol::semaphore::{self, Identity, Parameters, Semaphore},
+        };
+        use openzl_util::rand::{Rand, Sample};
+
+        /// Poseidon Hasher over BN254 with Arity Two
+        type Poseidon2<COM = ()> = Hasher<Spec<Fr, 2>, TwoPowerMinusOneDomainTag, 2, COM>;
+
+        /// Singleton Accumulator Model
+        ///
+        /// Accumulates a single item whose accumulated output is the item itself.
+        #[derive(Clone, Copy, Debug, Default)]
+        struct Singleton<F>(PhantomData<F>);
+
+        impl<F> Types for Singleton<F> {
+            type Item = F;
+            type Witness = ();
+            type Output = F;
+        }
+
+        impl Model for Singleton<Fp<Fr>> {
+            type Verification = bool;
+
+            #[inline]
+            fn verify(&self, item: &Fp<Fr>, _: &(), output: &Fp<Fr>, _: &mut ()) -> bool {
+                item == output
+            }
+        }
+
+        impl Constant<R1CS<Fr>> for Singleton<FpVar<Fr>> {
+            type Type = Singleton<Fp<Fr>>;
+
+            #[inline]
+            fn new_constant(this: &Self::Type, compiler: &mut R1CS<Fr>) -> Self {
+                let _ = (this, compiler);
+                Self(PhantomData)
+            }
+        }
+
+        impl Model<R1CS<Fr>> for Singleton<FpVar<Fr>> {
+            type Verification = Bool<R1CS<Fr>>;
+
+            #[inline]
+            fn verify(
+                &self,
+                item: &FpVar<Fr>,
+                _: &(),
+                output: &FpVar<Fr>,
+                compiler: &mut R1CS<Fr>,
+            ) -> Bool<R1CS<Fr>> {
+                eclair::cmp::PartialEq::eq(item, output, compiler)
+            }
+        }
+
+        impl AssertValidVerification<R1CS<Fr>> for Singleton<FpVar<Fr>> {
+            #[inline]
+            fn assert_valid(
+                &self,
+                item: &FpVar<Fr>,
+                _: &(),
+                output: &FpVar<Fr>,
+                compiler: &mut R1CS<Fr>,
+            ) {
+                compiler.assert_eq(item, output);
+            }
+        }
+
+        /// Tests that a Semaphore signal proves and verifies, and that its proof does not verify
+        /// for a different signal hash.
+        #[test]
+        fn semaphore_signal_proves_and_verifies() {
+            type Native = Semaphore<Poseidon2, Poseidon2, Singleton<Fp<Fr>>>;
+            type Compiled =
+                Semaphore<Poseidon2<R1CS<Fr>>, Poseidon2<R1CS<Fr>>, Singleton<FpVar<Fr>>>;
+            let mut rng = TestRng::new();
+            let statement = Native::new(
+                Parameters::new(rng.gen(), rng.gen()),
+                Singleton(PhantomData),
+            );
+            let (proving_key, verifying_key) = statement
+                .generate_keys::<Groth16<Bn254>, Compiled, _>(&(), &mut rng)
+                .expect("Unable to generate keys.");
+            let identity = Identity::<Fp<Fr>>::gen(&mut rng);
+            let commitment = statement.identity_commitment(&identity);
+            let (mut signal, witness) = statement.signal(
+                identity,
+                MembershipProof::new((), commitment),
+                rng.gen(),
+                rng.gen(),
+            );
+            let proof = statement
+                .prove::<Groth16<Bn254>, Compiled, _>(&proving_key, &signal, &witness, &mut rng)
+                .expect("Unable to generate proof.");
+            assert!(
+                semaphore::verify::<Groth16<Bn254>, _, _>(&verifying_key, &signal, &proof)
+                    .expect("Unable to verify proof."),
+                "Proofs of a Semaphore signal should be valid."
+            );
+            signal.signal_hash = rng.gen();
+            assert!(
+                !semaphore::verify::<Groth16<Bn254>, _, _>(&verifying_key, &signal, &proof)
+                    .expect("Unable to verify proof."),
+                "Proofs of a Semaphore signal should not be valid for other signal hashes."
+            );
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Groth16 Recursion
+//!
+//! One layer of recursion proves, with a [`Groth16`] circuit over an outer curve, that a Groth16
+//! proof over an inner curve verifies. This requires the scalar field of the outer curve to be the
+//! base field of the inner curve, like for BW6-761 over BLS12-377. The inner verifying key is
+//! compiled into the outer circuit as a constant, the inner proof is a secret witness, and the
+//! inner public input becomes part of the public input of the outer circuit. See [`Verifier`] for
+//! the circuit glue and [`outer_input`] for the matching native glue.
+
+use crate::{
+    constraint::{fp::Fp, Boolean, FpVar, R1CS},
+    ec::PairingEngine,
+    ff::{BigInteger, One, PrimeField},
+    groth16::{Groth16, Proof, VerifyingContext},
+    pairing::constraint::{
+        groth16::{ProofVar, VerifyingKeyVar},
+        Configuration,
+    },
+    r1cs_std::ToBitsGadget,
+};
+use alloc::vec::Vec;
+use eclair::{
+    alloc::{
+        mode::{Public, Secret},
+        Allocate, Allocator,
+    },
+    bool::Assert,
+};
+use openzl_util::derivative;
+
+/// Recursion Configuration
+pub trait Recursion: Configuration {
+    /// Outer Pairing Engine
+    ///
+    /// The outer circuit is compiled for [`Groth16`] over this pairing engine, so its scalar field
+    /// must be the [`ConstraintField`](Configuration::ConstraintField) of the inner pairing.
+    type Outer: PairingEngine<Fr = Self::ConstraintField>;
+}
+
+#[cfg(all(feature = "bls12-377", feature = "bw6-761"))]
+impl Recursion for crate::pairing::constraint::Bls12_377 {
+    type Outer = crate::bw6_761::BW6_761;
+}
+
+/// Inner Scalar Field Type
+pub type InnerScalar<C> = <<C as Configuration>::Pairing as PairingEngine>::Fr;
+
+/// Outer Proof System Type
+pub type OuterProofSystem<C> = Groth16<<C as Recursion>::Outer>;
+
+/// Converts `scalar` from the inner scalar field into the outer scalar field.
+///
+/// The inner scalar field is smaller than the outer one, so the canonical integer representative
+/// of `scalar` is preserved.
+#[inline]
+pub fn outer_scalar<C>(scalar: &InnerScalar<C>) -> C::ConstraintField
+where
+    C: Configuration,
+{
+    C::ConstraintField::from_le_bytes_mod_order(&scalar.into_repr().to_bytes_le())
+}
+
+/// Converts the public `input` of an inner proof into the public input of the outer circuit.
+#[inline]
+pub fn outer_input<C>(input: &[InnerScalar<C>]) -> Vec<C::ConstraintField>
+where
+    C: Configuration,
+{
+    input.iter().map(outer_scalar::<C>).collect()
+}
+
+/// Inner Proof
+///
+/// This is the native witness for one inner verification in the outer circuit.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct InnerProof<C>
+where
+    C: Configuration,
+{
+    /// Public Input of the Inner Proof
+    pub input: Vec<InnerScalar<C>>,
+
+    /// Inner Proof
+    pub proof: Proof<C::Pairing>,
+}
+
+impl<C> InnerProof<C>
+where
+    C: Configuration,
+{
+    /// Builds a new [`InnerProof`] from `input` and `proof`.
+    #[inline]
+    pub fn new(input: Vec<InnerScalar<C>>, proof: Proof<C::Pairing>) -> Self {
+        Self { input, proof }
+    }
+
+    /// Returns the public input of the outer circuit which verifies `self`.
+    #[inline]
+    pub fn outer_input(&self) -> Vec<C::ConstraintField> {
+        outer_input::<C>(&self.input)
+    }
+}
+
+/// Inner Proof Variable
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct InnerProofVar<C>
+where
+    C: Configuration,
+{
+    /// Public Input of the Inner Proof
+    pub input: Vec<FpVar<C::ConstraintField>>,
+
+    /// Inner Proof
+    pub proof: ProofVar<C>,
+}
+
+/// Recursive Verifier
+///
+/// This gadget holds the verifying key of the inner circuit as a constant of the outer circuit,
+/// so every outer circuit built with it only accepts proofs for that inner circuit.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct Verifier<C>
+where
+    C: Configuration,
+{
+    /// Inner Verifying Key
+    verifying_key: VerifyingKeyVar<C>,
+}
+
+impl<C> Verifier<C>
+where
+    C: Configuration,
+{
+    /// Allocates the inner verifying key of `context` as a constant in `compiler`.
+    #[inline]
+    pub fn new(
+        context: &VerifyingContext<C::Pairing>,
+        compiler: &mut R1CS<C::ConstraintField>,
+    ) -> Self {
+        Self {
+            verifying_key: context.0.vk.as_constant(compiler),
+        }
+    }
+
+    /// Returns the number of public input elements of the inner circuit.
+    #[inline]
+    pub fn input_length(&self) -> usize {
+        self.verifying_key.gamma_abc_g1.len() - 1
+    }
+
+    /// Allocates `inner_proof` into `compiler`, with its public input as public input of the outer
+    /// circuit and its proof as a secret witness.
+    #[inline]
+    pub fn allocate_known(
+        &self,
+        inner_proof: &InnerProof<C>,
+        compiler: &mut R1CS<C::ConstraintField>,
+    ) -> InnerProofVar<C> {
+        InnerProofVar {
+            input: inner_proof
+                .outer_input()
+                .into_iter()
+                .map(|scalar| Fp(scalar).as_known::<Public, _>(compiler))
+                .collect(),
+            proof: inner_proof.proof.0.as_known::<Secret, _>(compiler),
+        }
+    }
+
+    /// Allocates an unknown inner proof with as many public input elements as the inner circuit
+    /// expects into `compiler`. This is used when compiling the proving and verifying contexts of
+    /// the outer circuit.
+    #[inline]
+    pub fn allocate_unknown(&self, compiler: &mut R1CS<C::ConstraintField>) -> InnerProofVar<C> {
+        InnerProofVar {
+            input: (0..self.input_length())
+                .map(|_| compiler.allocate_unknown::<Public, _>())
+                .collect(),
+            proof: compiler.allocate_unknown::<Secret, _>(),
+        }
+    }
+
+    /// Returns `true` if `inner_proof` verifies under the inner verifying key.
+    ///
+    /// # Range Check
+    ///
+    /// The public input bases of the inner verifying key have the order of the inner scalar field,
+    /// so an input element `x` and `x + r`, where `r` is the inner scalar field modulus, would
+    /// verify alike. This method therefore constrains every input element to be smaller than `r`,
+    /// and the outer circuit is unsatisfiable if one of them is not.
+    #[inline]
+    pub fn verify(
+        &self,
+        inner_proof: &InnerProofVar<C>,
+        compiler: &mut R1CS<C::ConstraintField>,
+    ) -> Boolean<C::ConstraintField> {
+        let largest_scalar = (-<InnerScalar<C> as One>::one()).into_repr();
+        let input = inner_proof
+            .input
+            .iter()
+            .map(|scalar| {
+                let bits = scalar
+                    .to_bits_le()
+                    .expect("Bit decomposition is not allowed to fail.");
+                Boolean::enforce_smaller_or_equal_than_le(&bits, largest_scalar)
+                    .expect("Enforcing the inner scalar field range is not allowed to fail.");
+                bits
+            })
+            .collect::<Vec<_>>();
+        self.verifying_key
+            .verify(&input, &inner_proof.proof, compiler)
+    }
+
+    /// Asserts that `inner_proof` verifies under the inner verifying key.
+    #[inline]
+    pub fn assert_valid(
+        &self,
+        inner_proof: &InnerProofVar<C>,
+        compiler: &mut R1CS<C::ConstraintField>,
+    ) {
+        let is_valid = self.verify(inner_proof, compiler);
+        compiler.assert(&is_valid);
+    }
+}
+
+#[cfg(all(test, feature = "bls12-377", feature = "bw6-761"))]
+mod test {
+    use super::*;
+    use crate::{
+        bls12_377::{Bls12_377, Fq, Fr},
+        groth16::cube::cube_circuit,
+    };
+    use alloc::vec;
+    use openzl_crypto::constraint::ProofSystem;
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Inner Pairing Constraint Configuration
+    type Config = crate::pairing::constraint::Bls12_377;
+
+    /// Returns `true` if the outer circuit which verifies `proof` against the inner public `input`,
+    /// given in the outer scalar field, is satisfied.
+    #[inline]
+    fn is_satisfied(
+        verifying_context: &VerifyingContext<Bls12_377>,
+        input: Fq,
+        proof: &Proof<Bls12_377>,
+    ) -> bool {
+        let mut compiler = R1CS::<Fq>::for_proofs();
+        let verifier = Verifier::<Config>::new(verifying_context, &mut compiler);
+        let inner_proof = InnerProofVar {
+            input: vec![Fp(input).as_known::<Public, _>(&mut compiler)],
+            proof: proof.0.as_known::<Secret, _>(&mut compiler),
+        };
+        verifier.assert_valid(&inner_proof, &mut compiler);
+        compiler.is_satisfied()
+    }
+
+    /// Tests that the BW6-761 outer circuit is satisfied exactly by valid BLS12-377 inner proofs
+    /// with their canonical public input.
+    #[test]
+    fn outer_circuit_accepts_only_valid_inner_proofs() {
+        let mut rng = TestRng::new();
+        let mut compiler = Groth16::<Bls12_377>::context_compiler();
+        cube_circuit(None, None, &mut compiler);
+        let (proving_context, verifying_context) =
+            Groth16::<Bls12_377>::compile(&(), compiler, &mut rng)
+                .expect("Unable to compile circuit.");
+        let x = Fp::<Fr>::gen(&mut rng);
+        let output = Fp(x.0 * x.0 * x.0);
+        let mut compiler = Groth16::<Bls12_377>::proof_compiler();
+        cube_circuit(Some(&x), Some(&output), &mut compiler);
+        let proof = Groth16::<Bls12_377>::prove(&proving_context, compiler, &mut rng)
+            .expect("Unable to generate proof.");
+        assert!(
+            Groth16::<Bls12_377>::verify(&verifying_context, &vec![output.0], &proof)
+                .expect("Unable to verify proof."),
+            "The inner proof should be valid."
+        );
+        let mut compiler = R1CS::<Fq>::for_proofs();
+        let verifier = Verifier::<Config>::new(&verifying_context, &mut compiler);
+        assert_eq!(verifier.input_length(), 1);
+        let inner_proof = verifier.allocate_known(
+            &InnerProof::new(vec![output.0], proof.clone()),
+            &mut compiler,
+        );
+        verifier.assert_valid(&inner_proof, &mut compiler);
+        assert!(
+            compiler.is_satisfied(),
+            "The outer circuit must be satisfied by a valid inner proof."
+        );
+        assert!(
+            !is_satisfied(&verifying_context, outer_scalar::<Config>(&x.0), &proof),
+            "The outer circuit must not be satisfied for a wrong inner input."
+        );
+        let modulus = outer_scalar::<Config>(&-Fr::one()) + Fq::one();
+        assert!(
+            !is_satisfied(
+                &verifying_context,
+                outer_scalar::<Config>(&output.0) + modulus,
+                &proof
+            ),
+            "The outer circuit must not be satisfied for a non-canonical inner input."
+        );
+        let mut tampered = proof;
+        tampered.0.c = -tampered.0.c;
+        assert!(
+            !is_satisfied(
+                &verifying_context,
+                outer_scalar::<Config>(&output.0),
+                &tampered
+            ),
+            "The outer circuit must not be satisfied for a tampered inner proof."
+        );
+    }
+}
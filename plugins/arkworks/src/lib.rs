@@ -98,12 +98,23 @@ pub mod algebra;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "constraint")))]
 pub mod constraint;
 
+#[cfg(all(feature = "algebra", feature = "alloc", feature = "constraint"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "algebra", feature = "alloc", feature = "constraint")))
+)]
+pub mod encryption;
+
+#[cfg(feature = "verify")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "verify")))]
+pub mod export;
+
 #[cfg(feature = "ff")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "ff")))]
 pub mod ff;
 
-#[cfg(feature = "groth16")]
-#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+#[cfg(feature = "verify")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "verify")))]
 pub mod groth16;
 
 #[cfg(all(feature = "ec", feature = "ff"))]
@@ -121,3 +132,7 @@ pub mod ratio;
 #[cfg(feature = "serialize")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "serialize")))]
 pub mod serialize;
+
+#[cfg(feature = "wire")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "wire")))]
+pub mod wire;
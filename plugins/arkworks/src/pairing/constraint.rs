@@ -0,0 +1,480 @@
+//! Pairing Constraints
+//!
+//! A pairing over a curve `E` can be checked inside a constraint system over the base field of
+//! `E`, which is the scalar field of an outer curve when `E` is part of a two-chain like BLS12-377
+//! and BW6-761. The gadgets in this module express pairing-product checks over such inner curves,
+//! so that statements like "this Groth16 proof verifies" can be proven with the outer curve.
+
+use crate::{
+    constraint::{empty, full, Boolean, R1CS},
+    ec::{AffineCurve, PairingEngine},
+    ff::PrimeField,
+    r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::FieldVar, pairing::PairingVar},
+    relations::ns,
+};
+use alloc::vec::Vec;
+use eclair::{
+    alloc::{
+        mode::{self, Public, Secret},
+        Constant, Variable,
+    },
+    audit::Audit,
+};
+use openzl_util::derivative;
+
+/// Pairing Constraint Configuration
+pub trait Configuration {
+    /// Inner Pairing Engine
+    type Pairing: PairingEngine;
+
+    /// Constraint Field
+    ///
+    /// This is the base field of [`Pairing`](Self::Pairing) over which its pairing is computed
+    /// in-circuit.
+    type ConstraintField: PrimeField;
+
+    /// Pairing Variable
+    type PairingVar: PairingVar<Self::Pairing, Self::ConstraintField>;
+}
+
+/// Pairing Variable Type
+type PairingVarType<C> = <C as Configuration>::PairingVar;
+
+/// First Group Variable Type
+type G1VarType<C> = <PairingVarType<C> as PairingVar<
+    <C as Configuration>::Pairing,
+    <C as Configuration>::ConstraintField,
+>>::G1Var;
+
+/// Second Group Variable Type
+type G2VarType<C> = <PairingVarType<C> as PairingVar<
+    <C as Configuration>::Pairing,
+    <C as Configuration>::ConstraintField,
+>>::G2Var;
+
+/// Target Group Variable Type
+type GTVarType<C> = <PairingVarType<C> as PairingVar<
+    <C as Configuration>::Pairing,
+    <C as Configuration>::ConstraintField,
+>>::GTVar;
+
+/// Constraint System Type
+type Compiler<C> = R1CS<<C as Configuration>::ConstraintField>;
+
+/// BLS12-377 Pairing Constraints over the BW6-761 Scalar Field
+#[cfg(feature = "bls12-377")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bls12-377")))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Bls12_377;
+
+#[cfg(feature = "bls12-377")]
+impl Configuration for Bls12_377 {
+    type Pairing = crate::bls12_377::Bls12_377;
+    type ConstraintField = crate::bls12_377::Fq;
+    type PairingVar = crate::bls12_377::constraints::PairingVar;
+}
+
+/// First Group Variable
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct G1Var<C>(
+    /// Curve Variable
+    pub G1VarType<C>,
+)
+where
+    C: Configuration;
+
+/// Second Group Variable
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct G2Var<C>(
+    /// Curve Variable
+    pub G2VarType<C>,
+)
+where
+    C: Configuration;
+
+/// Implements [`Variable`] for the group variable `$type` wrapping `$var`, allocated from points of
+/// `$affine` through their `$projective` representation.
+macro_rules! impl_group_variable {
+    (
+        $type:ident,
+        $var:ident,
+        $affine:ident,
+        $projective:ident,
+        $constant:literal,
+        $public:literal,
+        $secret:literal
+    ) => {
+        impl<C> Constant<Compiler<C>> for $type<C>
+        where
+            C: Configuration,
+        {
+            type Type = <C::Pairing as PairingEngine>::$affine;
+
+            #[inline]
+            fn new_constant(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+                compiler.record_allocation::<Self, mode::Constant>();
+                Self(
+                    <$var<C> as AllocVar<<C::Pairing as PairingEngine>::$projective, _>>::new_constant(
+                        ns!(compiler.0, $constant),
+                        this.into_projective(),
+                    )
+                    .expect("Variable allocation is not allowed to fail."),
+                )
+            }
+        }
+
+        impl<C> Variable<Public, Compiler<C>> for $type<C>
+        where
+            C: Configuration,
+        {
+            type Type = <C::Pairing as PairingEngine>::$affine;
+
+            #[inline]
+            fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+                compiler.record_allocation::<Self, Public>();
+                Self(
+                    <$var<C> as AllocVar<<C::Pairing as PairingEngine>::$projective, _>>::new_input(
+                        ns!(compiler.0, $public),
+                        full(this.into_projective()),
+                    )
+                    .expect("Variable allocation is not allowed to fail."),
+                )
+            }
+
+            #[inline]
+            fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+                compiler.record_allocation::<Self, Public>();
+                Self(
+                    <$var<C> as AllocVar<<C::Pairing as PairingEngine>::$projective, _>>::new_input(
+                        ns!(compiler.0, $public),
+                        empty::<<C::Pairing as PairingEngine>::$projective>,
+                    )
+                    .expect("Variable allocation is not allowed to fail."),
+                )
+            }
+        }
+
+        impl<C> Variable<Secret, Compiler<C>> for $type<C>
+        where
+            C: Configuration,
+        {
+            type Type = <C::Pairing as PairingEngine>::$affine;
+
+            #[inline]
+            fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+                compiler.record_allocation::<Self, Secret>();
+                Self(
+                    <$var<C> as AllocVar<<C::Pairing as PairingEngine>::$projective, _>>::new_witness(
+                        ns!(compiler.0, $secret),
+                        full(this.into_projective()),
+                    )
+                    .expect("Variable allocation is not allowed to fail."),
+                )
+            }
+
+            #[inline]
+            fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+                compiler.record_allocation::<Self, Secret>();
+                Self(
+                    <$var<C> as AllocVar<<C::Pairing as PairingEngine>::$projective, _>>::new_witness(
+                        ns!(compiler.0, $secret),
+                        empty::<<C::Pairing as PairingEngine>::$projective>,
+                    )
+                    .expect("Variable allocation is not allowed to fail."),
+                )
+            }
+        }
+    };
+}
+
+impl_group_variable!(
+    G1Var,
+    G1VarType,
+    G1Affine,
+    G1Projective,
+    "g1 constant",
+    "g1 public input",
+    "g1 secret witness"
+);
+impl_group_variable!(
+    G2Var,
+    G2VarType,
+    G2Affine,
+    G2Projective,
+    "g2 constant",
+    "g2 public input",
+    "g2 secret witness"
+);
+
+/// Computes the product of the pairings of `pairs` in the target group.
+#[inline]
+fn product_of_pairings<C>(pairs: &[(&G1Var<C>, &G2Var<C>)]) -> GTVarType<C>
+where
+    C: Configuration,
+{
+    let (g1, g2): (Vec<_>, Vec<_>) = pairs
+        .iter()
+        .map(|(g1, g2)| {
+            (
+                C::PairingVar::prepare_g1(&g1.0).expect("Preparing points is not allowed to fail."),
+                C::PairingVar::prepare_g2(&g2.0).expect("Preparing points is not allowed to fail."),
+            )
+        })
+        .unzip();
+    C::PairingVar::product_of_pairings(&g1, &g2)
+        .expect("Computing the product of pairings is not allowed to fail.")
+}
+
+/// Returns `true` if the product of the pairings of `pairs` is the identity of the target group.
+#[inline]
+pub fn product_of_pairings_is_one<C>(
+    pairs: &[(&G1Var<C>, &G2Var<C>)],
+    compiler: &mut Compiler<C>,
+) -> Boolean<C::ConstraintField>
+where
+    C: Configuration,
+{
+    let _ = compiler;
+    product_of_pairings(pairs)
+        .is_eq(&GTVarType::<C>::one())
+        .expect("Equality checking is not allowed to fail.")
+}
+
+/// Returns `true` if the products of the pairings of `lhs` and `rhs` are equal.
+#[inline]
+pub fn same_product_of_pairings<C>(
+    lhs: &[(&G1Var<C>, &G2Var<C>)],
+    rhs: &[(&G1Var<C>, &G2Var<C>)],
+    compiler: &mut Compiler<C>,
+) -> Boolean<C::ConstraintField>
+where
+    C: Configuration,
+{
+    let _ = compiler;
+    product_of_pairings(lhs)
+        .is_eq(&product_of_pairings(rhs))
+        .expect("Equality checking is not allowed to fail.")
+}
+
+/// Groth16 Verification Constraints
+#[cfg(feature = "groth16")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+pub mod groth16 {
+    use super::*;
+    use crate::r1cs_std::groups::CurveVar;
+    use ark_groth16::{Proof, VerifyingKey};
+    use eclair::alloc::{Allocate, Allocator};
+
+    /// Groth16 Verifying Key Variable
+    #[derive(derivative::Derivative)]
+    #[derivative(Clone(bound = ""), Debug(bound = ""))]
+    pub struct VerifyingKeyVar<C>
+    where
+        C: Configuration,
+    {
+        /// Alpha in the First Group
+        pub alpha_g1: G1Var<C>,
+
+        /// Beta in the Second Group
+        pub beta_g2: G2Var<C>,
+
+        /// Gamma in the Second Group
+        pub gamma_g2: G2Var<C>,
+
+        /// Delta in the Second Group
+        pub delta_g2: G2Var<C>,
+
+        /// Public Input Bases in the First Group
+        pub gamma_abc_g1: Vec<G1Var<C>>,
+    }
+
+    impl<C> VerifyingKeyVar<C>
+    where
+        C: Configuration,
+    {
+        /// Verifies `proof` against the public `input`, given as the little-endian bit
+        /// decompositions of the public input scalars.
+        ///
+        /// This checks that `e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta)` where `L` is
+        /// the linear combination of the public input bases with `input`. Returns `false` if
+        /// `input` does not have as many elements as the verifying key expects.
+        #[inline]
+        pub fn verify(
+            &self,
+            input: &[Vec<Boolean<C::ConstraintField>>],
+            proof: &ProofVar<C>,
+            compiler: &mut Compiler<C>,
+        ) -> Boolean<C::ConstraintField> {
+            if input.len() + 1 != self.gamma_abc_g1.len() {
+                return Boolean::FALSE;
+            }
+            let mut linear_combination = self.gamma_abc_g1[0].0.clone();
+            for (bits, base) in input.iter().zip(&self.gamma_abc_g1[1..]) {
+                linear_combination += base
+                    .0
+                    .scalar_mul_le(bits.iter())
+                    .expect("Scalar multiplication is not allowed to fail.");
+            }
+            let linear_combination = G1Var(linear_combination);
+            same_product_of_pairings(
+                &[(&proof.a, &proof.b)],
+                &[
+                    (&self.alpha_g1, &self.beta_g2),
+                    (&linear_combination, &self.gamma_g2),
+                    (&proof.c, &self.delta_g2),
+                ],
+                compiler,
+            )
+        }
+    }
+
+    impl<C> Constant<Compiler<C>> for VerifyingKeyVar<C>
+    where
+        C: Configuration,
+    {
+        type Type = VerifyingKey<C::Pairing>;
+
+        #[inline]
+        fn new_constant(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+            Self {
+                alpha_g1: this.alpha_g1.as_constant(compiler),
+                beta_g2: this.beta_g2.as_constant(compiler),
+                gamma_g2: this.gamma_g2.as_constant(compiler),
+                delta_g2: this.delta_g2.as_constant(compiler),
+                gamma_abc_g1: this
+                    .gamma_abc_g1
+                    .iter()
+                    .map(|base| base.as_constant(compiler))
+                    .collect(),
+            }
+        }
+    }
+
+    impl<C, M> Variable<M, Compiler<C>> for VerifyingKeyVar<C>
+    where
+        C: Configuration,
+        G1Var<C>: Variable<M, Compiler<C>, Type = <C::Pairing as PairingEngine>::G1Affine>,
+        G2Var<C>: Variable<M, Compiler<C>, Type = <C::Pairing as PairingEngine>::G2Affine>,
+    {
+        type Type = VerifyingKey<C::Pairing>;
+
+        /// Allocates a verifying key with no public input bases, since their number is only known
+        /// from the underlying value.
+        #[inline]
+        fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+            Self {
+                alpha_g1: compiler.allocate_unknown(),
+                beta_g2: compiler.allocate_unknown(),
+                gamma_g2: compiler.allocate_unknown(),
+                delta_g2: compiler.allocate_unknown(),
+                gamma_abc_g1: Vec::new(),
+            }
+        }
+
+        #[inline]
+        fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+            Self {
+                alpha_g1: this.alpha_g1.as_known(compiler),
+                beta_g2: this.beta_g2.as_known(compiler),
+                gamma_g2: this.gamma_g2.as_known(compiler),
+                delta_g2: this.delta_g2.as_known(compiler),
+                gamma_abc_g1: this
+                    .gamma_abc_g1
+                    .iter()
+                    .map(|base| base.as_known(compiler))
+                    .collect(),
+            }
+        }
+    }
+
+    /// Groth16 Proof Variable
+    #[derive(derivative::Derivative)]
+    #[derivative(Clone(bound = ""), Debug(bound = ""))]
+    pub struct ProofVar<C>
+    where
+        C: Configuration,
+    {
+        /// First Proof Element
+        pub a: G1Var<C>,
+
+        /// Second Proof Element
+        pub b: G2Var<C>,
+
+        /// Third Proof Element
+        pub c: G1Var<C>,
+    }
+
+    impl<C, M> Variable<M, Compiler<C>> for ProofVar<C>
+    where
+        C: Configuration,
+        G1Var<C>: Variable<M, Compiler<C>, Type = <C::Pairing as PairingEngine>::G1Affine>,
+        G2Var<C>: Variable<M, Compiler<C>, Type = <C::Pairing as PairingEngine>::G2Affine>,
+    {
+        type Type = Proof<C::Pairing>;
+
+        #[inline]
+        fn new_unknown(compiler: &mut Compiler<C>) -> Self {
+            Self {
+                a: compiler.allocate_unknown(),
+                b: compiler.allocate_unknown(),
+                c: compiler.allocate_unknown(),
+            }
+        }
+
+        #[inline]
+        fn new_known(this: &Self::Type, compiler: &mut Compiler<C>) -> Self {
+            Self {
+                a: this.a.as_known(compiler),
+                b: this.b.as_known(compiler),
+                c: this.c.as_known(compiler),
+            }
+        }
+    }
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use crate::ec::ProjectiveCurve;
+    use eclair::alloc::Allocate;
+    use openzl_crypto::constraint::Satisfied;
+    use openzl_util::rand::{Rand, TestRng};
+
+    /// Checks that the in-circuit pairing check accepts equal pairing ratios and rejects unequal
+    /// ones over BLS12-377.
+    #[test]
+    fn bls12_377_pairing_ratio_in_circuit() {
+        let mut rng = TestRng::new();
+        let g1: <crate::bls12_377::Bls12_377 as PairingEngine>::G1Affine = rng.gen();
+        let g2: <crate::bls12_377::Bls12_377 as PairingEngine>::G2Affine = rng.gen();
+        let scalar: crate::bls12_377::Fr = rng.gen();
+        let other: crate::bls12_377::Fr = rng.gen();
+        for (scalar_g1, expected) in [(scalar, true), (other, false)] {
+            let mut cs = R1CS::<crate::bls12_377::Fq>::for_proofs();
+            let lhs_g1 = g1.as_known::<Secret, G1Var<Bls12_377>>(&mut cs);
+            let lhs_g2 = g2
+                .mul(scalar)
+                .into_affine()
+                .as_known::<Secret, G2Var<Bls12_377>>(&mut cs);
+            let rhs_g1 = g1
+                .mul(scalar_g1)
+                .into_affine()
+                .as_known::<Secret, G1Var<Bls12_377>>(&mut cs);
+            let rhs_g2 = g2.as_known::<Secret, G2Var<Bls12_377>>(&mut cs);
+            let same =
+                same_product_of_pairings(&[(&lhs_g1, &lhs_g2)], &[(&rhs_g1, &rhs_g2)], &mut cs);
+            assert_eq!(
+                expected,
+                crate::r1cs_std::R1CSVar::value(&same).expect("Unable to get the check value."),
+                "The in-circuit pairing check does not match the expected outcome."
+            );
+            assert!(
+                cs.is_satisfied(),
+                "The pairing constraints must be satisfied."
+            );
+        }
+    }
+}
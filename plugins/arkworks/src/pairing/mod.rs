@@ -3,6 +3,10 @@
 use crate::{ec::AffineCurve, ff::PrimeField};
 use core::iter;
 
+#[cfg(all(feature = "alloc", feature = "constraint"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", feature = "constraint"))))]
+pub mod constraint;
+
 pub use crate::ec::PairingEngine;
 
 /// Pairing Configuration
@@ -97,7 +101,7 @@ pub mod test {
     use crate::ec::ProjectiveCurve;
 
     #[cfg(test)]
-    use openzl_util::rand::{OsRng, Rand};
+    use openzl_util::rand::{Rand, TestRng};
 
     /// Asserts that `g1` and `g1*scalar` are in the same ratio as `g2` and `g2*scalar`.
     #[inline]
@@ -116,7 +120,7 @@ pub mod test {
     #[cfg(feature = "bls12-381")]
     #[test]
     fn bls12_381_has_valid_pairing_ratio() {
-        let mut rng = OsRng;
+        let mut rng = TestRng::new();
         assert_valid_pairing_ratio::<crate::bls12_381::Bls12_381>(rng.gen(), rng.gen(), rng.gen());
     }
 
@@ -124,7 +128,7 @@ pub mod test {
     #[cfg(feature = "bn254")]
     #[test]
     fn bn254_has_valid_pairing_ratio() {
-        let mut rng = OsRng;
+        let mut rng = TestRng::new();
         assert_valid_pairing_ratio::<crate::bn254::Bn254>(rng.gen(), rng.gen(), rng.gen());
     }
 }
@@ -0,0 +1,94 @@
+# Generates the Poseidon known-answer tests in this directory.
+#
+# This is an independent implementation of the Poseidon permutation with Grain LFSR round constants
+# and Cauchy MDS matrices, following the reference implementation of the Poseidon paper. It agrees
+# with `../permutation_hardcoded_test/width3`. For every field and width it writes:
+#
+#   - `<field>_width<t>_permutation`: the permutation of the state `[0, 1, ..., t - 1]`
+#   - `<field>_width<t>_hash`: the hash of `[1, 2, ..., t - 1]` with domain tag `2^(t - 1) - 1`
+#
+# Usage: python3 generate_kat.py
+
+FIELDS = {
+    "bn254": 0x30644E72E131A029B85045B68181585D2833E84879B9709143E1F593F0000001,
+    "bls12_381": 0x73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001,
+}
+
+# (field, width, full rounds, partial rounds)
+SPECIFICATIONS = [
+    ("bn254", 3, 8, 55),
+    ("bn254", 4, 8, 55),
+    ("bn254", 5, 8, 56),
+    ("bn254", 6, 8, 56),
+    ("bls12_381", 3, 8, 55),
+]
+
+
+class Grain:
+    def __init__(self, modulus_bits, width, full_rounds, partial_rounds):
+        self.state = []
+        for size, value in [
+            (2, 1),
+            (4, 0),
+            (12, modulus_bits),
+            (12, width),
+            (10, full_rounds),
+            (10, partial_rounds),
+            (30, (1 << 30) - 1),
+        ]:
+            self.state += [(value >> i) & 1 for i in reversed(range(size))]
+        for _ in range(160):
+            self.update()
+
+    def update(self):
+        s = self.state
+        bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0]
+        self.state = s[1:] + [bit]
+        return bit
+
+    def next(self):
+        bit = self.update()
+        while not bit:
+            self.update()
+            bit = self.update()
+        return self.update()
+
+
+def permute(modulus, width, full_rounds, partial_rounds, state):
+    bits = modulus.bit_length()
+    grain = Grain(bits, width, full_rounds, partial_rounds)
+    constants = []
+    while len(constants) < width * (full_rounds + partial_rounds):
+        value = 0
+        for _ in range(bits):
+            value = (value << 1) | grain.next()
+        if value < modulus:
+            constants.append(value)
+    mds = [[pow(i + width + j, -1, modulus) for j in range(width)] for i in range(width)]
+    half = full_rounds // 2
+    for round in range(full_rounds + partial_rounds):
+        keys = constants[round * width : (round + 1) * width]
+        state = [(x + k) % modulus for x, k in zip(state, keys)]
+        if round < half or round >= half + partial_rounds:
+            state = [pow(x, 5, modulus) for x in state]
+        else:
+            state[0] = pow(state[0], 5, modulus)
+        state = [sum(m * x for m, x in zip(row, state)) % modulus for row in mds]
+    return state
+
+
+def element(value):
+    return 'Fp(field_new!(Fr, "{}"))'.format(value)
+
+
+if __name__ == "__main__":
+    for field, width, full_rounds, partial_rounds in SPECIFICATIONS:
+        modulus = FIELDS[field]
+        permutation = permute(modulus, width, full_rounds, partial_rounds, list(range(width)))
+        tag = (1 << (width - 1)) - 1
+        hash = permute(modulus, width, full_rounds, partial_rounds, [tag] + list(range(1, width)))[0]
+        prefix = "{}_width{}".format(field, width)
+        with open(prefix + "_permutation", "w") as f:
+            f.write("vec![{}]".format(",".join(map(element, permutation))))
+        with open(prefix + "_hash", "w") as f:
+            f.write(element(hash))
@@ -10,39 +10,1633 @@ use {
 
 #[cfg(feature = "bn254")]
 mod duplexer {
-    use crate::{constraint::fp::Fp, poseidon::Spec};
+    use crate::{
+        constraint::fp::Fp,
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use alloc::boxed::Box;
+    use openzl_crypto::{
+        encryption::{rotate::Rotation, Decrypt, Encrypt, EncryptedMessage},
+        hash::{ArrayHashFunction, HashFunction},
+        merkle_tree::{
+            ciphertext::CiphertextTree, Configuration, HashConfiguration, InnerHash, Parameters,
+        },
+        poseidon::{
+            encryption::{BlockArray, FixedDuplexer, MessageHash, PlaintextBlock},
+            hash::Hasher,
+            Constants,
+        },
+    };
+    use openzl_util::{
+        codec::{Decode, Encode},
+        rand::{Sample, TestRng},
+    };
+
+    /// Tests Poseidon duplexer encryption works.
+    #[test]
+    fn poseidon_duplexer_test() {
+        const N: usize = 3;
+        let mut rng = TestRng::new();
+        let duplexer = FixedDuplexer::<1, Spec<bn254::Fr, N>>::gen(&mut rng);
+        let field_elements = <[Fp<bn254::Fr>; Spec::<bn254::Fr, N>::WIDTH - 1]>::gen(&mut rng);
+        let plaintext_block = PlaintextBlock(Box::new(field_elements));
+        let plaintext = BlockArray::<_, 1>([plaintext_block].into());
+        let mut key = Vec::new();
+        let key_element_1 = Fp::<bn254::Fr>::gen(&mut rng);
+        let key_element_2 = Fp::<bn254::Fr>::gen(&mut rng);
+        key.push(key_element_1);
+        key.push(key_element_2);
+        let header = vec![];
+        let ciphertext = duplexer.encrypt(&key, &(), &header, &plaintext, &mut ());
+        let (tag_matches, decrypted_plaintext) =
+            duplexer.decrypt(&key, &header, &ciphertext, &mut ());
+        assert!(tag_matches, "Tag doesn't match");
+        assert_eq!(
+            plaintext, decrypted_plaintext,
+            "Decrypted plaintext is not equal to original one."
+        );
+    }
+
+    /// Tests that duplex sessions match encryption and that checkpoints and forks behave as
+    /// separate transcripts.
+    #[test]
+    fn poseidon_duplexer_session_test() {
+        const N: usize = 3;
+        let mut rng = TestRng::new();
+        let duplexer = FixedDuplexer::<2, Spec<bn254::Fr, N>>::gen(&mut rng);
+        let mut block = || {
+            PlaintextBlock(Box::new(
+                <[Fp<bn254::Fr>; Spec::<bn254::Fr, N>::WIDTH - 1]>::gen(&mut rng),
+            ))
+        };
+        let blocks = [block(), block()];
+        let plaintext = BlockArray::<_, 2>(blocks.clone().into());
+        let key = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+        let header = vec![];
+        let ciphertext = duplexer.encrypt(&key, &(), &header, &plaintext, &mut ());
+        let mut session = duplexer.session(&key, &header, &mut ());
+        let start = session.checkpoint();
+        for (block, expected) in blocks.iter().zip(ciphertext.message.iter()) {
+            assert_eq!(&session.absorb(block, &mut ()), expected);
+        }
+        assert_eq!(session.tag(&mut ()), ciphertext.tag);
+        let end = session.restore(start.clone());
+        assert_eq!(session.state(), start.state());
+        for block in &blocks {
+            session.absorb(block, &mut ());
+        }
+        assert_eq!(session.checkpoint(), end);
+        let left = session.fork(&Fp::from(1u128), &mut ());
+        let right = session.fork(&Fp::from(2u128), &mut ());
+        assert_eq!(
+            session.checkpoint(),
+            end,
+            "Forking should not change the parent."
+        );
+        assert_ne!(left.state(), right.state());
+        assert_ne!(left.state(), session.state());
+        assert_eq!(
+            left.state(),
+            session.fork(&Fp::from(1u128), &mut ()).state()
+        );
+    }
+
+    /// Poseidon Hasher with Arity Two
+    type Poseidon2 = Hasher<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, 2>;
+
+    /// Ciphertext Merkle Tree Configuration
+    struct MailboxConfig;
+
+    impl InnerHash for MailboxConfig {
+        type LeafDigest = Fp<bn254::Fr>;
+        type Parameters = Poseidon2;
+        type Output = Fp<bn254::Fr>;
+
+        #[inline]
+        fn join(
+            parameters: &Self::Parameters,
+            lhs: &Self::Output,
+            rhs: &Self::Output,
+            compiler: &mut (),
+        ) -> Self::Output {
+            parameters.hash([lhs, rhs], compiler)
+        }
+
+        #[inline]
+        fn join_leaves(
+            parameters: &Self::Parameters,
+            lhs: &Self::LeafDigest,
+            rhs: &Self::LeafDigest,
+            compiler: &mut (),
+        ) -> Self::Output {
+            parameters.hash([lhs, rhs], compiler)
+        }
+    }
+
+    impl HashConfiguration for MailboxConfig {
+        type LeafHash =
+            MessageHash<1, Spec<bn254::Fr, 3>, Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag>;
+        type InnerHash = Self;
+    }
+
+    impl Configuration for MailboxConfig {
+        const HEIGHT: usize = 4;
+    }
+
+    /// Tests that a ciphertext merkle tree proves inclusion of its messages, rejects messages it
+    /// does not hold, and survives an encoding round trip.
+    #[test]
+    fn poseidon_ciphertext_tree_test() {
+        const N: usize = 3;
+        let mut rng = TestRng::new();
+        let duplexer = FixedDuplexer::<1, Spec<bn254::Fr, N>>::gen(&mut rng);
+        let key = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+        let hasher = Poseidon2::gen(&mut rng);
+        let mut message = || {
+            let plaintext = BlockArray::<_, 1>(
+                [PlaintextBlock(Box::new(<[Fp<bn254::Fr>;
+                    Spec::<bn254::Fr, N>::WIDTH - 1]>::gen(
+                    &mut rng
+                )))]
+                .into(),
+            );
+            let header = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+            duplexer.encrypt_into(&key, &(), header, &plaintext, &mut ())
+        };
+        let mut tree =
+            CiphertextTree::<_, MailboxConfig>::new(Parameters::new(hasher.clone(), hasher));
+        let messages = (0..tree.capacity()).map(|_| message()).collect::<Vec<_>>();
+        for (index, message) in messages.iter().enumerate() {
+            assert_eq!(tree.insert(message.clone()).ok(), Some(index));
+        }
+        assert!(
+            tree.insert(message()).is_err(),
+            "Insertion into a full tree should fail."
+        );
+        for (index, message) in messages.iter().enumerate() {
+            let (position, path) = tree.prove(message).expect("The message should be stored.");
+            assert_eq!(position, index);
+            assert!(
+                tree.verify(message, &path),
+                "The inclusion proof should verify."
+            );
+            assert_eq!(
+                tree.get(index).map(|stored| tree.digest(stored)),
+                Some(tree.digest(message))
+            );
+        }
+        let other = message();
+        assert_eq!(tree.position(&other), None);
+        assert!(
+            !tree.verify(&other, &tree.path(0).expect("The path should exist.")),
+            "Inclusion proofs should not verify for other messages."
+        );
+        let mut bytes = Vec::new();
+        tree.encode(&mut bytes)
+            .expect("Encoding into a vector is not allowed to fail.");
+        let decoded = CiphertextTree::<_, MailboxConfig>::decode(bytes.as_slice())
+            .expect("Unable to decode the ciphertext tree.");
+        assert_eq!(decoded.len(), tree.len());
+        assert_eq!(decoded.root(), tree.root());
+    }
+
+    /// Tag Digest for Key Rotation Tests
+    struct TagDigest;
+
+    impl HashFunction for TagDigest {
+        type Input = EncryptedMessage<FixedDuplexer<1, Spec<bn254::Fr, 3>>>;
+        type Output = Fp<bn254::Fr>;
+
+        #[inline]
+        fn hash(&self, input: &Self::Input, _: &mut ()) -> Self::Output {
+            input.ciphertext.tag.0
+        }
+    }
+
+    /// Tests that key rotation re-encrypts every message under the new key and records the
+    /// messages which cannot be decrypted with the old key.
+    #[test]
+    fn poseidon_duplexer_rotation_test() {
+        const N: usize = 3;
+        let mut rng = TestRng::new();
+        let duplexer = FixedDuplexer::<1, Spec<bn254::Fr, N>>::gen(&mut rng);
+        let old_key = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+        let new_key = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+        let other_key = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+        let mut plaintext = || {
+            BlockArray::<_, 1>(
+                [PlaintextBlock(Box::new(<[Fp<bn254::Fr>;
+                    Spec::<bn254::Fr, N>::WIDTH - 1]>::gen(
+                    &mut rng
+                )))]
+                .into(),
+            )
+        };
+        let plaintexts = [plaintext(), plaintext(), plaintext()];
+        let mut messages = plaintexts
+            .iter()
+            .map(|plaintext| duplexer.encrypt_into(&old_key, &(), vec![], plaintext, &mut ()))
+            .collect::<Vec<_>>();
+        messages.insert(
+            1,
+            duplexer.encrypt_into(&other_key, &(), vec![], &plaintexts[0], &mut ()),
+        );
+        let rotation = Rotation::new(&duplexer, &old_key, &new_key, &TagDigest);
+        let (rotated, log) = rotation.rotate_all(
+            messages.clone(),
+            |(verified, plaintext)| verified.then_some(plaintext),
+            &mut rng,
+        );
+        assert_eq!(log.len(), messages.len());
+        assert!(!log.is_complete(), "The log should record the failure.");
+        assert_eq!(
+            log.failures().map(|entry| entry.index).collect::<Vec<_>>(),
+            [1],
+            "Only the message under the other key should fail to rotate."
+        );
+        assert_eq!(
+            TagDigest.hash(&rotated[1], &mut ()),
+            TagDigest.hash(&messages[1], &mut ()),
+            "Messages which fail to rotate should be returned unchanged."
+        );
+        for (index, plaintext) in [0, 2, 3].into_iter().zip(&plaintexts) {
+            let (verified, decrypted) = rotated[index].decrypt(&duplexer, &new_key, &mut ());
+            assert!(
+                verified,
+                "Rotated messages should decrypt under the new key."
+            );
+            assert_eq!(&decrypted, plaintext);
+            assert_eq!(
+                log.lookup(&TagDigest.hash(&messages[index], &mut ())),
+                Some(&TagDigest.hash(&rotated[index], &mut ())),
+                "The log should map the old ciphertext to the new one."
+            );
+        }
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod differential {
+    use super::merkle_tree::{Compiler, PoseidonTree};
+    use crate::{
+        constraint::{fp::Fp, FpVar, R1CS},
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use eclair::alloc::{mode::Secret, Allocate};
+    use openzl_crypto::{
+        constraint::{
+            golden::{assert_golden, Golden},
+            test::differential::{hash_agreement, membership_agreement},
+        },
+        hash::{
+            prf::{HashPrf, PseudorandomFunction},
+            ArrayHashFunction,
+        },
+        merkle_tree::{full::Full, MerkleTree, Parameters},
+        poseidon::hash::Hasher,
+    };
+    use openzl_util::rand::{Rand, Sample, TestRng};
+
+    /// Poseidon Hasher over BN254 with Arity Two
+    type Poseidon2<COM = ()> = Hasher<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, 2, COM>;
+
+    /// Tests that the native and in-circuit Poseidon hashes agree on random inputs.
+    #[test]
+    fn poseidon_native_and_circuit_agree() {
+        let mut rng = TestRng::new();
+        let hasher = Poseidon2::gen(&mut rng);
+        for _ in 0..8 {
+            let input = rng.gen::<_, [Fp<bn254::Fr>; 2]>();
+            hash_agreement::<_, Poseidon2<R1CS<bn254::Fr>>, 2, _>(
+                &hasher,
+                &input,
+                &mut R1CS::for_proofs(),
+            );
+        }
+    }
+
+    /// Tests that the Poseidon PRF is the hash of its key and input, natively and in-circuit.
+    #[test]
+    fn poseidon_prf_native_and_circuit_agree() {
+        let mut rng = TestRng::new();
+        let prf = HashPrf::new(Poseidon2::gen(&mut rng));
+        let [key, input] = rng.gen::<_, [Fp<bn254::Fr>; 2]>();
+        let output = hash_agreement::<_, Poseidon2<R1CS<bn254::Fr>>, 2, _>(
+            &prf.0,
+            &[key, input],
+            &mut R1CS::for_proofs(),
+        );
+        assert_eq!(prf.evaluate(&key, &input, &mut ()), output);
+    }
+
+    /// Tests that the native and in-circuit verifications of Poseidon merkle paths agree on valid
+    /// paths and on paths for the wrong leaf.
+    #[test]
+    fn merkle_membership_native_and_circuit_agree() {
+        let mut rng = TestRng::new();
+        let leaves = (0..8).map(|_| Fp::gen(&mut rng)).collect::<Vec<_>>();
+        let tree = MerkleTree::<PoseidonTree<4>, Full<PoseidonTree<4>>>::from_iter(
+            Parameters::new((), Poseidon2::gen(&mut rng)),
+            &leaves,
+        )
+        .expect("Eight leaves should fit into a tree of height four.");
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.path(index).expect("Only valid queries are accepted.");
+            for (item, expected) in [(leaf, true), (&leaves[(index + 1) % 8], false)] {
+                assert_eq!(
+                    membership_agreement::<_, Parameters<PoseidonTree<4, Compiler>, Compiler>, _>(
+                        tree.parameters(),
+                        item,
+                        &path,
+                        tree.root(),
+                        &mut Compiler::for_proofs(),
+                    ),
+                    expected,
+                    "Paths should only verify for their own leaf."
+                );
+            }
+        }
+    }
+
+    /// Golden measurement of the Poseidon-2-to-1 hash over BN254: `79` S-boxes of `3` constraints
+    /// each, except for the S-box of the constant domain tag in the first round.
+    const POSEIDON_2_TO_1: Golden = Golden::new("Poseidon-2-to-1", 234).with_secret_variables(234);
+
+    /// Tests that the in-circuit Poseidon-2-to-1 hash has not drifted from its golden measurement.
+    #[test]
+    fn poseidon_matches_golden_measurement() {
+        let mut rng = TestRng::new();
+        let mut compiler = R1CS::for_proofs();
+        let hasher =
+            Poseidon2::gen(&mut rng).as_constant::<Poseidon2<R1CS<bn254::Fr>>>(&mut compiler);
+        let input = rng
+            .gen::<_, [Fp<bn254::Fr>; 2]>()
+            .map(|value| value.as_known::<Secret, FpVar<_>>(&mut compiler));
+        assert_golden(
+            &POSEIDON_2_TO_1,
+            |compiler| hasher.hash(input.each_ref(), compiler),
+            &mut compiler,
+        );
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod note {
+    use crate::{
+        constraint::fp::Fp,
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use alloc::boxed::Box;
+    use openzl_crypto::{
+        encryption::{
+            convert::plaintext::{Converter, Forward, Reverse},
+            DecryptedPlaintextType, PlaintextType,
+        },
+        hash::prf::HashPrf,
+        poseidon::{
+            encryption::{BlockArray, FixedDuplexer, FixedPlaintext, PlaintextBlock},
+            hash::Hasher,
+        },
+        protocol::note::{Note, Parameters, Payload},
+    };
+    use openzl_util::rand::{Rand, Sample, TestRng};
+
+    /// Field Element
+    type F = Fp<bn254::Fr>;
+
+    /// Poseidon Hasher over BN254
+    type Poseidon<const ARITY: usize> =
+        Hasher<Spec<bn254::Fr, ARITY>, TwoPowerMinusOneDomainTag, ARITY>;
+
+    /// Poseidon Duplexer over BN254 with Two Plaintext Blocks
+    type Duplexer = FixedDuplexer<2, Spec<bn254::Fr, 2>>;
+
+    /// Note Encryption Scheme
+    type Cipher = Converter<Duplexer, NoteBlocks>;
+
+    /// Note Block Encoding
+    ///
+    /// Encodes the three fields of a note into two plaintext blocks of two field elements each,
+    /// padding the last block with zero.
+    struct NoteBlocks;
+
+    impl PlaintextType for NoteBlocks {
+        type Plaintext = Note<F>;
+    }
+
+    impl DecryptedPlaintextType for NoteBlocks {
+        type DecryptedPlaintext = Option<Note<F>>;
+    }
+
+    impl Forward for NoteBlocks {
+        type TargetPlaintext = FixedPlaintext<2, Spec<bn254::Fr, 2>>;
+
+        #[inline]
+        fn as_target(source: &Self::Plaintext, _: &mut ()) -> Self::TargetPlaintext {
+            BlockArray(
+                [
+                    PlaintextBlock(Box::new([source.owner, source.value])),
+                    PlaintextBlock(Box::new([source.trapdoor, Fp(0u64.into())])),
+                ]
+                .into(),
+            )
+        }
+    }
+
+    impl Reverse for NoteBlocks {
+        type TargetDecryptedPlaintext = (bool, FixedPlaintext<2, Spec<bn254::Fr, 2>>);
+
+        #[inline]
+        fn into_source(
+            target: Self::TargetDecryptedPlaintext,
+            _: &mut (),
+        ) -> Self::DecryptedPlaintext {
+            let (verified, blocks) = target;
+            let [first, second] = *blocks.0 .0;
+            match (verified, &*first.0, &*second.0) {
+                (true, [owner, value], [trapdoor, padding]) if *padding == Fp(0u64.into()) => {
+                    Some(Note::new(*owner, *value, *trapdoor))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    /// Tests that note payloads open to their notes and that their nullifiers are bound to the
+    /// secret key of the owner.
+    #[test]
+    fn note_payload_opens_and_nullifies() {
+        let mut rng = TestRng::new();
+        let parameters = Parameters::new(
+            Poseidon::<1>::gen(&mut rng),
+            Poseidon::<3>::gen(&mut rng),
+            HashPrf::new(Poseidon::<2>::gen(&mut rng)),
+        );
+        let cipher = Cipher::new(Duplexer::gen(&mut rng));
+        let key = rng.gen::<_, [F; 2]>().to_vec();
+        let header = rng.gen::<_, [F; 1]>().to_vec();
+        let secret_key = F::gen(&mut rng);
+        let note = Note::new(
+            parameters.owner(&secret_key, &mut ()),
+            F::gen(&mut rng),
+            F::gen(&mut rng),
+        );
+        let payload: Payload<F, Cipher> =
+            parameters.payload(note, &cipher, &key, &(), header, &mut ());
+        assert_eq!(payload.commitment, parameters.commit(&note, &mut ()));
+        assert_eq!(parameters.open(&payload, &cipher, &key), Some(note));
+        let wrong_key = rng.gen::<_, [F; 2]>().to_vec();
+        assert_eq!(parameters.open(&payload, &cipher, &wrong_key), None);
+        let mut tampered = payload.clone();
+        tampered.commitment = F::gen(&mut rng);
+        assert_eq!(parameters.open(&tampered, &cipher, &key), None);
+        let nullifier = parameters.nullifier(&secret_key, &payload.commitment, &mut ());
+        assert_eq!(
+            nullifier,
+            parameters.nullifier(&secret_key, &payload.commitment, &mut ())
+        );
+        assert_ne!(
+            nullifier,
+            parameters.nullifier(&F::gen(&mut rng), &payload.commitment, &mut ())
+        );
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod timed_commitment {
+    use crate::{
+        constraint::{fp::Fp, FpVar, R1CS},
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use eclair::alloc::{
+        mode::{Public, Secret},
+        Allocate,
+    };
+    use openzl_crypto::{
+        commitment::timed::{Opening, Parameters, Reveal},
+        poseidon::hash::Hasher,
+    };
+    use openzl_util::rand::{Rand, Sample, TestRng};
+
+    /// Field Element
+    type F = Fp<bn254::Fr>;
+
+    /// Poseidon Hasher over BN254 with Arity Three
+    type Poseidon3<COM = ()> = Hasher<Spec<bn254::Fr, 3>, TwoPowerMinusOneDomainTag, 3, COM>;
+
+    /// Builds the timed reveal circuit for `reveal` and `trapdoor` at `current_epoch`, returning
+    /// `true` if its constraints are satisfied.
+    fn timed_reveal_is_satisfied(
+        parameters: &Parameters<Poseidon3>,
+        reveal: &Reveal<F>,
+        trapdoor: &F,
+        current_epoch: &F,
+    ) -> bool {
+        let mut compiler = R1CS::for_proofs();
+        let parameters =
+            parameters.as_constant::<Parameters<Poseidon3<R1CS<bn254::Fr>>>>(&mut compiler);
+        let reveal = reveal.as_known::<Public, Reveal<FpVar<_>>>(&mut compiler);
+        let trapdoor = trapdoor.as_known::<Secret, FpVar<_>>(&mut compiler);
+        let current_epoch = current_epoch.as_known::<Public, FpVar<_>>(&mut compiler);
+        parameters.assert_timed_reveal(&reveal, &trapdoor, &current_epoch, &mut compiler);
+        compiler.is_satisfied()
+    }
+
+    /// Tests that timed commitments can only be revealed with their trapdoor, for their value,
+    /// and from their epoch on.
+    #[test]
+    fn timed_commitment_reveals_after_epoch() {
+        let mut rng = TestRng::new();
+        let parameters = Parameters::new(Poseidon3::gen(&mut rng));
+        let opening = Opening::<F>::gen(&mut rng);
+        let epoch = Fp(12u64.into());
+        let reveal = parameters.reveal(&opening, epoch, &mut ());
+        assert!(
+            parameters.verify(&reveal, &opening.trapdoor),
+            "The trapdoor should open the commitment."
+        );
+        assert!(
+            !parameters.verify(&reveal, &F::gen(&mut rng)),
+            "A different trapdoor should not open the commitment."
+        );
+        assert_ne!(
+            parameters.commit_untimed(&opening, &mut ()),
+            reveal.commitment,
+            "The commitment should be bound to its epoch."
+        );
+        for current_epoch in [12u64, 13] {
+            assert!(
+                timed_reveal_is_satisfied(
+                    &parameters,
+                    &reveal,
+                    &opening.trapdoor,
+                    &Fp(current_epoch.into())
+                ),
+                "The commitment should be revealable at epoch {current_epoch}."
+            );
+        }
+        assert!(
+            !timed_reveal_is_satisfied(&parameters, &reveal, &opening.trapdoor, &Fp(11u64.into())),
+            "The commitment should not be revealable before its epoch."
+        );
+        let mut tampered = reveal;
+        tampered.value = F::gen(&mut rng);
+        assert!(
+            !timed_reveal_is_satisfied(&parameters, &tampered, &opening.trapdoor, &epoch),
+            "The commitment should not be revealable to a different value."
+        );
+        let mut early = reveal;
+        early.epoch = Fp(0u64.into());
+        assert!(
+            !timed_reveal_is_satisfied(&parameters, &early, &opening.trapdoor, &epoch),
+            "The commitment should not be revealable with a different epoch."
+        );
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod authenticated_encryption {
+    use crate::{
+        constraint::{fp::Fp, FpVar, R1CS},
+        poseidon::Spec,
+        r1cs_std::R1CSVar,
+    };
+    use alloc::boxed::Box;
+    use eclair::alloc::{
+        mode::{Public, Secret},
+        Allocate,
+    };
+    use openzl_crypto::{
+        encryption::Encrypt,
+        poseidon::encryption::{BlockArray, FixedDuplexer, PlaintextBlock},
+    };
+    use openzl_util::rand::{Rand, Sample, TestRng};
+
+    /// Poseidon Specification over BN254 with Arity Three
+    type S = Spec<bn254::Fr, 3>;
+
+    /// Tests that authenticated decryption accepts genuine ciphertexts and flags ciphertexts with
+    /// a tampered tag, both natively and in-circuit.
+    #[test]
+    fn poseidon_authenticated_decryption_test() {
+        let mut rng = TestRng::new();
+        let duplexer = FixedDuplexer::<1, S>::gen(&mut rng);
+        let plaintext = BlockArray::<_, 1>(
+            [PlaintextBlock(Box::new(
+                <[Fp<bn254::Fr>; S::WIDTH - 1]>::gen(&mut rng),
+            ))]
+            .into(),
+        );
+        let key = vec![Fp::<bn254::Fr>::gen(&mut rng)];
+        let header = vec![];
+        let ciphertext = duplexer.encrypt(&key, &(), &header, &plaintext, &mut ());
+        assert_eq!(
+            duplexer
+                .decrypt_authenticated(&key, &header, &ciphertext, &mut ())
+                .into_option(),
+            Some(plaintext),
+            "The genuine ciphertext should decrypt to the original plaintext."
+        );
+        let mut tampered = ciphertext.clone();
+        tampered.tag.0 = Fp(tampered.tag.0 .0 + bn254::Fr::from(1u64));
+        assert_eq!(
+            duplexer
+                .decrypt_authenticated(&key, &header, &tampered, &mut ())
+                .into_option(),
+            None,
+            "The tampered ciphertext should be rejected."
+        );
+        for (ciphertext, is_genuine) in [(ciphertext, true), (tampered, false)] {
+            let mut compiler = R1CS::<bn254::Fr>::for_proofs();
+            let duplexer =
+                duplexer.as_constant::<FixedDuplexer<1, S, R1CS<bn254::Fr>>>(&mut compiler);
+            let key = key
+                .iter()
+                .map(|element| element.as_known::<Secret, FpVar<_>>(&mut compiler))
+                .collect::<Vec<_>>();
+            let ciphertext = ciphertext.as_known::<Public, _>(&mut compiler);
+            let decrypted =
+                duplexer.decrypt_authenticated(&key, &Vec::new(), &ciphertext, &mut compiler);
+            assert_eq!(
+                decrypted
+                    .is_valid
+                    .value()
+                    .expect("Unable to get the validity of the tag."),
+                is_genuine,
+                "The in-circuit tag check should agree with the native one."
+            );
+            decrypted.assert_valid(&mut compiler);
+            assert_eq!(
+                compiler.is_satisfied(),
+                is_genuine,
+                "Asserting the validity of the tag should only be satisfied for genuine ciphertexts."
+            );
+        }
+    }
+}
+
+/// Poseidon Merkle Tree Configuration
+#[cfg(feature = "bn254")]
+pub mod merkle_tree {
+    use crate::{
+        constraint::R1CS,
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use core::marker::PhantomData;
+    use eclair::alloc::Constant;
+    use openzl_crypto::{
+        hash::ArrayHashFunction,
+        merkle_tree::{Configuration, HashConfiguration, IdentityLeafHash, InnerHash},
+        poseidon::{hash::Hasher, Field, Specification},
+    };
+
+    /// Poseidon Specification over BN254 with Arity Two
+    pub type S = Spec<bn254::Fr, 2>;
+
+    /// Poseidon Hasher with Arity Two
+    pub type Poseidon2<COM = ()> = Hasher<S, TwoPowerMinusOneDomainTag, 2, COM>;
+
+    /// Merkle Tree Digest
+    pub type Digest<COM = ()> = <S as Field<COM>>::Field;
+
+    /// Constraint Compiler
+    pub type Compiler = R1CS<bn254::Fr>;
+
+    /// Poseidon Merkle Tree Configuration with the given `HEIGHT`
+    pub struct PoseidonTree<const HEIGHT: usize, COM = ()>(PhantomData<COM>);
+
+    impl<const HEIGHT: usize, COM> InnerHash<COM> for PoseidonTree<HEIGHT, COM>
+    where
+        S: Specification<COM>,
+    {
+        type LeafDigest = Digest<COM>;
+        type Parameters = Poseidon2<COM>;
+        type Output = Digest<COM>;
+
+        #[inline]
+        fn join(
+            parameters: &Self::Parameters,
+            lhs: &Self::Output,
+            rhs: &Self::Output,
+            compiler: &mut COM,
+        ) -> Self::Output {
+            parameters.hash([lhs, rhs], compiler)
+        }
+
+        #[inline]
+        fn join_leaves(
+            parameters: &Self::Parameters,
+            lhs: &Self::LeafDigest,
+            rhs: &Self::LeafDigest,
+            compiler: &mut COM,
+        ) -> Self::Output {
+            parameters.hash([lhs, rhs], compiler)
+        }
+    }
+
+    impl<const HEIGHT: usize, COM> HashConfiguration<COM> for PoseidonTree<HEIGHT, COM>
+    where
+        S: Specification<COM>,
+        Digest<COM>: Clone,
+    {
+        type LeafHash = IdentityLeafHash<Digest<COM>, COM>;
+        type InnerHash = Self;
+    }
+
+    impl<const HEIGHT: usize, COM> Configuration<COM> for PoseidonTree<HEIGHT, COM>
+    where
+        S: Specification<COM>,
+        Digest<COM>: Clone,
+    {
+        const HEIGHT: usize = HEIGHT;
+    }
+
+    impl<const HEIGHT: usize> Constant<Compiler> for PoseidonTree<HEIGHT, Compiler> {
+        type Type = PoseidonTree<HEIGHT>;
+
+        #[inline]
+        fn new_constant(this: &Self::Type, compiler: &mut Compiler) -> Self {
+            let _ = (this, compiler);
+            Self(PhantomData)
+        }
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod two_level_forest {
+    use super::merkle_tree::{Compiler, Digest, Poseidon2, PoseidonTree};
+    use crate::{
+        constraint::{fp::Fp, FpVar},
+        ff::PrimeField,
+        r1cs_std::R1CSVar,
+    };
+    use eclair::{
+        alloc::{
+            mode::{Public, Secret},
+            Allocate,
+        },
+        bool::Assert,
+    };
+    use openzl_crypto::{
+        accumulator::Accumulator,
+        merkle_tree::{
+            forest::{self, RootAccumulatedForest, TwoLevelParameters, TwoLevelPathVar},
+            full::Full,
+            Parameters,
+        },
+    };
+    use openzl_util::rand::{Rand, Sample, TestRng};
+
+    /// Tree Configuration with four Leaves per Tree
+    type Trees<COM = ()> = PoseidonTree<3, COM>;
+
+    /// Tree of Roots Configuration with a Leaf for each of the `256` Trees
+    type Roots<COM = ()> = PoseidonTree<9, COM>;
+
+    impl forest::Configuration for Trees {
+        type Index = u8;
+
+        #[inline]
+        fn tree_index(leaf: &Digest) -> Self::Index {
+            leaf.0.into_repr().as_ref()[0] as u8
+        }
+    }
+
+    /// Root-Accumulated Merkle Forest
+    type Forest = RootAccumulatedForest<Trees, Roots, Full<Trees>, 256>;
+
+    /// Verifies the two-level membership proof of `leaf` against `root` in-circuit, returning the
+    /// in-circuit verification bit and whether asserting it is satisfied.
+    fn verify_in_circuit(
+        parameters: &TwoLevelParameters<Trees, Roots>,
+        path: &forest::TwoLevelPath<Trees, Roots>,
+        root: &Digest,
+        leaf: &Digest,
+    ) -> (bool, bool) {
+        let mut compiler = Compiler::for_proofs();
+        let parameters = parameters
+            .as_constant::<TwoLevelParameters<Trees<Compiler>, Roots<Compiler>, Compiler>>(
+                &mut compiler,
+            );
+        let path = path
+            .as_known::<Secret, TwoLevelPathVar<Trees<Compiler>, Roots<Compiler>, Compiler>>(
+                &mut compiler,
+            );
+        let root = root.as_known::<Public, FpVar<_>>(&mut compiler);
+        let leaf = leaf.as_known::<Secret, FpVar<_>>(&mut compiler);
+        let is_member = parameters.verify_path_with(&path, &root, &leaf, &mut compiler);
+        let verification = is_member
+            .value()
+            .expect("Unable to get the value of the membership bit.");
+        compiler.assert(&is_member);
+        (verification, compiler.is_satisfied())
+    }
+
+    /// Tests that two-level forest proofs open every item to the common root of the forest, both
+    /// natively and in-circuit, and reject items they do not witness.
+    #[test]
+    fn two_level_forest_proofs_verify() {
+        let mut rng = TestRng::new();
+        let hasher = Poseidon2::gen(&mut rng);
+        let mut forest = Forest::new(TwoLevelParameters::new(
+            Parameters::new((), hasher.clone()),
+            Parameters::new((), hasher),
+        ));
+        let leaves = (0..8).map(|_| Fp::gen(&mut rng)).collect::<Vec<_>>();
+        for leaf in &leaves {
+            assert!(forest.insert(leaf), "The leaf should have been inserted.");
+        }
+        let root = forest.root();
+        for leaf in &leaves {
+            let proof = forest
+                .prove(leaf)
+                .expect("The inserted leaf should have a membership proof.");
+            assert_eq!(
+                proof.output(),
+                &root,
+                "The proof should open to the root of the tree of roots."
+            );
+            assert!(
+                proof.verify(forest.model(), leaf, &mut ()),
+                "The proof should verify natively."
+            );
+            let (path, _) = proof.into_parts();
+            assert_eq!(
+                path.tree_index(),
+                leaf.0.into_repr().as_ref()[0] as usize,
+                "The proof should open the root of the tree storing the leaf."
+            );
+            assert_eq!(
+                verify_in_circuit(forest.parameters(), &path, &root, leaf),
+                (true, true),
+                "The proof should verify in-circuit."
+            );
+            let other = Fp::gen(&mut rng);
+            assert!(
+                !forest.parameters().verify_path(&path, &root, &other),
+                "The proof should not verify a different leaf natively."
+            );
+            assert_eq!(
+                verify_in_circuit(forest.parameters(), &path, &root, &other),
+                (false, false),
+                "The proof should not verify a different leaf in-circuit."
+            );
+        }
+        assert!(
+            forest.prove(&Fp::gen(&mut rng)).is_none(),
+            "Leaves which were not inserted should not have a membership proof."
+        );
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod multi_path {
+    use super::merkle_tree::{Compiler, Digest, Poseidon2, PoseidonTree};
+    use crate::{
+        constraint::{fp::Fp, FpVar},
+        r1cs_std::R1CSVar,
+    };
+    use eclair::{
+        alloc::{
+            mode::{Public, Secret},
+            Allocate,
+        },
+        bool::Assert,
+    };
+    use openzl_crypto::{
+        constraint::measure::Measure,
+        merkle_tree::{
+            full::Full,
+            path::{
+                constraint::{MultiPathVar, PathVar},
+                MultiPath,
+            },
+            test::assert_valid_multi_path,
+            MerkleTree, Parameters, Path,
+        },
+    };
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Tree Configuration with sixteen Leaves
+    type Tree<COM = ()> = PoseidonTree<5, COM>;
+
+    /// Full Merkle Tree
+    type Merkle = MerkleTree<Tree, Full<Tree>>;
+
+    /// Builds a full merkle tree over sixteen random leaves, returning the tree and its leaves.
+    fn sample_tree(rng: &mut TestRng) -> (Merkle, Vec<Digest>) {
+        let leaves = (0..16).map(|_| Fp::gen(rng)).collect::<Vec<_>>();
+        let tree = Merkle::from_iter(Parameters::new((), Poseidon2::gen(rng)), &leaves)
+            .expect("Sixteen leaves should fit into a tree of height five.");
+        (tree, leaves)
+    }
+
+    /// Verifies `multi_path` against `root` and `leaves` in-circuit, returning the in-circuit
+    /// verification bit, whether asserting it is satisfied, and the number of constraints.
+    fn verify_multi_path_in_circuit(
+        parameters: &Parameters<Tree>,
+        multi_path: &MultiPath<Tree>,
+        root: &Digest,
+        leaves: &[Digest],
+    ) -> (bool, bool, usize) {
+        let mut compiler = Compiler::for_proofs();
+        let parameters = parameters.as_constant::<Parameters<Tree<Compiler>, _>>(&mut compiler);
+        let multi_path = MultiPathVar::<Tree<Compiler>, _>::new_known(multi_path, &mut compiler);
+        let root = root.as_known::<Public, FpVar<_>>(&mut compiler);
+        let leaves = leaves
+            .iter()
+            .map(|leaf| leaf.as_known::<Secret, FpVar<_>>(&mut compiler))
+            .collect::<Vec<_>>();
+        let is_member = multi_path.verify(&parameters, &root, &leaves, &mut compiler);
+        let verification = is_member
+            .value()
+            .expect("Unable to get the value of the membership bit.");
+        compiler.assert(&is_member);
+        (
+            verification,
+            compiler.is_satisfied(),
+            compiler.constraint_count(),
+        )
+    }
+
+    /// Verifies each of `paths` against `root` and the matching leaf of `leaves` in-circuit,
+    /// returning whether asserting every verification bit is satisfied and the number of
+    /// constraints.
+    fn verify_paths_in_circuit(
+        parameters: &Parameters<Tree>,
+        paths: &[Path<Tree>],
+        root: &Digest,
+        leaves: &[Digest],
+    ) -> (bool, usize) {
+        let mut compiler = Compiler::for_proofs();
+        let parameters = parameters.as_constant::<Parameters<Tree<Compiler>, _>>(&mut compiler);
+        let root = root.as_known::<Public, FpVar<_>>(&mut compiler);
+        for (path, leaf) in paths.iter().zip(leaves) {
+            let path = path.as_known::<Secret, PathVar<Tree<Compiler>, _>>(&mut compiler);
+            let leaf = leaf.as_known::<Secret, FpVar<_>>(&mut compiler);
+            let is_member = path.verify(&parameters, &root, &leaf, &mut compiler);
+            compiler.assert(&is_member);
+        }
+        (compiler.is_satisfied(), compiler.constraint_count())
+    }
+
+    /// Returns the total number of digests stored in `multi_path`.
+    fn multi_path_size(multi_path: &MultiPath<Tree>) -> usize {
+        multi_path.leaf_siblings.len() + multi_path.path.len()
+    }
+
+    /// Tests that multi-paths built from a Poseidon merkle tree verify natively and in-circuit, and
+    /// that they are never larger or more expensive to verify than the independent paths they
+    /// merge, and strictly cheaper when the opened leaves share siblings.
+    #[test]
+    fn multi_paths_verify_and_beat_independent_paths() {
+        let mut rng = TestRng::new();
+        let (tree, leaves) = sample_tree(&mut rng);
+        let root = tree.root();
+        let index_sets: [&[usize]; 6] = [
+            &[5][..],
+            &[0, 1][..],
+            &[2, 3, 8][..],
+            &[0, 5, 10, 15][..],
+            &[4, 5, 6, 7][..],
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15][..],
+        ];
+        for indices in index_sets {
+            let opened = indices.iter().map(|i| leaves[*i]).collect::<Vec<_>>();
+            assert_valid_multi_path(&tree, indices, &opened);
+            let multi_path = tree
+                .multi_path(indices.iter().copied())
+                .expect("Only valid queries are accepted.");
+            let paths = indices
+                .iter()
+                .map(|i| tree.path(*i).expect("Only valid queries are accepted."))
+                .collect::<Vec<_>>();
+            let independent_size = paths
+                .iter()
+                .map(|path| 1 + path.inner_path.path.len())
+                .sum::<usize>();
+            let (verification, is_satisfied, multi_constraints) =
+                verify_multi_path_in_circuit(tree.parameters(), &multi_path, root, &opened);
+            assert!(
+                verification && is_satisfied,
+                "The multi-path for {:?} should verify in-circuit.",
+                indices
+            );
+            let (paths_satisfied, independent_constraints) =
+                verify_paths_in_circuit(tree.parameters(), &paths, root, &opened);
+            assert!(
+                paths_satisfied,
+                "The independent paths for {:?} should verify in-circuit.",
+                indices
+            );
+            assert!(
+                multi_constraints <= independent_constraints,
+                "The multi-path for {:?} should not need more constraints than the independent \
+                paths.",
+                indices
+            );
+            if indices.len() == 1 {
+                assert_eq!(
+                    multi_path_size(&multi_path),
+                    independent_size,
+                    "A multi-path opening one leaf should be as large as its path."
+                );
+            } else {
+                assert!(
+                    multi_path_size(&multi_path) < independent_size,
+                    "The multi-path for {:?} should be smaller than the independent paths.",
+                    indices
+                );
+                assert!(
+                    multi_constraints < independent_constraints,
+                    "The multi-path for {:?} should need fewer constraints than the independent \
+                    paths.",
+                    indices
+                );
+            }
+        }
+    }
+
+    /// Tests that multi-paths reject leaves they do not witness, natively and in-circuit.
+    #[test]
+    fn multi_paths_reject_wrong_leaves() {
+        let mut rng = TestRng::new();
+        let (tree, leaves) = sample_tree(&mut rng);
+        let indices = [1, 6, 7, 12];
+        let mut opened = indices.iter().map(|i| leaves[*i]).collect::<Vec<_>>();
+        let multi_path = tree
+            .multi_path(indices)
+            .expect("Only valid queries are accepted.");
+        for position in 0..opened.len() {
+            let leaf = opened[position];
+            opened[position] = Fp::gen(&mut rng);
+            assert!(
+                !multi_path.verify(tree.parameters(), tree.root(), &opened),
+                "The multi-path should not verify a replaced leaf natively."
+            );
+            let (verification, is_satisfied, _) =
+                verify_multi_path_in_circuit(tree.parameters(), &multi_path, tree.root(), &opened);
+            assert!(
+                !verification && !is_satisfied,
+                "The multi-path should not verify a replaced leaf in-circuit."
+            );
+            opened[position] = leaf;
+        }
+        opened.swap(0, 1);
+        assert!(
+            !multi_path.verify(tree.parameters(), tree.root(), &opened),
+            "The multi-path should not verify leaves given out of order."
+        );
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod mutated_paths {
+    use super::merkle_tree::{Compiler, Digest, Poseidon2, PoseidonTree};
+    use crate::constraint::fp::Fp;
+    use openzl_crypto::merkle_tree::{
+        full::Full,
+        test::{assert_rejects_mutated_paths, assert_rejects_mutated_paths_in_circuit},
+        MerkleTree, Parameters,
+    };
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Tree Configuration with eight Leaves
+    type Tree<COM = ()> = PoseidonTree<4, COM>;
+
+    /// Returns a digest which is different from `digest`.
+    fn flip(digest: &Digest) -> Digest {
+        Fp(digest.0 + bn254::Fr::from(1u64))
+    }
+
+    /// Tests that every mutation of every path of a Poseidon merkle tree is rejected natively and
+    /// in-circuit, with the in-circuit verification agreeing with the native one.
+    #[test]
+    fn mutated_paths_are_rejected() {
+        let mut rng = TestRng::new();
+        let leaves = (0..8).map(|_| Fp::gen(&mut rng)).collect::<Vec<_>>();
+        let tree = MerkleTree::<Tree, Full<Tree>>::from_iter(
+            Parameters::new((), Poseidon2::gen(&mut rng)),
+            &leaves,
+        )
+        .expect("Eight leaves should fit into a tree of height four.");
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert_rejects_mutated_paths(&tree, index, leaf, flip, flip);
+            assert_rejects_mutated_paths_in_circuit::<
+                _,
+                Parameters<Tree<Compiler>, Compiler>,
+                _,
+                _,
+                _,
+                _,
+            >(
+                tree.parameters(),
+                tree.root(),
+                leaf,
+                &tree.path(index).expect("Only valid queries are accepted."),
+                flip,
+                flip,
+                Compiler::for_proofs,
+            );
+        }
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod sparse {
+    use super::merkle_tree::{Digest, Poseidon2, PoseidonTree};
+    use crate::ff::PrimeField;
+    use openzl_crypto::{
+        accumulator::{
+            dynamic::test::assert_removal_laws,
+            test::{assert_random_operation_laws, Operations},
+            Accumulator,
+        },
+        merkle_tree::{
+            sparse::{LeafIndex, SparseMerkleTree},
+            Parameters,
+        },
+    };
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Tree Configuration with sixteen Leaves
+    type Tree = PoseidonTree<5>;
+
+    /// Leaf Index from the Lowest Byte of the Leaf Digest
+    struct LowestByte;
+
+    impl LeafIndex<Tree> for LowestByte {
+        #[inline]
+        fn leaf_index(&self, leaf_digest: &Digest) -> usize {
+            leaf_digest.0.into_repr().as_ref()[0] as usize
+        }
+    }
+
+    /// Sparse Merkle Tree
+    type Sparse = SparseMerkleTree<Tree, LowestByte>;
+
+    /// Samples an empty sparse merkle tree.
+    fn sample_tree(rng: &mut TestRng) -> Sparse {
+        Sparse::new(Parameters::new((), Poseidon2::gen(rng)), LowestByte)
+    }
+
+    /// Samples `count` leaves with distinct positions in `tree`.
+    fn sample_leaves(tree: &Sparse, count: usize, rng: &mut TestRng) -> Vec<Digest> {
+        let mut leaves = Vec::<Digest>::with_capacity(count);
+        while leaves.len() < count {
+            let leaf = Digest::gen(rng);
+            if leaves
+                .iter()
+                .all(|other| tree.position(other) != tree.position(&leaf))
+            {
+                leaves.push(leaf);
+            }
+        }
+        leaves
+    }
+
+    /// Samples a leaf whose position in `tree` is the position of `leaf` but which is different
+    /// from `leaf`.
+    fn sample_collision(tree: &Sparse, leaf: &Digest, rng: &mut TestRng) -> Digest {
+        loop {
+            let other = Digest::gen(rng);
+            if other != *leaf && tree.position(&other) == tree.position(leaf) {
+                return other;
+            }
+        }
+    }
+
+    /// Tests that inserted leaves have valid membership proofs, that colliding leaves are
+    /// rejected and that removing a leaf restores the root of the tree without it.
+    #[test]
+    fn sparse_tree_inserts_and_removes_leaves() {
+        let mut rng = TestRng::new();
+        let mut tree = sample_tree(&mut rng);
+        let empty_root = *tree.root();
+        let leaves = sample_leaves(&tree, 6, &mut rng);
+        for leaf in &leaves {
+            assert!(tree.insert(leaf).is_some(), "Leaves should be inserted.");
+        }
+        assert_eq!(tree.len(), leaves.len());
+        for leaf in &leaves {
+            assert!(
+                tree.prove(leaf)
+                    .expect("Inserted leaves should have membership proofs.")
+                    .verify(tree.model(), leaf, &mut ()),
+                "Membership proofs of inserted leaves must verify."
+            );
+        }
+        let collision = sample_collision(&tree, &leaves[0], &mut rng);
+        assert!(
+            tree.insert(&collision).is_none(),
+            "Leaves at occupied positions must be rejected."
+        );
+        assert!(!tree.contains(&collision));
+        assert!(tree.prove(&collision).is_none());
+        let mut without_removed = Sparse::new(tree.parameters().clone(), LowestByte);
+        for leaf in &leaves[1..] {
+            without_removed.insert(leaf);
+        }
+        assert!(tree.remove(&leaves[0]).is_some());
+        assert!(
+            tree.remove(&leaves[0]).is_none(),
+            "Removed leaves cannot be removed again."
+        );
+        assert!(!tree.contains(&leaves[0]));
+        assert_eq!(
+            tree.root(),
+            without_removed.root(),
+            "Removing a leaf should restore the root of the tree without it."
+        );
+        for leaf in &leaves[1..] {
+            assert!(tree.remove(leaf).is_some());
+        }
+        assert!(tree.is_empty());
+        assert_eq!(
+            *tree.root(),
+            empty_root,
+            "Removing every leaf should restore the empty root."
+        );
+    }
+
+    /// Tests that absent and removed leaves have valid non-membership proofs, and that stored
+    /// leaves do not.
+    #[test]
+    fn sparse_tree_proves_non_membership() {
+        let mut rng = TestRng::new();
+        let mut tree = sample_tree(&mut rng);
+        let leaves = sample_leaves(&tree, 7, &mut rng);
+        let (stored, absent) = leaves.split_at(4);
+        let proof = tree
+            .prove_non_membership(&absent[0])
+            .expect("Every position of the empty tree should be empty.");
+        assert!(tree.verify_non_membership(tree.root(), &absent[0], &proof));
+        for leaf in stored {
+            tree.insert(leaf);
+        }
+        for leaf in stored {
+            assert!(
+                tree.prove_non_membership(leaf).is_none(),
+                "Stored leaves must not have non-membership proofs."
+            );
+        }
+        for leaf in absent {
+            let proof = tree
+                .prove_non_membership(leaf)
+                .expect("Absent leaves should have non-membership proofs.");
+            assert!(
+                tree.verify_non_membership(tree.root(), leaf, &proof),
+                "Non-membership proofs of absent leaves must verify."
+            );
+            for other in absent.iter().filter(|other| *other != leaf) {
+                assert!(
+                    !tree.verify_non_membership(tree.root(), other, &proof),
+                    "Non-membership proofs must only verify at the position of their leaf."
+                );
+            }
+        }
+        let collision = sample_collision(&tree, &stored[0], &mut rng);
+        assert!(tree.prove_non_membership(&collision).is_none());
+        assert!(
+            !tree
+                .path(tree.position(&collision))
+                .expect("The position of the collision should be occupied.")
+                .verify_digest(tree.parameters(), tree.root(), &collision),
+            "The path of the occupant must not verify for a different leaf."
+        );
+        let root_with_stored = *tree.root();
+        tree.remove(&stored[0]);
+        let proof = tree
+            .prove_non_membership(&stored[0])
+            .expect("Removed leaves should have non-membership proofs.");
+        assert!(tree.verify_non_membership(tree.root(), &stored[0], &proof));
+        assert!(
+            !tree.verify_non_membership(&root_with_stored, &stored[0], &proof),
+            "Non-membership proofs must not verify against a root which stores the leaf."
+        );
+    }
+
+    /// Tests that random insertions and membership queries, including insertions at occupied
+    /// positions, satisfy the accumulator operation laws.
+    #[test]
+    fn sparse_tree_satisfies_operation_laws() {
+        let mut rng = TestRng::new();
+        for _ in 0..4 {
+            assert_random_operation_laws(
+                &mut sample_tree(&mut rng),
+                Operations::new(32, ()),
+                &mut rng,
+            );
+        }
+    }
+
+    /// Tests that the sparse merkle tree satisfies the removal laws of dynamic accumulators.
+    #[test]
+    fn sparse_tree_satisfies_removal_laws() {
+        let mut rng = TestRng::new();
+        let leaves = sample_leaves(&sample_tree(&mut rng), 6, &mut rng);
+        for removed in &leaves {
+            assert_removal_laws(&mut sample_tree(&mut rng), &leaves, removed);
+        }
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod batch {
+    use crate::{
+        constraint::fp::Fp,
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use openzl_crypto::{
+        hash::ArrayHashFunction,
+        permutation::PseudorandomPermutation,
+        poseidon::{batch::BatchState, hash::Hasher, Constants, Error, Permutation, State},
+    };
+    use openzl_util::rand::{Rand, Sample, TestRng};
+
+    /// Poseidon Hasher over BN254 with Arity Two
+    type Poseidon2 = Hasher<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, 2>;
+
+    /// Tests that batched permutation agrees with permuting every state on its own.
+    #[test]
+    fn batch_permutation_matches_permutation() {
+        let mut rng = TestRng::new();
+        let permutation = Permutation::<Spec<bn254::Fr, 4>>::gen(&mut rng);
+        let states = (0..17)
+            .map(|_| State::<Spec<bn254::Fr, 4>>::gen(&mut rng))
+            .collect::<Vec<_>>();
+        let mut batch = BatchState::from_states(states.clone());
+        permutation.permute_batch(&mut batch, &mut ());
+        for (mut state, batched) in states.into_iter().zip(batch.into_states()) {
+            permutation.permute(&mut state, &mut ());
+            assert_eq!(
+                state, batched,
+                "Batched permutation must match the permutation."
+            );
+        }
+    }
+
+    /// Tests that a permutation accepts its own parameters in row layout and rejects a changed
+    /// round constant.
+    #[test]
+    fn permutation_checks_parameters() {
+        let mut rng = TestRng::new();
+        let permutation = Permutation::<Spec<bn254::Fr, 2>>::gen(&mut rng);
+        let width = Spec::<bn254::Fr, 2>::WIDTH;
+        let mut round_constants = permutation
+            .additive_round_keys()
+            .chunks(width)
+            .map(|keys| keys.to_vec())
+            .collect::<Vec<_>>();
+        let mds_matrix = permutation.mds_matrix().chunks(width).collect::<Vec<_>>();
+        assert_eq!(
+            permutation.check_parameters(&round_constants, &mds_matrix),
+            Ok(())
+        );
+        round_constants[3][1] = Fp(round_constants[3][1].0 + bn254::Fr::from(1u64));
+        assert_eq!(
+            permutation.check_parameters(&round_constants, &mds_matrix),
+            Err(Error::MismatchedRoundConstant { round: 3, index: 1 })
+        );
+    }
+
+    /// Tests that a permutation rejects parameters in row layout with missing rounds, rows of the
+    /// wrong width, and changed MDS entries.
+    #[test]
+    fn permutation_rejects_mismatched_parameters() {
+        let mut rng = TestRng::new();
+        let permutation = Permutation::<Spec<bn254::Fr, 2>>::gen(&mut rng);
+        let width = Spec::<bn254::Fr, 2>::WIDTH;
+        let rounds = Spec::<bn254::Fr, 2>::ROUNDS;
+        let round_constants = permutation
+            .additive_round_keys()
+            .chunks(width)
+            .collect::<Vec<_>>();
+        let mut mds_matrix = permutation
+            .mds_matrix()
+            .chunks(width)
+            .map(|row| row.to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            permutation.check_parameters(&round_constants[..rounds - 1], &mds_matrix),
+            Err(Error::InvalidAdditiveRoundKeysLength {
+                expected: Spec::<bn254::Fr, 2>::ADDITIVE_ROUND_KEYS_COUNT,
+                found: (rounds - 1) * width,
+            }),
+            "Missing rounds must be rejected."
+        );
+        assert_eq!(
+            permutation.check_parameters(&round_constants, &mds_matrix[..width - 1]),
+            Err(Error::InvalidMdsMatrixLength {
+                expected: Spec::<bn254::Fr, 2>::MDS_MATRIX_SIZE,
+                found: (width - 1) * width,
+            }),
+            "Missing MDS rows must be rejected."
+        );
+        mds_matrix[1].pop();
+        assert_eq!(
+            permutation.check_parameters(&round_constants, &mds_matrix),
+            Err(Error::InvalidMdsMatrixLength {
+                expected: Spec::<bn254::Fr, 2>::MDS_MATRIX_SIZE,
+                found: 2 * width - 1,
+            }),
+            "MDS rows of the wrong width must be rejected."
+        );
+        mds_matrix[1] = permutation.mds_matrix()[width..2 * width].to_vec();
+        mds_matrix[1][2] = Fp(mds_matrix[1][2].0 + bn254::Fr::from(1u64));
+        assert_eq!(
+            permutation.check_parameters(&round_constants, &mds_matrix),
+            Err(Error::MismatchedMdsEntry { row: 1, column: 2 }),
+            "Changed MDS entries must be rejected."
+        );
+    }
+
+    /// Tests that states, permutations, and hashers of the wrong shape are rejected by their
+    /// fallible constructors.
+    #[test]
+    fn constructors_reject_invalid_lengths() {
+        let mut rng = TestRng::new();
+        let width = Spec::<bn254::Fr, 2>::WIDTH;
+        let state = (0..width - 1)
+            .map(|_| Fp::<bn254::Fr>::gen(&mut rng))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            State::<Spec<bn254::Fr, 2>>::try_new(state.into_boxed_slice()).err(),
+            Some(Error::InvalidStateLength {
+                expected: width,
+                found: width - 1,
+            }),
+            "States of the wrong width must be rejected."
+        );
+        let permutation = Permutation::<Spec<bn254::Fr, 2>>::gen(&mut rng);
+        let keys = permutation.additive_round_keys();
+        let mds_matrix = permutation.mds_matrix();
+        assert_eq!(
+            Permutation::<Spec<bn254::Fr, 2>>::try_new(keys[1..].into(), mds_matrix.into()).err(),
+            Some(Error::InvalidAdditiveRoundKeysLength {
+                expected: keys.len(),
+                found: keys.len() - 1,
+            }),
+            "Permutations with missing round keys must be rejected."
+        );
+        assert_eq!(
+            Permutation::<Spec<bn254::Fr, 2>>::try_new(keys.into(), mds_matrix[1..].into()).err(),
+            Some(Error::InvalidMdsMatrixLength {
+                expected: mds_matrix.len(),
+                found: mds_matrix.len() - 1,
+            }),
+            "Permutations with a truncated MDS matrix must be rejected."
+        );
+        assert_eq!(
+            Hasher::<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, 3>::try_new(
+                permutation.clone(),
+                Fp::gen(&mut rng),
+            )
+            .err(),
+            Some(Error::InvalidArity { arity: 3, width }),
+            "Hashers whose arity does not match the width must be rejected."
+        );
+        assert!(
+            Poseidon2::try_new(permutation, Fp::gen(&mut rng)).is_ok(),
+            "Hashers of arity two should be built over permutations of width three."
+        );
+    }
+
+    /// Tests that constants with too few rounds are rejected as insecure.
+    #[cfg(feature = "std")]
+    #[test]
+    fn insecure_constants_are_rejected() {
+        use openzl_crypto::poseidon::constants;
+
+        impl Constants for Spec<bn254::Fr, 1> {
+            const WIDTH: usize = 2;
+            const FULL_ROUNDS: usize = 2;
+            const PARTIAL_ROUNDS: usize = 1;
+        }
+
+        assert_eq!(
+            constants::Constants::try_from_specification::<Spec<bn254::Fr, 1>>(),
+            Err(Error::InsecureConstants),
+            "Constants with too few rounds must be rejected."
+        );
+        assert!(
+            constants::Constants::try_from_specification::<Spec<bn254::Fr, 2>>().is_ok(),
+            "The constants of arity two should be secure."
+        );
+    }
+
+    /// Tests that batched hashing agrees with hashing every input on its own.
+    #[test]
+    fn batch_hash_matches_hash() {
+        let mut rng = TestRng::new();
+        let hasher = Poseidon2::gen(&mut rng);
+        let inputs = (0..17)
+            .map(|_| rng.gen::<_, [Fp<bn254::Fr>; 2]>())
+            .collect::<Vec<_>>();
+        let outputs = hasher.hash_batch(&inputs, &mut ());
+        assert_eq!(outputs.len(), inputs.len());
+        for (input, output) in inputs.iter().zip(outputs) {
+            assert_eq!(hasher.hash(input.each_ref(), &mut ()), output);
+        }
+    }
+}
+
+#[cfg(feature = "bn254")]
+mod incremental {
+    use crate::{
+        constraint::fp::Fp,
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
     use alloc::boxed::Box;
     use openzl_crypto::{
-        encryption::{Decrypt, Encrypt},
+        hash::{
+            incremental::{IncrementalHash, MerkleDamgard, SpongeHash},
+            ArrayHashFunction,
+        },
+        permutation::sponge::Sponge,
         poseidon::{
-            encryption::{BlockArray, FixedDuplexer, PlaintextBlock},
-            Constants,
+            encryption::{PlaintextBlock, Tag},
+            hash::Hasher,
+            Permutation, State,
         },
     };
-    use openzl_util::rand::{OsRng, Sample};
+    use openzl_util::rand::{Sample, TestRng};
 
-    /// Tests Poseidon duplexer encryption works.
+    /// Poseidon Hasher over BN254 with Arity Two
+    type Poseidon2 = Hasher<Spec<bn254::Fr, 2>, TwoPowerMinusOneDomainTag, 2>;
+
+    /// Tests that streaming digests agree with compressing and absorbing the blocks by hand.
     #[test]
-    fn poseidon_duplexer_test() {
-        const N: usize = 3;
-        let mut rng = OsRng;
-        let duplexer = FixedDuplexer::<1, Spec<bn254::Fr, N>>::gen(&mut rng);
-        let field_elements = <[Fp<bn254::Fr>; Spec::<bn254::Fr, N>::WIDTH - 1]>::gen(&mut rng);
-        let plaintext_block = PlaintextBlock(Box::new(field_elements));
-        let plaintext = BlockArray::<_, 1>([plaintext_block].into());
-        let mut key = Vec::new();
-        let key_element_1 = Fp::<bn254::Fr>::gen(&mut rng);
-        let key_element_2 = Fp::<bn254::Fr>::gen(&mut rng);
-        key.push(key_element_1);
-        key.push(key_element_2);
-        let header = vec![];
-        let ciphertext = duplexer.encrypt(&key, &(), &header, &plaintext, &mut ());
-        let (tag_matches, decrypted_plaintext) =
-            duplexer.decrypt(&key, &header, &ciphertext, &mut ());
-        assert!(tag_matches, "Tag doesn't match");
+    fn incremental_hashes_match_manual_computation() {
+        let mut rng = TestRng::new();
+        let blocks = (0..9)
+            .map(|_| Fp::<bn254::Fr>::gen(&mut rng))
+            .collect::<Vec<_>>();
+        let hasher = Poseidon2::gen(&mut rng);
+        let initial_value = Fp::<bn254::Fr>::gen(&mut rng);
+        let merkle_damgard = MerkleDamgard::new(hasher.clone(), initial_value);
+        let mut state = merkle_damgard.init(&mut ());
+        for block in &blocks {
+            merkle_damgard.update(&mut state, block, &mut ());
+        }
+        let chain = blocks.iter().fold(initial_value, |chain, block| {
+            hasher.hash([&chain, block], &mut ())
+        });
+        let length = Fp(bn254::Fr::from(blocks.len() as u64));
         assert_eq!(
-            plaintext, decrypted_plaintext,
-            "Decrypted plaintext is not equal to original one."
+            merkle_damgard.finalize(state, &mut ()),
+            hasher.hash([&chain, &length], &mut ()),
+            "The Merkle-Damgard digest must end with the number of blocks."
+        );
+        assert_ne!(
+            merkle_damgard.hash_all(&blocks, &mut ()),
+            merkle_damgard.hash_all(&blocks[..8], &mut ()),
+            "Inputs of different lengths should have different digests."
+        );
+        let permutation = Permutation::<Spec<bn254::Fr, 2>>::gen(&mut rng);
+        let initial_state = State::<Spec<bn254::Fr, 2>>::gen(&mut rng);
+        let sponge_hash = SpongeHash::<_, PlaintextBlock<_>, Tag<_>>::new(
+            permutation.clone(),
+            initial_state.clone(),
+        );
+        let blocks = blocks
+            .chunks(3)
+            .map(|chunk| PlaintextBlock(Box::from(&chunk[..2])))
+            .collect::<Vec<_>>();
+        let mut state = initial_state;
+        let mut sponge = Sponge::new(&permutation, &mut state);
+        for block in &blocks {
+            sponge.absorb(block, &mut ());
+        }
+        assert_eq!(
+            sponge_hash.hash_all(&blocks, &mut ()),
+            sponge.squeeze::<Tag<_>>(&mut ()),
+            "The sponge digest must be the first squeeze after absorbing every block."
+        );
+    }
+}
+
+#[cfg(all(feature = "bn254", feature = "serialize"))]
+mod serialize {
+    use crate::{
+        poseidon::Spec,
+        serialize::{CanonicalDeserialize, CanonicalSerialize, Codec},
+    };
+    use openzl_crypto::poseidon::Permutation;
+    use openzl_util::rand::{Rand, TestRng};
+
+    /// Tests that Poseidon permutations round-trip through the arkworks canonical serialization.
+    #[test]
+    fn permutation_canonical_serialization_round_trip() {
+        let permutation = Codec(TestRng::new().gen::<_, Permutation<Spec<bn254::Fr, 2>>>());
+        let mut bytes = Vec::new();
+        permutation
+            .serialize(&mut bytes)
+            .expect("Serialization into a vector is not allowed to fail.");
+        assert_eq!(bytes.len(), permutation.serialized_size());
+        assert_eq!(
+            permutation,
+            Codec::deserialize(bytes.as_slice()).expect("Unable to deserialize the permutation."),
         );
     }
 }
@@ -381,10 +1975,11 @@ mod mds {
     use super::*;
     use crate::ff::UniformRand;
     use openzl_crypto::poseidon::{
-        matrix::{Matrix, MatrixOperations},
-        mds::MdsMatrices,
+        matrix::{Matrix, MatrixOperations, SquareMatrix},
+        mds::{self, MdsConstruction, MdsMatrices},
+        Error, FieldGeneration,
     };
-    use openzl_util::rand::OsRng;
+    use openzl_util::rand::TestRng;
 
     /// Checks if creating mds matrices is correct.
     #[test]
@@ -423,7 +2018,7 @@ mod mds {
     /// Checks if derived mds matrices are correct.
     #[test]
     fn derived_mds_is_correct() {
-        let mut rng = OsRng;
+        let mut rng = TestRng::new();
         let width = 3;
         let mds = MdsMatrices::new(width);
         let base = (0..width)
@@ -511,4 +2106,299 @@ mod mds {
             assert!(MdsMatrices::<Fp<Fr>>::generate_mds(t).is_symmetric());
         }
     }
+
+    /// Builds a square matrix over `Fp<Fr>` from the small integer `rows`.
+    fn square_matrix<const N: usize>(rows: [[u64; N]; N]) -> SquareMatrix<Fp<Fr>> {
+        SquareMatrix::new_unchecked(Matrix::new_unchecked(
+            rows.iter()
+                .map(|row| row.iter().map(|entry| Fp::from_u64(*entry)).collect())
+                .collect(),
+        ))
+    }
+
+    /// Checks that the Cauchy constructions reproduce the default mds and are MDS.
+    #[test]
+    fn cauchy_constructions_are_mds() {
+        for t in 2..6 {
+            let m = MdsConstruction::Cauchy
+                .generate::<Fp<Fr>>(t)
+                .expect("The Cauchy construction is valid for every positive width.");
+            assert_eq!(
+                m,
+                MdsMatrices::generate_mds(t),
+                "The Cauchy construction should reproduce the default mds."
+            );
+            assert_eq!(
+                m,
+                MdsConstruction::ShiftedCauchy {
+                    x_offset: 0,
+                    y_offset: t as u64,
+                }
+                .generate::<Fp<Fr>>(t)
+                .expect("The shifted Cauchy construction is valid for these offsets."),
+                "The shifted Cauchy construction should reproduce the default mds."
+            );
+            assert_eq!(MdsMatrices::validate(&m), Ok(()), "The mds must be valid.");
+            assert_eq!(mds::check_mds(&m), Ok(()), "The mds must be MDS.");
+        }
+        assert_eq!(
+            mds::cauchy_matrix(
+                &[Fp::<Fr>::from_u64(1)],
+                &[NativeField::sub(&Fp::zero(), &Fp::one())]
+            ),
+            Err(Error::InvalidMdsConstruction { row: 0, column: 0 }),
+            "Cauchy entries with zero denominators should be rejected."
+        );
+    }
+
+    /// Checks that circulant constructions are built by shifting the first row.
+    #[test]
+    fn circulant_construction_is_correct() {
+        let m = MdsConstruction::Circulant(&[2, 1, 3])
+            .generate::<Fp<Fr>>(3)
+            .expect("The circulant construction has the right width.");
+        assert_eq!(m, square_matrix([[2, 1, 3], [3, 2, 1], [1, 3, 2]]));
+        assert_eq!(
+            m,
+            mds::circulant_matrix(&[2, 1, 3].map(Fp::<Fr>::from_u64))
+                .expect("The first row is not empty."),
+        );
+        assert_eq!(
+            MdsConstruction::Circulant(&[2, 1, 1]).generate::<Fp<Fr>>(4),
+            Err(Error::InvalidMdsMatrixLength {
+                expected: 16,
+                found: 9
+            }),
+            "Circulant constructions of the wrong width should be rejected."
+        );
+    }
+
+    /// Checks that the mds validation rejects matrices which are not MDS.
+    #[test]
+    fn mds_validation_rejects_insecure_matrices() {
+        let m = square_matrix([[2, 1, 1], [1, 2, 1], [1, 1, 2]]);
+        assert_eq!(MdsMatrices::validate(&m), Ok(()), "circ(2, 1, 1) is valid.");
+        assert_eq!(mds::check_mds(&m), Ok(()), "circ(2, 1, 1) is MDS.");
+        assert_eq!(
+            MdsMatrices::validate(&square_matrix([[1, 1, 1], [1, 1, 1], [1, 1, 1]])),
+            Err(Error::SingularMdsMatrix),
+            "Singular matrices should be rejected."
+        );
+        let m = square_matrix([[0, 1, 0], [1, 1, 1], [0, 1, 1]]);
+        assert_eq!(
+            MdsMatrices::validate(&m),
+            Err(Error::SingularMdsMinor),
+            "Matrices with a singular minor should be rejected."
+        );
+        assert!(
+            MdsMatrices::try_derive_mds_matrices(m).is_err(),
+            "Deriving mds matrices from an invalid matrix should fail."
+        );
+        let m = square_matrix([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+        assert_eq!(MdsMatrices::validate(&m), Ok(()), "The identity is valid.");
+        assert_eq!(
+            mds::check_mds(&m),
+            Err(Error::SingularMdsSubmatrix {
+                rows: 0b001,
+                columns: 0b010
+            }),
+            "The identity should not be MDS."
+        );
+    }
+}
+
+#[cfg(feature = "bls12-381")]
+mod import {
+    use super::*;
+    use crate::poseidon::Spec;
+    use alloc::boxed::Box;
+    use openzl_crypto::{
+        permutation::PseudorandomPermutation,
+        poseidon::{
+            self,
+            import::{self, ImportError, Value},
+            Error, Permutation, State,
+        },
+    };
+    use openzl_util::rand::{Sample, TestRng};
+
+    impl poseidon::Constants for Spec<Fr, 2> {
+        const WIDTH: usize = 3;
+        const FULL_ROUNDS: usize = 8;
+        const PARTIAL_ROUNDS: usize = 55;
+    }
+
+    /// Imports the permutation stored under the `round_constants` and `mds_matrix` keys of
+    /// `document`.
+    fn import_permutation(document: &str) -> Result<Permutation<Spec<Fr, 2>>, ImportError> {
+        let document = Value::parse(document)?;
+        import::permutation(
+            document
+                .get("round_constants")
+                .expect("The document must contain round constants."),
+            document
+                .get("mds_matrix")
+                .expect("The document must contain an MDS matrix."),
+        )
+    }
+
+    /// Checks that the parameters published by the reference implementation, in hexadecimal and
+    /// in decimal, produce the outputs of the reference implementation.
+    #[test]
+    fn imported_permutation_matches_reference_output() {
+        let permutation =
+            import_permutation(include_str!("permutation_hardcoded_test/width3.json"))
+                .expect("The hexadecimal parameters are valid.");
+        assert_eq!(
+            permutation,
+            import_permutation(include_str!(
+                "permutation_hardcoded_test/width3_decimal.json"
+            ))
+            .expect("The decimal parameters are valid."),
+            "The hexadecimal and decimal parameters should be the same."
+        );
+        assert_eq!(
+            permutation,
+            Permutation::gen(&mut TestRng::new()),
+            "The reference parameters should match the generated parameters."
+        );
+        let mut state = State::new(Box::new([3, 1, 2].map(|x| Fp(Fr::from(x as u64)))));
+        permutation.permute(&mut state, &mut ());
+        assert_eq!(
+            state.iter().cloned().collect::<Vec<_>>(),
+            include!("permutation_hardcoded_test/width3"),
+            "The imported permutation should match the reference output."
+        );
+    }
+
+    /// Checks that malformed parameter sets are rejected.
+    #[test]
+    fn import_rejects_malformed_parameters() {
+        assert_eq!(
+            Value::parse("[1, 2"),
+            Err(ImportError::Syntax { position: 5 })
+        );
+        assert_eq!(
+            Value::parse("[-1]"),
+            Err(ImportError::Syntax { position: 1 })
+        );
+        assert_eq!(
+            Value::parse("[1] 2"),
+            Err(ImportError::Syntax { position: 4 })
+        );
+        let modulus = "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+        assert_eq!(import::parse_field_element::<Fp<Fr>>(modulus), None);
+        assert_eq!(
+            import::parse_field_element::<Fp<Fr>>(
+                "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000"
+            ),
+            Some(Fp(-Fr::from(1u64))),
+        );
+        for literal in ["", "0x", "12a", "0x12g", "1.5"] {
+            assert_eq!(import::parse_field_element::<Fp<Fr>>(literal), None);
+        }
+        assert_eq!(
+            import_permutation(r#"{"round_constants": ["1", "2", "3"], "mds_matrix": []}"#),
+            Err(ImportError::Permutation(
+                Error::InvalidAdditiveRoundKeysLength {
+                    expected: 189,
+                    found: 3
+                }
+            ))
+        );
+        assert_eq!(
+            import_permutation(r#"{"round_constants": [], "mds_matrix": [["1", "2"]]}"#),
+            Err(ImportError::Permutation(Error::InvalidMdsMatrixLength {
+                expected: 9,
+                found: 2
+            }))
+        );
+        assert_eq!(
+            import_permutation(r#"{"round_constants": [{}], "mds_matrix": []}"#),
+            Err(ImportError::UnexpectedValue { index: 0 })
+        );
+    }
+}
+
+#[cfg(any(feature = "bls12-381", feature = "bn254"))]
+mod kat {
+    use crate::{
+        constraint::fp::Fp,
+        ff::{field_new, PrimeField},
+        poseidon::{Spec, TwoPowerMinusOneDomainTag},
+    };
+    use alloc::boxed::Box;
+    use openzl_crypto::{
+        hash::ArrayHashFunction,
+        permutation::PseudorandomPermutation,
+        poseidon::{hash::Hasher, Constants, Permutation, State},
+    };
+    use openzl_util::rand::{Sample, TestRng};
+
+    /// Checks that the generated parameters of `Spec<F, ARITY>` map the state `[0, 1, ..., ARITY]`
+    /// to `permutation`, and hash the input `[1, 2, ..., ARITY]` to `hash`.
+    ///
+    /// The known answers are computed by the independent implementation in
+    /// `kat_hardcoded_test/generate_kat.py`.
+    fn check_known_answers<F, const ARITY: usize>(permutation: Vec<Fp<F>>, hash: Fp<F>)
+    where
+        F: PrimeField,
+        Spec<F, ARITY>: Constants,
+    {
+        let mut state = State::<Spec<F, ARITY>>::new(
+            (0..=ARITY)
+                .map(|i| Fp(F::from(i as u64)))
+                .collect::<Box<_>>(),
+        );
+        Permutation::gen(&mut TestRng::new()).permute(&mut state, &mut ());
+        assert_eq!(
+            state.iter().cloned().collect::<Vec<_>>(),
+            permutation,
+            "The permutation with arity {} should match the known answer.",
+            ARITY,
+        );
+        let input = core::array::from_fn::<_, ARITY, _>(|i| Fp(F::from(i as u64 + 1)));
+        assert_eq!(
+            Hasher::<Spec<F, ARITY>, TwoPowerMinusOneDomainTag, ARITY>::gen(&mut TestRng::new())
+                .hash(input.each_ref(), &mut ()),
+            hash,
+            "The hash with arity {} should match the known answer.",
+            ARITY,
+        );
+    }
+
+    /// Checks the Poseidon permutation and hasher over BN254 against the known answers for every
+    /// supported arity.
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn bn254_known_answers() {
+        use bn254::Fr;
+        check_known_answers::<Fr, 2>(
+            include!("kat_hardcoded_test/bn254_width3_permutation"),
+            include!("kat_hardcoded_test/bn254_width3_hash"),
+        );
+        check_known_answers::<Fr, 3>(
+            include!("kat_hardcoded_test/bn254_width4_permutation"),
+            include!("kat_hardcoded_test/bn254_width4_hash"),
+        );
+        check_known_answers::<Fr, 4>(
+            include!("kat_hardcoded_test/bn254_width5_permutation"),
+            include!("kat_hardcoded_test/bn254_width5_hash"),
+        );
+        check_known_answers::<Fr, 5>(
+            include!("kat_hardcoded_test/bn254_width6_permutation"),
+            include!("kat_hardcoded_test/bn254_width6_hash"),
+        );
+    }
+
+    /// Checks the Poseidon permutation and hasher over BLS12-381 against the known answers.
+    #[cfg(feature = "bls12-381")]
+    #[test]
+    fn bls12_381_known_answers() {
+        use bls12_381::Fr;
+        check_known_answers::<Fr, 2>(
+            include!("kat_hardcoded_test/bls12_381_width3_permutation"),
+            include!("kat_hardcoded_test/bls12_381_width3_hash"),
+        );
+    }
 }
@@ -1,6 +1,22 @@
 //! Arkworks Canonical Serialize and Deserialize Backend
+//!
+//! Curve points which come from untrusted sources should be deserialized with
+//! [`deserialize_checked`], which rejects points that are not on the curve or not in its
+//! prime-order subgroup. See [`Validate`] for more.
 
-use openzl_util::codec;
+use openzl_util::codec::{self, DecodeError, ReadExactError};
+
+#[cfg(all(feature = "ec", feature = "ff"))]
+use {
+    crate::{
+        ec::{
+            models::{short_weierstrass_jacobian, twisted_edwards_extended},
+            AffineCurve, SWModelParameters, TEModelParameters,
+        },
+        ff::Zero,
+    },
+    core::fmt,
+};
 
 #[cfg(feature = "serde")]
 use {
@@ -9,10 +25,7 @@ use {
 };
 
 #[cfg(feature = "ark-std")]
-use {
-    ark_std::io::{self, Error, ErrorKind},
-    openzl_util::codec::ReadExactError,
-};
+use ark_std::io::{self, Error, ErrorKind};
 
 #[doc(inline)]
 pub use serialize::*;
@@ -168,6 +181,271 @@ where
     }
 }
 
+/// Codec Reader
+///
+/// This `struct` adapts an arkworks [`Read`] implementation into a [`codec::Read`]
+/// implementation, which is the opposite direction of [`ArkReader`].
+pub struct CodecReader<R>(
+    /// Reader
+    pub R,
+)
+where
+    R: Read;
+
+impl<R> codec::Read for CodecReader<R>
+where
+    R: Read,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn read<T>(&mut self, output: &mut T) -> Result<usize, Self::Error>
+    where
+        T: AsMut<[u8]> + ?Sized,
+    {
+        Ok(self.0.read(output.as_mut())?)
+    }
+
+    #[inline]
+    fn read_exact<T>(&mut self, output: &mut T) -> Result<(), ReadExactError<Self::Error>>
+    where
+        T: AsMut<[u8]> + ?Sized,
+    {
+        // NOTE: We can't use `ReadExactError::UnexpectedEnd` here since the arkworks `Read` trait
+        //       doesn't expose any information about how many bytes remain in the output buffer.
+        self.0
+            .read_exact(output.as_mut())
+            .map_err(|err| ReadExactError::Read(err.into()))
+    }
+}
+
+/// Codec Writer
+///
+/// This `struct` adapts an arkworks [`Write`] implementation into a [`codec::Write`]
+/// implementation, which is the opposite direction of [`ArkWriter`].
+pub struct CodecWriter<W>(
+    /// Writer
+    pub W,
+)
+where
+    W: Write;
+
+impl<W> codec::Write for CodecWriter<W>
+where
+    W: Write,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn write(&mut self, input: &mut &[u8]) -> Result<usize, Self::Error> {
+        let len = input.len();
+        self.0.write_all(input)?;
+        *input = &input[..0];
+        Ok(len)
+    }
+}
+
+/// Canonical Serialization Bridge
+///
+/// This `struct` implements [`CanonicalSerialize`] and [`CanonicalDeserialize`] for any type
+/// which implements [`Encode`](codec::Encode) and [`Decode`](codec::Decode) by reusing its
+/// [`codec`] encoding. This allows OpenZL data structures, like Merkle tree paths and parameters
+/// or Poseidon permutations, to be passed to arkworks-native tooling whenever their underlying
+/// digests and parameters can be encoded.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Codec<T>(
+    /// Underlying Value
+    pub T,
+);
+
+impl<T> Codec<T> {
+    /// Returns the underlying value of `self`.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Codec<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> CanonicalSerialize for Codec<T>
+where
+    T: codec::Encode,
+{
+    #[inline]
+    fn serialize<W>(&self, writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        self.0.encode(CodecWriter(writer))
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.0.encoded_len()
+    }
+}
+
+impl<T> CanonicalDeserialize for Codec<T>
+where
+    T: codec::Decode,
+{
+    #[inline]
+    fn deserialize<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        match T::decode(CodecReader(reader)) {
+            Ok(value) => Ok(Self(value)),
+            Err(DecodeError::Read(err)) => Err(err),
+            Err(DecodeError::Decode(_)) => Err(SerializationError::InvalidData),
+        }
+    }
+}
+
+/// Curve Point Validation Error
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ValidationError {
+    /// Point does not satisfy the Curve Equation
+    NotOnCurve,
+
+    /// Point is outside of the Prime-Order Subgroup
+    NotInSubgroup,
+}
+
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+impl fmt::Display for ValidationError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotOnCurve => write!(f, "point is not on the curve"),
+            Self::NotInSubgroup => write!(f, "point is not in the prime-order subgroup"),
+        }
+    }
+}
+
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+impl From<ValidationError> for SerializationError {
+    #[inline]
+    fn from(err: ValidationError) -> Self {
+        let _ = err;
+        Self::InvalidData
+    }
+}
+
+/// Curve Point Validation
+///
+/// Unchecked deserialization accepts any pair of coordinates, including points which are not on
+/// the curve and points of small order on curves with a cofactor, like the twisted Edwards curves
+/// used for signatures and commitments. Protocols which rely on the hardness of the discrete
+/// logarithm in the prime-order subgroup must reject both kinds of points whenever they come from
+/// an untrusted source.
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+pub trait Validate: AffineCurve {
+    /// Returns `true` if `self` satisfies the curve equation.
+    fn is_on_curve(&self) -> bool;
+
+    /// Returns `true` if `self` is in the prime-order subgroup, assuming that `self` is on the
+    /// curve.
+    fn is_in_prime_order_subgroup(&self) -> bool;
+
+    /// Returns `true` if multiplying `self` by the cofactor gives the identity, which means that
+    /// `self` has no component in the prime-order subgroup. The identity itself has small order.
+    #[inline]
+    fn has_small_order(&self) -> bool {
+        self.mul_by_cofactor_to_projective().is_zero()
+    }
+
+    /// Checks that `self` is on the curve and in the prime-order subgroup.
+    #[inline]
+    fn validate(&self) -> Result<(), ValidationError> {
+        if !Validate::is_on_curve(self) {
+            return Err(ValidationError::NotOnCurve);
+        }
+        if !self.is_in_prime_order_subgroup() {
+            return Err(ValidationError::NotInSubgroup);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+impl<P> Validate for short_weierstrass_jacobian::GroupAffine<P>
+where
+    P: SWModelParameters,
+{
+    #[inline]
+    fn is_on_curve(&self) -> bool {
+        short_weierstrass_jacobian::GroupAffine::is_on_curve(self)
+    }
+
+    #[inline]
+    fn is_in_prime_order_subgroup(&self) -> bool {
+        self.is_in_correct_subgroup_assuming_on_curve()
+    }
+}
+
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+impl<P> Validate for twisted_edwards_extended::GroupAffine<P>
+where
+    P: TEModelParameters,
+{
+    #[inline]
+    fn is_on_curve(&self) -> bool {
+        twisted_edwards_extended::GroupAffine::is_on_curve(self)
+    }
+
+    #[inline]
+    fn is_in_prime_order_subgroup(&self) -> bool {
+        self.is_in_correct_subgroup_assuming_on_curve()
+    }
+}
+
+/// Deserializes a point from its uncompressed encoding in `reader`, checking that it is on the
+/// curve and in the prime-order subgroup. See [`Validate`] for more.
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+#[inline]
+pub fn deserialize_checked<A, R>(reader: R) -> Result<A, SerializationError>
+where
+    A: Validate,
+    R: Read,
+{
+    let point = A::deserialize_unchecked(reader)?;
+    point.validate()?;
+    Ok(point)
+}
+
+/// Deserializes a point from its uncompressed encoding in `reader` without checking that it is on
+/// the curve or in the prime-order subgroup.
+///
+/// # Crypto Safety
+///
+/// This function should only be used for points coming from a trusted source. See
+/// [`deserialize_checked`] for the validated variant.
+#[cfg(all(feature = "ec", feature = "ff"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "ec", feature = "ff"))))]
+#[inline]
+pub fn deserialize_unchecked<A, R>(reader: R) -> Result<A, SerializationError>
+where
+    A: AffineCurve,
+    R: Read,
+{
+    A::deserialize_unchecked(reader)
+}
+
 /// Serializes `data` using the [`CanonicalSerialize`] format with `S` as the [`Serializer`].
 #[cfg(feature = "serde")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
@@ -177,7 +455,7 @@ where
     T: CanonicalSerialize,
     S: Serializer,
 {
-    let mut bytes = Vec::new();
+    let mut bytes = Vec::with_capacity(data.serialized_size());
     data.serialize(&mut bytes).map_err(ser::Error::custom)?;
     Serialize::serialize(&bytes, serializer)
 }
@@ -191,7 +469,7 @@ where
     T: CanonicalSerialize,
     S: Serializer,
 {
-    let mut bytes = Vec::new();
+    let mut bytes = Vec::with_capacity(data.uncompressed_size());
     data.serialize_uncompressed(&mut bytes)
         .map_err(ser::Error::custom)?;
     Serialize::serialize(&bytes, serializer)
@@ -0,0 +1,347 @@
+//! Protobuf Wire Format
+//!
+//! The messages in this module define a stable [Protocol Buffers] schema for the artifacts which
+//! proving services exchange: Groth16 proofs and verifying keys, public inputs, and the manifests
+//! of published parameter files. They mirror the `proto/wire.proto` schema shipped with this
+//! crate, so that services written in other languages can generate compatible bindings from it.
+//!
+//! Curve points and field elements are stored as `bytes` in their compressed
+//! [`CanonicalSerialize`] encoding, so the schema itself does not depend on the pairing engine.
+//! Messages are encoded and decoded with the [`Message`] trait, and converted from and into the
+//! internal types with [`From`] and [`TryFrom`].
+//!
+//! [Protocol Buffers]: https://protobuf.dev
+
+use crate::{
+    ec::PairingEngine,
+    ff::Field,
+    groth16,
+    serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError},
+};
+use alloc::{string::String, vec::Vec};
+
+#[doc(inline)]
+pub use prost::Message;
+
+/// Schema Version
+///
+/// This version is increased whenever a message of the schema changes in a way which is not
+/// backwards compatible.
+pub const VERSION: u32 = 1;
+
+/// Groth16 Proof Message
+#[derive(Clone, Eq, Hash, PartialEq, Message)]
+pub struct Proof {
+    /// Compressed `A` Point in G1
+    #[prost(bytes = "vec", tag = "1")]
+    pub a: Vec<u8>,
+
+    /// Compressed `B` Point in G2
+    #[prost(bytes = "vec", tag = "2")]
+    pub b: Vec<u8>,
+
+    /// Compressed `C` Point in G1
+    #[prost(bytes = "vec", tag = "3")]
+    pub c: Vec<u8>,
+}
+
+/// Groth16 Verifying Key Message
+#[derive(Clone, Eq, Hash, PartialEq, Message)]
+pub struct VerifyingKey {
+    /// Compressed `alpha` Point in G1
+    #[prost(bytes = "vec", tag = "1")]
+    pub alpha_g1: Vec<u8>,
+
+    /// Compressed `beta` Point in G2
+    #[prost(bytes = "vec", tag = "2")]
+    pub beta_g2: Vec<u8>,
+
+    /// Compressed `gamma` Point in G2
+    #[prost(bytes = "vec", tag = "3")]
+    pub gamma_g2: Vec<u8>,
+
+    /// Compressed `delta` Point in G2
+    #[prost(bytes = "vec", tag = "4")]
+    pub delta_g2: Vec<u8>,
+
+    /// Compressed Public Input Bases in G1
+    #[prost(bytes = "vec", repeated, tag = "5")]
+    pub gamma_abc_g1: Vec<Vec<u8>>,
+}
+
+/// Public Input Message
+#[derive(Clone, Eq, Hash, PartialEq, Message)]
+pub struct PublicInput {
+    /// Field Elements
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub elements: Vec<Vec<u8>>,
+}
+
+/// Parameter File Entry
+#[derive(Clone, Eq, Hash, PartialEq, Message)]
+pub struct ParameterEntry {
+    /// Parameter Name
+    #[prost(string, tag = "1")]
+    pub name: String,
+
+    /// Location of the Parameter File
+    ///
+    /// This is usually a URL or a path relative to the manifest.
+    #[prost(string, tag = "2")]
+    pub location: String,
+
+    /// Length of the Parameter File in Bytes
+    #[prost(uint64, tag = "3")]
+    pub len: u64,
+
+    /// Digest of the Parameter File
+    #[prost(bytes = "vec", tag = "4")]
+    pub digest: Vec<u8>,
+}
+
+/// Parameter Manifest Message
+///
+/// A manifest lists the parameter files of a release of a proving service, so that clients can
+/// fetch and check them before building proofs.
+#[derive(Clone, Eq, Hash, PartialEq, Message)]
+pub struct ParameterManifest {
+    /// Schema Version
+    ///
+    /// See [`VERSION`] for more.
+    #[prost(uint32, tag = "1")]
+    pub version: u32,
+
+    /// Name of the Pairing Engine
+    #[prost(string, tag = "2")]
+    pub curve: String,
+
+    /// Parameter Files
+    #[prost(message, repeated, tag = "3")]
+    pub entries: Vec<ParameterEntry>,
+}
+
+impl ParameterManifest {
+    /// Builds a new empty [`ParameterManifest`] for `curve` at the current [`VERSION`].
+    #[inline]
+    pub fn new(curve: String) -> Self {
+        Self {
+            version: VERSION,
+            curve,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the entry with the given `name` if it exists.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&ParameterEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// Returns the compressed encoding of `value`.
+#[inline]
+fn to_bytes<T>(value: &T) -> Vec<u8>
+where
+    T: CanonicalSerialize,
+{
+    let mut buffer = Vec::with_capacity(value.serialized_size());
+    value
+        .serialize(&mut buffer)
+        .expect("Serialization is not allowed to fail.");
+    buffer
+}
+
+/// Decodes a value from its compressed encoding in `bytes`, rejecting trailing bytes.
+#[inline]
+fn from_bytes<T>(mut bytes: &[u8]) -> Result<T, SerializationError>
+where
+    T: CanonicalDeserialize,
+{
+    let value = T::deserialize(&mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(value)
+}
+
+impl PublicInput {
+    /// Builds a new [`PublicInput`] message from `elements`.
+    #[inline]
+    pub fn from_elements<F>(elements: &[F]) -> Self
+    where
+        F: Field,
+    {
+        Self {
+            elements: elements.iter().map(to_bytes).collect(),
+        }
+    }
+
+    /// Decodes the field elements of `self`.
+    #[inline]
+    pub fn to_elements<F>(&self) -> Result<Vec<F>, SerializationError>
+    where
+        F: Field,
+    {
+        self.elements
+            .iter()
+            .map(|element| from_bytes(element))
+            .collect()
+    }
+}
+
+impl<E> From<&groth16::Proof<E>> for Proof
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn from(proof: &groth16::Proof<E>) -> Self {
+        Self {
+            a: to_bytes(&proof.0.a),
+            b: to_bytes(&proof.0.b),
+            c: to_bytes(&proof.0.c),
+        }
+    }
+}
+
+impl<E> TryFrom<&Proof> for groth16::Proof<E>
+where
+    E: PairingEngine,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn try_from(proof: &Proof) -> Result<Self, Self::Error> {
+        Ok(Self(ark_groth16::Proof {
+            a: from_bytes(&proof.a)?,
+            b: from_bytes(&proof.b)?,
+            c: from_bytes(&proof.c)?,
+        }))
+    }
+}
+
+impl<E> From<&groth16::VerifyingKey<E>> for VerifyingKey
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn from(key: &groth16::VerifyingKey<E>) -> Self {
+        Self {
+            alpha_g1: to_bytes(&key.alpha_g1),
+            beta_g2: to_bytes(&key.beta_g2),
+            gamma_g2: to_bytes(&key.gamma_g2),
+            delta_g2: to_bytes(&key.delta_g2),
+            gamma_abc_g1: key.gamma_abc_g1.iter().map(to_bytes).collect(),
+        }
+    }
+}
+
+impl<E> TryFrom<&VerifyingKey> for groth16::VerifyingKey<E>
+where
+    E: PairingEngine,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn try_from(key: &VerifyingKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            alpha_g1: from_bytes(&key.alpha_g1)?,
+            beta_g2: from_bytes(&key.beta_g2)?,
+            gamma_g2: from_bytes(&key.gamma_g2)?,
+            delta_g2: from_bytes(&key.delta_g2)?,
+            gamma_abc_g1: key
+                .gamma_abc_g1
+                .iter()
+                .map(|point| from_bytes(point))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl<E> From<&groth16::VerifyingContext<E>> for VerifyingKey
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn from(context: &groth16::VerifyingContext<E>) -> Self {
+        Self::from(&context.0.vk)
+    }
+}
+
+impl<E> TryFrom<&VerifyingKey> for groth16::VerifyingContext<E>
+where
+    E: PairingEngine,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn try_from(key: &VerifyingKey) -> Result<Self, Self::Error> {
+        Ok(Self::from_verifying_key(&key.try_into()?))
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        bn254::{Bn254, Fr, G1Projective, G2Projective},
+        ec::ProjectiveCurve,
+        ff::UniformRand,
+    };
+    use openzl_util::rand::TestRng;
+
+    /// Checks that proofs, verifying keys, and public inputs round-trip through their protobuf
+    /// encodings.
+    #[test]
+    fn groth16_artifacts_round_trip() {
+        let mut rng = TestRng::new();
+        let proof = groth16::Proof::<Bn254>(ark_groth16::Proof {
+            a: G1Projective::rand(&mut rng).into_affine(),
+            b: G2Projective::rand(&mut rng).into_affine(),
+            c: G1Projective::rand(&mut rng).into_affine(),
+        });
+        let message = Proof::decode(Proof::from(&proof).encode_to_vec().as_slice())
+            .expect("Decoding an encoded proof should succeed.");
+        assert_eq!(
+            groth16::Proof::<Bn254>::try_from(&message).expect("The proof should be valid."),
+            proof,
+            "The proof should round-trip through its protobuf encoding."
+        );
+        let key = groth16::VerifyingKey::<Bn254> {
+            alpha_g1: G1Projective::rand(&mut rng).into_affine(),
+            beta_g2: G2Projective::rand(&mut rng).into_affine(),
+            gamma_g2: G2Projective::rand(&mut rng).into_affine(),
+            delta_g2: G2Projective::rand(&mut rng).into_affine(),
+            gamma_abc_g1: (0..3)
+                .map(|_| G1Projective::rand(&mut rng).into_affine())
+                .collect(),
+        };
+        let message = VerifyingKey::decode(VerifyingKey::from(&key).encode_to_vec().as_slice())
+            .expect("Decoding an encoded verifying key should succeed.");
+        assert_eq!(
+            groth16::VerifyingKey::<Bn254>::try_from(&message)
+                .expect("The verifying key should be valid."),
+            key,
+            "The verifying key should round-trip through its protobuf encoding."
+        );
+        let input = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let mut message = PublicInput::decode(
+            PublicInput::from_elements(&input)
+                .encode_to_vec()
+                .as_slice(),
+        )
+        .expect("Decoding an encoded public input should succeed.");
+        assert_eq!(
+            message
+                .to_elements::<Fr>()
+                .expect("The public input should be valid."),
+            input,
+            "The public input should round-trip through its protobuf encoding."
+        );
+        message.elements[0].push(0);
+        assert!(
+            message.to_elements::<Fr>().is_err(),
+            "Trailing bytes should be rejected."
+        );
+    }
+}